@@ -98,6 +98,8 @@
 //! - `tess_ctrl`
 //! - `tess_eval`
 //! - `compute`
+//! - `task`
+//! - `mesh`
 //! - `raygen`
 //! - `anyhit`
 //! - `closesthit`
@@ -107,6 +109,9 @@
 //!
 //! For details on what these shader types mean, [see Vulkano's documentation][pipeline].
 //!
+//! Task and mesh shaders using `GL_EXT_mesh_shader` need to target at least SPIR-V 1.4, see the
+//! `spirv_version` field below.
+//!
 //! ## `src: "..."`
 //!
 //! Provides the raw GLSL source to be compiled in the form of a string. Cannot be used in
@@ -439,6 +444,8 @@ impl Parse for MacroInput {
                         "tess_ctrl" => ShaderKind::TessControl,
                         "tess_eval" => ShaderKind::TessEvaluation,
                         "compute" => ShaderKind::Compute,
+                        "task" => ShaderKind::Task,
+                        "mesh" => ShaderKind::Mesh,
                         "raygen" => ShaderKind::RayGeneration,
                         "anyhit" => ShaderKind::AnyHit,
                         "closesthit" => ShaderKind::ClosestHit,
@@ -448,8 +455,8 @@ impl Parse for MacroInput {
                         ty => bail!(
                             lit,
                             "expected `vertex`, `fragment`, `geometry`, `tess_ctrl`, `tess_eval`, \
-                            `compute`, `raygen`, `anyhit`, `closesthit`, `miss`, `intersection` or \
-                            `callable`, found `{ty}`",
+                            `compute`, `task`, `mesh`, `raygen`, `anyhit`, `closesthit`, `miss`, \
+                            `intersection` or `callable`, found `{ty}`",
                         ),
                     });
                 }
@@ -0,0 +1,2208 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! An opaque data structure that is used to accelerate spatial queries on geometry data.
+//!
+//! Acceleration structures contain geometry data, arranged in such a way that the device can
+//! easily search through the data and check for intersections between the geometry and rays
+//! (lines). The geometry data can consist of either triangles, or axis-aligned bounding boxes
+//! (AABBs).
+//!
+//! Acceleration structures come in two forms: top-level and bottom-level. A bottom-level
+//! acceleration structure holds the actual geometry data, while a top-level structure contains
+//! instances of (references to) one or more bottom-level structures. A top-level structure is
+//! intended to contain the whole rendered scene (or the relevant parts of it), while a
+//! bottom-level structure may contain individual objects within the scene. This two-level
+//! arrangement allows you to easily rearrange the scene, adding and removing parts of it as needed.
+//!
+//! # Building an acceleration structure
+//!
+//! An acceleration structure is backed by a [`Subbuffer`], which holds its data. The buffer must
+//! have been created with the [`ACCELERATION_STRUCTURE_STORAGE`] usage, and it must be large
+//! enough to hold the acceleration structure. To find out how large it must be, describe the
+//! geometry with an [`AccelerationStructureBuildGeometryInfo`] and pass it to
+//! [`Device::acceleration_structure_build_sizes`]. This also returns how large the scratch buffer
+//! must be, which is used as temporary storage during the build.
+//!
+//! Once the acceleration structure object has been created, it is still empty. It is built either
+//! on the device, by recording [`build_acceleration_structure`] into a command buffer, or on the
+//! host, by calling [`Device::build_acceleration_structure`]. Host builds can optionally be
+//! deferred with a [`DeferredOperation`].
+//!
+//! # Compacting an acceleration structure
+//!
+//! If an acceleration structure was built with the [`ALLOW_COMPACTION`] flag, then its compacted
+//! size can be queried after the build, by using [`write_acceleration_structures_properties`]
+//! with a query pool of type [`QueryType::AccelerationStructureCompactedSize`]. A new, smaller
+//! acceleration structure can then be created, and the original copied into it with
+//! [`CopyAccelerationStructureMode::Compact`].
+//!
+//! [`ACCELERATION_STRUCTURE_STORAGE`]: crate::buffer::BufferUsage::ACCELERATION_STRUCTURE_STORAGE
+//! [`build_acceleration_structure`]: crate::command_buffer::AutoCommandBufferBuilder::build_acceleration_structure
+//! [`ALLOW_COMPACTION`]: BuildAccelerationStructureFlags::ALLOW_COMPACTION
+//! [`write_acceleration_structures_properties`]: crate::command_buffer::AutoCommandBufferBuilder::write_acceleration_structures_properties
+//! [`QueryType::AccelerationStructureCompactedSize`]: crate::query::QueryType::AccelerationStructureCompactedSize
+//! [`DeferredOperation`]: crate::deferred::DeferredOperation
+
+use crate::{
+    buffer::{BufferUsage, Subbuffer},
+    command_buffer::synced::SyncCommandBufferBuilderError,
+    device::{Device, DeviceOwned},
+    format::{Format, FormatFeatures},
+    macros::{impl_id_counter, vulkan_bitflags, vulkan_enum},
+    pipeline::graphics::input_assembly::IndexType,
+    DeviceSize, NonZeroDeviceSize, OomError, RequirementNotMet, RequiresOneOf, VulkanError,
+    VulkanObject,
+};
+use bytemuck::{Pod, Zeroable};
+use smallvec::SmallVec;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    mem::MaybeUninit,
+    num::NonZeroU64,
+    ptr,
+    sync::Arc,
+};
+
+/// An opaque data structure that is used to accelerate spatial queries on geometry data.
+#[derive(Debug)]
+pub struct AccelerationStructure {
+    handle: ash::vk::AccelerationStructureKHR,
+    device: Arc<Device>,
+    id: NonZeroU64,
+
+    buffer: Subbuffer<[u8]>,
+    ty: AccelerationStructureType,
+}
+
+impl AccelerationStructure {
+    /// Creates a new `AccelerationStructure`.
+    ///
+    /// The [`acceleration_structure`] feature must be enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - `create_info.buffer` (and any subbuffer it overlaps with) must not be accessed
+    ///   while it is bound to the acceleration structure.
+    ///
+    /// [`acceleration_structure`]: crate::device::Features::acceleration_structure
+    #[inline]
+    pub unsafe fn new(
+        device: Arc<Device>,
+        create_info: AccelerationStructureCreateInfo,
+    ) -> Result<Arc<Self>, AccelerationStructureCreationError> {
+        Self::validate_new(&device, &create_info)?;
+
+        Ok(Self::new_unchecked(device, create_info)?)
+    }
+
+    fn validate_new(
+        device: &Device,
+        create_info: &AccelerationStructureCreateInfo,
+    ) -> Result<(), AccelerationStructureCreationError> {
+        // VUID-vkCreateAccelerationStructureKHR-accelerationStructure-03611
+        if !device.enabled_features().acceleration_structure {
+            return Err(AccelerationStructureCreationError::RequirementNotMet {
+                required_for: "`AccelerationStructure::new`",
+                requires_one_of: RequiresOneOf {
+                    features: &["acceleration_structure"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let &AccelerationStructureCreateInfo {
+            ref buffer,
+            ty,
+            _ne: _,
+        } = create_info;
+
+        assert_eq!(device, buffer.device().as_ref());
+
+        // VUID-VkAccelerationStructureCreateInfoKHR-type-parameter
+        ty.validate_device(device)?;
+
+        // VUID-VkAccelerationStructureCreateInfoKHR-buffer-03614
+        if !buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::ACCELERATION_STRUCTURE_STORAGE)
+        {
+            return Err(AccelerationStructureCreationError::BufferMissingUsage);
+        }
+
+        // VUID-VkAccelerationStructureCreateInfoKHR-offset-03734
+        if buffer.offset() % 256 != 0 {
+            return Err(AccelerationStructureCreationError::BufferOffsetNotAligned {
+                offset: buffer.offset(),
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn new_unchecked(
+        device: Arc<Device>,
+        create_info: AccelerationStructureCreateInfo,
+    ) -> Result<Arc<Self>, VulkanError> {
+        let &AccelerationStructureCreateInfo {
+            ref buffer,
+            ty,
+            _ne: _,
+        } = &create_info;
+
+        let create_info_vk = ash::vk::AccelerationStructureCreateInfoKHR {
+            create_flags: ash::vk::AccelerationStructureCreateFlagsKHR::empty(),
+            buffer: buffer.buffer().handle(),
+            offset: buffer.offset(),
+            size: buffer.size(),
+            ty: ty.into(),
+            device_address: 0,
+            ..Default::default()
+        };
+
+        let handle = {
+            let fns = device.fns();
+            let mut output = MaybeUninit::uninit();
+            (fns.khr_acceleration_structure
+                .create_acceleration_structure_khr)(
+                device.handle(),
+                &create_info_vk,
+                ptr::null(),
+                output.as_mut_ptr(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+            output.assume_init()
+        };
+
+        Ok(Self::from_handle(device, handle, create_info))
+    }
+
+    /// Creates a new `AccelerationStructure` from a raw object handle.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid Vulkan object handle created from `device`.
+    /// - `create_info` must match the info used to create the object.
+    #[inline]
+    pub unsafe fn from_handle(
+        device: Arc<Device>,
+        handle: ash::vk::AccelerationStructureKHR,
+        create_info: AccelerationStructureCreateInfo,
+    ) -> Arc<Self> {
+        let AccelerationStructureCreateInfo { buffer, ty, _ne: _ } = create_info;
+
+        Arc::new(Self {
+            handle,
+            device,
+            id: Self::next_id(),
+            buffer,
+            ty,
+        })
+    }
+
+    /// Returns the subbuffer that the acceleration structure is stored in.
+    #[inline]
+    pub fn buffer(&self) -> &Subbuffer<[u8]> {
+        &self.buffer
+    }
+
+    /// Returns the size of the acceleration structure.
+    #[inline]
+    pub fn size(&self) -> DeviceSize {
+        self.buffer.size()
+    }
+
+    /// Returns the type of the acceleration structure.
+    #[inline]
+    pub fn ty(&self) -> AccelerationStructureType {
+        self.ty
+    }
+
+    /// Returns the device address of the acceleration structure.
+    ///
+    /// The device address of a bottom-level acceleration structure is used to refer to it in
+    /// [`AccelerationStructureInstance::acceleration_structure_reference`].
+    #[inline]
+    pub fn device_address(&self) -> NonZeroDeviceSize {
+        let info_vk = ash::vk::AccelerationStructureDeviceAddressInfoKHR {
+            acceleration_structure: self.handle,
+            ..Default::default()
+        };
+        let ptr = unsafe {
+            let fns = self.device.fns();
+            (fns.khr_acceleration_structure
+                .get_acceleration_structure_device_address_khr)(
+                self.device.handle(), &info_vk
+            )
+        };
+
+        NonZeroDeviceSize::new(ptr).unwrap()
+    }
+}
+
+impl Drop for AccelerationStructure {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let fns = self.device.fns();
+            (fns.khr_acceleration_structure
+                .destroy_acceleration_structure_khr)(
+                self.device.handle(),
+                self.handle,
+                ptr::null(),
+            );
+        }
+    }
+}
+
+unsafe impl VulkanObject for AccelerationStructure {
+    type Handle = ash::vk::AccelerationStructureKHR;
+
+    #[inline]
+    fn handle(&self) -> Self::Handle {
+        self.handle
+    }
+}
+
+unsafe impl DeviceOwned for AccelerationStructure {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+impl_id_counter!(AccelerationStructure);
+
+vulkan_enum! {
+    #[non_exhaustive]
+
+    /// The type of an acceleration structure.
+    AccelerationStructureType = AccelerationStructureTypeKHR(i32);
+
+    /// Refers to bottom-level acceleration structures. This type can be bound to a descriptor.
+    TopLevel = TOP_LEVEL,
+
+    /// Contains AABBs or geometry to be intersected.
+    BottomLevel = BOTTOM_LEVEL,
+
+    /// The type is determined at build time.
+    ///
+    /// Use of this type is discouraged, it is preferred to specify the type at create time.
+    Generic = GENERIC,
+}
+
+/// Parameters to create a new `AccelerationStructure`.
+#[derive(Clone, Debug)]
+pub struct AccelerationStructureCreateInfo {
+    /// The subbuffer to store the acceleration structure in.
+    ///
+    /// The subbuffer must have an offset that is a multiple of 256, and its buffer must have the
+    /// [`BufferUsage::ACCELERATION_STRUCTURE_STORAGE`] usage. It must be at least as large as the
+    /// [`acceleration_structure_size`] returned by [`Device::acceleration_structure_build_sizes`].
+    ///
+    /// There is no default value.
+    ///
+    /// [`acceleration_structure_size`]: AccelerationStructureBuildSizesInfo::acceleration_structure_size
+    pub buffer: Subbuffer<[u8]>,
+
+    /// The type of acceleration structure to create.
+    ///
+    /// The default value is [`AccelerationStructureType::Generic`].
+    pub ty: AccelerationStructureType,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl AccelerationStructureCreateInfo {
+    /// Returns a `AccelerationStructureCreateInfo` with the specified `buffer`.
+    #[inline]
+    pub fn new(buffer: Subbuffer<[u8]>) -> Self {
+        Self {
+            buffer,
+            ty: AccelerationStructureType::Generic,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Geometry data that is used to build an acceleration structure.
+#[derive(Clone, Debug)]
+pub struct AccelerationStructureBuildGeometryInfo {
+    /// Specifies options that affect the build.
+    ///
+    /// The default value is empty.
+    pub flags: BuildAccelerationStructureFlags,
+
+    /// Specifies whether a new acceleration structure is built, or an existing one is updated.
+    ///
+    /// The default value is [`BuildAccelerationStructureMode::Build`].
+    pub mode: BuildAccelerationStructureMode,
+
+    /// The acceleration structure to build or update.
+    ///
+    /// This can be `None` when calling [`Device::acceleration_structure_build_sizes`],
+    /// but must be `Some` otherwise.
+    ///
+    /// The default value is `None`.
+    pub dst_acceleration_structure: Option<Arc<AccelerationStructure>>,
+
+    /// The geometries that will be built into `dst_acceleration_structure`.
+    ///
+    /// The variant of this value determines the type of acceleration structure that is built:
+    /// triangles and AABBs build a bottom-level acceleration structure, and instances build a
+    /// top-level acceleration structure.
+    ///
+    /// There is no default value.
+    pub geometries: AccelerationStructureGeometries,
+
+    /// Scratch memory to be used for the build.
+    ///
+    /// This can be `None` when calling [`Device::acceleration_structure_build_sizes`],
+    /// but must be `Some` otherwise. Its buffer must have the [`BufferUsage::STORAGE_BUFFER`] and
+    /// [`BufferUsage::SHADER_DEVICE_ADDRESS`] usages, and its device address must be a multiple of
+    /// the [`min_acceleration_structure_scratch_offset_alignment`] device property. It must be at
+    /// least as large as the [`build_scratch_size`] or [`update_scratch_size`] returned by
+    /// [`Device::acceleration_structure_build_sizes`], depending on the build mode.
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`min_acceleration_structure_scratch_offset_alignment`]: crate::device::Properties::min_acceleration_structure_scratch_offset_alignment
+    /// [`build_scratch_size`]: AccelerationStructureBuildSizesInfo::build_scratch_size
+    /// [`update_scratch_size`]: AccelerationStructureBuildSizesInfo::update_scratch_size
+    pub scratch_data: Option<Subbuffer<[u8]>>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl AccelerationStructureBuildGeometryInfo {
+    /// Returns a `AccelerationStructureBuildGeometryInfo` with the specified `geometries`.
+    #[inline]
+    pub fn geometries(geometries: AccelerationStructureGeometries) -> Self {
+        Self {
+            flags: BuildAccelerationStructureFlags::empty(),
+            mode: BuildAccelerationStructureMode::Build,
+            dst_acceleration_structure: None,
+            geometries,
+            scratch_data: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+
+    /// Returns the type of acceleration structure that the geometries build.
+    #[inline]
+    pub fn ty(&self) -> AccelerationStructureType {
+        match self.geometries {
+            AccelerationStructureGeometries::Triangles(_)
+            | AccelerationStructureGeometries::Aabbs(_) => AccelerationStructureType::BottomLevel,
+            AccelerationStructureGeometries::Instances(_) => AccelerationStructureType::TopLevel,
+        }
+    }
+
+    /// Returns an iterator over all subbuffers that are read by the build, along with the index
+    /// of the geometry that they belong to.
+    pub(crate) fn input_buffers(&self) -> impl Iterator<Item = (usize, &Subbuffer<[u8]>)> {
+        let buffers: SmallVec<[_; 8]> = match &self.geometries {
+            AccelerationStructureGeometries::Triangles(geometries) => geometries
+                .iter()
+                .enumerate()
+                .flat_map(|(index, triangles_data)| {
+                    [
+                        triangles_data.vertex_data.as_ref(),
+                        triangles_data.index_data.as_ref(),
+                        triangles_data
+                            .transform_data
+                            .as_ref()
+                            .map(|transform_data| transform_data.as_bytes()),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .map(move |buffer| (index, buffer))
+                })
+                .collect(),
+            AccelerationStructureGeometries::Aabbs(geometries) => geometries
+                .iter()
+                .enumerate()
+                .filter_map(|(index, aabbs_data)| {
+                    aabbs_data.data.as_ref().map(|buffer| (index, buffer))
+                })
+                .collect(),
+            AccelerationStructureGeometries::Instances(instances_data) => {
+                match &instances_data.data {
+                    AccelerationStructureGeometryInstancesDataType::Values(data) => data
+                        .as_ref()
+                        .map(|buffer| (0, buffer.as_bytes()))
+                        .into_iter()
+                        .collect(),
+                    AccelerationStructureGeometryInstancesDataType::Pointers(data) => data
+                        .as_ref()
+                        .map(|buffer| (0, buffer.as_bytes()))
+                        .into_iter()
+                        .collect(),
+                }
+            }
+        };
+
+        buffers.into_iter()
+    }
+
+    /// Validates the parameters of a build, or of a build size query if `build_range_infos` is
+    /// `None`.
+    pub(crate) fn validate(
+        &self,
+        device: &Device,
+        build_type: AccelerationStructureBuildType,
+        build_range_infos: Option<&[AccelerationStructureBuildRangeInfo]>,
+    ) -> Result<(), AccelerationStructureError> {
+        let &AccelerationStructureBuildGeometryInfo {
+            flags,
+            ref mode,
+            ref dst_acceleration_structure,
+            ref geometries,
+            ref scratch_data,
+            _ne: _,
+        } = self;
+
+        let properties = device.physical_device().properties();
+        let is_build = build_range_infos.is_some();
+        let host = matches!(build_type, AccelerationStructureBuildType::Host);
+
+        // VUID-VkAccelerationStructureBuildGeometryInfoKHR-flags-parameter
+        flags.validate_device(device)?;
+
+        // VUID-VkAccelerationStructureBuildGeometryInfoKHR-flags-03796
+        if flags.contains(
+            BuildAccelerationStructureFlags::PREFER_FAST_TRACE
+                | BuildAccelerationStructureFlags::PREFER_FAST_BUILD,
+        ) {
+            return Err(AccelerationStructureError::BuildFlagsPreferTraceAndBuild);
+        }
+
+        let geometry_count = geometries.len() as u64;
+
+        match geometries {
+            AccelerationStructureGeometries::Triangles(geometries) => {
+                for (geometry_index, triangles_data) in geometries.iter().enumerate() {
+                    let &AccelerationStructureGeometryTrianglesData {
+                        flags,
+                        vertex_format,
+                        ref vertex_data,
+                        vertex_stride,
+                        max_vertex: _,
+                        ref index_data,
+                        index_type,
+                        ref transform_data,
+                        _ne: _,
+                    } = triangles_data;
+
+                    // VUID-VkAccelerationStructureGeometryKHR-flags-parameter
+                    flags.validate_device(device)?;
+
+                    // VUID-VkAccelerationStructureGeometryTrianglesDataKHR-vertexFormat-parameter
+                    vertex_format.validate_device(device)?;
+
+                    // VUID-VkAccelerationStructureGeometryTrianglesDataKHR-vertexFormat-03797
+                    if !unsafe {
+                        device
+                            .physical_device()
+                            .format_properties_unchecked(vertex_format)
+                    }
+                    .buffer_features
+                    .intersects(FormatFeatures::ACCELERATION_STRUCTURE_VERTEX_BUFFER)
+                    {
+                        return Err(AccelerationStructureError::VertexFormatNotSupported {
+                            geometry_index,
+                            format: vertex_format,
+                        });
+                    }
+
+                    // VUID-VkAccelerationStructureGeometryTrianglesDataKHR-vertexStride-03735
+                    let smallest_component_bytes = (vertex_format.components().into_iter())
+                        .filter(|&bits| bits != 0)
+                        .min()
+                        .map_or(1, |bits| (bits as u32 + 7) / 8);
+
+                    if vertex_stride % smallest_component_bytes != 0 {
+                        return Err(AccelerationStructureError::VertexStrideNotAligned {
+                            geometry_index,
+                            vertex_stride,
+                            required_alignment: smallest_component_bytes,
+                        });
+                    }
+
+                    if index_data.is_some() {
+                        // VUID-VkAccelerationStructureGeometryTrianglesDataKHR-indexType-parameter
+                        index_type.validate_device(device)?;
+
+                        // VUID-VkAccelerationStructureGeometryTrianglesDataKHR-indexType-03798
+                        if !matches!(index_type, IndexType::U16 | IndexType::U32) {
+                            return Err(AccelerationStructureError::IndexTypeNotSupported {
+                                geometry_index,
+                                index_type,
+                            });
+                        }
+                    }
+
+                    if is_build && vertex_data.is_none() {
+                        return Err(AccelerationStructureError::GeometryDataMissing {
+                            geometry_index,
+                        });
+                    }
+
+                    for buffer in [vertex_data.as_ref(), index_data.as_ref()]
+                        .into_iter()
+                        .flatten()
+                        .chain(transform_data.as_ref().map(|buffer| buffer.as_bytes()))
+                    {
+                        assert_eq!(device, buffer.device().as_ref());
+                    }
+                }
+            }
+            AccelerationStructureGeometries::Aabbs(geometries) => {
+                for (geometry_index, aabbs_data) in geometries.iter().enumerate() {
+                    let &AccelerationStructureGeometryAabbsData {
+                        flags,
+                        ref data,
+                        stride,
+                        _ne: _,
+                    } = aabbs_data;
+
+                    // VUID-VkAccelerationStructureGeometryKHR-flags-parameter
+                    flags.validate_device(device)?;
+
+                    // VUID-VkAccelerationStructureGeometryAabbsDataKHR-stride-03545
+                    if stride % 8 != 0 {
+                        return Err(AccelerationStructureError::AabbStrideNotAligned {
+                            geometry_index,
+                            stride,
+                        });
+                    }
+
+                    if let Some(data) = data {
+                        assert_eq!(device, data.device().as_ref());
+                    } else if is_build {
+                        return Err(AccelerationStructureError::GeometryDataMissing {
+                            geometry_index,
+                        });
+                    }
+                }
+            }
+            AccelerationStructureGeometries::Instances(instances_data) => {
+                let &AccelerationStructureGeometryInstancesData {
+                    flags,
+                    ref data,
+                    _ne: _,
+                } = instances_data;
+
+                // VUID-VkAccelerationStructureGeometryKHR-flags-parameter
+                flags.validate_device(device)?;
+
+                let data = match data {
+                    AccelerationStructureGeometryInstancesDataType::Values(data) => {
+                        data.as_ref().map(|buffer| buffer.as_bytes())
+                    }
+                    AccelerationStructureGeometryInstancesDataType::Pointers(data) => {
+                        data.as_ref().map(|buffer| buffer.as_bytes())
+                    }
+                };
+
+                if let Some(data) = data {
+                    assert_eq!(device, data.device().as_ref());
+
+                    // VUID-vkCmdBuildAccelerationStructuresKHR-pInfos-03715
+                    // VUID-vkCmdBuildAccelerationStructuresKHR-pInfos-03716
+                    if !host
+                        && data
+                            .device_address()
+                            .map_or(true, |addr| addr.get() % 16 != 0)
+                    {
+                        return Err(AccelerationStructureError::BufferNotAligned {
+                            required_alignment: 16,
+                        });
+                    }
+                } else if is_build {
+                    return Err(AccelerationStructureError::GeometryDataMissing {
+                        geometry_index: 0,
+                    });
+                }
+            }
+        }
+
+        // VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03793
+        if matches!(self.ty(), AccelerationStructureType::BottomLevel)
+            && geometry_count > properties.max_geometry_count.unwrap_or(0)
+        {
+            return Err(AccelerationStructureError::MaxGeometryCountExceeded {
+                geometry_count,
+                max: properties.max_geometry_count.unwrap_or(0),
+            });
+        }
+
+        if let Some(dst_acceleration_structure) = dst_acceleration_structure {
+            assert_eq!(device, dst_acceleration_structure.device().as_ref());
+
+            // VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03789
+            // VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03791
+            if !matches!(
+                dst_acceleration_structure.ty(),
+                AccelerationStructureType::Generic
+            ) && dst_acceleration_structure.ty() != self.ty()
+            {
+                return Err(AccelerationStructureError::DstTypeIncompatible {
+                    provided: dst_acceleration_structure.ty(),
+                    required: self.ty(),
+                });
+            }
+        }
+
+        if let BuildAccelerationStructureMode::Update(src_acceleration_structure) = mode {
+            assert_eq!(device, src_acceleration_structure.device().as_ref());
+        }
+
+        let build_range_infos = match build_range_infos {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+
+        let dst_acceleration_structure = match dst_acceleration_structure {
+            Some(x) => x,
+            None => return Err(AccelerationStructureError::DstAccelerationStructureMissing),
+        };
+
+        let scratch_data = match scratch_data {
+            Some(x) => x,
+            None => return Err(AccelerationStructureError::ScratchDataMissing),
+        };
+
+        assert_eq!(device, scratch_data.device().as_ref());
+
+        // VUID-vkCmdBuildAccelerationStructuresKHR-ppBuildRangeInfos-03676
+        if build_range_infos.len() as u64 != geometry_count {
+            return Err(AccelerationStructureError::BuildRangeInfosCountMismatch {
+                geometry_count,
+                build_range_info_count: build_range_infos.len() as u64,
+            });
+        }
+
+        match geometries {
+            AccelerationStructureGeometries::Triangles(_)
+            | AccelerationStructureGeometries::Aabbs(_) => {
+                let primitive_count: u64 = build_range_infos
+                    .iter()
+                    .map(|info| info.primitive_count as u64)
+                    .sum();
+
+                // VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03795
+                // VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03794
+                if primitive_count > properties.max_primitive_count.unwrap_or(0) {
+                    return Err(AccelerationStructureError::MaxPrimitiveCountExceeded {
+                        primitive_count,
+                        max: properties.max_primitive_count.unwrap_or(0),
+                    });
+                }
+            }
+            AccelerationStructureGeometries::Instances(_) => {
+                let instance_count = build_range_infos[0].primitive_count as u64;
+
+                // VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03801
+                if instance_count > properties.max_instance_count.unwrap_or(0) {
+                    return Err(AccelerationStructureError::MaxInstanceCountExceeded {
+                        instance_count,
+                        max: properties.max_instance_count.unwrap_or(0),
+                    });
+                }
+            }
+        }
+
+        if host {
+            // VUID-vkBuildAccelerationStructuresKHR-pInfos-03722
+            // VUID-vkBuildAccelerationStructuresKHR-pInfos-03725
+            // VUID-vkBuildAccelerationStructuresKHR-pInfos-03771
+            if dst_acceleration_structure.buffer().mapped_ptr().is_none()
+                || scratch_data.mapped_ptr().is_none()
+                || self
+                    .input_buffers()
+                    .any(|(_, buffer)| buffer.mapped_ptr().is_none())
+            {
+                return Err(AccelerationStructureError::BufferNotHostAccessible);
+            }
+
+            if let BuildAccelerationStructureMode::Update(src_acceleration_structure) = mode {
+                // VUID-vkBuildAccelerationStructuresKHR-pInfos-03723
+                if src_acceleration_structure.buffer().mapped_ptr().is_none() {
+                    return Err(AccelerationStructureError::BufferNotHostAccessible);
+                }
+            }
+        } else {
+            // VUID-vkCmdBuildAccelerationStructuresKHR-geometry-03673
+            for (_, buffer) in self.input_buffers() {
+                if !buffer.buffer().usage().contains(
+                    BufferUsage::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY
+                        | BufferUsage::SHADER_DEVICE_ADDRESS,
+                ) {
+                    return Err(AccelerationStructureError::MissingUsage {
+                        usage: "acceleration_structure_build_input_read_only and \
+                            shader_device_address",
+                    });
+                }
+            }
+
+            // VUID-vkCmdBuildAccelerationStructuresKHR-pInfos-03674
+            if !scratch_data
+                .buffer()
+                .usage()
+                .contains(BufferUsage::STORAGE_BUFFER | BufferUsage::SHADER_DEVICE_ADDRESS)
+            {
+                return Err(AccelerationStructureError::MissingUsage {
+                    usage: "storage_buffer and shader_device_address",
+                });
+            }
+
+            // VUID-vkCmdBuildAccelerationStructuresKHR-pInfos-03710
+            let required_alignment = properties
+                .min_acceleration_structure_scratch_offset_alignment
+                .unwrap_or(1) as DeviceSize;
+
+            if scratch_data
+                .device_address()
+                .map_or(true, |addr| addr.get() % required_alignment != 0)
+            {
+                return Err(AccelerationStructureError::BufferNotAligned { required_alignment });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the Vulkan build info, and the geometries that it refers to. The caller must set
+    /// `p_geometries` to point to the geometries.
+    ///
+    /// If `host` is true, then host addresses are used instead of device addresses. Data that is
+    /// `None` is given an address of zero.
+    pub(crate) fn to_vulkan(
+        &self,
+        host: bool,
+    ) -> (
+        ash::vk::AccelerationStructureBuildGeometryInfoKHR,
+        SmallVec<[ash::vk::AccelerationStructureGeometryKHR; 8]>,
+    ) {
+        let &AccelerationStructureBuildGeometryInfo {
+            flags,
+            ref mode,
+            ref dst_acceleration_structure,
+            ref geometries,
+            ref scratch_data,
+            _ne: _,
+        } = self;
+
+        let geometries_vk: SmallVec<[_; 8]> = match geometries {
+            AccelerationStructureGeometries::Triangles(geometries) => geometries
+                .iter()
+                .map(|triangles_data| {
+                    let &AccelerationStructureGeometryTrianglesData {
+                        flags,
+                        vertex_format,
+                        ref vertex_data,
+                        vertex_stride,
+                        max_vertex,
+                        ref index_data,
+                        index_type,
+                        ref transform_data,
+                        _ne: _,
+                    } = triangles_data;
+
+                    ash::vk::AccelerationStructureGeometryKHR {
+                        geometry_type: ash::vk::GeometryTypeKHR::TRIANGLES,
+                        geometry: ash::vk::AccelerationStructureGeometryDataKHR {
+                            triangles: ash::vk::AccelerationStructureGeometryTrianglesDataKHR {
+                                vertex_format: vertex_format.into(),
+                                vertex_data: address_const(vertex_data.as_ref(), host),
+                                vertex_stride: vertex_stride as DeviceSize,
+                                max_vertex,
+                                index_type: if index_data.is_some() {
+                                    index_type.into()
+                                } else {
+                                    ash::vk::IndexType::NONE_KHR
+                                },
+                                index_data: address_const(index_data.as_ref(), host),
+                                transform_data: address_const(
+                                    transform_data
+                                        .as_ref()
+                                        .map(|transform_data| transform_data.as_bytes()),
+                                    host,
+                                ),
+                                ..Default::default()
+                            },
+                        },
+                        flags: flags.into(),
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+            AccelerationStructureGeometries::Aabbs(geometries) => geometries
+                .iter()
+                .map(|aabbs_data| {
+                    let &AccelerationStructureGeometryAabbsData {
+                        flags,
+                        ref data,
+                        stride,
+                        _ne: _,
+                    } = aabbs_data;
+
+                    ash::vk::AccelerationStructureGeometryKHR {
+                        geometry_type: ash::vk::GeometryTypeKHR::AABBS,
+                        geometry: ash::vk::AccelerationStructureGeometryDataKHR {
+                            aabbs: ash::vk::AccelerationStructureGeometryAabbsDataKHR {
+                                data: address_const(data.as_ref(), host),
+                                stride: stride as DeviceSize,
+                                ..Default::default()
+                            },
+                        },
+                        flags: flags.into(),
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+            AccelerationStructureGeometries::Instances(instances_data) => {
+                let &AccelerationStructureGeometryInstancesData {
+                    flags,
+                    ref data,
+                    _ne: _,
+                } = instances_data;
+
+                let (array_of_pointers, data) = match data {
+                    AccelerationStructureGeometryInstancesDataType::Values(data) => {
+                        (false, data.as_ref().map(|buffer| buffer.as_bytes()))
+                    }
+                    AccelerationStructureGeometryInstancesDataType::Pointers(data) => {
+                        (true, data.as_ref().map(|buffer| buffer.as_bytes()))
+                    }
+                };
+
+                [ash::vk::AccelerationStructureGeometryKHR {
+                    geometry_type: ash::vk::GeometryTypeKHR::INSTANCES,
+                    geometry: ash::vk::AccelerationStructureGeometryDataKHR {
+                        instances: ash::vk::AccelerationStructureGeometryInstancesDataKHR {
+                            array_of_pointers: array_of_pointers as ash::vk::Bool32,
+                            data: address_const(data, host),
+                            ..Default::default()
+                        },
+                    },
+                    flags: flags.into(),
+                    ..Default::default()
+                }]
+                .into_iter()
+                .collect()
+            }
+        };
+
+        let (mode_vk, src_acceleration_structure_vk) = match mode {
+            BuildAccelerationStructureMode::Build => (
+                ash::vk::BuildAccelerationStructureModeKHR::BUILD,
+                ash::vk::AccelerationStructureKHR::null(),
+            ),
+            BuildAccelerationStructureMode::Update(src_acceleration_structure) => (
+                ash::vk::BuildAccelerationStructureModeKHR::UPDATE,
+                src_acceleration_structure.handle(),
+            ),
+        };
+
+        let info_vk = ash::vk::AccelerationStructureBuildGeometryInfoKHR {
+            ty: self.ty().into(),
+            flags: flags.into(),
+            mode: mode_vk,
+            src_acceleration_structure: src_acceleration_structure_vk,
+            dst_acceleration_structure: dst_acceleration_structure
+                .as_ref()
+                .map_or_else(ash::vk::AccelerationStructureKHR::null, |dst| dst.handle()),
+            geometry_count: geometries_vk.len() as u32,
+            p_geometries: ptr::null(),
+            pp_geometries: ptr::null(),
+            scratch_data: address(scratch_data.as_ref(), host),
+            ..Default::default()
+        };
+
+        (info_vk, geometries_vk)
+    }
+}
+
+fn address_const(
+    buffer: Option<&Subbuffer<[u8]>>,
+    host: bool,
+) -> ash::vk::DeviceOrHostAddressConstKHR {
+    match buffer {
+        None => ash::vk::DeviceOrHostAddressConstKHR { device_address: 0 },
+        Some(buffer) if host => ash::vk::DeviceOrHostAddressConstKHR {
+            host_address: buffer.mapped_ptr().unwrap().as_ptr(),
+        },
+        Some(buffer) => ash::vk::DeviceOrHostAddressConstKHR {
+            device_address: buffer.device_address().unwrap().get(),
+        },
+    }
+}
+
+fn address(buffer: Option<&Subbuffer<[u8]>>, host: bool) -> ash::vk::DeviceOrHostAddressKHR {
+    match buffer {
+        None => ash::vk::DeviceOrHostAddressKHR { device_address: 0 },
+        Some(buffer) if host => ash::vk::DeviceOrHostAddressKHR {
+            host_address: buffer.mapped_ptr().unwrap().as_ptr(),
+        },
+        Some(buffer) => ash::vk::DeviceOrHostAddressKHR {
+            device_address: buffer.device_address().unwrap().get(),
+        },
+    }
+}
+
+vulkan_bitflags! {
+    #[non_exhaustive]
+
+    /// Flags to control how an acceleration structure should be built.
+    BuildAccelerationStructureFlags = BuildAccelerationStructureFlagsKHR(u32);
+
+    /// The acceleration structure can be updated later with
+    /// [`BuildAccelerationStructureMode::Update`].
+    ALLOW_UPDATE = ALLOW_UPDATE,
+
+    /// The acceleration structure can be the source of a copy with
+    /// [`CopyAccelerationStructureMode::Compact`].
+    ALLOW_COMPACTION = ALLOW_COMPACTION,
+
+    /// Prioritize trace performance over build time.
+    PREFER_FAST_TRACE = PREFER_FAST_TRACE,
+
+    /// Prioritize build time over trace performance.
+    PREFER_FAST_BUILD = PREFER_FAST_BUILD,
+
+    /// Minimize the amount of scratch memory used during the build, and the memory used by the
+    /// acceleration structure itself, at the expense of build time and trace performance.
+    LOW_MEMORY = LOW_MEMORY,
+}
+
+/// What mode an acceleration structure build command should operate in.
+#[derive(Clone, Debug)]
+pub enum BuildAccelerationStructureMode {
+    /// Build a new acceleration structure from scratch.
+    Build,
+
+    /// Update a previously built source acceleration structure with new data, storing the
+    /// updated structure in the destination. The source and destination acceleration structures
+    /// may be the same.
+    ///
+    /// The source acceleration structure must have been built with the
+    /// [`BuildAccelerationStructureFlags::ALLOW_UPDATE`] flag.
+    Update(Arc<AccelerationStructure>),
+}
+
+vulkan_enum! {
+    #[non_exhaustive]
+
+    /// Where the building of an acceleration structure will take place.
+    AccelerationStructureBuildType = AccelerationStructureBuildTypeKHR(i32);
+
+    /// Building will take place on the host.
+    Host = HOST,
+
+    /// Building will take place on the device.
+    Device = DEVICE,
+
+    /// Building will take place on either the host or the device.
+    HostOrDevice = HOST_OR_DEVICE,
+}
+
+/// The geometries that are built into an acceleration structure.
+#[derive(Clone, Debug)]
+pub enum AccelerationStructureGeometries {
+    /// The geometries consist of bottom-level triangles data.
+    Triangles(Vec<AccelerationStructureGeometryTrianglesData>),
+
+    /// The geometries consist of bottom-level axis-aligned bounding box data.
+    Aabbs(Vec<AccelerationStructureGeometryAabbsData>),
+
+    /// The geometries consist of top-level instance data.
+    Instances(AccelerationStructureGeometryInstancesData),
+}
+
+impl AccelerationStructureGeometries {
+    /// Returns the number of geometries.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Triangles(geometries) => geometries.len(),
+            Self::Aabbs(geometries) => geometries.len(),
+            Self::Instances(_) => 1,
+        }
+    }
+
+    /// Returns whether there are no geometries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<Vec<AccelerationStructureGeometryTrianglesData>> for AccelerationStructureGeometries {
+    #[inline]
+    fn from(value: Vec<AccelerationStructureGeometryTrianglesData>) -> Self {
+        Self::Triangles(value)
+    }
+}
+
+impl From<Vec<AccelerationStructureGeometryAabbsData>> for AccelerationStructureGeometries {
+    #[inline]
+    fn from(value: Vec<AccelerationStructureGeometryAabbsData>) -> Self {
+        Self::Aabbs(value)
+    }
+}
+
+impl From<AccelerationStructureGeometryInstancesData> for AccelerationStructureGeometries {
+    #[inline]
+    fn from(value: AccelerationStructureGeometryInstancesData) -> Self {
+        Self::Instances(value)
+    }
+}
+
+vulkan_bitflags! {
+    #[non_exhaustive]
+
+    /// Flags to control how an acceleration structure geometry should be built.
+    GeometryFlags = GeometryFlagsKHR(u32);
+
+    /// The geometry does not invoke the any-hit shaders, even if it is present in a hit group.
+    OPAQUE = OPAQUE,
+
+    /// The any-hit shader will never be called more than once for each primitive in the geometry.
+    NO_DUPLICATE_ANY_HIT_INVOCATION = NO_DUPLICATE_ANY_HIT_INVOCATION,
+}
+
+/// A bottom-level geometry consisting of triangles.
+#[derive(Clone, Debug)]
+pub struct AccelerationStructureGeometryTrianglesData {
+    /// Specifies how the geometry should be built.
+    ///
+    /// The default value is empty.
+    pub flags: GeometryFlags,
+
+    /// The format of each vertex in `vertex_data`.
+    ///
+    /// This works in the same way as formats for vertex buffers. The format must support the
+    /// [`FormatFeatures::ACCELERATION_STRUCTURE_VERTEX_BUFFER`] buffer feature.
+    ///
+    /// There is no default value.
+    pub vertex_format: Format,
+
+    /// The vertex data itself, consisting of an array of `vertex_format` values.
+    ///
+    /// This can be `None` when calling [`Device::acceleration_structure_build_sizes`],
+    /// but must be `Some` otherwise.
+    ///
+    /// The default value is `None`.
+    pub vertex_data: Option<Subbuffer<[u8]>>,
+
+    /// The number of bytes between the start of successive elements in `vertex_data`.
+    ///
+    /// This must be a multiple of the smallest component size of `vertex_format`.
+    ///
+    /// The default value is `0`, which must be overridden.
+    pub vertex_stride: u32,
+
+    /// The highest vertex index that may be read from `vertex_data`.
+    ///
+    /// The default value is `0`, which must be overridden.
+    pub max_vertex: u32,
+
+    /// If indices are to be used, the buffer holding the index data.
+    ///
+    /// The indices will be used to index into the elements of `vertex_data`.
+    ///
+    /// The default value is `None`.
+    pub index_data: Option<Subbuffer<[u8]>>,
+
+    /// The type of the indices in `index_data`. This is ignored if `index_data` is `None`.
+    ///
+    /// Only [`IndexType::U16`] and [`IndexType::U32`] are allowed.
+    ///
+    /// The default value is [`IndexType::U32`].
+    pub index_type: IndexType,
+
+    /// Optionally, a 3x4 matrix that will be used to transform the vertices in
+    /// `vertex_data` to the space in which the acceleration structure is defined.
+    ///
+    /// The first three columns must be a 3x3 invertible matrix.
+    ///
+    /// The default value is `None`.
+    pub transform_data: Option<Subbuffer<TransformMatrix>>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl AccelerationStructureGeometryTrianglesData {
+    /// Returns a `AccelerationStructureGeometryTrianglesData` with the specified
+    /// `vertex_format`.
+    #[inline]
+    pub fn vertex_format(vertex_format: Format) -> Self {
+        Self {
+            flags: GeometryFlags::empty(),
+            vertex_format,
+            vertex_data: None,
+            vertex_stride: 0,
+            max_vertex: 0,
+            index_data: None,
+            index_type: IndexType::U32,
+            transform_data: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// A 3x4 transformation matrix, stored in row-major order.
+pub type TransformMatrix = [[f32; 4]; 3];
+
+/// A bottom-level geometry consisting of axis-aligned bounding boxes.
+#[derive(Clone, Debug)]
+pub struct AccelerationStructureGeometryAabbsData {
+    /// Specifies how the geometry should be built.
+    ///
+    /// The default value is empty.
+    pub flags: GeometryFlags,
+
+    /// The AABB data itself, consisting of an array of [`AabbPositions`] structs.
+    ///
+    /// This can be `None` when calling [`Device::acceleration_structure_build_sizes`],
+    /// but must be `Some` otherwise.
+    ///
+    /// The default value is `None`.
+    pub data: Option<Subbuffer<[u8]>>,
+
+    /// The number of bytes between the start of successive elements in `data`.
+    ///
+    /// This must be a multiple of 8.
+    ///
+    /// The default value is `0`, which must be overridden.
+    pub stride: u32,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for AccelerationStructureGeometryAabbsData {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            flags: GeometryFlags::empty(),
+            data: None,
+            stride: 0,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// A structure representing an axis-aligned bounding box.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)]
+pub struct AabbPositions {
+    /// The minimum of the box, with the X, Y and Z coordinates in order.
+    pub min: [f32; 3],
+
+    /// The maximum of the box, with the X, Y and Z coordinates in order.
+    pub max: [f32; 3],
+}
+
+/// A top-level geometry consisting of instances of bottom-level acceleration structures.
+#[derive(Clone, Debug)]
+pub struct AccelerationStructureGeometryInstancesData {
+    /// Specifies how the geometry should be built.
+    ///
+    /// The default value is empty.
+    pub flags: GeometryFlags,
+
+    /// The instance data itself.
+    ///
+    /// There is no default value.
+    pub data: AccelerationStructureGeometryInstancesDataType,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl AccelerationStructureGeometryInstancesData {
+    /// Returns a `AccelerationStructureGeometryInstancesData` with the specified `data`.
+    #[inline]
+    pub fn data(data: AccelerationStructureGeometryInstancesDataType) -> Self {
+        Self {
+            flags: GeometryFlags::empty(),
+            data,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// The data of a top-level geometry.
+#[derive(Clone, Debug)]
+pub enum AccelerationStructureGeometryInstancesDataType {
+    /// The data buffer contains an array of [`AccelerationStructureInstance`] structures directly.
+    ///
+    /// The inner value can be `None` when calling [`Device::acceleration_structure_build_sizes`],
+    /// but must be `Some` otherwise.
+    Values(Option<Subbuffer<[AccelerationStructureInstance]>>),
+
+    /// The data buffer contains an array of device addresses, each pointing to an
+    /// [`AccelerationStructureInstance`] structure.
+    ///
+    /// The inner value can be `None` when calling [`Device::acceleration_structure_build_sizes`],
+    /// but must be `Some` otherwise.
+    Pointers(Option<Subbuffer<[DeviceSize]>>),
+}
+
+impl From<Subbuffer<[AccelerationStructureInstance]>>
+    for AccelerationStructureGeometryInstancesDataType
+{
+    #[inline]
+    fn from(value: Subbuffer<[AccelerationStructureInstance]>) -> Self {
+        Self::Values(Some(value))
+    }
+}
+
+impl From<Subbuffer<[DeviceSize]>> for AccelerationStructureGeometryInstancesDataType {
+    #[inline]
+    fn from(value: Subbuffer<[DeviceSize]>) -> Self {
+        Self::Pointers(Some(value))
+    }
+}
+
+/// An instance of a bottom-level acceleration structure, as stored in the data of a top-level
+/// geometry.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod, PartialEq)]
+pub struct AccelerationStructureInstance {
+    /// A 3x4 transformation matrix to be applied to the bottom-level acceleration structure.
+    ///
+    /// The default value is the identity matrix.
+    pub transform: TransformMatrix,
+
+    /// The lower 24 bits are a user-supplied identifier for the instance, which is available in
+    /// shaders as `InstanceCustomIndexKHR`. The upper 8 bits are a visibility mask; the instance
+    /// is only hit by rays whose cull mask has at least one bit in common with it.
+    ///
+    /// The default value is `0x00` for the index and `0xFF` for the mask.
+    pub instance_custom_index_and_mask: Packed24_8,
+
+    /// The lower 24 bits are an offset used in calculating the binding table index of the hit
+    /// shader. The upper 8 bits are [`GeometryInstanceFlags`].
+    ///
+    /// The default value is `0x00` for the offset and empty flags.
+    pub instance_shader_binding_table_record_offset_and_flags: Packed24_8,
+
+    /// The device address of the bottom-level acceleration structure in this instance, as
+    /// returned by [`AccelerationStructure::device_address`].
+    ///
+    /// The default value is `0` (null).
+    pub acceleration_structure_reference: DeviceSize,
+}
+
+impl Default for AccelerationStructureInstance {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            transform: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+            ],
+            instance_custom_index_and_mask: Packed24_8::new(0, 0xff),
+            instance_shader_binding_table_record_offset_and_flags: Packed24_8::new(0, 0),
+            acceleration_structure_reference: 0,
+        }
+    }
+}
+
+/// A 32-bit integer value, consisting of a 24-bit low part and an 8-bit high part.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, Hash)]
+pub struct Packed24_8(u32);
+
+impl Packed24_8 {
+    /// Returns a new `Packed24_8` value. Only the lower 24 bits of `low_24` are used.
+    #[inline]
+    pub fn new(low_24: u32, high_8: u8) -> Self {
+        Self((low_24 & 0x00ff_ffff) | ((high_8 as u32) << 24))
+    }
+
+    /// Returns the lower 24 bits.
+    #[inline]
+    pub fn low_24(&self) -> u32 {
+        self.0 & 0x00ff_ffff
+    }
+
+    /// Returns the upper 8 bits.
+    #[inline]
+    pub fn high_8(&self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+}
+
+vulkan_bitflags! {
+    #[non_exhaustive]
+
+    /// Additional options for an instance in a top-level acceleration structure.
+    ///
+    /// These are stored in the upper 8 bits of
+    /// [`AccelerationStructureInstance::instance_shader_binding_table_record_offset_and_flags`];
+    /// use [`GeometryInstanceFlags::into_packed`] to convert them.
+    GeometryInstanceFlags impl {
+        /// Returns the flags as the 8-bit value that is stored in an
+        /// [`AccelerationStructureInstance`].
+        #[inline]
+        pub fn into_packed(self) -> u8 {
+            ash::vk::GeometryInstanceFlagsKHR::from(self).as_raw() as u8
+        }
+    }
+    = GeometryInstanceFlagsKHR(u32);
+
+    /// Disable face culling for the instance.
+    TRIANGLE_FACING_CULL_DISABLE = TRIANGLE_FACING_CULL_DISABLE,
+
+    /// Flip the facing (front vs back) of triangles.
+    TRIANGLE_FLIP_FACING = TRIANGLE_FLIP_FACING,
+
+    /// Geometries in this instance will act as if [`GeometryFlags::OPAQUE`] were specified.
+    FORCE_OPAQUE = FORCE_OPAQUE,
+
+    /// Geometries in this instance will act as if [`GeometryFlags::OPAQUE`] were not specified.
+    FORCE_NO_OPAQUE = FORCE_NO_OPAQUE,
+}
+
+/// Counts and offsets for an acceleration structure build operation.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq)]
+pub struct AccelerationStructureBuildRangeInfo {
+    /// The number of primitives.
+    ///
+    /// For triangles, this is the number of triangles, for AABBs the number of boxes, and for
+    /// instances the number of instances.
+    pub primitive_count: u32,
+
+    /// The offset (in bytes) into the buffer holding geometry data, to where the first primitive
+    /// is stored.
+    pub primitive_offset: u32,
+
+    /// The index of the first vertex to build from. This is used only for triangle geometries
+    /// that use an index buffer.
+    pub first_vertex: u32,
+
+    /// The offset (in bytes) into the buffer holding transform matrices, to where the matrix is
+    /// stored. This is used only for triangle geometries that have a transform.
+    pub transform_offset: u32,
+}
+
+impl From<AccelerationStructureBuildRangeInfo> for ash::vk::AccelerationStructureBuildRangeInfoKHR {
+    #[inline]
+    fn from(val: AccelerationStructureBuildRangeInfo) -> Self {
+        Self {
+            primitive_count: val.primitive_count,
+            primitive_offset: val.primitive_offset,
+            first_vertex: val.first_vertex,
+            transform_offset: val.transform_offset,
+        }
+    }
+}
+
+/// The sizes that are needed to build an acceleration structure, as returned by
+/// [`Device::acceleration_structure_build_sizes`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct AccelerationStructureBuildSizesInfo {
+    /// The minimum required size of the acceleration structure for a build or update operation.
+    pub acceleration_structure_size: DeviceSize,
+
+    /// The minimum required size of the scratch data buffer for an update operation.
+    pub update_scratch_size: DeviceSize,
+
+    /// The minimum required size of the scratch data buffer for a build operation.
+    pub build_scratch_size: DeviceSize,
+}
+
+/// Parameters for copying an acceleration structure into another.
+#[derive(Clone, Debug)]
+pub struct CopyAccelerationStructureInfo {
+    /// The acceleration structure to copy from.
+    ///
+    /// There is no default value.
+    pub src: Arc<AccelerationStructure>,
+
+    /// The acceleration structure to copy into.
+    ///
+    /// There is no default value.
+    pub dst: Arc<AccelerationStructure>,
+
+    /// Additional operations to perform during the copy.
+    ///
+    /// Only [`CopyAccelerationStructureMode::Clone`] and [`CopyAccelerationStructureMode::Compact`]
+    /// are allowed.
+    ///
+    /// The default value is [`CopyAccelerationStructureMode::Clone`].
+    pub mode: CopyAccelerationStructureMode,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl CopyAccelerationStructureInfo {
+    /// Returns a `CopyAccelerationStructureInfo` with the specified `src` and `dst`.
+    #[inline]
+    pub fn new(src: Arc<AccelerationStructure>, dst: Arc<AccelerationStructure>) -> Self {
+        Self {
+            src,
+            dst,
+            mode: CopyAccelerationStructureMode::Clone,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+
+    pub(crate) fn validate(
+        &self,
+        device: &Device,
+        host: bool,
+    ) -> Result<(), AccelerationStructureError> {
+        let &Self {
+            ref src,
+            ref dst,
+            mode,
+            _ne: _,
+        } = self;
+
+        assert_eq!(device, src.device().as_ref());
+        assert_eq!(device, dst.device().as_ref());
+
+        // VUID-VkCopyAccelerationStructureInfoKHR-mode-parameter
+        mode.validate_device(device)?;
+
+        // VUID-VkCopyAccelerationStructureInfoKHR-mode-03410
+        if !matches!(
+            mode,
+            CopyAccelerationStructureMode::Clone | CopyAccelerationStructureMode::Compact
+        ) {
+            return Err(AccelerationStructureError::CopyModeNotAllowed { mode });
+        }
+
+        // VUID-VkCopyAccelerationStructureInfoKHR-dst-07791
+        if src.buffer().buffer() == dst.buffer().buffer() {
+            let src_range = src.buffer().offset()..src.buffer().offset() + src.size();
+            let dst_range = dst.buffer().offset()..dst.buffer().offset() + dst.size();
+
+            if src_range.start < dst_range.end && dst_range.start < src_range.end {
+                return Err(AccelerationStructureError::SrcDstOverlap);
+            }
+        }
+
+        if host {
+            // VUID-vkCopyAccelerationStructureKHR-buffer-03727
+            // VUID-vkCopyAccelerationStructureKHR-buffer-03728
+            if src.buffer().mapped_ptr().is_none() || dst.buffer().mapped_ptr().is_none() {
+                return Err(AccelerationStructureError::BufferNotHostAccessible);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn to_vulkan(&self) -> ash::vk::CopyAccelerationStructureInfoKHR {
+        ash::vk::CopyAccelerationStructureInfoKHR {
+            src: self.src.handle(),
+            dst: self.dst.handle(),
+            mode: self.mode.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parameters for serializing an acceleration structure into a buffer.
+#[derive(Clone, Debug)]
+pub struct CopyAccelerationStructureToMemoryInfo {
+    /// The acceleration structure to copy from.
+    ///
+    /// There is no default value.
+    pub src: Arc<AccelerationStructure>,
+
+    /// The buffer to copy into.
+    ///
+    /// Its device address must be a multiple of 256, and its buffer must have the
+    /// [`BufferUsage::SHADER_DEVICE_ADDRESS`] usage.
+    ///
+    /// There is no default value.
+    pub dst: Subbuffer<[u8]>,
+
+    /// Additional operations to perform during the copy.
+    ///
+    /// Only [`CopyAccelerationStructureMode::Serialize`] is allowed.
+    ///
+    /// The default value is [`CopyAccelerationStructureMode::Serialize`].
+    pub mode: CopyAccelerationStructureMode,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl CopyAccelerationStructureToMemoryInfo {
+    /// Returns a `CopyAccelerationStructureToMemoryInfo` with the specified `src` and `dst`.
+    #[inline]
+    pub fn new(src: Arc<AccelerationStructure>, dst: Subbuffer<[u8]>) -> Self {
+        Self {
+            src,
+            dst,
+            mode: CopyAccelerationStructureMode::Serialize,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+
+    pub(crate) fn validate(&self, device: &Device) -> Result<(), AccelerationStructureError> {
+        let &Self {
+            ref src,
+            ref dst,
+            mode,
+            _ne: _,
+        } = self;
+
+        assert_eq!(device, src.device().as_ref());
+        assert_eq!(device, dst.device().as_ref());
+
+        // VUID-VkCopyAccelerationStructureToMemoryInfoKHR-mode-parameter
+        mode.validate_device(device)?;
+
+        // VUID-VkCopyAccelerationStructureToMemoryInfoKHR-mode-03412
+        if !matches!(mode, CopyAccelerationStructureMode::Serialize) {
+            return Err(AccelerationStructureError::CopyModeNotAllowed { mode });
+        }
+
+        if !dst
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::SHADER_DEVICE_ADDRESS)
+        {
+            return Err(AccelerationStructureError::MissingUsage {
+                usage: "shader_device_address",
+            });
+        }
+
+        // VUID-vkCmdCopyAccelerationStructureToMemoryKHR-pInfo-03740
+        if dst
+            .device_address()
+            .map_or(true, |addr| addr.get() % 256 != 0)
+        {
+            return Err(AccelerationStructureError::BufferNotAligned {
+                required_alignment: 256,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn to_vulkan(&self) -> ash::vk::CopyAccelerationStructureToMemoryInfoKHR {
+        ash::vk::CopyAccelerationStructureToMemoryInfoKHR {
+            src: self.src.handle(),
+            dst: address(Some(&self.dst), false),
+            mode: self.mode.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parameters for deserializing an acceleration structure from a buffer.
+#[derive(Clone, Debug)]
+pub struct CopyMemoryToAccelerationStructureInfo {
+    /// The buffer to copy from.
+    ///
+    /// Its device address must be a multiple of 256, and its buffer must have the
+    /// [`BufferUsage::SHADER_DEVICE_ADDRESS`] usage.
+    ///
+    /// There is no default value.
+    pub src: Subbuffer<[u8]>,
+
+    /// The acceleration structure to copy into.
+    ///
+    /// There is no default value.
+    pub dst: Arc<AccelerationStructure>,
+
+    /// Additional operations to perform during the copy.
+    ///
+    /// Only [`CopyAccelerationStructureMode::Deserialize`] is allowed.
+    ///
+    /// The default value is [`CopyAccelerationStructureMode::Deserialize`].
+    pub mode: CopyAccelerationStructureMode,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl CopyMemoryToAccelerationStructureInfo {
+    /// Returns a `CopyMemoryToAccelerationStructureInfo` with the specified `src` and `dst`.
+    #[inline]
+    pub fn new(src: Subbuffer<[u8]>, dst: Arc<AccelerationStructure>) -> Self {
+        Self {
+            src,
+            dst,
+            mode: CopyAccelerationStructureMode::Deserialize,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+
+    pub(crate) fn validate(&self, device: &Device) -> Result<(), AccelerationStructureError> {
+        let &Self {
+            ref src,
+            ref dst,
+            mode,
+            _ne: _,
+        } = self;
+
+        assert_eq!(device, src.device().as_ref());
+        assert_eq!(device, dst.device().as_ref());
+
+        // VUID-VkCopyMemoryToAccelerationStructureInfoKHR-mode-parameter
+        mode.validate_device(device)?;
+
+        // VUID-VkCopyMemoryToAccelerationStructureInfoKHR-mode-03413
+        if !matches!(mode, CopyAccelerationStructureMode::Deserialize) {
+            return Err(AccelerationStructureError::CopyModeNotAllowed { mode });
+        }
+
+        if !src
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::SHADER_DEVICE_ADDRESS)
+        {
+            return Err(AccelerationStructureError::MissingUsage {
+                usage: "shader_device_address",
+            });
+        }
+
+        // VUID-vkCmdCopyMemoryToAccelerationStructureKHR-pInfo-03743
+        if src
+            .device_address()
+            .map_or(true, |addr| addr.get() % 256 != 0)
+        {
+            return Err(AccelerationStructureError::BufferNotAligned {
+                required_alignment: 256,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn to_vulkan(&self) -> ash::vk::CopyMemoryToAccelerationStructureInfoKHR {
+        ash::vk::CopyMemoryToAccelerationStructureInfoKHR {
+            src: address_const(Some(&self.src), false),
+            dst: self.dst.handle(),
+            mode: self.mode.into(),
+            ..Default::default()
+        }
+    }
+}
+
+vulkan_enum! {
+    #[non_exhaustive]
+
+    /// What mode an acceleration structure copy command should operate in.
+    CopyAccelerationStructureMode = CopyAccelerationStructureModeKHR(i32);
+
+    /// Copy the source into the destination.
+    /// This is a shallow copy: if the source holds references to other acceleration structures,
+    /// only the references are copied, not the other acceleration structures.
+    ///
+    /// Both source and destination must have been created with the same
+    /// [`AccelerationStructureCreateInfo`].
+    Clone = CLONE,
+
+    /// Create a more compact version of the source in the destination.
+    /// This is a shallow copy: if the source holds references to other acceleration structures,
+    /// only the references are copied, not the other acceleration structures.
+    ///
+    /// The source acceleration structure must have been built with the
+    /// [`BuildAccelerationStructureFlags::ALLOW_COMPACTION`] flag.
+    Compact = COMPACT,
+
+    /// Serialize the acceleration structure into data in a semi-opaque format,
+    /// that can be deserialized by a compatible Vulkan implementation.
+    Serialize = SERIALIZE,
+
+    /// Deserialize data back into an acceleration structure.
+    Deserialize = DESERIALIZE,
+}
+
+/// Error that can happen when creating an `AccelerationStructure`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccelerationStructureCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// The buffer was not created with the [`BufferUsage::ACCELERATION_STRUCTURE_STORAGE`] usage.
+    BufferMissingUsage,
+
+    /// The offset of the buffer is not a multiple of 256.
+    BufferOffsetNotAligned { offset: DeviceSize },
+}
+
+impl Error for AccelerationStructureCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OomError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AccelerationStructureCreationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::BufferMissingUsage => write!(
+                f,
+                "the buffer was not created with the `acceleration_structure_storage` usage",
+            ),
+            Self::BufferOffsetNotAligned { offset } => write!(
+                f,
+                "the offset of the buffer ({}) is not a multiple of 256",
+                offset,
+            ),
+        }
+    }
+}
+
+impl From<OomError> for AccelerationStructureCreationError {
+    fn from(err: OomError) -> Self {
+        Self::OomError(err)
+    }
+}
+
+impl From<VulkanError> for AccelerationStructureCreationError {
+    fn from(err: VulkanError) -> Self {
+        match err {
+            err @ VulkanError::OutOfHostMemory | err @ VulkanError::OutOfDeviceMemory => {
+                Self::OomError(OomError::from(err))
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+impl From<RequirementNotMet> for AccelerationStructureCreationError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
+/// Error that can happen when building, copying or querying acceleration structures, either on
+/// the host or in a command buffer.
+#[derive(Clone, Debug)]
+pub enum AccelerationStructureError {
+    SyncCommandBufferBuilderError(SyncCommandBufferBuilderError),
+
+    /// Not enough memory.
+    OomError(OomError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// Operation forbidden inside of a render pass.
+    ForbiddenInsideRenderPass,
+
+    /// The queue family doesn't allow this operation.
+    NotSupportedByQueueFamily,
+
+    /// The stride of an AABBs geometry is not a multiple of 8.
+    AabbStrideNotAligned {
+        geometry_index: usize,
+        stride: u32,
+    },
+
+    /// A buffer that is accessed on the host is not host-visible memory, or is not mapped.
+    BufferNotHostAccessible,
+
+    /// The device address of a buffer is not a multiple of the required alignment.
+    BufferNotAligned {
+        required_alignment: DeviceSize,
+    },
+
+    /// Both the `PREFER_FAST_TRACE` and `PREFER_FAST_BUILD` build flags were set.
+    BuildFlagsPreferTraceAndBuild,
+
+    /// The number of build range infos does not match the number of geometries.
+    BuildRangeInfosCountMismatch {
+        geometry_count: u64,
+        build_range_info_count: u64,
+    },
+
+    /// The copy mode is not allowed for this operation.
+    CopyModeNotAllowed {
+        mode: CopyAccelerationStructureMode,
+    },
+
+    /// The `dst_acceleration_structure` of a build was `None`.
+    DstAccelerationStructureMissing,
+
+    /// The type of the destination acceleration structure is not compatible with the geometries
+    /// that are built into it.
+    DstTypeIncompatible {
+        provided: AccelerationStructureType,
+        required: AccelerationStructureType,
+    },
+
+    /// The data of a geometry was `None` while performing a build.
+    GeometryDataMissing {
+        geometry_index: usize,
+    },
+
+    /// The index type of a triangles geometry is not supported.
+    IndexTypeNotSupported {
+        geometry_index: usize,
+        index_type: IndexType,
+    },
+
+    /// The number of geometries exceeds the
+    /// [`max_geometry_count`](crate::device::Properties::max_geometry_count) limit.
+    MaxGeometryCountExceeded {
+        geometry_count: u64,
+        max: u64,
+    },
+
+    /// The number of instances exceeds the
+    /// [`max_instance_count`](crate::device::Properties::max_instance_count) limit.
+    MaxInstanceCountExceeded {
+        instance_count: u64,
+        max: u64,
+    },
+
+    /// The total number of primitives exceeds the
+    /// [`max_primitive_count`](crate::device::Properties::max_primitive_count) limit.
+    MaxPrimitiveCountExceeded {
+        primitive_count: u64,
+        max: u64,
+    },
+
+    /// A buffer was missing a usage flag that was required.
+    MissingUsage {
+        usage: &'static str,
+    },
+
+    /// The number of primitive counts does not match the number of geometries.
+    PrimitiveCountsCountMismatch {
+        geometry_count: u64,
+        primitive_counts_count: u64,
+    },
+
+    /// The query is out of range for the query pool.
+    QueryOutOfRange {
+        query_index: u32,
+        query_count: u32,
+    },
+
+    /// The query pool has a type that is not allowed for this operation.
+    QueryTypeNotAllowed,
+
+    /// The `scratch_data` of a build was `None`.
+    ScratchDataMissing,
+
+    /// The source and destination acceleration structures overlap in memory.
+    SrcDstOverlap,
+
+    /// The format of a triangles geometry does not support the
+    /// [`FormatFeatures::ACCELERATION_STRUCTURE_VERTEX_BUFFER`] buffer feature.
+    VertexFormatNotSupported {
+        geometry_index: usize,
+        format: Format,
+    },
+
+    /// The vertex stride of a triangles geometry is not a multiple of the smallest component size
+    /// of the vertex format.
+    VertexStrideNotAligned {
+        geometry_index: usize,
+        vertex_stride: u32,
+        required_alignment: u32,
+    },
+}
+
+impl Error for AccelerationStructureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SyncCommandBufferBuilderError(err) => Some(err),
+            Self::OomError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AccelerationStructureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::SyncCommandBufferBuilderError(_) => write!(f, "a SyncCommandBufferBuilderError"),
+            Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::ForbiddenInsideRenderPass => {
+                write!(f, "operation forbidden inside of a render pass")
+            }
+            Self::NotSupportedByQueueFamily => {
+                write!(f, "the queue family doesn't allow this operation")
+            }
+            Self::AabbStrideNotAligned {
+                geometry_index,
+                stride,
+            } => write!(
+                f,
+                "the stride ({}) of AABBs geometry {} is not a multiple of 8",
+                stride, geometry_index,
+            ),
+            Self::BufferNotHostAccessible => write!(
+                f,
+                "a buffer that is accessed on the host is not host-visible memory, or is not \
+                mapped",
+            ),
+            Self::BufferNotAligned { required_alignment } => write!(
+                f,
+                "the device address of a buffer is not a multiple of the required alignment ({})",
+                required_alignment,
+            ),
+            Self::BuildFlagsPreferTraceAndBuild => write!(
+                f,
+                "both the `prefer_fast_trace` and `prefer_fast_build` build flags were set",
+            ),
+            Self::BuildRangeInfosCountMismatch {
+                geometry_count,
+                build_range_info_count,
+            } => write!(
+                f,
+                "the number of build range infos ({}) does not match the number of geometries \
+                ({})",
+                build_range_info_count, geometry_count,
+            ),
+            Self::CopyModeNotAllowed { mode } => write!(
+                f,
+                "the copy mode {:?} is not allowed for this operation",
+                mode,
+            ),
+            Self::DstAccelerationStructureMissing => {
+                write!(f, "the `dst_acceleration_structure` of a build was `None`",)
+            }
+            Self::DstTypeIncompatible { provided, required } => write!(
+                f,
+                "the type of the destination acceleration structure ({:?}) is not compatible \
+                with the geometries that are built into it ({:?})",
+                provided, required,
+            ),
+            Self::GeometryDataMissing { geometry_index } => write!(
+                f,
+                "the data of geometry {} was `None` while performing a build",
+                geometry_index,
+            ),
+            Self::IndexTypeNotSupported {
+                geometry_index,
+                index_type,
+            } => write!(
+                f,
+                "the index type {:?} of triangles geometry {} is not supported",
+                index_type, geometry_index,
+            ),
+            Self::MaxGeometryCountExceeded {
+                geometry_count,
+                max,
+            } => write!(
+                f,
+                "the number of geometries ({}) exceeds the `max_geometry_count` limit ({})",
+                geometry_count, max,
+            ),
+            Self::MaxInstanceCountExceeded {
+                instance_count,
+                max,
+            } => write!(
+                f,
+                "the number of instances ({}) exceeds the `max_instance_count` limit ({})",
+                instance_count, max,
+            ),
+            Self::MaxPrimitiveCountExceeded {
+                primitive_count,
+                max,
+            } => write!(
+                f,
+                "the total number of primitives ({}) exceeds the `max_primitive_count` limit ({})",
+                primitive_count, max,
+            ),
+            Self::MissingUsage { usage } => {
+                write!(f, "a buffer was missing the required usage: {}", usage,)
+            }
+            Self::PrimitiveCountsCountMismatch {
+                geometry_count,
+                primitive_counts_count,
+            } => write!(
+                f,
+                "the number of primitive counts ({}) does not match the number of geometries ({})",
+                primitive_counts_count, geometry_count,
+            ),
+            Self::QueryOutOfRange {
+                query_index,
+                query_count,
+            } => write!(
+                f,
+                "the query index {} is out of range for the query pool with {} queries",
+                query_index, query_count,
+            ),
+            Self::QueryTypeNotAllowed => write!(
+                f,
+                "the query pool has a type that is not allowed for this operation",
+            ),
+            Self::ScratchDataMissing => write!(f, "the `scratch_data` of a build was `None`"),
+            Self::SrcDstOverlap => write!(
+                f,
+                "the source and destination acceleration structures overlap in memory",
+            ),
+            Self::VertexFormatNotSupported {
+                geometry_index,
+                format,
+            } => write!(
+                f,
+                "the format {:?} of triangles geometry {} does not support the \
+                `acceleration_structure_vertex_buffer` buffer feature",
+                format, geometry_index,
+            ),
+            Self::VertexStrideNotAligned {
+                geometry_index,
+                vertex_stride,
+                required_alignment,
+            } => write!(
+                f,
+                "the vertex stride ({}) of triangles geometry {} is not a multiple of the smallest \
+                component size of the vertex format ({})",
+                vertex_stride, geometry_index, required_alignment,
+            ),
+        }
+    }
+}
+
+impl From<SyncCommandBufferBuilderError> for AccelerationStructureError {
+    fn from(err: SyncCommandBufferBuilderError) -> Self {
+        Self::SyncCommandBufferBuilderError(err)
+    }
+}
+
+impl From<OomError> for AccelerationStructureError {
+    fn from(err: OomError) -> Self {
+        Self::OomError(err)
+    }
+}
+
+impl From<VulkanError> for AccelerationStructureError {
+    fn from(err: VulkanError) -> Self {
+        match err {
+            err @ VulkanError::OutOfHostMemory | err @ VulkanError::OutOfDeviceMemory => {
+                Self::OomError(OomError::from(err))
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+impl From<RequirementNotMet> for AccelerationStructureError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AccelerationStructureBuildGeometryInfo, AccelerationStructureBuildType,
+        AccelerationStructureError, AccelerationStructureGeometries,
+        AccelerationStructureGeometryAabbsData, AccelerationStructureInstance,
+        BuildAccelerationStructureFlags, GeometryInstanceFlags, Packed24_8,
+    };
+    use std::mem::size_of;
+
+    #[test]
+    fn instance_layout() {
+        // The layout must match `VkAccelerationStructureInstanceKHR`.
+        assert_eq!(size_of::<AccelerationStructureInstance>(), 64);
+
+        let packed = Packed24_8::new(
+            0x0123_4567,
+            GeometryInstanceFlags::FORCE_OPAQUE.into_packed(),
+        );
+        assert_eq!(packed.low_24(), 0x23_4567);
+        assert_eq!(packed.high_8(), 0x04);
+    }
+
+    #[test]
+    fn build_sizes_feature_required() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let build_info = AccelerationStructureBuildGeometryInfo::geometries(
+            AccelerationStructureGeometries::Aabbs(vec![AccelerationStructureGeometryAabbsData {
+                stride: 24,
+                ..Default::default()
+            }]),
+        );
+
+        match device.acceleration_structure_build_sizes(
+            AccelerationStructureBuildType::Device,
+            &build_info,
+            &[1],
+        ) {
+            Err(AccelerationStructureError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn prefer_trace_and_build() {
+        let (device, _) = gfx_dev_and_queue!(acceleration_structure);
+
+        let build_info = AccelerationStructureBuildGeometryInfo {
+            flags: BuildAccelerationStructureFlags::PREFER_FAST_TRACE
+                | BuildAccelerationStructureFlags::PREFER_FAST_BUILD,
+            ..AccelerationStructureBuildGeometryInfo::geometries(
+                AccelerationStructureGeometries::Aabbs(vec![
+                    AccelerationStructureGeometryAabbsData {
+                        stride: 24,
+                        ..Default::default()
+                    },
+                ]),
+            )
+        };
+
+        match device.acceleration_structure_build_sizes(
+            AccelerationStructureBuildType::Device,
+            &build_info,
+            &[1],
+        ) {
+            Err(AccelerationStructureError::BuildFlagsPreferTraceAndBuild) => (),
+            _ => panic!(),
+        }
+    }
+}
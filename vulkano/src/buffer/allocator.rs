@@ -22,12 +22,17 @@ use crate::{
         },
         DeviceAlignment,
     },
-    DeviceSize, NonZeroDeviceSize,
+    sync::{
+        fence::{Fence, FenceError},
+        future::{FenceSignalFuture, GpuFuture},
+    },
+    DeviceSize, NonZeroDeviceSize, OomError, VulkanError,
 };
 use crossbeam_queue::ArrayQueue;
 use std::{
     cell::UnsafeCell,
     cmp,
+    collections::VecDeque,
     hash::{Hash, Hasher},
     mem::ManuallyDrop,
     sync::Arc,
@@ -79,6 +84,67 @@ const MAX_ARENAS: usize = 32;
 /// Download or device-only usage is much the same. Try to make the arenas fit all the data you
 /// need to store at once.
 ///
+/// # Ring buffer
+///
+/// Even when the arenas are sized well, a new arena has to be allocated whenever all of them are
+/// still in use, which can cause spikes in the middle of a frame. To avoid this, the allocator
+/// can instead be created with [`SubbufferAllocatorMode::RingBuffer`] or
+/// [`SubbufferAllocatorMode::BlockingRingBuffer`]. It then allocates a single buffer of
+/// `arena_size` bytes up front, and suballocates it as a ring: subbuffers are allocated one after
+/// the other, wrapping around to the start of the buffer when reaching the end.
+///
+/// The allocator can't know when the GPU is done with a subbuffer by itself, so the allocations
+/// are grouped into frames. At the end of each frame, call [`finish_frame`] with the future that
+/// signals the end of the GPU work of the frame. The space of the frame is reused once the fence
+/// of that future is signaled. When the ring is full and the oldest frame is still in use, the
+/// allocator either returns [`AllocationCreationError::OutOfPoolMemory`] or waits for the oldest
+/// frame to finish, depending on the mode.
+///
+/// ```
+/// use vulkano::buffer::allocator::{
+///     SubbufferAllocator, SubbufferAllocatorCreateInfo, SubbufferAllocatorMode,
+/// };
+/// use vulkano::buffer::BufferUsage;
+/// use vulkano::command_buffer::{
+///     AutoCommandBufferBuilder, CommandBufferUsage, PrimaryCommandBufferAbstract,
+/// };
+/// use vulkano::sync::GpuFuture;
+/// # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+/// # let memory_allocator: std::sync::Arc<vulkano::memory::allocator::StandardMemoryAllocator> = return;
+/// # let command_buffer_allocator: vulkano::command_buffer::allocator::StandardCommandBufferAllocator = return;
+///
+/// // Enough room for 3 frames of 64 KiB of uniforms each.
+/// let uniform_allocator = SubbufferAllocator::new(
+///     memory_allocator.clone(),
+///     SubbufferAllocatorCreateInfo {
+///         arena_size: 3 * 64 * 1024,
+///         buffer_usage: BufferUsage::UNIFORM_BUFFER,
+///         mode: SubbufferAllocatorMode::BlockingRingBuffer,
+///         ..Default::default()
+///     },
+/// );
+///
+/// for n in 0..25u32 {
+///     let subbuffer = uniform_allocator.allocate_sized().unwrap();
+///     *subbuffer.write().unwrap() = [n as f32, 0.0, 0.0, 1.0];
+///
+///     let future = AutoCommandBufferBuilder::primary(
+///         &command_buffer_allocator,
+///         queue.queue_family_index(),
+///         CommandBufferUsage::OneTimeSubmit,
+///     )
+///     .unwrap()
+///     .build()
+///     .unwrap()
+///     .execute(queue.clone())
+///     .unwrap()
+///     .then_signal_fence_and_flush()
+///     .unwrap();
+///
+///     uniform_allocator.finish_frame(&future);
+/// }
+/// ```
+///
 /// # Examples
 ///
 /// ```
@@ -128,14 +194,21 @@ where
     A: MemoryAllocator,
 {
     /// Creates a new `SubbufferAllocator`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.mode` is a ring buffer mode and `create_info.arena_size` is zero.
     pub fn new(memory_allocator: A, create_info: SubbufferAllocatorCreateInfo) -> Self {
         let SubbufferAllocatorCreateInfo {
             arena_size,
             buffer_usage,
             memory_usage,
+            mode,
             _ne: _,
         } = create_info;
 
+        assert!(mode == SubbufferAllocatorMode::Arenas || arena_size != 0);
+
         let properties = memory_allocator.device().physical_device().properties();
         let buffer_alignment = [
             buffer_usage
@@ -163,10 +236,19 @@ where
                 arena: None,
                 free_start: 0,
                 reserve: None,
+                mode,
+                ring_head: 0,
+                ring_tail: 0,
+                frames: VecDeque::new(),
             }),
         }
     }
 
+    /// Returns the mode of the allocator.
+    pub fn mode(&self) -> SubbufferAllocatorMode {
+        unsafe { &*self.state.get() }.mode
+    }
+
     /// Returns the current size of the arenas.
     pub fn arena_size(&self) -> DeviceSize {
         unsafe { &*self.state.get() }.arena_size
@@ -176,8 +258,15 @@ where
     ///
     /// The next time you allocate a subbuffer, a new arena will be allocated with the new size,
     /// and all subsequently allocated arenas will also share the new size.
+    ///
+    /// In a ring buffer mode, this replaces the ring buffer with a new one of the new size.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the allocator is in a ring buffer mode and `size` is zero.
     pub fn set_arena_size(&self, size: DeviceSize) {
         let state = unsafe { &mut *self.state.get() };
+        assert!(state.mode == SubbufferAllocatorMode::Arenas || size != 0);
         state.arena_size = size;
         state.arena = None;
         state.reserve = None;
@@ -188,17 +277,47 @@ where
     /// If `size` is greater than the current arena size, then a new arena will be allocated with
     /// the new size, and all subsequently allocated arenas will also share the new size. Otherwise
     /// this has no effect.
+    ///
+    /// In a ring buffer mode, this replaces the ring buffer with a new one of the new size.
     pub fn reserve(&self, size: DeviceSize) -> Result<(), AllocationCreationError> {
         if size > self.arena_size() {
             let state = unsafe { &mut *self.state.get() };
             state.arena_size = size;
             state.reserve = None;
-            state.arena = Some(state.next_arena()?);
+
+            if state.mode == SubbufferAllocatorMode::Arenas {
+                state.arena = Some(state.next_arena()?);
+            } else {
+                state.create_ring()?;
+            }
         }
 
         Ok(())
     }
 
+    /// Marks the end of a frame, whose GPU work is finished once `future` is signaled.
+    ///
+    /// In a ring buffer mode, the space of the subbuffers allocated since the previous call is
+    /// reused once the fence of `future` is signaled. `future` must have been flushed, otherwise
+    /// waiting for it in [`SubbufferAllocatorMode::BlockingRingBuffer`] mode never returns.
+    ///
+    /// In [`SubbufferAllocatorMode::Arenas`] mode, this has no effect.
+    pub fn finish_frame<F>(&self, future: &FenceSignalFuture<F>)
+    where
+        F: GpuFuture,
+    {
+        let state = unsafe { &mut *self.state.get() };
+
+        if state.mode == SubbufferAllocatorMode::Arenas {
+            return;
+        }
+
+        state.frames.push_back((state.ring_head, future.fence()));
+
+        // Reclaim what we can right away, so that frames don't pile up if nothing is allocated.
+        let _ = state.retire_frames(false);
+    }
+
     /// Allocates a subbuffer for sized data.
     pub fn allocate_sized<T>(&self) -> Result<Subbuffer<T>, AllocationCreationError>
     where
@@ -285,6 +404,14 @@ struct SubbufferAllocatorState<A> {
     free_start: DeviceSize,
     // When an `Arena` is dropped, it returns itself here for reuse.
     reserve: Option<Arc<ArrayQueue<Arc<Buffer>>>>,
+    mode: SubbufferAllocatorMode,
+    // In a ring buffer mode, the positions of the end and the start of the used part of the ring.
+    // These only ever increase, the offset in the ring is the position modulo the ring size.
+    ring_head: DeviceSize,
+    ring_tail: DeviceSize,
+    // In a ring buffer mode, the position of the end of each frame that is in flight, along with
+    // the fence that signals the end of the frame, or `None` if it was already signaled.
+    frames: VecDeque<(DeviceSize, Option<Arc<Fence>>)>,
 }
 
 impl<A> SubbufferAllocatorState<A>
@@ -295,6 +422,12 @@ where
         &mut self,
         layout: DeviceLayout,
     ) -> Result<Subbuffer<[u8]>, AllocationCreationError> {
+        match self.mode {
+            SubbufferAllocatorMode::Arenas => (),
+            SubbufferAllocatorMode::RingBuffer => return self.allocate_ring(layout, false),
+            SubbufferAllocatorMode::BlockingRingBuffer => return self.allocate_ring(layout, true),
+        }
+
         let size = layout.size();
         let alignment = cmp::max(layout.alignment(), self.buffer_alignment);
 
@@ -335,6 +468,99 @@ where
         }
     }
 
+    fn allocate_ring(
+        &mut self,
+        layout: DeviceLayout,
+        wait: bool,
+    ) -> Result<Subbuffer<[u8]>, AllocationCreationError> {
+        if self.arena.is_none() {
+            self.create_ring()?;
+        }
+
+        let arena = self.arena.clone().unwrap();
+        let allocation = match arena.buffer.memory() {
+            BufferMemory::Normal(a) => a,
+            BufferMemory::Sparse => unreachable!(),
+        };
+        let arena_offset = allocation.offset();
+        let atom_size = allocation.atom_size().unwrap_or(DeviceAlignment::MIN);
+
+        let size = layout.size();
+        let alignment = cmp::max(layout.alignment(), self.buffer_alignment);
+        let alignment = cmp::max(alignment, atom_size);
+        let ring_size = self.arena_size;
+
+        // The offset that allocations start at after wrapping around.
+        let wrap_offset = align_up(arena_offset, alignment) - arena_offset;
+
+        if wrap_offset + size > ring_size {
+            return Err(AllocationCreationError::OutOfPoolMemory);
+        }
+
+        loop {
+            let head_offset = self.ring_head % ring_size;
+            let ring_start = self.ring_head - head_offset;
+            let offset = align_up(arena_offset + head_offset, alignment) - arena_offset;
+
+            let (offset, end) = if offset + size <= ring_size {
+                (offset, ring_start + offset + size)
+            } else {
+                (wrap_offset, ring_start + ring_size + wrap_offset + size)
+            };
+
+            if end - self.ring_tail <= ring_size {
+                self.ring_head = end;
+
+                return Ok(Subbuffer::from_arena(arena, offset, size));
+            }
+
+            // The ring is full, wait for the oldest frame to be done with its part.
+            if !self.retire_frames(wait)? {
+                return Err(AllocationCreationError::OutOfPoolMemory);
+            }
+        }
+    }
+
+    fn create_ring(&mut self) -> Result<(), AllocationCreationError> {
+        // The ring is never returned to a pool, so it gets a queue of its own that is dropped
+        // along with it.
+        self.arena = Some(Arc::new(Arena {
+            buffer: ManuallyDrop::new(self.create_arena()?),
+            reserve: Arc::new(ArrayQueue::new(1)),
+        }));
+        self.ring_head = 0;
+        self.ring_tail = 0;
+        self.frames.clear();
+
+        Ok(())
+    }
+
+    // Frees the space of the frames that the GPU is done with, in order. If `wait` is true, waits
+    // for the oldest frame if no frame is done yet. Returns whether any space was freed.
+    fn retire_frames(&mut self, wait: bool) -> Result<bool, AllocationCreationError> {
+        let mut retired = false;
+
+        while let Some((end, fence)) = self.frames.front() {
+            if let Some(fence) = fence {
+                let signaled = fence.is_signaled().map_err(|err| fence_error(err.into()))?;
+
+                if !signaled {
+                    if !wait || retired {
+                        break;
+                    }
+
+                    fence.wait(None).map_err(fence_error)?;
+                }
+            }
+
+            self.ring_tail = *end;
+            self.frames.pop_front();
+            retired = true;
+        }
+
+        Ok(retired)
+    }
+
     fn next_arena(&mut self) -> Result<Arc<Arena>, AllocationCreationError> {
         if self.reserve.is_none() {
             self.reserve = Some(Arc::new(ArrayQueue::new(MAX_ARENAS)));
@@ -375,6 +601,16 @@ where
     }
 }
 
+fn fence_error(err: FenceError) -> AllocationCreationError {
+    AllocationCreationError::VulkanError(match err {
+        FenceError::OomError(OomError::OutOfHostMemory) => VulkanError::OutOfHostMemory,
+        FenceError::OomError(OomError::OutOfDeviceMemory) => VulkanError::OutOfDeviceMemory,
+        FenceError::DeviceLost => VulkanError::DeviceLost,
+        // We wait without a timeout, therefore the other errors can't happen.
+        _ => unreachable!(),
+    })
+}
+
 #[derive(Debug)]
 pub(super) struct Arena {
     buffer: ManuallyDrop<Arc<Buffer>>,
@@ -430,6 +666,11 @@ pub struct SubbufferAllocatorCreateInfo {
     /// The default value is [`MemoryUsage::Upload`].
     pub memory_usage: MemoryUsage,
 
+    /// How the allocator manages its buffers.
+    ///
+    /// The default value is [`SubbufferAllocatorMode::Arenas`].
+    pub mode: SubbufferAllocatorMode,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -440,11 +681,32 @@ impl Default for SubbufferAllocatorCreateInfo {
             arena_size: 0,
             buffer_usage: BufferUsage::TRANSFER_SRC,
             memory_usage: MemoryUsage::Upload,
+            mode: SubbufferAllocatorMode::Arenas,
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
+/// How a [`SubbufferAllocator`] manages its buffers.
+///
+/// See the documentation of [`SubbufferAllocator`] for details.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SubbufferAllocatorMode {
+    /// Subbuffers are allocated from a pool of arenas, which grows and whose arenas are resized
+    /// as needed.
+    Arenas,
+
+    /// Subbuffers are allocated from a single ring buffer of `arena_size` bytes, which is never
+    /// reallocated. When the ring is full, [`AllocationCreationError::OutOfPoolMemory`] is
+    /// returned.
+    RingBuffer,
+
+    /// Like `RingBuffer`, but when the ring is full, the current thread is blocked until the
+    /// oldest frame has finished.
+    BlockingRingBuffer,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -472,4 +734,62 @@ mod tests {
         buffer_allocator.allocate_sized::<u32>().unwrap();
         assert_eq!(buffer_allocator.arena_size(), 8);
     }
+
+    #[test]
+    fn ring_buffer() {
+        use crate::command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            CommandBufferUsage, PrimaryCommandBufferAbstract,
+        };
+
+        let (device, queue) = gfx_dev_and_queue!();
+        let memory_allocator = StandardMemoryAllocator::new_default(device.clone());
+        let command_buffer_allocator =
+            StandardCommandBufferAllocator::new(device, Default::default());
+
+        let buffer_allocator = SubbufferAllocator::new(
+            memory_allocator,
+            SubbufferAllocatorCreateInfo {
+                arena_size: 64,
+                mode: SubbufferAllocatorMode::RingBuffer,
+                ..Default::default()
+            },
+        );
+
+        let first = buffer_allocator.allocate_slice::<u8>(48).unwrap();
+        let ring = first.buffer().clone();
+        assert_eq!(first.offset(), 0);
+
+        // The frame isn't finished, so its space can't be reused.
+        assert!(matches!(
+            buffer_allocator.allocate_slice::<u8>(32),
+            Err(AllocationCreationError::OutOfPoolMemory),
+        ));
+
+        let future = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap()
+        .build()
+        .unwrap()
+        .execute(queue)
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .unwrap();
+        future.wait(None).unwrap();
+        buffer_allocator.finish_frame(&future);
+
+        // Now the allocation wraps around to the start of the same buffer.
+        let second = buffer_allocator.allocate_slice::<u8>(32).unwrap();
+        assert_eq!(second.buffer(), &ring);
+        assert_eq!(second.offset(), 0);
+
+        // Bigger than the whole ring.
+        assert!(matches!(
+            buffer_allocator.allocate_slice::<u8>(65),
+            Err(AllocationCreationError::OutOfPoolMemory),
+        ));
+    }
 }
@@ -102,7 +102,7 @@
 //! [the `shader` module documentation]: crate::shader
 
 pub use self::{
-    subbuffer::{BufferContents, BufferContentsLayout, Subbuffer},
+    subbuffer::{BufferContents, BufferContentsLayout, DeviceAddress, Subbuffer},
     sys::BufferCreateInfo,
     usage::BufferUsage,
 };
@@ -111,15 +111,18 @@ use self::{
     sys::RawBuffer,
 };
 use crate::{
-    device::{Device, DeviceOwned},
+    device::{physical::PhysicalDeviceError, Device, DeviceOwned},
     macros::vulkan_bitflags,
     memory::{
         allocator::{
             AllocationCreateInfo, AllocationCreationError, AllocationType, DeviceLayout,
-            MemoryAlloc, MemoryAllocator,
+            MemoryAlloc, MemoryAllocator, MemoryUsage,
         },
-        is_aligned, DedicatedAllocation, DeviceAlignment, ExternalMemoryHandleType,
-        ExternalMemoryHandleTypes, ExternalMemoryProperties, MemoryRequirements,
+        is_aligned,
+        sparse::SparseResidency,
+        DedicatedAllocation, DeviceAlignment, DeviceMemory, DeviceMemoryError,
+        ExternalMemoryHandleType, ExternalMemoryHandleTypes, ExternalMemoryProperties,
+        MemoryAllocateInfo, MemoryImportInfo, MemoryRequirements, SparseBufferMemoryBind,
     },
     range_map::RangeMap,
     sync::{future::AccessError, CurrentAccess, Sharing},
@@ -131,6 +134,7 @@ use smallvec::SmallVec;
 use std::{
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
+    fs::File,
     hash::{Hash, Hasher},
     mem::size_of_val,
     ops::Range,
@@ -241,6 +245,7 @@ pub struct Buffer {
     inner: RawBuffer,
     memory: BufferMemory,
     state: Mutex<BufferState>,
+    sparse_residency: Option<Mutex<SparseResidency>>,
 }
 
 /// The type of backing memory that a buffer can have.
@@ -251,9 +256,11 @@ pub enum BufferMemory {
     /// [`bind_memory`]: RawBuffer::bind_memory
     Normal(MemoryAlloc),
 
-    /// The buffer is backed by sparse memory, bound with [`bind_sparse`].
+    /// The buffer is backed by sparse memory, bound with sparse bind operations on a queue.
     ///
-    /// [`bind_sparse`]: crate::device::QueueGuard::bind_sparse
+    /// See [`BindSparseInfo`] for more information.
+    ///
+    /// [`BindSparseInfo`]: crate::memory::BindSparseInfo
     Sparse,
 }
 
@@ -400,6 +407,8 @@ impl Buffer {
     /// # Panics
     ///
     /// - Panics if `buffer_info.size` is not zero.
+    /// - Panics if `buffer_info.flags` contains [`BufferCreateFlags::SPARSE_BINDING`]. Use
+    ///   [`Buffer::new_sparse`] to create sparse buffers.
     /// - Panics if `layout.alignment()` is greater than 64.
     pub fn new(
         allocator: &(impl MemoryAllocator + ?Sized),
@@ -408,8 +417,9 @@ impl Buffer {
         layout: DeviceLayout,
     ) -> Result<Arc<Self>, BufferError> {
         assert!(layout.alignment().as_devicesize() <= 64);
-        // TODO: Enable once sparse binding materializes
-        // assert!(!allocate_info.flags.contains(BufferCreateFlags::SPARSE_BINDING));
+        assert!(!buffer_info
+            .flags
+            .intersects(BufferCreateFlags::SPARSE_BINDING));
 
         assert!(
             buffer_info.size == 0,
@@ -446,13 +456,216 @@ impl Buffer {
             .map_err(|(err, _, _)| err.into())
     }
 
+    /// Creates a new uninitialized `Buffer` with the given `layout`, whose memory can be exported
+    /// to other processes or APIs using one of `export_handle_types`.
+    ///
+    /// The buffer is always given a dedicated allocation, from a memory type that is picked
+    /// according to `memory_usage`, so that the exported memory contains nothing but the buffer.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `buffer_info.size` is not zero.
+    /// - Panics if `buffer_info.flags` contains [`BufferCreateFlags::SPARSE_BINDING`].
+    /// - Panics if `buffer_info.external_memory_handle_types` is not empty.
+    /// - Panics if `export_handle_types` is empty.
+    /// - Panics if `layout.alignment()` is greater than 64.
+    pub fn new_exportable(
+        allocator: &(impl MemoryAllocator + ?Sized),
+        mut buffer_info: BufferCreateInfo,
+        memory_usage: MemoryUsage,
+        layout: DeviceLayout,
+        export_handle_types: ExternalMemoryHandleTypes,
+    ) -> Result<Arc<Self>, BufferError> {
+        assert!(layout.alignment().as_devicesize() <= 64);
+        assert!(!buffer_info
+            .flags
+            .intersects(BufferCreateFlags::SPARSE_BINDING));
+        assert!(buffer_info.external_memory_handle_types.is_empty());
+        assert!(!export_handle_types.is_empty());
+
+        assert!(
+            buffer_info.size == 0,
+            "`Buffer::new*` functions set the `buffer_info.size` field themselves, you should not \
+            set it yourself",
+        );
+
+        let device = allocator.device();
+
+        for handle_type in export_handle_types {
+            let external_buffer_properties =
+                device
+                    .physical_device()
+                    .external_buffer_properties(ExternalBufferInfo {
+                        usage: buffer_info.usage,
+                        ..ExternalBufferInfo::handle_type(handle_type)
+                    })?;
+
+            // VUID-VkExportMemoryAllocateInfo-handleTypes-00656
+            if !external_buffer_properties
+                .external_memory_properties
+                .exportable
+            {
+                return Err(BufferError::ExternalMemoryHandleTypesNotSupported {
+                    handle_types: export_handle_types,
+                });
+            }
+        }
+
+        buffer_info.size = layout.size();
+        buffer_info.external_memory_handle_types = export_handle_types;
+
+        let raw_buffer = RawBuffer::new(device.clone(), buffer_info)?;
+        let requirements = *raw_buffer.memory_requirements();
+        let memory_type_index = allocator
+            .find_memory_type_index(requirements.memory_type_bits, memory_usage.into())
+            .ok_or(BufferError::NoSuitableMemoryType)?;
+
+        // VUID-VkMemoryAllocateInfo-pNext-00639
+        // Guaranteed because we always create a dedicated allocation.
+        let mut allocation = unsafe {
+            allocator.allocate_dedicated_unchecked(
+                memory_type_index,
+                requirements.layout.size(),
+                Some(DedicatedAllocation::Buffer(&raw_buffer)),
+                export_handle_types,
+            )
+        }?;
+        debug_assert!(is_aligned(
+            allocation.offset(),
+            requirements.layout.alignment(),
+        ));
+        debug_assert!(allocation.size() == requirements.layout.size());
+
+        allocation.shrink(layout.size());
+
+        unsafe { raw_buffer.bind_memory_unchecked(allocation) }
+            .map(Arc::new)
+            .map_err(|(err, _, _)| err.into())
+    }
+
+    /// Creates a new `Buffer` with the given `layout`, from memory that is imported from a Unix
+    /// file descriptor.
+    ///
+    /// The memory is imported as a dedicated allocation of the buffer, into a memory type that is
+    /// picked according to `memory_usage`.
+    ///
+    /// # Safety
+    ///
+    /// - `file` must contain memory that is valid for `handle_type`, and that is at least as large
+    ///   as the memory requirements of the buffer.
+    /// - If `handle_type` is [`ExternalMemoryHandleType::OpaqueFd`], then `file` must have been
+    ///   exported from a buffer that was created with [`Buffer::new_exportable`], on the same
+    ///   physical device, with the same `buffer_info`, `memory_usage` and `layout`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `buffer_info.size` is not zero.
+    /// - Panics if `buffer_info.flags` contains [`BufferCreateFlags::SPARSE_BINDING`].
+    /// - Panics if `buffer_info.external_memory_handle_types` is not empty.
+    /// - Panics if `layout.alignment()` is greater than 64.
+    pub unsafe fn from_fd(
+        allocator: &(impl MemoryAllocator + ?Sized),
+        mut buffer_info: BufferCreateInfo,
+        memory_usage: MemoryUsage,
+        layout: DeviceLayout,
+        handle_type: ExternalMemoryHandleType,
+        file: File,
+    ) -> Result<Arc<Self>, BufferError> {
+        assert!(layout.alignment().as_devicesize() <= 64);
+        assert!(!buffer_info
+            .flags
+            .intersects(BufferCreateFlags::SPARSE_BINDING));
+        assert!(buffer_info.external_memory_handle_types.is_empty());
+
+        assert!(
+            buffer_info.size == 0,
+            "`Buffer::new*` functions set the `buffer_info.size` field themselves, you should not \
+            set it yourself",
+        );
+
+        buffer_info.size = layout.size();
+        buffer_info.external_memory_handle_types = handle_type.into();
+
+        let device = allocator.device();
+        let raw_buffer = RawBuffer::new(device.clone(), buffer_info)?;
+        let requirements = *raw_buffer.memory_requirements();
+        let mut memory_type_bits = requirements.memory_type_bits;
+
+        // VUID-vkGetMemoryFdPropertiesKHR-handleType-00674
+        if handle_type != ExternalMemoryHandleType::OpaqueFd {
+            memory_type_bits &= device
+                .memory_fd_properties_unchecked(handle_type, &file)?
+                .memory_type_bits;
+        }
+
+        let memory_type_index = allocator
+            .find_memory_type_index(memory_type_bits, memory_usage.into())
+            .ok_or(BufferError::NoSuitableMemoryType)?;
+
+        let memory = DeviceMemory::import(
+            device.clone(),
+            MemoryAllocateInfo {
+                allocation_size: requirements.layout.size(),
+                memory_type_index,
+                dedicated_allocation: (device.api_version() >= Version::V1_1
+                    || device.enabled_extensions().khr_dedicated_allocation)
+                    .then_some(DedicatedAllocation::Buffer(&raw_buffer)),
+                ..Default::default()
+            },
+            MemoryImportInfo::Fd { handle_type, file },
+        )?;
+        let mut allocation = MemoryAlloc::new(memory)?;
+        allocation.shrink(layout.size());
+
+        raw_buffer
+            .bind_memory_unchecked(allocation)
+            .map(Arc::new)
+            .map_err(|(err, _, _)| err.into())
+    }
+
+    /// Creates a new `Buffer` that is backed by sparse memory.
+    ///
+    /// No memory is bound to the buffer when it is created. Memory must be bound to it with
+    /// sparse bind operations on a queue, using [`BindSparseInfo`], before the bound parts can be
+    /// used. Which parts of the buffer currently have memory bound to them can be queried with
+    /// [`is_sparse_range_resident`].
+    ///
+    /// The size in bytes of the sparse blocks of the buffer is given by the alignment of its
+    /// [`memory_requirements`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.size` is zero.
+    ///
+    /// [`BindSparseInfo`]: crate::memory::BindSparseInfo
+    /// [`is_sparse_range_resident`]: Self::is_sparse_range_resident
+    /// [`memory_requirements`]: Self::memory_requirements
+    pub fn new_sparse(
+        device: Arc<Device>,
+        create_info: BufferCreateInfo,
+    ) -> Result<Arc<Self>, BufferError> {
+        if !create_info
+            .flags
+            .intersects(BufferCreateFlags::SPARSE_BINDING)
+        {
+            return Err(BufferError::SparseBindingFlagMissing);
+        }
+
+        let raw_buffer = RawBuffer::new(device, create_info)?;
+
+        Ok(Arc::new(Buffer::from_raw(raw_buffer, BufferMemory::Sparse)))
+    }
+
     fn from_raw(inner: RawBuffer, memory: BufferMemory) -> Self {
         let state = Mutex::new(BufferState::new(inner.size()));
+        let sparse_residency =
+            matches!(memory, BufferMemory::Sparse).then(|| Mutex::new(SparseResidency::default()));
 
         Buffer {
             inner,
             memory,
             state,
+            sparse_residency,
         }
     }
 
@@ -498,6 +711,29 @@ impl Buffer {
         self.inner.external_memory_handle_types()
     }
 
+    /// Exports the memory of the buffer as a Unix file descriptor, so that it can be imported in
+    /// another process or API.
+    ///
+    /// The buffer must have been created with [`Buffer::new_exportable`] or [`Buffer::from_fd`],
+    /// and `handle_type` must be one of the external memory handle types of the buffer.
+    pub fn export_fd(&self, handle_type: ExternalMemoryHandleType) -> Result<File, BufferError> {
+        if !self
+            .external_memory_handle_types()
+            .contains_enum(handle_type)
+        {
+            return Err(BufferError::ExternalMemoryHandleTypesNotSupported {
+                handle_types: handle_type.into(),
+            });
+        }
+
+        let allocation = match &self.memory {
+            BufferMemory::Normal(allocation) => allocation,
+            BufferMemory::Sparse => return Err(BufferError::SparseBindingFlagNotAllowed),
+        };
+
+        Ok(allocation.device_memory().export_fd(handle_type)?)
+    }
+
     /// Returns the device address for this buffer.
     // TODO: Caching?
     pub fn device_address(&self) -> Result<NonZeroDeviceSize, BufferError> {
@@ -536,9 +772,47 @@ impl Buffer {
         Ok(NonZeroDeviceSize::new(ptr).unwrap())
     }
 
+    /// Returns whether memory is currently bound to the whole of `range` of the buffer.
+    ///
+    /// This reflects the sparse bind operations that have been submitted to a queue so far, not
+    /// only those that have finished executing. Buffers that are not sparse always return `true`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `range` is out of bounds of the buffer.
+    pub fn is_sparse_range_resident(&self, range: Range<DeviceSize>) -> bool {
+        assert!(range.start <= range.end && range.end <= self.size());
+
+        self.sparse_residency
+            .as_ref()
+            .map_or(true, |residency| residency.lock().is_resident(range))
+    }
+
     pub(crate) fn state(&self) -> MutexGuard<'_, BufferState> {
         self.state.lock()
     }
+
+    /// Records the effect of sparse bind operations on the buffer, after they have been
+    /// submitted to a queue.
+    pub(crate) fn sparse_bind(&self, memory_binds: &[SparseBufferMemoryBind]) {
+        let mut residency = match &self.sparse_residency {
+            Some(residency) => residency.lock(),
+            None => return,
+        };
+
+        for memory_bind in memory_binds {
+            let &SparseBufferMemoryBind {
+                offset,
+                size,
+                ref memory,
+            } = memory_bind;
+
+            residency.bind(
+                offset..offset + size,
+                memory.as_ref().map(|(memory, _)| memory),
+            );
+        }
+    }
 }
 
 unsafe impl VulkanObject for Buffer {
@@ -786,6 +1060,9 @@ pub enum BufferError {
     /// Allocating memory failed.
     AllocError(AllocationCreationError),
 
+    /// Importing or exporting memory failed.
+    DeviceMemoryError(DeviceMemoryError),
+
     RequirementNotMet {
         required_for: &'static str,
         requires_one_of: RequiresOneOf,
@@ -800,6 +1077,12 @@ pub enum BufferError {
     /// A dedicated allocation is required for this buffer, but one was not provided.
     DedicatedAllocationRequired,
 
+    /// The external memory handle types are not supported for exporting the buffer
+    /// configuration, or are not among the handle types of the buffer.
+    ExternalMemoryHandleTypesNotSupported {
+        handle_types: ExternalMemoryHandleTypes,
+    },
+
     /// The host is already using this buffer in a way that is incompatible with the
     /// requested access.
     InUseByHost,
@@ -860,6 +1143,17 @@ pub enum BufferError {
         allowed_memory_type_bits: u32,
     },
 
+    /// None of the memory types that the buffer can be bound to are suitable, or compatible with
+    /// the imported memory.
+    NoSuitableMemoryType,
+
+    /// The `SPARSE_BINDING` create flag was required, but not provided.
+    SparseBindingFlagMissing,
+
+    /// The buffer was created with the `SPARSE_BINDING` flag, which is not allowed for this
+    /// operation.
+    SparseBindingFlagNotAllowed,
+
     /// The sharing mode was set to `Concurrent`, but one of the specified queue family indices was
     /// out of range.
     SharingQueueFamilyIndexOutOfRange {
@@ -873,6 +1167,7 @@ impl Error for BufferError {
         match self {
             Self::VulkanError(err) => Some(err),
             Self::AllocError(err) => Some(err),
+            Self::DeviceMemoryError(err) => Some(err),
             _ => None,
         }
     }
@@ -883,6 +1178,7 @@ impl Display for BufferError {
         match self {
             Self::VulkanError(_) => write!(f, "a runtime error occurred"),
             Self::AllocError(_) => write!(f, "allocating memory failed"),
+            Self::DeviceMemoryError(_) => write!(f, "importing or exporting memory failed"),
             Self::RequirementNotMet {
                 required_for,
                 requires_one_of,
@@ -902,6 +1198,12 @@ impl Display for BufferError {
                 f,
                 "a dedicated allocation is required for this buffer, but one was not provided"
             ),
+            Self::ExternalMemoryHandleTypesNotSupported { handle_types } => write!(
+                f,
+                "the external memory handle types {:?} are not supported for exporting the buffer \
+                configuration, or are not among the handle types of the buffer",
+                handle_types,
+            ),
             Self::InUseByHost => write!(
                 f,
                 "the host is already using this buffer in a way that is incompatible with the \
@@ -984,6 +1286,20 @@ impl Display for BufferError {
                 Ok(())
             })
             .and_then(|_| write!(f, ") that can be bound to this buffer")),
+            Self::NoSuitableMemoryType => write!(
+                f,
+                "none of the memory types that the buffer can be bound to are suitable, or \
+                compatible with the imported memory",
+            ),
+            Self::SparseBindingFlagMissing => write!(
+                f,
+                "the `SPARSE_BINDING` create flag was required, but not provided",
+            ),
+            Self::SparseBindingFlagNotAllowed => write!(
+                f,
+                "the buffer was created with the `SPARSE_BINDING` flag, which is not allowed for \
+                this operation",
+            ),
             Self::SharingQueueFamilyIndexOutOfRange { .. } => write!(
                 f,
                 "the sharing mode was set to `Concurrent`, but one of the specified queue family \
@@ -1005,6 +1321,28 @@ impl From<AllocationCreationError> for BufferError {
     }
 }
 
+impl From<DeviceMemoryError> for BufferError {
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::DeviceMemoryError(err)
+    }
+}
+
+impl From<PhysicalDeviceError> for BufferError {
+    fn from(err: PhysicalDeviceError) -> Self {
+        match err {
+            PhysicalDeviceError::VulkanError(err) => Self::VulkanError(err),
+            PhysicalDeviceError::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            },
+            _ => panic!("unexpected error value"),
+        }
+    }
+}
+
 impl From<RequirementNotMet> for BufferError {
     fn from(err: RequirementNotMet) -> Self {
         Self::RequirementNotMet {
@@ -1038,7 +1376,6 @@ vulkan_bitflags! {
     /// Flags to be set when creating a buffer.
     BufferCreateFlags = BufferCreateFlags(u32);
 
-    /* TODO: enable
     /// The buffer will be backed by sparse memory binding (through queue commands) instead of
     /// regular binding (through [`bind_memory`]).
     ///
@@ -1046,9 +1383,8 @@ vulkan_bitflags! {
     ///
     /// [`bind_memory`]: sys::RawBuffer::bind_memory
     /// [`sparse_binding`]: crate::device::Features::sparse_binding
-    SPARSE_BINDING = SPARSE_BINDING,*/
+    SPARSE_BINDING = SPARSE_BINDING,
 
-    /* TODO: enable
     /// The buffer can be used without being fully resident in memory at the time of use.
     ///
     /// This requires the `sparse_binding` flag as well.
@@ -1056,9 +1392,8 @@ vulkan_bitflags! {
     /// The [`sparse_residency_buffer`] feature must be enabled on the device.
     ///
     /// [`sparse_residency_buffer`]: crate::device::Features::sparse_residency_buffer
-    SPARSE_RESIDENCY = SPARSE_RESIDENCY,*/
+    SPARSE_RESIDENCY = SPARSE_RESIDENCY,
 
-    /* TODO: enable
     /// The buffer's memory can alias with another buffer or a different part of the same buffer.
     ///
     /// This requires the `sparse_binding` flag as well.
@@ -1066,7 +1401,7 @@ vulkan_bitflags! {
     /// The [`sparse_residency_aliased`] feature must be enabled on the device.
     ///
     /// [`sparse_residency_aliased`]: crate::device::Features::sparse_residency_aliased
-    SPARSE_ALIASED = SPARSE_ALIASED,*/
+    SPARSE_ALIASED = SPARSE_ALIASED,
 
     /* TODO: enable
     /// The buffer is protected, and can only be used in combination with protected memory and other
@@ -1124,3 +1459,60 @@ pub struct ExternalBufferProperties {
     /// The properties for external memory.
     pub external_memory_properties: ExternalMemoryProperties,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Buffer, BufferCreateInfo, BufferError, BufferUsage, Subbuffer};
+    use crate::memory::{
+        allocator::{DeviceLayout, MemoryUsage, StandardMemoryAllocator},
+        ExternalMemoryHandleType, ExternalMemoryHandleTypes,
+    };
+
+    #[test]
+    fn export_import_opaque_fd() {
+        let (exporter, _) = gfx_dev_and_queue!(extensions: [khr_external_memory_fd];);
+        let (importer, _) = gfx_dev_and_queue!(extensions: [khr_external_memory_fd];);
+        let exporter_allocator = StandardMemoryAllocator::new_default(exporter);
+        let importer_allocator = StandardMemoryAllocator::new_default(importer);
+        let buffer_info = || BufferCreateInfo {
+            usage: BufferUsage::TRANSFER_SRC,
+            ..Default::default()
+        };
+        let layout = DeviceLayout::from_size_alignment(64, 1).unwrap();
+
+        let exported = match Buffer::new_exportable(
+            &exporter_allocator,
+            buffer_info(),
+            MemoryUsage::Upload,
+            layout,
+            ExternalMemoryHandleTypes::OPAQUE_FD,
+        ) {
+            Ok(buffer) => Subbuffer::from(buffer),
+            Err(
+                BufferError::ExternalMemoryHandleTypesNotSupported { .. }
+                | BufferError::RequirementNotMet { .. },
+            ) => return,
+            Err(err) => panic!("{}", err),
+        };
+        exported.write().unwrap().copy_from_slice(&[42; 64]);
+        let file = exported
+            .buffer()
+            .export_fd(ExternalMemoryHandleType::OpaqueFd)
+            .unwrap();
+
+        let imported = Subbuffer::from(
+            unsafe {
+                Buffer::from_fd(
+                    &importer_allocator,
+                    buffer_info(),
+                    MemoryUsage::Upload,
+                    layout,
+                    ExternalMemoryHandleType::OpaqueFd,
+                    file,
+                )
+            }
+            .unwrap(),
+        );
+        assert_eq!(&*imported.read().unwrap(), &[42; 64]);
+    }
+}
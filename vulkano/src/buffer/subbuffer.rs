@@ -20,13 +20,13 @@ use crate::{
     },
     DeviceSize, NonZeroDeviceSize,
 };
-use bytemuck::{AnyBitPattern, PodCastError};
+use bytemuck::{AnyBitPattern, Pod, PodCastError, Zeroable};
 use std::{
     alloc::Layout,
     cmp,
     error::Error,
     ffi::c_void,
-    fmt::{Display, Error as FmtError, Formatter},
+    fmt::{Debug, Display, Error as FmtError, Formatter},
     hash::{Hash, Hasher},
     marker::PhantomData,
     mem::{self, align_of, size_of},
@@ -94,12 +94,12 @@ impl<T: ?Sized> Subbuffer<T> {
 
     /// Returns the offset of the subbuffer, in bytes, relative to the [`DeviceMemory`] block.
     fn memory_offset(&self) -> DeviceSize {
-        let allocation = match self.buffer().memory() {
-            BufferMemory::Normal(a) => a,
-            BufferMemory::Sparse => unreachable!(),
-        };
-
-        allocation.offset() + self.offset
+        match self.buffer().memory() {
+            BufferMemory::Normal(a) => a.offset() + self.offset,
+            // Sparse memory is always bound at offsets that are aligned to the sparse block size,
+            // so the offset within the buffer is equivalent for alignment purposes.
+            BufferMemory::Sparse => self.offset,
+        }
     }
 
     /// Returns the size of the subbuffer in bytes.
@@ -130,7 +130,7 @@ impl<T: ?Sized> Subbuffer<T> {
                 // so the offset better be in range.
                 unsafe { NonNull::new_unchecked(ptr.as_ptr().add(self.offset as usize)) }
             }),
-            BufferMemory::Sparse => unreachable!(),
+            BufferMemory::Sparse => None,
         }
     }
 
@@ -144,6 +144,19 @@ impl<T: ?Sized> Subbuffer<T> {
         })
     }
 
+    /// Returns the device address for this subbuffer, typed with the contents of the subbuffer.
+    ///
+    /// Unlike [`device_address`], the returned [`DeviceAddress`] can be embedded directly in push
+    /// constants and in buffer contents.
+    ///
+    /// [`device_address`]: Self::device_address
+    pub fn typed_device_address(&self) -> Result<DeviceAddress<T>, BufferError> {
+        self.device_address().map(|address| DeviceAddress {
+            address: address.get(),
+            _marker: PhantomData,
+        })
+    }
+
     /// Casts the subbuffer to a slice of raw bytes.
     pub fn into_bytes(self) -> Subbuffer<[u8]> {
         unsafe { self.reinterpret_unchecked_inner() }
@@ -252,7 +265,7 @@ where
     pub fn read(&self) -> Result<BufferReadGuard<'_, T>, BufferError> {
         let allocation = match self.buffer().memory() {
             BufferMemory::Normal(a) => a,
-            BufferMemory::Sparse => todo!("`Subbuffer::read` doesn't support sparse binding yet"),
+            BufferMemory::Sparse => return Err(BufferError::MemoryNotHostVisible),
         };
 
         let range = if let Some(atom_size) = allocation.atom_size() {
@@ -321,7 +334,7 @@ where
     pub fn write(&self) -> Result<BufferWriteGuard<'_, T>, BufferError> {
         let allocation = match self.buffer().memory() {
             BufferMemory::Normal(a) => a,
-            BufferMemory::Sparse => todo!("`Subbuffer::write` doesn't support sparse binding yet"),
+            BufferMemory::Sparse => return Err(BufferError::MemoryNotHostVisible),
         };
 
         let range = if let Some(atom_size) = allocation.atom_size() {
@@ -593,6 +606,105 @@ impl<T: ?Sized> Hash for Subbuffer<T> {
     }
 }
 
+/// The device address of a subbuffer, typed with the contents of the subbuffer.
+///
+/// This is a plain 64-bit value with the same layout as a GLSL `buffer_reference` or a SPIR-V
+/// `PhysicalStorageBuffer` pointer, so it implements [`BufferContents`] and can be embedded in
+/// push constants and in other buffer contents. A `DeviceAddress` is created with
+/// [`Subbuffer::typed_device_address`].
+///
+/// The device address doesn't keep the subbuffer alive, and accesses that a shader makes through
+/// it can't be seen by the command buffer builder. The subbuffers that are accessed through
+/// device addresses must therefore be bound with
+/// [`AutoCommandBufferBuilder::bind_device_address_buffers`], which keeps them alive and makes the
+/// builder insert the needed pipeline barriers for them.
+///
+/// [`AutoCommandBufferBuilder::bind_device_address_buffers`]: crate::command_buffer::AutoCommandBufferBuilder::bind_device_address_buffers
+#[repr(transparent)]
+pub struct DeviceAddress<T: ?Sized> {
+    address: DeviceSize,
+    _marker: PhantomData<fn() -> *const T>,
+}
+
+impl<T: ?Sized> DeviceAddress<T> {
+    /// Returns the null device address.
+    #[inline]
+    pub const fn null() -> Self {
+        DeviceAddress {
+            address: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns whether this is the null device address.
+    #[inline]
+    pub const fn is_null(self) -> bool {
+        self.address == 0
+    }
+
+    /// Returns the raw device address.
+    #[inline]
+    pub const fn get(self) -> DeviceSize {
+        self.address
+    }
+}
+
+impl<T> DeviceAddress<[T]> {
+    /// Returns the device address of the element at `index`.
+    ///
+    /// The index is not checked against the length of the subbuffer that the address was created
+    /// from.
+    #[inline]
+    pub const fn element(self, index: DeviceSize) -> DeviceAddress<T> {
+        DeviceAddress {
+            address: self.address + index * size_of::<T>() as DeviceSize,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for DeviceAddress<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for DeviceAddress<T> {}
+
+impl<T: ?Sized> Debug for DeviceAddress<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "DeviceAddress({:#x})", self.address)
+    }
+}
+
+impl<T: ?Sized> Default for DeviceAddress<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+impl<T: ?Sized> PartialEq for DeviceAddress<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+    }
+}
+
+impl<T: ?Sized> Eq for DeviceAddress<T> {}
+
+impl<T: ?Sized> Hash for DeviceAddress<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
+unsafe impl<T: ?Sized> Zeroable for DeviceAddress<T> {}
+
+unsafe impl<T: ?Sized + 'static> Pod for DeviceAddress<T> {}
+
 /// RAII structure used to release the CPU access of a subbuffer when dropped.
 ///
 /// This structure is created by the [`read`] method on [`Subbuffer`].
@@ -1212,6 +1324,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn device_address_contents() {
+        #[derive(BufferContents)]
+        #[repr(C)]
+        struct PushConstants {
+            vertices: DeviceAddress<[[f32; 3]]>,
+            count: u32,
+        }
+
+        assert_eq!(size_of::<DeviceAddress<[[f32; 3]]>>(), size_of::<u64>());
+        assert_eq!(
+            PushConstants::LAYOUT.alignment().as_devicesize() as usize,
+            align_of::<u64>(),
+        );
+
+        let push_constants = PushConstants {
+            vertices: DeviceAddress::null(),
+            count: 0,
+        };
+        assert!(push_constants.vertices.is_null());
+        assert_eq!(push_constants.vertices.element(0), DeviceAddress::null());
+        assert_eq!(push_constants.vertices.element(3).get(), 36);
+        assert_eq!(push_constants.count, 0);
+    }
+
     #[test]
     fn split_at() {
         let (device, _) = gfx_dev_and_queue!();
@@ -98,12 +98,12 @@ impl RawBuffer {
         // VUID-VkBufferCreateInfo-size-00912
         assert!(size != 0);
 
-        /* Enable when sparse binding is properly handled
-        if let Some(sparse_level) = sparse {
+        if flags.intersects(BufferCreateFlags::SPARSE_BINDING) {
             // VUID-VkBufferCreateInfo-flags-00915
             if !device.enabled_features().sparse_binding {
                 return Err(BufferError::RequirementNotMet {
-                    required_for: "`create_info.sparse` is `Some`",
+                    required_for:
+                        "`create_info.flags` contains `BufferCreateFlags::SPARSE_BINDING`",
                     requires_one_of: RequiresOneOf {
                         features: &["sparse_binding"],
                         ..Default::default()
@@ -112,10 +112,12 @@ impl RawBuffer {
             }
 
             // VUID-VkBufferCreateInfo-flags-00916
-            if sparse_level.sparse_residency && !device.enabled_features().sparse_residency_buffer {
+            if flags.intersects(BufferCreateFlags::SPARSE_RESIDENCY)
+                && !device.enabled_features().sparse_residency_buffer
+            {
                 return Err(BufferError::RequirementNotMet {
-                    required_for: "`create_info.sparse` is `Some(sparse_level)`, where \
-                        `sparse_level` contains `BufferCreateFlags::SPARSE_RESIDENCY`",
+                    required_for: "`create_info.flags` contains \
+                        `BufferCreateFlags::SPARSE_RESIDENCY`",
                     requires_one_of: RequiresOneOf {
                         features: &["sparse_residency_buffer"],
                         ..Default::default()
@@ -124,20 +126,24 @@ impl RawBuffer {
             }
 
             // VUID-VkBufferCreateInfo-flags-00917
-            if sparse_level.sparse_aliased && !device.enabled_features().sparse_residency_aliased {
+            if flags.intersects(BufferCreateFlags::SPARSE_ALIASED)
+                && !device.enabled_features().sparse_residency_aliased
+            {
                 return Err(BufferError::RequirementNotMet {
-                    required_for: "`create_info.sparse` is `Some(sparse_level)`, where \
-                        `sparse_level` contains `BufferCreateFlags::SPARSE_ALIASED`",
+                    required_for: "`create_info.flags` contains \
+                        `BufferCreateFlags::SPARSE_ALIASED`",
                     requires_one_of: RequiresOneOf {
                         features: &["sparse_residency_aliased"],
                         ..Default::default()
                     },
                 });
             }
-
+        } else if flags
+            .intersects(BufferCreateFlags::SPARSE_RESIDENCY | BufferCreateFlags::SPARSE_ALIASED)
+        {
             // VUID-VkBufferCreateInfo-flags-00918
+            return Err(BufferError::SparseBindingFlagMissing);
         }
-        */
 
         match sharing {
             Sharing::Exclusive => (),
@@ -425,8 +431,9 @@ impl RawBuffer {
         // Ensured by taking ownership of `RawBuffer`.
 
         // VUID-VkBindBufferMemoryInfo-buffer-01030
-        // Currently ensured by not having sparse binding flags, but this needs to be checked once
-        // those are enabled.
+        if self.flags.intersects(BufferCreateFlags::SPARSE_BINDING) {
+            return Err(BufferError::SparseBindingFlagNotAllowed);
+        }
 
         // VUID-VkBindBufferMemoryInfo-memoryOffset-01031
         // Assume that `allocation` was created correctly.
@@ -689,7 +696,11 @@ impl Default for BufferCreateInfo {
 #[cfg(test)]
 mod tests {
     use super::{BufferCreateInfo, BufferUsage, RawBuffer};
-    use crate::device::{Device, DeviceOwned};
+    use crate::{
+        buffer::{BufferCreateFlags, BufferError},
+        device::{Device, DeviceOwned},
+        RequiresOneOf,
+    };
 
     #[test]
     fn create() {
@@ -710,16 +721,15 @@ mod tests {
         assert_eq!(&**buf.device() as *const Device, &*device as *const Device);
     }
 
-    /* Re-enable when sparse binding is properly implemented
     #[test]
     fn missing_feature_sparse_binding() {
         let (device, _) = gfx_dev_and_queue!();
         match RawBuffer::new(
             device,
             BufferCreateInfo {
+                flags: BufferCreateFlags::SPARSE_BINDING,
                 size: 128,
-                sparse: Some(BufferCreateFlags::empty()),
-                usage: BufferUsage::transfer_dst,
+                usage: BufferUsage::TRANSFER_DST,
                 ..Default::default()
             },
         ) {
@@ -737,13 +747,9 @@ mod tests {
         match RawBuffer::new(
             device,
             BufferCreateInfo {
+                flags: BufferCreateFlags::SPARSE_BINDING | BufferCreateFlags::SPARSE_RESIDENCY,
                 size: 128,
-                sparse: Some(BufferCreateFlags {
-                    sparse_residency: true,
-                    sparse_aliased: false,
-                    ..Default::default()
-                }),
-                usage: BufferUsage::transfer_dst,
+                usage: BufferUsage::TRANSFER_DST,
                 ..Default::default()
             },
         ) {
@@ -761,13 +767,9 @@ mod tests {
         match RawBuffer::new(
             device,
             BufferCreateInfo {
+                flags: BufferCreateFlags::SPARSE_BINDING | BufferCreateFlags::SPARSE_ALIASED,
                 size: 128,
-                sparse: Some(BufferCreateFlags {
-                    sparse_residency: false,
-                    sparse_aliased: true,
-                    ..Default::default()
-                }),
-                usage: BufferUsage::transfer_dst,
+                usage: BufferUsage::TRANSFER_DST,
                 ..Default::default()
             },
         ) {
@@ -778,7 +780,23 @@ mod tests {
             _ => panic!(),
         }
     }
-    */
+
+    #[test]
+    fn sparse_flags_without_sparse_binding() {
+        let (device, _) = gfx_dev_and_queue!();
+        match RawBuffer::new(
+            device,
+            BufferCreateInfo {
+                flags: BufferCreateFlags::SPARSE_RESIDENCY,
+                size: 128,
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+        ) {
+            Err(BufferError::SparseBindingFlagMissing) => (),
+            _ => panic!(),
+        }
+    }
 
     #[test]
     fn create_empty_buffer() {
@@ -69,17 +69,15 @@ vulkan_bitflags! {
         device_extensions: [khr_video_decode_queue],
     },*/
 
-    /* TODO: enable
-    // TODO: document
+    /// The buffer can be bound as a transform feedback buffer, to capture vertex data.
     TRANSFORM_FEEDBACK_BUFFER = TRANSFORM_FEEDBACK_BUFFER_EXT {
         device_extensions: [ext_transform_feedback],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// The buffer can be used as a transform feedback counter buffer.
     TRANSFORM_FEEDBACK_COUNTER_BUFFER = TRANSFORM_FEEDBACK_COUNTER_BUFFER_EXT {
         device_extensions: [ext_transform_feedback],
-    },*/
+    },
 
     /* TODO: enable
     // TODO: document
@@ -87,23 +85,21 @@ vulkan_bitflags! {
         device_extensions: [ext_conditional_rendering],
     },*/
 
-    /* TODO: enable
-    // TODO: document
-    ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR = ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR {
+    /// The buffer can be used as a read-only input to an acceleration structure build, such as
+    /// vertex, index, transform, AABB or instance data.
+    ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY = ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR {
         device_extensions: [khr_acceleration_structure],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// The buffer can be used as storage space for an acceleration structure.
     ACCELERATION_STRUCTURE_STORAGE = ACCELERATION_STRUCTURE_STORAGE_KHR {
         device_extensions: [khr_acceleration_structure],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// The buffer can be used as a shader binding table for ray tracing.
     SHADER_BINDING_TABLE = SHADER_BINDING_TABLE_KHR {
         device_extensions: [khr_ray_tracing_pipeline, nv_ray_tracing],
-    },*/
+    },
 
     /* TODO: enable
     // TODO: document
@@ -117,23 +113,22 @@ vulkan_bitflags! {
         device_extensions: [khr_video_encode_queue],
     },*/
 
-    /* TODO: enable
-    // TODO: document
+    /// The buffer can be bound as a descriptor buffer containing sampler and combined image
+    /// sampler descriptors.
     SAMPLER_DESCRIPTOR_BUFFER = SAMPLER_DESCRIPTOR_BUFFER_EXT {
         device_extensions: [ext_descriptor_buffer],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// The buffer can be bound as a descriptor buffer containing descriptors other than
+    /// samplers.
     RESOURCE_DESCRIPTOR_BUFFER = RESOURCE_DESCRIPTOR_BUFFER_EXT {
         device_extensions: [ext_descriptor_buffer],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// The buffer can be bound as a descriptor buffer that is used to hold push descriptors.
     PUSH_DESCRIPTORS_DESCRIPTOR_BUFFER = PUSH_DESCRIPTORS_DESCRIPTOR_BUFFER_EXT {
         device_extensions: [ext_descriptor_buffer],
-    },*/
+    },
 
     /* TODO: enable
     // TODO: document
@@ -14,18 +14,20 @@ use super::{
     },
     synced::{CommandBufferBuilderState, SyncCommandBuffer, SyncCommandBufferBuilder},
     sys::CommandBufferBeginInfo,
-    CommandBufferExecError, CommandBufferInheritanceInfo, CommandBufferInheritanceRenderPassInfo,
-    CommandBufferInheritanceRenderPassType, CommandBufferLevel, CommandBufferResourcesUsage,
-    CommandBufferState, CommandBufferUsage, PrimaryCommandBufferAbstract, RenderingAttachmentInfo,
-    SecondaryCommandBufferAbstract, SecondaryCommandBufferResourcesUsage, SubpassContents,
+    BarrierReason, BarrierReportEntry, CommandBufferExecError, CommandBufferInheritanceInfo,
+    CommandBufferInheritanceRenderPassInfo, CommandBufferInheritanceRenderPassType,
+    CommandBufferLevel, CommandBufferResourcesUsage, CommandBufferState, CommandBufferUsage,
+    PrimaryCommandBufferAbstract, RenderingAttachmentInfo, SecondaryCommandBufferAbstract,
+    SecondaryCommandBufferResourcesUsage, SubpassContents,
 };
 use crate::{
     command_buffer::CommandBufferInheritanceRenderingInfo,
     device::{Device, DeviceOwned, QueueFamilyProperties},
     format::{Format, FormatFeatures},
-    image::ImageAspects,
+    image::{sys::Image, ImageAspects},
     query::{QueryControlFlags, QueryType},
     render_pass::{Framebuffer, Subpass},
+    sync::DependencyInfo,
     OomError, RequirementNotMet, RequiresOneOf, VulkanObject,
 };
 use ahash::HashMap;
@@ -52,7 +54,7 @@ where
     builder_alloc: A::Builder, // Safety: must be dropped after `inner`
 
     // The index of the queue family that this command buffer is being created for.
-    queue_family_index: u32,
+    pub(super) queue_family_index: u32,
 
     // The inheritance for secondary command buffers.
     // Must be `None` in a primary command buffer and `Some` in a secondary command buffer.
@@ -67,6 +69,9 @@ where
     // If any queries are active, this hashmap contains their state.
     pub(super) query_state: HashMap<ash::vk::QueryType, QueryState>,
 
+    // Whether transform feedback is currently active.
+    pub(super) transform_feedback_active: bool,
+
     _data: PhantomData<L>,
 }
 
@@ -273,6 +278,7 @@ where
             queue_family_index,
             render_pass_state,
             query_state: HashMap::default(),
+            transform_feedback_active: false,
             inheritance_info,
             usage,
             _data: PhantomData,
@@ -619,6 +625,10 @@ where
             return Err(BuildError::QueryActive);
         }
 
+        if self.inner.has_set_events() {
+            return Err(BuildError::EventNotWaited);
+        }
+
         Ok(PrimaryAutoCommandBuffer {
             inner: self.inner.build()?,
             _alloc: self.builder_alloc.into_alloc(),
@@ -627,6 +637,35 @@ where
             state: Mutex::new(Default::default()),
         })
     }
+
+    /// Records the barriers that are needed before a resource starts using memory that was
+    /// previously used by other resources. `retired_images` are transitioned to their final
+    /// layout first, then `dependency_info` is recorded, both after all previous commands.
+    ///
+    /// Returns `false` without recording anything if a render pass is active.
+    ///
+    /// # Safety
+    ///
+    /// - `retired_images` must not be used by any commands that are added afterwards.
+    /// - The resources of `dependency_info` must not have been used by earlier commands.
+    pub(crate) unsafe fn aliasing_barrier(
+        &mut self,
+        retired_images: &[Arc<Image>],
+        dependency_info: DependencyInfo,
+    ) -> bool {
+        if self.render_pass_state.is_some() {
+            return false;
+        }
+
+        for image in retired_images {
+            self.inner.transition_to_final_layout_immediate(image);
+        }
+
+        self.inner
+            .pipeline_barrier_immediate(dependency_info, BarrierReason::MemoryAliasing);
+
+        true
+    }
 }
 
 impl<A> AutoCommandBufferBuilder<SecondaryAutoCommandBuffer<A::Alloc>, A>
@@ -639,6 +678,10 @@ where
             return Err(BuildError::QueryActive);
         }
 
+        if self.inner.has_set_events() {
+            return Err(BuildError::EventNotWaited);
+        }
+
         let submit_state = match self.usage {
             CommandBufferUsage::MultipleSubmit => SubmitState::ExclusiveUse {
                 in_use: AtomicBool::new(false),
@@ -669,6 +712,9 @@ pub enum BuildError {
 
     /// A query is still active on the command buffer.
     QueryActive,
+
+    /// An event was set on the command buffer with `set_event`, but not waited on.
+    EventNotWaited,
 }
 
 impl Error for BuildError {
@@ -688,6 +734,10 @@ impl Display for BuildError {
                 write!(f, "a render pass is still active on the command buffer")
             }
             Self::QueryActive => write!(f, "a query is still active on the command buffer"),
+            Self::EventNotWaited => write!(
+                f,
+                "an event was set on the command buffer, but not waited on",
+            ),
         }
     }
 }
@@ -710,6 +760,17 @@ where
     pub fn state(&self) -> CommandBufferBuilderState<'_> {
         self.inner.state()
     }
+
+    /// Starts listing every pipeline barrier that the builder inserts from now on, along with the
+    /// resource and the command that needed it, and why. Once the command buffer is built, the
+    /// list can be retrieved with `barrier_report`.
+    ///
+    /// This can be used to find out how to reorder commands so that fewer barriers are needed.
+    #[inline]
+    pub fn enable_barrier_report(&mut self) -> &mut Self {
+        self.inner.enable_barrier_report();
+        self
+    }
 }
 
 unsafe impl<L, A> DeviceOwned for AutoCommandBufferBuilder<L, A>
@@ -729,6 +790,17 @@ pub struct PrimaryAutoCommandBuffer<A = StandardCommandBufferAlloc> {
     state: Mutex<CommandBufferState>,
 }
 
+impl<A> PrimaryAutoCommandBuffer<A> {
+    /// Returns every pipeline barrier that was inserted in the command buffer, ordered by the
+    /// command before which it is recorded, or `None` if
+    /// [`enable_barrier_report`](AutoCommandBufferBuilder::enable_barrier_report) wasn't called
+    /// when building it.
+    #[inline]
+    pub fn barrier_report(&self) -> Option<&[BarrierReportEntry]> {
+        self.inner.barrier_report()
+    }
+}
+
 unsafe impl<A> DeviceOwned for PrimaryAutoCommandBuffer<A> {
     fn device(&self) -> &Arc<Device> {
         self.inner.device()
@@ -770,6 +842,17 @@ pub struct SecondaryAutoCommandBuffer<A = StandardCommandBufferAlloc> {
     submit_state: SubmitState,
 }
 
+impl<A> SecondaryAutoCommandBuffer<A> {
+    /// Returns every pipeline barrier that was inserted in the command buffer, ordered by the
+    /// command before which it is recorded, or `None` if
+    /// [`enable_barrier_report`](AutoCommandBufferBuilder::enable_barrier_report) wasn't called
+    /// when building it.
+    #[inline]
+    pub fn barrier_report(&self) -> Option<&[BarrierReportEntry]> {
+        self.inner.barrier_report()
+    }
+}
+
 unsafe impl<A> VulkanObject for SecondaryAutoCommandBuffer<A> {
     type Handle = ash::vk::CommandBuffer;
 
@@ -863,14 +946,27 @@ enum SubmitState {
 mod tests {
     use super::*;
     use crate::{
-        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
         command_buffer::{
-            synced::SyncCommandBufferBuilderError, BufferCopy, CopyBufferInfoTyped, CopyError,
-            ExecuteCommandsError,
+            synced::SyncCommandBufferBuilderError, BarrierResource, BufferCopy,
+            CopyBufferInfoTyped, CopyBufferToImageInfo, CopyError, CopyImageToBufferInfo,
+            ExecuteCommandsError, ParallelRecordError, ResourceInCommand, SynchronizationError,
         },
         device::{DeviceCreateInfo, QueueCreateInfo},
+        format::Format,
+        image::{
+            ImageAccess, ImageCreateFlags, ImageDimensions, ImageLayout, ImageUsage, StorageImage,
+        },
         memory::allocator::{AllocationCreateInfo, MemoryUsage, StandardMemoryAllocator},
-        sync::GpuFuture,
+        pipeline::{
+            compute::ComputePipelineCreateInfo, layout::PipelineDescriptorSetLayoutCreateInfo,
+            ComputePipeline, PipelineBindPoint, PipelineLayout,
+        },
+        shader::{PipelineShaderStageCreateInfo, ShaderModule},
+        sync::{
+            event::Event, AccessFlags, BufferMemoryBarrier, DependencyInfo, GpuFuture,
+            ImageMemoryBarrier, PipelineStages, QueueFamilyOwnershipTransfer,
+        },
     };
 
     #[test]
@@ -1131,4 +1227,585 @@ mod tests {
             })
         ));
     }
+    #[test]
+    fn parallel_recording_outside_render_pass() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device.clone(), Default::default());
+        let thread_allocators: Vec<_> = (0..2)
+            .map(|_| StandardCommandBufferAllocator::new(device.clone(), Default::default()))
+            .collect();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            builder.execute_commands_parallel(&thread_allocators, |_, _| Ok(())),
+            Err(ParallelRecordError::ForbiddenOutsideRenderPass)
+        ));
+    }
+    #[test]
+    fn barrier_report() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = StandardMemoryAllocator::new_default(device.clone());
+        let [source, intermediate, destination] = [0_u32, 1, 2].map(|_| {
+            Buffer::from_iter(
+                &memory_allocator,
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    usage: MemoryUsage::Upload,
+                    ..Default::default()
+                },
+                [0_u32, 1, 2, 3].iter().copied(),
+            )
+            .unwrap()
+        });
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        builder
+            .enable_barrier_report()
+            .copy_buffer(CopyBufferInfoTyped::buffers(source, intermediate.clone()))
+            .unwrap()
+            .copy_buffer(CopyBufferInfoTyped::buffers(
+                intermediate.clone(),
+                destination,
+            ))
+            .unwrap();
+
+        let cb = builder.build().unwrap();
+        let report = cb.barrier_report().unwrap();
+
+        assert_eq!(
+            report
+                .iter()
+                .filter(|entry| entry.reason == BarrierReason::FirstUse)
+                .count(),
+            3
+        );
+
+        let hazard = report
+            .iter()
+            .find(|entry| entry.reason == BarrierReason::ReadAfterWrite)
+            .unwrap();
+        assert!(matches!(
+            &hazard.resource,
+            BarrierResource::Buffer { buffer, .. } if buffer == intermediate.buffer()
+        ));
+        assert_eq!(hazard.use_ref.unwrap().command_index, 1);
+        assert_eq!(hazard.previous_use_ref.unwrap().command_index, 0);
+        assert_eq!(hazard.position, 1);
+        assert_eq!(hazard.split_position, None);
+    }
+
+    #[test]
+    fn event_overlapping_work() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = StandardMemoryAllocator::new_default(device.clone());
+        let [source, intermediate, destination, unrelated] = [0_u32, 1, 2, 3].map(|_| {
+            Buffer::from_iter(
+                &memory_allocator,
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    usage: MemoryUsage::Upload,
+                    ..Default::default()
+                },
+                [0_u32, 1, 2, 3].iter().copied(),
+            )
+            .unwrap()
+        });
+        let event = Arc::new(Event::from_pool(device.clone()).unwrap());
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        builder
+            .copy_buffer(CopyBufferInfoTyped::buffers(source, intermediate.clone()))
+            .unwrap();
+
+        unsafe {
+            builder
+                .set_event(
+                    event.clone(),
+                    DependencyInfo {
+                        buffer_memory_barriers: [BufferMemoryBarrier {
+                            src_stages: PipelineStages::ALL_TRANSFER,
+                            src_access: AccessFlags::TRANSFER_WRITE,
+                            dst_stages: PipelineStages::ALL_TRANSFER,
+                            dst_access: AccessFlags::TRANSFER_READ,
+                            range: 0..intermediate.size(),
+                            ..BufferMemoryBarrier::buffer(intermediate.buffer().clone())
+                        }]
+                        .into_iter()
+                        .collect(),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        }
+
+        // Work on other resources can overlap with the event.
+        builder
+            .copy_buffer(CopyBufferInfoTyped::buffers(destination.clone(), unrelated))
+            .unwrap();
+
+        // The intermediate buffer can't be used before the event is waited on.
+        assert!(matches!(
+            builder.copy_buffer(CopyBufferInfoTyped::buffers(
+                intermediate.clone(),
+                destination.clone(),
+            )),
+            Err(CopyError::SyncCommandBufferBuilderError(
+                SyncCommandBufferBuilderError::Conflict { .. }
+            ))
+        ));
+
+        unsafe {
+            builder.wait_events([event.clone()]).unwrap();
+
+            // The event can only be waited on once.
+            assert!(matches!(
+                builder.wait_events([event]),
+                Err(SynchronizationError::EventNotSet { event_index: 0 })
+            ));
+        }
+
+        builder
+            .copy_buffer(CopyBufferInfoTyped::buffers(intermediate, destination))
+            .unwrap();
+
+        builder.build().unwrap();
+    }
+
+    #[test]
+    fn event_not_waited() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = StandardMemoryAllocator::new_default(device.clone());
+        let buffer = Buffer::from_iter(
+            &memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Upload,
+                ..Default::default()
+            },
+            [0_u32; 4],
+        )
+        .unwrap();
+        let event = Arc::new(Event::from_pool(device.clone()).unwrap());
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        builder.fill_buffer(buffer.clone(), 1).unwrap();
+
+        unsafe {
+            builder
+                .set_event(
+                    event,
+                    DependencyInfo {
+                        buffer_memory_barriers: [BufferMemoryBarrier {
+                            src_stages: PipelineStages::ALL_TRANSFER,
+                            src_access: AccessFlags::TRANSFER_WRITE,
+                            dst_stages: PipelineStages::ALL_TRANSFER,
+                            dst_access: AccessFlags::TRANSFER_READ,
+                            range: 0..buffer.size(),
+                            ..BufferMemoryBarrier::buffer(buffer.buffer().clone())
+                        }]
+                        .into_iter()
+                        .collect(),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        }
+
+        assert!(matches!(builder.build(), Err(BuildError::EventNotWaited)));
+    }
+
+    #[test]
+    fn event_image_barrier() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = StandardMemoryAllocator::new_default(device.clone());
+        let [source, destination] = [0_u32, 1].map(|_| {
+            Buffer::from_iter(
+                &memory_allocator,
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    usage: MemoryUsage::Upload,
+                    ..Default::default()
+                },
+                [0_u32; 16 * 16],
+            )
+            .unwrap()
+        });
+        let image = StorageImage::with_usage(
+            &memory_allocator,
+            ImageDimensions::Dim2d {
+                width: 16,
+                height: 16,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+            ImageCreateFlags::empty(),
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+        let event = Arc::new(Event::from_pool(device.clone()).unwrap());
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        builder
+            .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(source, image.clone()))
+            .unwrap();
+
+        // The event transitions the image to the layout of the next copy.
+        unsafe {
+            builder
+                .set_event(
+                    event.clone(),
+                    DependencyInfo {
+                        image_memory_barriers: [ImageMemoryBarrier {
+                            src_stages: PipelineStages::ALL_TRANSFER,
+                            src_access: AccessFlags::TRANSFER_WRITE,
+                            dst_stages: PipelineStages::ALL_TRANSFER,
+                            dst_access: AccessFlags::TRANSFER_READ,
+                            old_layout: ImageLayout::TransferDstOptimal,
+                            new_layout: ImageLayout::TransferSrcOptimal,
+                            subresource_range: image.subresource_range(),
+                            ..ImageMemoryBarrier::image(image.inner().image.clone())
+                        }]
+                        .into_iter()
+                        .collect(),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        }
+
+        // The image can't be used before the event is waited on.
+        assert!(matches!(
+            builder.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+                image.clone(),
+                destination.clone(),
+            )),
+            Err(CopyError::SyncCommandBufferBuilderError(
+                SyncCommandBufferBuilderError::Conflict { .. }
+            ))
+        ));
+
+        unsafe {
+            builder.wait_events([event]).unwrap();
+        }
+
+        builder
+            .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image, destination))
+            .unwrap();
+
+        builder.build().unwrap();
+    }
+
+    fn ownership_transfer_resources(
+        device: Arc<Device>,
+        queue_family_index: u32,
+    ) -> (Subbuffer<[u32]>, Arc<StorageImage>) {
+        let memory_allocator = StandardMemoryAllocator::new_default(device);
+        let buffer = Buffer::from_iter(
+            &memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Upload,
+                ..Default::default()
+            },
+            [0_u32; 16 * 16],
+        )
+        .unwrap();
+        let image = StorageImage::with_usage(
+            &memory_allocator,
+            ImageDimensions::Dim2d {
+                width: 16,
+                height: 16,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            ImageUsage::TRANSFER_DST,
+            ImageCreateFlags::empty(),
+            [queue_family_index],
+        )
+        .unwrap();
+
+        (buffer, image)
+    }
+
+    #[test]
+    fn release_barrier_uses_tracked_layout() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let (buffer, image) =
+            ownership_transfer_resources(device.clone(), queue.queue_family_index());
+        let final_layout = image.final_layout_requirement();
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        builder
+            .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(buffer, image.clone()))
+            .unwrap();
+
+        // The copy leaves the image in `TransferDstOptimal` until the end of the command buffer,
+        // where it's transitioned to its final layout before the release.
+        let release = unsafe {
+            builder.inner.pipeline_barrier_at_end(DependencyInfo {
+                image_memory_barriers: [ImageMemoryBarrier {
+                    src_stages: PipelineStages::ALL_TRANSFER,
+                    src_access: AccessFlags::TRANSFER_WRITE,
+                    queue_family_ownership_transfer: Some(
+                        QueueFamilyOwnershipTransfer::ExclusiveToExternal {
+                            src_index: queue.queue_family_index(),
+                        },
+                    ),
+                    subresource_range: image.subresource_range(),
+                    ..ImageMemoryBarrier::image(image.inner().image.clone())
+                }]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            })
+        };
+
+        assert!(!release.image_memory_barriers.is_empty());
+        assert!(release.image_memory_barriers.iter().all(|barrier| {
+            barrier.old_layout == final_layout && barrier.new_layout == final_layout
+        }));
+
+        builder.build().unwrap();
+    }
+
+    #[test]
+    fn acquire_barrier_is_tracked() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let (buffer, image) =
+            ownership_transfer_resources(device.clone(), queue.queue_family_index());
+        let layout = image.final_layout_requirement();
+        let queue_family_ownership_transfer = QueueFamilyOwnershipTransfer::ExclusiveFromExternal {
+            dst_index: queue.queue_family_index(),
+        };
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder.enable_barrier_report();
+
+        unsafe {
+            builder.inner.pipeline_barrier_immediate(
+                DependencyInfo {
+                    buffer_memory_barriers: [BufferMemoryBarrier {
+                        dst_stages: PipelineStages::ALL_COMMANDS,
+                        dst_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                        queue_family_ownership_transfer: Some(queue_family_ownership_transfer),
+                        range: 0..buffer.size(),
+                        ..BufferMemoryBarrier::buffer(buffer.buffer().clone())
+                    }]
+                    .into_iter()
+                    .collect(),
+                    image_memory_barriers: [ImageMemoryBarrier {
+                        dst_stages: PipelineStages::ALL_COMMANDS,
+                        dst_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                        old_layout: layout,
+                        new_layout: layout,
+                        queue_family_ownership_transfer: Some(queue_family_ownership_transfer),
+                        subresource_range: image.subresource_range(),
+                        ..ImageMemoryBarrier::image(image.inner().image.clone())
+                    }]
+                    .into_iter()
+                    .collect(),
+                    ..Default::default()
+                },
+                BarrierReason::QueueFamilyOwnershipTransfer,
+            )
+        };
+
+        builder
+            .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(
+                buffer.clone(),
+                image.clone(),
+            ))
+            .unwrap();
+
+        let cb = builder.build().unwrap();
+        let report = cb.barrier_report().unwrap();
+
+        // The acquire synchronizes the first use of both resources, and the read of the buffer is
+        // within its destination scope.
+        assert!(!report
+            .iter()
+            .any(|entry| entry.reason == BarrierReason::FirstUse));
+        assert!(!report.iter().any(|entry| {
+            entry.reason != BarrierReason::QueueFamilyOwnershipTransfer
+                && matches!(&entry.resource, BarrierResource::Buffer { .. })
+        }));
+
+        // The image is expected in the layout it was acquired in, and only transitioned for the
+        // copy.
+        let image_usage = &cb.resources_usage().images[0];
+        assert!(
+            image_usage
+                .ranges
+                .iter()
+                .all(|(_range, usage)| usage.expected_layout == layout
+                    && usage.final_layout == layout)
+        );
+        assert!(report.iter().any(|entry| {
+            matches!(
+                &entry.resource,
+                BarrierResource::Image { new_layout, .. }
+                    if *new_layout == ImageLayout::TransferDstOptimal
+            )
+        }));
+    }
+
+    #[test]
+    fn device_address_buffer_barrier() {
+        let (device, queue) = gfx_dev_and_queue!(buffer_device_address);
+
+        let cs = unsafe {
+            /*
+            #version 450
+
+            layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+            void main() {}
+            */
+            const MODULE: [u32; 35] = [
+                0x07230203, 0x00010000, 0, 6, 0, 0x00020011, 1, 0x0003000e, 0, 1, 0x0005000f, 5, 4,
+                0x6e69616d, 0, 0x00060010, 4, 17, 1, 1, 1, 0x00020013, 2, 0x00030021, 3, 2,
+                0x00050036, 2, 4, 0, 3, 0x000200f8, 5, 0x000100fd, 0x00010038,
+            ];
+            let module = ShaderModule::from_words(device.clone(), &MODULE).unwrap();
+            module.entry_point("main").unwrap()
+        };
+
+        let pipeline = {
+            let stage = PipelineShaderStageCreateInfo::entry_point(cs);
+            let layout = PipelineLayout::new(
+                device.clone(),
+                PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+                    .into_pipeline_layout_create_info(device.clone())
+                    .unwrap(),
+            )
+            .unwrap();
+            ComputePipeline::new(
+                device.clone(),
+                None,
+                ComputePipelineCreateInfo::stage_layout(stage, layout),
+            )
+            .unwrap()
+        };
+
+        let memory_allocator = StandardMemoryAllocator::new_default(device.clone());
+        let buffer = Buffer::from_iter(
+            &memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST | BufferUsage::SHADER_DEVICE_ADDRESS,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::DeviceOnly,
+                ..Default::default()
+            },
+            [0_u32; 4],
+        )
+        .unwrap();
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        builder
+            .enable_barrier_report()
+            .fill_buffer(buffer.clone(), 1)
+            .unwrap()
+            .bind_pipeline_compute(pipeline)
+            .bind_device_address_buffers(PipelineBindPoint::Compute, [buffer.as_bytes().clone()])
+            .dispatch([1, 1, 1])
+            .unwrap();
+
+        let cb = builder.build().unwrap();
+        let report = cb.barrier_report().unwrap();
+
+        // The dispatch is assumed to read and write the buffer through its device address, so it
+        // must wait for the fill in the compute shader stage.
+        let hazard = report
+            .iter()
+            .find(|entry| entry.reason == BarrierReason::WriteAfterWrite)
+            .unwrap();
+        assert!(matches!(
+            &hazard.resource,
+            BarrierResource::Buffer { buffer: b, .. } if b == buffer.buffer()
+        ));
+        assert_eq!(
+            hazard.use_ref.unwrap().resource_in_command,
+            ResourceInCommand::DeviceAddressBuffer { index: 0 }
+        );
+        assert_eq!(hazard.dst_stages, PipelineStages::COMPUTE_SHADER);
+    }
 }
@@ -0,0 +1,813 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use crate::{
+    acceleration_structure::{
+        AccelerationStructure, AccelerationStructureBuildGeometryInfo,
+        AccelerationStructureBuildRangeInfo, AccelerationStructureBuildType,
+        AccelerationStructureError, BuildAccelerationStructureMode, CopyAccelerationStructureInfo,
+        CopyAccelerationStructureToMemoryInfo, CopyMemoryToAccelerationStructureInfo,
+    },
+    command_buffer::{
+        allocator::CommandBufferAllocator,
+        synced::{Command, Resource, SyncCommandBufferBuilder, SyncCommandBufferBuilderError},
+        sys::UnsafeCommandBufferBuilder,
+        AutoCommandBufferBuilder, ResourceInCommand, ResourceUseRef,
+    },
+    device::{DeviceOwned, QueueFlags},
+    query::{QueryPool, QueryType},
+    sync::{AccessFlags, PipelineMemoryAccess, PipelineStages},
+    VulkanObject,
+};
+use smallvec::SmallVec;
+use std::sync::Arc;
+
+/// # Commands to build and copy acceleration structures.
+impl<L, A> AutoCommandBufferBuilder<L, A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Builds or updates an acceleration structure.
+    ///
+    /// `build_range_infos` must contain one element for each geometry in `info`.
+    ///
+    /// # Safety
+    ///
+    /// - The contents of the geometry buffers in `info` must be valid for `build_range_infos`.
+    ///   For instance geometries, every instance must refer to a valid bottom-level acceleration
+    ///   structure that has been built, and is kept alive while the command buffer is in use.
+    /// - If `info.mode` is [`BuildAccelerationStructureMode::Update`], then the source
+    ///   acceleration structure must have been built with
+    ///   [`BuildAccelerationStructureFlags::ALLOW_UPDATE`], and the geometries must be the same
+    ///   as in that build, except for their data.
+    ///
+    /// [`BuildAccelerationStructureFlags::ALLOW_UPDATE`]: crate::acceleration_structure::BuildAccelerationStructureFlags::ALLOW_UPDATE
+    pub unsafe fn build_acceleration_structure(
+        &mut self,
+        info: AccelerationStructureBuildGeometryInfo,
+        build_range_infos: SmallVec<[AccelerationStructureBuildRangeInfo; 8]>,
+    ) -> Result<&mut Self, AccelerationStructureError> {
+        self.validate_build_acceleration_structure(&info, &build_range_infos)?;
+
+        self.inner
+            .build_acceleration_structure(info, build_range_infos)?;
+
+        Ok(self)
+    }
+
+    fn validate_build_acceleration_structure(
+        &self,
+        info: &AccelerationStructureBuildGeometryInfo,
+        build_range_infos: &[AccelerationStructureBuildRangeInfo],
+    ) -> Result<(), AccelerationStructureError> {
+        // VUID-vkCmdBuildAccelerationStructuresKHR-renderpass
+        if self.render_pass_state.is_some() {
+            return Err(AccelerationStructureError::ForbiddenInsideRenderPass);
+        }
+
+        // VUID-vkCmdBuildAccelerationStructuresKHR-commandBuffer-cmdpool
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::COMPUTE)
+        {
+            return Err(AccelerationStructureError::NotSupportedByQueueFamily);
+        }
+
+        info.validate(
+            self.device(),
+            AccelerationStructureBuildType::Device,
+            Some(build_range_infos),
+        )
+    }
+
+    /// Copies an acceleration structure into another.
+    ///
+    /// # Safety
+    ///
+    /// - `info.src` must have been built when this command is executed.
+    /// - If `info.mode` is [`CopyAccelerationStructureMode::Compact`], then `info.src` must
+    ///   have been built with [`BuildAccelerationStructureFlags::ALLOW_COMPACTION`], and
+    ///   `info.dst` must be at least as large as the compacted size of `info.src`.
+    ///
+    /// [`CopyAccelerationStructureMode::Compact`]: crate::acceleration_structure::CopyAccelerationStructureMode::Compact
+    /// [`BuildAccelerationStructureFlags::ALLOW_COMPACTION`]: crate::acceleration_structure::BuildAccelerationStructureFlags::ALLOW_COMPACTION
+    pub unsafe fn copy_acceleration_structure(
+        &mut self,
+        info: CopyAccelerationStructureInfo,
+    ) -> Result<&mut Self, AccelerationStructureError> {
+        self.validate_copy_acceleration_structure(&info)?;
+
+        self.inner.copy_acceleration_structure(info)?;
+
+        Ok(self)
+    }
+
+    fn validate_copy_acceleration_structure(
+        &self,
+        info: &CopyAccelerationStructureInfo,
+    ) -> Result<(), AccelerationStructureError> {
+        // VUID-vkCmdCopyAccelerationStructureKHR-renderpass
+        if self.render_pass_state.is_some() {
+            return Err(AccelerationStructureError::ForbiddenInsideRenderPass);
+        }
+
+        // VUID-vkCmdCopyAccelerationStructureKHR-commandBuffer-cmdpool
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::COMPUTE)
+        {
+            return Err(AccelerationStructureError::NotSupportedByQueueFamily);
+        }
+
+        info.validate(self.device(), false)
+    }
+
+    /// Serializes an acceleration structure into a buffer.
+    ///
+    /// # Safety
+    ///
+    /// - `info.src` must have been built when this command is executed.
+    /// - `info.dst` must be large enough to hold the serialized data.
+    pub unsafe fn copy_acceleration_structure_to_memory(
+        &mut self,
+        info: CopyAccelerationStructureToMemoryInfo,
+    ) -> Result<&mut Self, AccelerationStructureError> {
+        self.validate_copy_acceleration_structure_to_memory(&info)?;
+
+        self.inner.copy_acceleration_structure_to_memory(info)?;
+
+        Ok(self)
+    }
+
+    fn validate_copy_acceleration_structure_to_memory(
+        &self,
+        info: &CopyAccelerationStructureToMemoryInfo,
+    ) -> Result<(), AccelerationStructureError> {
+        // VUID-vkCmdCopyAccelerationStructureToMemoryKHR-renderpass
+        if self.render_pass_state.is_some() {
+            return Err(AccelerationStructureError::ForbiddenInsideRenderPass);
+        }
+
+        // VUID-vkCmdCopyAccelerationStructureToMemoryKHR-commandBuffer-cmdpool
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::COMPUTE)
+        {
+            return Err(AccelerationStructureError::NotSupportedByQueueFamily);
+        }
+
+        info.validate(self.device())
+    }
+
+    /// Deserializes an acceleration structure from a buffer.
+    ///
+    /// # Safety
+    ///
+    /// - `info.src` must contain data that was previously serialized with
+    ///   [`copy_acceleration_structure_to_memory`](Self::copy_acceleration_structure_to_memory),
+    ///   on a device that is compatible with this one.
+    /// - `info.dst` must be large enough to hold the deserialized acceleration structure.
+    pub unsafe fn copy_memory_to_acceleration_structure(
+        &mut self,
+        info: CopyMemoryToAccelerationStructureInfo,
+    ) -> Result<&mut Self, AccelerationStructureError> {
+        self.validate_copy_memory_to_acceleration_structure(&info)?;
+
+        self.inner.copy_memory_to_acceleration_structure(info)?;
+
+        Ok(self)
+    }
+
+    fn validate_copy_memory_to_acceleration_structure(
+        &self,
+        info: &CopyMemoryToAccelerationStructureInfo,
+    ) -> Result<(), AccelerationStructureError> {
+        // VUID-vkCmdCopyMemoryToAccelerationStructureKHR-renderpass
+        if self.render_pass_state.is_some() {
+            return Err(AccelerationStructureError::ForbiddenInsideRenderPass);
+        }
+
+        // VUID-vkCmdCopyMemoryToAccelerationStructureKHR-commandBuffer-cmdpool
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::COMPUTE)
+        {
+            return Err(AccelerationStructureError::NotSupportedByQueueFamily);
+        }
+
+        info.validate(self.device())
+    }
+
+    /// Writes the properties of one or more acceleration structures to queries in a query pool.
+    ///
+    /// The query pool must be of type [`QueryType::AccelerationStructureCompactedSize`] or
+    /// [`QueryType::AccelerationStructureSerializationSize`], and one query is written for each
+    /// element of `acceleration_structures`, starting at `first_query`.
+    ///
+    /// # Safety
+    ///
+    /// - The queries must be unavailable, ensured by calling
+    ///   [`reset_query_pool`](Self::reset_query_pool).
+    /// - The acceleration structures must have been built when this command is executed.
+    /// - If the query pool is of type [`QueryType::AccelerationStructureCompactedSize`], the
+    ///   acceleration structures must have been built with
+    ///   [`BuildAccelerationStructureFlags::ALLOW_COMPACTION`].
+    ///
+    /// [`BuildAccelerationStructureFlags::ALLOW_COMPACTION`]: crate::acceleration_structure::BuildAccelerationStructureFlags::ALLOW_COMPACTION
+    pub unsafe fn write_acceleration_structures_properties(
+        &mut self,
+        acceleration_structures: SmallVec<[Arc<AccelerationStructure>; 4]>,
+        query_pool: Arc<QueryPool>,
+        first_query: u32,
+    ) -> Result<&mut Self, AccelerationStructureError> {
+        self.validate_write_acceleration_structures_properties(
+            &acceleration_structures,
+            &query_pool,
+            first_query,
+        )?;
+
+        self.inner.write_acceleration_structures_properties(
+            acceleration_structures,
+            query_pool,
+            first_query,
+        )?;
+
+        Ok(self)
+    }
+
+    fn validate_write_acceleration_structures_properties(
+        &self,
+        acceleration_structures: &[Arc<AccelerationStructure>],
+        query_pool: &QueryPool,
+        first_query: u32,
+    ) -> Result<(), AccelerationStructureError> {
+        // VUID-vkCmdWriteAccelerationStructuresPropertiesKHR-renderpass
+        if self.render_pass_state.is_some() {
+            return Err(AccelerationStructureError::ForbiddenInsideRenderPass);
+        }
+
+        // VUID-vkCmdWriteAccelerationStructuresPropertiesKHR-commandBuffer-cmdpool
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::COMPUTE)
+        {
+            return Err(AccelerationStructureError::NotSupportedByQueueFamily);
+        }
+
+        let device = self.device();
+
+        // VUID-vkCmdWriteAccelerationStructuresPropertiesKHR-commonparent
+        assert_eq!(device, query_pool.device());
+
+        for acceleration_structure in acceleration_structures {
+            assert_eq!(device, acceleration_structure.device());
+        }
+
+        // VUID-vkCmdWriteAccelerationStructuresPropertiesKHR-queryType-06742
+        if !matches!(
+            query_pool.query_type(),
+            QueryType::AccelerationStructureCompactedSize
+                | QueryType::AccelerationStructureSerializationSize
+        ) {
+            return Err(AccelerationStructureError::QueryTypeNotAllowed);
+        }
+
+        // VUID-vkCmdWriteAccelerationStructuresPropertiesKHR-query-04880
+        let query_end = first_query as u64 + acceleration_structures.len() as u64;
+
+        if query_end > query_pool.query_count() as u64 {
+            return Err(AccelerationStructureError::QueryOutOfRange {
+                query_index: query_end.saturating_sub(1) as u32,
+                query_count: query_pool.query_count(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl SyncCommandBufferBuilder {
+    /// Calls `vkCmdBuildAccelerationStructuresKHR` on the builder.
+    pub unsafe fn build_acceleration_structure(
+        &mut self,
+        info: AccelerationStructureBuildGeometryInfo,
+        build_range_infos: SmallVec<[AccelerationStructureBuildRangeInfo; 8]>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            info: AccelerationStructureBuildGeometryInfo,
+            build_range_infos: SmallVec<[AccelerationStructureBuildRangeInfo; 8]>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "build_acceleration_structure"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.build_acceleration_structure(&self.info, &self.build_range_infos);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "build_acceleration_structure";
+        let mut resources: SmallVec<[_; 8]> = info
+            .input_buffers()
+            .map(|(index, buffer)| {
+                (
+                    ResourceUseRef {
+                        command_index,
+                        command_name,
+                        resource_in_command: ResourceInCommand::GeometryData {
+                            index: index as u32,
+                        },
+                        secondary_use_ref: None,
+                    },
+                    Resource::Buffer {
+                        buffer: buffer.clone(),
+                        range: 0..buffer.size(),
+                        memory: PipelineMemoryAccess {
+                            stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                            access: AccessFlags::SHADER_READ,
+                            exclusive: false,
+                        },
+                    },
+                )
+            })
+            .collect();
+
+        if let BuildAccelerationStructureMode::Update(src_acceleration_structure) = &info.mode {
+            // An in-place update only needs the write access below.
+            if info
+                .dst_acceleration_structure
+                .as_ref()
+                .map_or(true, |dst| {
+                    dst.handle() != src_acceleration_structure.handle()
+                })
+            {
+                let buffer = src_acceleration_structure.buffer();
+                resources.push((
+                    ResourceUseRef {
+                        command_index,
+                        command_name,
+                        resource_in_command: ResourceInCommand::Source,
+                        secondary_use_ref: None,
+                    },
+                    Resource::Buffer {
+                        buffer: buffer.clone(),
+                        range: 0..buffer.size(),
+                        memory: PipelineMemoryAccess {
+                            stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                            access: AccessFlags::ACCELERATION_STRUCTURE_READ,
+                            exclusive: false,
+                        },
+                    },
+                ));
+            }
+        }
+
+        if let Some(dst_acceleration_structure) = &info.dst_acceleration_structure {
+            let buffer = dst_acceleration_structure.buffer();
+            resources.push((
+                ResourceUseRef {
+                    command_index,
+                    command_name,
+                    resource_in_command: ResourceInCommand::Destination,
+                    secondary_use_ref: None,
+                },
+                Resource::Buffer {
+                    buffer: buffer.clone(),
+                    range: 0..buffer.size(),
+                    memory: PipelineMemoryAccess {
+                        stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                        access: AccessFlags::ACCELERATION_STRUCTURE_READ
+                            | AccessFlags::ACCELERATION_STRUCTURE_WRITE,
+                        exclusive: true,
+                    },
+                },
+            ));
+        }
+
+        if let Some(scratch_data) = &info.scratch_data {
+            resources.push((
+                ResourceUseRef {
+                    command_index,
+                    command_name,
+                    resource_in_command: ResourceInCommand::ScratchData,
+                    secondary_use_ref: None,
+                },
+                Resource::Buffer {
+                    buffer: scratch_data.clone(),
+                    range: 0..scratch_data.size(),
+                    memory: PipelineMemoryAccess {
+                        stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                        access: AccessFlags::ACCELERATION_STRUCTURE_READ
+                            | AccessFlags::ACCELERATION_STRUCTURE_WRITE,
+                        exclusive: true,
+                    },
+                },
+            ));
+        }
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            info,
+            build_range_infos,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdCopyAccelerationStructureKHR` on the builder.
+    pub unsafe fn copy_acceleration_structure(
+        &mut self,
+        info: CopyAccelerationStructureInfo,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            info: CopyAccelerationStructureInfo,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "copy_acceleration_structure"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.copy_acceleration_structure(&self.info);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "copy_acceleration_structure";
+        let resources = [
+            (
+                ResourceUseRef {
+                    command_index,
+                    command_name,
+                    resource_in_command: ResourceInCommand::Source,
+                    secondary_use_ref: None,
+                },
+                Resource::Buffer {
+                    buffer: info.src.buffer().clone(),
+                    range: 0..info.src.size(),
+                    memory: PipelineMemoryAccess {
+                        stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                        access: AccessFlags::ACCELERATION_STRUCTURE_READ,
+                        exclusive: false,
+                    },
+                },
+            ),
+            (
+                ResourceUseRef {
+                    command_index,
+                    command_name,
+                    resource_in_command: ResourceInCommand::Destination,
+                    secondary_use_ref: None,
+                },
+                Resource::Buffer {
+                    buffer: info.dst.buffer().clone(),
+                    range: 0..info.dst.size(),
+                    memory: PipelineMemoryAccess {
+                        stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                        access: AccessFlags::ACCELERATION_STRUCTURE_WRITE,
+                        exclusive: true,
+                    },
+                },
+            ),
+        ];
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd { info }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdCopyAccelerationStructureToMemoryKHR` on the builder.
+    pub unsafe fn copy_acceleration_structure_to_memory(
+        &mut self,
+        info: CopyAccelerationStructureToMemoryInfo,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            info: CopyAccelerationStructureToMemoryInfo,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "copy_acceleration_structure_to_memory"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.copy_acceleration_structure_to_memory(&self.info);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "copy_acceleration_structure_to_memory";
+        let resources = [
+            (
+                ResourceUseRef {
+                    command_index,
+                    command_name,
+                    resource_in_command: ResourceInCommand::Source,
+                    secondary_use_ref: None,
+                },
+                Resource::Buffer {
+                    buffer: info.src.buffer().clone(),
+                    range: 0..info.src.size(),
+                    memory: PipelineMemoryAccess {
+                        stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                        access: AccessFlags::ACCELERATION_STRUCTURE_READ,
+                        exclusive: false,
+                    },
+                },
+            ),
+            (
+                ResourceUseRef {
+                    command_index,
+                    command_name,
+                    resource_in_command: ResourceInCommand::Destination,
+                    secondary_use_ref: None,
+                },
+                Resource::Buffer {
+                    buffer: info.dst.clone(),
+                    range: 0..info.dst.size(),
+                    memory: PipelineMemoryAccess {
+                        stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                        access: AccessFlags::TRANSFER_WRITE,
+                        exclusive: true,
+                    },
+                },
+            ),
+        ];
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd { info }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdCopyMemoryToAccelerationStructureKHR` on the builder.
+    pub unsafe fn copy_memory_to_acceleration_structure(
+        &mut self,
+        info: CopyMemoryToAccelerationStructureInfo,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            info: CopyMemoryToAccelerationStructureInfo,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "copy_memory_to_acceleration_structure"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.copy_memory_to_acceleration_structure(&self.info);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "copy_memory_to_acceleration_structure";
+        let resources = [
+            (
+                ResourceUseRef {
+                    command_index,
+                    command_name,
+                    resource_in_command: ResourceInCommand::Source,
+                    secondary_use_ref: None,
+                },
+                Resource::Buffer {
+                    buffer: info.src.clone(),
+                    range: 0..info.src.size(),
+                    memory: PipelineMemoryAccess {
+                        stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                        access: AccessFlags::TRANSFER_READ,
+                        exclusive: false,
+                    },
+                },
+            ),
+            (
+                ResourceUseRef {
+                    command_index,
+                    command_name,
+                    resource_in_command: ResourceInCommand::Destination,
+                    secondary_use_ref: None,
+                },
+                Resource::Buffer {
+                    buffer: info.dst.buffer().clone(),
+                    range: 0..info.dst.size(),
+                    memory: PipelineMemoryAccess {
+                        stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                        access: AccessFlags::ACCELERATION_STRUCTURE_WRITE,
+                        exclusive: true,
+                    },
+                },
+            ),
+        ];
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd { info }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdWriteAccelerationStructuresPropertiesKHR` on the builder.
+    pub unsafe fn write_acceleration_structures_properties(
+        &mut self,
+        acceleration_structures: SmallVec<[Arc<AccelerationStructure>; 4]>,
+        query_pool: Arc<QueryPool>,
+        first_query: u32,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            acceleration_structures: SmallVec<[Arc<AccelerationStructure>; 4]>,
+            query_pool: Arc<QueryPool>,
+            first_query: u32,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "write_acceleration_structures_properties"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.write_acceleration_structures_properties(
+                    &self.acceleration_structures,
+                    &self.query_pool,
+                    self.first_query,
+                );
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "write_acceleration_structures_properties";
+        let resources: SmallVec<[_; 4]> = acceleration_structures
+            .iter()
+            .enumerate()
+            .map(|(index, acceleration_structure)| {
+                (
+                    ResourceUseRef {
+                        command_index,
+                        command_name,
+                        resource_in_command: ResourceInCommand::AccelerationStructure {
+                            index: index as u32,
+                        },
+                        secondary_use_ref: None,
+                    },
+                    Resource::Buffer {
+                        buffer: acceleration_structure.buffer().clone(),
+                        range: 0..acceleration_structure.size(),
+                        memory: PipelineMemoryAccess {
+                            stages: PipelineStages::ACCELERATION_STRUCTURE_BUILD,
+                            access: AccessFlags::ACCELERATION_STRUCTURE_READ,
+                            exclusive: false,
+                        },
+                    },
+                )
+            })
+            .collect();
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            acceleration_structures,
+            query_pool,
+            first_query,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+}
+
+impl UnsafeCommandBufferBuilder {
+    /// Calls `vkCmdBuildAccelerationStructuresKHR` on the builder.
+    pub unsafe fn build_acceleration_structure(
+        &mut self,
+        info: &AccelerationStructureBuildGeometryInfo,
+        build_range_infos: &[AccelerationStructureBuildRangeInfo],
+    ) {
+        let (mut info_vk, geometries_vk) = info.to_vulkan(false);
+        info_vk.p_geometries = geometries_vk.as_ptr();
+
+        let build_range_infos_vk: SmallVec<[_; 8]> = build_range_infos
+            .iter()
+            .copied()
+            .map(ash::vk::AccelerationStructureBuildRangeInfoKHR::from)
+            .collect();
+
+        let fns = self.device.fns();
+        (fns.khr_acceleration_structure
+            .cmd_build_acceleration_structures_khr)(
+            self.handle,
+            1,
+            &info_vk,
+            &build_range_infos_vk.as_ptr(),
+        );
+    }
+
+    /// Calls `vkCmdCopyAccelerationStructureKHR` on the builder.
+    #[inline]
+    pub unsafe fn copy_acceleration_structure(&mut self, info: &CopyAccelerationStructureInfo) {
+        let info_vk = info.to_vulkan();
+
+        let fns = self.device.fns();
+        (fns.khr_acceleration_structure
+            .cmd_copy_acceleration_structure_khr)(self.handle, &info_vk);
+    }
+
+    /// Calls `vkCmdCopyAccelerationStructureToMemoryKHR` on the builder.
+    #[inline]
+    pub unsafe fn copy_acceleration_structure_to_memory(
+        &mut self,
+        info: &CopyAccelerationStructureToMemoryInfo,
+    ) {
+        let info_vk = info.to_vulkan();
+
+        let fns = self.device.fns();
+        (fns.khr_acceleration_structure
+            .cmd_copy_acceleration_structure_to_memory_khr)(self.handle, &info_vk);
+    }
+
+    /// Calls `vkCmdCopyMemoryToAccelerationStructureKHR` on the builder.
+    #[inline]
+    pub unsafe fn copy_memory_to_acceleration_structure(
+        &mut self,
+        info: &CopyMemoryToAccelerationStructureInfo,
+    ) {
+        let info_vk = info.to_vulkan();
+
+        let fns = self.device.fns();
+        (fns.khr_acceleration_structure
+            .cmd_copy_memory_to_acceleration_structure_khr)(self.handle, &info_vk);
+    }
+
+    /// Calls `vkCmdWriteAccelerationStructuresPropertiesKHR` on the builder.
+    pub unsafe fn write_acceleration_structures_properties(
+        &mut self,
+        acceleration_structures: &[Arc<AccelerationStructure>],
+        query_pool: &QueryPool,
+        first_query: u32,
+    ) {
+        if acceleration_structures.is_empty() {
+            return;
+        }
+
+        let acceleration_structures_vk: SmallVec<[_; 4]> = acceleration_structures
+            .iter()
+            .map(|acceleration_structure| acceleration_structure.handle())
+            .collect();
+
+        let fns = self.device.fns();
+        (fns.khr_acceleration_structure
+            .cmd_write_acceleration_structures_properties_khr)(
+            self.handle,
+            acceleration_structures_vk.len() as u32,
+            acceleration_structures_vk.as_ptr(),
+            query_pool.query_type().into(),
+            query_pool.handle(),
+            first_query,
+        );
+    }
+}
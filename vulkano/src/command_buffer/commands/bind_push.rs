@@ -30,7 +30,8 @@ use crate::{
             subpass::PipelineSubpassType,
             vertex_input::VertexBuffersCollection,
         },
-        ComputePipeline, GraphicsPipeline, PipelineBindPoint, PipelineLayout,
+        ComputePipeline, GraphicsPipeline, PipelineBindPoint, PipelineCreateFlags, PipelineLayout,
+        RayTracingPipeline,
     },
     shader::ShaderStages,
     DeviceSize, RequirementNotMet, RequiresOneOf, VulkanObject,
@@ -106,7 +107,7 @@ where
         // VUID-vkCmdBindDescriptorSets-commandBuffer-cmdpool
         // VUID-vkCmdBindDescriptorSets-pipelineBindPoint-00361
         match pipeline_bind_point {
-            PipelineBindPoint::Compute => {
+            PipelineBindPoint::Compute | PipelineBindPoint::RayTracing => {
                 if !queue_family_properties
                     .queue_flags
                     .intersects(QueueFlags::COMPUTE)
@@ -224,6 +225,83 @@ where
         Ok(())
     }
 
+    /// Binds buffers that future dispatch, draw or trace rays calls access through their
+    /// [device address], replacing all previously bound device address buffers for
+    /// `pipeline_bind_point`.
+    ///
+    /// Accesses that shaders make through a device address can't be seen by the command buffer
+    /// builder, so the buffers must be bound with this command in order to be kept alive and
+    /// synchronized. Each buffer is assumed to be both read and written by all shader stages of
+    /// the pipeline.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support `pipeline_bind_point`.
+    /// - Panics if `self` and any element of `buffers` do not belong to the same device.
+    /// - Panics if any element of `buffers` does not have the
+    ///   [`BufferUsage::SHADER_DEVICE_ADDRESS`] usage enabled.
+    ///
+    /// [device address]: crate::buffer::DeviceAddress
+    pub fn bind_device_address_buffers(
+        &mut self,
+        pipeline_bind_point: PipelineBindPoint,
+        buffers: impl IntoIterator<Item = Subbuffer<[u8]>>,
+    ) -> &mut Self {
+        let buffers: SmallVec<[_; 4]> = buffers.into_iter().collect();
+        self.validate_bind_device_address_buffers(pipeline_bind_point, &buffers)
+            .unwrap();
+
+        unsafe {
+            self.inner
+                .bind_device_address_buffers(pipeline_bind_point, buffers);
+        }
+
+        self
+    }
+
+    fn validate_bind_device_address_buffers(
+        &self,
+        pipeline_bind_point: PipelineBindPoint,
+        buffers: &[Subbuffer<[u8]>],
+    ) -> Result<(), BindPushError> {
+        pipeline_bind_point.validate_device(self.device())?;
+
+        let queue_family_properties = self.queue_family_properties();
+
+        match pipeline_bind_point {
+            PipelineBindPoint::Compute | PipelineBindPoint::RayTracing => {
+                if !queue_family_properties
+                    .queue_flags
+                    .intersects(QueueFlags::COMPUTE)
+                {
+                    return Err(BindPushError::NotSupportedByQueueFamily);
+                }
+            }
+            PipelineBindPoint::Graphics => {
+                if !queue_family_properties
+                    .queue_flags
+                    .intersects(QueueFlags::GRAPHICS)
+                {
+                    return Err(BindPushError::NotSupportedByQueueFamily);
+                }
+            }
+        }
+
+        for buffer in buffers {
+            assert_eq!(self.device(), buffer.device());
+
+            if !buffer
+                .buffer()
+                .usage()
+                .intersects(BufferUsage::SHADER_DEVICE_ADDRESS)
+            {
+                return Err(BindPushError::DeviceAddressBufferMissingUsage);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Binds an index buffer for future indexed draw calls.
     ///
     /// # Panics
@@ -334,6 +412,7 @@ where
     ///
     /// - Panics if the queue family of the command buffer does not support graphics operations.
     /// - Panics if `self` and `pipeline` do not belong to the same device.
+    /// - Panics if transform feedback is active.
     pub fn bind_pipeline_graphics(&mut self, pipeline: Arc<GraphicsPipeline>) -> &mut Self {
         self.validate_bind_pipeline_graphics(&pipeline).unwrap();
 
@@ -361,6 +440,11 @@ where
         // VUID-vkCmdBindPipeline-commonparent
         assert_eq!(self.device(), pipeline.device());
 
+        // VUID-vkCmdBindPipeline-None-02323
+        if self.transform_feedback_active {
+            return Err(BindPushError::TransformFeedbackActive);
+        }
+
         if let Some(last_pipeline) = self
             .render_pass_state
             .as_ref()
@@ -406,6 +490,48 @@ where
         Ok(())
     }
 
+    /// Binds a ray tracing pipeline for future ray tracing calls.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support compute operations.
+    /// - Panics if `self` and `pipeline` do not belong to the same device.
+    /// - Panics if `pipeline` is a pipeline library.
+    pub fn bind_pipeline_ray_tracing(&mut self, pipeline: Arc<RayTracingPipeline>) -> &mut Self {
+        self.validate_bind_pipeline_ray_tracing(&pipeline).unwrap();
+
+        unsafe {
+            self.inner.bind_pipeline_ray_tracing(pipeline);
+        }
+
+        self
+    }
+
+    fn validate_bind_pipeline_ray_tracing(
+        &self,
+        pipeline: &RayTracingPipeline,
+    ) -> Result<(), BindPushError> {
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdBindPipeline-pipelineBindPoint-02391
+        if !queue_family_properties
+            .queue_flags
+            .intersects(QueueFlags::COMPUTE)
+        {
+            return Err(BindPushError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdBindPipeline-commonparent
+        assert_eq!(self.device(), pipeline.device());
+
+        // VUID-vkCmdBindPipeline-pipeline-03382
+        if pipeline.flags().intersects(PipelineCreateFlags::LIBRARY) {
+            return Err(BindPushError::PipelineIsLibrary);
+        }
+
+        Ok(())
+    }
+
     /// Binds vertex buffers for future draw calls.
     ///
     /// # Panics
@@ -669,7 +795,7 @@ where
         // VUID-vkCmdPushDescriptorSetKHR-commandBuffer-cmdpool
         // VUID-vkCmdPushDescriptorSetKHR-pipelineBindPoint-00363
         match pipeline_bind_point {
-            PipelineBindPoint::Compute => {
+            PipelineBindPoint::Compute | PipelineBindPoint::RayTracing => {
                 if !queue_family_properties
                     .queue_flags
                     .intersects(QueueFlags::COMPUTE)
@@ -724,6 +850,22 @@ impl SyncCommandBufferBuilder {
         }
     }
 
+    /// Sets the buffers that are accessed through their device address by future commands that
+    /// use `pipeline_bind_point`.
+    ///
+    /// This doesn't correspond to any Vulkan command, it only records the buffers so that these
+    /// commands track them as resources.
+    #[inline]
+    pub unsafe fn bind_device_address_buffers(
+        &mut self,
+        pipeline_bind_point: PipelineBindPoint,
+        buffers: SmallVec<[Subbuffer<[u8]>; 4]>,
+    ) {
+        self.current_state
+            .device_address_buffers
+            .insert(pipeline_bind_point, buffers);
+    }
+
     /// Calls `vkCmdBindIndexBuffer` on the builder.
     #[inline]
     pub unsafe fn bind_index_buffer(&mut self, buffer: Subbuffer<[u8]>, index_type: IndexType) {
@@ -796,6 +938,27 @@ impl SyncCommandBufferBuilder {
         self.commands.push(Box::new(Cmd { pipeline }));
     }
 
+    /// Calls `vkCmdBindPipeline` on the builder with a ray tracing pipeline.
+    #[inline]
+    pub unsafe fn bind_pipeline_ray_tracing(&mut self, pipeline: Arc<RayTracingPipeline>) {
+        struct Cmd {
+            pipeline: Arc<RayTracingPipeline>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "bind_pipeline_ray_tracing"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.bind_pipeline_ray_tracing(&self.pipeline);
+            }
+        }
+
+        self.current_state.pipeline_ray_tracing = Some(pipeline.clone());
+        self.commands.push(Box::new(Cmd { pipeline }));
+    }
+
     /// Starts the process of binding vertex buffers. Returns an intermediate struct which can be
     /// used to add the buffers.
     #[inline]
@@ -1134,6 +1297,17 @@ impl UnsafeCommandBufferBuilder {
         );
     }
 
+    /// Calls `vkCmdBindPipeline` on the builder with a ray tracing pipeline.
+    #[inline]
+    pub unsafe fn bind_pipeline_ray_tracing(&mut self, pipeline: &RayTracingPipeline) {
+        let fns = self.device.fns();
+        (fns.v1_0.cmd_bind_pipeline)(
+            self.handle,
+            ash::vk::PipelineBindPoint::RAY_TRACING_KHR,
+            pipeline.handle(),
+        );
+    }
+
     /// Calls `vkCmdBindVertexBuffers` on the builder.
     ///
     /// Does nothing if the list of buffers is empty, as it would be a no-op and isn't a valid
@@ -1214,7 +1388,7 @@ impl UnsafeCommandBufferBuilder {
     ) {
         debug_assert!(self.device.enabled_extensions().khr_push_descriptor);
 
-        let (infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = descriptor_writes
+        let (mut infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = descriptor_writes
             .into_iter()
             .map(|write| {
                 let binding =
@@ -1232,7 +1406,7 @@ impl UnsafeCommandBufferBuilder {
         }
 
         // Set the info pointers separately.
-        for (info, write) in infos.iter().zip(writes.iter_mut()) {
+        for (info, write) in infos.iter_mut().zip(writes.iter_mut()) {
             match info {
                 DescriptorWriteInfo::Image(info) => {
                     write.descriptor_count = info.len() as u32;
@@ -1246,6 +1420,15 @@ impl UnsafeCommandBufferBuilder {
                     write.descriptor_count = info.len() as u32;
                     write.p_texel_buffer_view = info.as_ptr();
                 }
+                DescriptorWriteInfo::AccelerationStructure(info, info_vk) => {
+                    *info_vk = ash::vk::WriteDescriptorSetAccelerationStructureKHR {
+                        acceleration_structure_count: info.len() as u32,
+                        p_acceleration_structures: info.as_ptr(),
+                        ..Default::default()
+                    };
+                    write.descriptor_count = info.len() as u32;
+                    write.p_next = info_vk as *const _ as *const _;
+                }
             }
 
             debug_assert!(write.descriptor_count != 0);
@@ -1356,6 +1539,9 @@ pub(in super::super) enum BindPushError {
         buffer_size: DeviceSize,
     },
 
+    /// A device address buffer is missing the `shader_device_address` usage.
+    DeviceAddressBufferMissingUsage,
+
     /// An index buffer is missing the `index_buffer` usage.
     IndexBufferMissingUsage,
 
@@ -1368,6 +1554,9 @@ pub(in super::super) enum BindPushError {
     /// The queue family doesn't allow this operation.
     NotSupportedByQueueFamily,
 
+    /// The pipeline being bound is a pipeline library.
+    PipelineIsLibrary,
+
     /// The newly set pipeline has color attachment formats that do not match the
     /// previously used pipeline.
     PreviousPipelineColorAttachmentFormatMismatch,
@@ -1392,6 +1581,9 @@ pub(in super::super) enum BindPushError {
     /// The push constants size is not a multiple of 4.
     PushConstantsSizeNotAligned,
 
+    /// Transform feedback is active, which is not allowed for this operation.
+    TransformFeedbackActive,
+
     /// A vertex buffer is missing the `vertex_buffer` usage.
     VertexBufferMissingUsage,
 }
@@ -1477,6 +1669,10 @@ impl Display for BindPushError {
                 bound to the descriptor set ({}), is greater than the size of the buffer ({})",
                 set_num, binding_num, index, offset, range_end, buffer_size,
             ),
+            Self::DeviceAddressBufferMissingUsage => write!(
+                f,
+                "a device address buffer is missing the `shader_device_address` usage",
+            ),
             Self::IndexBufferMissingUsage => {
                 write!(f, "an index buffer is missing the `index_buffer` usage")
             }
@@ -1486,6 +1682,7 @@ impl Display for BindPushError {
             Self::NotSupportedByQueueFamily => {
                 write!(f, "the queue family doesn't allow this operation")
             }
+            Self::PipelineIsLibrary => write!(f, "the pipeline being bound is a pipeline library"),
             Self::PreviousPipelineColorAttachmentFormatMismatch => write!(
                 f,
                 "the newly set pipeline has color attachment formats that do not match the \
@@ -1513,6 +1710,10 @@ impl Display for BindPushError {
             Self::PushConstantsSizeNotAligned => {
                 write!(f, "the push constants size is not a multiple of 4")
             }
+            Self::TransformFeedbackActive => write!(
+                f,
+                "transform feedback is active, which is not allowed for this operation",
+            ),
             Self::VertexBufferMissingUsage => {
                 write!(f, "a vertex buffer is missing the `vertex_buffer` usage")
             }
@@ -0,0 +1,745 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use crate::{
+    buffer::{BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::CommandBufferAllocator,
+        synced::{
+            Command, Resource, SetOrPush, SyncCommandBufferBuilder, SyncCommandBufferBuilderError,
+        },
+        sys::UnsafeCommandBufferBuilder,
+        AutoCommandBufferBuilder, ResourceInCommand, ResourceUseRef,
+    },
+    descriptor_set::layout::DescriptorType,
+    device::{DeviceOwned, QueueFlags},
+    pipeline::{PipelineBindPoint, PipelineLayout},
+    sync::{AccessFlags, PipelineMemoryAccess, PipelineStages},
+    DeviceSize, RequirementNotMet, RequiresOneOf, VulkanObject,
+};
+use smallvec::SmallVec;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+/// # Commands to bind descriptor buffers.
+///
+/// These commands require the [`descriptor_buffer`](crate::device::Features::descriptor_buffer)
+/// feature to be enabled on the device, and a graphics or compute queue.
+///
+/// See the [`descriptor_buffer`](crate::descriptor_set::descriptor_buffer) module for how to write
+/// descriptors into a buffer.
+impl<L, A> AutoCommandBufferBuilder<L, A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Binds descriptor buffers, replacing all previously bound descriptor buffers.
+    ///
+    /// The buffers can then be referred to by their index in `buffers` when calling
+    /// [`set_descriptor_buffer_offsets`](Self::set_descriptor_buffer_offsets). Each buffer must
+    /// have the [`SHADER_DEVICE_ADDRESS`](BufferUsage::SHADER_DEVICE_ADDRESS) usage, and at least
+    /// one of the [`SAMPLER_DESCRIPTOR_BUFFER`](BufferUsage::SAMPLER_DESCRIPTOR_BUFFER),
+    /// [`RESOURCE_DESCRIPTOR_BUFFER`](BufferUsage::RESOURCE_DESCRIPTOR_BUFFER) or
+    /// [`PUSH_DESCRIPTORS_DESCRIPTOR_BUFFER`](BufferUsage::PUSH_DESCRIPTORS_DESCRIPTOR_BUFFER)
+    /// usages. The device address of each buffer must be a multiple of the
+    /// [`descriptor_buffer_offset_alignment`](crate::device::Properties::descriptor_buffer_offset_alignment)
+    /// limit.
+    pub fn bind_descriptor_buffers(
+        &mut self,
+        buffers: Vec<Subbuffer<[u8]>>,
+    ) -> Result<&mut Self, DescriptorBufferError> {
+        self.validate_bind_descriptor_buffers(&buffers)?;
+
+        unsafe {
+            self.inner.bind_descriptor_buffers(buffers)?;
+        }
+
+        Ok(self)
+    }
+
+    fn validate_bind_descriptor_buffers(
+        &self,
+        buffers: &[Subbuffer<[u8]>],
+    ) -> Result<(), DescriptorBufferError> {
+        let device = self.device();
+
+        // VUID-vkCmdBindDescriptorBuffersEXT-None-08047
+        if !device.enabled_features().descriptor_buffer {
+            return Err(DescriptorBufferError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::bind_descriptor_buffers`",
+                requires_one_of: RequiresOneOf {
+                    features: &["descriptor_buffer"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdBindDescriptorBuffersEXT-commandBuffer-cmdpool
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::GRAPHICS | QueueFlags::COMPUTE)
+        {
+            return Err(DescriptorBufferError::NotSupportedByQueueFamily);
+        }
+
+        let properties = device.physical_device().properties();
+        let max_descriptor_buffer_bindings = properties.max_descriptor_buffer_bindings.unwrap_or(0);
+
+        // VUID-vkCmdBindDescriptorBuffersEXT-bufferCount-08051
+        if buffers.len() as u32 > max_descriptor_buffer_bindings {
+            return Err(DescriptorBufferError::MaxDescriptorBufferBindingsExceeded {
+                buffer_count: buffers.len() as u32,
+                max: max_descriptor_buffer_bindings,
+            });
+        }
+
+        let count_with_usage = |usage: BufferUsage| {
+            buffers
+                .iter()
+                .filter(|buffer| buffer.buffer().usage().intersects(usage))
+                .count() as u32
+        };
+
+        let max_sampler_descriptor_buffer_bindings = properties
+            .max_sampler_descriptor_buffer_bindings
+            .unwrap_or(0);
+        let sampler_buffer_count = count_with_usage(BufferUsage::SAMPLER_DESCRIPTOR_BUFFER);
+
+        // VUID-vkCmdBindDescriptorBuffersEXT-maxSamplerDescriptorBufferBindings-08048
+        if sampler_buffer_count > max_sampler_descriptor_buffer_bindings {
+            return Err(
+                DescriptorBufferError::MaxSamplerDescriptorBufferBindingsExceeded {
+                    buffer_count: sampler_buffer_count,
+                    max: max_sampler_descriptor_buffer_bindings,
+                },
+            );
+        }
+
+        let max_resource_descriptor_buffer_bindings = properties
+            .max_resource_descriptor_buffer_bindings
+            .unwrap_or(0);
+        let resource_buffer_count = count_with_usage(BufferUsage::RESOURCE_DESCRIPTOR_BUFFER);
+
+        // VUID-vkCmdBindDescriptorBuffersEXT-maxResourceDescriptorBufferBindings-08049
+        if resource_buffer_count > max_resource_descriptor_buffer_bindings {
+            return Err(
+                DescriptorBufferError::MaxResourceDescriptorBufferBindingsExceeded {
+                    buffer_count: resource_buffer_count,
+                    max: max_resource_descriptor_buffer_bindings,
+                },
+            );
+        }
+
+        // VUID-vkCmdBindDescriptorBuffersEXT-None-08050
+        if count_with_usage(BufferUsage::PUSH_DESCRIPTORS_DESCRIPTOR_BUFFER) > 1 {
+            return Err(DescriptorBufferError::PushDescriptorsBufferMultiple);
+        }
+
+        let alignment = properties.descriptor_buffer_offset_alignment.unwrap_or(1);
+
+        for (index, buffer) in (0..).zip(buffers) {
+            // VUID-vkCmdBindDescriptorBuffersEXT-commonparent
+            assert_eq!(device, buffer.device());
+
+            let usage = buffer.buffer().usage();
+
+            // VUID?
+            if !usage.intersects(
+                BufferUsage::SAMPLER_DESCRIPTOR_BUFFER
+                    | BufferUsage::RESOURCE_DESCRIPTOR_BUFFER
+                    | BufferUsage::PUSH_DESCRIPTORS_DESCRIPTOR_BUFFER,
+            ) {
+                return Err(DescriptorBufferError::BufferMissingUsage {
+                    buffer_index: index,
+                });
+            }
+
+            // VUID?
+            let address = match buffer.device_address() {
+                Ok(address) => address.get(),
+                Err(_) => {
+                    return Err(DescriptorBufferError::BufferMissingUsage {
+                        buffer_index: index,
+                    })
+                }
+            };
+
+            // VUID-VkDescriptorBufferBindingInfoEXT-address-08057
+            if address % alignment != 0 {
+                return Err(DescriptorBufferError::BufferAddressNotAligned {
+                    buffer_index: index,
+                    address,
+                    required_alignment: alignment,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets descriptor sets for future dispatch or draw calls, by pointing them at offsets in the
+    /// bound descriptor buffers.
+    ///
+    /// Each element of `buffer_indices_and_offsets` is the index of a buffer that was bound with
+    /// [`bind_descriptor_buffers`](Self::bind_descriptor_buffers), and the byte offset into that
+    /// buffer where the descriptor set is stored, for the set numbers starting at `first_set`.
+    /// The offsets must be multiples of the
+    /// [`descriptor_buffer_offset_alignment`](crate::device::Properties::descriptor_buffer_offset_alignment)
+    /// limit, and the descriptor set layouts in `pipeline_layout` must have been created with
+    /// [`descriptor_buffer`] enabled.
+    ///
+    /// # Safety
+    ///
+    /// Vulkano can not see the contents of descriptor buffers, so the resources that they refer
+    /// to are not validated, kept alive or synchronized.
+    ///
+    /// - The descriptor data at each offset must have been written for the corresponding set
+    ///   layout of `pipeline_layout`, for example with [`DescriptorBufferSet`].
+    /// - All resources referred to by descriptors that are accessed by later commands must be
+    ///   kept alive until the command buffer has finished executing, must be in the image layouts
+    ///   that the descriptors were written for, and accesses to them must be synchronized
+    ///   manually.
+    /// - The descriptor data must not be modified while the command buffer is executing.
+    ///
+    /// [`descriptor_buffer`]: crate::descriptor_set::layout::DescriptorSetLayoutCreateInfo::descriptor_buffer
+    /// [`DescriptorBufferSet`]: crate::descriptor_set::descriptor_buffer::DescriptorBufferSet
+    pub unsafe fn set_descriptor_buffer_offsets(
+        &mut self,
+        pipeline_bind_point: PipelineBindPoint,
+        pipeline_layout: Arc<PipelineLayout>,
+        first_set: u32,
+        buffer_indices_and_offsets: Vec<(u32, DeviceSize)>,
+    ) -> Result<&mut Self, DescriptorBufferError> {
+        self.validate_set_descriptor_buffer_offsets(
+            pipeline_bind_point,
+            &pipeline_layout,
+            first_set,
+            &buffer_indices_and_offsets,
+        )?;
+
+        self.inner.set_descriptor_buffer_offsets(
+            pipeline_bind_point,
+            pipeline_layout,
+            first_set,
+            buffer_indices_and_offsets,
+        );
+
+        Ok(self)
+    }
+
+    fn validate_set_descriptor_buffer_offsets(
+        &self,
+        pipeline_bind_point: PipelineBindPoint,
+        pipeline_layout: &PipelineLayout,
+        first_set: u32,
+        buffer_indices_and_offsets: &[(u32, DeviceSize)],
+    ) -> Result<(), DescriptorBufferError> {
+        let device = self.device();
+
+        // VUID-vkCmdSetDescriptorBufferOffsetsEXT-None-08060
+        if !device.enabled_features().descriptor_buffer {
+            return Err(DescriptorBufferError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::set_descriptor_buffer_offsets`",
+                requires_one_of: RequiresOneOf {
+                    features: &["descriptor_buffer"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdSetDescriptorBufferOffsetsEXT-pipelineBindPoint-parameter
+        pipeline_bind_point.validate_device(device)?;
+
+        // VUID-vkCmdSetDescriptorBufferOffsetsEXT-commonparent
+        assert_eq!(device, pipeline_layout.device());
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdSetDescriptorBufferOffsetsEXT-commandBuffer-cmdpool
+        let required_queue_flags = match pipeline_bind_point {
+            PipelineBindPoint::Compute | PipelineBindPoint::RayTracing => QueueFlags::COMPUTE,
+            PipelineBindPoint::Graphics => QueueFlags::GRAPHICS,
+        };
+
+        if !queue_family_properties
+            .queue_flags
+            .intersects(required_queue_flags)
+        {
+            return Err(DescriptorBufferError::NotSupportedByQueueFamily);
+        }
+
+        let set_layouts = pipeline_layout.set_layouts();
+
+        // VUID-vkCmdSetDescriptorBufferOffsetsEXT-firstSet-08066
+        if first_set + buffer_indices_and_offsets.len() as u32 > set_layouts.len() as u32 {
+            return Err(DescriptorBufferError::DescriptorSetOutOfRange {
+                set_num: first_set + buffer_indices_and_offsets.len() as u32,
+                pipeline_layout_set_count: set_layouts.len() as u32,
+            });
+        }
+
+        let descriptor_buffers = self.state().descriptor_buffers();
+        let alignment = device
+            .physical_device()
+            .properties()
+            .descriptor_buffer_offset_alignment
+            .unwrap_or(1);
+
+        for (set_num, &(buffer_index, offset)) in (first_set..).zip(buffer_indices_and_offsets) {
+            let set_layout = &set_layouts[set_num as usize];
+
+            // VUID?
+            if !set_layout.descriptor_buffer() || set_layout.push_descriptor() {
+                return Err(DescriptorBufferError::SetLayoutNotDescriptorBuffer { set_num });
+            }
+
+            // VUID-vkCmdSetDescriptorBufferOffsetsEXT-pBufferIndices-08064
+            let buffer = match descriptor_buffers.get(buffer_index as usize) {
+                Some(x) => x,
+                None => {
+                    return Err(DescriptorBufferError::BufferIndexOutOfRange {
+                        set_num,
+                        buffer_index,
+                        buffer_count: descriptor_buffers.len() as u32,
+                    })
+                }
+            };
+
+            // VUID?
+            let mut required_usage = BufferUsage::empty();
+
+            for binding in set_layout.bindings().values() {
+                required_usage |= match binding.descriptor_type {
+                    DescriptorType::Sampler => BufferUsage::SAMPLER_DESCRIPTOR_BUFFER,
+                    DescriptorType::CombinedImageSampler => {
+                        BufferUsage::SAMPLER_DESCRIPTOR_BUFFER
+                            | BufferUsage::RESOURCE_DESCRIPTOR_BUFFER
+                    }
+                    _ => BufferUsage::RESOURCE_DESCRIPTOR_BUFFER,
+                };
+            }
+
+            if !buffer.buffer().usage().contains(required_usage) {
+                return Err(DescriptorBufferError::BufferMissingUsage { buffer_index });
+            }
+
+            // VUID-vkCmdSetDescriptorBufferOffsetsEXT-pOffsets-08061
+            if offset % alignment != 0 {
+                return Err(DescriptorBufferError::OffsetNotAligned {
+                    set_num,
+                    offset,
+                    required_alignment: alignment,
+                });
+            }
+
+            let set_size = set_layout.descriptor_buffer_size().unwrap();
+
+            // VUID-vkCmdSetDescriptorBufferOffsetsEXT-pOffsets-08063
+            if offset + set_size > buffer.size() {
+                return Err(DescriptorBufferError::OffsetOutOfBufferBounds {
+                    set_num,
+                    offset,
+                    set_size,
+                    buffer_size: buffer.size(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SyncCommandBufferBuilder {
+    /// Calls `vkCmdBindDescriptorBuffersEXT` on the builder.
+    #[inline]
+    pub unsafe fn bind_descriptor_buffers(
+        &mut self,
+        buffers: Vec<Subbuffer<[u8]>>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            buffers: Vec<Subbuffer<[u8]>>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "bind_descriptor_buffers"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.bind_descriptor_buffers(&self.buffers);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "bind_descriptor_buffers";
+
+        // The descriptor data is read by the commands that use the descriptor sets, which must all
+        // come after this command. Tracking the read here makes sure that the descriptor buffers
+        // are not written while they are in use.
+        let resources: SmallVec<[_; 4]> = (0..)
+            .zip(&buffers)
+            .map(|(index, buffer)| {
+                (
+                    ResourceUseRef {
+                        command_index,
+                        command_name,
+                        resource_in_command: ResourceInCommand::DescriptorBuffer { index },
+                        secondary_use_ref: None,
+                    },
+                    Resource::Buffer {
+                        buffer: buffer.clone(),
+                        range: 0..buffer.size(),
+                        memory: PipelineMemoryAccess {
+                            stages: PipelineStages::ALL_COMMANDS,
+                            access: AccessFlags::SHADER_READ,
+                            exclusive: false,
+                        },
+                    },
+                )
+            })
+            .collect();
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.current_state.descriptor_buffers = buffers.iter().cloned().collect();
+        self.commands.push(Box::new(Cmd { buffers }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdSetDescriptorBufferOffsetsEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_descriptor_buffer_offsets(
+        &mut self,
+        pipeline_bind_point: PipelineBindPoint,
+        pipeline_layout: Arc<PipelineLayout>,
+        first_set: u32,
+        buffer_indices_and_offsets: Vec<(u32, DeviceSize)>,
+    ) {
+        struct Cmd {
+            pipeline_bind_point: PipelineBindPoint,
+            pipeline_layout: Arc<PipelineLayout>,
+            first_set: u32,
+            buffer_indices_and_offsets: Vec<(u32, DeviceSize)>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "set_descriptor_buffer_offsets"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.set_descriptor_buffer_offsets(
+                    self.pipeline_bind_point,
+                    &self.pipeline_layout,
+                    self.first_set,
+                    &self.buffer_indices_and_offsets,
+                );
+            }
+        }
+
+        let state = self.current_state.invalidate_descriptor_sets(
+            pipeline_bind_point,
+            pipeline_layout.clone(),
+            first_set,
+            buffer_indices_and_offsets.len() as u32,
+        );
+
+        for (set_num, &(buffer_index, offset)) in (first_set..).zip(&buffer_indices_and_offsets) {
+            state.descriptor_sets.insert(
+                set_num,
+                SetOrPush::DescriptorBuffer {
+                    buffer_index,
+                    offset,
+                },
+            );
+        }
+
+        self.commands.push(Box::new(Cmd {
+            pipeline_bind_point,
+            pipeline_layout,
+            first_set,
+            buffer_indices_and_offsets,
+        }));
+    }
+}
+
+impl UnsafeCommandBufferBuilder {
+    /// Calls `vkCmdBindDescriptorBuffersEXT` on the builder.
+    #[inline]
+    pub unsafe fn bind_descriptor_buffers(&mut self, buffers: &[Subbuffer<[u8]>]) {
+        let binding_infos_vk: SmallVec<[_; 4]> = buffers
+            .iter()
+            .map(|buffer| ash::vk::DescriptorBufferBindingInfoEXT {
+                address: buffer.device_address().unwrap().get(),
+                usage: buffer.buffer().usage().into(),
+                ..Default::default()
+            })
+            .collect();
+
+        let fns = self.device.fns();
+        (fns.ext_descriptor_buffer.cmd_bind_descriptor_buffers_ext)(
+            self.handle,
+            binding_infos_vk.len() as u32,
+            binding_infos_vk.as_ptr(),
+        );
+    }
+
+    /// Calls `vkCmdSetDescriptorBufferOffsetsEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_descriptor_buffer_offsets(
+        &mut self,
+        pipeline_bind_point: PipelineBindPoint,
+        pipeline_layout: &PipelineLayout,
+        first_set: u32,
+        buffer_indices_and_offsets: &[(u32, DeviceSize)],
+    ) {
+        if buffer_indices_and_offsets.is_empty() {
+            return;
+        }
+
+        let (buffer_indices_vk, offsets_vk): (SmallVec<[_; 4]>, SmallVec<[_; 4]>) =
+            buffer_indices_and_offsets.iter().copied().unzip();
+
+        let fns = self.device.fns();
+        (fns.ext_descriptor_buffer
+            .cmd_set_descriptor_buffer_offsets_ext)(
+            self.handle,
+            pipeline_bind_point.into(),
+            pipeline_layout.handle(),
+            first_set,
+            buffer_indices_vk.len() as u32,
+            buffer_indices_vk.as_ptr(),
+            offsets_vk.as_ptr(),
+        );
+    }
+}
+
+/// Error that can happen when recording a descriptor buffer command.
+#[derive(Clone, Debug)]
+pub enum DescriptorBufferError {
+    SyncCommandBufferBuilderError(SyncCommandBufferBuilderError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// The device address of a descriptor buffer is not a multiple of the
+    /// `descriptor_buffer_offset_alignment` limit.
+    BufferAddressNotAligned {
+        buffer_index: u32,
+        address: DeviceSize,
+        required_alignment: DeviceSize,
+    },
+
+    /// A buffer index refers to a descriptor buffer that is not bound.
+    BufferIndexOutOfRange {
+        set_num: u32,
+        buffer_index: u32,
+        buffer_count: u32,
+    },
+
+    /// A descriptor buffer is missing a usage that is required for this operation.
+    BufferMissingUsage {
+        buffer_index: u32,
+    },
+
+    /// The highest descriptor set slot being set is greater than the number of sets in the
+    /// pipeline layout.
+    DescriptorSetOutOfRange {
+        set_num: u32,
+        pipeline_layout_set_count: u32,
+    },
+
+    /// More descriptor buffers were provided than the `max_descriptor_buffer_bindings` limit.
+    MaxDescriptorBufferBindingsExceeded {
+        buffer_count: u32,
+        max: u32,
+    },
+
+    /// More descriptor buffers with the `resource_descriptor_buffer` usage were provided than the
+    /// `max_resource_descriptor_buffer_bindings` limit.
+    MaxResourceDescriptorBufferBindingsExceeded {
+        buffer_count: u32,
+        max: u32,
+    },
+
+    /// More descriptor buffers with the `sampler_descriptor_buffer` usage were provided than the
+    /// `max_sampler_descriptor_buffer_bindings` limit.
+    MaxSamplerDescriptorBufferBindingsExceeded {
+        buffer_count: u32,
+        max: u32,
+    },
+
+    /// The queue family doesn't allow this operation.
+    NotSupportedByQueueFamily,
+
+    /// An offset is not a multiple of the `descriptor_buffer_offset_alignment` limit.
+    OffsetNotAligned {
+        set_num: u32,
+        offset: DeviceSize,
+        required_alignment: DeviceSize,
+    },
+
+    /// A descriptor set at the given offset would extend beyond the end of the descriptor
+    /// buffer.
+    OffsetOutOfBufferBounds {
+        set_num: u32,
+        offset: DeviceSize,
+        set_size: DeviceSize,
+        buffer_size: DeviceSize,
+    },
+
+    /// More than one descriptor buffer with the `push_descriptors_descriptor_buffer` usage was
+    /// provided.
+    PushDescriptorsBufferMultiple,
+
+    /// The descriptor set layout of a set number was not created with `descriptor_buffer`
+    /// enabled, or was created for push descriptors.
+    SetLayoutNotDescriptorBuffer {
+        set_num: u32,
+    },
+}
+
+impl Error for DescriptorBufferError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SyncCommandBufferBuilderError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DescriptorBufferError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::SyncCommandBufferBuilderError(_) => write!(f, "a SyncCommandBufferBuilderError"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::BufferAddressNotAligned {
+                buffer_index,
+                address,
+                required_alignment,
+            } => write!(
+                f,
+                "the device address ({}) of descriptor buffer {} is not a multiple of the \
+                `descriptor_buffer_offset_alignment` limit ({})",
+                address, buffer_index, required_alignment,
+            ),
+            Self::BufferIndexOutOfRange {
+                set_num,
+                buffer_index,
+                buffer_count,
+            } => write!(
+                f,
+                "the buffer index ({}) for set {} is not less than the number of bound descriptor \
+                buffers ({})",
+                buffer_index, set_num, buffer_count,
+            ),
+            Self::BufferMissingUsage { buffer_index } => write!(
+                f,
+                "descriptor buffer {} is missing a usage that is required for this operation",
+                buffer_index,
+            ),
+            Self::DescriptorSetOutOfRange {
+                set_num,
+                pipeline_layout_set_count,
+            } => write!(
+                f,
+                "the highest descriptor set slot being set ({}) is greater than the number of \
+                sets in the pipeline layout ({})",
+                set_num, pipeline_layout_set_count,
+            ),
+            Self::MaxDescriptorBufferBindingsExceeded { buffer_count, max } => write!(
+                f,
+                "the number of descriptor buffers ({}) exceeds the \
+                `max_descriptor_buffer_bindings` limit ({})",
+                buffer_count, max,
+            ),
+            Self::MaxResourceDescriptorBufferBindingsExceeded { buffer_count, max } => write!(
+                f,
+                "the number of resource descriptor buffers ({}) exceeds the \
+                `max_resource_descriptor_buffer_bindings` limit ({})",
+                buffer_count, max,
+            ),
+            Self::MaxSamplerDescriptorBufferBindingsExceeded { buffer_count, max } => write!(
+                f,
+                "the number of sampler descriptor buffers ({}) exceeds the \
+                `max_sampler_descriptor_buffer_bindings` limit ({})",
+                buffer_count, max,
+            ),
+            Self::NotSupportedByQueueFamily => {
+                write!(f, "the queue family doesn't allow this operation")
+            }
+            Self::OffsetNotAligned {
+                set_num,
+                offset,
+                required_alignment,
+            } => write!(
+                f,
+                "the offset ({}) for set {} is not a multiple of the \
+                `descriptor_buffer_offset_alignment` limit ({})",
+                offset, set_num, required_alignment,
+            ),
+            Self::OffsetOutOfBufferBounds {
+                set_num,
+                offset,
+                set_size,
+                buffer_size,
+            } => write!(
+                f,
+                "the descriptor set {} at offset {} with size {} extends beyond the end of the \
+                descriptor buffer ({})",
+                set_num, offset, set_size, buffer_size,
+            ),
+            Self::PushDescriptorsBufferMultiple => write!(
+                f,
+                "more than one descriptor buffer with the `push_descriptors_descriptor_buffer` \
+                usage was provided",
+            ),
+            Self::SetLayoutNotDescriptorBuffer { set_num } => write!(
+                f,
+                "the descriptor set layout of set {} was not created with `descriptor_buffer` \
+                enabled, or was created for push descriptors",
+                set_num,
+            ),
+        }
+    }
+}
+
+impl From<SyncCommandBufferBuilderError> for DescriptorBufferError {
+    fn from(err: SyncCommandBufferBuilderError) -> Self {
+        Self::SyncCommandBufferBuilderError(err)
+    }
+}
+
+impl From<RequirementNotMet> for DescriptorBufferError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
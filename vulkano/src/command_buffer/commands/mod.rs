@@ -7,13 +7,16 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+pub(super) mod acceleration_structure;
 pub(super) mod bind_push;
 pub(super) mod clear;
 pub(super) mod copy;
 pub(super) mod debug;
+pub(super) mod descriptor_buffer;
 pub(super) mod dynamic_state;
 pub(super) mod pipeline;
 pub(super) mod query;
 pub(super) mod render_pass;
 pub(super) mod secondary;
 pub(super) mod sync;
+pub(super) mod transform_feedback;
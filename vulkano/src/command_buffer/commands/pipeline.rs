@@ -15,7 +15,8 @@ use crate::{
         synced::{Command, Resource, SyncCommandBufferBuilder, SyncCommandBufferBuilderError},
         sys::UnsafeCommandBufferBuilder,
         AutoCommandBufferBuilder, DispatchIndirectCommand, DrawIndexedIndirectCommand,
-        DrawIndirectCommand, ResourceInCommand, ResourceUseRef, SubpassContents,
+        DrawIndirectCommand, DrawMeshTasksIndirectCommand, ResourceInCommand, ResourceUseRef,
+        SubpassContents, TraceRaysIndirectCommand,
     },
     descriptor_set::{layout::DescriptorType, DescriptorBindingResources},
     device::{DeviceOwned, QueueFlags},
@@ -30,7 +31,9 @@ use crate::{
             subpass::PipelineSubpassType,
             vertex_input::VertexInputRate,
         },
-        DynamicState, GraphicsPipeline, PartialStateMode, Pipeline, PipelineLayout,
+        ray_tracing::{ShaderBindingTable, ShaderBindingTableAddresses},
+        DynamicState, GraphicsPipeline, PartialStateMode, Pipeline, PipelineBindPoint,
+        PipelineLayout,
     },
     sampler::{Sampler, SamplerImageViewIncompatibleError},
     shader::{DescriptorBindingRequirements, ShaderScalarType, ShaderStage},
@@ -48,7 +51,8 @@ use std::{
 
 /// # Commands to execute a bound pipeline.
 ///
-/// Dispatch commands require a compute queue, draw commands require a graphics queue.
+/// Dispatch and ray tracing commands require a compute queue, draw commands require a graphics
+/// queue.
 impl<L, A> AutoCommandBufferBuilder<L, A>
 where
     A: CommandBufferAllocator,
@@ -166,6 +170,185 @@ where
         Ok(())
     }
 
+    /// Traces rays using a ray tracing pipeline.
+    ///
+    /// `dimensions` is the number of ray generation shader invocations to launch, in the width,
+    /// height and depth dimension.
+    ///
+    /// A ray tracing pipeline must have been bound using
+    /// [`bind_pipeline_ray_tracing`](Self::bind_pipeline_ray_tracing). Any resources used by the
+    /// ray tracing pipeline, such as descriptor sets, must have been set beforehand.
+    ///
+    /// `shader_binding_table` must have been created for the bound pipeline, or for a pipeline
+    /// with identical shader groups.
+    pub fn trace_rays(
+        &mut self,
+        shader_binding_table: ShaderBindingTable,
+        dimensions: [u32; 3],
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        self.validate_trace_rays(&shader_binding_table, dimensions)?;
+
+        unsafe {
+            self.inner.trace_rays(shader_binding_table, dimensions)?;
+        }
+
+        Ok(self)
+    }
+
+    fn validate_trace_rays(
+        &self,
+        shader_binding_table: &ShaderBindingTable,
+        dimensions: [u32; 3],
+    ) -> Result<(), PipelineExecutionError> {
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdTraceRaysKHR-commandBuffer-cmdpool
+        if !queue_family_properties
+            .queue_flags
+            .intersects(QueueFlags::COMPUTE)
+        {
+            return Err(PipelineExecutionError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdTraceRaysKHR-renderpass
+        if self.render_pass_state.is_some() {
+            return Err(PipelineExecutionError::ForbiddenInsideRenderPass);
+        }
+
+        // VUID-vkCmdTraceRaysKHR-None-02700
+        let pipeline = match self.state().pipeline_ray_tracing() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
+
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+
+        // VUID-vkCmdTraceRaysKHR-commonparent
+        assert_eq!(self.device(), shader_binding_table.buffer().device());
+
+        let properties = self.device().physical_device().properties();
+        let max = [0, 1, 2].map(|i| {
+            properties.max_compute_work_group_count[i] as u64
+                * properties.max_compute_work_group_size[i] as u64
+        });
+
+        // VUID-vkCmdTraceRaysKHR-width-03638
+        // VUID-vkCmdTraceRaysKHR-height-03639
+        // VUID-vkCmdTraceRaysKHR-depth-03640
+        if dimensions[0] as u64 > max[0]
+            || dimensions[1] as u64 > max[1]
+            || dimensions[2] as u64 > max[2]
+        {
+            return Err(PipelineExecutionError::RayDispatchDimensionsExceeded {
+                requested: dimensions,
+                max,
+            });
+        }
+
+        // VUID-vkCmdTraceRaysKHR-width-03641
+        let invocation_count = dimensions.iter().map(|&x| x as u64).product::<u64>();
+        let max_ray_dispatch_invocation_count =
+            properties.max_ray_dispatch_invocation_count.unwrap_or(0);
+
+        if invocation_count > max_ray_dispatch_invocation_count as u64 {
+            return Err(
+                PipelineExecutionError::MaxRayDispatchInvocationCountExceeded {
+                    provided: invocation_count,
+                    max: max_ray_dispatch_invocation_count,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Traces rays using a ray tracing pipeline, reading the dimensions from a buffer.
+    ///
+    /// A ray tracing pipeline must have been bound using
+    /// [`bind_pipeline_ray_tracing`](Self::bind_pipeline_ray_tracing). Any resources used by the
+    /// ray tracing pipeline, such as descriptor sets, must have been set beforehand.
+    ///
+    /// `indirect_buffer` must have both the
+    /// [`INDIRECT_BUFFER`](crate::buffer::BufferUsage::INDIRECT_BUFFER) and the
+    /// [`SHADER_DEVICE_ADDRESS`](crate::buffer::BufferUsage::SHADER_DEVICE_ADDRESS) usage.
+    pub fn trace_rays_indirect(
+        &mut self,
+        shader_binding_table: ShaderBindingTable,
+        indirect_buffer: Subbuffer<TraceRaysIndirectCommand>,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        self.validate_trace_rays_indirect(&shader_binding_table, indirect_buffer.as_bytes())?;
+
+        unsafe {
+            self.inner
+                .trace_rays_indirect(shader_binding_table, indirect_buffer)?;
+        }
+
+        Ok(self)
+    }
+
+    fn validate_trace_rays_indirect(
+        &self,
+        shader_binding_table: &ShaderBindingTable,
+        indirect_buffer: &Subbuffer<[u8]>,
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID-vkCmdTraceRaysIndirectKHR-rayTracingPipelineTraceRaysIndirect-03637
+        if !self
+            .device()
+            .enabled_features()
+            .ray_tracing_pipeline_trace_rays_indirect
+        {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::trace_rays_indirect`",
+                requires_one_of: RequiresOneOf {
+                    features: &["ray_tracing_pipeline_trace_rays_indirect"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdTraceRaysIndirectKHR-commandBuffer-cmdpool
+        if !queue_family_properties
+            .queue_flags
+            .intersects(QueueFlags::COMPUTE)
+        {
+            return Err(PipelineExecutionError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdTraceRaysIndirectKHR-renderpass
+        if self.render_pass_state.is_some() {
+            return Err(PipelineExecutionError::ForbiddenInsideRenderPass);
+        }
+
+        // VUID-vkCmdTraceRaysIndirectKHR-None-02700
+        let pipeline = match self.state().pipeline_ray_tracing() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
+
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+
+        // VUID-vkCmdTraceRaysIndirectKHR-commonparent
+        assert_eq!(self.device(), shader_binding_table.buffer().device());
+
+        // VUID-vkCmdTraceRaysIndirectKHR-indirectDeviceAddress-03633
+        self.validate_indirect_buffer(indirect_buffer)?;
+
+        // The command takes the device address of the buffer.
+        if !indirect_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::SHADER_DEVICE_ADDRESS)
+        {
+            return Err(PipelineExecutionError::IndirectBufferMissingShaderDeviceAddressUsage);
+        }
+
+        Ok(())
+    }
+
     /// Perform a single draw operation using a graphics pipeline.
     ///
     /// The parameters specify the first vertex and the number of vertices to draw, and the first
@@ -219,6 +402,11 @@ where
             None => return Err(PipelineExecutionError::PipelineNotBound),
         };
 
+        // VUID-vkCmdDraw-stage-06481
+        if pipeline.shader(ShaderStage::Mesh).is_some() {
+            return Err(PipelineExecutionError::PipelineMeshShaderNotAllowed);
+        }
+
         self.validate_pipeline_descriptor_sets(pipeline)?;
         self.validate_pipeline_push_constants(pipeline.layout())?;
         self.validate_pipeline_graphics_dynamic_state(pipeline)?;
@@ -287,6 +475,11 @@ where
             None => return Err(PipelineExecutionError::PipelineNotBound),
         };
 
+        // VUID-vkCmdDrawIndirect-stage-06481
+        if pipeline.shader(ShaderStage::Mesh).is_some() {
+            return Err(PipelineExecutionError::PipelineMeshShaderNotAllowed);
+        }
+
         self.validate_pipeline_descriptor_sets(pipeline)?;
         self.validate_pipeline_push_constants(pipeline.layout())?;
         self.validate_pipeline_graphics_dynamic_state(pipeline)?;
@@ -398,6 +591,11 @@ where
             None => return Err(PipelineExecutionError::PipelineNotBound),
         };
 
+        // VUID-vkCmdDrawIndexed-stage-06481
+        if pipeline.shader(ShaderStage::Mesh).is_some() {
+            return Err(PipelineExecutionError::PipelineMeshShaderNotAllowed);
+        }
+
         self.validate_pipeline_descriptor_sets(pipeline)?;
         self.validate_pipeline_push_constants(pipeline.layout())?;
         self.validate_pipeline_graphics_dynamic_state(pipeline)?;
@@ -473,6 +671,11 @@ where
             None => return Err(PipelineExecutionError::PipelineNotBound),
         };
 
+        // VUID-vkCmdDrawIndexedIndirect-stage-06481
+        if pipeline.shader(ShaderStage::Mesh).is_some() {
+            return Err(PipelineExecutionError::PipelineMeshShaderNotAllowed);
+        }
+
         self.validate_pipeline_descriptor_sets(pipeline)?;
         self.validate_pipeline_push_constants(pipeline.layout())?;
         self.validate_pipeline_graphics_dynamic_state(pipeline)?;
@@ -510,507 +713,1252 @@ where
         Ok(())
     }
 
-    fn validate_index_buffer(
+    /// Perform multiple draw operations using a graphics pipeline, reading the number of draws
+    /// from a buffer.
+    ///
+    /// One draw is performed for each [`DrawIndirectCommand`] struct in `indirect_buffer`, up to
+    /// the number of draws that is read from `count_buffer` when the command is executed.
+    /// `max_draw_count` is an upper bound on the number of draws, and must not be greater than the
+    /// number of commands in `indirect_buffer`.
+    ///
+    /// The [`draw_indirect_count`](crate::device::Features::draw_indirect_count) feature or the
+    /// [`khr_draw_indirect_count`](crate::device::DeviceExtensions::khr_draw_indirect_count)
+    /// extension must be enabled on the device. `count_buffer` must have the
+    /// [`INDIRECT_BUFFER`](crate::buffer::BufferUsage::INDIRECT_BUFFER) usage, and its offset must
+    /// be a multiple of 4.
+    ///
+    /// A graphics pipeline must have been bound using
+    /// [`bind_pipeline_graphics`](Self::bind_pipeline_graphics). Any resources used by the graphics
+    /// pipeline, such as descriptor sets, vertex buffers and dynamic state, must have been set
+    /// beforehand. If the bound graphics pipeline uses vertex buffers, then the vertex and instance
+    /// ranges of each `DrawIndirectCommand` in the indirect buffer must be in range of the bound
+    /// vertex buffers.
+    pub fn draw_indirect_count(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        let stride = size_of::<DrawIndirectCommand>() as u32;
+        self.validate_draw_indirect_count(
+            indirect_buffer.as_bytes(),
+            count_buffer.as_bytes(),
+            max_draw_count,
+            stride,
+        )?;
+
+        unsafe {
+            self.inner.draw_indirect_count(
+                indirect_buffer,
+                count_buffer,
+                max_draw_count,
+                stride,
+            )?;
+        }
+
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.render_pass_state.as_mut().unwrap().render_pass
+        {
+            state.pipeline_used = true;
+        }
+
+        Ok(self)
+    }
+
+    fn validate_draw_indirect_count(
         &self,
-        indices: Option<(u32, u32)>,
+        indirect_buffer: &Subbuffer<[u8]>,
+        count_buffer: &Subbuffer<[u8]>,
+        max_draw_count: u32,
+        stride: u32,
     ) -> Result<(), PipelineExecutionError> {
-        let current_state = self.state();
+        // VUID-vkCmdDrawIndirectCount-None-04445
+        if !(self.device().enabled_features().draw_indirect_count
+            || self.device().enabled_extensions().khr_draw_indirect_count)
+        {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::draw_indirect_count`",
+                requires_one_of: RequiresOneOf {
+                    features: &["draw_indirect_count"],
+                    device_extensions: &["khr_draw_indirect_count"],
+                    ..Default::default()
+                },
+            });
+        }
 
-        // VUID?
-        let (index_buffer, index_type) = match current_state.index_buffer() {
-            Some(x) => x,
-            None => return Err(PipelineExecutionError::IndexBufferNotBound),
-        };
+        // VUID-vkCmdDrawIndirectCount-renderpass
+        let render_pass_state = self
+            .render_pass_state
+            .as_ref()
+            .ok_or(PipelineExecutionError::ForbiddenOutsideRenderPass)?;
 
-        if let Some((first_index, index_count)) = indices {
-            let max_index_count = (index_buffer.size() / index_type.size()) as u32;
+        // VUID-vkCmdDrawIndirectCount-None-02700
+        let pipeline = match self.state().pipeline_graphics() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
 
-            // // VUID-vkCmdDrawIndexed-firstIndex-04932
-            if first_index + index_count > max_index_count {
-                return Err(PipelineExecutionError::IndexBufferRangeOutOfBounds {
-                    highest_index: first_index + index_count,
-                    max_index_count,
-                });
-            }
+        // VUID-vkCmdDrawIndirectCount-stage-06481
+        if pipeline.shader(ShaderStage::Mesh).is_some() {
+            return Err(PipelineExecutionError::PipelineMeshShaderNotAllowed);
         }
 
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+        self.validate_pipeline_graphics_dynamic_state(pipeline)?;
+        self.validate_pipeline_graphics_render_pass(pipeline, render_pass_state)?;
+        self.validate_pipeline_graphics_vertex_buffers(pipeline, None, None)?;
+
+        self.validate_indirect_buffer(indirect_buffer)?;
+        self.validate_indirect_count_buffer(count_buffer)?;
+        self.validate_indirect_buffer_max_draw_count(indirect_buffer, max_draw_count, stride)?;
+
         Ok(())
     }
 
-    fn validate_indirect_buffer(
-        &self,
-        buffer: &Subbuffer<[u8]>,
-    ) -> Result<(), PipelineExecutionError> {
-        // VUID-vkCmdDispatchIndirect-commonparent
-        assert_eq!(self.device(), buffer.device());
+    /// Perform multiple draw operations using a graphics pipeline, using an index buffer, and
+    /// reading the number of draws from a buffer.
+    ///
+    /// One draw is performed for each [`DrawIndexedIndirectCommand`] struct in `indirect_buffer`,
+    /// up to the number of draws that is read from `count_buffer` when the command is executed.
+    /// `max_draw_count` is an upper bound on the number of draws, and must not be greater than the
+    /// number of commands in `indirect_buffer`.
+    ///
+    /// The [`draw_indirect_count`](crate::device::Features::draw_indirect_count) feature or the
+    /// [`khr_draw_indirect_count`](crate::device::DeviceExtensions::khr_draw_indirect_count)
+    /// extension must be enabled on the device. `count_buffer` must have the
+    /// [`INDIRECT_BUFFER`](crate::buffer::BufferUsage::INDIRECT_BUFFER) usage, and its offset must
+    /// be a multiple of 4.
+    ///
+    /// An index buffer must have been bound using
+    /// [`bind_index_buffer`](Self::bind_index_buffer), and the index ranges of each
+    /// `DrawIndexedIndirectCommand` in the indirect buffer must be in range of the bound index
+    /// buffer.
+    ///
+    /// A graphics pipeline must have been bound using
+    /// [`bind_pipeline_graphics`](Self::bind_pipeline_graphics). Any resources used by the graphics
+    /// pipeline, such as descriptor sets, vertex buffers and dynamic state, must have been set
+    /// beforehand. If the bound graphics pipeline uses vertex buffers, then the instance ranges of
+    /// each `DrawIndexedIndirectCommand` in the indirect buffer must be in range of the bound
+    /// vertex buffers.
+    pub fn draw_indexed_indirect_count(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawIndexedIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        let stride = size_of::<DrawIndexedIndirectCommand>() as u32;
+        self.validate_draw_indexed_indirect_count(
+            indirect_buffer.as_bytes(),
+            count_buffer.as_bytes(),
+            max_draw_count,
+            stride,
+        )?;
 
-        // VUID-vkCmdDispatchIndirect-buffer-02709
-        if !buffer
-            .buffer()
-            .usage()
-            .intersects(BufferUsage::INDIRECT_BUFFER)
-        {
-            return Err(PipelineExecutionError::IndirectBufferMissingUsage);
+        unsafe {
+            self.inner.draw_indexed_indirect_count(
+                indirect_buffer,
+                count_buffer,
+                max_draw_count,
+                stride,
+            )?;
         }
 
-        // VUID-vkCmdDispatchIndirect-offset-02710
-        // TODO:
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.render_pass_state.as_mut().unwrap().render_pass
+        {
+            state.pipeline_used = true;
+        }
 
-        Ok(())
+        Ok(self)
     }
 
-    fn validate_pipeline_descriptor_sets<Pl: Pipeline>(
+    fn validate_draw_indexed_indirect_count(
         &self,
-        pipeline: &Pl,
+        indirect_buffer: &Subbuffer<[u8]>,
+        count_buffer: &Subbuffer<[u8]>,
+        max_draw_count: u32,
+        stride: u32,
     ) -> Result<(), PipelineExecutionError> {
-        fn validate_resources<T>(
-            set_num: u32,
-            binding_num: u32,
-            binding_reqs: &DescriptorBindingRequirements,
-            elements: &[Option<T>],
-            mut extra_check: impl FnMut(u32, &T) -> Result<(), DescriptorResourceInvalidError>,
-        ) -> Result<(), PipelineExecutionError> {
-            let elements_to_check = if let Some(descriptor_count) = binding_reqs.descriptor_count {
-                // The shader has a fixed-sized array, so it will never access more than
-                // the first `descriptor_count` elements.
-                elements.get(..descriptor_count as usize).ok_or({
-                    // There are less than `descriptor_count` elements in `elements`
-                    PipelineExecutionError::DescriptorResourceInvalid {
-                        set_num,
-                        binding_num,
-                        index: elements.len() as u32,
-                        error: DescriptorResourceInvalidError::Missing,
-                    }
-                })?
-            } else {
-                // The shader has a runtime-sized array, so any element could potentially
-                // be accessed. We must check them all.
-                elements
-            };
+        // VUID-vkCmdDrawIndexedIndirectCount-None-04445
+        if !(self.device().enabled_features().draw_indirect_count
+            || self.device().enabled_extensions().khr_draw_indirect_count)
+        {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::draw_indexed_indirect_count`",
+                requires_one_of: RequiresOneOf {
+                    features: &["draw_indirect_count"],
+                    device_extensions: &["khr_draw_indirect_count"],
+                    ..Default::default()
+                },
+            });
+        }
 
-            for (index, element) in elements_to_check.iter().enumerate() {
-                let index = index as u32;
+        // VUID-vkCmdDrawIndexedIndirectCount-renderpass
+        let render_pass_state = self
+            .render_pass_state
+            .as_ref()
+            .ok_or(PipelineExecutionError::ForbiddenOutsideRenderPass)?;
 
-                // VUID-vkCmdDispatch-None-02699
-                let element = match element {
-                    Some(x) => x,
-                    None => {
-                        return Err(PipelineExecutionError::DescriptorResourceInvalid {
-                            set_num,
-                            binding_num,
-                            index,
-                            error: DescriptorResourceInvalidError::Missing,
-                        })
-                    }
-                };
+        // VUID-vkCmdDrawIndexedIndirectCount-None-02700
+        let pipeline = match self.state().pipeline_graphics() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
 
-                if let Err(error) = extra_check(index, element) {
-                    return Err(PipelineExecutionError::DescriptorResourceInvalid {
-                        set_num,
-                        binding_num,
-                        index,
-                        error,
-                    });
-                }
-            }
+        // VUID-vkCmdDrawIndexedIndirectCount-stage-06481
+        if pipeline.shader(ShaderStage::Mesh).is_some() {
+            return Err(PipelineExecutionError::PipelineMeshShaderNotAllowed);
+        }
 
-            Ok(())
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+        self.validate_pipeline_graphics_dynamic_state(pipeline)?;
+        self.validate_pipeline_graphics_render_pass(pipeline, render_pass_state)?;
+        self.validate_pipeline_graphics_vertex_buffers(pipeline, None, None)?;
+
+        self.validate_index_buffer(None)?;
+        self.validate_indirect_buffer(indirect_buffer)?;
+        self.validate_indirect_count_buffer(count_buffer)?;
+        self.validate_indirect_buffer_max_draw_count(indirect_buffer, max_draw_count, stride)?;
+
+        Ok(())
+    }
+
+    /// Perform a single draw operation using a graphics pipeline, taking the number of vertices
+    /// from a transform feedback counter buffer.
+    ///
+    /// The number of vertices is the byte count that is read from `counter_buffer` when the
+    /// command is executed, minus `counter_offset`, divided by `vertex_stride`. This is usually a
+    /// counter buffer that was written by
+    /// [`end_transform_feedback`](Self::end_transform_feedback), to draw the vertices that were
+    /// captured with transform feedback.
+    ///
+    /// The [`transform_feedback`](crate::device::Features::transform_feedback) feature must be
+    /// enabled on the device, and the
+    /// [`transform_feedback_draw`](crate::device::Properties::transform_feedback_draw) device
+    /// property must be `true`. `counter_buffer` must have the
+    /// [`INDIRECT_BUFFER`](crate::buffer::BufferUsage::INDIRECT_BUFFER) usage, and its offset must
+    /// be a multiple of 4.
+    ///
+    /// A graphics pipeline must have been bound using
+    /// [`bind_pipeline_graphics`](Self::bind_pipeline_graphics). Any resources used by the graphics
+    /// pipeline, such as descriptor sets, vertex buffers and dynamic state, must have been set
+    /// beforehand. If the bound graphics pipeline uses vertex buffers, then the drawn vertex and
+    /// instance ranges must be in range of the bound vertex buffers.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `vertex_stride` is 0.
+    pub fn draw_indirect_byte_count(
+        &mut self,
+        instance_count: u32,
+        first_instance: u32,
+        counter_buffer: Subbuffer<u32>,
+        counter_offset: u32,
+        vertex_stride: u32,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        self.validate_draw_indirect_byte_count(
+            instance_count,
+            first_instance,
+            counter_buffer.as_bytes(),
+            vertex_stride,
+        )?;
+
+        unsafe {
+            self.inner.draw_indirect_byte_count(
+                instance_count,
+                first_instance,
+                counter_buffer,
+                counter_offset,
+                vertex_stride,
+            )?;
         }
 
-        if pipeline.num_used_descriptor_sets() == 0 {
-            return Ok(());
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.render_pass_state.as_mut().unwrap().render_pass
+        {
+            state.pipeline_used = true;
         }
 
-        let current_state = self.state();
+        Ok(self)
+    }
 
-        // VUID-vkCmdDispatch-None-02697
-        let bindings_pipeline_layout =
-            match current_state.descriptor_sets_pipeline_layout(pipeline.bind_point()) {
-                Some(x) => x,
-                None => return Err(PipelineExecutionError::PipelineLayoutNotCompatible),
-            };
+    fn validate_draw_indirect_byte_count(
+        &self,
+        instance_count: u32,
+        first_instance: u32,
+        counter_buffer: &Subbuffer<[u8]>,
+        vertex_stride: u32,
+    ) -> Result<(), PipelineExecutionError> {
+        let device = self.device();
 
-        // VUID-vkCmdDispatch-None-02697
-        if !pipeline.layout().is_compatible_with(
-            bindings_pipeline_layout,
-            pipeline.num_used_descriptor_sets(),
-        ) {
-            return Err(PipelineExecutionError::PipelineLayoutNotCompatible);
+        // VUID-vkCmdDrawIndirectByteCountEXT-transformFeedback-02287
+        if !device.enabled_features().transform_feedback {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::draw_indirect_byte_count`",
+                requires_one_of: RequiresOneOf {
+                    features: &["transform_feedback"],
+                    ..Default::default()
+                },
+            });
         }
 
-        for (&(set_num, binding_num), binding_reqs) in pipeline.descriptor_binding_requirements() {
-            let layout_binding =
-                &pipeline.layout().set_layouts()[set_num as usize].bindings()[&binding_num];
+        let properties = device.physical_device().properties();
 
-            let check_buffer =
-                |_index: u32, (_buffer, _range): &(Subbuffer<[u8]>, Range<DeviceSize>)| Ok(());
+        // VUID-vkCmdDrawIndirectByteCountEXT-transformFeedbackDraw-02288
+        if !properties.transform_feedback_draw.unwrap_or(false) {
+            return Err(PipelineExecutionError::TransformFeedbackDrawNotSupported);
+        }
 
-            let check_buffer_view = |index: u32, buffer_view: &Arc<BufferView>| {
-                for desc_reqs in (binding_reqs.descriptors.get(&Some(index)).into_iter())
-                    .chain(binding_reqs.descriptors.get(&None))
-                {
-                    if layout_binding.descriptor_type == DescriptorType::StorageTexelBuffer {
-                        // VUID-vkCmdDispatch-OpTypeImage-06423
-                        if binding_reqs.image_format.is_none()
-                            && !desc_reqs.memory_write.is_empty()
-                            && !buffer_view
-                                .format_features()
-                                .intersects(FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT)
-                        {
-                            return Err(DescriptorResourceInvalidError::StorageWriteWithoutFormatNotSupported);
-                        }
+        // VUID-vkCmdDrawIndirectByteCountEXT-renderpass
+        let render_pass_state = self
+            .render_pass_state
+            .as_ref()
+            .ok_or(PipelineExecutionError::ForbiddenOutsideRenderPass)?;
 
-                        // VUID-vkCmdDispatch-OpTypeImage-06424
-                        if binding_reqs.image_format.is_none()
-                            && !desc_reqs.memory_read.is_empty()
-                            && !buffer_view
-                                .format_features()
-                                .intersects(FormatFeatures::STORAGE_READ_WITHOUT_FORMAT)
-                        {
-                            return Err(DescriptorResourceInvalidError::StorageReadWithoutFormatNotSupported);
-                        }
-                    }
-                }
+        // VUID-vkCmdDrawIndirectByteCountEXT-None-02700
+        let pipeline = match self.state().pipeline_graphics() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
 
-                Ok(())
-            };
+        // VUID-vkCmdDrawIndirectByteCountEXT-stage-06481
+        if pipeline.shader(ShaderStage::Mesh).is_some() {
+            return Err(PipelineExecutionError::PipelineMeshShaderNotAllowed);
+        }
 
-            let check_image_view_common = |index: u32, image_view: &Arc<dyn ImageViewAbstract>| {
-                for desc_reqs in (binding_reqs.descriptors.get(&Some(index)).into_iter())
-                    .chain(binding_reqs.descriptors.get(&None))
-                {
-                    // VUID-vkCmdDispatch-None-02691
-                    if desc_reqs.storage_image_atomic
-                        && !image_view
-                            .format_features()
-                            .intersects(FormatFeatures::STORAGE_IMAGE_ATOMIC)
-                    {
-                        return Err(DescriptorResourceInvalidError::StorageImageAtomicNotSupported);
-                    }
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+        self.validate_pipeline_graphics_dynamic_state(pipeline)?;
+        self.validate_pipeline_graphics_render_pass(pipeline, render_pass_state)?;
+        self.validate_pipeline_graphics_vertex_buffers(
+            pipeline,
+            None,
+            Some((first_instance, instance_count)),
+        )?;
 
-                    if layout_binding.descriptor_type == DescriptorType::StorageImage {
-                        // VUID-vkCmdDispatch-OpTypeImage-06423
-                        if binding_reqs.image_format.is_none()
-                            && !desc_reqs.memory_write.is_empty()
-                            && !image_view
-                                .format_features()
-                                .intersects(FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT)
-                        {
-                            return Err(
-                            DescriptorResourceInvalidError::StorageWriteWithoutFormatNotSupported,
-                        );
-                        }
+        // VUID-vkCmdDrawIndirectByteCountEXT-counterBuffer-02290
+        // VUID-vkCmdDrawIndirectByteCountEXT-counterBufferOffset-04568
+        self.validate_indirect_count_buffer(counter_buffer)?;
 
-                        // VUID-vkCmdDispatch-OpTypeImage-06424
-                        if binding_reqs.image_format.is_none()
-                            && !desc_reqs.memory_read.is_empty()
-                            && !image_view
-                                .format_features()
-                                .intersects(FormatFeatures::STORAGE_READ_WITHOUT_FORMAT)
-                        {
-                            return Err(
-                            DescriptorResourceInvalidError::StorageReadWithoutFormatNotSupported,
-                        );
-                        }
-                    }
-                }
+        // VUID-vkCmdDrawIndirectByteCountEXT-vertexStride-02289
+        assert!(vertex_stride != 0);
 
-                /*
-                   Instruction/Sampler/Image View Validation
-                   https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap16.html#textures-input-validation
-                */
+        let max_transform_feedback_buffer_data_stride = properties
+            .max_transform_feedback_buffer_data_stride
+            .unwrap_or(0);
 
-                // The SPIR-V Image Format is not compatible with the image view’s format.
-                if let Some(format) = binding_reqs.image_format {
-                    if image_view.format() != Some(format) {
-                        return Err(DescriptorResourceInvalidError::ImageViewFormatMismatch {
-                            required: format,
-                            provided: image_view.format(),
-                        });
-                    }
-                }
+        // VUID-vkCmdDrawIndirectByteCountEXT-vertexStride-02289
+        if vertex_stride > max_transform_feedback_buffer_data_stride {
+            return Err(
+                PipelineExecutionError::MaxTransformFeedbackBufferDataStrideExceeded {
+                    vertex_stride,
+                    max: max_transform_feedback_buffer_data_stride,
+                },
+            );
+        }
 
-                // Rules for viewType
-                if let Some(image_view_type) = binding_reqs.image_view_type {
-                    if image_view.view_type() != image_view_type {
-                        return Err(DescriptorResourceInvalidError::ImageViewTypeMismatch {
-                            required: image_view_type,
-                            provided: image_view.view_type(),
-                        });
-                    }
-                }
+        Ok(())
+    }
 
-                // - If the image was created with VkImageCreateInfo::samples equal to
-                //   VK_SAMPLE_COUNT_1_BIT, the instruction must have MS = 0.
-                // - If the image was created with VkImageCreateInfo::samples not equal to
-                //   VK_SAMPLE_COUNT_1_BIT, the instruction must have MS = 1.
-                if binding_reqs.image_multisampled
-                    != (image_view.image().samples() != SampleCount::Sample1)
-                {
-                    return Err(
-                        DescriptorResourceInvalidError::ImageViewMultisampledMismatch {
-                            required: binding_reqs.image_multisampled,
-                            provided: image_view.image().samples() != SampleCount::Sample1,
-                        },
-                    );
-                }
+    /// Perform a single draw operation using a graphics pipeline with a mesh shader.
+    ///
+    /// `group_counts` is the number of workgroups to launch in the X, Y and Z dimensions. If the
+    /// pipeline contains a task shader, these are task shader workgroups, otherwise they are mesh
+    /// shader workgroups.
+    ///
+    /// A graphics pipeline that contains a mesh shader must have been bound using
+    /// [`bind_pipeline_graphics`](Self::bind_pipeline_graphics). Any resources used by the graphics
+    /// pipeline, such as descriptor sets and dynamic state, must have been set beforehand.
+    pub fn draw_mesh_tasks(
+        &mut self,
+        group_counts: [u32; 3],
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        self.validate_draw_mesh_tasks(group_counts)?;
 
-                // - If the Sampled Type of the OpTypeImage does not match the numeric format of the
-                //   image, as shown in the SPIR-V Sampled Type column of the
-                //   Interpretation of Numeric Format table.
-                // - If the signedness of any read or sample operation does not match the signedness of
-                //   the image’s format.
-                if let Some(scalar_type) = binding_reqs.image_scalar_type {
-                    let aspects = image_view.subresource_range().aspects;
-                    let view_scalar_type = ShaderScalarType::from(
-                        if aspects.intersects(
-                            ImageAspects::COLOR
-                                | ImageAspects::PLANE_0
-                                | ImageAspects::PLANE_1
-                                | ImageAspects::PLANE_2,
-                        ) {
-                            image_view.format().unwrap().type_color().unwrap()
-                        } else if aspects.intersects(ImageAspects::DEPTH) {
-                            image_view.format().unwrap().type_depth().unwrap()
-                        } else if aspects.intersects(ImageAspects::STENCIL) {
-                            image_view.format().unwrap().type_stencil().unwrap()
-                        } else {
-                            // Per `ImageViewBuilder::aspects` and
-                            // VUID-VkDescriptorImageInfo-imageView-01976
-                            unreachable!()
-                        },
-                    );
-
-                    if scalar_type != view_scalar_type {
-                        return Err(
-                            DescriptorResourceInvalidError::ImageViewScalarTypeMismatch {
-                                required: scalar_type,
-                                provided: view_scalar_type,
-                            },
-                        );
-                    }
-                }
-
-                Ok(())
-            };
-
-            let check_sampler_common = |index: u32, sampler: &Arc<Sampler>| {
-                for desc_reqs in (binding_reqs.descriptors.get(&Some(index)).into_iter())
-                    .chain(binding_reqs.descriptors.get(&None))
-                {
-                    // VUID-vkCmdDispatch-None-02703
-                    // VUID-vkCmdDispatch-None-02704
-                    if desc_reqs.sampler_no_unnormalized_coordinates
-                        && sampler.unnormalized_coordinates()
-                    {
-                        return Err(
-                        DescriptorResourceInvalidError::SamplerUnnormalizedCoordinatesNotAllowed,
-                    );
-                    }
-
-                    // - OpImageFetch, OpImageSparseFetch, OpImage*Gather, and OpImageSparse*Gather must not
-                    //   be used with a sampler that enables sampler Y′CBCR conversion.
-                    // - The ConstOffset and Offset operands must not be used with a sampler that enables
-                    //   sampler Y′CBCR conversion.
-                    if desc_reqs.sampler_no_ycbcr_conversion
-                        && sampler.sampler_ycbcr_conversion().is_some()
-                    {
-                        return Err(
-                            DescriptorResourceInvalidError::SamplerYcbcrConversionNotAllowed,
-                        );
-                    }
-
-                    /*
-                        Instruction/Sampler/Image View Validation
-                        https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap16.html#textures-input-validation
-                    */
-
-                    // - The SPIR-V instruction is one of the OpImage*Dref* instructions and the sampler
-                    //   compareEnable is VK_FALSE
-                    // - The SPIR-V instruction is not one of the OpImage*Dref* instructions and the sampler
-                    //   compareEnable is VK_TRUE
-                    if desc_reqs.sampler_compare != sampler.compare().is_some() {
-                        return Err(DescriptorResourceInvalidError::SamplerCompareMismatch {
-                            required: desc_reqs.sampler_compare,
-                            provided: sampler.compare().is_some(),
-                        });
-                    }
-                }
-
-                Ok(())
-            };
-
-            let check_image_view = |index: u32, image_view: &Arc<dyn ImageViewAbstract>| {
-                check_image_view_common(index, image_view)?;
-
-                if let Some(sampler) = layout_binding.immutable_samplers.get(index as usize) {
-                    check_sampler_common(index, sampler)?;
-                }
-
-                Ok(())
-            };
+        unsafe {
+            self.inner.draw_mesh_tasks(group_counts)?;
+        }
 
-            let check_image_view_sampler =
-                |index: u32, (image_view, sampler): &(Arc<dyn ImageViewAbstract>, Arc<Sampler>)| {
-                    check_image_view_common(index, image_view)?;
-                    check_sampler_common(index, sampler)?;
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.render_pass_state.as_mut().unwrap().render_pass
+        {
+            state.pipeline_used = true;
+        }
 
-                    Ok(())
-                };
+        Ok(self)
+    }
 
-            let check_sampler = |index: u32, sampler: &Arc<Sampler>| {
-                check_sampler_common(index, sampler)?;
+    fn validate_draw_mesh_tasks(
+        &self,
+        group_counts: [u32; 3],
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID-vkCmdDrawMeshTasksEXT-renderpass
+        let render_pass_state = self
+            .render_pass_state
+            .as_ref()
+            .ok_or(PipelineExecutionError::ForbiddenOutsideRenderPass)?;
 
-                for desc_reqs in (binding_reqs.descriptors.get(&Some(index)).into_iter())
-                    .chain(binding_reqs.descriptors.get(&None))
-                {
-                    // Check sampler-image compatibility. Only done for separate samplers;
-                    // combined image samplers are checked when updating the descriptor set.
+        // VUID-vkCmdDrawMeshTasksEXT-None-02700
+        let pipeline = match self.state().pipeline_graphics() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
 
-                    // If the image view isn't actually present in the resources, then just skip it.
-                    // It will be caught later by check_resources.
-                    let iter = desc_reqs.sampler_with_images.iter().filter_map(|id| {
-                        current_state
-                            .descriptor_set(pipeline.bind_point(), id.set)
-                            .and_then(|set| set.resources().binding(id.binding))
-                            .and_then(|res| match res {
-                                DescriptorBindingResources::ImageView(elements) => elements
-                                    .get(id.index as usize)
-                                    .and_then(|opt| opt.as_ref().map(|opt| (id, opt))),
-                                _ => None,
-                            })
-                    });
+        self.validate_pipeline_graphics_mesh_shader(pipeline)?;
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+        self.validate_pipeline_graphics_dynamic_state(pipeline)?;
+        self.validate_pipeline_graphics_render_pass(pipeline, render_pass_state)?;
 
-                    for (id, image_view) in iter {
-                        if let Err(error) = sampler.check_can_sample(image_view.as_ref()) {
-                            return Err(
-                                DescriptorResourceInvalidError::SamplerImageViewIncompatible {
-                                    image_view_set_num: id.set,
-                                    image_view_binding_num: id.binding,
-                                    image_view_index: id.index,
-                                    error,
-                                },
-                            );
-                        }
-                    }
-                }
+        let properties = self.device().physical_device().properties();
+        let total_count = group_counts.iter().map(|&x| x as u64).product::<u64>();
 
-                Ok(())
-            };
+        if pipeline.shader(ShaderStage::Task).is_some() {
+            let max = properties.max_task_work_group_count.unwrap_or_default();
 
-            let check_none = |index: u32, _: &()| {
-                if let Some(sampler) = layout_binding.immutable_samplers.get(index as usize) {
-                    check_sampler(index, sampler)?;
-                }
+            // VUID-vkCmdDrawMeshTasksEXT-TaskEXT-07322
+            // VUID-vkCmdDrawMeshTasksEXT-TaskEXT-07323
+            // VUID-vkCmdDrawMeshTasksEXT-TaskEXT-07324
+            if group_counts[0] > max[0] || group_counts[1] > max[1] || group_counts[2] > max[2] {
+                return Err(PipelineExecutionError::MaxTaskWorkGroupCountExceeded {
+                    requested: group_counts,
+                    max,
+                });
+            }
 
-                Ok(())
-            };
+            let max = properties.max_task_work_group_total_count.unwrap_or(0);
 
-            let set_resources = match current_state.descriptor_set(pipeline.bind_point(), set_num) {
-                Some(x) => x.resources(),
-                None => return Err(PipelineExecutionError::DescriptorSetNotBound { set_num }),
-            };
+            // VUID-vkCmdDrawMeshTasksEXT-TaskEXT-07325
+            if total_count > max as u64 {
+                return Err(PipelineExecutionError::MaxTaskWorkGroupTotalCountExceeded {
+                    requested: total_count,
+                    max,
+                });
+            }
+        } else {
+            let max = properties.max_mesh_work_group_count.unwrap_or_default();
+
+            // VUID-vkCmdDrawMeshTasksEXT-TaskEXT-07326
+            // VUID-vkCmdDrawMeshTasksEXT-TaskEXT-07327
+            // VUID-vkCmdDrawMeshTasksEXT-TaskEXT-07328
+            if group_counts[0] > max[0] || group_counts[1] > max[1] || group_counts[2] > max[2] {
+                return Err(PipelineExecutionError::MaxMeshWorkGroupCountExceeded {
+                    requested: group_counts,
+                    max,
+                });
+            }
 
-            let binding_resources = set_resources.binding(binding_num).unwrap();
+            let max = properties.max_mesh_work_group_total_count.unwrap_or(0);
 
-            match binding_resources {
-                DescriptorBindingResources::None(elements) => {
-                    validate_resources(set_num, binding_num, binding_reqs, elements, check_none)?;
-                }
-                DescriptorBindingResources::Buffer(elements) => {
-                    validate_resources(set_num, binding_num, binding_reqs, elements, check_buffer)?;
-                }
-                DescriptorBindingResources::BufferView(elements) => {
-                    validate_resources(
-                        set_num,
-                        binding_num,
-                        binding_reqs,
-                        elements,
-                        check_buffer_view,
-                    )?;
-                }
-                DescriptorBindingResources::ImageView(elements) => {
-                    validate_resources(
-                        set_num,
-                        binding_num,
-                        binding_reqs,
-                        elements,
-                        check_image_view,
-                    )?;
-                }
-                DescriptorBindingResources::ImageViewSampler(elements) => {
-                    validate_resources(
-                        set_num,
-                        binding_num,
-                        binding_reqs,
-                        elements,
-                        check_image_view_sampler,
-                    )?;
-                }
-                DescriptorBindingResources::Sampler(elements) => {
-                    validate_resources(
-                        set_num,
-                        binding_num,
-                        binding_reqs,
-                        elements,
-                        check_sampler,
-                    )?;
-                }
+            // VUID-vkCmdDrawMeshTasksEXT-TaskEXT-07329
+            if total_count > max as u64 {
+                return Err(PipelineExecutionError::MaxMeshWorkGroupTotalCountExceeded {
+                    requested: total_count,
+                    max,
+                });
             }
         }
 
         Ok(())
     }
 
-    fn validate_pipeline_push_constants(
-        &self,
-        pipeline_layout: &PipelineLayout,
-    ) -> Result<(), PipelineExecutionError> {
-        if pipeline_layout.push_constant_ranges().is_empty()
-            || self.device().enabled_features().maintenance4
-        {
-            return Ok(());
-        }
-
-        let current_state = self.state();
-
-        // VUID-vkCmdDispatch-maintenance4-06425
-        let constants_pipeline_layout = match current_state.push_constants_pipeline_layout() {
-            Some(x) => x,
-            None => return Err(PipelineExecutionError::PushConstantsMissing),
-        };
+    /// Perform multiple draw operations using a graphics pipeline with a mesh shader.
+    ///
+    /// One draw is performed for each [`DrawMeshTasksIndirectCommand`] struct in
+    /// `indirect_buffer`. The maximum number of draw commands in the buffer is limited by the
+    /// [`max_draw_indirect_count`](crate::device::Properties::max_draw_indirect_count) limit.
+    /// This limit is 1 unless the
+    /// [`multi_draw_indirect`](crate::device::Features::multi_draw_indirect) feature has been
+    /// enabled.
+    ///
+    /// A graphics pipeline that contains a mesh shader must have been bound using
+    /// [`bind_pipeline_graphics`](Self::bind_pipeline_graphics). Any resources used by the graphics
+    /// pipeline, such as descriptor sets and dynamic state, must have been set beforehand. The
+    /// workgroup counts of each `DrawMeshTasksIndirectCommand` in the indirect buffer must not
+    /// exceed the limits of the device.
+    pub fn draw_mesh_tasks_indirect(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawMeshTasksIndirectCommand]>,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        let draw_count = indirect_buffer.len() as u32;
+        let stride = size_of::<DrawMeshTasksIndirectCommand>() as u32;
+        self.validate_draw_mesh_tasks_indirect(indirect_buffer.as_bytes(), draw_count, stride)?;
 
-        // VUID-vkCmdDispatch-maintenance4-06425
-        if pipeline_layout.handle() != constants_pipeline_layout.handle()
-            && pipeline_layout.push_constant_ranges()
-                != constants_pipeline_layout.push_constant_ranges()
-        {
-            return Err(PipelineExecutionError::PushConstantsNotCompatible);
+        unsafe {
+            self.inner
+                .draw_mesh_tasks_indirect(indirect_buffer, draw_count, stride)?;
         }
 
-        let set_bytes = current_state.push_constants();
-
-        // VUID-vkCmdDispatch-maintenance4-06425
-        if !pipeline_layout
-            .push_constant_ranges()
-            .iter()
-            .all(|pc_range| set_bytes.contains(pc_range.offset..pc_range.offset + pc_range.size))
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.render_pass_state.as_mut().unwrap().render_pass
         {
-            return Err(PipelineExecutionError::PushConstantsMissing);
+            state.pipeline_used = true;
         }
 
-        Ok(())
+        Ok(self)
     }
 
-    fn validate_pipeline_graphics_dynamic_state(
+    fn validate_draw_mesh_tasks_indirect(
         &self,
-        pipeline: &GraphicsPipeline,
+        indirect_buffer: &Subbuffer<[u8]>,
+        draw_count: u32,
+        _stride: u32,
     ) -> Result<(), PipelineExecutionError> {
-        let device = pipeline.device();
-        let current_state = self.state();
-
-        // VUID-vkCmdDraw-commandBuffer-02701
-        for dynamic_state in pipeline
+        // VUID-vkCmdDrawMeshTasksIndirectEXT-renderpass
+        let render_pass_state = self
+            .render_pass_state
+            .as_ref()
+            .ok_or(PipelineExecutionError::ForbiddenOutsideRenderPass)?;
+
+        // VUID-vkCmdDrawMeshTasksIndirectEXT-None-02700
+        let pipeline = match self.state().pipeline_graphics() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
+
+        self.validate_pipeline_graphics_mesh_shader(pipeline)?;
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+        self.validate_pipeline_graphics_dynamic_state(pipeline)?;
+        self.validate_pipeline_graphics_render_pass(pipeline, render_pass_state)?;
+
+        self.validate_indirect_buffer(indirect_buffer)?;
+
+        // VUID-vkCmdDrawMeshTasksIndirectEXT-drawCount-02718
+        if draw_count > 1 && !self.device().enabled_features().multi_draw_indirect {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`draw_count` is greater than `1`",
+                requires_one_of: RequiresOneOf {
+                    features: &["multi_draw_indirect"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let max = self
+            .device()
+            .physical_device()
+            .properties()
+            .max_draw_indirect_count;
+
+        // VUID-vkCmdDrawMeshTasksIndirectEXT-drawCount-02719
+        if draw_count > max {
+            return Err(PipelineExecutionError::MaxDrawIndirectCountExceeded {
+                provided: draw_count,
+                max,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Perform multiple draw operations using a graphics pipeline with a mesh shader, reading the
+    /// number of draws from a buffer.
+    ///
+    /// One draw is performed for each [`DrawMeshTasksIndirectCommand`] struct in
+    /// `indirect_buffer`, up to the number of draws that is read from `count_buffer` when the
+    /// command is executed. `max_draw_count` is an upper bound on the number of draws, and must not
+    /// be greater than the number of commands in `indirect_buffer`.
+    ///
+    /// The [`draw_indirect_count`](crate::device::Features::draw_indirect_count) feature must be
+    /// enabled on the device.
+    ///
+    /// A graphics pipeline that contains a mesh shader must have been bound using
+    /// [`bind_pipeline_graphics`](Self::bind_pipeline_graphics). Any resources used by the graphics
+    /// pipeline, such as descriptor sets and dynamic state, must have been set beforehand. The
+    /// workgroup counts of each `DrawMeshTasksIndirectCommand` in the indirect buffer must not
+    /// exceed the limits of the device.
+    pub fn draw_mesh_tasks_indirect_count(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawMeshTasksIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        let stride = size_of::<DrawMeshTasksIndirectCommand>() as u32;
+        self.validate_draw_mesh_tasks_indirect_count(
+            indirect_buffer.as_bytes(),
+            count_buffer.as_bytes(),
+            max_draw_count,
+            stride,
+        )?;
+
+        unsafe {
+            self.inner.draw_mesh_tasks_indirect_count(
+                indirect_buffer,
+                count_buffer,
+                max_draw_count,
+                stride,
+            )?;
+        }
+
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.render_pass_state.as_mut().unwrap().render_pass
+        {
+            state.pipeline_used = true;
+        }
+
+        Ok(self)
+    }
+
+    fn validate_draw_mesh_tasks_indirect_count(
+        &self,
+        indirect_buffer: &Subbuffer<[u8]>,
+        count_buffer: &Subbuffer<[u8]>,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID-vkCmdDrawMeshTasksIndirectCountEXT-None-04445
+        if !(self.device().enabled_features().draw_indirect_count
+            || self.device().enabled_extensions().khr_draw_indirect_count)
+        {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::draw_mesh_tasks_indirect_count`",
+                requires_one_of: RequiresOneOf {
+                    features: &["draw_indirect_count"],
+                    device_extensions: &["khr_draw_indirect_count"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdDrawMeshTasksIndirectCountEXT-renderpass
+        let render_pass_state = self
+            .render_pass_state
+            .as_ref()
+            .ok_or(PipelineExecutionError::ForbiddenOutsideRenderPass)?;
+
+        // VUID-vkCmdDrawMeshTasksIndirectCountEXT-None-02700
+        let pipeline = match self.state().pipeline_graphics() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
+
+        self.validate_pipeline_graphics_mesh_shader(pipeline)?;
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+        self.validate_pipeline_graphics_dynamic_state(pipeline)?;
+        self.validate_pipeline_graphics_render_pass(pipeline, render_pass_state)?;
+
+        self.validate_indirect_buffer(indirect_buffer)?;
+        self.validate_indirect_count_buffer(count_buffer)?;
+        self.validate_indirect_buffer_max_draw_count(indirect_buffer, max_draw_count, stride)?;
+
+        Ok(())
+    }
+
+    fn validate_pipeline_graphics_mesh_shader(
+        &self,
+        pipeline: &GraphicsPipeline,
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID-vkCmdDrawMeshTasksEXT-stage-06480
+        if pipeline.shader(ShaderStage::Mesh).is_none() {
+            return Err(PipelineExecutionError::PipelineMeshShaderMissing);
+        }
+
+        // The pipeline may have been created with only the `mesh_shader` feature of
+        // `VK_NV_mesh_shader`, which doesn't provide the commands used here.
+        if !self.device().enabled_extensions().ext_mesh_shader {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::draw_mesh_tasks*`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_mesh_shader"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    fn validate_index_buffer(
+        &self,
+        indices: Option<(u32, u32)>,
+    ) -> Result<(), PipelineExecutionError> {
+        let current_state = self.state();
+
+        // VUID?
+        let (index_buffer, index_type) = match current_state.index_buffer() {
+            Some(x) => x,
+            None => return Err(PipelineExecutionError::IndexBufferNotBound),
+        };
+
+        if let Some((first_index, index_count)) = indices {
+            let max_index_count = (index_buffer.size() / index_type.size()) as u32;
+
+            // // VUID-vkCmdDrawIndexed-firstIndex-04932
+            if first_index + index_count > max_index_count {
+                return Err(PipelineExecutionError::IndexBufferRangeOutOfBounds {
+                    highest_index: first_index + index_count,
+                    max_index_count,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_indirect_buffer(
+        &self,
+        buffer: &Subbuffer<[u8]>,
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID-vkCmdDispatchIndirect-commonparent
+        assert_eq!(self.device(), buffer.device());
+
+        // VUID-vkCmdDispatchIndirect-buffer-02709
+        if !buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER)
+        {
+            return Err(PipelineExecutionError::IndirectBufferMissingUsage);
+        }
+
+        // VUID-vkCmdDispatchIndirect-offset-02710
+        // TODO:
+
+        Ok(())
+    }
+
+    fn validate_indirect_count_buffer(
+        &self,
+        count_buffer: &Subbuffer<[u8]>,
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID-vkCmdDrawIndirectCount-commonparent
+        assert_eq!(self.device(), count_buffer.device());
+
+        // VUID-vkCmdDrawIndirectCount-countBuffer-02715
+        if !count_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER)
+        {
+            return Err(PipelineExecutionError::IndirectCountBufferMissingUsage);
+        }
+
+        // VUID-vkCmdDrawIndirectCount-countBufferOffset-02716
+        if count_buffer.offset() % 4 != 0 {
+            return Err(
+                PipelineExecutionError::IndirectCountBufferOffsetNotAligned {
+                    offset: count_buffer.offset(),
+                },
+            );
+        }
+
+        // VUID-vkCmdDrawIndirectCount-countBufferOffset-04129
+        // Ensured by the size of the `Subbuffer<u32>`.
+
+        Ok(())
+    }
+
+    fn validate_indirect_buffer_max_draw_count(
+        &self,
+        indirect_buffer: &Subbuffer<[u8]>,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> Result<(), PipelineExecutionError> {
+        let commands_in_buffer = indirect_buffer.size() / stride as DeviceSize;
+
+        // VUID-vkCmdDrawIndirectCount-maxDrawCount-03142
+        if max_draw_count as DeviceSize > commands_in_buffer {
+            return Err(PipelineExecutionError::IndirectBufferDrawCountOutOfBounds {
+                max_draw_count,
+                commands_in_buffer,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn validate_pipeline_descriptor_sets<Pl: Pipeline>(
+        &self,
+        pipeline: &Pl,
+    ) -> Result<(), PipelineExecutionError> {
+        fn validate_resources<T>(
+            set_num: u32,
+            binding_num: u32,
+            binding_reqs: &DescriptorBindingRequirements,
+            partially_bound: bool,
+            elements: &[Option<T>],
+            mut extra_check: impl FnMut(u32, &T) -> Result<(), DescriptorResourceInvalidError>,
+        ) -> Result<(), PipelineExecutionError> {
+            let elements_to_check = if let Some(descriptor_count) = binding_reqs.descriptor_count {
+                // The shader has a fixed-sized array, so it will never access more than
+                // the first `descriptor_count` elements.
+                elements.get(..descriptor_count as usize).ok_or({
+                    // There are less than `descriptor_count` elements in `elements`
+                    PipelineExecutionError::DescriptorResourceInvalid {
+                        set_num,
+                        binding_num,
+                        index: elements.len() as u32,
+                        error: DescriptorResourceInvalidError::Missing,
+                    }
+                })?
+            } else {
+                // The shader has a runtime-sized array, so any element could potentially
+                // be accessed. We must check them all.
+                elements
+            };
+
+            for (index, element) in elements_to_check.iter().enumerate() {
+                let index = index as u32;
+
+                // VUID-vkCmdDispatch-None-02699
+                let element = match element {
+                    Some(x) => x,
+                    // Elements of a partially bound binding may be left unwritten, as long as the
+                    // shader doesn't dynamically use them. That can't be checked here.
+                    None if partially_bound => continue,
+                    None => {
+                        return Err(PipelineExecutionError::DescriptorResourceInvalid {
+                            set_num,
+                            binding_num,
+                            index,
+                            error: DescriptorResourceInvalidError::Missing,
+                        })
+                    }
+                };
+
+                if let Err(error) = extra_check(index, element) {
+                    return Err(PipelineExecutionError::DescriptorResourceInvalid {
+                        set_num,
+                        binding_num,
+                        index,
+                        error,
+                    });
+                }
+            }
+
+            Ok(())
+        }
+
+        if pipeline.num_used_descriptor_sets() == 0 {
+            return Ok(());
+        }
+
+        let current_state = self.state();
+
+        // VUID-vkCmdDispatch-None-02697
+        let bindings_pipeline_layout =
+            match current_state.descriptor_sets_pipeline_layout(pipeline.bind_point()) {
+                Some(x) => x,
+                None => return Err(PipelineExecutionError::PipelineLayoutNotCompatible),
+            };
+
+        // VUID-vkCmdDispatch-None-02697
+        if !pipeline.layout().is_compatible_with(
+            bindings_pipeline_layout,
+            pipeline.num_used_descriptor_sets(),
+        ) {
+            return Err(PipelineExecutionError::PipelineLayoutNotCompatible);
+        }
+
+        for (&(set_num, binding_num), binding_reqs) in pipeline.descriptor_binding_requirements() {
+            let layout_binding =
+                &pipeline.layout().set_layouts()[set_num as usize].bindings()[&binding_num];
+
+            let check_buffer =
+                |_index: u32, (_buffer, _range): &(Subbuffer<[u8]>, Range<DeviceSize>)| Ok(());
+
+            let check_buffer_view = |index: u32, buffer_view: &Arc<BufferView>| {
+                for desc_reqs in (binding_reqs.descriptors.get(&Some(index)).into_iter())
+                    .chain(binding_reqs.descriptors.get(&None))
+                {
+                    if layout_binding.descriptor_type == DescriptorType::StorageTexelBuffer {
+                        // VUID-vkCmdDispatch-OpTypeImage-06423
+                        if binding_reqs.image_format.is_none()
+                            && !desc_reqs.memory_write.is_empty()
+                            && !buffer_view
+                                .format_features()
+                                .intersects(FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT)
+                        {
+                            return Err(DescriptorResourceInvalidError::StorageWriteWithoutFormatNotSupported);
+                        }
+
+                        // VUID-vkCmdDispatch-OpTypeImage-06424
+                        if binding_reqs.image_format.is_none()
+                            && !desc_reqs.memory_read.is_empty()
+                            && !buffer_view
+                                .format_features()
+                                .intersects(FormatFeatures::STORAGE_READ_WITHOUT_FORMAT)
+                        {
+                            return Err(DescriptorResourceInvalidError::StorageReadWithoutFormatNotSupported);
+                        }
+                    }
+                }
+
+                Ok(())
+            };
+
+            let check_image_view_common = |index: u32, image_view: &Arc<dyn ImageViewAbstract>| {
+                for desc_reqs in (binding_reqs.descriptors.get(&Some(index)).into_iter())
+                    .chain(binding_reqs.descriptors.get(&None))
+                {
+                    // VUID-vkCmdDispatch-None-02691
+                    if desc_reqs.storage_image_atomic
+                        && !image_view
+                            .format_features()
+                            .intersects(FormatFeatures::STORAGE_IMAGE_ATOMIC)
+                    {
+                        return Err(DescriptorResourceInvalidError::StorageImageAtomicNotSupported);
+                    }
+
+                    if layout_binding.descriptor_type == DescriptorType::StorageImage {
+                        // VUID-vkCmdDispatch-OpTypeImage-06423
+                        if binding_reqs.image_format.is_none()
+                            && !desc_reqs.memory_write.is_empty()
+                            && !image_view
+                                .format_features()
+                                .intersects(FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT)
+                        {
+                            return Err(
+                            DescriptorResourceInvalidError::StorageWriteWithoutFormatNotSupported,
+                        );
+                        }
+
+                        // VUID-vkCmdDispatch-OpTypeImage-06424
+                        if binding_reqs.image_format.is_none()
+                            && !desc_reqs.memory_read.is_empty()
+                            && !image_view
+                                .format_features()
+                                .intersects(FormatFeatures::STORAGE_READ_WITHOUT_FORMAT)
+                        {
+                            return Err(
+                            DescriptorResourceInvalidError::StorageReadWithoutFormatNotSupported,
+                        );
+                        }
+                    }
+                }
+
+                /*
+                   Instruction/Sampler/Image View Validation
+                   https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap16.html#textures-input-validation
+                */
+
+                // The SPIR-V Image Format is not compatible with the image view’s format.
+                if let Some(format) = binding_reqs.image_format {
+                    if image_view.format() != Some(format) {
+                        return Err(DescriptorResourceInvalidError::ImageViewFormatMismatch {
+                            required: format,
+                            provided: image_view.format(),
+                        });
+                    }
+                }
+
+                // Rules for viewType
+                if let Some(image_view_type) = binding_reqs.image_view_type {
+                    if image_view.view_type() != image_view_type {
+                        return Err(DescriptorResourceInvalidError::ImageViewTypeMismatch {
+                            required: image_view_type,
+                            provided: image_view.view_type(),
+                        });
+                    }
+                }
+
+                // - If the image was created with VkImageCreateInfo::samples equal to
+                //   VK_SAMPLE_COUNT_1_BIT, the instruction must have MS = 0.
+                // - If the image was created with VkImageCreateInfo::samples not equal to
+                //   VK_SAMPLE_COUNT_1_BIT, the instruction must have MS = 1.
+                if binding_reqs.image_multisampled
+                    != (image_view.image().samples() != SampleCount::Sample1)
+                {
+                    return Err(
+                        DescriptorResourceInvalidError::ImageViewMultisampledMismatch {
+                            required: binding_reqs.image_multisampled,
+                            provided: image_view.image().samples() != SampleCount::Sample1,
+                        },
+                    );
+                }
+
+                // - If the Sampled Type of the OpTypeImage does not match the numeric format of the
+                //   image, as shown in the SPIR-V Sampled Type column of the
+                //   Interpretation of Numeric Format table.
+                // - If the signedness of any read or sample operation does not match the signedness of
+                //   the image’s format.
+                if let Some(scalar_type) = binding_reqs.image_scalar_type {
+                    let aspects = image_view.subresource_range().aspects;
+                    let view_scalar_type = ShaderScalarType::from(
+                        if aspects.intersects(
+                            ImageAspects::COLOR
+                                | ImageAspects::PLANE_0
+                                | ImageAspects::PLANE_1
+                                | ImageAspects::PLANE_2,
+                        ) {
+                            image_view.format().unwrap().type_color().unwrap()
+                        } else if aspects.intersects(ImageAspects::DEPTH) {
+                            image_view.format().unwrap().type_depth().unwrap()
+                        } else if aspects.intersects(ImageAspects::STENCIL) {
+                            image_view.format().unwrap().type_stencil().unwrap()
+                        } else {
+                            // Per `ImageViewBuilder::aspects` and
+                            // VUID-VkDescriptorImageInfo-imageView-01976
+                            unreachable!()
+                        },
+                    );
+
+                    if scalar_type != view_scalar_type {
+                        return Err(
+                            DescriptorResourceInvalidError::ImageViewScalarTypeMismatch {
+                                required: scalar_type,
+                                provided: view_scalar_type,
+                            },
+                        );
+                    }
+                }
+
+                Ok(())
+            };
+
+            let check_sampler_common = |index: u32, sampler: &Arc<Sampler>| {
+                for desc_reqs in (binding_reqs.descriptors.get(&Some(index)).into_iter())
+                    .chain(binding_reqs.descriptors.get(&None))
+                {
+                    // VUID-vkCmdDispatch-None-02703
+                    // VUID-vkCmdDispatch-None-02704
+                    if desc_reqs.sampler_no_unnormalized_coordinates
+                        && sampler.unnormalized_coordinates()
+                    {
+                        return Err(
+                        DescriptorResourceInvalidError::SamplerUnnormalizedCoordinatesNotAllowed,
+                    );
+                    }
+
+                    // - OpImageFetch, OpImageSparseFetch, OpImage*Gather, and OpImageSparse*Gather must not
+                    //   be used with a sampler that enables sampler Y′CBCR conversion.
+                    // - The ConstOffset and Offset operands must not be used with a sampler that enables
+                    //   sampler Y′CBCR conversion.
+                    if desc_reqs.sampler_no_ycbcr_conversion
+                        && sampler.sampler_ycbcr_conversion().is_some()
+                    {
+                        return Err(
+                            DescriptorResourceInvalidError::SamplerYcbcrConversionNotAllowed,
+                        );
+                    }
+
+                    /*
+                        Instruction/Sampler/Image View Validation
+                        https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap16.html#textures-input-validation
+                    */
+
+                    // - The SPIR-V instruction is one of the OpImage*Dref* instructions and the sampler
+                    //   compareEnable is VK_FALSE
+                    // - The SPIR-V instruction is not one of the OpImage*Dref* instructions and the sampler
+                    //   compareEnable is VK_TRUE
+                    if desc_reqs.sampler_compare != sampler.compare().is_some() {
+                        return Err(DescriptorResourceInvalidError::SamplerCompareMismatch {
+                            required: desc_reqs.sampler_compare,
+                            provided: sampler.compare().is_some(),
+                        });
+                    }
+                }
+
+                Ok(())
+            };
+
+            let check_image_view = |index: u32, image_view: &Arc<dyn ImageViewAbstract>| {
+                check_image_view_common(index, image_view)?;
+
+                if let Some(sampler) = layout_binding.immutable_samplers.get(index as usize) {
+                    check_sampler_common(index, sampler)?;
+                }
+
+                Ok(())
+            };
+
+            let check_image_view_sampler =
+                |index: u32, (image_view, sampler): &(Arc<dyn ImageViewAbstract>, Arc<Sampler>)| {
+                    check_image_view_common(index, image_view)?;
+                    check_sampler_common(index, sampler)?;
+
+                    Ok(())
+                };
+
+            let check_sampler = |index: u32, sampler: &Arc<Sampler>| {
+                check_sampler_common(index, sampler)?;
+
+                for desc_reqs in (binding_reqs.descriptors.get(&Some(index)).into_iter())
+                    .chain(binding_reqs.descriptors.get(&None))
+                {
+                    // Check sampler-image compatibility. Only done for separate samplers;
+                    // combined image samplers are checked when updating the descriptor set.
+
+                    // If the image view isn't actually present in the resources, then just skip it.
+                    // It will be caught later by check_resources.
+                    let iter = desc_reqs.sampler_with_images.iter().filter_map(|id| {
+                        current_state
+                            .descriptor_set(pipeline.bind_point(), id.set)
+                            .and_then(|set| set.resources()?.binding(id.binding))
+                            .and_then(|res| match res {
+                                DescriptorBindingResources::ImageView(elements) => elements
+                                    .get(id.index as usize)
+                                    .and_then(|opt| opt.as_ref().map(|opt| (id, opt))),
+                                _ => None,
+                            })
+                    });
+
+                    for (id, image_view) in iter {
+                        if let Err(error) = sampler.check_can_sample(image_view.as_ref()) {
+                            return Err(
+                                DescriptorResourceInvalidError::SamplerImageViewIncompatible {
+                                    image_view_set_num: id.set,
+                                    image_view_binding_num: id.binding,
+                                    image_view_index: id.index,
+                                    error,
+                                },
+                            );
+                        }
+                    }
+                }
+
+                Ok(())
+            };
+
+            let check_none = |index: u32, _: &()| {
+                if let Some(sampler) = layout_binding.immutable_samplers.get(index as usize) {
+                    check_sampler(index, sampler)?;
+                }
+
+                Ok(())
+            };
+
+            let set_resources = match current_state.descriptor_set(pipeline.bind_point(), set_num) {
+                Some(x) => match x.resources() {
+                    Some(x) => x,
+                    // The contents of descriptor buffers can't be validated.
+                    None => continue,
+                },
+                None => return Err(PipelineExecutionError::DescriptorSetNotBound { set_num }),
+            };
+
+            let binding_resources = set_resources.binding(binding_num).unwrap();
+
+            match binding_resources {
+                DescriptorBindingResources::None(elements) => {
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        binding_reqs,
+                        layout_binding.partially_bound,
+                        elements,
+                        check_none,
+                    )?;
+                }
+                DescriptorBindingResources::Buffer(elements) => {
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        binding_reqs,
+                        layout_binding.partially_bound,
+                        elements,
+                        check_buffer,
+                    )?;
+                }
+                DescriptorBindingResources::BufferView(elements) => {
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        binding_reqs,
+                        layout_binding.partially_bound,
+                        elements,
+                        check_buffer_view,
+                    )?;
+                }
+                DescriptorBindingResources::ImageView(elements) => {
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        binding_reqs,
+                        layout_binding.partially_bound,
+                        elements,
+                        check_image_view,
+                    )?;
+                }
+                DescriptorBindingResources::ImageViewSampler(elements) => {
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        binding_reqs,
+                        layout_binding.partially_bound,
+                        elements,
+                        check_image_view_sampler,
+                    )?;
+                }
+                DescriptorBindingResources::Sampler(elements) => {
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        binding_reqs,
+                        layout_binding.partially_bound,
+                        elements,
+                        check_sampler,
+                    )?;
+                }
+                DescriptorBindingResources::AccelerationStructure(elements) => {
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        binding_reqs,
+                        layout_binding.partially_bound,
+                        elements,
+                        |_, _| Ok(()),
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_pipeline_push_constants(
+        &self,
+        pipeline_layout: &PipelineLayout,
+    ) -> Result<(), PipelineExecutionError> {
+        if pipeline_layout.push_constant_ranges().is_empty()
+            || self.device().enabled_features().maintenance4
+        {
+            return Ok(());
+        }
+
+        let current_state = self.state();
+
+        // VUID-vkCmdDispatch-maintenance4-06425
+        let constants_pipeline_layout = match current_state.push_constants_pipeline_layout() {
+            Some(x) => x,
+            None => return Err(PipelineExecutionError::PushConstantsMissing),
+        };
+
+        // VUID-vkCmdDispatch-maintenance4-06425
+        if pipeline_layout.handle() != constants_pipeline_layout.handle()
+            && pipeline_layout.push_constant_ranges()
+                != constants_pipeline_layout.push_constant_ranges()
+        {
+            return Err(PipelineExecutionError::PushConstantsNotCompatible);
+        }
+
+        let set_bytes = current_state.push_constants();
+
+        // VUID-vkCmdDispatch-maintenance4-06425
+        if !pipeline_layout
+            .push_constant_ranges()
+            .iter()
+            .all(|pc_range| set_bytes.contains(pc_range.offset..pc_range.offset + pc_range.size))
+        {
+            return Err(PipelineExecutionError::PushConstantsMissing);
+        }
+
+        Ok(())
+    }
+
+    fn validate_pipeline_graphics_dynamic_state(
+        &self,
+        pipeline: &GraphicsPipeline,
+    ) -> Result<(), PipelineExecutionError> {
+        let device = pipeline.device();
+        let current_state = self.state();
+
+        // VUID-vkCmdDraw-commandBuffer-02701
+        for dynamic_state in pipeline
             .dynamic_states()
             .filter(|(_, d)| *d)
             .map(|(s, _)| s)
@@ -1018,687 +1966,1206 @@ where
             match dynamic_state {
                 DynamicState::BlendConstants => {
                     // VUID?
-                    if current_state.blend_constants().is_none() {
+                    if current_state.blend_constants().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::ColorWriteEnable => {
+                    // VUID-vkCmdDraw-attachmentCount-06667
+                    let enables = if let Some(enables) = current_state.color_write_enable() {
+                        enables
+                    } else {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    };
+
+                    // VUID-vkCmdDraw-attachmentCount-06667
+                    if enables.len() < pipeline.color_blend_state().unwrap().attachments.len() {
+                        return Err(
+                            PipelineExecutionError::DynamicColorWriteEnableNotEnoughValues {
+                                color_write_enable_count: enables.len() as u32,
+                                attachment_count: pipeline
+                                    .color_blend_state()
+                                    .unwrap()
+                                    .attachments
+                                    .len() as u32,
+                            },
+                        );
+                    }
+                }
+                DynamicState::CullMode => {
+                    // VUID?
+                    if current_state.cull_mode().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::DepthBias => {
+                    // VUID?
+                    if current_state.depth_bias().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::DepthBiasEnable => {
+                    // VUID-vkCmdDraw-None-04877
+                    if current_state.depth_bias_enable().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::DepthBounds => {
+                    // VUID?
+                    if current_state.depth_bounds().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::DepthBoundsTestEnable => {
+                    // VUID?
+                    if current_state.depth_bounds_test_enable().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::DepthCompareOp => {
+                    // VUID?
+                    if current_state.depth_compare_op().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::DepthTestEnable => {
+                    // VUID?
+                    if current_state.depth_test_enable().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::DepthWriteEnable => {
+                    // VUID?
+                    if current_state.depth_write_enable().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+
+                    // TODO: Check if the depth buffer is writable
+                }
+                DynamicState::DiscardRectangle => {
+                    let discard_rectangle_count =
+                        match pipeline.discard_rectangle_state().unwrap().rectangles {
+                            PartialStateMode::Dynamic(count) => count,
+                            _ => unreachable!(),
+                        };
+
+                    for num in 0..discard_rectangle_count {
+                        // VUID?
+                        if current_state.discard_rectangle(num).is_none() {
+                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                        }
+                    }
+                }
+                DynamicState::ExclusiveScissor => todo!(),
+                DynamicState::FragmentShadingRate => todo!(),
+                DynamicState::FrontFace => {
+                    // VUID?
+                    if current_state.front_face().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::LineStipple => {
+                    // VUID?
+                    if current_state.line_stipple().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::LineWidth => {
+                    // VUID?
+                    if current_state.line_width().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::LogicOp => {
+                    // VUID-vkCmdDraw-logicOp-04878
+                    if current_state.logic_op().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::PatchControlPoints => {
+                    // VUID-vkCmdDraw-None-04875
+                    if current_state.patch_control_points().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::PrimitiveRestartEnable => {
+                    // Pipelines with a mesh shader have no input assembly state.
+                    let input_assembly_state = match pipeline.input_assembly_state() {
+                        Some(x) => x,
+                        None => continue,
+                    };
+
+                    // VUID-vkCmdDraw-None-04879
+                    let primitive_restart_enable =
+                        if let Some(enable) = current_state.primitive_restart_enable() {
+                            enable
+                        } else {
+                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                        };
+
+                    if primitive_restart_enable {
+                        let topology = match input_assembly_state.topology {
+                            PartialStateMode::Fixed(topology) => topology,
+                            PartialStateMode::Dynamic(_) => {
+                                if let Some(topology) = current_state.primitive_topology() {
+                                    topology
+                                } else {
+                                    return Err(PipelineExecutionError::DynamicStateNotSet {
+                                        dynamic_state: DynamicState::PrimitiveTopology,
+                                    });
+                                }
+                            }
+                        };
+
+                        match topology {
+                            PrimitiveTopology::PointList
+                            | PrimitiveTopology::LineList
+                            | PrimitiveTopology::TriangleList
+                            | PrimitiveTopology::LineListWithAdjacency
+                            | PrimitiveTopology::TriangleListWithAdjacency => {
+                                // VUID?
+                                if !device.enabled_features().primitive_topology_list_restart {
+                                    return Err(PipelineExecutionError::RequirementNotMet {
+                                        required_for: "The bound pipeline sets \
+                                            `DynamicState::PrimitiveRestartEnable` and the \
+                                            current primitive topology is \
+                                            `PrimitiveTopology::*List`",
+                                        requires_one_of: RequiresOneOf {
+                                            features: &["primitive_topology_list_restart"],
+                                            ..Default::default()
+                                        },
+                                    });
+                                }
+                            }
+                            PrimitiveTopology::PatchList => {
+                                // VUID?
+                                if !device
+                                    .enabled_features()
+                                    .primitive_topology_patch_list_restart
+                                {
+                                    return Err(PipelineExecutionError::RequirementNotMet {
+                                        required_for: "The bound pipeline sets \
+                                            `DynamicState::PrimitiveRestartEnable` and the \
+                                            current primitive topology is \
+                                            `PrimitiveTopology::PatchList`",
+                                        requires_one_of: RequiresOneOf {
+                                            features: &["primitive_topology_patch_list_restart"],
+                                            ..Default::default()
+                                        },
+                                    });
+                                }
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                DynamicState::PrimitiveTopology => {
+                    let input_assembly_state = match pipeline.input_assembly_state() {
+                        Some(x) => x,
+                        None => continue,
+                    };
+
+                    // VUID-vkCmdDraw-primitiveTopology-03420
+                    let topology = if let Some(topology) = current_state.primitive_topology() {
+                        topology
+                    } else {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    };
+
+                    if pipeline.shader(ShaderStage::TessellationControl).is_some() {
+                        // VUID?
+                        if !matches!(topology, PrimitiveTopology::PatchList) {
+                            return Err(PipelineExecutionError::DynamicPrimitiveTopologyInvalid {
+                                topology,
+                            });
+                        }
+                    } else {
+                        // VUID?
+                        if matches!(topology, PrimitiveTopology::PatchList) {
+                            return Err(PipelineExecutionError::DynamicPrimitiveTopologyInvalid {
+                                topology,
+                            });
+                        }
+                    }
+
+                    let required_topology_class = match input_assembly_state.topology {
+                        PartialStateMode::Dynamic(topology_class) => topology_class,
+                        _ => unreachable!(),
+                    };
+
+                    // VUID-vkCmdDraw-primitiveTopology-03420
+                    if topology.class() != required_topology_class {
+                        return Err(
+                            PipelineExecutionError::DynamicPrimitiveTopologyClassMismatch {
+                                provided_class: topology.class(),
+                                required_class: required_topology_class,
+                            },
+                        );
+                    }
+
+                    // TODO: check that the topology matches the geometry shader
+                }
+                DynamicState::RasterizerDiscardEnable => {
+                    // VUID-vkCmdDraw-None-04876
+                    if current_state.rasterizer_discard_enable().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::RayTracingPipelineStackSize => unreachable!(
+                    "RayTracingPipelineStackSize dynamic state should not occur on a graphics pipeline"
+                ),
+                DynamicState::SampleLocations => todo!(),
+                DynamicState::Scissor => {
+                    for num in 0..pipeline.viewport_state().unwrap().count().unwrap() {
+                        // VUID?
+                        if current_state.scissor(num).is_none() {
+                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                        }
+                    }
+                }
+                DynamicState::ScissorWithCount => {
+                    // VUID-vkCmdDraw-scissorCount-03418
+                    // VUID-vkCmdDraw-viewportCount-03419
+                    let scissor_count = if let Some(scissors) = current_state.scissor_with_count() {
+                        scissors.len() as u32
+                    } else {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    };
+
+                    // Check if the counts match, but only if the viewport count is fixed.
+                    // If the viewport count is also dynamic, then the
+                    // DynamicState::ViewportWithCount match arm will handle it.
+                    if let Some(viewport_count) = pipeline.viewport_state().unwrap().count() {
+                        // VUID-vkCmdDraw-scissorCount-03418
+                        if viewport_count != scissor_count {
+                            return Err(
+                                PipelineExecutionError::DynamicViewportScissorCountMismatch {
+                                    viewport_count,
+                                    scissor_count,
+                                },
+                            );
+                        }
+                    }
+                }
+                DynamicState::StencilCompareMask => {
+                    let state = current_state.stencil_compare_mask();
+
+                    // VUID?
+                    if state.front.is_none() || state.back.is_none() {
                         return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
                     }
                 }
-                DynamicState::ColorWriteEnable => {
-                    // VUID-vkCmdDraw-attachmentCount-06667
-                    let enables = if let Some(enables) = current_state.color_write_enable() {
-                        enables
+                DynamicState::StencilOp => {
+                    let state = current_state.stencil_op();
+
+                    // VUID?
+                    if state.front.is_none() || state.back.is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::StencilReference => {
+                    let state = current_state.stencil_reference();
+
+                    // VUID?
+                    if state.front.is_none() || state.back.is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::StencilTestEnable => {
+                    // VUID?
+                    if current_state.stencil_test_enable().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+
+                    // TODO: Check if the stencil buffer is writable
+                }
+                DynamicState::StencilWriteMask => {
+                    let state = current_state.stencil_write_mask();
+
+                    // VUID?
+                    if state.front.is_none() || state.back.is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
+                DynamicState::VertexInput => todo!(),
+                DynamicState::VertexInputBindingStride => todo!(),
+                DynamicState::Viewport => {
+                    for num in 0..pipeline.viewport_state().unwrap().count().unwrap() {
+                        // VUID?
+                        if current_state.viewport(num).is_none() {
+                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                        }
+                    }
+                }
+                DynamicState::ViewportCoarseSampleOrder => todo!(),
+                DynamicState::ViewportShadingRatePalette => todo!(),
+                DynamicState::ViewportWithCount => {
+                    // VUID-vkCmdDraw-viewportCount-03417
+                    let viewport_count = if let Some(viewports) = current_state.viewport_with_count() {
+                        viewports.len() as u32
                     } else {
                         return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
                     };
 
-                    // VUID-vkCmdDraw-attachmentCount-06667
-                    if enables.len() < pipeline.color_blend_state().unwrap().attachments.len() {
+                    let scissor_count = if let Some(scissor_count) =
+                        pipeline.viewport_state().unwrap().count()
+                    {
+                        // The scissor count is fixed.
+                        scissor_count
+                    } else {
+                        // VUID-vkCmdDraw-viewportCount-03419
+                        // The scissor count is also dynamic.
+                        if let Some(scissors) = current_state.scissor_with_count() {
+                            scissors.len() as u32
+                        } else {
+                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                        }
+                    };
+
+                    // VUID-vkCmdDraw-viewportCount-03417
+                    // VUID-vkCmdDraw-viewportCount-03419
+                    if viewport_count != scissor_count {
                         return Err(
-                            PipelineExecutionError::DynamicColorWriteEnableNotEnoughValues {
-                                color_write_enable_count: enables.len() as u32,
-                                attachment_count: pipeline
-                                    .color_blend_state()
-                                    .unwrap()
-                                    .attachments
-                                    .len() as u32,
+                            PipelineExecutionError::DynamicViewportScissorCountMismatch {
+                                viewport_count,
+                                scissor_count,
+                            },
+                        );
+                    }
+
+                    // TODO: VUID-vkCmdDrawIndexed-primitiveFragmentShadingRateWithMultipleViewports-04552
+                    // If the primitiveFragmentShadingRateWithMultipleViewports limit is not supported,
+                    // the bound graphics pipeline was created with the
+                    // VK_DYNAMIC_STATE_VIEWPORT_WITH_COUNT_EXT dynamic state enabled, and any of the
+                    // shader stages of the bound graphics pipeline write to the PrimitiveShadingRateKHR
+                    // built-in, then vkCmdSetViewportWithCountEXT must have been called in the current
+                    // command buffer prior to this drawing command, and the viewportCount parameter of
+                    // vkCmdSetViewportWithCountEXT must be 1
+                }
+                DynamicState::ViewportWScaling => todo!(),
+                DynamicState::TessellationDomainOrigin => todo!(),
+                DynamicState::DepthClampEnable => todo!(),
+                DynamicState::PolygonMode => todo!(),
+                DynamicState::RasterizationSamples => todo!(),
+                DynamicState::SampleMask => todo!(),
+                DynamicState::AlphaToCoverageEnable => todo!(),
+                DynamicState::AlphaToOneEnable => todo!(),
+                DynamicState::LogicOpEnable => todo!(),
+                DynamicState::ColorBlendEnable => todo!(),
+                DynamicState::ColorBlendEquation => todo!(),
+                DynamicState::ColorWriteMask => todo!(),
+                DynamicState::RasterizationStream => todo!(),
+                DynamicState::ConservativeRasterizationMode => todo!(),
+                DynamicState::ExtraPrimitiveOverestimationSize => todo!(),
+                DynamicState::DepthClipEnable => todo!(),
+                DynamicState::SampleLocationsEnable => todo!(),
+                DynamicState::ColorBlendAdvanced => todo!(),
+                DynamicState::ProvokingVertexMode => todo!(),
+                DynamicState::LineRasterizationMode => todo!(),
+                DynamicState::LineStippleEnable => todo!(),
+                DynamicState::DepthClipNegativeOneToOne => todo!(),
+                DynamicState::ViewportWScalingEnable => todo!(),
+                DynamicState::ViewportSwizzle => todo!(),
+                DynamicState::CoverageToColorEnable => todo!(),
+                DynamicState::CoverageToColorLocation => todo!(),
+                DynamicState::CoverageModulationMode => todo!(),
+                DynamicState::CoverageModulationTableEnable => todo!(),
+                DynamicState::CoverageModulationTable => todo!(),
+                DynamicState::ShadingRateImageEnable => todo!(),
+                DynamicState::RepresentativeFragmentTestEnable => todo!(),
+                DynamicState::CoverageReductionMode => todo!(),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_pipeline_graphics_render_pass(
+        &self,
+        pipeline: &GraphicsPipeline,
+        render_pass_state: &RenderPassState,
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID?
+        if render_pass_state.contents != SubpassContents::Inline {
+            return Err(PipelineExecutionError::ForbiddenWithSubpassContents {
+                subpass_contents: render_pass_state.contents,
+            });
+        }
+
+        match (&render_pass_state.render_pass, pipeline.subpass()) {
+            (
+                RenderPassStateType::BeginRenderPass(state),
+                PipelineSubpassType::BeginRenderPass(pipeline_subpass),
+            ) => {
+                // VUID-vkCmdDraw-renderPass-02684
+                if !pipeline_subpass
+                    .render_pass()
+                    .is_compatible_with(state.subpass.render_pass())
+                {
+                    return Err(PipelineExecutionError::PipelineRenderPassNotCompatible);
+                }
+
+                // VUID-vkCmdDraw-subpass-02685
+                if pipeline_subpass.index() != state.subpass.index() {
+                    return Err(PipelineExecutionError::PipelineSubpassMismatch {
+                        pipeline: pipeline_subpass.index(),
+                        current: state.subpass.index(),
+                    });
+                }
+            }
+            (
+                RenderPassStateType::BeginRendering(current_rendering_info),
+                PipelineSubpassType::BeginRendering(pipeline_rendering_info),
+            ) => {
+                // VUID-vkCmdDraw-viewMask-06178
+                if pipeline_rendering_info.view_mask != render_pass_state.view_mask {
+                    return Err(PipelineExecutionError::PipelineViewMaskMismatch {
+                        pipeline_view_mask: pipeline_rendering_info.view_mask,
+                        required_view_mask: render_pass_state.view_mask,
+                    });
+                }
+
+                // VUID-vkCmdDraw-colorAttachmentCount-06179
+                if pipeline_rendering_info.color_attachment_formats.len()
+                    != current_rendering_info.color_attachment_formats.len()
+                {
+                    return Err(
+                        PipelineExecutionError::PipelineColorAttachmentCountMismatch {
+                            pipeline_count: pipeline_rendering_info.color_attachment_formats.len()
+                                as u32,
+                            required_count: current_rendering_info.color_attachment_formats.len()
+                                as u32,
+                        },
+                    );
+                }
+
+                for (color_attachment_index, required_format, pipeline_format) in
+                    current_rendering_info
+                        .color_attachment_formats
+                        .iter()
+                        .zip(
+                            pipeline_rendering_info
+                                .color_attachment_formats
+                                .iter()
+                                .copied(),
+                        )
+                        .enumerate()
+                        .filter_map(|(i, (r, p))| r.map(|r| (i as u32, r, p)))
+                {
+                    // VUID-vkCmdDraw-colorAttachmentCount-06180
+                    if Some(required_format) != pipeline_format {
+                        return Err(
+                            PipelineExecutionError::PipelineColorAttachmentFormatMismatch {
+                                color_attachment_index,
+                                pipeline_format,
+                                required_format,
+                            },
+                        );
+                    }
+                }
+
+                if let Some((required_format, pipeline_format)) = current_rendering_info
+                    .depth_attachment_format
+                    .map(|r| (r, pipeline_rendering_info.depth_attachment_format))
+                {
+                    // VUID-vkCmdDraw-pDepthAttachment-06181
+                    if Some(required_format) != pipeline_format {
+                        return Err(
+                            PipelineExecutionError::PipelineDepthAttachmentFormatMismatch {
+                                pipeline_format,
+                                required_format,
                             },
                         );
                     }
                 }
-                DynamicState::CullMode => {
-                    // VUID?
-                    if current_state.cull_mode().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::DepthBias => {
-                    // VUID?
-                    if current_state.depth_bias().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::DepthBiasEnable => {
-                    // VUID-vkCmdDraw-None-04877
-                    if current_state.depth_bias_enable().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+
+                if let Some((required_format, pipeline_format)) = current_rendering_info
+                    .stencil_attachment_format
+                    .map(|r| (r, pipeline_rendering_info.stencil_attachment_format))
+                {
+                    // VUID-vkCmdDraw-pStencilAttachment-06182
+                    if Some(required_format) != pipeline_format {
+                        return Err(
+                            PipelineExecutionError::PipelineStencilAttachmentFormatMismatch {
+                                pipeline_format,
+                                required_format,
+                            },
+                        );
                     }
                 }
-                DynamicState::DepthBounds => {
-                    // VUID?
-                    if current_state.depth_bounds().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
+
+                // VUID-vkCmdDraw-imageView-06172
+                // VUID-vkCmdDraw-imageView-06173
+                // VUID-vkCmdDraw-imageView-06174
+                // VUID-vkCmdDraw-imageView-06175
+                // VUID-vkCmdDraw-imageView-06176
+                // VUID-vkCmdDraw-imageView-06177
+                // TODO:
+            }
+            _ => return Err(PipelineExecutionError::PipelineRenderPassTypeMismatch),
+        }
+
+        // VUID-vkCmdDraw-None-02686
+        // TODO:
+
+        Ok(())
+    }
+
+    fn validate_pipeline_graphics_vertex_buffers(
+        &self,
+        pipeline: &GraphicsPipeline,
+        vertices: Option<(u32, u32)>,
+        instances: Option<(u32, u32)>,
+    ) -> Result<(), PipelineExecutionError> {
+        let vertex_input_bindings = pipeline
+            .vertex_input_state()
+            .into_iter()
+            .flat_map(|state| &state.bindings);
+        let mut vertices_in_buffers: Option<u64> = None;
+        let mut instances_in_buffers: Option<u64> = None;
+        let current_state = self.state();
+
+        for (&binding_num, binding_desc) in vertex_input_bindings {
+            // VUID-vkCmdDraw-None-04007
+            let vertex_buffer = match current_state.vertex_buffer(binding_num) {
+                Some(x) => x,
+                None => return Err(PipelineExecutionError::VertexBufferNotBound { binding_num }),
+            };
+
+            let mut num_elements = vertex_buffer.size() / binding_desc.stride as u64;
+
+            match binding_desc.input_rate {
+                VertexInputRate::Vertex => {
+                    vertices_in_buffers = Some(if let Some(x) = vertices_in_buffers {
+                        min(x, num_elements)
+                    } else {
+                        num_elements
+                    });
                 }
-                DynamicState::DepthBoundsTestEnable => {
-                    // VUID?
-                    if current_state.depth_bounds_test_enable().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                VertexInputRate::Instance { divisor } => {
+                    if divisor == 0 {
+                        // A divisor of 0 means the same instance data is used for all instances,
+                        // so we can draw any number of instances from a single element.
+                        // The buffer must contain at least one element though.
+                        if num_elements != 0 {
+                            num_elements = u64::MAX;
+                        }
+                    } else {
+                        // If divisor is e.g. 2, we use only half the amount of data from the source
+                        // buffer, so the number of instances that can be drawn is twice as large.
+                        num_elements = num_elements.saturating_mul(divisor as u64);
                     }
+
+                    instances_in_buffers = Some(if let Some(x) = instances_in_buffers {
+                        min(x, num_elements)
+                    } else {
+                        num_elements
+                    });
                 }
-                DynamicState::DepthCompareOp => {
-                    // VUID?
-                    if current_state.depth_compare_op().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
+            };
+        }
+
+        if let Some((first_vertex, vertex_count)) = vertices {
+            let vertices_needed = first_vertex as u64 + vertex_count as u64;
+
+            if let Some(vertices_in_buffers) = vertices_in_buffers {
+                // VUID-vkCmdDraw-None-02721
+                if vertices_needed > vertices_in_buffers {
+                    return Err(PipelineExecutionError::VertexBufferVertexRangeOutOfBounds {
+                        vertices_needed,
+                        vertices_in_buffers,
+                    });
                 }
-                DynamicState::DepthTestEnable => {
-                    // VUID?
-                    if current_state.depth_test_enable().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
+            }
+        }
+
+        if let Some((first_instance, instance_count)) = instances {
+            let instances_needed = first_instance as u64 + instance_count as u64;
+
+            if let Some(instances_in_buffers) = instances_in_buffers {
+                // VUID-vkCmdDraw-None-02721
+                if instances_needed > instances_in_buffers {
+                    return Err(
+                        PipelineExecutionError::VertexBufferInstanceRangeOutOfBounds {
+                            instances_needed,
+                            instances_in_buffers,
+                        },
+                    );
                 }
-                DynamicState::DepthWriteEnable => {
-                    // VUID?
-                    if current_state.depth_write_enable().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
+            }
 
-                    // TODO: Check if the depth buffer is writable
+            let view_mask = match pipeline.subpass() {
+                PipelineSubpassType::BeginRenderPass(subpass) => subpass.render_pass().views_used(),
+                PipelineSubpassType::BeginRendering(rendering_info) => rendering_info.view_mask,
+            };
+
+            if view_mask != 0 {
+                let max = pipeline
+                    .device()
+                    .physical_device()
+                    .properties()
+                    .max_multiview_instance_index
+                    .unwrap_or(0);
+
+                let highest_instance = instances_needed.saturating_sub(1);
+
+                // VUID-vkCmdDraw-maxMultiviewInstanceIndex-02688
+                if highest_instance > max as u64 {
+                    return Err(PipelineExecutionError::MaxMultiviewInstanceIndexExceeded {
+                        highest_instance,
+                        max,
+                    });
                 }
-                DynamicState::DiscardRectangle => {
-                    let discard_rectangle_count =
-                        match pipeline.discard_rectangle_state().unwrap().rectangles {
-                            PartialStateMode::Dynamic(count) => count,
-                            _ => unreachable!(),
-                        };
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SyncCommandBufferBuilder {
+    /// Calls `vkCmdDispatch` on the builder.
+    #[inline]
+    pub unsafe fn dispatch(
+        &mut self,
+        group_counts: [u32; 3],
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            group_counts: [u32; 3],
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "dispatch"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.dispatch(self.group_counts);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "dispatch";
+        let pipeline = self
+            .current_state
+            .pipeline_compute
+            .as_ref()
+            .unwrap()
+            .as_ref();
+
+        let mut resources = Vec::new();
+        self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd { group_counts }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdDispatchIndirect` on the builder.
+    #[inline]
+    pub unsafe fn dispatch_indirect(
+        &mut self,
+        indirect_buffer: Subbuffer<[DispatchIndirectCommand]>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            indirect_buffer: Subbuffer<[DispatchIndirectCommand]>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "dispatch_indirect"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.dispatch_indirect(&self.indirect_buffer);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "dispatch_indirect";
+        let pipeline = self
+            .current_state
+            .pipeline_compute
+            .as_ref()
+            .unwrap()
+            .as_ref();
+
+        let mut resources = Vec::new();
+        self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_indirect_buffer(
+            &mut resources,
+            command_index,
+            command_name,
+            indirect_buffer.as_bytes(),
+        );
 
-                    for num in 0..discard_rectangle_count {
-                        // VUID?
-                        if current_state.discard_rectangle(num).is_none() {
-                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                        }
-                    }
-                }
-                DynamicState::ExclusiveScissor => todo!(),
-                DynamicState::FragmentShadingRate => todo!(),
-                DynamicState::FrontFace => {
-                    // VUID?
-                    if current_state.front_face().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::LineStipple => {
-                    // VUID?
-                    if current_state.line_stipple().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::LineWidth => {
-                    // VUID?
-                    if current_state.line_width().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::LogicOp => {
-                    // VUID-vkCmdDraw-logicOp-04878
-                    if current_state.logic_op().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::PatchControlPoints => {
-                    // VUID-vkCmdDraw-None-04875
-                    if current_state.patch_control_points().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::PrimitiveRestartEnable => {
-                    // VUID-vkCmdDraw-None-04879
-                    let primitive_restart_enable =
-                        if let Some(enable) = current_state.primitive_restart_enable() {
-                            enable
-                        } else {
-                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                        };
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
 
-                    if primitive_restart_enable {
-                        let topology = match pipeline.input_assembly_state().topology {
-                            PartialStateMode::Fixed(topology) => topology,
-                            PartialStateMode::Dynamic(_) => {
-                                if let Some(topology) = current_state.primitive_topology() {
-                                    topology
-                                } else {
-                                    return Err(PipelineExecutionError::DynamicStateNotSet {
-                                        dynamic_state: DynamicState::PrimitiveTopology,
-                                    });
-                                }
-                            }
-                        };
+        self.commands.push(Box::new(Cmd { indirect_buffer }));
 
-                        match topology {
-                            PrimitiveTopology::PointList
-                            | PrimitiveTopology::LineList
-                            | PrimitiveTopology::TriangleList
-                            | PrimitiveTopology::LineListWithAdjacency
-                            | PrimitiveTopology::TriangleListWithAdjacency => {
-                                // VUID?
-                                if !device.enabled_features().primitive_topology_list_restart {
-                                    return Err(PipelineExecutionError::RequirementNotMet {
-                                        required_for: "The bound pipeline sets \
-                                            `DynamicState::PrimitiveRestartEnable` and the \
-                                            current primitive topology is \
-                                            `PrimitiveTopology::*List`",
-                                        requires_one_of: RequiresOneOf {
-                                            features: &["primitive_topology_list_restart"],
-                                            ..Default::default()
-                                        },
-                                    });
-                                }
-                            }
-                            PrimitiveTopology::PatchList => {
-                                // VUID?
-                                if !device
-                                    .enabled_features()
-                                    .primitive_topology_patch_list_restart
-                                {
-                                    return Err(PipelineExecutionError::RequirementNotMet {
-                                        required_for: "The bound pipeline sets \
-                                            `DynamicState::PrimitiveRestartEnable` and the \
-                                            current primitive topology is \
-                                            `PrimitiveTopology::PatchList`",
-                                        requires_one_of: RequiresOneOf {
-                                            features: &["primitive_topology_patch_list_restart"],
-                                            ..Default::default()
-                                        },
-                                    });
-                                }
-                            }
-                            _ => (),
-                        }
-                    }
-                }
-                DynamicState::PrimitiveTopology => {
-                    // VUID-vkCmdDraw-primitiveTopology-03420
-                    let topology = if let Some(topology) = current_state.primitive_topology() {
-                        topology
-                    } else {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    };
+        for resource in resources {
+            self.add_resource(resource);
+        }
 
-                    if pipeline.shader(ShaderStage::TessellationControl).is_some() {
-                        // VUID?
-                        if !matches!(topology, PrimitiveTopology::PatchList) {
-                            return Err(PipelineExecutionError::DynamicPrimitiveTopologyInvalid {
-                                topology,
-                            });
-                        }
-                    } else {
-                        // VUID?
-                        if matches!(topology, PrimitiveTopology::PatchList) {
-                            return Err(PipelineExecutionError::DynamicPrimitiveTopologyInvalid {
-                                topology,
-                            });
-                        }
-                    }
+        Ok(())
+    }
 
-                    let required_topology_class = match pipeline.input_assembly_state().topology {
-                        PartialStateMode::Dynamic(topology_class) => topology_class,
-                        _ => unreachable!(),
-                    };
+    /// Calls `vkCmdTraceRaysKHR` on the builder.
+    #[inline]
+    pub unsafe fn trace_rays(
+        &mut self,
+        shader_binding_table: ShaderBindingTable,
+        dimensions: [u32; 3],
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            shader_binding_table: ShaderBindingTable,
+            dimensions: [u32; 3],
+        }
 
-                    // VUID-vkCmdDraw-primitiveTopology-03420
-                    if topology.class() != required_topology_class {
-                        return Err(
-                            PipelineExecutionError::DynamicPrimitiveTopologyClassMismatch {
-                                provided_class: topology.class(),
-                                required_class: required_topology_class,
-                            },
-                        );
-                    }
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "trace_rays"
+            }
 
-                    // TODO: check that the topology matches the geometry shader
-                }
-                DynamicState::RasterizerDiscardEnable => {
-                    // VUID-vkCmdDraw-None-04876
-                    if current_state.rasterizer_discard_enable().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::RayTracingPipelineStackSize => unreachable!(
-                    "RayTracingPipelineStackSize dynamic state should not occur on a graphics pipeline"
-                ),
-                DynamicState::SampleLocations => todo!(),
-                DynamicState::Scissor => {
-                    for num in 0..pipeline.viewport_state().unwrap().count().unwrap() {
-                        // VUID?
-                        if current_state.scissor(num).is_none() {
-                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                        }
-                    }
-                }
-                DynamicState::ScissorWithCount => {
-                    // VUID-vkCmdDraw-scissorCount-03418
-                    // VUID-vkCmdDraw-viewportCount-03419
-                    let scissor_count = if let Some(scissors) = current_state.scissor_with_count() {
-                        scissors.len() as u32
-                    } else {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    };
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.trace_rays(self.shader_binding_table.addresses(), self.dimensions);
+            }
+        }
 
-                    // Check if the counts match, but only if the viewport count is fixed.
-                    // If the viewport count is also dynamic, then the
-                    // DynamicState::ViewportWithCount match arm will handle it.
-                    if let Some(viewport_count) = pipeline.viewport_state().unwrap().count() {
-                        // VUID-vkCmdDraw-scissorCount-03418
-                        if viewport_count != scissor_count {
-                            return Err(
-                                PipelineExecutionError::DynamicViewportScissorCountMismatch {
-                                    viewport_count,
-                                    scissor_count,
-                                },
-                            );
-                        }
-                    }
-                }
-                DynamicState::StencilCompareMask => {
-                    let state = current_state.stencil_compare_mask();
+        let command_index = self.commands.len();
+        let command_name = "trace_rays";
+        let pipeline = self
+            .current_state
+            .pipeline_ray_tracing
+            .as_ref()
+            .unwrap()
+            .as_ref();
 
-                    // VUID?
-                    if state.front.is_none() || state.back.is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::StencilOp => {
-                    let state = current_state.stencil_op();
+        let mut resources = Vec::new();
+        self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_shader_binding_table(
+            &mut resources,
+            command_index,
+            command_name,
+            &shader_binding_table,
+        );
 
-                    // VUID?
-                    if state.front.is_none() || state.back.is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::StencilReference => {
-                    let state = current_state.stencil_reference();
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
 
-                    // VUID?
-                    if state.front.is_none() || state.back.is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::StencilTestEnable => {
-                    // VUID?
-                    if current_state.stencil_test_enable().is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
+        self.commands.push(Box::new(Cmd {
+            shader_binding_table,
+            dimensions,
+        }));
 
-                    // TODO: Check if the stencil buffer is writable
-                }
-                DynamicState::StencilWriteMask => {
-                    let state = current_state.stencil_write_mask();
+        for resource in resources {
+            self.add_resource(resource);
+        }
 
-                    // VUID?
-                    if state.front.is_none() || state.back.is_none() {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    }
-                }
-                DynamicState::VertexInput => todo!(),
-                DynamicState::VertexInputBindingStride => todo!(),
-                DynamicState::Viewport => {
-                    for num in 0..pipeline.viewport_state().unwrap().count().unwrap() {
-                        // VUID?
-                        if current_state.viewport(num).is_none() {
-                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                        }
-                    }
-                }
-                DynamicState::ViewportCoarseSampleOrder => todo!(),
-                DynamicState::ViewportShadingRatePalette => todo!(),
-                DynamicState::ViewportWithCount => {
-                    // VUID-vkCmdDraw-viewportCount-03417
-                    let viewport_count = if let Some(viewports) = current_state.viewport_with_count() {
-                        viewports.len() as u32
-                    } else {
-                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                    };
+        Ok(())
+    }
 
-                    let scissor_count = if let Some(scissor_count) =
-                        pipeline.viewport_state().unwrap().count()
-                    {
-                        // The scissor count is fixed.
-                        scissor_count
-                    } else {
-                        // VUID-vkCmdDraw-viewportCount-03419
-                        // The scissor count is also dynamic.
-                        if let Some(scissors) = current_state.scissor_with_count() {
-                            scissors.len() as u32
-                        } else {
-                            return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
-                        }
-                    };
+    /// Calls `vkCmdTraceRaysIndirectKHR` on the builder.
+    #[inline]
+    pub unsafe fn trace_rays_indirect(
+        &mut self,
+        shader_binding_table: ShaderBindingTable,
+        indirect_buffer: Subbuffer<TraceRaysIndirectCommand>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            shader_binding_table: ShaderBindingTable,
+            indirect_buffer: Subbuffer<TraceRaysIndirectCommand>,
+        }
 
-                    // VUID-vkCmdDraw-viewportCount-03417
-                    // VUID-vkCmdDraw-viewportCount-03419
-                    if viewport_count != scissor_count {
-                        return Err(
-                            PipelineExecutionError::DynamicViewportScissorCountMismatch {
-                                viewport_count,
-                                scissor_count,
-                            },
-                        );
-                    }
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "trace_rays_indirect"
+            }
 
-                    // TODO: VUID-vkCmdDrawIndexed-primitiveFragmentShadingRateWithMultipleViewports-04552
-                    // If the primitiveFragmentShadingRateWithMultipleViewports limit is not supported,
-                    // the bound graphics pipeline was created with the
-                    // VK_DYNAMIC_STATE_VIEWPORT_WITH_COUNT_EXT dynamic state enabled, and any of the
-                    // shader stages of the bound graphics pipeline write to the PrimitiveShadingRateKHR
-                    // built-in, then vkCmdSetViewportWithCountEXT must have been called in the current
-                    // command buffer prior to this drawing command, and the viewportCount parameter of
-                    // vkCmdSetViewportWithCountEXT must be 1
-                }
-                DynamicState::ViewportWScaling => todo!(),
-                DynamicState::TessellationDomainOrigin => todo!(),
-                DynamicState::DepthClampEnable => todo!(),
-                DynamicState::PolygonMode => todo!(),
-                DynamicState::RasterizationSamples => todo!(),
-                DynamicState::SampleMask => todo!(),
-                DynamicState::AlphaToCoverageEnable => todo!(),
-                DynamicState::AlphaToOneEnable => todo!(),
-                DynamicState::LogicOpEnable => todo!(),
-                DynamicState::ColorBlendEnable => todo!(),
-                DynamicState::ColorBlendEquation => todo!(),
-                DynamicState::ColorWriteMask => todo!(),
-                DynamicState::RasterizationStream => todo!(),
-                DynamicState::ConservativeRasterizationMode => todo!(),
-                DynamicState::ExtraPrimitiveOverestimationSize => todo!(),
-                DynamicState::DepthClipEnable => todo!(),
-                DynamicState::SampleLocationsEnable => todo!(),
-                DynamicState::ColorBlendAdvanced => todo!(),
-                DynamicState::ProvokingVertexMode => todo!(),
-                DynamicState::LineRasterizationMode => todo!(),
-                DynamicState::LineStippleEnable => todo!(),
-                DynamicState::DepthClipNegativeOneToOne => todo!(),
-                DynamicState::ViewportWScalingEnable => todo!(),
-                DynamicState::ViewportSwizzle => todo!(),
-                DynamicState::CoverageToColorEnable => todo!(),
-                DynamicState::CoverageToColorLocation => todo!(),
-                DynamicState::CoverageModulationMode => todo!(),
-                DynamicState::CoverageModulationTableEnable => todo!(),
-                DynamicState::CoverageModulationTable => todo!(),
-                DynamicState::ShadingRateImageEnable => todo!(),
-                DynamicState::RepresentativeFragmentTestEnable => todo!(),
-                DynamicState::CoverageReductionMode => todo!(),
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.trace_rays_indirect(
+                    self.shader_binding_table.addresses(),
+                    &self.indirect_buffer,
+                );
             }
         }
 
+        let command_index = self.commands.len();
+        let command_name = "trace_rays_indirect";
+        let pipeline = self
+            .current_state
+            .pipeline_ray_tracing
+            .as_ref()
+            .unwrap()
+            .as_ref();
+
+        let mut resources = Vec::new();
+        self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_shader_binding_table(
+            &mut resources,
+            command_index,
+            command_name,
+            &shader_binding_table,
+        );
+        self.add_indirect_buffer(
+            &mut resources,
+            command_index,
+            command_name,
+            indirect_buffer.as_bytes(),
+        );
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            shader_binding_table,
+            indirect_buffer,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
         Ok(())
     }
 
-    fn validate_pipeline_graphics_render_pass(
-        &self,
-        pipeline: &GraphicsPipeline,
-        render_pass_state: &RenderPassState,
-    ) -> Result<(), PipelineExecutionError> {
-        // VUID?
-        if render_pass_state.contents != SubpassContents::Inline {
-            return Err(PipelineExecutionError::ForbiddenWithSubpassContents {
-                subpass_contents: render_pass_state.contents,
-            });
+    /// Calls `vkCmdDraw` on the builder.
+    #[inline]
+    pub unsafe fn draw(
+        &mut self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            vertex_count: u32,
+            instance_count: u32,
+            first_vertex: u32,
+            first_instance: u32,
         }
 
-        match (&render_pass_state.render_pass, pipeline.subpass()) {
-            (
-                RenderPassStateType::BeginRenderPass(state),
-                PipelineSubpassType::BeginRenderPass(pipeline_subpass),
-            ) => {
-                // VUID-vkCmdDraw-renderPass-02684
-                if !pipeline_subpass
-                    .render_pass()
-                    .is_compatible_with(state.subpass.render_pass())
-                {
-                    return Err(PipelineExecutionError::PipelineRenderPassNotCompatible);
-                }
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "draw"
+            }
 
-                // VUID-vkCmdDraw-subpass-02685
-                if pipeline_subpass.index() != state.subpass.index() {
-                    return Err(PipelineExecutionError::PipelineSubpassMismatch {
-                        pipeline: pipeline_subpass.index(),
-                        current: state.subpass.index(),
-                    });
-                }
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.draw(
+                    self.vertex_count,
+                    self.instance_count,
+                    self.first_vertex,
+                    self.first_instance,
+                );
             }
-            (
-                RenderPassStateType::BeginRendering(current_rendering_info),
-                PipelineSubpassType::BeginRendering(pipeline_rendering_info),
-            ) => {
-                // VUID-vkCmdDraw-viewMask-06178
-                if pipeline_rendering_info.view_mask != render_pass_state.view_mask {
-                    return Err(PipelineExecutionError::PipelineViewMaskMismatch {
-                        pipeline_view_mask: pipeline_rendering_info.view_mask,
-                        required_view_mask: render_pass_state.view_mask,
-                    });
-                }
+        }
 
-                // VUID-vkCmdDraw-colorAttachmentCount-06179
-                if pipeline_rendering_info.color_attachment_formats.len()
-                    != current_rendering_info.color_attachment_formats.len()
-                {
-                    return Err(
-                        PipelineExecutionError::PipelineColorAttachmentCountMismatch {
-                            pipeline_count: pipeline_rendering_info.color_attachment_formats.len()
-                                as u32,
-                            required_count: current_rendering_info.color_attachment_formats.len()
-                                as u32,
-                        },
-                    );
-                }
+        let command_index = self.commands.len();
+        let command_name = "draw";
+        let pipeline = self
+            .current_state
+            .pipeline_graphics
+            .as_ref()
+            .unwrap()
+            .as_ref();
 
-                for (color_attachment_index, required_format, pipeline_format) in
-                    current_rendering_info
-                        .color_attachment_formats
-                        .iter()
-                        .zip(
-                            pipeline_rendering_info
-                                .color_attachment_formats
-                                .iter()
-                                .copied(),
-                        )
-                        .enumerate()
-                        .filter_map(|(i, (r, p))| r.map(|r| (i as u32, r, p)))
-                {
-                    // VUID-vkCmdDraw-colorAttachmentCount-06180
-                    if Some(required_format) != pipeline_format {
-                        return Err(
-                            PipelineExecutionError::PipelineColorAttachmentFormatMismatch {
-                                color_attachment_index,
-                                pipeline_format,
-                                required_format,
-                            },
-                        );
-                    }
-                }
+        let mut resources = Vec::new();
+        self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            vertex_count,
+            instance_count,
+            first_vertex,
+            first_instance,
+        }));
 
-                if let Some((required_format, pipeline_format)) = current_rendering_info
-                    .depth_attachment_format
-                    .map(|r| (r, pipeline_rendering_info.depth_attachment_format))
-                {
-                    // VUID-vkCmdDraw-pDepthAttachment-06181
-                    if Some(required_format) != pipeline_format {
-                        return Err(
-                            PipelineExecutionError::PipelineDepthAttachmentFormatMismatch {
-                                pipeline_format,
-                                required_format,
-                            },
-                        );
-                    }
-                }
+        for resource in resources {
+            self.add_resource(resource);
+        }
 
-                if let Some((required_format, pipeline_format)) = current_rendering_info
-                    .stencil_attachment_format
-                    .map(|r| (r, pipeline_rendering_info.stencil_attachment_format))
-                {
-                    // VUID-vkCmdDraw-pStencilAttachment-06182
-                    if Some(required_format) != pipeline_format {
-                        return Err(
-                            PipelineExecutionError::PipelineStencilAttachmentFormatMismatch {
-                                pipeline_format,
-                                required_format,
-                            },
-                        );
-                    }
-                }
+        Ok(())
+    }
 
-                // VUID-vkCmdDraw-imageView-06172
-                // VUID-vkCmdDraw-imageView-06173
-                // VUID-vkCmdDraw-imageView-06174
-                // VUID-vkCmdDraw-imageView-06175
-                // VUID-vkCmdDraw-imageView-06176
-                // VUID-vkCmdDraw-imageView-06177
-                // TODO:
+    /// Calls `vkCmdDrawIndexed` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed(
+        &mut self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            index_count: u32,
+            instance_count: u32,
+            first_index: u32,
+            vertex_offset: i32,
+            first_instance: u32,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "draw_indexed"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.draw_indexed(
+                    self.index_count,
+                    self.instance_count,
+                    self.first_index,
+                    self.vertex_offset,
+                    self.first_instance,
+                );
             }
-            _ => return Err(PipelineExecutionError::PipelineRenderPassTypeMismatch),
         }
 
-        // VUID-vkCmdDraw-None-02686
-        // TODO:
+        let command_index = self.commands.len();
+        let command_name = "draw_indexed";
+        let pipeline = self
+            .current_state
+            .pipeline_graphics
+            .as_ref()
+            .unwrap()
+            .as_ref();
+
+        let mut resources = Vec::new();
+        self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_index_buffer(&mut resources, command_index, command_name);
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            index_count,
+            instance_count,
+            first_index,
+            vertex_offset,
+            first_instance,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
 
         Ok(())
     }
 
-    fn validate_pipeline_graphics_vertex_buffers(
-        &self,
-        pipeline: &GraphicsPipeline,
-        vertices: Option<(u32, u32)>,
-        instances: Option<(u32, u32)>,
-    ) -> Result<(), PipelineExecutionError> {
-        let vertex_input = pipeline.vertex_input_state();
-        let mut vertices_in_buffers: Option<u64> = None;
-        let mut instances_in_buffers: Option<u64> = None;
-        let current_state = self.state();
+    /// Calls `vkCmdDrawIndirect` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawIndirectCommand]>,
+        draw_count: u32,
+        stride: u32,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            indirect_buffer: Subbuffer<[DrawIndirectCommand]>,
+            draw_count: u32,
+            stride: u32,
+        }
 
-        for (&binding_num, binding_desc) in &vertex_input.bindings {
-            // VUID-vkCmdDraw-None-04007
-            let vertex_buffer = match current_state.vertex_buffer(binding_num) {
-                Some(x) => x,
-                None => return Err(PipelineExecutionError::VertexBufferNotBound { binding_num }),
-            };
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "draw_indirect"
+            }
 
-            let mut num_elements = vertex_buffer.size() / binding_desc.stride as u64;
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.draw_indirect(&self.indirect_buffer, self.draw_count, self.stride);
+            }
+        }
 
-            match binding_desc.input_rate {
-                VertexInputRate::Vertex => {
-                    vertices_in_buffers = Some(if let Some(x) = vertices_in_buffers {
-                        min(x, num_elements)
-                    } else {
-                        num_elements
-                    });
-                }
-                VertexInputRate::Instance { divisor } => {
-                    if divisor == 0 {
-                        // A divisor of 0 means the same instance data is used for all instances,
-                        // so we can draw any number of instances from a single element.
-                        // The buffer must contain at least one element though.
-                        if num_elements != 0 {
-                            num_elements = u64::MAX;
-                        }
-                    } else {
-                        // If divisor is e.g. 2, we use only half the amount of data from the source
-                        // buffer, so the number of instances that can be drawn is twice as large.
-                        num_elements = num_elements.saturating_mul(divisor as u64);
-                    }
+        let command_index = self.commands.len();
+        let command_name = "draw_indirect";
+        let pipeline = self
+            .current_state
+            .pipeline_graphics
+            .as_ref()
+            .unwrap()
+            .as_ref();
 
-                    instances_in_buffers = Some(if let Some(x) = instances_in_buffers {
-                        min(x, num_elements)
-                    } else {
-                        num_elements
-                    });
-                }
-            };
+        let mut resources = Vec::new();
+        self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_indirect_buffer(
+            &mut resources,
+            command_index,
+            command_name,
+            indirect_buffer.as_bytes(),
+        );
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
         }
 
-        if let Some((first_vertex, vertex_count)) = vertices {
-            let vertices_needed = first_vertex as u64 + vertex_count as u64;
+        self.commands.push(Box::new(Cmd {
+            indirect_buffer,
+            draw_count,
+            stride,
+        }));
 
-            if let Some(vertices_in_buffers) = vertices_in_buffers {
-                // VUID-vkCmdDraw-None-02721
-                if vertices_needed > vertices_in_buffers {
-                    return Err(PipelineExecutionError::VertexBufferVertexRangeOutOfBounds {
-                        vertices_needed,
-                        vertices_in_buffers,
-                    });
-                }
-            }
+        for resource in resources {
+            self.add_resource(resource);
         }
 
-        if let Some((first_instance, instance_count)) = instances {
-            let instances_needed = first_instance as u64 + instance_count as u64;
+        Ok(())
+    }
 
-            if let Some(instances_in_buffers) = instances_in_buffers {
-                // VUID-vkCmdDraw-None-02721
-                if instances_needed > instances_in_buffers {
-                    return Err(
-                        PipelineExecutionError::VertexBufferInstanceRangeOutOfBounds {
-                            instances_needed,
-                            instances_in_buffers,
-                        },
-                    );
-                }
+    /// Calls `vkCmdDrawIndexedIndirect` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed_indirect(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawIndexedIndirectCommand]>,
+        draw_count: u32,
+        stride: u32,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            indirect_buffer: Subbuffer<[DrawIndexedIndirectCommand]>,
+            draw_count: u32,
+            stride: u32,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "draw_indexed_indirect"
             }
 
-            let view_mask = match pipeline.subpass() {
-                PipelineSubpassType::BeginRenderPass(subpass) => subpass.render_pass().views_used(),
-                PipelineSubpassType::BeginRendering(rendering_info) => rendering_info.view_mask,
-            };
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.draw_indexed_indirect(&self.indirect_buffer, self.draw_count, self.stride);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "draw_indexed_indirect";
+        let pipeline = self
+            .current_state
+            .pipeline_graphics
+            .as_ref()
+            .unwrap()
+            .as_ref();
+
+        let mut resources = Vec::new();
+        self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_index_buffer(&mut resources, command_index, command_name);
+        self.add_indirect_buffer(
+            &mut resources,
+            command_index,
+            command_name,
+            indirect_buffer.as_bytes(),
+        );
 
-            if view_mask != 0 {
-                let max = pipeline
-                    .device()
-                    .physical_device()
-                    .properties()
-                    .max_multiview_instance_index
-                    .unwrap_or(0);
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
 
-                let highest_instance = instances_needed.saturating_sub(1);
+        self.commands.push(Box::new(Cmd {
+            indirect_buffer,
+            draw_count,
+            stride,
+        }));
 
-                // VUID-vkCmdDraw-maxMultiviewInstanceIndex-02688
-                if highest_instance > max as u64 {
-                    return Err(PipelineExecutionError::MaxMultiviewInstanceIndexExceeded {
-                        highest_instance,
-                        max,
-                    });
-                }
-            }
+        for resource in resources {
+            self.add_resource(resource);
         }
 
         Ok(())
     }
-}
 
-impl SyncCommandBufferBuilder {
-    /// Calls `vkCmdDispatch` on the builder.
+    /// Calls `vkCmdDrawIndirectCount` on the builder.
     #[inline]
-    pub unsafe fn dispatch(
+    pub unsafe fn draw_indirect_count(
         &mut self,
-        group_counts: [u32; 3],
+        indirect_buffer: Subbuffer<[DrawIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
+        stride: u32,
     ) -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
-            group_counts: [u32; 3],
+            indirect_buffer: Subbuffer<[DrawIndirectCommand]>,
+            count_buffer: Subbuffer<u32>,
+            max_draw_count: u32,
+            stride: u32,
         }
 
         impl Command for Cmd {
             fn name(&self) -> &'static str {
-                "dispatch"
+                "draw_indirect_count"
             }
 
             unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
-                out.dispatch(self.group_counts);
+                out.draw_indirect_count(
+                    &self.indirect_buffer,
+                    &self.count_buffer,
+                    self.max_draw_count,
+                    self.stride,
+                );
             }
         }
 
         let command_index = self.commands.len();
-        let command_name = "dispatch";
+        let command_name = "draw_indirect_count";
         let pipeline = self
             .current_state
-            .pipeline_compute
+            .pipeline_graphics
             .as_ref()
             .unwrap()
             .as_ref();
 
         let mut resources = Vec::new();
         self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_indirect_buffer(
+            &mut resources,
+            command_index,
+            command_name,
+            indirect_buffer.as_bytes(),
+        );
+        self.add_indirect_count_buffer(
+            &mut resources,
+            command_index,
+            command_name,
+            count_buffer.as_bytes(),
+        );
 
         for resource in &resources {
             self.check_resource_conflicts(resource)?;
         }
 
-        self.commands.push(Box::new(Cmd { group_counts }));
+        self.commands.push(Box::new(Cmd {
+            indirect_buffer,
+            count_buffer,
+            max_draw_count,
+            stride,
+        }));
 
         for resource in resources {
             self.add_resource(resource);
@@ -1707,49 +3174,74 @@ impl SyncCommandBufferBuilder {
         Ok(())
     }
 
-    /// Calls `vkCmdDispatchIndirect` on the builder.
+    /// Calls `vkCmdDrawIndexedIndirectCount` on the builder.
     #[inline]
-    pub unsafe fn dispatch_indirect(
+    pub unsafe fn draw_indexed_indirect_count(
         &mut self,
-        indirect_buffer: Subbuffer<[DispatchIndirectCommand]>,
+        indirect_buffer: Subbuffer<[DrawIndexedIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
+        stride: u32,
     ) -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
-            indirect_buffer: Subbuffer<[DispatchIndirectCommand]>,
+            indirect_buffer: Subbuffer<[DrawIndexedIndirectCommand]>,
+            count_buffer: Subbuffer<u32>,
+            max_draw_count: u32,
+            stride: u32,
         }
 
         impl Command for Cmd {
             fn name(&self) -> &'static str {
-                "dispatch_indirect"
+                "draw_indexed_indirect_count"
             }
 
             unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
-                out.dispatch_indirect(&self.indirect_buffer);
+                out.draw_indexed_indirect_count(
+                    &self.indirect_buffer,
+                    &self.count_buffer,
+                    self.max_draw_count,
+                    self.stride,
+                );
             }
         }
 
         let command_index = self.commands.len();
-        let command_name = "dispatch_indirect";
+        let command_name = "draw_indexed_indirect_count";
         let pipeline = self
             .current_state
-            .pipeline_compute
+            .pipeline_graphics
             .as_ref()
             .unwrap()
             .as_ref();
 
         let mut resources = Vec::new();
         self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_index_buffer(&mut resources, command_index, command_name);
         self.add_indirect_buffer(
             &mut resources,
             command_index,
             command_name,
             indirect_buffer.as_bytes(),
         );
+        self.add_indirect_count_buffer(
+            &mut resources,
+            command_index,
+            command_name,
+            count_buffer.as_bytes(),
+        );
 
         for resource in &resources {
             self.check_resource_conflicts(resource)?;
         }
 
-        self.commands.push(Box::new(Cmd { indirect_buffer }));
+        self.commands.push(Box::new(Cmd {
+            indirect_buffer,
+            count_buffer,
+            max_draw_count,
+            stride,
+        }));
 
         for resource in resources {
             self.add_resource(resource);
@@ -1758,39 +3250,42 @@ impl SyncCommandBufferBuilder {
         Ok(())
     }
 
-    /// Calls `vkCmdDraw` on the builder.
+    /// Calls `vkCmdDrawIndirectByteCountEXT` on the builder.
     #[inline]
-    pub unsafe fn draw(
+    pub unsafe fn draw_indirect_byte_count(
         &mut self,
-        vertex_count: u32,
         instance_count: u32,
-        first_vertex: u32,
         first_instance: u32,
+        counter_buffer: Subbuffer<u32>,
+        counter_offset: u32,
+        vertex_stride: u32,
     ) -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
-            vertex_count: u32,
             instance_count: u32,
-            first_vertex: u32,
             first_instance: u32,
+            counter_buffer: Subbuffer<u32>,
+            counter_offset: u32,
+            vertex_stride: u32,
         }
 
         impl Command for Cmd {
             fn name(&self) -> &'static str {
-                "draw"
+                "draw_indirect_byte_count"
             }
 
             unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
-                out.draw(
-                    self.vertex_count,
+                out.draw_indirect_byte_count(
                     self.instance_count,
-                    self.first_vertex,
                     self.first_instance,
+                    &self.counter_buffer,
+                    self.counter_offset,
+                    self.vertex_stride,
                 );
             }
         }
 
         let command_index = self.commands.len();
-        let command_name = "draw";
+        let command_name = "draw_indirect_byte_count";
         let pipeline = self
             .current_state
             .pipeline_graphics
@@ -1800,17 +3295,25 @@ impl SyncCommandBufferBuilder {
 
         let mut resources = Vec::new();
         self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
         self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_indirect_count_buffer(
+            &mut resources,
+            command_index,
+            command_name,
+            counter_buffer.as_bytes(),
+        );
 
         for resource in &resources {
             self.check_resource_conflicts(resource)?;
         }
 
         self.commands.push(Box::new(Cmd {
-            vertex_count,
             instance_count,
-            first_vertex,
             first_instance,
+            counter_buffer,
+            counter_offset,
+            vertex_stride,
         }));
 
         for resource in resources {
@@ -1820,42 +3323,28 @@ impl SyncCommandBufferBuilder {
         Ok(())
     }
 
-    /// Calls `vkCmdDrawIndexed` on the builder.
+    /// Calls `vkCmdDrawMeshTasksEXT` on the builder.
     #[inline]
-    pub unsafe fn draw_indexed(
+    pub unsafe fn draw_mesh_tasks(
         &mut self,
-        index_count: u32,
-        instance_count: u32,
-        first_index: u32,
-        vertex_offset: i32,
-        first_instance: u32,
+        group_counts: [u32; 3],
     ) -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
-            index_count: u32,
-            instance_count: u32,
-            first_index: u32,
-            vertex_offset: i32,
-            first_instance: u32,
+            group_counts: [u32; 3],
         }
 
         impl Command for Cmd {
             fn name(&self) -> &'static str {
-                "draw_indexed"
+                "draw_mesh_tasks"
             }
 
             unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
-                out.draw_indexed(
-                    self.index_count,
-                    self.instance_count,
-                    self.first_index,
-                    self.vertex_offset,
-                    self.first_instance,
-                );
+                out.draw_mesh_tasks(self.group_counts);
             }
         }
 
         let command_index = self.commands.len();
-        let command_name = "draw_indexed";
+        let command_name = "draw_mesh_tasks";
         let pipeline = self
             .current_state
             .pipeline_graphics
@@ -1865,20 +3354,13 @@ impl SyncCommandBufferBuilder {
 
         let mut resources = Vec::new();
         self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
-        self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
-        self.add_index_buffer(&mut resources, command_index, command_name);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
 
         for resource in &resources {
             self.check_resource_conflicts(resource)?;
         }
 
-        self.commands.push(Box::new(Cmd {
-            index_count,
-            instance_count,
-            first_index,
-            vertex_offset,
-            first_instance,
-        }));
+        self.commands.push(Box::new(Cmd { group_counts }));
 
         for resource in resources {
             self.add_resource(resource);
@@ -1887,32 +3369,32 @@ impl SyncCommandBufferBuilder {
         Ok(())
     }
 
-    /// Calls `vkCmdDrawIndirect` on the builder.
+    /// Calls `vkCmdDrawMeshTasksIndirectEXT` on the builder.
     #[inline]
-    pub unsafe fn draw_indirect(
+    pub unsafe fn draw_mesh_tasks_indirect(
         &mut self,
-        indirect_buffer: Subbuffer<[DrawIndirectCommand]>,
+        indirect_buffer: Subbuffer<[DrawMeshTasksIndirectCommand]>,
         draw_count: u32,
         stride: u32,
     ) -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
-            indirect_buffer: Subbuffer<[DrawIndirectCommand]>,
+            indirect_buffer: Subbuffer<[DrawMeshTasksIndirectCommand]>,
             draw_count: u32,
             stride: u32,
         }
 
         impl Command for Cmd {
             fn name(&self) -> &'static str {
-                "draw_indirect"
+                "draw_mesh_tasks_indirect"
             }
 
             unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
-                out.draw_indirect(&self.indirect_buffer, self.draw_count, self.stride);
+                out.draw_mesh_tasks_indirect(&self.indirect_buffer, self.draw_count, self.stride);
             }
         }
 
         let command_index = self.commands.len();
-        let command_name = "draw_indirect";
+        let command_name = "draw_mesh_tasks_indirect";
         let pipeline = self
             .current_state
             .pipeline_graphics
@@ -1922,7 +3404,7 @@ impl SyncCommandBufferBuilder {
 
         let mut resources = Vec::new();
         self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
-        self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
         self.add_indirect_buffer(
             &mut resources,
             command_index,
@@ -1947,32 +3429,39 @@ impl SyncCommandBufferBuilder {
         Ok(())
     }
 
-    /// Calls `vkCmdDrawIndexedIndirect` on the builder.
+    /// Calls `vkCmdDrawMeshTasksIndirectCountEXT` on the builder.
     #[inline]
-    pub unsafe fn draw_indexed_indirect(
+    pub unsafe fn draw_mesh_tasks_indirect_count(
         &mut self,
-        indirect_buffer: Subbuffer<[DrawIndexedIndirectCommand]>,
-        draw_count: u32,
+        indirect_buffer: Subbuffer<[DrawMeshTasksIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
         stride: u32,
     ) -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
-            indirect_buffer: Subbuffer<[DrawIndexedIndirectCommand]>,
-            draw_count: u32,
+            indirect_buffer: Subbuffer<[DrawMeshTasksIndirectCommand]>,
+            count_buffer: Subbuffer<u32>,
+            max_draw_count: u32,
             stride: u32,
         }
 
         impl Command for Cmd {
             fn name(&self) -> &'static str {
-                "draw_indexed_indirect"
+                "draw_mesh_tasks_indirect_count"
             }
 
             unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
-                out.draw_indexed_indirect(&self.indirect_buffer, self.draw_count, self.stride);
+                out.draw_mesh_tasks_indirect_count(
+                    &self.indirect_buffer,
+                    &self.count_buffer,
+                    self.max_draw_count,
+                    self.stride,
+                );
             }
         }
 
         let command_index = self.commands.len();
-        let command_name = "draw_indexed_indirect";
+        let command_name = "draw_mesh_tasks_indirect_count";
         let pipeline = self
             .current_state
             .pipeline_graphics
@@ -1982,14 +3471,19 @@ impl SyncCommandBufferBuilder {
 
         let mut resources = Vec::new();
         self.add_descriptor_sets(&mut resources, command_index, command_name, pipeline);
-        self.add_vertex_buffers(&mut resources, command_index, command_name, pipeline);
-        self.add_index_buffer(&mut resources, command_index, command_name);
+        self.add_device_address_buffers(&mut resources, command_index, command_name, pipeline);
         self.add_indirect_buffer(
             &mut resources,
             command_index,
             command_name,
             indirect_buffer.as_bytes(),
         );
+        self.add_indirect_count_buffer(
+            &mut resources,
+            command_index,
+            command_name,
+            count_buffer.as_bytes(),
+        );
 
         for resource in &resources {
             self.check_resource_conflicts(resource)?;
@@ -1997,7 +3491,8 @@ impl SyncCommandBufferBuilder {
 
         self.commands.push(Box::new(Cmd {
             indirect_buffer,
-            draw_count,
+            count_buffer,
+            max_draw_count,
             stride,
         }));
 
@@ -2053,6 +3548,9 @@ impl SyncCommandBufferBuilder {
                 DescriptorType::UniformBuffer | DescriptorType::UniformBufferDynamic => {
                     (Some(AccessFlags::UNIFORM_READ), None)
                 }
+                DescriptorType::AccelerationStructure => {
+                    (Some(AccessFlags::ACCELERATION_STRUCTURE_READ), None)
+                }
             };
 
             let memory_iter = move |index: u32| {
@@ -2136,7 +3634,13 @@ impl SyncCommandBufferBuilder {
 
             let descriptor_set_state = &descriptor_sets_state.descriptor_sets[&set];
 
-            match descriptor_set_state.resources().binding(binding).unwrap() {
+            // The resources of descriptor sets in descriptor buffers are not known.
+            let set_resources = match descriptor_set_state.resources() {
+                Some(x) => x,
+                None => continue,
+            };
+
+            match set_resources.binding(binding).unwrap() {
                 DescriptorBindingResources::None(_) => continue,
                 DescriptorBindingResources::Buffer(elements) => {
                     if matches!(
@@ -2218,10 +3722,78 @@ impl SyncCommandBufferBuilder {
                     );
                 }
                 DescriptorBindingResources::Sampler(_) => (),
+                DescriptorBindingResources::AccelerationStructure(elements) => {
+                    resources.extend(
+                        (elements.iter().enumerate())
+                            .filter_map(|(index, element)| {
+                                element.as_ref().map(|acceleration_structure| {
+                                    (
+                                        index as u32,
+                                        acceleration_structure.buffer().clone(),
+                                        0..acceleration_structure.size(),
+                                    )
+                                })
+                            })
+                            .flat_map(buffer_resource),
+                    );
+                }
             }
         }
     }
 
+    fn add_device_address_buffers<Pl: Pipeline>(
+        &self,
+        resources: &mut Vec<(ResourceUseRef, Resource)>,
+        command_index: usize,
+        command_name: &'static str,
+        pipeline: &Pl,
+    ) {
+        let buffers = match self
+            .current_state
+            .device_address_buffers
+            .get(&pipeline.bind_point())
+        {
+            Some(x) => x,
+            None => return,
+        };
+
+        // We can't know which shaders dereference the addresses, or whether they read or write
+        // through them, so be conservative. For graphics pipelines, only the stages that the
+        // pipeline has are named, since the grouping stages such as `PRE_RASTERIZATION_SHADERS`
+        // don't exist without `synchronization2`.
+        let stages = match pipeline.bind_point() {
+            PipelineBindPoint::Compute => PipelineStages::COMPUTE_SHADER,
+            PipelineBindPoint::Graphics => self
+                .current_state
+                .pipeline_graphics
+                .as_ref()
+                .unwrap()
+                .shader_stages()
+                .into(),
+            PipelineBindPoint::RayTracing => PipelineStages::RAY_TRACING_SHADER,
+        };
+
+        resources.extend((0..).zip(buffers).map(|(index, buffer)| {
+            (
+                ResourceUseRef {
+                    command_index,
+                    command_name,
+                    resource_in_command: ResourceInCommand::DeviceAddressBuffer { index },
+                    secondary_use_ref: None,
+                },
+                Resource::Buffer {
+                    buffer: buffer.clone(),
+                    range: 0..buffer.size(),
+                    memory: PipelineMemoryAccess {
+                        stages,
+                        access: AccessFlags::SHADER_READ | AccessFlags::SHADER_WRITE,
+                        exclusive: true,
+                    },
+                },
+            )
+        }));
+    }
+
     fn add_vertex_buffers(
         &self,
         resources: &mut Vec<(ResourceUseRef, Resource)>,
@@ -2232,9 +3804,9 @@ impl SyncCommandBufferBuilder {
         resources.extend(
             pipeline
                 .vertex_input_state()
-                .bindings
-                .iter()
-                .map(|(&binding, _)| {
+                .into_iter()
+                .flat_map(|state| state.bindings.keys())
+                .map(|&binding| {
                     let vertex_buffer = &self.current_state.vertex_buffers[&binding];
                     (
                         ResourceUseRef {
@@ -2256,53 +3828,105 @@ impl SyncCommandBufferBuilder {
                 }),
         );
     }
-
-    fn add_index_buffer(
+
+    fn add_index_buffer(
+        &self,
+        resources: &mut Vec<(ResourceUseRef, Resource)>,
+        command_index: usize,
+        command_name: &'static str,
+    ) {
+        let index_buffer = &self.current_state.index_buffer.as_ref().unwrap().0;
+        resources.push((
+            ResourceUseRef {
+                command_index,
+                command_name,
+                resource_in_command: ResourceInCommand::IndexBuffer,
+                secondary_use_ref: None,
+            },
+            Resource::Buffer {
+                buffer: index_buffer.clone(),
+                range: 0..index_buffer.size(), // TODO:
+                memory: PipelineMemoryAccess {
+                    stages: PipelineStages::VERTEX_INPUT,
+                    access: AccessFlags::INDEX_READ,
+                    exclusive: false,
+                },
+            },
+        ));
+    }
+
+    fn add_indirect_buffer(
+        &self,
+        resources: &mut Vec<(ResourceUseRef, Resource)>,
+        command_index: usize,
+        command_name: &'static str,
+        indirect_buffer: &Subbuffer<[u8]>,
+    ) {
+        resources.push((
+            ResourceUseRef {
+                command_index,
+                command_name,
+                resource_in_command: ResourceInCommand::IndirectBuffer,
+                secondary_use_ref: None,
+            },
+            Resource::Buffer {
+                buffer: indirect_buffer.clone(),
+                range: 0..indirect_buffer.size(), // TODO:
+                memory: PipelineMemoryAccess {
+                    stages: PipelineStages::DRAW_INDIRECT,
+                    access: AccessFlags::INDIRECT_COMMAND_READ,
+                    exclusive: false,
+                },
+            },
+        ));
+    }
+    fn add_indirect_count_buffer(
         &self,
         resources: &mut Vec<(ResourceUseRef, Resource)>,
         command_index: usize,
         command_name: &'static str,
+        count_buffer: &Subbuffer<[u8]>,
     ) {
-        let index_buffer = &self.current_state.index_buffer.as_ref().unwrap().0;
         resources.push((
             ResourceUseRef {
                 command_index,
                 command_name,
-                resource_in_command: ResourceInCommand::IndexBuffer,
+                resource_in_command: ResourceInCommand::IndirectCountBuffer,
                 secondary_use_ref: None,
             },
             Resource::Buffer {
-                buffer: index_buffer.clone(),
-                range: 0..index_buffer.size(), // TODO:
+                buffer: count_buffer.clone(),
+                range: 0..count_buffer.size(),
                 memory: PipelineMemoryAccess {
-                    stages: PipelineStages::VERTEX_INPUT,
-                    access: AccessFlags::INDEX_READ,
+                    stages: PipelineStages::DRAW_INDIRECT,
+                    access: AccessFlags::INDIRECT_COMMAND_READ,
                     exclusive: false,
                 },
             },
         ));
     }
 
-    fn add_indirect_buffer(
+    fn add_shader_binding_table(
         &self,
         resources: &mut Vec<(ResourceUseRef, Resource)>,
         command_index: usize,
         command_name: &'static str,
-        indirect_buffer: &Subbuffer<[u8]>,
+        shader_binding_table: &ShaderBindingTable,
     ) {
+        let buffer = shader_binding_table.buffer();
         resources.push((
             ResourceUseRef {
                 command_index,
                 command_name,
-                resource_in_command: ResourceInCommand::IndirectBuffer,
+                resource_in_command: ResourceInCommand::ShaderBindingTable,
                 secondary_use_ref: None,
             },
             Resource::Buffer {
-                buffer: indirect_buffer.clone(),
-                range: 0..indirect_buffer.size(), // TODO:
+                buffer: buffer.clone(),
+                range: 0..buffer.size(),
                 memory: PipelineMemoryAccess {
-                    stages: PipelineStages::DRAW_INDIRECT,
-                    access: AccessFlags::INDIRECT_COMMAND_READ,
+                    stages: PipelineStages::RAY_TRACING_SHADER,
+                    access: AccessFlags::SHADER_BINDING_TABLE_READ,
                     exclusive: false,
                 },
             },
@@ -2349,6 +3973,59 @@ impl UnsafeCommandBufferBuilder {
         (fns.v1_0.cmd_dispatch_indirect)(self.handle, buffer.buffer().handle(), buffer.offset());
     }
 
+    /// Calls `vkCmdTraceRaysKHR` on the builder.
+    #[inline]
+    pub unsafe fn trace_rays(
+        &mut self,
+        shader_binding_table: &ShaderBindingTableAddresses,
+        dimensions: [u32; 3],
+    ) {
+        let raygen = shader_binding_table.raygen.into();
+        let miss = shader_binding_table.miss.into();
+        let hit = shader_binding_table.hit.into();
+        let callable = shader_binding_table.callable.into();
+
+        let fns = self.device.fns();
+        (fns.khr_ray_tracing_pipeline.cmd_trace_rays_khr)(
+            self.handle,
+            &raygen,
+            &miss,
+            &hit,
+            &callable,
+            dimensions[0],
+            dimensions[1],
+            dimensions[2],
+        );
+    }
+
+    /// Calls `vkCmdTraceRaysIndirectKHR` on the builder.
+    #[inline]
+    pub unsafe fn trace_rays_indirect(
+        &mut self,
+        shader_binding_table: &ShaderBindingTableAddresses,
+        indirect_buffer: &Subbuffer<TraceRaysIndirectCommand>,
+    ) {
+        debug_assert!(indirect_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER));
+
+        let raygen = shader_binding_table.raygen.into();
+        let miss = shader_binding_table.miss.into();
+        let hit = shader_binding_table.hit.into();
+        let callable = shader_binding_table.callable.into();
+
+        let fns = self.device.fns();
+        (fns.khr_ray_tracing_pipeline.cmd_trace_rays_indirect_khr)(
+            self.handle,
+            &raygen,
+            &miss,
+            &hit,
+            &callable,
+            indirect_buffer.device_address().unwrap().get(),
+        );
+    }
+
     /// Calls `vkCmdDraw` on the builder.
     #[inline]
     pub unsafe fn draw(
@@ -2444,6 +4121,216 @@ impl UnsafeCommandBufferBuilder {
             stride,
         );
     }
+
+    /// Calls `vkCmdDrawIndirectCount` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_count(
+        &mut self,
+        buffer: &Subbuffer<[DrawIndirectCommand]>,
+        count_buffer: &Subbuffer<u32>,
+        max_draw_count: u32,
+        stride: u32,
+    ) {
+        let fns = self.device.fns();
+
+        debug_assert!(
+            (stride % 4) == 0 && stride as usize >= size_of::<ash::vk::DrawIndirectCommand>()
+        );
+
+        debug_assert!(buffer.offset() < buffer.buffer().size());
+        debug_assert!(buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER));
+        debug_assert!(count_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER));
+
+        // The core entry point may only be used if the feature is enabled; otherwise validation
+        // passed through the extension.
+        if self.device.enabled_features().draw_indirect_count {
+            (fns.v1_2.cmd_draw_indirect_count)(
+                self.handle,
+                buffer.buffer().handle(),
+                buffer.offset(),
+                count_buffer.buffer().handle(),
+                count_buffer.offset(),
+                max_draw_count,
+                stride,
+            );
+        } else {
+            (fns.khr_draw_indirect_count.cmd_draw_indirect_count_khr)(
+                self.handle,
+                buffer.buffer().handle(),
+                buffer.offset(),
+                count_buffer.buffer().handle(),
+                count_buffer.offset(),
+                max_draw_count,
+                stride,
+            );
+        }
+    }
+
+    /// Calls `vkCmdDrawIndexedIndirectCount` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed_indirect_count(
+        &mut self,
+        buffer: &Subbuffer<[DrawIndexedIndirectCommand]>,
+        count_buffer: &Subbuffer<u32>,
+        max_draw_count: u32,
+        stride: u32,
+    ) {
+        let fns = self.device.fns();
+
+        debug_assert!(
+            (stride % 4) == 0
+                && stride as usize >= size_of::<ash::vk::DrawIndexedIndirectCommand>()
+        );
+
+        debug_assert!(buffer.offset() < buffer.buffer().size());
+        debug_assert!(buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER));
+        debug_assert!(count_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER));
+
+        // The core entry point may only be used if the feature is enabled; otherwise validation
+        // passed through the extension.
+        if self.device.enabled_features().draw_indirect_count {
+            (fns.v1_2.cmd_draw_indexed_indirect_count)(
+                self.handle,
+                buffer.buffer().handle(),
+                buffer.offset(),
+                count_buffer.buffer().handle(),
+                count_buffer.offset(),
+                max_draw_count,
+                stride,
+            );
+        } else {
+            (fns.khr_draw_indirect_count
+                .cmd_draw_indexed_indirect_count_khr)(
+                self.handle,
+                buffer.buffer().handle(),
+                buffer.offset(),
+                count_buffer.buffer().handle(),
+                count_buffer.offset(),
+                max_draw_count,
+                stride,
+            );
+        }
+    }
+
+    /// Calls `vkCmdDrawIndirectByteCountEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_byte_count(
+        &mut self,
+        instance_count: u32,
+        first_instance: u32,
+        counter_buffer: &Subbuffer<u32>,
+        counter_offset: u32,
+        vertex_stride: u32,
+    ) {
+        let fns = self.device.fns();
+
+        debug_assert!(counter_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER));
+
+        (fns.ext_transform_feedback.cmd_draw_indirect_byte_count_ext)(
+            self.handle,
+            instance_count,
+            first_instance,
+            counter_buffer.buffer().handle(),
+            counter_buffer.offset(),
+            counter_offset,
+            vertex_stride,
+        );
+    }
+
+    /// Calls `vkCmdDrawMeshTasksEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_mesh_tasks(&mut self, group_counts: [u32; 3]) {
+        let fns = self.device.fns();
+        (fns.ext_mesh_shader.cmd_draw_mesh_tasks_ext)(
+            self.handle,
+            group_counts[0],
+            group_counts[1],
+            group_counts[2],
+        );
+    }
+
+    /// Calls `vkCmdDrawMeshTasksIndirectEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_mesh_tasks_indirect(
+        &mut self,
+        buffer: &Subbuffer<[DrawMeshTasksIndirectCommand]>,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        let fns = self.device.fns();
+
+        debug_assert!(
+            draw_count == 0
+                || ((stride % 4) == 0)
+                    && stride as usize >= size_of::<ash::vk::DrawMeshTasksIndirectCommandEXT>()
+        );
+
+        debug_assert!(buffer.offset() < buffer.buffer().size());
+        debug_assert!(buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER));
+
+        (fns.ext_mesh_shader.cmd_draw_mesh_tasks_indirect_ext)(
+            self.handle,
+            buffer.buffer().handle(),
+            buffer.offset(),
+            draw_count,
+            stride,
+        );
+    }
+
+    /// Calls `vkCmdDrawMeshTasksIndirectCountEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_mesh_tasks_indirect_count(
+        &mut self,
+        buffer: &Subbuffer<[DrawMeshTasksIndirectCommand]>,
+        count_buffer: &Subbuffer<u32>,
+        max_draw_count: u32,
+        stride: u32,
+    ) {
+        let fns = self.device.fns();
+
+        debug_assert!(
+            (stride % 4) == 0
+                && stride as usize >= size_of::<ash::vk::DrawMeshTasksIndirectCommandEXT>()
+        );
+
+        debug_assert!(buffer.offset() < buffer.buffer().size());
+        debug_assert!(buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER));
+        debug_assert!(count_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER));
+
+        (fns.ext_mesh_shader.cmd_draw_mesh_tasks_indirect_count_ext)(
+            self.handle,
+            buffer.buffer().handle(),
+            buffer.offset(),
+            count_buffer.buffer().handle(),
+            count_buffer.offset(),
+            max_draw_count,
+            stride,
+        );
+    }
 }
 
 /// Error that can happen when recording a bound pipeline execution command.
@@ -2523,9 +4410,27 @@ pub enum PipelineExecutionError {
         max_index_count: u32,
     },
 
+    /// The `max_draw_count` of an indirect count draw command exceeds the number of commands
+    /// in the indirect buffer.
+    IndirectBufferDrawCountOutOfBounds {
+        max_draw_count: u32,
+        commands_in_buffer: DeviceSize,
+    },
+
     /// The `indirect_buffer` usage was not enabled on the indirect buffer.
     IndirectBufferMissingUsage,
 
+    /// The `shader_device_address` usage was not enabled on the indirect buffer.
+    IndirectBufferMissingShaderDeviceAddressUsage,
+
+    /// The `indirect_buffer` usage was not enabled on the count buffer.
+    IndirectCountBufferMissingUsage,
+
+    /// The offset of the count buffer is not a multiple of 4.
+    IndirectCountBufferOffsetNotAligned {
+        offset: DeviceSize,
+    },
+
     /// The `max_compute_work_group_count` limit has been exceeded.
     MaxComputeWorkGroupCountExceeded {
         requested: [u32; 3],
@@ -2538,12 +4443,48 @@ pub enum PipelineExecutionError {
         max: u32,
     },
 
+    /// The `max_mesh_work_group_count` limit has been exceeded.
+    MaxMeshWorkGroupCountExceeded {
+        requested: [u32; 3],
+        max: [u32; 3],
+    },
+
+    /// The `max_mesh_work_group_total_count` limit has been exceeded.
+    MaxMeshWorkGroupTotalCountExceeded {
+        requested: u64,
+        max: u32,
+    },
+
     /// The `max_multiview_instance_index` limit has been exceeded.
     MaxMultiviewInstanceIndexExceeded {
         highest_instance: u64,
         max: u32,
     },
 
+    /// The `max_ray_dispatch_invocation_count` limit has been exceeded.
+    MaxRayDispatchInvocationCountExceeded {
+        provided: u64,
+        max: u32,
+    },
+
+    /// The `max_task_work_group_count` limit has been exceeded.
+    MaxTaskWorkGroupCountExceeded {
+        requested: [u32; 3],
+        max: [u32; 3],
+    },
+
+    /// The `max_task_work_group_total_count` limit has been exceeded.
+    MaxTaskWorkGroupTotalCountExceeded {
+        requested: u64,
+        max: u32,
+    },
+
+    /// The vertex stride exceeds the `max_transform_feedback_buffer_data_stride` limit.
+    MaxTransformFeedbackBufferDataStrideExceeded {
+        vertex_stride: u32,
+        max: u32,
+    },
+
     /// The queue family doesn't allow this operation.
     NotSupportedByQueueFamily,
 
@@ -2572,6 +4513,14 @@ pub enum PipelineExecutionError {
     /// The bound pipeline is not compatible with the layout used to bind the descriptor sets.
     PipelineLayoutNotCompatible,
 
+    /// The operation requires a graphics pipeline with a mesh shader, but the bound graphics
+    /// pipeline does not have one.
+    PipelineMeshShaderMissing,
+
+    /// The operation requires a graphics pipeline without a mesh shader, but the bound graphics
+    /// pipeline has one.
+    PipelineMeshShaderNotAllowed,
+
     /// No pipeline was bound to the bind point used by the operation.
     PipelineNotBound,
 
@@ -2609,6 +4558,17 @@ pub enum PipelineExecutionError {
     /// Not all push constants used by the pipeline have been set.
     PushConstantsMissing,
 
+    /// The dimensions of a ray tracing command exceed the product of the
+    /// `max_compute_work_group_count` and `max_compute_work_group_size` limits.
+    RayDispatchDimensionsExceeded {
+        requested: [u32; 3],
+        max: [u64; 3],
+    },
+
+    /// The [`transform_feedback_draw`](crate::device::Properties::transform_feedback_draw) device
+    /// property was `false`.
+    TransformFeedbackDrawNotSupported,
+
     /// The bound graphics pipeline requires a vertex buffer bound to a binding number, but none
     /// was bound.
     VertexBufferNotBound {
@@ -2732,10 +4692,26 @@ impl Display for PipelineExecutionError {
                 bound index buffer ({})",
                 highest_index, max_index_count,
             ),
+            Self::IndirectBufferDrawCountOutOfBounds { .. } => write!(
+                f,
+                "the `max_draw_count` of an indirect count draw command exceeds the number of \
+                commands in the indirect buffer",
+            ),
             Self::IndirectBufferMissingUsage => write!(
                 f,
                 "the `indirect_buffer` usage was not enabled on the indirect buffer",
             ),
+            Self::IndirectBufferMissingShaderDeviceAddressUsage => write!(
+                f,
+                "the `shader_device_address` usage was not enabled on the indirect buffer",
+            ),
+            Self::IndirectCountBufferMissingUsage => write!(
+                f,
+                "the `indirect_buffer` usage was not enabled on the count buffer",
+            ),
+            Self::IndirectCountBufferOffsetNotAligned { .. } => {
+                write!(f, "the offset of the count buffer is not a multiple of 4")
+            }
             Self::MaxComputeWorkGroupCountExceeded { .. } => write!(
                 f,
                 "the `max_compute_work_group_count` limit has been exceeded",
@@ -2743,10 +4719,32 @@ impl Display for PipelineExecutionError {
             Self::MaxDrawIndirectCountExceeded { .. } => {
                 write!(f, "the `max_draw_indirect_count` limit has been exceeded")
             }
+            Self::MaxMeshWorkGroupCountExceeded { .. } => {
+                write!(f, "the `max_mesh_work_group_count` limit has been exceeded",)
+            }
+            Self::MaxMeshWorkGroupTotalCountExceeded { .. } => write!(
+                f,
+                "the `max_mesh_work_group_total_count` limit has been exceeded",
+            ),
             Self::MaxMultiviewInstanceIndexExceeded { .. } => write!(
                 f,
                 "the `max_multiview_instance_index` limit has been exceeded",
             ),
+            Self::MaxRayDispatchInvocationCountExceeded { .. } => write!(
+                f,
+                "the `max_ray_dispatch_invocation_count` limit has been exceeded",
+            ),
+            Self::MaxTaskWorkGroupCountExceeded { .. } => {
+                write!(f, "the `max_task_work_group_count` limit has been exceeded",)
+            }
+            Self::MaxTaskWorkGroupTotalCountExceeded { .. } => write!(
+                f,
+                "the `max_task_work_group_total_count` limit has been exceeded",
+            ),
+            Self::MaxTransformFeedbackBufferDataStrideExceeded { .. } => write!(
+                f,
+                "the vertex stride exceeds the `max_transform_feedback_buffer_data_stride` limit",
+            ),
             Self::NotSupportedByQueueFamily => {
                 write!(f, "the queue family doesn't allow this operation")
             }
@@ -2783,6 +4781,16 @@ impl Display for PipelineExecutionError {
                 "the bound pipeline is not compatible with the layout used to bind the descriptor \
                 sets",
             ),
+            Self::PipelineMeshShaderMissing => write!(
+                f,
+                "the operation requires a graphics pipeline with a mesh shader, but the bound \
+                graphics pipeline does not have one",
+            ),
+            Self::PipelineMeshShaderNotAllowed => write!(
+                f,
+                "the operation requires a graphics pipeline without a mesh shader, but the bound \
+                graphics pipeline has one",
+            ),
             Self::PipelineNotBound => write!(
                 f,
                 "no pipeline was bound to the bind point used by the operation",
@@ -2829,6 +4837,14 @@ impl Display for PipelineExecutionError {
                 f,
                 "not all push constants used by the pipeline have been set",
             ),
+            Self::RayDispatchDimensionsExceeded { .. } => write!(
+                f,
+                "the dimensions of the ray tracing command exceed the product of the \
+                `max_compute_work_group_count` and `max_compute_work_group_size` limits",
+            ),
+            Self::TransformFeedbackDrawNotSupported => {
+                write!(f, "the `transform_feedback_draw` device property was false",)
+            }
             Self::VertexBufferNotBound { binding_num } => write!(
                 f,
                 "the bound graphics pipeline requires a vertex buffer bound to binding number {}, \
@@ -2971,3 +4987,260 @@ impl Display for DescriptorResourceInvalidError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PipelineExecutionError;
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            CommandBufferUsage, DrawIndirectCommand, PrimaryAutoCommandBuffer, RenderPassBeginInfo,
+            SubpassContents,
+        },
+        device::Device,
+        memory::allocator::{AllocationCreateInfo, MemoryUsage, StandardMemoryAllocator},
+        pipeline::graphics::{
+            input_assembly::InputAssemblyState,
+            tests::{discard_pipeline_create_info, MESH_MODULE, VERTEX_MODULE},
+            vertex_input::VertexInputState,
+            GraphicsPipeline, GraphicsPipelineCreateInfo,
+        },
+        render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
+    };
+    use std::sync::Arc;
+
+    fn vertex_pipeline(device: Arc<Device>, render_pass: Arc<RenderPass>) -> Arc<GraphicsPipeline> {
+        GraphicsPipeline::new(
+            device,
+            None,
+            GraphicsPipelineCreateInfo {
+                vertex_input_state: Some(VertexInputState::new()),
+                input_assembly_state: Some(InputAssemblyState::new()),
+                ..discard_pipeline_create_info(
+                    Subpass::from(render_pass, 0).unwrap(),
+                    &[&VERTEX_MODULE],
+                )
+            },
+        )
+        .unwrap()
+    }
+
+    fn indirect_count_buffers(
+        device: Arc<Device>,
+        count_buffer_usage: BufferUsage,
+    ) -> (Subbuffer<[DrawIndirectCommand]>, Subbuffer<[u32]>) {
+        let memory_allocator = StandardMemoryAllocator::new_default(device);
+        let indirect_buffer = Buffer::from_iter(
+            &memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::INDIRECT_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Upload,
+                ..Default::default()
+            },
+            [DrawIndirectCommand {
+                vertex_count: 3,
+                instance_count: 1,
+                first_vertex: 0,
+                first_instance: 0,
+            }],
+        )
+        .unwrap();
+        let count_buffer = Buffer::from_iter(
+            &memory_allocator,
+            BufferCreateInfo {
+                usage: count_buffer_usage,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Upload,
+                ..Default::default()
+            },
+            [1u32, 1],
+        )
+        .unwrap();
+
+        (indirect_buffer, count_buffer)
+    }
+
+    fn begin_empty_render_pass(
+        builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        render_pass: Arc<RenderPass>,
+    ) {
+        let framebuffer = Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                extent: [1, 1],
+                layers: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        builder
+            .begin_render_pass(
+                RenderPassBeginInfo::framebuffer(framebuffer),
+                SubpassContents::Inline,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn draw_mesh_tasks_outside_render_pass() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            builder.draw_mesh_tasks([1, 1, 1]),
+            Err(PipelineExecutionError::ForbiddenOutsideRenderPass)
+        ));
+    }
+
+    #[test]
+    fn draw_mesh_tasks_without_mesh_shader() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let render_pass = RenderPass::empty_single_pass(device.clone()).unwrap();
+        let pipeline = vertex_pipeline(device.clone(), render_pass.clone());
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        begin_empty_render_pass(&mut builder, render_pass);
+        builder.bind_pipeline_graphics(pipeline);
+
+        assert!(matches!(
+            builder.draw_mesh_tasks([1, 1, 1]),
+            Err(PipelineExecutionError::PipelineMeshShaderMissing)
+        ));
+    }
+
+    #[test]
+    fn draw_mesh_tasks_work_group_count() {
+        let (device, queue) =
+            gfx_dev_and_queue!(extensions: [ext_mesh_shader, khr_spirv_1_4]; mesh_shader);
+
+        let render_pass = RenderPass::empty_single_pass(device.clone()).unwrap();
+        let pipeline = GraphicsPipeline::new(
+            device.clone(),
+            None,
+            discard_pipeline_create_info(
+                Subpass::from(render_pass.clone(), 0).unwrap(),
+                &[&MESH_MODULE],
+            ),
+        )
+        .unwrap();
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        begin_empty_render_pass(&mut builder, render_pass);
+        builder.bind_pipeline_graphics(pipeline);
+
+        assert!(matches!(
+            builder.draw_mesh_tasks([u32::MAX, 1, 1]),
+            Err(PipelineExecutionError::MaxMeshWorkGroupCountExceeded { .. })
+        ));
+        builder.draw_mesh_tasks([1, 1, 1]).unwrap();
+    }
+
+    #[test]
+    fn draw_indirect_count_buffer_usage() {
+        let (device, queue) = gfx_dev_and_queue!(draw_indirect_count);
+
+        let render_pass = RenderPass::empty_single_pass(device.clone()).unwrap();
+        let pipeline = vertex_pipeline(device.clone(), render_pass.clone());
+        let (indirect_buffer, count_buffer) =
+            indirect_count_buffers(device.clone(), BufferUsage::TRANSFER_SRC);
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        begin_empty_render_pass(&mut builder, render_pass);
+        builder.bind_pipeline_graphics(pipeline);
+
+        assert!(matches!(
+            builder.draw_indirect_count(indirect_buffer, count_buffer.index(0), 1),
+            Err(PipelineExecutionError::IndirectCountBufferMissingUsage)
+        ));
+    }
+
+    #[test]
+    fn draw_indirect_count_buffer_offset() {
+        let (device, queue) = gfx_dev_and_queue!(draw_indirect_count);
+
+        let (_, count_buffer) =
+            indirect_count_buffers(device.clone(), BufferUsage::INDIRECT_BUFFER);
+        let count_bytes = count_buffer.into_bytes();
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        // A `Subbuffer<u32>` is always aligned, so check the byte range directly.
+        assert!(matches!(
+            builder.validate_indirect_count_buffer(&count_bytes.clone().slice(2..6)),
+            Err(PipelineExecutionError::IndirectCountBufferOffsetNotAligned { offset: 2 })
+        ));
+        builder
+            .validate_indirect_count_buffer(&count_bytes.slice(4..8))
+            .unwrap();
+    }
+
+    #[test]
+    fn draw_indirect_count_max_draw_count() {
+        let (device, queue) = gfx_dev_and_queue!(draw_indirect_count);
+
+        let render_pass = RenderPass::empty_single_pass(device.clone()).unwrap();
+        let pipeline = vertex_pipeline(device.clone(), render_pass.clone());
+        let (indirect_buffer, count_buffer) =
+            indirect_count_buffers(device.clone(), BufferUsage::INDIRECT_BUFFER);
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        begin_empty_render_pass(&mut builder, render_pass);
+        builder.bind_pipeline_graphics(pipeline);
+
+        assert!(matches!(
+            builder.draw_indirect_count(indirect_buffer.clone(), count_buffer.clone().index(0), 2),
+            Err(PipelineExecutionError::IndirectBufferDrawCountOutOfBounds {
+                max_draw_count: 2,
+                commands_in_buffer: 1,
+            })
+        ));
+        builder
+            .draw_indirect_count(indirect_buffer, count_buffer.index(0), 1)
+            .unwrap();
+    }
+}
@@ -470,6 +470,11 @@ where
             return Err(RenderPassError::QueryIsActive);
         }
 
+        // VUID-vkCmdNextSubpass2-None-02350
+        if self.transform_feedback_active {
+            return Err(RenderPassError::TransformFeedbackActive);
+        }
+
         // VUID-vkCmdNextSubpass2-commandBuffer-cmdpool
         debug_assert!(self
             .queue_family_properties()
@@ -528,6 +533,11 @@ where
             return Err(RenderPassError::QueryIsActive);
         }
 
+        // VUID-vkCmdEndRenderPass2-None-02352
+        if self.transform_feedback_active {
+            return Err(RenderPassError::TransformFeedbackActive);
+        }
+
         // VUID-vkCmdEndRenderPass2-commandBuffer-cmdpool
         debug_assert!(self
             .queue_family_properties()
@@ -1193,6 +1203,11 @@ where
             RenderPassStateType::BeginRendering(_) => (),
         }
 
+        // VUID-vkCmdEndRendering-None-06781
+        if self.transform_feedback_active {
+            return Err(RenderPassError::TransformFeedbackActive);
+        }
+
         // VUID-vkCmdEndRendering-commandBuffer-cmdpool
         debug_assert!(self
             .queue_family_properties()
@@ -1583,6 +1598,7 @@ impl SyncCommandBufferBuilder {
         self.commands.push(Box::new(Cmd));
         debug_assert!(self.latest_render_pass_enter.is_some());
         self.latest_render_pass_enter = None;
+        self.latest_render_pass_exit = self.commands.len();
     }
 
     /// Calls `vkCmdBeginRendering` on the builder.
@@ -1856,6 +1872,7 @@ impl SyncCommandBufferBuilder {
         self.commands.push(Box::new(Cmd));
         debug_assert!(self.latest_render_pass_enter.is_some());
         self.latest_render_pass_enter = None;
+        self.latest_render_pass_exit = self.commands.len();
     }
 
     /// Calls `vkCmdClearAttachments` on the builder.
@@ -2847,6 +2864,9 @@ pub enum RenderPassError {
         current_subpass: u32,
         remaining_subpasses: u32,
     },
+
+    /// Transform feedback is active, which is not allowed for this operation.
+    TransformFeedbackActive,
 }
 
 impl Error for RenderPassError {
@@ -3155,6 +3175,10 @@ impl Display for RenderPassError {
                 the render pass",
                 current_subpass, remaining_subpasses,
             ),
+            Self::TransformFeedbackActive => write!(
+                f,
+                "transform feedback is active, which is not allowed for this operation",
+            ),
         }
     }
 }
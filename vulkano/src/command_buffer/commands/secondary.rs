@@ -13,21 +13,29 @@ use crate::{
         auto::RenderPassStateType,
         synced::{Command, Resource, SyncCommandBufferBuilder, SyncCommandBufferBuilderError},
         sys::UnsafeCommandBufferBuilder,
-        AutoCommandBufferBuilder, CommandBufferExecError, CommandBufferInheritanceRenderPassType,
-        CommandBufferUsage, ResourceInCommand, ResourceUseRef, SecondaryCommandBufferAbstract,
+        AutoCommandBufferBuilder, BuildError, CommandBufferBeginError, CommandBufferExecError,
+        CommandBufferInheritanceInfo, CommandBufferInheritanceRenderPassInfo,
+        CommandBufferInheritanceRenderPassType, CommandBufferInheritanceRenderingInfo,
+        CommandBufferUsage, PrimaryAutoCommandBuffer, ResourceInCommand, ResourceUseRef,
+        SecondaryAutoCommandBuffer, SecondaryCommandBufferAbstract,
         SecondaryCommandBufferBufferUsage, SecondaryCommandBufferImageUsage,
-        SecondaryCommandBufferResourcesUsage, SubpassContents,
+        SecondaryCommandBufferResourcesUsage, SecondaryResourceUseRef, SubpassContents,
     },
     device::{DeviceOwned, QueueFlags},
     format::Format,
-    image::SampleCount,
+    image::{ImageLayout, ImageSubresourceRange, SampleCount},
     query::{QueryControlFlags, QueryPipelineStatisticFlags, QueryType},
-    RequiresOneOf, SafeDeref, VulkanObject,
+    DeviceSize, RequiresOneOf, SafeDeref, VulkanObject,
 };
+use ahash::HashMap;
 use smallvec::SmallVec;
 use std::{
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
+    ops::Range,
+    panic,
+    sync::Arc,
+    thread,
 };
 
 /// # Commands to execute a secondary command buffer inside a primary command buffer.
@@ -128,6 +136,11 @@ where
             return Err(ExecuteCommandsError::NotSupportedByQueueFamily);
         }
 
+        // VUID-vkCmdExecuteCommands-None-02286
+        if self.transform_feedback_active {
+            return Err(ExecuteCommandsError::TransformFeedbackActive);
+        }
+
         // TODO:
         // VUID-vkCmdExecuteCommands-pCommandBuffers-00094
 
@@ -374,7 +387,13 @@ where
                         );
                     }
                 }
-                QueryType::Timestamp => (),
+                QueryType::Timestamp
+                | QueryType::AccelerationStructureCompactedSize
+                | QueryType::TransformFeedbackStream
+                | QueryType::PrimitivesGenerated
+                | QueryType::MeshPrimitivesGenerated
+                | QueryType::AccelerationStructureSerializationSize
+                | QueryType::Performance => (),
             }
         }
 
@@ -395,6 +414,203 @@ where
     }
 }
 
+/// # Commands to record secondary command buffers in parallel.
+impl<A> AutoCommandBufferBuilder<PrimaryAutoCommandBuffer<A::Alloc>, A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Records secondary command buffers on multiple threads, and executes them in the current
+    /// subpass.
+    ///
+    /// A secondary command buffer is recorded for each allocator in `allocators`, each on a
+    /// thread of its own, by calling `record` with the index of the allocator and a builder that
+    /// allocates from it. The builders inherit the current subpass and framebuffer, or the
+    /// attachment formats when rendering with [`begin_rendering`], as well as the active
+    /// queries. The command buffers are then executed in the order of their allocators, as with
+    /// [`execute_commands_from_vec`].
+    ///
+    /// Each thread merges the uses of the same resource by its command buffer into one, and
+    /// checks them for conflicts with the commands that were recorded before, so that the
+    /// primary command buffer only has to add the merged uses to its resource tracking.
+    ///
+    /// The current subpass must have been begun with [`SubpassContents::SecondaryCommandBuffers`].
+    ///
+    /// [`begin_rendering`]: Self::begin_rendering
+    /// [`execute_commands_from_vec`]: Self::execute_commands_from_vec
+    pub fn execute_commands_parallel<B, F>(
+        &mut self,
+        allocators: &[B],
+        record: F,
+    ) -> Result<&mut Self, ParallelRecordError>
+    where
+        B: CommandBufferAllocator + Sync,
+        F: Fn(
+                usize,
+                &mut AutoCommandBufferBuilder<SecondaryAutoCommandBuffer<B::Alloc>, B>,
+            ) -> Result<(), Box<dyn Error + Send + Sync>>
+            + Sync,
+    {
+        let inheritance_info = self.validate_execute_commands_parallel()?;
+
+        let queue_family_index = self.queue_family_index;
+        let usage = self.usage;
+        let command_index = self.inner.commands.len();
+        let primary = &self.inner;
+        let record = &record;
+
+        let results: Vec<_> = thread::scope(|scope| {
+            let threads: Vec<_> = allocators
+                .iter()
+                .enumerate()
+                .map(|(index, allocator)| {
+                    let inheritance_info = inheritance_info.clone();
+
+                    scope.spawn(move || -> Result<_, ParallelRecordError> {
+                        let mut builder = AutoCommandBufferBuilder::secondary(
+                            allocator,
+                            queue_family_index,
+                            usage,
+                            inheritance_info,
+                        )?;
+                        record(index, &mut builder).map_err(|error| {
+                            ParallelRecordError::RecordError {
+                                index,
+                                error: error.into(),
+                            }
+                        })?;
+                        let command_buffer = builder.build()?;
+
+                        let resources = merged_secondary_resources(
+                            command_buffer.resources_usage(),
+                            index as u32,
+                            command_index,
+                        );
+
+                        for resource in &resources {
+                            primary
+                                .check_resource_conflicts(resource)
+                                .map_err(ExecuteCommandsError::from)?;
+                        }
+
+                        Ok((command_buffer, resources))
+                    })
+                })
+                .collect();
+
+            threads
+                .into_iter()
+                .map(|thread| {
+                    thread
+                        .join()
+                        .unwrap_or_else(|payload| panic::resume_unwind(payload))
+                })
+                .collect()
+        });
+
+        let mut command_buffers = Vec::with_capacity(results.len());
+        let mut resources = Vec::new();
+
+        for result in results {
+            let (command_buffer, command_buffer_resources) = result?;
+            command_buffers.push(command_buffer);
+            resources.extend(command_buffer_resources);
+        }
+
+        for (command_buffer_index, command_buffer) in command_buffers.iter().enumerate() {
+            self.validate_execute_commands(command_buffer, command_buffer_index as u32)?;
+        }
+
+        unsafe {
+            let mut builder = self.inner.execute_commands();
+            for command_buffer in command_buffers {
+                builder.add(command_buffer);
+            }
+            builder
+                .submit_checked(resources)
+                .map_err(ExecuteCommandsError::from)?;
+
+            // Secondary command buffer could leave the primary in any state.
+            self.inner.reset_state();
+        }
+
+        Ok(self)
+    }
+
+    // Returns the inheritance info of the secondary command buffers.
+    fn validate_execute_commands_parallel(
+        &self,
+    ) -> Result<CommandBufferInheritanceInfo, ParallelRecordError> {
+        let render_pass_state = self
+            .render_pass_state
+            .as_ref()
+            .ok_or(ParallelRecordError::ForbiddenOutsideRenderPass)?;
+
+        // VUID-vkCmdExecuteCommands-contents-06018
+        if render_pass_state.contents != SubpassContents::SecondaryCommandBuffers {
+            return Err(ExecuteCommandsError::ForbiddenWithSubpassContents {
+                contents: render_pass_state.contents,
+            }
+            .into());
+        }
+
+        let render_pass = match &render_pass_state.render_pass {
+            RenderPassStateType::BeginRenderPass(state) => {
+                CommandBufferInheritanceRenderPassType::BeginRenderPass(
+                    CommandBufferInheritanceRenderPassInfo {
+                        subpass: state.subpass.clone(),
+                        framebuffer: state.framebuffer.clone(),
+                    },
+                )
+            }
+            RenderPassStateType::BeginRendering(state) => {
+                let rasterization_samples = state
+                    .attachments
+                    .as_ref()
+                    .and_then(|attachments| {
+                        attachments
+                            .color_attachments
+                            .iter()
+                            .flatten()
+                            .chain(&attachments.depth_attachment)
+                            .chain(&attachments.stencil_attachment)
+                            .next()
+                    })
+                    .map_or(SampleCount::Sample1, |attachment| {
+                        attachment.image_view.image().samples()
+                    });
+
+                CommandBufferInheritanceRenderPassType::BeginRendering(
+                    CommandBufferInheritanceRenderingInfo {
+                        view_mask: render_pass_state.view_mask,
+                        color_attachment_formats: state.color_attachment_formats.clone(),
+                        depth_attachment_format: state.depth_attachment_format,
+                        stencil_attachment_format: state.stencil_attachment_format,
+                        rasterization_samples,
+                    },
+                )
+            }
+        };
+
+        let mut occlusion_query = None;
+        let mut query_statistics_flags = QueryPipelineStatisticFlags::empty();
+
+        for state in self.query_state.values() {
+            match state.ty {
+                QueryType::Occlusion => occlusion_query = Some(state.flags),
+                QueryType::PipelineStatistics(flags) => query_statistics_flags = flags,
+                _ => (),
+            }
+        }
+
+        Ok(CommandBufferInheritanceInfo {
+            render_pass: Some(render_pass),
+            occlusion_query,
+            query_statistics_flags,
+            ..Default::default()
+        })
+    }
+}
+
 impl SyncCommandBufferBuilder {
     /// Starts the process of executing secondary command buffers. Returns an intermediate struct
     /// which can be used to add the command buffers.
@@ -421,6 +637,29 @@ impl<'a> SyncCommandBufferBuilderExecuteCommands<'a> {
 
     #[inline]
     pub unsafe fn submit(self) -> Result<(), SyncCommandBufferBuilderError> {
+        let command_index = self.builder.commands.len();
+        let resources: Vec<_> = self
+            .inner
+            .iter()
+            .enumerate()
+            .flat_map(|(index, cbuf)| {
+                secondary_resources(cbuf.resources_usage(), index as u32, command_index)
+            })
+            .collect();
+
+        for resource in &resources {
+            self.builder.check_resource_conflicts(resource)?;
+        }
+
+        self.submit_checked(resources)
+    }
+
+    /// Same as `submit`, except that `resources` are the resources used by the command buffers,
+    /// which have already been checked for conflicts with the commands of the builder.
+    pub(in crate::command_buffer) unsafe fn submit_checked(
+        self,
+        resources: Vec<(ResourceUseRef, Resource)>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
         struct DropUnlock(Box<dyn SecondaryCommandBufferAbstract>);
         impl std::ops::Deref for DropUnlock {
             type Target = Box<dyn SecondaryCommandBufferAbstract>;
@@ -455,76 +694,6 @@ impl<'a> SyncCommandBufferBuilderExecuteCommands<'a> {
             }
         }
 
-        let command_index = self.builder.commands.len();
-        let command_name = "execute_commands";
-        let resources: Vec<_> = self
-            .inner
-            .iter()
-            .enumerate()
-            .flat_map(|(index, cbuf)| {
-                let index = index as u32;
-                let SecondaryCommandBufferResourcesUsage { buffers, images } =
-                    cbuf.resources_usage();
-
-                (buffers.iter().map(move |usage| {
-                    let &SecondaryCommandBufferBufferUsage {
-                        use_ref,
-                        ref buffer,
-                        ref range,
-                        memory,
-                    } = usage;
-
-                    (
-                        ResourceUseRef {
-                            command_index,
-                            command_name,
-                            resource_in_command: ResourceInCommand::SecondaryCommandBuffer {
-                                index,
-                            },
-                            secondary_use_ref: Some(use_ref.into()),
-                        },
-                        Resource::Buffer {
-                            buffer: buffer.clone(),
-                            range: range.clone(),
-                            memory,
-                        },
-                    )
-                }))
-                .chain(images.iter().map(move |usage| {
-                    let &SecondaryCommandBufferImageUsage {
-                        use_ref,
-                        ref image,
-                        ref subresource_range,
-                        memory,
-                        start_layout,
-                        end_layout,
-                    } = usage;
-
-                    (
-                        ResourceUseRef {
-                            command_index,
-                            command_name,
-                            resource_in_command: ResourceInCommand::SecondaryCommandBuffer {
-                                index,
-                            },
-                            secondary_use_ref: Some(use_ref.into()),
-                        },
-                        Resource::Image {
-                            image: image.clone(),
-                            subresource_range: subresource_range.clone(),
-                            memory,
-                            start_layout,
-                            end_layout,
-                        },
-                    )
-                }))
-            })
-            .collect();
-
-        for resource in &resources {
-            self.builder.check_resource_conflicts(resource)?;
-        }
-
         self.builder.commands.push(Box::new(Cmd(self
             .inner
             .into_iter()
@@ -542,6 +711,132 @@ impl<'a> SyncCommandBufferBuilderExecuteCommands<'a> {
     }
 }
 
+// Returns the resources that a secondary command buffer uses, as uses of the command at
+// `command_index` that executes it as its `index`th command buffer.
+fn secondary_resources(
+    resources_usage: &SecondaryCommandBufferResourcesUsage,
+    index: u32,
+    command_index: usize,
+) -> impl Iterator<Item = (ResourceUseRef, Resource)> + '_ {
+    let use_ref = move |secondary_use_ref: SecondaryResourceUseRef| ResourceUseRef {
+        command_index,
+        command_name: "execute_commands",
+        resource_in_command: ResourceInCommand::SecondaryCommandBuffer { index },
+        secondary_use_ref: Some(secondary_use_ref),
+    };
+
+    (resources_usage.buffers.iter().map(move |usage| {
+        let &SecondaryCommandBufferBufferUsage {
+            use_ref: secondary_use_ref,
+            ref buffer,
+            ref range,
+            memory,
+        } = usage;
+
+        (
+            use_ref(secondary_use_ref.into()),
+            Resource::Buffer {
+                buffer: buffer.clone(),
+                range: range.clone(),
+                memory,
+            },
+        )
+    }))
+    .chain(resources_usage.images.iter().map(move |usage| {
+        let &SecondaryCommandBufferImageUsage {
+            use_ref: secondary_use_ref,
+            ref image,
+            ref subresource_range,
+            memory,
+            start_layout,
+            end_layout,
+        } = usage;
+
+        (
+            use_ref(secondary_use_ref.into()),
+            Resource::Image {
+                image: image.clone(),
+                subresource_range: subresource_range.clone(),
+                memory,
+                start_layout,
+                end_layout,
+            },
+        )
+    }))
+}
+
+// Same as `secondary_resources`, except that the uses of the same range of a resource, with the
+// same layouts, are merged into a single use that has the stages and accesses of all of them.
+fn merged_secondary_resources(
+    resources_usage: &SecondaryCommandBufferResourcesUsage,
+    index: u32,
+    command_index: usize,
+) -> Vec<(ResourceUseRef, Resource)> {
+    let mut resources: Vec<(ResourceUseRef, Resource)> = Vec::new();
+    let mut buffer_uses: HashMap<(ash::vk::Buffer, Range<DeviceSize>), usize> = HashMap::default();
+    let mut image_uses: HashMap<
+        (
+            ash::vk::Image,
+            ImageSubresourceRange,
+            ImageLayout,
+            ImageLayout,
+        ),
+        usize,
+    > = HashMap::default();
+
+    for (use_ref, resource) in secondary_resources(resources_usage, index, command_index) {
+        let next_index = resources.len();
+        let resource_index = match &resource {
+            Resource::Buffer { buffer, range, .. } => *buffer_uses
+                .entry((
+                    buffer.buffer().handle(),
+                    buffer.offset() + range.start..buffer.offset() + range.end,
+                ))
+                .or_insert(next_index),
+            Resource::Image {
+                image,
+                subresource_range,
+                start_layout,
+                end_layout,
+                ..
+            } => {
+                let inner = image.inner();
+                let mut subresource_range = subresource_range.clone();
+                subresource_range.array_layers.start += inner.first_layer;
+                subresource_range.array_layers.end += inner.first_layer;
+                subresource_range.mip_levels.start += inner.first_mipmap_level;
+                subresource_range.mip_levels.end += inner.first_mipmap_level;
+
+                *image_uses
+                    .entry((
+                        inner.image.handle(),
+                        subresource_range,
+                        *start_layout,
+                        *end_layout,
+                    ))
+                    .or_insert(next_index)
+            }
+        };
+
+        if resource_index == next_index {
+            resources.push((use_ref, resource));
+        } else {
+            merge_memory(&mut resources[resource_index].1, &resource);
+        }
+    }
+
+    resources
+}
+
+fn merge_memory(resource: &mut Resource, other: &Resource) {
+    let (Resource::Buffer { memory, .. } | Resource::Image { memory, .. }) = resource;
+    let (Resource::Buffer { memory: other, .. } | Resource::Image { memory: other, .. }) = other;
+
+    memory.stages |= other.stages;
+    memory.access |= other.access;
+    memory.exclusive |= other.exclusive;
+}
+
 impl UnsafeCommandBufferBuilder {
     /// Calls `vkCmdExecuteCommands` on the builder.
     ///
@@ -727,6 +1022,9 @@ pub enum ExecuteCommandsError {
         required_view_mask: u32,
         inherited_view_mask: u32,
     },
+
+    /// Transform feedback is active, which is not allowed for this operation.
+    TransformFeedbackActive,
 }
 
 impl Error for ExecuteCommandsError {
@@ -919,6 +1217,10 @@ impl Display for ExecuteCommandsError {
                 mask ({})",
                 inherited_view_mask, command_buffer_index, required_view_mask,
             ),
+            Self::TransformFeedbackActive => write!(
+                f,
+                "transform feedback is active, which is not allowed for this operation",
+            ),
         }
     }
 }
@@ -928,3 +1230,77 @@ impl From<SyncCommandBufferBuilderError> for ExecuteCommandsError {
         Self::SyncCommandBufferBuilderError(err)
     }
 }
+
+/// Error that can happen when recording secondary command buffers in parallel.
+#[derive(Clone, Debug)]
+pub enum ParallelRecordError {
+    /// Beginning a secondary command buffer failed.
+    CommandBufferBeginError(CommandBufferBeginError),
+
+    /// Recording the secondary command buffer at `index` failed.
+    RecordError {
+        index: usize,
+        error: Arc<dyn Error + Send + Sync>,
+    },
+
+    /// Building a secondary command buffer failed.
+    BuildError(BuildError),
+
+    /// Executing the secondary command buffers failed.
+    ExecuteCommandsError(ExecuteCommandsError),
+
+    /// Operation forbidden outside of a render pass.
+    ForbiddenOutsideRenderPass,
+}
+
+impl Error for ParallelRecordError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::CommandBufferBeginError(err) => Some(err),
+            Self::RecordError { error, .. } => Some(error.as_ref()),
+            Self::BuildError(err) => Some(err),
+            Self::ExecuteCommandsError(err) => Some(err),
+            Self::ForbiddenOutsideRenderPass => None,
+        }
+    }
+}
+
+impl Display for ParallelRecordError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::CommandBufferBeginError(_) => {
+                write!(f, "beginning a secondary command buffer failed")
+            }
+            Self::RecordError { index, .. } => write!(
+                f,
+                "recording the secondary command buffer at index {} failed",
+                index,
+            ),
+            Self::BuildError(_) => write!(f, "building a secondary command buffer failed"),
+            Self::ExecuteCommandsError(_) => {
+                write!(f, "executing the secondary command buffers failed")
+            }
+            Self::ForbiddenOutsideRenderPass => {
+                write!(f, "operation forbidden outside of a render pass")
+            }
+        }
+    }
+}
+
+impl From<CommandBufferBeginError> for ParallelRecordError {
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBeginError(err)
+    }
+}
+
+impl From<BuildError> for ParallelRecordError {
+    fn from(err: BuildError) -> Self {
+        Self::BuildError(err)
+    }
+}
+
+impl From<ExecuteCommandsError> for ParallelRecordError {
+    fn from(err: ExecuteCommandsError) -> Self {
+        Self::ExecuteCommandsError(err)
+    }
+}
@@ -9,23 +9,1089 @@
 
 use crate::{
     command_buffer::{
-        synced::{Command, SyncCommandBufferBuilder},
+        allocator::CommandBufferAllocator,
+        synced::{Command, SyncCommandBufferBuilder, SyncCommandBufferBuilderError},
         sys::UnsafeCommandBufferBuilder,
+        AutoCommandBufferBuilder, PrimaryAutoCommandBuffer, ResourceInCommand, ResourceUseRef,
     },
-    image::ImageLayout,
+    device::{DeviceOwned, QueueFlags},
+    image::{ImageAspects, ImageCreateFlags, ImageLayout, ImageUsage},
     sync::{
         event::Event, AccessFlags, BufferMemoryBarrier, DependencyFlags, DependencyInfo,
-        ImageMemoryBarrier, MemoryBarrier, PipelineStages,
+        ImageMemoryBarrier, MemoryBarrier, PipelineStages, QueueFamilyOwnershipTransfer, Sharing,
     },
-    Version, VulkanObject,
+    DeviceSize, RequirementNotMet, RequiresOneOf, Version, VulkanObject,
 };
 use smallvec::SmallVec;
-use std::{ptr, sync::Arc};
+use std::{
+    cmp::max,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    ptr,
+    sync::Arc,
+};
+
+/// # Commands to synchronize work within the command buffer.
+impl<A> AutoCommandBufferBuilder<PrimaryAutoCommandBuffer<A::Alloc>, A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Sets `event` once the commands before it have completed the source scope of
+    /// `dependency_info`.
+    ///
+    /// Together with [`wait_events`], this splits a pipeline barrier in two halves, so that the
+    /// commands recorded in between can execute while the barrier is pending. The builder keeps
+    /// track of `dependency_info`: the resources of its buffer and image memory barriers can't be
+    /// used by any commands until the event is waited on, and once it is, the builder doesn't
+    /// insert another barrier before commands whose accesses are within the destination scope.
+    /// If earlier accesses of a resource are not within the source scope, the builder inserts
+    /// a barrier before this command to make them so. The event must be waited on before the
+    /// command buffer is built.
+    ///
+    /// # Safety
+    ///
+    /// - `event` must not be set, reset or waited on by anything else from when the command
+    ///   buffer starts executing until the event is waited on.
+    /// - `event` must be unsignaled when the command buffer starts executing. If the command
+    ///   buffer is executed more than once, the event must be reset in between.
+    ///
+    /// [`wait_events`]: Self::wait_events
+    #[inline]
+    pub unsafe fn set_event(
+        &mut self,
+        event: Arc<Event>,
+        dependency_info: DependencyInfo,
+    ) -> Result<&mut Self, SynchronizationError> {
+        self.validate_set_event(&event, &dependency_info)?;
+
+        self.inner.set_event(event, dependency_info)?;
+
+        Ok(self)
+    }
+
+    fn validate_set_event(
+        &self,
+        event: &Event,
+        dependency_info: &DependencyInfo,
+    ) -> Result<(), SynchronizationError> {
+        // VUID-vkCmdSetEvent2-renderpass
+        if self.render_pass_state.is_some() {
+            return Err(SynchronizationError::ForbiddenInsideRenderPass);
+        }
+
+        // VUID-vkCmdSetEvent2-commandBuffer-03826
+        // TODO:
+
+        let device = self.device();
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdSetEvent2-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.intersects(
+            QueueFlags::GRAPHICS
+                | QueueFlags::COMPUTE
+                | QueueFlags::VIDEO_DECODE
+                | QueueFlags::VIDEO_ENCODE,
+        ) {
+            return Err(SynchronizationError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdSetEvent2-commonparent
+        assert_eq!(device, event.device());
+
+        let &DependencyInfo {
+            dependency_flags,
+            ref memory_barriers,
+            ref buffer_memory_barriers,
+            ref image_memory_barriers,
+            _ne: _,
+        } = dependency_info;
+
+        // VUID-VkDependencyInfo-dependencyFlags-parameter
+        dependency_flags.validate_device(device)?;
+
+        // VUID-vkCmdSetEvent2-dependencyFlags-03825
+        if !dependency_flags.is_empty() {
+            return Err(SynchronizationError::DependencyFlagsNotAllowed);
+        }
+
+        let check_stages_access = |ty: char,
+                                   barrier_index: usize,
+                                   src_stages: PipelineStages,
+                                   src_access: AccessFlags,
+                                   dst_stages: PipelineStages,
+                                   dst_access: AccessFlags|
+         -> Result<(), SynchronizationError> {
+            for (stages, access) in [(src_stages, src_access), (dst_stages, dst_access)] {
+                // VUID-vkCmdSetEvent2-synchronization2-03824
+                if !device.enabled_features().synchronization2 {
+                    if stages.is_2() {
+                        return Err(SynchronizationError::RequirementNotMet {
+                            required_for: "One of `dependency_info.memory_barriers`, \
+                                `dependency_info.buffer_memory_barriers` or \
+                                `dependency_info.image_memory_barriers` has an element where \
+                                `src_stages` or `dst_stages` contains flags from \
+                                `VkPipelineStageFlagBits2`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["synchronization2"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+
+                    if access.is_2() {
+                        return Err(SynchronizationError::RequirementNotMet {
+                            required_for: "One of `dependency_info.memory_barriers`, \
+                                `dependency_info.buffer_memory_barriers` or \
+                                `dependency_info.image_memory_barriers` has an element where \
+                                `src_access` or `dst_access` contains flags from \
+                                `VkAccessFlagBits2`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["synchronization2"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-parameter
+                // VUID-VkMemoryBarrier2-dstStageMask-parameter
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-parameter
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-parameter
+                // VUID-VkImageMemoryBarrier2-srcStageMask-parameter
+                // VUID-VkImageMemoryBarrier2-dstStageMask-parameter
+                stages.validate_device(device)?;
+
+                // VUID-VkMemoryBarrier2-srcAccessMask-parameter
+                // VUID-VkMemoryBarrier2-dstAccessMask-parameter
+                // VUID-VkBufferMemoryBarrier2-srcAccessMask-parameter
+                // VUID-VkBufferMemoryBarrier2-dstAccessMask-parameter
+                // VUID-VkImageMemoryBarrier2-srcAccessMask-parameter
+                // VUID-VkImageMemoryBarrier2-dstAccessMask-parameter
+                access.validate_device(device)?;
+
+                // VUID-vkCmdSetEvent2-srcStageMask-03827
+                // VUID-vkCmdSetEvent2-dstStageMask-03828
+                if !PipelineStages::from(queue_family_properties.queue_flags).contains(stages) {
+                    match ty {
+                        'm' => {
+                            return Err(SynchronizationError::MemoryBarrierStageNotSupported {
+                                barrier_index,
+                            })
+                        }
+                        'b' => {
+                            return Err(
+                                SynchronizationError::BufferMemoryBarrierStageNotSupported {
+                                    barrier_index,
+                                },
+                            )
+                        }
+                        'i' => {
+                            return Err(SynchronizationError::ImageMemoryBarrierStageNotSupported {
+                                barrier_index,
+                            })
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-03929
+                // VUID-VkMemoryBarrier2-dstStageMask-03929
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-03929
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-03929
+                // VUID-VkImageMemoryBarrier2-srcStageMask-03930
+                // VUID-VkImageMemoryBarrier2-dstStageMask-03930
+                if stages.intersects(PipelineStages::GEOMETRY_SHADER)
+                    && !device.enabled_features().geometry_shader
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::GEOMETRY_SHADER`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["geometry_shader"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-03930
+                // VUID-VkMemoryBarrier2-dstStageMask-03930
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-03930
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-03930
+                // VUID-VkImageMemoryBarrier2-srcStageMask-03930
+                // VUID-VkImageMemoryBarrier2-dstStageMask-03930
+                if stages.intersects(
+                    PipelineStages::TESSELLATION_CONTROL_SHADER
+                        | PipelineStages::TESSELLATION_EVALUATION_SHADER,
+                ) && !device.enabled_features().tessellation_shader
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::TESSELLATION_CONTROL_SHADER` or \
+                            `PipelineStages::TESSELLATION_EVALUATION_SHADER`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["tessellation_shader"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-03931
+                // VUID-VkMemoryBarrier2-dstStageMask-03931
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-03931
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-03931
+                // VUID-VImagekMemoryBarrier2-srcStageMask-03931
+                // VUID-VkImageMemoryBarrier2-dstStageMask-03931
+                if stages.intersects(PipelineStages::CONDITIONAL_RENDERING)
+                    && !device.enabled_features().conditional_rendering
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::CONDITIONAL_RENDERING`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["conditional_rendering"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-03932
+                // VUID-VkMemoryBarrier2-dstStageMask-03932
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-03932
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-03932
+                // VUID-VkImageMemoryBarrier2-srcStageMask-03932
+                // VUID-VkImageMemoryBarrier2-dstStageMask-03932
+                if stages.intersects(PipelineStages::FRAGMENT_DENSITY_PROCESS)
+                    && !device.enabled_features().fragment_density_map
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::FRAGMENT_DENSITY_PROCESS`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["fragment_density_map"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-03933
+                // VUID-VkMemoryBarrier2-dstStageMask-03933
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-03933
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-03933
+                // VUID-VkImageMemoryBarrier2-srcStageMask-03933
+                // VUID-VkImageMemoryBarrier2-dstStageMask-03933
+                if stages.intersects(PipelineStages::TRANSFORM_FEEDBACK)
+                    && !device.enabled_features().transform_feedback
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::TRANSFORM_FEEDBACK`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["transform_feedback"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-03934
+                // VUID-VkMemoryBarrier2-dstStageMask-03934
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-03934
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-03934
+                // VUID-VkImageMemoryBarrier2-srcStageMask-03934
+                // VUID-VkImageMemoryBarrier2-dstStageMask-03934
+                if stages.intersects(PipelineStages::MESH_SHADER)
+                    && !device.enabled_features().mesh_shader
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::MESH_SHADER`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["mesh_shader"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-03935
+                // VUID-VkMemoryBarrier2-dstStageMask-03935
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-03935
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-03935
+                // VUID-VkImageMemoryBarrier2-srcStageMask-03935
+                // VUID-VkImageMemoryBarrier2-dstStageMask-03935
+                if stages.intersects(PipelineStages::TASK_SHADER)
+                    && !device.enabled_features().task_shader
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::TASK_SHADER`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["task_shader"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkMemoryBarrier2-shadingRateImage-07316
+                // VUID-VkMemoryBarrier2-shadingRateImage-07316
+                // VUID-VkBufferMemoryBarrier2-shadingRateImage-07316
+                // VUID-VkBufferMemoryBarrier2-shadingRateImage-07316
+                // VUID-VkImageMemoryBarrier2-shadingRateImage-07316
+                // VUID-VkImageMemoryBarrier2-shadingRateImage-07316
+                if stages.intersects(PipelineStages::FRAGMENT_SHADING_RATE_ATTACHMENT)
+                    && !(device.enabled_features().attachment_fragment_shading_rate
+                        || device.enabled_features().shading_rate_image)
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::FRAGMENT_SHADING_RATE_ATTACHMENT`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["attachment_fragment_shading_rate", "shading_rate_image"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-04957
+                // VUID-VkMemoryBarrier2-dstStageMask-04957
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-04957
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-04957
+                // VUID-VkImageMemoryBarrier2-srcStageMask-04957
+                // VUID-VkImageMemoryBarrier2-dstStageMask-04957
+                if stages.intersects(PipelineStages::SUBPASS_SHADING)
+                    && !device.enabled_features().subpass_shading
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::SUBPASS_SHADING`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["subpass_shading"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkMemoryBarrier2-srcStageMask-04995
+                // VUID-VkMemoryBarrier2-dstStageMask-04995
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-04995
+                // VUID-VkBufferMemoryBarrier2-dstStageMask-04995
+                // VUID-VkImageMemoryBarrier2-srcStageMask-04995
+                // VUID-VkImageMemoryBarrier2-dstStageMask-04995
+                if stages.intersects(PipelineStages::INVOCATION_MASK)
+                    && !device.enabled_features().invocation_mask
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            contains `PipelineStages::INVOCATION_MASK`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["invocation_mask"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-vkCmdSetEvent-stageMask-03937
+                if stages.is_empty() && !device.enabled_features().synchronization2 {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "One of `dependency_info.memory_barriers`, \
+                            `dependency_info.buffer_memory_barriers` or \
+                            `dependency_info.image_memory_barriers` has an element where `stages` \
+                            is empty",
+                        requires_one_of: RequiresOneOf {
+                            features: &["synchronization2"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // A bit of a ridiculous number of VUIDs...
+
+                // VUID-VkMemoryBarrier2-srcAccessMask-03900
+                // ..
+                // VUID-VkMemoryBarrier2-srcAccessMask-07458
+
+                // VUID-VkMemoryBarrier2-dstAccessMask-03900
+                // ..
+                // VUID-VkMemoryBarrier2-dstAccessMask-07458
+
+                // VUID-VkBufferMemoryBarrier2-srcAccessMask-03900
+                // ..
+                // VUID-VkBufferMemoryBarrier2-srcAccessMask-07458
+
+                // VUID-VkBufferMemoryBarrier2-dstAccessMask-03900
+                // ..
+                // VUID-VkBufferMemoryBarrier2-dstAccessMask-07458
+
+                // VUID-VkImageMemoryBarrier2-srcAccessMask-03900
+                // ..
+                // VUID-VkImageMemoryBarrier2-srcAccessMask-07458
+
+                // VUID-VkImageMemoryBarrier2-dstAccessMask-03900
+                // ..
+                // VUID-VkImageMemoryBarrier2-dstAccessMask-07458
+
+                if !AccessFlags::from(stages).contains(access) {
+                    match ty {
+                        'm' => {
+                            return Err(
+                                SynchronizationError::MemoryBarrierAccessNotSupportedByStages {
+                                    barrier_index,
+                                },
+                            )
+                        }
+                        'b' => return Err(
+                            SynchronizationError::BufferMemoryBarrierAccessNotSupportedByStages {
+                                barrier_index,
+                            },
+                        ),
+                        'i' => return Err(
+                            SynchronizationError::ImageMemoryBarrierAccessNotSupportedByStages {
+                                barrier_index,
+                            },
+                        ),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+
+            // VUID-VkMemoryBarrier2-srcAccessMask-06256
+            // VUID-VkBufferMemoryBarrier2-srcAccessMask-06256
+            // VUID-VkImageMemoryBarrier2-srcAccessMask-06256
+            if !device.enabled_features().ray_query
+                && src_access.intersects(AccessFlags::ACCELERATION_STRUCTURE_READ)
+                && src_stages.intersects(
+                    PipelineStages::VERTEX_SHADER
+                        | PipelineStages::TESSELLATION_CONTROL_SHADER
+                        | PipelineStages::TESSELLATION_EVALUATION_SHADER
+                        | PipelineStages::GEOMETRY_SHADER
+                        | PipelineStages::FRAGMENT_SHADER
+                        | PipelineStages::COMPUTE_SHADER
+                        | PipelineStages::PRE_RASTERIZATION_SHADERS
+                        | PipelineStages::TASK_SHADER
+                        | PipelineStages::MESH_SHADER,
+                )
+            {
+                return Err(SynchronizationError::RequirementNotMet {
+                    required_for: "One of `dependency_info.memory_barriers`, \
+                        `dependency_info.buffer_memory_barriers` or \
+                        `dependency_info.image_memory_barriers` has an element where \
+                        `src_access` contains `ACCELERATION_STRUCTURE_READ`, and \
+                        `src_stages` contains a shader stage other than `RAY_TRACING_SHADER`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["ray_query"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            Ok(())
+        };
+
+        let check_queue_family_ownership_transfer = |ty: char,
+                                                     barrier_index: usize,
+                                                     src_stages: PipelineStages,
+                                                     dst_stages: PipelineStages,
+                                                     queue_family_ownership_transfer: Option<
+            QueueFamilyOwnershipTransfer,
+        >,
+                                                     sharing: &Sharing<_>|
+         -> Result<(), SynchronizationError> {
+            if let Some(transfer) = queue_family_ownership_transfer {
+                // VUID?
+                transfer.validate_device(device)?;
+
+                // VUID-VkBufferMemoryBarrier2-srcQueueFamilyIndex-04087
+                // VUID-VkImageMemoryBarrier2-srcQueueFamilyIndex-04070
+                // Ensured by the definition of `QueueFamilyOwnershipTransfer`.
+
+                // VUID-VkBufferMemoryBarrier2-buffer-04088
+                // VUID-VkImageMemoryBarrier2-image-04071
+                // Ensured by the definition of `QueueFamilyOwnershipTransfer`.
+
+                let queue_family_count =
+                    device.physical_device().queue_family_properties().len() as u32;
+
+                let provided_queue_family_index = match (sharing, transfer) {
+                    (
+                        Sharing::Exclusive,
+                        QueueFamilyOwnershipTransfer::ExclusiveBetweenLocal {
+                            src_index,
+                            dst_index,
+                        },
+                    ) => Some(max(src_index, dst_index)),
+                    (
+                        Sharing::Exclusive,
+                        QueueFamilyOwnershipTransfer::ExclusiveToExternal { src_index }
+                        | QueueFamilyOwnershipTransfer::ExclusiveToForeign { src_index },
+                    ) => Some(src_index),
+                    (
+                        Sharing::Exclusive,
+                        QueueFamilyOwnershipTransfer::ExclusiveFromExternal { dst_index }
+                        | QueueFamilyOwnershipTransfer::ExclusiveFromForeign { dst_index },
+                    ) => Some(dst_index),
+                    (
+                        Sharing::Concurrent(_),
+                        QueueFamilyOwnershipTransfer::ConcurrentToExternal
+                        | QueueFamilyOwnershipTransfer::ConcurrentFromExternal
+                        | QueueFamilyOwnershipTransfer::ConcurrentToForeign
+                        | QueueFamilyOwnershipTransfer::ConcurrentFromForeign,
+                    ) => None,
+                    _ => match ty {
+                        'b' => return Err(SynchronizationError::BufferMemoryBarrierOwnershipTransferSharingMismatch {
+                            barrier_index,
+                        }),
+                        'i' => return Err(SynchronizationError::ImageMemoryBarrierOwnershipTransferSharingMismatch {
+                            barrier_index,
+                        }),
+                        _ => unreachable!(),
+                    },
+                }.filter(|&index| index >= queue_family_count);
+
+                // VUID-VkBufferMemoryBarrier2-buffer-04089
+                // VUID-VkImageMemoryBarrier2-image-04072
+
+                if let Some(provided_queue_family_index) = provided_queue_family_index {
+                    match ty {
+                        'b' => return Err(SynchronizationError::BufferMemoryBarrierOwnershipTransferIndexOutOfRange {
+                            barrier_index,
+                            provided_queue_family_index,
+                            queue_family_count,
+                        }),
+                        'i' => return Err(SynchronizationError::ImageMemoryBarrierOwnershipTransferIndexOutOfRange {
+                            barrier_index,
+                            provided_queue_family_index,
+                            queue_family_count,
+                        }),
+                        _ => unreachable!(),
+                    }
+                }
+
+                // VUID-VkBufferMemoryBarrier2-srcStageMask-03851
+                // VUID-VkImageMemoryBarrier2-srcStageMask-03854
+                if src_stages.intersects(PipelineStages::HOST)
+                    || dst_stages.intersects(PipelineStages::HOST)
+                {
+                    match ty {
+                        'b' => return Err(SynchronizationError::BufferMemoryBarrierOwnershipTransferHostNotAllowed {
+                            barrier_index,
+                        }),
+                        'i' => return Err(SynchronizationError::ImageMemoryBarrierOwnershipTransferHostForbidden {
+                            barrier_index,
+                        }),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+
+            Ok(())
+        };
+
+        for (barrier_index, barrier) in memory_barriers.iter().enumerate() {
+            let &MemoryBarrier {
+                src_stages,
+                src_access,
+                dst_stages,
+                dst_access,
+                _ne: _,
+            } = barrier;
+
+            /*
+                Check stages and access
+            */
+
+            check_stages_access(
+                'm',
+                barrier_index,
+                src_stages,
+                src_access,
+                dst_stages,
+                dst_access,
+            )?;
+        }
+
+        for (barrier_index, barrier) in buffer_memory_barriers.iter().enumerate() {
+            let &BufferMemoryBarrier {
+                src_stages,
+                src_access,
+                dst_stages,
+                dst_access,
+                queue_family_ownership_transfer,
+                ref buffer,
+                ref range,
+                _ne: _,
+            } = barrier;
+
+            // VUID-VkBufferMemoryBarrier2-buffer-01931
+            // Ensured by Buffer type construction.
+
+            /*
+                Check stages and access
+            */
+
+            check_stages_access(
+                'b',
+                barrier_index,
+                src_stages,
+                src_access,
+                dst_stages,
+                dst_access,
+            )?;
+
+            /*
+                Check queue family transfer
+            */
+
+            check_queue_family_ownership_transfer(
+                'b',
+                barrier_index,
+                src_stages,
+                dst_stages,
+                queue_family_ownership_transfer,
+                buffer.sharing(),
+            )?;
+
+            /*
+                Check range
+            */
+
+            // VUID-VkBufferMemoryBarrier2-size-01188
+            assert!(!range.is_empty());
+
+            // VUID-VkBufferMemoryBarrier2-offset-01187
+            // VUID-VkBufferMemoryBarrier2-size-01189
+            if range.end > buffer.size() {
+                return Err(SynchronizationError::BufferMemoryBarrierOutOfRange {
+                    barrier_index,
+                    range_end: range.end,
+                    buffer_size: buffer.size(),
+                });
+            }
+        }
+
+        for (barrier_index, barrier) in image_memory_barriers.iter().enumerate() {
+            let &ImageMemoryBarrier {
+                src_stages,
+                src_access,
+                dst_stages,
+                dst_access,
+                old_layout,
+                new_layout,
+                queue_family_ownership_transfer,
+                ref image,
+                ref subresource_range,
+                _ne: _,
+            } = barrier;
+
+            // VUID-VkImageMemoryBarrier2-image-01932
+            // Ensured by Image type construction.
+
+            /*
+                Check stages and access
+            */
+
+            check_stages_access(
+                'i',
+                barrier_index,
+                src_stages,
+                src_access,
+                dst_stages,
+                dst_access,
+            )?;
+
+            /*
+                Check layouts
+            */
+
+            // VUID-VkImageMemoryBarrier2-oldLayout-parameter
+            old_layout.validate_device(device)?;
+
+            // VUID-VkImageMemoryBarrier2-newLayout-parameter
+            new_layout.validate_device(device)?;
+
+            // VUID-VkImageMemoryBarrier2-srcStageMask-03855
+            if src_stages.intersects(PipelineStages::HOST)
+                && !matches!(
+                    old_layout,
+                    ImageLayout::Preinitialized | ImageLayout::Undefined | ImageLayout::General
+                )
+            {
+                return Err(
+                    SynchronizationError::ImageMemoryBarrierOldLayoutFromHostInvalid {
+                        barrier_index,
+                        old_layout,
+                    },
+                );
+            }
+
+            // VUID-VkImageMemoryBarrier2-oldLayout-01197
+            // Not checked yet, therefore unsafe.
+
+            // VUID-VkImageMemoryBarrier2-newLayout-01198
+            if matches!(
+                new_layout,
+                ImageLayout::Undefined | ImageLayout::Preinitialized
+            ) {
+                return Err(SynchronizationError::ImageMemoryBarrierNewLayoutInvalid {
+                    barrier_index,
+                });
+            }
+
+            // VUID-VkImageMemoryBarrier2-attachmentFeedbackLoopLayout-07313
+            /*if !device.enabled_features().attachment_feedback_loop_layout
+                && matches!(new_layout, ImageLayout::AttachmentFeedbackLoopOptimal)
+            {
+                return Err(SynchronizationError::RequirementNotMet {
+                    required_for: "`dependency_info.image_memory_barriers` has an element where \
+                        `new_layout` is `AttachmentFeedbackLoopOptimal`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["attachment_feedback_loop_layout"],
+                        ..Default::default()
+                    },
+                });
+            }*/
+
+            for layout in [old_layout, new_layout] {
+                // VUID-VkImageMemoryBarrier2-synchronization2-06911
+                /*if !device.enabled_features().synchronization2
+                    && matches!(
+                        layout,
+                        ImageLayout::AttachmentOptimal | ImageLayout::ReadOnlyOptimal
+                    )
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "`dependency_info.image_memory_barriers` has an element \
+                            where `old_layout` or `new_layout` is `AttachmentOptimal` or \
+                            `ReadOnlyOptimal`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["synchronization2"],
+                            ..Default::default()
+                        },
+                    });
+                }*/
+
+                // VUID-VkImageMemoryBarrier2-srcQueueFamilyIndex-07006
+                /*if layout == ImageLayout::AttachmentFeedbackLoopOptimal {
+                    if !image.usage().intersects(
+                        ImageUsage::COLOR_ATTACHMENT | ImageUsage::DEPTH_STENCIL_ATTACHMENT,
+                    ) {
+                        return Err(
+                            SynchronizationError::ImageMemoryBarrierImageMissingUsageForLayout {
+                                barrier_index,
+                                layout,
+                                requires_one_of_usage: ImageUsage::COLOR_ATTACHMENT
+                                    | ImageUsage::DEPTH_STENCIL_ATTACHMENT,
+                            },
+                        );
+                    }
+
+                    if !image
+                        .usage()
+                        .intersects(ImageUsage::INPUT_ATTACHMENT | ImageUsage::SAMPLED)
+                    {
+                        return Err(
+                            SynchronizationError::ImageMemoryBarrierImageMissingUsageForLayout {
+                                barrier_index,
+                                layout,
+                                requires_one_of_usage: ImageUsage::INPUT_ATTACHMENT
+                                    | ImageUsage::SAMPLED,
+                            },
+                        );
+                    }
+
+                    if !image
+                        .usage()
+                        .intersects(ImageUsage::ATTACHMENT_FEEDBACK_LOOP)
+                    {
+                        return Err(
+                            SynchronizationError::ImageMemoryBarrierImageMissingUsageForLayout {
+                                barrier_index,
+                                layout,
+                                requires_one_of_usage: ImageUsage::ATTACHMENT_FEEDBACK_LOOP,
+                            },
+                        );
+                    }
+                }*/
+
+                let requires_one_of_usage = match layout {
+                    // VUID-VkImageMemoryBarrier2-oldLayout-01208
+                    ImageLayout::ColorAttachmentOptimal => ImageUsage::COLOR_ATTACHMENT,
+
+                    // VUID-VkImageMemoryBarrier2-oldLayout-01209
+                    ImageLayout::DepthStencilAttachmentOptimal => {
+                        ImageUsage::DEPTH_STENCIL_ATTACHMENT
+                    }
+
+                    // VUID-VkImageMemoryBarrier2-oldLayout-01210
+                    ImageLayout::DepthStencilReadOnlyOptimal => {
+                        ImageUsage::DEPTH_STENCIL_ATTACHMENT
+                    }
+
+                    // VUID-VkImageMemoryBarrier2-oldLayout-01211
+                    ImageLayout::ShaderReadOnlyOptimal => {
+                        ImageUsage::SAMPLED | ImageUsage::INPUT_ATTACHMENT
+                    }
+
+                    // VUID-VkImageMemoryBarrier2-oldLayout-01212
+                    ImageLayout::TransferSrcOptimal => ImageUsage::TRANSFER_SRC,
+
+                    // VUID-VkImageMemoryBarrier2-oldLayout-01213
+                    ImageLayout::TransferDstOptimal => ImageUsage::TRANSFER_DST,
+
+                    // VUID-VkImageMemoryBarrier2-oldLayout-01658
+                    ImageLayout::DepthReadOnlyStencilAttachmentOptimal => {
+                        ImageUsage::DEPTH_STENCIL_ATTACHMENT
+                    }
+
+                    // VUID-VkImageMemoryBarrier2-oldLayout-01659
+                    ImageLayout::DepthAttachmentStencilReadOnlyOptimal => {
+                        ImageUsage::DEPTH_STENCIL_ATTACHMENT
+                    }
+
+                    /*
+                    // VUID-VkImageMemoryBarrier2-srcQueueFamilyIndex-04065
+                    ImageLayout::DepthReadOnlyOptimal => {
+                        ImageUsage::DEPTH_STENCIL_ATTACHMENT
+                            | ImageUsage::SAMPLED
+                            | ImageUsage::INPUT_ATTACHMENT
+                    }
+
+                    // VUID-VkImageMemoryBarrier2-srcQueueFamilyIndex-04066
+                    ImageLayout::DepthAttachmentOptimal => ImageUsage::DEPTH_STENCIL_ATTACHMENT,
+
+                    // VUID-VkImageMemoryBarrier2-srcQueueFamilyIndex-04067
+                    ImageLayout::StencilReadOnlyOptimal => {
+                        ImageUsage::DEPTH_STENCIL_ATTACHMENT
+                            | ImageUsage::SAMPLED
+                            | ImageUsage::INPUT_ATTACHMENT
+                    }
+
+                    // VUID-VkImageMemoryBarrier2-srcQueueFamilyIndex-04068
+                    ImageLayout::StencilAttachmentOptimal => ImageUsage::DEPTH_STENCIL_ATTACHMENT,
+
+                    // VUID-VkImageMemoryBarrier2-srcQueueFamilyIndex-03938
+                    ImageLayout::AttachmentOptimal => {
+                        ImageUsage::COLOR_ATTACHMENT | ImageUsage::DEPTH_STENCIL_ATTACHMENT
+                    }
+
+                    // VUID-VkImageMemoryBarrier2-srcQueueFamilyIndex-03939
+                    ImageLayout::ReadOnlyOptimal => {
+                        ImageUsage::DEPTH_STENCIL_ATTACHMENT
+                            | ImageUsage::SAMPLED
+                            | ImageUsage::INPUT_ATTACHMENT
+                    }
+
+                    // VUID-VkImageMemoryBarrier2-oldLayout-02088
+                    ImageLayout::FragmentShadingRateAttachmentOptimal => {
+                        ImageUsage::FRAGMENT_SHADING_RATE_ATTACHMENT
+                    }
+                     */
+                    _ => continue,
+                };
+
+                if !image.usage().intersects(requires_one_of_usage) {
+                    return Err(
+                        SynchronizationError::ImageMemoryBarrierImageMissingUsageForLayout {
+                            barrier_index,
+                            layout,
+                            requires_one_of_usage,
+                        },
+                    );
+                }
+            }
+
+            /*
+                Check queue family tansfer
+            */
+
+            check_queue_family_ownership_transfer(
+                'i',
+                barrier_index,
+                src_stages,
+                dst_stages,
+                queue_family_ownership_transfer,
+                image.sharing(),
+            )?;
+
+            /*
+                Check subresource range
+            */
+
+            // VUID-VkImageSubresourceRange-aspectMask-requiredbitmask
+            assert!(!subresource_range.aspects.is_empty());
+
+            // VUID-VkImageSubresourceRange-aspectMask-parameter
+            subresource_range.aspects.validate_device(device)?;
+
+            let image_aspects = image.format().unwrap().aspects();
+
+            // VUID-VkImageMemoryBarrier2-image-01673
+            // VUID-VkImageMemoryBarrier2-image-03319
+            if !image_aspects.contains(subresource_range.aspects) {
+                return Err(SynchronizationError::ImageMemoryBarrierAspectsNotAllowed {
+                    barrier_index,
+                    aspects: subresource_range.aspects - image_aspects,
+                });
+            }
+
+            if image_aspects.intersects(ImageAspects::DEPTH | ImageAspects::STENCIL) {
+                // VUID-VkImageMemoryBarrier2-image-03320
+                if !device.enabled_features().separate_depth_stencil_layouts
+                    && image_aspects.contains(ImageAspects::DEPTH | ImageAspects::STENCIL)
+                    && !subresource_range
+                        .aspects
+                        .contains(ImageAspects::DEPTH | ImageAspects::STENCIL)
+                {
+                    return Err(SynchronizationError::RequirementNotMet {
+                        required_for: "`dependency_info.image_memory_barriers` has an element \
+                            where `image` has both a depth and a stencil aspect, and \
+                            `subresource_range.aspects` does not contain both aspects",
+                        requires_one_of: RequiresOneOf {
+                            features: &["separate_depth_stencil_layouts"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            } else {
+                // VUID-VkImageMemoryBarrier2-image-01671
+                if !image.flags().intersects(ImageCreateFlags::DISJOINT)
+                    && subresource_range.aspects != ImageAspects::COLOR
+                {
+                    return Err(SynchronizationError::ImageMemoryBarrierAspectsNotAllowed {
+                        barrier_index,
+                        aspects: subresource_range.aspects - ImageAspects::COLOR,
+                    });
+                }
+            }
+
+            // VUID-VkImageSubresourceRange-levelCount-01720
+            assert!(!subresource_range.mip_levels.is_empty());
+
+            // VUID-VkImageMemoryBarrier2-subresourceRange-01486
+            // VUID-VkImageMemoryBarrier2-subresourceRange-01724
+            if subresource_range.mip_levels.end > image.mip_levels() {
+                return Err(
+                    SynchronizationError::ImageMemoryBarrierMipLevelsOutOfRange {
+                        barrier_index,
+                        mip_levels_range_end: subresource_range.mip_levels.end,
+                        image_mip_levels: image.mip_levels(),
+                    },
+                );
+            }
+
+            // VUID-VkImageSubresourceRange-layerCount-01721
+            assert!(!subresource_range.array_layers.is_empty());
+
+            // VUID-VkImageMemoryBarrier2-subresourceRange-01488
+            // VUID-VkImageMemoryBarrier2-subresourceRange-01725
+            if subresource_range.array_layers.end > image.dimensions().array_layers() {
+                return Err(
+                    SynchronizationError::ImageMemoryBarrierArrayLayersOutOfRange {
+                        barrier_index,
+                        array_layers_range_end: subresource_range.array_layers.end,
+                        image_array_layers: image.dimensions().array_layers(),
+                    },
+                );
+            }
+        }
+
+        self.inner
+            .validate_set_event_barriers(event, dependency_info)?;
+
+        Ok(())
+    }
+
+    /// Waits for `events` to be set, and completes the dependencies that they were set with.
+    ///
+    /// Each event must have been set with [`set_event`] earlier in the command buffer, and the
+    /// `DependencyInfo` that was given there is used to wait on it.
+    ///
+    /// # Safety
+    ///
+    /// - The safety requirements of [`set_event`] apply.
+    ///
+    /// [`set_event`]: Self::set_event
+    #[inline]
+    pub unsafe fn wait_events(
+        &mut self,
+        events: impl IntoIterator<Item = Arc<Event>>,
+    ) -> Result<&mut Self, SynchronizationError> {
+        let events: SmallVec<[Arc<Event>; 4]> = events.into_iter().collect();
+        self.validate_wait_events(&events)?;
+
+        self.inner.wait_events(events);
+
+        Ok(self)
+    }
+
+    fn validate_wait_events(&self, events: &[Arc<Event>]) -> Result<(), SynchronizationError> {
+        // VUID-vkCmdWaitEvents2-dependencyFlags-03844
+        // The builder doesn't track barriers inside render passes.
+        if self.render_pass_state.is_some() {
+            return Err(SynchronizationError::ForbiddenInsideRenderPass);
+        }
+
+        let device = self.device();
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdWaitEvents2-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.intersects(
+            QueueFlags::GRAPHICS
+                | QueueFlags::COMPUTE
+                | QueueFlags::VIDEO_DECODE
+                | QueueFlags::VIDEO_ENCODE,
+        ) {
+            return Err(SynchronizationError::NotSupportedByQueueFamily);
+        }
+
+        for (event_index, event) in events.iter().enumerate() {
+            // VUID-vkCmdWaitEvents2-commonparent
+            assert_eq!(device, event.device());
+
+            // VUID-vkCmdWaitEvents2-pEvents-03837
+            // VUID-vkCmdWaitEvents2-pEvents-03838
+            // VUID-vkCmdWaitEvents2-pEvents-03839
+            if !self.inner.is_event_set(event)
+                || events[..event_index]
+                    .iter()
+                    .any(|other| Arc::ptr_eq(other, event))
+            {
+                return Err(SynchronizationError::EventNotSet { event_index });
+            }
+        }
+
+        Ok(())
+    }
+}
 
 impl SyncCommandBufferBuilder {
     /// Calls `vkCmdSetEvent` on the builder.
+    ///
+    /// The resources of the barriers of `dependency_info` can't be used by other commands until
+    /// the event is waited on with `wait_events`.
     #[inline]
-    pub unsafe fn set_event(&mut self, event: Arc<Event>, dependency_info: DependencyInfo) {
+    pub unsafe fn set_event(
+        &mut self,
+        event: Arc<Event>,
+        dependency_info: DependencyInfo,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
             event: Arc<Event>,
             dependency_info: DependencyInfo,
@@ -41,18 +1107,22 @@ impl SyncCommandBufferBuilder {
             }
         }
 
+        let use_refs = event_barrier_use_refs(self.commands.len(), "set_event", &dependency_info);
+        self.check_event_barrier_conflicts(&use_refs, &dependency_info)?;
+
         self.commands.push(Box::new(Cmd {
-            event,
-            dependency_info,
+            event: event.clone(),
+            dependency_info: dependency_info.clone(),
         }));
+        self.add_set_event(use_refs, event, dependency_info);
+
+        Ok(())
     }
 
-    /// Calls `vkCmdWaitEvents` on the builder.
+    /// Calls `vkCmdWaitEvents` on the builder, with the `DependencyInfo` that each event was set
+    /// with.
     #[inline]
-    pub unsafe fn wait_events(
-        &mut self,
-        events: impl IntoIterator<Item = (Arc<Event>, DependencyInfo)>,
-    ) {
+    pub unsafe fn wait_events(&mut self, events: impl IntoIterator<Item = Arc<Event>>) {
         struct Cmd {
             events: SmallVec<[(Arc<Event>, DependencyInfo); 4]>,
         }
@@ -71,9 +1141,23 @@ impl SyncCommandBufferBuilder {
             }
         }
 
+        let command_index = self.commands.len();
+        let events: SmallVec<[_; 4]> = events
+            .into_iter()
+            .map(|event| {
+                let dependency_info = self.take_set_event(&event);
+                (event, dependency_info)
+            })
+            .collect();
+
         self.commands.push(Box::new(Cmd {
-            events: events.into_iter().collect(),
+            events: events.clone(),
         }));
+
+        for (_, dependency_info) in events {
+            let use_refs = event_barrier_use_refs(command_index, "wait_events", &dependency_info);
+            self.add_wait_event(use_refs, dependency_info);
+        }
     }
 
     /// Calls `vkCmdResetEvent` on the builder.
@@ -98,6 +1182,34 @@ impl SyncCommandBufferBuilder {
     }
 }
 
+// Returns the use of each buffer and image memory barrier of `dependency_info` by a command.
+fn event_barrier_use_refs(
+    command_index: usize,
+    command_name: &'static str,
+    dependency_info: &DependencyInfo,
+) -> SmallVec<[ResourceUseRef; 8]> {
+    let buffer_use_refs =
+        (0..dependency_info.buffer_memory_barriers.len()).map(|index| ResourceUseRef {
+            command_index,
+            command_name,
+            resource_in_command: ResourceInCommand::BufferMemoryBarrier {
+                index: index as u32,
+            },
+            secondary_use_ref: None,
+        });
+    let image_use_refs =
+        (0..dependency_info.image_memory_barriers.len()).map(|index| ResourceUseRef {
+            command_index,
+            command_name,
+            resource_in_command: ResourceInCommand::ImageMemoryBarrier {
+                index: index as u32,
+            },
+            secondary_use_ref: None,
+        });
+
+    buffer_use_refs.chain(image_use_refs).collect()
+}
+
 impl UnsafeCommandBufferBuilder {
     #[inline]
     pub unsafe fn pipeline_barrier(&mut self, dependency_info: &DependencyInfo) {
@@ -422,16 +1534,13 @@ impl UnsafeCommandBufferBuilder {
     #[inline]
     pub unsafe fn set_event(&mut self, event: &Event, dependency_info: &DependencyInfo) {
         let &DependencyInfo {
-            mut dependency_flags,
+            dependency_flags,
             ref memory_barriers,
             ref buffer_memory_barriers,
             ref image_memory_barriers,
             _ne: _,
         } = dependency_info;
 
-        // TODO: Is this needed?
-        dependency_flags |= DependencyFlags::BY_REGION;
-
         let fns = self.device.fns();
 
         if self.device.enabled_features().synchronization2 {
@@ -599,16 +1708,13 @@ impl UnsafeCommandBufferBuilder {
 
             for (event, dependency_info) in events {
                 let &DependencyInfo {
-                    mut dependency_flags,
+                    dependency_flags,
                     ref memory_barriers,
                     ref buffer_memory_barriers,
                     ref image_memory_barriers,
                     _ne: _,
                 } = dependency_info;
 
-                // TODO: Is this needed?
-                dependency_flags |= DependencyFlags::BY_REGION;
-
                 let memory_barriers_vk: SmallVec<[_; 2]> = memory_barriers
                     .into_iter()
                     .map(|barrier| {
@@ -929,3 +2035,490 @@ impl UnsafeCommandBufferBuilder {
 
     // TODO: wait_event
 }
+
+/// Error that can happen when recording a synchronization command.
+#[derive(Clone, Debug)]
+pub enum SynchronizationError {
+    SyncCommandBufferBuilderError(SyncCommandBufferBuilderError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// One or more accesses of a buffer memory barrier are not supported by the corresponding
+    /// pipeline stages.
+    BufferMemoryBarrierAccessNotSupportedByStages {
+        barrier_index: usize,
+    },
+
+    /// Buffer memory barriers are forbidden inside a render pass instance.
+    BufferMemoryBarrierForbiddenInsideRenderPass,
+
+    /// The end of `range` of a buffer memory barrier is greater than the size of `buffer`.
+    BufferMemoryBarrierOutOfRange {
+        barrier_index: usize,
+        range_end: DeviceSize,
+        buffer_size: DeviceSize,
+    },
+
+    /// A buffer memory barrier contains a queue family ownership transfer, but either the
+    /// `src_stages` or `dst_stages` contain [`HOST`].
+    ///
+    /// [`HOST`]: crate::sync::PipelineStages::HOST
+    BufferMemoryBarrierOwnershipTransferHostNotAllowed {
+        barrier_index: usize,
+    },
+
+    /// The provided `src_index` or `dst_index` in the queue family ownership transfer of a
+    /// buffer memory barrier is not less than the number of queue families in the physical device.
+    BufferMemoryBarrierOwnershipTransferIndexOutOfRange {
+        barrier_index: usize,
+        provided_queue_family_index: u32,
+        queue_family_count: u32,
+    },
+
+    /// The provided `queue_family_ownership_transfer` value of a buffer memory barrier does not
+    /// match the sharing mode of `buffer`.
+    BufferMemoryBarrierOwnershipTransferSharingMismatch {
+        barrier_index: usize,
+    },
+
+    /// One or more pipeline stages of a buffer memory barrier are not supported by the queue
+    /// family of the command buffer.
+    BufferMemoryBarrierStageNotSupported {
+        barrier_index: usize,
+    },
+
+    /// Dependency flags were provided when setting an event, which is not allowed.
+    DependencyFlagsNotAllowed,
+
+    /// A render pass instance is not active, and the `VIEW_LOCAL` dependency flag was provided.
+    DependencyFlagsViewLocalNotAllowed,
+
+    /// The event was already set by an earlier command, and hasn't been waited on yet.
+    EventAlreadySet,
+
+    /// An event that is waited on wasn't set by an earlier command of the command buffer, or was
+    /// already waited on.
+    EventNotSet {
+        event_index: usize,
+    },
+
+    /// Operation forbidden inside a render pass.
+    ForbiddenInsideRenderPass,
+
+    /// Operation forbidden inside a render pass instance that was begun with `begin_rendering`.
+    ForbiddenWithBeginRendering,
+
+    /// One or more accesses of an image memory barrier are not supported by the corresponding
+    /// pipeline stages.
+    ImageMemoryBarrierAccessNotSupportedByStages {
+        barrier_index: usize,
+    },
+
+    /// The end of the range of array layers of the subresource range of an image memory barrier
+    /// is greater than the number of array layers in the image.
+    ImageMemoryBarrierArrayLayersOutOfRange {
+        barrier_index: usize,
+        array_layers_range_end: u32,
+        image_array_layers: u32,
+    },
+
+    /// The aspects of the subresource range of an image memory barrier contain aspects that are
+    /// not present in the image, or that are not allowed.
+    ImageMemoryBarrierAspectsNotAllowed {
+        barrier_index: usize,
+        aspects: ImageAspects,
+    },
+
+    /// For the `old_layout` or `new_layout` of an image memory barrier, `image` does not have a
+    /// usage that is required.
+    ImageMemoryBarrierImageMissingUsageForLayout {
+        barrier_index: usize,
+        layout: ImageLayout,
+        requires_one_of_usage: ImageUsage,
+    },
+
+    /// An image memory barrier contains an image layout transition, but a render pass
+    /// instance is active.
+    ImageMemoryBarrierLayoutTransitionForbiddenInsideRenderPass {
+        barrier_index: usize,
+    },
+
+    /// The end of the range of mip levels of the subresource range of an image memory barrier
+    /// is greater than the number of mip levels in the image.
+    ImageMemoryBarrierMipLevelsOutOfRange {
+        barrier_index: usize,
+        mip_levels_range_end: u32,
+        image_mip_levels: u32,
+    },
+
+    /// The `new_layout` of an image memory barrier is `Undefined` or `Preinitialized`.
+    ImageMemoryBarrierNewLayoutInvalid {
+        barrier_index: usize,
+    },
+
+    /// A render pass instance is active, and the image of an image memory barrier is not a color
+    /// or depth/stencil attachment of the current subpass.
+    ImageMemoryBarrierNotColorDepthStencilAttachment {
+        barrier_index: usize,
+    },
+
+    /// A render pass instance is active, and the image of an image memory barrier is not an input
+    /// attachment of the current subpass.
+    ImageMemoryBarrierNotInputAttachment {
+        barrier_index: usize,
+    },
+
+    /// An image memory barrier contains an image layout transition, but some of its subresources
+    /// haven't been used by earlier commands of the command buffer.
+    ImageMemoryBarrierNotPreviouslyUsed {
+        barrier_index: usize,
+    },
+
+    /// The `src_stages` of an image memory barrier contains [`HOST`], but `old_layout` is not
+    /// `Preinitialized`, `Undefined` or `General`.
+    ///
+    /// [`HOST`]: crate::sync::PipelineStages::HOST
+    ImageMemoryBarrierOldLayoutFromHostInvalid {
+        barrier_index: usize,
+        old_layout: ImageLayout,
+    },
+
+    /// The `old_layout` of an image memory barrier is not the layout that some of its
+    /// subresources are in after the earlier commands of the command buffer.
+    ImageMemoryBarrierOldLayoutMismatch {
+        barrier_index: usize,
+        current_layout: ImageLayout,
+    },
+
+    /// An image memory barrier contains a queue family ownership transfer, but a render pass
+    /// instance is active.
+    ImageMemoryBarrierOwnershipTransferForbiddenInsideRenderPass {
+        barrier_index: usize,
+    },
+
+    /// An image memory barrier contains a queue family ownership transfer, but either the
+    /// `src_stages` or `dst_stages` contain [`HOST`].
+    ///
+    /// [`HOST`]: crate::sync::PipelineStages::HOST
+    ImageMemoryBarrierOwnershipTransferHostForbidden {
+        barrier_index: usize,
+    },
+
+    /// The provided `src_index` or `dst_index` in the queue family ownership transfer of an
+    /// image memory barrier is not less than the number of queue families in the physical device.
+    ImageMemoryBarrierOwnershipTransferIndexOutOfRange {
+        barrier_index: usize,
+        provided_queue_family_index: u32,
+        queue_family_count: u32,
+    },
+
+    /// The provided `queue_family_ownership_transfer` value of an image memory barrier does not
+    /// match the sharing mode of `image`.
+    ImageMemoryBarrierOwnershipTransferSharingMismatch {
+        barrier_index: usize,
+    },
+
+    /// One or more pipeline stages of an image memory barrier are not supported by the queue
+    /// family of the command buffer.
+    ImageMemoryBarrierStageNotSupported {
+        barrier_index: usize,
+    },
+
+    /// One or more accesses of a memory barrier are not supported by the corresponding
+    /// pipeline stages.
+    MemoryBarrierAccessNotSupportedByStages {
+        barrier_index: usize,
+    },
+
+    /// A render pass instance is active, but the render pass does not have a subpass
+    /// self-dependency for the current subpass that is a superset of the barriers.
+    MemoryBarrierNoMatchingSubpassSelfDependency,
+
+    /// One or more pipeline stages of a memory barrier are not supported by the queue
+    /// family of the command buffer.
+    MemoryBarrierStageNotSupported {
+        barrier_index: usize,
+    },
+
+    /// The queue family doesn't allow this operation.
+    NotSupportedByQueueFamily,
+}
+
+impl Error for SynchronizationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SyncCommandBufferBuilderError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SynchronizationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::SyncCommandBufferBuilderError(_) => write!(f, "a SyncCommandBufferBuilderError"),
+
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+
+            Self::BufferMemoryBarrierAccessNotSupportedByStages { barrier_index } => write!(
+                f,
+                "one or more accesses of buffer memory barrier {} are not supported by the \
+                corresponding pipeline stages",
+                barrier_index,
+            ),
+            Self::BufferMemoryBarrierForbiddenInsideRenderPass => write!(
+                f,
+                "buffer memory barriers are forbidden inside a render pass instance",
+            ),
+            Self::BufferMemoryBarrierOutOfRange {
+                barrier_index,
+                range_end,
+                buffer_size,
+            } => write!(
+                f,
+                "the end of `range` ({}) of buffer memory barrier {} is greater than the size of \
+                `buffer` ({})",
+                range_end, barrier_index, buffer_size,
+            ),
+            Self::BufferMemoryBarrierOwnershipTransferHostNotAllowed { barrier_index } => write!(
+                f,
+                "buffer memory barrier {} contains a queue family ownership transfer, but either \
+                the `src_stages` or `dst_stages` contain `HOST`",
+                barrier_index,
+            ),
+            Self::BufferMemoryBarrierOwnershipTransferIndexOutOfRange {
+                barrier_index,
+                provided_queue_family_index,
+                queue_family_count,
+            } => write!(
+                f,
+                "the provided `src_index` or `dst_index` ({}) in the queue family ownership \
+                transfer of buffer memory barrier {} is not less than the number of queue \
+                families in the physical device ({})",
+                provided_queue_family_index, barrier_index, queue_family_count,
+            ),
+            Self::BufferMemoryBarrierOwnershipTransferSharingMismatch { barrier_index } => write!(
+                f,
+                "the provided `queue_family_ownership_transfer` value of buffer memory barrier {} \
+                does not match the sharing mode of `buffer`",
+                barrier_index,
+            ),
+            Self::BufferMemoryBarrierStageNotSupported { barrier_index } => write!(
+                f,
+                "one or more pipeline stages of buffer memory barrier {} are not supported by the \
+                queue family of the command buffer",
+                barrier_index,
+            ),
+            Self::DependencyFlagsNotAllowed => write!(
+                f,
+                "dependency flags were provided when setting an event, which is not allowed",
+            ),
+            Self::DependencyFlagsViewLocalNotAllowed => write!(
+                f,
+                "a render pass instance is not active, and the `VIEW_LOCAL` dependency flag was \
+                provided",
+            ),
+            Self::EventAlreadySet => write!(
+                f,
+                "the event was already set by an earlier command, and hasn't been waited on yet",
+            ),
+            Self::EventNotSet { event_index } => write!(
+                f,
+                "event {} wasn't set by an earlier command of the command buffer, or was already \
+                waited on",
+                event_index,
+            ),
+            Self::ForbiddenInsideRenderPass => {
+                write!(f, "operation forbidden inside a render pass")
+            }
+            Self::ForbiddenWithBeginRendering => write!(
+                f,
+                "operation forbidden inside a render pass instance that was begun with \
+                `begin_rendering`",
+            ),
+            Self::ImageMemoryBarrierAccessNotSupportedByStages { barrier_index } => write!(
+                f,
+                "one or more accesses of image memory barrier {} are not supported by the \
+                corresponding pipeline stages",
+                barrier_index,
+            ),
+            Self::ImageMemoryBarrierArrayLayersOutOfRange {
+                barrier_index,
+                array_layers_range_end,
+                image_array_layers,
+            } => write!(
+                f,
+                "the end of the range of array layers ({}) of the subresource range of image \
+                memory barrier {} is greater than the number of array layers in the image ({})",
+                array_layers_range_end, barrier_index, image_array_layers,
+            ),
+            Self::ImageMemoryBarrierAspectsNotAllowed {
+                barrier_index,
+                aspects,
+            } => write!(
+                f,
+                "the aspects of the subresource range of image memory barrier {} contain aspects \
+                that are not present in the image, or that are not allowed ({:?})",
+                barrier_index, aspects,
+            ),
+            Self::ImageMemoryBarrierImageMissingUsageForLayout {
+                barrier_index,
+                layout,
+                requires_one_of_usage,
+            } => write!(
+                f,
+                "for the `old_layout` or `new_layout` ({:?}) of image memory barrier {}, `image` \
+                does not have a usage that is required ({}{:?})",
+                layout,
+                barrier_index,
+                if requires_one_of_usage.count() > 1 {
+                    "one of "
+                } else {
+                    ""
+                },
+                requires_one_of_usage,
+            ),
+            Self::ImageMemoryBarrierLayoutTransitionForbiddenInsideRenderPass { barrier_index } => {
+                write!(
+                    f,
+                    "image memory barrier {} contains an image layout transition, but a render \
+                    pass instance is active",
+                    barrier_index,
+                )
+            }
+            Self::ImageMemoryBarrierMipLevelsOutOfRange {
+                barrier_index,
+                mip_levels_range_end,
+                image_mip_levels,
+            } => write!(
+                f,
+                "the end of the range of mip levels ({}) of the subresource range of image \
+                memory barrier {} is greater than the number of mip levels in the image ({})",
+                mip_levels_range_end, barrier_index, image_mip_levels,
+            ),
+            Self::ImageMemoryBarrierNewLayoutInvalid { barrier_index } => write!(
+                f,
+                "the `new_layout` of image memory barrier {} is `Undefined` or `Preinitialized`",
+                barrier_index,
+            ),
+            Self::ImageMemoryBarrierNotColorDepthStencilAttachment { barrier_index } => write!(
+                f,
+                "a render pass instance is active, and the image of image memory barrier {} is \
+                not a color or depth/stencil attachment of the current subpass",
+                barrier_index,
+            ),
+            Self::ImageMemoryBarrierNotInputAttachment { barrier_index } => write!(
+                f,
+                "a render pass instance is active, and the image of image memory barrier {} is \
+                not an input attachment of the current subpass",
+                barrier_index,
+            ),
+            Self::ImageMemoryBarrierNotPreviouslyUsed { barrier_index } => write!(
+                f,
+                "image memory barrier {} contains an image layout transition, but some of its \
+                subresources haven't been used by earlier commands of the command buffer",
+                barrier_index,
+            ),
+            Self::ImageMemoryBarrierOldLayoutFromHostInvalid {
+                barrier_index,
+                old_layout,
+            } => write!(
+                f,
+                "the `src_stages` of image memory barrier {} contains `HOST`, but `old_layout`
+                ({:?}) is not `Preinitialized`, `Undefined` or `General`",
+                barrier_index, old_layout,
+            ),
+            Self::ImageMemoryBarrierOldLayoutMismatch {
+                barrier_index,
+                current_layout,
+            } => write!(
+                f,
+                "the `old_layout` of image memory barrier {} is not the layout that some of its \
+                subresources are in after the earlier commands of the command buffer ({:?})",
+                barrier_index, current_layout,
+            ),
+            Self::ImageMemoryBarrierOwnershipTransferForbiddenInsideRenderPass {
+                barrier_index,
+            } => write!(
+                f,
+                "image memory barrier {} contains a queue family ownership transfer, but a render \
+                pass instance is active",
+                barrier_index,
+            ),
+            Self::ImageMemoryBarrierOwnershipTransferHostForbidden { barrier_index } => write!(
+                f,
+                "image memory barrier {} contains a queue family ownership transfer, but either \
+                the `src_stages` or `dst_stages` contain `HOST`",
+                barrier_index,
+            ),
+            Self::ImageMemoryBarrierOwnershipTransferIndexOutOfRange {
+                barrier_index,
+                provided_queue_family_index,
+                queue_family_count,
+            } => write!(
+                f,
+                "the provided `src_index` or `dst_index` ({}) in the queue family ownership \
+                transfer of image memory barrier {} is not less than the number of queue
+                families in the physical device ({})",
+                provided_queue_family_index, barrier_index, queue_family_count,
+            ),
+            Self::ImageMemoryBarrierOwnershipTransferSharingMismatch { barrier_index } => write!(
+                f,
+                "the provided `queue_family_ownership_transfer` value of image memory barrier {} \
+                does not match the sharing mode of `image`",
+                barrier_index,
+            ),
+            Self::ImageMemoryBarrierStageNotSupported { barrier_index } => write!(
+                f,
+                "one or more pipeline stages of image memory barrier {} are not supported by the \
+                queue family of the command buffer",
+                barrier_index,
+            ),
+            Self::MemoryBarrierAccessNotSupportedByStages { barrier_index } => write!(
+                f,
+                "one or more accesses of memory barrier {} are not supported by the \
+                corresponding pipeline stages",
+                barrier_index,
+            ),
+            Self::MemoryBarrierNoMatchingSubpassSelfDependency => write!(
+                f,
+                "a render pass instance is active, but the render pass does not have a subpass \
+                self-dependency for the current subpass that is a superset of the barriers",
+            ),
+            Self::MemoryBarrierStageNotSupported { barrier_index } => write!(
+                f,
+                "one or more pipeline stages of memory barrier {} are not supported by the \
+                queue family of the command buffer",
+                barrier_index,
+            ),
+            Self::NotSupportedByQueueFamily => {
+                write!(f, "the queue family doesn't allow this operation")
+            }
+        }
+    }
+}
+
+impl From<RequirementNotMet> for SynchronizationError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
+impl From<SyncCommandBufferBuilderError> for SynchronizationError {
+    fn from(err: SyncCommandBufferBuilderError) -> Self {
+        Self::SyncCommandBufferBuilderError(err)
+    }
+}
@@ -0,0 +1,757 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use crate::{
+    buffer::{BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::CommandBufferAllocator,
+        synced::{Command, Resource, SyncCommandBufferBuilder, SyncCommandBufferBuilderError},
+        sys::UnsafeCommandBufferBuilder,
+        AutoCommandBufferBuilder, ResourceInCommand, ResourceUseRef,
+    },
+    device::{DeviceOwned, QueueFlags},
+    sync::{AccessFlags, PipelineMemoryAccess, PipelineStages},
+    DeviceSize, RequirementNotMet, RequiresOneOf, VulkanObject,
+};
+use smallvec::SmallVec;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+};
+
+/// # Commands to capture vertex data with transform feedback.
+///
+/// These commands require the [`transform_feedback`](crate::device::Features::transform_feedback)
+/// feature to be enabled on the device, and a graphics queue.
+impl<L, A> AutoCommandBufferBuilder<L, A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Binds buffers to the transform feedback bindings, starting at `first_binding`.
+    ///
+    /// The whole range of each subbuffer is used to capture vertex data. The buffers must have
+    /// the [`TRANSFORM_FEEDBACK_BUFFER`](BufferUsage::TRANSFORM_FEEDBACK_BUFFER) usage, and their
+    /// offsets must be a multiple of 4.
+    ///
+    /// Transform feedback must not be active.
+    pub fn bind_transform_feedback_buffers(
+        &mut self,
+        first_binding: u32,
+        buffers: Vec<Subbuffer<[u8]>>,
+    ) -> Result<&mut Self, TransformFeedbackError> {
+        self.validate_bind_transform_feedback_buffers(first_binding, &buffers)?;
+
+        unsafe {
+            self.inner
+                .bind_transform_feedback_buffers(first_binding, buffers)?;
+        }
+
+        Ok(self)
+    }
+
+    fn validate_bind_transform_feedback_buffers(
+        &self,
+        first_binding: u32,
+        buffers: &[Subbuffer<[u8]>],
+    ) -> Result<(), TransformFeedbackError> {
+        let device = self.device();
+
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-transformFeedback-02355
+        if !device.enabled_features().transform_feedback {
+            return Err(TransformFeedbackError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::bind_transform_feedback_buffers`",
+                requires_one_of: RequiresOneOf {
+                    features: &["transform_feedback"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-commandBuffer-cmdpool
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::GRAPHICS)
+        {
+            return Err(TransformFeedbackError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-None-02365
+        if self.transform_feedback_active {
+            return Err(TransformFeedbackError::TransformFeedbackActive);
+        }
+
+        let properties = device.physical_device().properties();
+        let max_transform_feedback_buffers = properties.max_transform_feedback_buffers.unwrap_or(0);
+
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-firstBinding-02356
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-firstBinding-02357
+        if first_binding + buffers.len() as u32 > max_transform_feedback_buffers {
+            return Err(
+                TransformFeedbackError::MaxTransformFeedbackBuffersExceeded {
+                    binding_count: first_binding + buffers.len() as u32,
+                    max: max_transform_feedback_buffers,
+                },
+            );
+        }
+
+        for (binding, buffer) in (first_binding..).zip(buffers) {
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-commonparent
+            assert_eq!(device, buffer.device());
+
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-pBuffers-02360
+            if !buffer
+                .buffer()
+                .usage()
+                .intersects(BufferUsage::TRANSFORM_FEEDBACK_BUFFER)
+            {
+                return Err(TransformFeedbackError::BufferMissingUsage { binding });
+            }
+
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-pOffsets-02359
+            if buffer.offset() % 4 != 0 {
+                return Err(TransformFeedbackError::BufferOffsetNotAligned {
+                    binding,
+                    offset: buffer.offset(),
+                });
+            }
+
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-pOffsets-02358
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-pSize-02361
+            // Ensured by the `Subbuffer`.
+
+            let max_transform_feedback_buffer_size =
+                properties.max_transform_feedback_buffer_size.unwrap_or(0);
+
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-pSize-02362
+            if buffer.size() > max_transform_feedback_buffer_size {
+                return Err(
+                    TransformFeedbackError::MaxTransformFeedbackBufferSizeExceeded {
+                        binding,
+                        size: buffer.size(),
+                        max: max_transform_feedback_buffer_size,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes transform feedback active, so that vertex data is captured into the bound transform
+    /// feedback buffers by subsequent draw commands.
+    ///
+    /// `counter_buffers` hold the byte position in each transform feedback buffer, starting at
+    /// `first_counter_buffer`, at which to resume capturing. These are usually the counter
+    /// buffers that were written by a previous call to
+    /// [`end_transform_feedback`](Self::end_transform_feedback). Capturing starts at the
+    /// beginning of any transform feedback buffer that has no counter buffer. The counter buffers
+    /// must have the
+    /// [`TRANSFORM_FEEDBACK_COUNTER_BUFFER`](BufferUsage::TRANSFORM_FEEDBACK_COUNTER_BUFFER)
+    /// usage.
+    ///
+    /// Transform feedback must not already be active, and the command must be recorded inside a
+    /// render pass. Transform feedback must be made inactive before the end of the subpass.
+    pub fn begin_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: Vec<Subbuffer<u32>>,
+    ) -> Result<&mut Self, TransformFeedbackError> {
+        self.validate_begin_transform_feedback(first_counter_buffer, &counter_buffers)?;
+
+        unsafe {
+            self.inner
+                .begin_transform_feedback(first_counter_buffer, counter_buffers)?;
+        }
+
+        self.transform_feedback_active = true;
+
+        Ok(self)
+    }
+
+    fn validate_begin_transform_feedback(
+        &self,
+        first_counter_buffer: u32,
+        counter_buffers: &[Subbuffer<u32>],
+    ) -> Result<(), TransformFeedbackError> {
+        let device = self.device();
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-transformFeedback-02366
+        if !device.enabled_features().transform_feedback {
+            return Err(TransformFeedbackError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::begin_transform_feedback`",
+                requires_one_of: RequiresOneOf {
+                    features: &["transform_feedback"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-commandBuffer-cmdpool
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::GRAPHICS)
+        {
+            return Err(TransformFeedbackError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-renderpass
+        if self.render_pass_state.is_none() {
+            return Err(TransformFeedbackError::ForbiddenOutsideRenderPass);
+        }
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-None-02367
+        if self.transform_feedback_active {
+            return Err(TransformFeedbackError::TransformFeedbackActive);
+        }
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-firstCounterBuffer-02368
+        // VUID-vkCmdBeginTransformFeedbackEXT-firstCounterBuffer-02369
+        self.validate_transform_feedback_counter_buffers(first_counter_buffer, counter_buffers)?;
+
+        // TODO:
+        // VUID-vkCmdBeginTransformFeedbackEXT-None-02373
+        // VUID-vkCmdBeginTransformFeedbackEXT-None-04128
+        // VUID-vkCmdBeginTransformFeedbackEXT-None-06233
+
+        Ok(())
+    }
+
+    /// Makes transform feedback inactive, after it was made active with
+    /// [`begin_transform_feedback`](Self::begin_transform_feedback).
+    ///
+    /// The byte position in each transform feedback buffer, starting at `first_counter_buffer`,
+    /// at which capturing stopped is written to `counter_buffers`. These can be passed to a later
+    /// call to `begin_transform_feedback` to resume capturing, or to
+    /// [`draw_indirect_byte_count`](Self::draw_indirect_byte_count). The counter buffers must have
+    /// the [`TRANSFORM_FEEDBACK_COUNTER_BUFFER`](BufferUsage::TRANSFORM_FEEDBACK_COUNTER_BUFFER)
+    /// usage.
+    pub fn end_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: Vec<Subbuffer<u32>>,
+    ) -> Result<&mut Self, TransformFeedbackError> {
+        self.validate_end_transform_feedback(first_counter_buffer, &counter_buffers)?;
+
+        unsafe {
+            self.inner
+                .end_transform_feedback(first_counter_buffer, counter_buffers)?;
+        }
+
+        self.transform_feedback_active = false;
+
+        Ok(self)
+    }
+
+    fn validate_end_transform_feedback(
+        &self,
+        first_counter_buffer: u32,
+        counter_buffers: &[Subbuffer<u32>],
+    ) -> Result<(), TransformFeedbackError> {
+        let device = self.device();
+
+        // VUID-vkCmdEndTransformFeedbackEXT-transformFeedback-02374
+        if !device.enabled_features().transform_feedback {
+            return Err(TransformFeedbackError::RequirementNotMet {
+                required_for: "`AutoCommandBufferBuilder::end_transform_feedback`",
+                requires_one_of: RequiresOneOf {
+                    features: &["transform_feedback"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdEndTransformFeedbackEXT-commandBuffer-cmdpool
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::GRAPHICS)
+        {
+            return Err(TransformFeedbackError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdEndTransformFeedbackEXT-renderpass
+        if self.render_pass_state.is_none() {
+            return Err(TransformFeedbackError::ForbiddenOutsideRenderPass);
+        }
+
+        // VUID-vkCmdEndTransformFeedbackEXT-None-02375
+        if !self.transform_feedback_active {
+            return Err(TransformFeedbackError::TransformFeedbackNotActive);
+        }
+
+        // VUID-vkCmdEndTransformFeedbackEXT-firstCounterBuffer-02376
+        // VUID-vkCmdEndTransformFeedbackEXT-firstCounterBuffer-02377
+        self.validate_transform_feedback_counter_buffers(first_counter_buffer, counter_buffers)?;
+
+        Ok(())
+    }
+
+    fn validate_transform_feedback_counter_buffers(
+        &self,
+        first_counter_buffer: u32,
+        counter_buffers: &[Subbuffer<u32>],
+    ) -> Result<(), TransformFeedbackError> {
+        let device = self.device();
+        let max_transform_feedback_buffers = device
+            .physical_device()
+            .properties()
+            .max_transform_feedback_buffers
+            .unwrap_or(0);
+
+        if first_counter_buffer + counter_buffers.len() as u32 > max_transform_feedback_buffers {
+            return Err(
+                TransformFeedbackError::MaxTransformFeedbackBuffersExceeded {
+                    binding_count: first_counter_buffer + counter_buffers.len() as u32,
+                    max: max_transform_feedback_buffers,
+                },
+            );
+        }
+
+        for (index, counter_buffer) in (first_counter_buffer..).zip(counter_buffers) {
+            // VUID-vkCmdBeginTransformFeedbackEXT-commonparent
+            assert_eq!(device, counter_buffer.device());
+
+            // VUID-vkCmdBeginTransformFeedbackEXT-pCounterBuffers-02372
+            if !counter_buffer
+                .buffer()
+                .usage()
+                .intersects(BufferUsage::TRANSFORM_FEEDBACK_COUNTER_BUFFER)
+            {
+                return Err(TransformFeedbackError::CounterBufferMissingUsage { index });
+            }
+
+            // VUID-vkCmdBeginTransformFeedbackEXT-pCounterBufferOffsets-02370
+            // Ensured by the size of the `Subbuffer<u32>`.
+        }
+
+        Ok(())
+    }
+}
+
+impl SyncCommandBufferBuilder {
+    /// Calls `vkCmdBindTransformFeedbackBuffersEXT` on the builder.
+    #[inline]
+    pub unsafe fn bind_transform_feedback_buffers(
+        &mut self,
+        first_binding: u32,
+        buffers: Vec<Subbuffer<[u8]>>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            first_binding: u32,
+            buffers: Vec<Subbuffer<[u8]>>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "bind_transform_feedback_buffers"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.bind_transform_feedback_buffers(self.first_binding, &self.buffers);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "bind_transform_feedback_buffers";
+
+        // The buffers are written by the draw commands that are recorded while transform feedback
+        // is active, which must all come after this command. Tracking the write here makes sure
+        // that any later use of the buffers waits for the captured data.
+        let resources: SmallVec<[_; 4]> = (first_binding..)
+            .zip(&buffers)
+            .map(|(binding, buffer)| {
+                (
+                    ResourceUseRef {
+                        command_index,
+                        command_name,
+                        resource_in_command: ResourceInCommand::TransformFeedbackBuffer { binding },
+                        secondary_use_ref: None,
+                    },
+                    Resource::Buffer {
+                        buffer: buffer.clone(),
+                        range: 0..buffer.size(),
+                        memory: PipelineMemoryAccess {
+                            stages: PipelineStages::TRANSFORM_FEEDBACK,
+                            access: AccessFlags::TRANSFORM_FEEDBACK_WRITE,
+                            exclusive: true,
+                        },
+                    },
+                )
+            })
+            .collect();
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            first_binding,
+            buffers,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdBeginTransformFeedbackEXT` on the builder.
+    #[inline]
+    pub unsafe fn begin_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: Vec<Subbuffer<u32>>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            first_counter_buffer: u32,
+            counter_buffers: Vec<Subbuffer<u32>>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "begin_transform_feedback"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.begin_transform_feedback(self.first_counter_buffer, &self.counter_buffers);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "begin_transform_feedback";
+        let resources: SmallVec<[_; 4]> = (first_counter_buffer..)
+            .zip(&counter_buffers)
+            .map(|(index, counter_buffer)| {
+                (
+                    ResourceUseRef {
+                        command_index,
+                        command_name,
+                        resource_in_command: ResourceInCommand::TransformFeedbackCounterBuffer {
+                            index,
+                        },
+                        secondary_use_ref: None,
+                    },
+                    Resource::Buffer {
+                        buffer: counter_buffer.as_bytes().clone(),
+                        range: 0..counter_buffer.size(),
+                        memory: PipelineMemoryAccess {
+                            stages: PipelineStages::DRAW_INDIRECT,
+                            access: AccessFlags::TRANSFORM_FEEDBACK_COUNTER_READ,
+                            exclusive: false,
+                        },
+                    },
+                )
+            })
+            .collect();
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            first_counter_buffer,
+            counter_buffers,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdEndTransformFeedbackEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: Vec<Subbuffer<u32>>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            first_counter_buffer: u32,
+            counter_buffers: Vec<Subbuffer<u32>>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "end_transform_feedback"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.end_transform_feedback(self.first_counter_buffer, &self.counter_buffers);
+            }
+        }
+
+        let command_index = self.commands.len();
+        let command_name = "end_transform_feedback";
+        let resources: SmallVec<[_; 4]> = (first_counter_buffer..)
+            .zip(&counter_buffers)
+            .map(|(index, counter_buffer)| {
+                (
+                    ResourceUseRef {
+                        command_index,
+                        command_name,
+                        resource_in_command: ResourceInCommand::TransformFeedbackCounterBuffer {
+                            index,
+                        },
+                        secondary_use_ref: None,
+                    },
+                    Resource::Buffer {
+                        buffer: counter_buffer.as_bytes().clone(),
+                        range: 0..counter_buffer.size(),
+                        memory: PipelineMemoryAccess {
+                            stages: PipelineStages::TRANSFORM_FEEDBACK,
+                            access: AccessFlags::TRANSFORM_FEEDBACK_COUNTER_WRITE,
+                            exclusive: true,
+                        },
+                    },
+                )
+            })
+            .collect();
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            first_counter_buffer,
+            counter_buffers,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+}
+
+impl UnsafeCommandBufferBuilder {
+    /// Calls `vkCmdBindTransformFeedbackBuffersEXT` on the builder.
+    #[inline]
+    pub unsafe fn bind_transform_feedback_buffers(
+        &mut self,
+        first_binding: u32,
+        buffers: &[Subbuffer<[u8]>],
+    ) {
+        if buffers.is_empty() {
+            return;
+        }
+
+        let (buffers_vk, (offsets_vk, sizes_vk)): (
+            SmallVec<[_; 4]>,
+            (SmallVec<[_; 4]>, SmallVec<[_; 4]>),
+        ) = buffers
+            .iter()
+            .map(|buffer| {
+                debug_assert!(buffer
+                    .buffer()
+                    .usage()
+                    .intersects(BufferUsage::TRANSFORM_FEEDBACK_BUFFER));
+
+                (buffer.buffer().handle(), (buffer.offset(), buffer.size()))
+            })
+            .unzip();
+
+        let fns = self.device.fns();
+        (fns.ext_transform_feedback
+            .cmd_bind_transform_feedback_buffers_ext)(
+            self.handle,
+            first_binding,
+            buffers_vk.len() as u32,
+            buffers_vk.as_ptr(),
+            offsets_vk.as_ptr(),
+            sizes_vk.as_ptr(),
+        );
+    }
+
+    /// Calls `vkCmdBeginTransformFeedbackEXT` on the builder.
+    #[inline]
+    pub unsafe fn begin_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: &[Subbuffer<u32>],
+    ) {
+        let (counter_buffers_vk, counter_buffer_offsets_vk): (SmallVec<[_; 4]>, SmallVec<[_; 4]>) =
+            counter_buffers
+                .iter()
+                .map(|counter_buffer| (counter_buffer.buffer().handle(), counter_buffer.offset()))
+                .unzip();
+
+        let fns = self.device.fns();
+        (fns.ext_transform_feedback.cmd_begin_transform_feedback_ext)(
+            self.handle,
+            first_counter_buffer,
+            counter_buffers_vk.len() as u32,
+            counter_buffers_vk.as_ptr(),
+            counter_buffer_offsets_vk.as_ptr(),
+        );
+    }
+
+    /// Calls `vkCmdEndTransformFeedbackEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: &[Subbuffer<u32>],
+    ) {
+        let (counter_buffers_vk, counter_buffer_offsets_vk): (SmallVec<[_; 4]>, SmallVec<[_; 4]>) =
+            counter_buffers
+                .iter()
+                .map(|counter_buffer| (counter_buffer.buffer().handle(), counter_buffer.offset()))
+                .unzip();
+
+        let fns = self.device.fns();
+        (fns.ext_transform_feedback.cmd_end_transform_feedback_ext)(
+            self.handle,
+            first_counter_buffer,
+            counter_buffers_vk.len() as u32,
+            counter_buffers_vk.as_ptr(),
+            counter_buffer_offsets_vk.as_ptr(),
+        );
+    }
+}
+
+/// Error that can happen when recording a transform feedback command.
+#[derive(Clone, Debug)]
+pub enum TransformFeedbackError {
+    SyncCommandBufferBuilderError(SyncCommandBufferBuilderError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// The `transform_feedback_buffer` usage was not enabled on a transform feedback buffer.
+    BufferMissingUsage {
+        binding: u32,
+    },
+
+    /// The offset of a transform feedback buffer is not a multiple of 4.
+    BufferOffsetNotAligned {
+        binding: u32,
+        offset: DeviceSize,
+    },
+
+    /// The `transform_feedback_counter_buffer` usage was not enabled on a counter buffer.
+    CounterBufferMissingUsage {
+        index: u32,
+    },
+
+    /// Operation forbidden outside of a render pass.
+    ForbiddenOutsideRenderPass,
+
+    /// The size of a transform feedback buffer exceeds the
+    /// `max_transform_feedback_buffer_size` limit.
+    MaxTransformFeedbackBufferSizeExceeded {
+        binding: u32,
+        size: DeviceSize,
+        max: DeviceSize,
+    },
+
+    /// The highest transform feedback binding or counter buffer index exceeds the
+    /// `max_transform_feedback_buffers` limit.
+    MaxTransformFeedbackBuffersExceeded {
+        binding_count: u32,
+        max: u32,
+    },
+
+    /// The queue family doesn't allow this operation.
+    NotSupportedByQueueFamily,
+
+    /// Transform feedback is active, which is not allowed for this operation.
+    TransformFeedbackActive,
+
+    /// Transform feedback is not active.
+    TransformFeedbackNotActive,
+}
+
+impl Error for TransformFeedbackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SyncCommandBufferBuilderError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TransformFeedbackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::SyncCommandBufferBuilderError(_) => write!(f, "a SyncCommandBufferBuilderError"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::BufferMissingUsage { binding } => write!(
+                f,
+                "the `transform_feedback_buffer` usage was not enabled on the transform feedback \
+                buffer at binding {}",
+                binding,
+            ),
+            Self::BufferOffsetNotAligned { binding, offset } => write!(
+                f,
+                "the offset ({}) of the transform feedback buffer at binding {} is not a multiple \
+                of 4",
+                offset, binding,
+            ),
+            Self::CounterBufferMissingUsage { index } => write!(
+                f,
+                "the `transform_feedback_counter_buffer` usage was not enabled on counter buffer \
+                {}",
+                index,
+            ),
+            Self::ForbiddenOutsideRenderPass => {
+                write!(f, "operation forbidden outside of a render pass")
+            }
+            Self::MaxTransformFeedbackBufferSizeExceeded { binding, size, max } => write!(
+                f,
+                "the size ({}) of the transform feedback buffer at binding {} exceeds the \
+                `max_transform_feedback_buffer_size` limit ({})",
+                size, binding, max,
+            ),
+            Self::MaxTransformFeedbackBuffersExceeded { binding_count, max } => write!(
+                f,
+                "the number of transform feedback bindings or counter buffers ({}) exceeds the \
+                `max_transform_feedback_buffers` limit ({})",
+                binding_count, max,
+            ),
+            Self::NotSupportedByQueueFamily => {
+                write!(f, "the queue family doesn't allow this operation")
+            }
+            Self::TransformFeedbackActive => write!(
+                f,
+                "transform feedback is active, which is not allowed for this operation",
+            ),
+            Self::TransformFeedbackNotActive => write!(f, "transform feedback is not active"),
+        }
+    }
+}
+
+impl From<SyncCommandBufferBuilderError> for TransformFeedbackError {
+    fn from(err: SyncCommandBufferBuilderError) -> Self {
+        Self::SyncCommandBufferBuilderError(err)
+    }
+}
+
+impl From<RequirementNotMet> for TransformFeedbackError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
@@ -0,0 +1,1847 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Scheduling the passes of a frame and synchronizing them across queues.
+//!
+//! A renderer is usually made of many passes, each of which reads some resources and writes
+//! others. A [`RenderGraphBuilder`] is given the resources of the frame and the passes in the
+//! order in which they would run on a single queue. Each pass declares which buffers and images
+//! it reads and writes, and at which pipeline stages, and provides a closure that records its
+//! commands.
+//!
+//! Building the graph compiles it into a [`RenderGraph`] once:
+//!
+//! - Passes that don't contribute to any output are culled. A pass is kept if it has
+//!   [side effects], if it writes a resource that was [marked as an output], or if it writes a
+//!   resource that a kept pass reads afterwards.
+//! - Passes that run on the [`AsyncCompute`] queue are only ordered after the graphics passes
+//!   that they depend on, and the other way around. Passes are grouped into as few submissions as
+//!   possible, and a submission only waits on a semaphore of the other queue when one of its
+//!   passes depends on a pass of that submission.
+//! - Resources with [`Sharing::Exclusive`] that move between queue families have their ownership
+//!   released at the end of one submission and acquired at the start of the next one. Resources
+//!   are owned by the queue family of the first pass that accesses them, both before and after
+//!   the graph is executed, which may require an additional submission at the end of the graph.
+//! - [Transient images] are created in a [`TransientPool`], and share memory with other transient
+//!   images that are used by different passes.
+//!
+//! Within a submission, the passes are recorded into the same command buffer, which inserts the
+//! pipeline barriers and layout transitions between them.
+//!
+//! Transient images don't keep their contents from one submission to the next, so all the passes
+//! that use a transient image must end up in the same submission. Import an image instead if it
+//! needs to be shared between the graphics and the compute queue.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use vulkano::buffer::Subbuffer;
+//! # use vulkano::command_buffer::{
+//! #     allocator::StandardCommandBufferAllocator,
+//! #     graph::{PassQueue, RenderGraphBuilder},
+//! # };
+//! # use vulkano::image::view::ImageViewAbstract;
+//! # use vulkano::memory::allocator::TransientImageCreateInfo;
+//! # use vulkano::sync::{self, AccessFlags, GpuFuture, PipelineStages};
+//! # let graphics_queue: Arc<vulkano::device::Queue> = return;
+//! # let compute_queue: Arc<vulkano::device::Queue> = return;
+//! # let command_buffer_allocator: StandardCommandBufferAllocator = return;
+//! # let particles: Subbuffer<[f32]> = return;
+//! # let swapchain_image: Arc<dyn ImageViewAbstract> = return;
+//! # let scene_color_info: TransientImageCreateInfo = return;
+//! #
+//! let mut builder: RenderGraphBuilder = RenderGraphBuilder::new();
+//!
+//! let particles = builder.import_buffer(particles);
+//! let scene_color = builder.create_image(scene_color_info);
+//! let output = builder.import_image(swapchain_image);
+//! builder.mark_image_output(output);
+//!
+//! builder
+//!     .add_pass("simulate particles", PassQueue::AsyncCompute)
+//!     .writes_buffer(
+//!         particles,
+//!         PipelineStages::COMPUTE_SHADER,
+//!         AccessFlags::SHADER_WRITE,
+//!     )
+//!     .record(|builder, resources| {
+//!         // Dispatch the simulation, using `resources.buffer(particles)`...
+//!         Ok(())
+//!     });
+//!
+//! builder
+//!     .add_pass("scene", PassQueue::Graphics)
+//!     .reads_buffer(
+//!         particles,
+//!         PipelineStages::VERTEX_ATTRIBUTE_INPUT,
+//!         AccessFlags::VERTEX_ATTRIBUTE_READ,
+//!     )
+//!     .writes_image(
+//!         scene_color,
+//!         PipelineStages::COLOR_ATTACHMENT_OUTPUT,
+//!         AccessFlags::COLOR_ATTACHMENT_WRITE,
+//!     )
+//!     .record(|builder, resources| {
+//!         // Draw the scene into `resources.image(scene_color)`...
+//!         Ok(())
+//!     });
+//!
+//! builder
+//!     .add_pass("tonemap", PassQueue::Graphics)
+//!     .reads_image(
+//!         scene_color,
+//!         PipelineStages::FRAGMENT_SHADER,
+//!         AccessFlags::SHADER_SAMPLED_READ,
+//!     )
+//!     .writes_image(
+//!         output,
+//!         PipelineStages::COLOR_ATTACHMENT_OUTPUT,
+//!         AccessFlags::COLOR_ATTACHMENT_WRITE,
+//!     )
+//!     .record(|builder, resources| {
+//!         // Tonemap `resources.image(scene_color)` into `resources.image(output)`...
+//!         Ok(())
+//!     });
+//!
+//! let mut graph = builder
+//!     .build(graphics_queue.clone(), Some(compute_queue))
+//!     .unwrap();
+//!
+//! let future = graph
+//!     .execute(
+//!         &command_buffer_allocator,
+//!         sync::now(graphics_queue.device().clone()),
+//!     )
+//!     .unwrap()
+//!     .then_signal_fence_and_flush()
+//!     .unwrap();
+//! ```
+//!
+//! [side effects]: PassBuilder::side_effects
+//! [marked as an output]: RenderGraphBuilder::mark_image_output
+//! [`AsyncCompute`]: PassQueue::AsyncCompute
+//! [Transient images]: RenderGraphBuilder::create_image
+
+use super::{
+    allocator::{CommandBufferAllocator, StandardCommandBufferAllocator},
+    AutoCommandBufferBuilder, BarrierReason, BuildError, CommandBufferBeginError,
+    CommandBufferExecError, CommandBufferUsage, PrimaryAutoCommandBuffer,
+};
+use crate::{
+    buffer::Subbuffer,
+    device::{DeviceOwned, Queue, QueueFlags},
+    image::{
+        view::{ImageView, ImageViewAbstract, ImageViewCreationError},
+        ImageSubresourceRange,
+    },
+    memory::allocator::{
+        TransientImageCreateInfo, TransientPool, TransientPoolCreateInfo, TransientPoolError,
+    },
+    sync::{
+        self, AccessFlags, BufferMemoryBarrier, DependencyInfo, FlushError, GpuFuture,
+        ImageMemoryBarrier, PipelineStages, QueueFamilyOwnershipTransfer, Sharing,
+    },
+};
+use smallvec::SmallVec;
+use std::{
+    error::Error,
+    fmt::{Debug, Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+/// Builds a [`RenderGraph`].
+///
+/// See the [module-level documentation] for more information.
+///
+/// [module-level documentation]: self
+pub struct RenderGraphBuilder<A = StandardCommandBufferAllocator>
+where
+    A: CommandBufferAllocator,
+{
+    resources: Vec<ResourceNode>,
+    passes: Vec<PassInfo>,
+    records: Vec<Box<RecordFn<A>>>,
+}
+
+type RecordFn<A> = dyn FnMut(
+        &mut AutoCommandBufferBuilder<
+            PrimaryAutoCommandBuffer<<A as CommandBufferAllocator>::Alloc>,
+            A,
+        >,
+        &PassResources,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>
+    + Send;
+
+#[derive(Debug)]
+struct ResourceNode {
+    kind: ResourceKind,
+    output: bool,
+}
+
+#[derive(Debug)]
+enum ResourceKind {
+    Buffer(Subbuffer<[u8]>),
+    Image(Arc<dyn ImageViewAbstract>),
+    TransientImage(TransientImageCreateInfo),
+}
+
+impl<A> RenderGraphBuilder<A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Creates a new `RenderGraphBuilder` with no resources and no passes.
+    #[inline]
+    pub fn new() -> Self {
+        RenderGraphBuilder {
+            resources: Vec::new(),
+            passes: Vec::new(),
+            records: Vec::new(),
+        }
+    }
+
+    /// Adds a buffer that was created outside of the graph.
+    ///
+    /// Each buffer must only be imported once.
+    pub fn import_buffer<T>(&mut self, buffer: Subbuffer<T>) -> BufferId
+    where
+        T: ?Sized,
+    {
+        self.resources.push(ResourceNode {
+            kind: ResourceKind::Buffer(buffer.into_bytes()),
+            output: false,
+        });
+
+        BufferId(self.resources.len() - 1)
+    }
+
+    /// Adds an image that was created outside of the graph.
+    ///
+    /// Each image must only be imported once.
+    pub fn import_image(&mut self, image_view: Arc<dyn ImageViewAbstract>) -> ImageId {
+        self.resources.push(ResourceNode {
+            kind: ResourceKind::Image(image_view),
+            output: false,
+        });
+
+        ImageId(self.resources.len() - 1)
+    }
+
+    /// Adds an image that is created by the graph, and that only lives for the duration of the
+    /// passes that use it.
+    ///
+    /// The `lifetime` of `create_info` is ignored: it is computed from the passes that use the
+    /// image. The image is not created if all of these passes are culled. The contents of the
+    /// image are undefined at the start of the first pass that uses it, which must write it.
+    pub fn create_image(&mut self, create_info: TransientImageCreateInfo) -> ImageId {
+        self.resources.push(ResourceNode {
+            kind: ResourceKind::TransientImage(create_info),
+            output: false,
+        });
+
+        ImageId(self.resources.len() - 1)
+    }
+
+    /// Marks a buffer as an output of the graph, so that the passes that write it are not
+    /// culled.
+    #[inline]
+    pub fn mark_buffer_output(&mut self, buffer: BufferId) {
+        self.resources[buffer.0].output = true;
+    }
+
+    /// Marks an image as an output of the graph, so that the passes that write it are not
+    /// culled.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `image` was created with [`create_image`], since the contents of transient
+    ///   images don't outlive the graph.
+    ///
+    /// [`create_image`]: Self::create_image
+    #[inline]
+    pub fn mark_image_output(&mut self, image: ImageId) {
+        let resource = &mut self.resources[image.0];
+        assert!(!matches!(resource.kind, ResourceKind::TransientImage(_)));
+
+        resource.output = true;
+    }
+
+    /// Adds a pass that runs after all the passes that were added before it, on the queue given
+    /// by `queue`.
+    ///
+    /// The pass is added once [`PassBuilder::record`] is called.
+    pub fn add_pass(&mut self, name: impl Into<String>, queue: PassQueue) -> PassBuilder<'_, A> {
+        PassBuilder {
+            graph: self,
+            info: PassInfo {
+                name: name.into(),
+                queue,
+                accesses: SmallVec::new(),
+                side_effects: false,
+            },
+        }
+    }
+
+    /// Compiles the graph for execution on `graphics_queue`, and on `compute_queue` for the
+    /// passes on [`PassQueue::AsyncCompute`].
+    ///
+    /// If `compute_queue` is `None` or is the same queue as `graphics_queue`, all passes are
+    /// executed on `graphics_queue`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `compute_queue` or any of the imported resources don't belong to the same
+    ///   device as `graphics_queue`.
+    pub fn build(
+        self,
+        graphics_queue: Arc<Queue>,
+        compute_queue: Option<Arc<Queue>>,
+    ) -> Result<RenderGraph<A>, RenderGraphError> {
+        let device = graphics_queue.device().clone();
+
+        let mut queues: SmallVec<[Arc<Queue>; 2]> = SmallVec::new();
+        queues.push(graphics_queue);
+
+        if let Some(compute_queue) = compute_queue {
+            assert_eq!(compute_queue.device(), &device);
+
+            let queue_family_index = compute_queue.queue_family_index();
+
+            if !device.physical_device().queue_family_properties()[queue_family_index as usize]
+                .queue_flags
+                .intersects(QueueFlags::COMPUTE)
+            {
+                return Err(RenderGraphError::ComputeQueueFamilyNotSupported {
+                    queue_family_index,
+                });
+            }
+
+            if compute_queue != queues[0] {
+                queues.push(compute_queue);
+            }
+        }
+
+        let queue_family_indices: SmallVec<[u32; 2]> = queues
+            .iter()
+            .map(|queue| queue.queue_family_index())
+            .collect();
+
+        let resource_infos: Vec<_> = self
+            .resources
+            .iter()
+            .map(|resource| {
+                let exclusive = match &resource.kind {
+                    ResourceKind::Buffer(buffer) => {
+                        assert_eq!(buffer.device(), &device);
+                        matches!(buffer.buffer().sharing(), Sharing::Exclusive)
+                    }
+                    ResourceKind::Image(image_view) => {
+                        assert_eq!(image_view.device(), &device);
+                        matches!(
+                            image_view.image().inner().image.sharing(),
+                            Sharing::Exclusive
+                        )
+                    }
+                    // Transient images never leave the submission that they are used in.
+                    ResourceKind::TransientImage(_) => false,
+                };
+
+                ResourceInfo {
+                    exclusive,
+                    output: resource.output,
+                }
+            })
+            .collect();
+
+        let schedule = schedule(&self.passes, &resource_infos, &queue_family_indices);
+
+        let mut resources: Vec<_> = self
+            .resources
+            .iter()
+            .map(|resource| match &resource.kind {
+                ResourceKind::Buffer(buffer) => Some(GraphResource::Buffer(buffer.clone())),
+                ResourceKind::Image(image_view) => Some(GraphResource::Image(image_view.clone())),
+                ResourceKind::TransientImage(_) => None,
+            })
+            .collect();
+        let mut transient_pools: Vec<Option<TransientPool>> =
+            (0..schedule.batches.len()).map(|_| None).collect();
+
+        // The batch that uses each transient image.
+        let mut transient_batches = vec![None; self.resources.len()];
+
+        for (batch_index, batch) in schedule.batches.iter().enumerate() {
+            let mut images: SmallVec<[(usize, TransientImageCreateInfo); 4]> = SmallVec::new();
+
+            for (pass_position, &pass_index) in batch.passes.iter().enumerate() {
+                let pass_position = pass_position as u32;
+
+                for access in &self.passes[pass_index].accesses {
+                    let create_info = match &self.resources[access.resource].kind {
+                        ResourceKind::TransientImage(create_info) => create_info,
+                        _ => continue,
+                    };
+
+                    if let Some((_, create_info)) = images
+                        .iter_mut()
+                        .find(|(resource, _)| *resource == access.resource)
+                    {
+                        create_info.lifetime.end = pass_position + 1;
+                        continue;
+                    }
+
+                    if transient_batches[access.resource]
+                        .replace(batch_index)
+                        .is_some()
+                    {
+                        return Err(RenderGraphError::TransientImageUsedAcrossSubmissions {
+                            image: ImageId(access.resource),
+                        });
+                    }
+
+                    if !self.passes[pass_index]
+                        .accesses
+                        .iter()
+                        .any(|other| other.resource == access.resource && other.write)
+                    {
+                        return Err(RenderGraphError::TransientImageReadBeforeWrite {
+                            image: ImageId(access.resource),
+                        });
+                    }
+
+                    images.push((
+                        access.resource,
+                        TransientImageCreateInfo {
+                            lifetime: pass_position..pass_position + 1,
+                            ..create_info.clone()
+                        },
+                    ));
+                }
+            }
+
+            if images.is_empty() {
+                continue;
+            }
+
+            let pool = TransientPool::new(
+                device.clone(),
+                TransientPoolCreateInfo {
+                    images: images
+                        .iter()
+                        .map(|(_, create_info)| create_info.clone())
+                        .collect(),
+                    ..Default::default()
+                },
+            )?;
+
+            for (index, &(resource, _)) in images.iter().enumerate() {
+                // SAFETY: The lifetime of each image covers the passes of the batch that use it,
+                // `execute` activates the pool at the start of each of these passes, and the
+                // passes only use the resources that they declared.
+                let image = unsafe { pool.image(index) }.clone();
+                resources[resource] = Some(GraphResource::TransientImage(ImageView::new_default(
+                    image,
+                )?));
+            }
+
+            transient_pools[batch_index] = Some(pool);
+        }
+
+        Ok(RenderGraph {
+            queues,
+            passes: self.passes,
+            records: self.records,
+            resources: PassResources { resources },
+            schedule,
+            transient_pools,
+        })
+    }
+}
+
+impl<A> Default for RenderGraphBuilder<A>
+where
+    A: CommandBufferAllocator,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> Debug for RenderGraphBuilder<A>
+where
+    A: CommandBufferAllocator,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        f.debug_struct("RenderGraphBuilder")
+            .field("resources", &self.resources)
+            .field("passes", &self.passes)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Declares the resources that a pass accesses, and the commands that it records.
+///
+/// Created with [`RenderGraphBuilder::add_pass`].
+pub struct PassBuilder<'a, A>
+where
+    A: CommandBufferAllocator,
+{
+    graph: &'a mut RenderGraphBuilder<A>,
+    info: PassInfo,
+}
+
+impl<'a, A> PassBuilder<'a, A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Declares that the pass reads `buffer` at `stages` with `access`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `access` contains accesses that are not supported by `stages`.
+    #[inline]
+    pub fn reads_buffer(
+        self,
+        buffer: BufferId,
+        stages: PipelineStages,
+        access: AccessFlags,
+    ) -> Self {
+        self.access(buffer.0, false, stages, access)
+    }
+
+    /// Declares that the pass writes `buffer` at `stages` with `access`.
+    ///
+    /// A pass that also depends on the previous contents of the buffer must declare a read as
+    /// well.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `access` contains accesses that are not supported by `stages`.
+    #[inline]
+    pub fn writes_buffer(
+        self,
+        buffer: BufferId,
+        stages: PipelineStages,
+        access: AccessFlags,
+    ) -> Self {
+        self.access(buffer.0, true, stages, access)
+    }
+
+    /// Declares that the pass reads `image` at `stages` with `access`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `access` contains accesses that are not supported by `stages`.
+    #[inline]
+    pub fn reads_image(self, image: ImageId, stages: PipelineStages, access: AccessFlags) -> Self {
+        self.access(image.0, false, stages, access)
+    }
+
+    /// Declares that the pass writes `image` at `stages` with `access`.
+    ///
+    /// A pass that also depends on the previous contents of the image, for example because it
+    /// blends into it, must declare a read as well.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `access` contains accesses that are not supported by `stages`.
+    #[inline]
+    pub fn writes_image(self, image: ImageId, stages: PipelineStages, access: AccessFlags) -> Self {
+        self.access(image.0, true, stages, access)
+    }
+
+    /// Declares that the pass has effects that are not visible to the graph, such as writing to
+    /// a buffer that is read by the host, so that it is never culled.
+    #[inline]
+    pub fn side_effects(mut self) -> Self {
+        self.info.side_effects = true;
+        self
+    }
+
+    fn access(
+        mut self,
+        resource: usize,
+        write: bool,
+        stages: PipelineStages,
+        access: AccessFlags,
+    ) -> Self {
+        assert!(resource < self.graph.resources.len());
+        assert!(AccessFlags::from(stages).contains(access));
+
+        self.info.accesses.push(PassAccess {
+            resource,
+            write,
+            stages,
+            access,
+        });
+        self
+    }
+
+    /// Adds the pass to the graph, with `record` recording its commands each time the graph is
+    /// executed.
+    ///
+    /// `record` is called outside of a render pass, and must leave the command buffer outside of
+    /// a render pass. It must only use the resources that the pass declared, and only in the
+    /// way that it declared them.
+    pub fn record<F>(self, record: F) -> PassId
+    where
+        F: FnMut(
+                &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer<A::Alloc>, A>,
+                &PassResources,
+            ) -> Result<(), Box<dyn Error + Send + Sync>>
+            + Send
+            + 'static,
+    {
+        let PassBuilder { graph, info } = self;
+        graph.passes.push(info);
+        graph.records.push(Box::new(record));
+
+        PassId(graph.passes.len() - 1)
+    }
+}
+
+/// The queue that a pass runs on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PassQueue {
+    /// The pass runs on the graphics queue.
+    Graphics,
+
+    /// The pass runs on the compute queue, concurrently with the graphics passes that it doesn't
+    /// depend on.
+    ///
+    /// If the graph is built without a separate compute queue, the pass runs on the graphics
+    /// queue instead.
+    AsyncCompute,
+}
+
+/// Identifies a buffer of a render graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BufferId(usize);
+
+/// Identifies an image of a render graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImageId(usize);
+
+/// Identifies a pass of a render graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PassId(usize);
+
+/// The resources of a render graph, as they are given to the passes while recording.
+#[derive(Debug)]
+pub struct PassResources {
+    // `None` for transient images whose passes were all culled.
+    resources: Vec<Option<GraphResource>>,
+}
+
+#[derive(Clone, Debug)]
+enum GraphResource {
+    Buffer(Subbuffer<[u8]>),
+    Image(Arc<dyn ImageViewAbstract>),
+    TransientImage(Arc<dyn ImageViewAbstract>),
+}
+
+impl PassResources {
+    /// Returns the buffer identified by `buffer`.
+    #[inline]
+    pub fn buffer(&self, buffer: BufferId) -> &Subbuffer<[u8]> {
+        match &self.resources[buffer.0] {
+            Some(GraphResource::Buffer(buffer)) => buffer,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns a view of the image identified by `image`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `image` is a transient image that is not used by any pass that was kept.
+    #[inline]
+    pub fn image(&self, image: ImageId) -> &Arc<dyn ImageViewAbstract> {
+        match &self.resources[image.0] {
+            Some(GraphResource::Image(image_view) | GraphResource::TransientImage(image_view)) => {
+                image_view
+            }
+            Some(GraphResource::Buffer(_)) => unreachable!(),
+            None => panic!("the transient image is not used by any pass that was kept"),
+        }
+    }
+}
+
+/// A compiled render graph, that can be executed any number of times.
+///
+/// See the [module-level documentation] for more information.
+///
+/// [module-level documentation]: self
+pub struct RenderGraph<A = StandardCommandBufferAllocator>
+where
+    A: CommandBufferAllocator,
+{
+    queues: SmallVec<[Arc<Queue>; 2]>,
+    passes: Vec<PassInfo>,
+    records: Vec<Box<RecordFn<A>>>,
+    resources: PassResources,
+    schedule: Schedule,
+    // The pool of the transient images of each batch.
+    transient_pools: Vec<Option<TransientPool>>,
+}
+
+impl<A> RenderGraph<A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Returns whether `pass` was culled because it doesn't contribute to any output.
+    #[inline]
+    pub fn is_culled(&self, pass: PassId) -> bool {
+        self.schedule.pass_batches[pass.0].is_none()
+    }
+
+    /// Returns the number of command buffers that are submitted each time the graph is executed.
+    #[inline]
+    pub fn submission_count(&self) -> usize {
+        self.schedule.batches.len()
+    }
+
+    /// Returns the resources of the graph.
+    #[inline]
+    pub fn resources(&self) -> &PassResources {
+        &self.resources
+    }
+
+    /// Replaces an imported buffer, for example to use a different buffer each frame.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `buffer` doesn't belong to the same device or doesn't use the same kind of
+    ///   [`Sharing`] as the buffer that it replaces.
+    pub fn set_buffer<T>(&mut self, id: BufferId, buffer: Subbuffer<T>)
+    where
+        T: ?Sized,
+    {
+        let buffer = buffer.into_bytes();
+        let resource = &mut self.resources.resources[id.0];
+
+        match resource {
+            Some(GraphResource::Buffer(old_buffer)) => {
+                assert_eq!(buffer.device(), old_buffer.device());
+                assert_eq!(
+                    matches!(buffer.buffer().sharing(), Sharing::Exclusive),
+                    matches!(old_buffer.buffer().sharing(), Sharing::Exclusive),
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        *resource = Some(GraphResource::Buffer(buffer));
+    }
+
+    /// Replaces an imported image, for example with the swapchain image that was acquired for
+    /// the current frame.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `image` is a transient image.
+    /// - Panics if `image_view` doesn't belong to the same device or doesn't use the same kind
+    ///   of [`Sharing`] as the image that it replaces.
+    pub fn set_image(&mut self, id: ImageId, image_view: Arc<dyn ImageViewAbstract>) {
+        let resource = &mut self.resources.resources[id.0];
+
+        match resource {
+            Some(GraphResource::Image(old_image_view)) => {
+                assert_eq!(image_view.device(), old_image_view.device());
+                assert_eq!(
+                    matches!(
+                        image_view.image().inner().image.sharing(),
+                        Sharing::Exclusive
+                    ),
+                    matches!(
+                        old_image_view.image().inner().image.sharing(),
+                        Sharing::Exclusive
+                    ),
+                );
+            }
+            Some(GraphResource::TransientImage(_)) | None => {
+                panic!("transient images can't be replaced")
+            }
+            Some(GraphResource::Buffer(_)) => unreachable!(),
+        }
+
+        *resource = Some(GraphResource::Image(image_view));
+    }
+}
+
+impl<A> RenderGraph<A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Records the passes that were kept into command buffers, and submits them to their queues
+    /// after `before`.
+    ///
+    /// The returned future is on the graphics queue, and includes the submissions to the compute
+    /// queue.
+    ///
+    /// The first submission of the graph waits for `before`, but submissions to the compute
+    /// queue whose passes don't depend on a graphics pass may start before it. If they use
+    /// resources that a previous execution of the graph may still be using, wait for the
+    /// previous execution to complete first, for example with a fence.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `command_buffer_allocator` doesn't belong to the same device as the graph.
+    pub fn execute<F>(
+        &mut self,
+        command_buffer_allocator: &A,
+        before: F,
+    ) -> Result<Box<dyn GpuFuture>, RenderGraphError>
+    where
+        F: GpuFuture + 'static,
+    {
+        let device = self.queues[0].device().clone();
+        assert_eq!(command_buffer_allocator.device(), &device);
+
+        let mut before = Some(before.boxed());
+
+        // The submissions of each queue so far.
+        let mut chains: SmallVec<[Option<Box<dyn GpuFuture>>; 2]> =
+            self.queues.iter().map(|_| None).collect();
+        // The semaphores that the batches signal, until another batch waits on them.
+        let mut signals: Vec<Option<Box<dyn GpuFuture>>> =
+            (0..self.schedule.batches.len()).map(|_| None).collect();
+        // The barriers that each batch released the ownership of resources with.
+        let mut releases: Vec<DependencyInfo> = (0..self.schedule.batches.len())
+            .map(|_| DependencyInfo::default())
+            .collect();
+
+        for batch_index in 0..self.schedule.batches.len() {
+            let (command_buffer, release) =
+                self.record_batch(batch_index, command_buffer_allocator, &releases)?;
+            releases[batch_index] = release;
+            let batch = &self.schedule.batches[batch_index];
+            let queue = &self.queues[batch.queue];
+
+            let mut future = match (chains[batch.queue].take(), before.take()) {
+                (_, Some(before)) => match before.queue() {
+                    Some(before_queue) if before_queue != *queue => {
+                        before.then_signal_semaphore_and_flush()?.boxed()
+                    }
+                    _ => before,
+                },
+                (Some(future), None) => future,
+                (None, None) => sync::now(device.clone()).boxed(),
+            };
+
+            for &wait in &batch.waits {
+                future = future.join(signals[wait].take().unwrap()).boxed();
+            }
+
+            let future = future.then_execute(queue.clone(), command_buffer)?;
+
+            if batch.signals {
+                // The submission is shared between the batches that wait on the semaphore and
+                // the later batches of the same queue, which must not wait on the semaphore too.
+                // A flushed fence signal future adds nothing to the submissions that follow it,
+                // so the semaphore is signaled by a submission of its own.
+                #[allow(clippy::arc_with_non_send_sync)]
+                let future = Arc::new(future.then_signal_fence_and_flush()?);
+                signals[batch_index] =
+                    Some(future.clone().then_signal_semaphore_and_flush()?.boxed());
+                chains[batch.queue] = Some(future.boxed());
+            } else {
+                chains[batch.queue] = Some(future.boxed());
+            }
+        }
+
+        // Nothing was submitted.
+        if let Some(before) = before {
+            return Ok(before);
+        }
+
+        let mut future: Option<Box<dyn GpuFuture>> = None;
+
+        for chain in chains.into_iter().flatten() {
+            future = Some(match future {
+                None => chain,
+                Some(future) => future
+                    .join(chain.then_signal_semaphore_and_flush()?)
+                    .boxed(),
+            });
+        }
+
+        Ok(future.unwrap_or_else(|| sync::now(device).boxed()))
+    }
+
+    /// Records the batch at `batch_index`, and returns the barriers that it releases the
+    /// ownership of resources with, as they were recorded.
+    fn record_batch(
+        &mut self,
+        batch_index: usize,
+        command_buffer_allocator: &A,
+        releases: &[DependencyInfo],
+    ) -> Result<(PrimaryAutoCommandBuffer<A::Alloc>, DependencyInfo), RenderGraphError> {
+        let batch = &self.schedule.batches[batch_index];
+        let release = self.ownership_releases(batch_index);
+        let acquire = self.ownership_acquires(batch_index, releases);
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            command_buffer_allocator,
+            self.queues[batch.queue].queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?;
+
+        // SAFETY: The resources were released by the batch that the acquiring batch waits on, and
+        // the acquire barriers match the release barriers as they were recorded, including the
+        // layouts that the images were left in.
+        if !acquire.buffer_memory_barriers.is_empty() || !acquire.image_memory_barriers.is_empty() {
+            unsafe {
+                builder.inner.pipeline_barrier_immediate(
+                    acquire,
+                    BarrierReason::QueueFamilyOwnershipTransfer,
+                )
+            };
+        }
+
+        for (pass_position, &pass_index) in batch.passes.iter().enumerate() {
+            if let Some(pool) = &self.transient_pools[batch_index] {
+                pool.activate(&mut builder, pass_position as u32)?;
+            }
+
+            (self.records[pass_index])(&mut builder, &self.resources).map_err(|error| {
+                RenderGraphError::PassError {
+                    pass: self.passes[pass_index].name.clone(),
+                    error: error.into(),
+                }
+            })?;
+        }
+
+        // SAFETY: The released resources are used by the passes of the batch.
+        let release = if !release.buffer_memory_barriers.is_empty()
+            || !release.image_memory_barriers.is_empty()
+        {
+            unsafe { builder.inner.pipeline_barrier_at_end(release) }
+        } else {
+            release
+        };
+
+        Ok((builder.build()?, release))
+    }
+
+    /// Returns the barriers that release the ownership of resources at the end of the batch at
+    /// `batch_index`.
+    fn ownership_releases(&self, batch_index: usize) -> DependencyInfo {
+        let mut release = DependencyInfo::default();
+
+        for transfer in self
+            .schedule
+            .transfers
+            .iter()
+            .filter(|transfer| transfer.release_batch == batch_index)
+        {
+            let queue_family_ownership_transfer =
+                Some(self.queue_family_ownership_transfer(transfer));
+
+            match &self.resources.resources[transfer.resource] {
+                Some(GraphResource::Buffer(buffer)) => {
+                    release.buffer_memory_barriers.push(BufferMemoryBarrier {
+                        src_stages: transfer.src_stages,
+                        src_access: transfer.src_access,
+                        queue_family_ownership_transfer,
+                        range: buffer.offset()..buffer.offset() + buffer.size(),
+                        ..BufferMemoryBarrier::buffer(buffer.buffer().clone())
+                    });
+                }
+                Some(GraphResource::Image(image_view)) => {
+                    let image = image_view.image();
+                    // The layouts are replaced with the ones that the command buffer leaves the
+                    // image in when the barrier is recorded.
+                    let layout = image.final_layout_requirement();
+
+                    release.image_memory_barriers.push(ImageMemoryBarrier {
+                        src_stages: transfer.src_stages,
+                        src_access: transfer.src_access,
+                        old_layout: layout,
+                        new_layout: layout,
+                        queue_family_ownership_transfer,
+                        subresource_range: image_view.subresource_range().clone(),
+                        ..ImageMemoryBarrier::image(image.inner().image.clone())
+                    });
+                }
+                Some(GraphResource::TransientImage(_)) | None => unreachable!(),
+            }
+        }
+
+        release
+    }
+
+    /// Returns the barriers that acquire the ownership of resources at the start of the batch at
+    /// `batch_index`. They are made from the barriers that the earlier batches released the
+    /// resources with, as given by `releases`.
+    fn ownership_acquires(
+        &self,
+        batch_index: usize,
+        releases: &[DependencyInfo],
+    ) -> DependencyInfo {
+        let mut acquire = DependencyInfo::default();
+
+        for transfer in self
+            .schedule
+            .transfers
+            .iter()
+            .filter(|transfer| transfer.acquire_batch == batch_index)
+        {
+            let release = &releases[transfer.release_batch];
+
+            match &self.resources.resources[transfer.resource] {
+                Some(GraphResource::Buffer(buffer)) => {
+                    let range = buffer.offset()..buffer.offset() + buffer.size();
+
+                    acquire.buffer_memory_barriers.extend(
+                        (release.buffer_memory_barriers.iter())
+                            .filter(|barrier| {
+                                barrier.buffer == *buffer.buffer() && barrier.range == range
+                            })
+                            .map(|barrier| BufferMemoryBarrier {
+                                src_stages: PipelineStages::empty(),
+                                src_access: AccessFlags::empty(),
+                                dst_stages: transfer.dst_stages,
+                                dst_access: transfer.dst_access,
+                                ..barrier.clone()
+                            }),
+                    );
+                }
+                Some(GraphResource::Image(image_view)) => {
+                    let image = image_view.image().inner().image.clone();
+                    let subresource_range = image_view.subresource_range();
+
+                    // The release may have been split where the layout of the image differs
+                    // between subresources.
+                    acquire.image_memory_barriers.extend(
+                        (release.image_memory_barriers.iter())
+                            .filter(|barrier| {
+                                barrier.image == image
+                                    && subresource_ranges_overlap(
+                                        &barrier.subresource_range,
+                                        subresource_range,
+                                    )
+                            })
+                            .map(|barrier| ImageMemoryBarrier {
+                                src_stages: PipelineStages::empty(),
+                                src_access: AccessFlags::empty(),
+                                dst_stages: transfer.dst_stages,
+                                dst_access: transfer.dst_access,
+                                ..barrier.clone()
+                            }),
+                    );
+                }
+                Some(GraphResource::TransientImage(_)) | None => unreachable!(),
+            }
+        }
+
+        acquire
+    }
+
+    fn queue_family_ownership_transfer(
+        &self,
+        transfer: &OwnershipTransfer,
+    ) -> QueueFamilyOwnershipTransfer {
+        QueueFamilyOwnershipTransfer::ExclusiveBetweenLocal {
+            src_index: self.queues[self.schedule.batches[transfer.release_batch].queue]
+                .queue_family_index(),
+            dst_index: self.queues[self.schedule.batches[transfer.acquire_batch].queue]
+                .queue_family_index(),
+        }
+    }
+}
+
+fn subresource_ranges_overlap(a: &ImageSubresourceRange, b: &ImageSubresourceRange) -> bool {
+    a.aspects.intersects(b.aspects)
+        && a.mip_levels.start < b.mip_levels.end
+        && b.mip_levels.start < a.mip_levels.end
+        && a.array_layers.start < b.array_layers.end
+        && b.array_layers.start < a.array_layers.end
+}
+
+impl<A> Debug for RenderGraph<A>
+where
+    A: CommandBufferAllocator,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        f.debug_struct("RenderGraph")
+            .field("queues", &self.queues)
+            .field("passes", &self.passes)
+            .field("resources", &self.resources)
+            .field("schedule", &self.schedule)
+            .field("transient_pools", &self.transient_pools)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+struct PassInfo {
+    name: String,
+    queue: PassQueue,
+    accesses: SmallVec<[PassAccess; 4]>,
+    side_effects: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PassAccess {
+    resource: usize,
+    write: bool,
+    stages: PipelineStages,
+    access: AccessFlags,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ResourceInfo {
+    exclusive: bool,
+    output: bool,
+}
+
+#[derive(Debug)]
+struct Schedule {
+    // The batch of each pass, or `None` if the pass was culled.
+    pass_batches: Vec<Option<usize>>,
+    // The batches in submission order.
+    batches: Vec<Batch>,
+    transfers: Vec<OwnershipTransfer>,
+}
+
+// The passes that are recorded into the same command buffer.
+#[derive(Debug)]
+struct Batch {
+    // 0 for the graphics queue, 1 for the compute queue.
+    queue: usize,
+    passes: Vec<usize>,
+    // The batches of the other queues that must signal a semaphore before this one starts.
+    waits: SmallVec<[usize; 1]>,
+    // Whether another batch waits on this one.
+    signals: bool,
+}
+
+#[derive(Debug)]
+struct OwnershipTransfer {
+    resource: usize,
+    release_batch: usize,
+    acquire_batch: usize,
+    src_stages: PipelineStages,
+    src_access: AccessFlags,
+    dst_stages: PipelineStages,
+    dst_access: AccessFlags,
+}
+
+/// Culls the passes that don't contribute to any output, groups the remaining passes into
+/// batches, and works out the semaphores and the ownership transfers between the batches.
+///
+/// `queue_family_indices` has the queue family of the graphics queue, followed by the one of the
+/// compute queue if there is a separate compute queue.
+fn schedule(
+    passes: &[PassInfo],
+    resources: &[ResourceInfo],
+    queue_family_indices: &[u32],
+) -> Schedule {
+    let queue_count = queue_family_indices.len();
+    let queue_of = |pass: &PassInfo| match pass.queue {
+        PassQueue::AsyncCompute if queue_count > 1 => 1,
+        _ => 0,
+    };
+
+    // Walk the passes backwards, keeping the ones that write a resource whose contents are
+    // needed by an output or by a pass that was kept.
+    let mut needed: Vec<bool> = resources.iter().map(|resource| resource.output).collect();
+    let mut alive = vec![false; passes.len()];
+
+    for (pass_index, pass) in passes.iter().enumerate().rev() {
+        if pass.side_effects
+            || pass
+                .accesses
+                .iter()
+                .any(|access| access.write && needed[access.resource])
+        {
+            alive[pass_index] = true;
+
+            for access in pass.accesses.iter().filter(|access| !access.write) {
+                needed[access.resource] = true;
+            }
+        }
+    }
+
+    // Find the passes that each pass depends on, and the level of each pass: the number of
+    // times that the queue changes along the longest chain of dependencies that leads to it.
+    #[derive(Clone, Default)]
+    struct ResourceState {
+        last_writer: Option<usize>,
+        readers_since_write: SmallVec<[usize; 4]>,
+    }
+
+    let mut states = vec![ResourceState::default(); resources.len()];
+    let mut dependencies: Vec<SmallVec<[usize; 4]>> = vec![SmallVec::new(); passes.len()];
+    let mut levels = vec![0u32; passes.len()];
+
+    for (pass_index, pass) in passes.iter().enumerate() {
+        if !alive[pass_index] {
+            continue;
+        }
+
+        let queue = queue_of(pass);
+        let dependencies = &mut dependencies[pass_index];
+
+        for access in &pass.accesses {
+            let state = &states[access.resource];
+            dependencies.extend(state.last_writer);
+
+            if access.write {
+                dependencies.extend(state.readers_since_write.iter().copied());
+            } else if resources[access.resource].exclusive {
+                // Reading on another queue family requires an ownership transfer, which must
+                // happen after the reads on the current queue family.
+                dependencies.extend(state.readers_since_write.iter().copied().filter(|&reader| {
+                    queue_family_indices[queue_of(&passes[reader])] != queue_family_indices[queue]
+                }));
+            }
+        }
+
+        dependencies.retain(|dependency| *dependency != pass_index);
+        dependencies.sort_unstable();
+        dependencies.dedup();
+
+        levels[pass_index] = dependencies
+            .iter()
+            .map(|&dependency| levels[dependency] + (queue_of(&passes[dependency]) != queue) as u32)
+            .max()
+            .unwrap_or(0);
+
+        for access in &pass.accesses {
+            let state = &mut states[access.resource];
+
+            if access.write {
+                state.last_writer = Some(pass_index);
+                state.readers_since_write.clear();
+            } else if !state.readers_since_write.contains(&pass_index) {
+                state.readers_since_write.push(pass_index);
+            }
+        }
+    }
+
+    // Passes with the same level that run on the same queue go in the same batch. Batches are
+    // submitted level by level, so a batch only ever waits on batches that were submitted
+    // before it.
+    let mut keys: Vec<(u32, usize)> = (0..passes.len())
+        .filter(|&pass_index| alive[pass_index])
+        .map(|pass_index| (levels[pass_index], queue_of(&passes[pass_index])))
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+
+    let new_batch = |queue| Batch {
+        queue,
+        passes: Vec::new(),
+        waits: SmallVec::new(),
+        signals: false,
+    };
+    let mut batches: Vec<Batch> = keys.iter().map(|&(_, queue)| new_batch(queue)).collect();
+    let mut pass_batches = vec![None; passes.len()];
+
+    for pass_index in (0..passes.len()).filter(|&pass_index| alive[pass_index]) {
+        let batch_index = keys
+            .binary_search(&(levels[pass_index], queue_of(&passes[pass_index])))
+            .unwrap();
+        pass_batches[pass_index] = Some(batch_index);
+        batches[batch_index].passes.push(pass_index);
+    }
+
+    // Follow the owner of each exclusive resource through the batches in submission order.
+    let mut transfers: Vec<OwnershipTransfer> = Vec::new();
+
+    if queue_count > 1 && queue_family_indices[0] != queue_family_indices[1] {
+        struct Owner {
+            // The queue that owns the resource before and after the graph.
+            home_queue: usize,
+            queue: usize,
+            // The last batch on `queue` that accessed the resource.
+            batch: usize,
+            stages: PipelineStages,
+            access: AccessFlags,
+            // The transfer that made `batch` acquire the resource, if any.
+            acquire: Option<usize>,
+        }
+
+        let mut owners: Vec<Option<Owner>> = (0..resources.len()).map(|_| None).collect();
+
+        for (batch_index, batch) in batches.iter().enumerate() {
+            for &pass_index in &batch.passes {
+                for access in passes[pass_index]
+                    .accesses
+                    .iter()
+                    .filter(|access| resources[access.resource].exclusive)
+                {
+                    // Only writes need to be made available before the transfer.
+                    let src_access = if access.write {
+                        access.access
+                    } else {
+                        AccessFlags::empty()
+                    };
+                    let owner = owners[access.resource].get_or_insert_with(|| Owner {
+                        home_queue: batch.queue,
+                        queue: batch.queue,
+                        batch: batch_index,
+                        stages: PipelineStages::empty(),
+                        access: AccessFlags::empty(),
+                        acquire: None,
+                    });
+
+                    if owner.queue == batch.queue {
+                        if owner.batch != batch_index {
+                            owner.batch = batch_index;
+                            owner.acquire = None;
+                        } else if let Some(acquire) = owner.acquire {
+                            transfers[acquire].dst_stages |= access.stages;
+                            transfers[acquire].dst_access |= access.access;
+                        }
+
+                        owner.stages |= access.stages;
+                        owner.access |= src_access;
+                    } else {
+                        transfers.push(OwnershipTransfer {
+                            resource: access.resource,
+                            release_batch: owner.batch,
+                            acquire_batch: batch_index,
+                            src_stages: owner.stages,
+                            src_access: owner.access,
+                            dst_stages: access.stages,
+                            dst_access: access.access,
+                        });
+
+                        owner.queue = batch.queue;
+                        owner.batch = batch_index;
+                        owner.stages = access.stages;
+                        owner.access = src_access;
+                        owner.acquire = Some(transfers.len() - 1);
+                    }
+                }
+            }
+        }
+
+        // Give the resources back to the queue that owned them at the start, in the last batch
+        // of that queue if it comes late enough, or in an additional batch otherwise.
+        let mut epilogues: SmallVec<[Option<usize>; 2]> = (0..queue_count).map(|_| None).collect();
+
+        for (resource, owner) in owners.iter().enumerate() {
+            let owner = match owner {
+                Some(owner) if owner.queue != owner.home_queue => owner,
+                _ => continue,
+            };
+
+            let acquire_batch = match batches
+                .iter()
+                .rposition(|batch| batch.queue == owner.home_queue)
+                .filter(|&batch_index| batch_index > owner.batch)
+            {
+                Some(batch_index) => batch_index,
+                None => *epilogues[owner.home_queue].get_or_insert_with(|| {
+                    batches.push(new_batch(owner.home_queue));
+                    batches.len() - 1
+                }),
+            };
+
+            transfers.push(OwnershipTransfer {
+                resource,
+                release_batch: owner.batch,
+                acquire_batch,
+                src_stages: owner.stages,
+                src_access: owner.access,
+                dst_stages: PipelineStages::ALL_COMMANDS,
+                dst_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+            });
+        }
+    }
+
+    // Make each batch wait on the latest batch of each other queue that it depends on, unless an
+    // earlier batch of its queue already waited on that batch or a later one.
+    let mut waited: Vec<Option<usize>> = vec![None; queue_count * queue_count];
+
+    for batch_index in 0..batches.len() {
+        let queue = batches[batch_index].queue;
+        let mut producers: SmallVec<[Option<usize>; 2]> = (0..queue_count).map(|_| None).collect();
+        let mut add_producer = |producer: usize| {
+            let producer_queue = batches[producer].queue;
+
+            if producer_queue != queue {
+                producers[producer_queue] = producers[producer_queue].max(Some(producer));
+            }
+        };
+
+        for &pass_index in &batches[batch_index].passes {
+            for &dependency in &dependencies[pass_index] {
+                add_producer(pass_batches[dependency].unwrap());
+            }
+        }
+
+        for transfer in transfers
+            .iter()
+            .filter(|transfer| transfer.acquire_batch == batch_index)
+        {
+            add_producer(transfer.release_batch);
+        }
+
+        for (producer_queue, producer) in producers.into_iter().enumerate() {
+            let producer = match producer {
+                Some(producer) => producer,
+                None => continue,
+            };
+            let waited = &mut waited[queue * queue_count + producer_queue];
+
+            if *waited < Some(producer) {
+                *waited = Some(producer);
+                batches[batch_index].waits.push(producer);
+
+                // With two queues, the batches of one queue wait on each batch of the other queue
+                // at most once.
+                debug_assert!(!batches[producer].signals);
+                batches[producer].signals = true;
+            }
+        }
+    }
+
+    Schedule {
+        pass_batches,
+        batches,
+        transfers,
+    }
+}
+
+/// Error that can happen when building or executing a [`RenderGraph`].
+#[derive(Clone, Debug)]
+pub enum RenderGraphError {
+    /// The queue family of the compute queue doesn't support compute operations.
+    ComputeQueueFamilyNotSupported { queue_family_index: u32 },
+
+    /// A transient image is used by passes that are in different submissions.
+    TransientImageUsedAcrossSubmissions { image: ImageId },
+
+    /// The first pass that uses a transient image doesn't write it.
+    TransientImageReadBeforeWrite { image: ImageId },
+
+    /// Creating the transient images failed.
+    TransientPoolError(TransientPoolError),
+
+    /// Creating a view of a transient image failed.
+    ImageViewCreationError(ImageViewCreationError),
+
+    /// Beginning a command buffer failed.
+    CommandBufferBeginError(CommandBufferBeginError),
+
+    /// Recording the commands of a pass failed.
+    PassError {
+        pass: String,
+        error: Arc<dyn Error + Send + Sync>,
+    },
+
+    /// Building a command buffer failed.
+    BuildError(BuildError),
+
+    /// Submitting a command buffer failed.
+    CommandBufferExecError(CommandBufferExecError),
+
+    /// Flushing a submission failed.
+    FlushError(FlushError),
+}
+
+impl Error for RenderGraphError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::TransientPoolError(err) => Some(err),
+            Self::ImageViewCreationError(err) => Some(err),
+            Self::CommandBufferBeginError(err) => Some(err),
+            Self::PassError { error, .. } => Some(error.as_ref()),
+            Self::BuildError(err) => Some(err),
+            Self::CommandBufferExecError(err) => Some(err),
+            Self::FlushError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for RenderGraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::ComputeQueueFamilyNotSupported { queue_family_index } => write!(
+                f,
+                "the queue family of the compute queue ({}) doesn't support compute operations",
+                queue_family_index,
+            ),
+            Self::TransientImageUsedAcrossSubmissions { .. } => write!(
+                f,
+                "a transient image is used by passes that are in different submissions",
+            ),
+            Self::TransientImageReadBeforeWrite { .. } => write!(
+                f,
+                "the first pass that uses a transient image doesn't write it",
+            ),
+            Self::TransientPoolError(_) => write!(f, "creating the transient images failed"),
+            Self::ImageViewCreationError(_) => {
+                write!(f, "creating a view of a transient image failed")
+            }
+            Self::CommandBufferBeginError(_) => write!(f, "beginning a command buffer failed"),
+            Self::PassError { pass, .. } => {
+                write!(f, "recording the commands of the pass `{}` failed", pass)
+            }
+            Self::BuildError(_) => write!(f, "building a command buffer failed"),
+            Self::CommandBufferExecError(_) => write!(f, "submitting a command buffer failed"),
+            Self::FlushError(_) => write!(f, "flushing a submission failed"),
+        }
+    }
+}
+
+impl From<TransientPoolError> for RenderGraphError {
+    fn from(err: TransientPoolError) -> Self {
+        Self::TransientPoolError(err)
+    }
+}
+
+impl From<ImageViewCreationError> for RenderGraphError {
+    fn from(err: ImageViewCreationError) -> Self {
+        Self::ImageViewCreationError(err)
+    }
+}
+
+impl From<CommandBufferBeginError> for RenderGraphError {
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBeginError(err)
+    }
+}
+
+impl From<BuildError> for RenderGraphError {
+    fn from(err: BuildError) -> Self {
+        Self::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for RenderGraphError {
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::CommandBufferExecError(err)
+    }
+}
+
+impl From<FlushError> for RenderGraphError {
+    fn from(err: FlushError) -> Self {
+        Self::FlushError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        schedule, PassAccess, PassInfo, PassQueue, RenderGraphBuilder, ResourceInfo, Schedule,
+    };
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        command_buffer::{allocator::StandardCommandBufferAllocator, CopyBufferInfo},
+        device::{Device, DeviceCreateInfo, QueueCreateInfo, QueueFlags},
+        memory::allocator::{AllocationCreateInfo, MemoryUsage, StandardMemoryAllocator},
+        sync::{self, AccessFlags, GpuFuture, PipelineStages},
+    };
+
+    fn pass(queue: PassQueue, reads: &[usize], writes: &[usize]) -> PassInfo {
+        let access = |resource, write| PassAccess {
+            resource,
+            write,
+            stages: PipelineStages::ALL_COMMANDS,
+            access: if write {
+                AccessFlags::MEMORY_WRITE
+            } else {
+                AccessFlags::MEMORY_READ
+            },
+        };
+
+        PassInfo {
+            name: String::new(),
+            queue,
+            accesses: reads
+                .iter()
+                .map(|&resource| access(resource, false))
+                .chain(writes.iter().map(|&resource| access(resource, true)))
+                .collect(),
+            side_effects: false,
+        }
+    }
+
+    fn resources(outputs: &[bool]) -> Vec<ResourceInfo> {
+        outputs
+            .iter()
+            .map(|&output| ResourceInfo {
+                exclusive: true,
+                output,
+            })
+            .collect()
+    }
+
+    fn batches(schedule: &Schedule) -> Vec<(usize, Vec<usize>, Vec<usize>)> {
+        schedule
+            .batches
+            .iter()
+            .map(|batch| (batch.queue, batch.passes.clone(), batch.waits.to_vec()))
+            .collect()
+    }
+
+    fn transfers(schedule: &Schedule) -> Vec<(usize, usize, usize)> {
+        schedule
+            .transfers
+            .iter()
+            .map(|transfer| {
+                (
+                    transfer.resource,
+                    transfer.release_batch,
+                    transfer.acquire_batch,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cull_unused_passes() {
+        let passes = [
+            pass(PassQueue::Graphics, &[], &[1]),
+            pass(PassQueue::Graphics, &[], &[2]),
+            pass(PassQueue::Graphics, &[1], &[0]),
+            PassInfo {
+                side_effects: true,
+                ..pass(PassQueue::Graphics, &[], &[2])
+            },
+        ];
+        let schedule = schedule(&passes, &resources(&[true, false, false]), &[0]);
+
+        assert_eq!(schedule.pass_batches, [Some(0), None, Some(0), Some(0)]);
+        assert_eq!(batches(&schedule), [(0, vec![0, 2, 3], vec![])]);
+        assert!(schedule.transfers.is_empty());
+    }
+
+    #[test]
+    fn async_compute() {
+        // 0 is written on the graphics queue and read on the compute queue, which writes 1 for
+        // the graphics queue. 2 and 3 are outputs.
+        let passes = [
+            pass(PassQueue::Graphics, &[], &[0]),
+            pass(PassQueue::AsyncCompute, &[0], &[1]),
+            pass(PassQueue::Graphics, &[], &[2]),
+            pass(PassQueue::Graphics, &[1], &[3]),
+        ];
+        let resources = resources(&[false, false, true, true]);
+
+        let schedule = schedule(&passes, &resources, &[0, 1]);
+        assert_eq!(
+            batches(&schedule),
+            [
+                (0, vec![0, 2], vec![]),
+                (1, vec![1], vec![0]),
+                (0, vec![3], vec![1]),
+                // Gives 1 back to the compute queue.
+                (1, vec![], vec![2]),
+            ],
+        );
+        assert_eq!(
+            transfers(&schedule),
+            [(0, 0, 1), (1, 1, 2), (0, 1, 2), (1, 2, 3)],
+        );
+        assert_eq!(schedule.transfers[0].src_access, AccessFlags::MEMORY_WRITE,);
+        assert_eq!(schedule.transfers[0].dst_access, AccessFlags::MEMORY_READ,);
+
+        // With a single queue family, the queues still wait on each other, but no ownership is
+        // transferred.
+        let schedule = super::schedule(&passes, &resources, &[0, 0]);
+        assert_eq!(
+            batches(&schedule),
+            [
+                (0, vec![0, 2], vec![]),
+                (1, vec![1], vec![0]),
+                (0, vec![3], vec![1]),
+            ],
+        );
+        assert!(schedule.transfers.is_empty());
+
+        // Without a compute queue, everything goes in a single batch.
+        let schedule = super::schedule(&passes, &resources, &[0]);
+        assert_eq!(batches(&schedule), [(0, vec![0, 1, 2, 3], vec![])]);
+    }
+
+    #[test]
+    fn independent_queues() {
+        let passes = [
+            pass(PassQueue::AsyncCompute, &[], &[0]),
+            pass(PassQueue::Graphics, &[], &[1]),
+        ];
+        let schedule = schedule(&passes, &resources(&[true, true]), &[0, 1]);
+
+        assert_eq!(
+            batches(&schedule),
+            [(0, vec![1], vec![]), (1, vec![0], vec![])],
+        );
+        assert!(schedule.batches.iter().all(|batch| !batch.signals));
+    }
+
+    #[test]
+    fn signaling_batch_stays_in_its_queue() {
+        // The first graphics batch signals the compute queue, and the second graphics batch
+        // only waits on the compute queue for 0, but still uses 2 after the first batch.
+        let passes = [
+            pass(PassQueue::AsyncCompute, &[], &[0]),
+            pass(PassQueue::Graphics, &[], &[1, 2]),
+            pass(PassQueue::AsyncCompute, &[1], &[3]),
+            pass(PassQueue::Graphics, &[0, 2], &[4]),
+        ];
+        let schedule = schedule(
+            &passes,
+            &resources(&[false, false, false, true, true]),
+            &[0, 0],
+        );
+
+        assert_eq!(
+            batches(&schedule),
+            [
+                (0, vec![1], vec![]),
+                (1, vec![0], vec![]),
+                (0, vec![3], vec![1]),
+                (1, vec![2], vec![0]),
+            ],
+        );
+        assert!(schedule.batches[0].signals && schedule.batches[1].signals);
+    }
+
+    #[test]
+    fn execute_signaling_batch() {
+        let instance = instance!();
+
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+        let queue_family_index =
+            match physical_device
+                .queue_family_properties()
+                .iter()
+                .position(|properties| {
+                    properties
+                        .queue_flags
+                        .contains(QueueFlags::GRAPHICS | QueueFlags::COMPUTE)
+                        && properties.queue_count >= 2
+                }) {
+                Some(index) => index as u32,
+                None => return,
+            };
+
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    queues: vec![0.5; 2],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let graphics_queue = queues.next().unwrap();
+        let compute_queue = queues.next().unwrap();
+
+        let memory_allocator = StandardMemoryAllocator::new_default(device.clone());
+        let [buffer0, buffer1, buffer2, buffer3, buffer4] = [0; 5].map(|_| {
+            Buffer::from_iter(
+                &memory_allocator,
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    usage: MemoryUsage::Upload,
+                    ..Default::default()
+                },
+                [0_u32; 4],
+            )
+            .unwrap()
+        });
+
+        // The same passes as in `signaling_batch_stays_in_its_queue`.
+        let mut builder: RenderGraphBuilder = RenderGraphBuilder::new();
+        let [id0, id1, id2, id3] =
+            [buffer0, buffer1, buffer2, buffer3].map(|buffer| builder.import_buffer(buffer));
+        let id4 = builder.import_buffer(buffer4.clone());
+        builder.mark_buffer_output(id3);
+        builder.mark_buffer_output(id4);
+
+        let (stages, read, write) = (
+            PipelineStages::ALL_TRANSFER,
+            AccessFlags::TRANSFER_READ,
+            AccessFlags::TRANSFER_WRITE,
+        );
+        builder
+            .add_pass("fill 0", PassQueue::AsyncCompute)
+            .writes_buffer(id0, stages, write)
+            .record(move |builder, resources| {
+                let buffer = resources.buffer(id0).clone().try_cast_slice().unwrap();
+                builder.fill_buffer(buffer, 1)?;
+                Ok(())
+            });
+        builder
+            .add_pass("fill 1 and 2", PassQueue::Graphics)
+            .writes_buffer(id1, stages, write)
+            .writes_buffer(id2, stages, write)
+            .record(move |builder, resources| {
+                for id in [id1, id2] {
+                    let buffer = resources.buffer(id).clone().try_cast_slice().unwrap();
+                    builder.fill_buffer(buffer, 2)?;
+                }
+                Ok(())
+            });
+        builder
+            .add_pass("copy 1 to 3", PassQueue::AsyncCompute)
+            .reads_buffer(id1, stages, read)
+            .writes_buffer(id3, stages, write)
+            .record(move |builder, resources| {
+                builder.copy_buffer(CopyBufferInfo::buffers(
+                    resources.buffer(id1).clone(),
+                    resources.buffer(id3).clone(),
+                ))?;
+                Ok(())
+            });
+        builder
+            .add_pass("copy 0 and 2 to 4", PassQueue::Graphics)
+            .reads_buffer(id0, stages, read)
+            .reads_buffer(id2, stages, read)
+            .writes_buffer(id4, stages, write)
+            .record(move |builder, resources| {
+                for id in [id0, id2] {
+                    builder.copy_buffer(CopyBufferInfo::buffers(
+                        resources.buffer(id).clone(),
+                        resources.buffer(id4).clone(),
+                    ))?;
+                }
+                Ok(())
+            });
+
+        let mut graph = builder.build(graphics_queue, Some(compute_queue)).unwrap();
+        assert_eq!(graph.submission_count(), 4);
+
+        let command_buffer_allocator =
+            StandardCommandBufferAllocator::new(device.clone(), Default::default());
+
+        // The second graphics batch must be able to use 2 after the first one, even though the
+        // first one signaled a semaphore, and the graph can be executed again afterwards.
+        for _ in 0..2 {
+            graph
+                .execute(&command_buffer_allocator, sync::now(device.clone()))
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap()
+                .wait(None)
+                .unwrap();
+        }
+
+        assert_eq!(*buffer4.read().unwrap(), [2; 4]);
+    }
+}
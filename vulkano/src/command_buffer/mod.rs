@@ -123,14 +123,23 @@ pub use self::{
             CopyImageToBufferInfo, ImageBlit, ImageCopy, ImageResolve, ResolveImageInfo,
         },
         debug::DebugUtilsError,
+        descriptor_buffer::DescriptorBufferError,
         pipeline::PipelineExecutionError,
         query::QueryError,
         render_pass::{
             ClearAttachment, ClearRect, RenderPassBeginInfo, RenderPassError,
             RenderingAttachmentInfo, RenderingAttachmentResolveInfo, RenderingInfo,
         },
-        secondary::ExecuteCommandsError,
+        secondary::{ExecuteCommandsError, ParallelRecordError},
+        sync::SynchronizationError,
+        transform_feedback::TransformFeedbackError,
     },
+    parameterized::{
+        ParameterizedCommandBuffer, ParameterizedCommandBufferCreateInfo,
+        ParameterizedCommandBufferError, SlotBindings,
+    },
+    readback::{ImageReadbackLayout, Readback, ReadbackAllocator, ReadbackError, ReadbackFuture},
+    staging::{StagingBelt, StagingBeltCreateInfo, StagingError, StagingFuture, StagingUpload},
     traits::{
         CommandBufferExecError, CommandBufferExecFuture, PrimaryCommandBufferAbstract,
         SecondaryCommandBufferAbstract,
@@ -144,7 +153,7 @@ use crate::{
     query::{QueryControlFlags, QueryPipelineStatisticFlags},
     range_map::RangeMap,
     render_pass::{Framebuffer, Subpass},
-    sync::{semaphore::Semaphore, PipelineMemoryAccess, PipelineStages},
+    sync::{semaphore::Semaphore, AccessFlags, PipelineMemoryAccess, PipelineStages},
     DeviceSize,
 };
 use ahash::HashMap;
@@ -154,7 +163,11 @@ use std::{ops::Range, sync::Arc};
 pub mod allocator;
 mod auto;
 mod commands;
+pub mod graph;
+pub mod parameterized;
 pub mod pool;
+pub mod readback;
+mod staging;
 pub(crate) mod standard;
 pub mod synced;
 pub mod sys;
@@ -179,6 +192,14 @@ pub struct DrawIndexedIndirectCommand {
     pub first_instance: u32,
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq)]
+pub struct DrawMeshTasksIndirectCommand {
+    pub group_count_x: u32,
+    pub group_count_y: u32,
+    pub group_count_z: u32,
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq)]
 pub struct DispatchIndirectCommand {
@@ -187,6 +208,14 @@ pub struct DispatchIndirectCommand {
     pub z: u32,
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq)]
+pub struct TraceRaysIndirectCommand {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+}
+
 vulkan_enum! {
     #[non_exhaustive]
 
@@ -465,6 +494,17 @@ pub struct SemaphoreSubmitInfo {
     /// The semaphore to signal or wait for.
     pub semaphore: Arc<Semaphore>,
 
+    /// If `semaphore` is a timeline semaphore, the counter value to wait for or to signal.
+    ///
+    /// For a semaphore wait operation, the operation waits until the counter value of the
+    /// semaphore is at least this value. For a semaphore signal operation, the counter value is
+    /// set to this value, which must be greater than the current counter value.
+    ///
+    /// This value is ignored for binary semaphores.
+    ///
+    /// The default value is 0.
+    pub value: u64,
+
     /// For a semaphore wait operation, specifies the pipeline stages in the second synchronization
     /// scope: stages of queue operations following the wait operation that can start executing
     /// after the semaphore is signalled.
@@ -490,6 +530,7 @@ impl SemaphoreSubmitInfo {
     pub fn semaphore(semaphore: Arc<Semaphore>) -> Self {
         Self {
             semaphore,
+            value: 0,
             stages: PipelineStages::ALL_COMMANDS,
             _ne: crate::NonExhaustive(()),
         }
@@ -594,21 +635,126 @@ impl From<ResourceUseRef> for SecondaryResourceUseRef {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum ResourceInCommand {
+    AccelerationStructure { index: u32 },
+    BufferMemoryBarrier { index: u32 },
     ColorAttachment { index: u32 },
     ColorResolveAttachment { index: u32 },
     DepthStencilAttachment,
     DepthStencilResolveAttachment,
+    DescriptorBuffer { index: u32 },
     DescriptorSet { set: u32, binding: u32, index: u32 },
+    DeviceAddressBuffer { index: u32 },
     Destination,
     FramebufferAttachment { index: u32 },
+    GeometryData { index: u32 },
     ImageMemoryBarrier { index: u32 },
     IndexBuffer,
     IndirectBuffer,
+    IndirectCountBuffer,
+    ScratchData,
     SecondaryCommandBuffer { index: u32 },
+    ShaderBindingTable,
     Source,
+    TransformFeedbackBuffer { binding: u32 },
+    TransformFeedbackCounterBuffer { index: u32 },
     VertexBuffer { binding: u32 },
 }
 
+/// A barrier that the builder of a command buffer inserted, as listed in its barrier report.
+///
+/// See [`AutoCommandBufferBuilder::enable_barrier_report`].
+#[derive(Clone, Debug)]
+pub struct BarrierReportEntry {
+    /// The resource that the barrier is for.
+    pub resource: BarrierResource,
+
+    /// Why the barrier was needed.
+    pub reason: BarrierReason,
+
+    /// The use of the resource that the barrier was inserted for, or `None` if the barrier wasn't
+    /// inserted for a command, such as when an image is transitioned to its final layout at the
+    /// end of the command buffer.
+    pub use_ref: Option<ResourceUseRef>,
+
+    /// The last use of the resource before the barrier, or `None` if the resource wasn't used
+    /// before in the command buffer.
+    pub previous_use_ref: Option<ResourceUseRef>,
+
+    /// The pipeline stages that the barrier waits for.
+    pub src_stages: PipelineStages,
+
+    /// The memory accesses that the barrier makes available.
+    pub src_access: AccessFlags,
+
+    /// The pipeline stages that wait for the barrier.
+    pub dst_stages: PipelineStages,
+
+    /// The memory accesses that the barrier makes visible.
+    pub dst_access: AccessFlags,
+
+    /// The index of the command before which the barrier is recorded, or the number of commands
+    /// if it is recorded at the end of the command buffer. For a split barrier, this is where the
+    /// event is waited on.
+    pub position: usize,
+
+    /// If the barrier was split into setting an event and waiting on it, the index of the command
+    /// before which the event is set.
+    pub split_position: Option<usize>,
+}
+
+/// The resource of a [`BarrierReportEntry`].
+#[derive(Clone, Debug)]
+pub enum BarrierResource {
+    /// A range of a buffer.
+    Buffer {
+        buffer: Arc<Buffer>,
+        range: Range<DeviceSize>,
+    },
+
+    /// Subresources of an image, which may be transitioned from one layout to another.
+    Image {
+        image: Arc<Image>,
+        subresource_range: ImageSubresourceRange,
+        old_layout: ImageLayout,
+        new_layout: ImageLayout,
+    },
+}
+
+/// Why a barrier was inserted in a command buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BarrierReason {
+    /// The resource is used for the first time in the command buffer, and the barrier
+    /// synchronizes it with the command buffers that were submitted before.
+    FirstUse,
+
+    /// The resource is read after it was written.
+    ReadAfterWrite,
+
+    /// The resource is written after it was read.
+    WriteAfterRead,
+
+    /// The resource is written after it was written.
+    WriteAfterWrite,
+
+    /// The image is only read, but in a different layout than before.
+    LayoutTransition,
+
+    /// The image is transitioned to the layout that it must have once the command buffer has
+    /// executed.
+    FinalLayout,
+
+    /// The ownership of the resource is transferred from one queue family to another.
+    QueueFamilyOwnershipTransfer,
+
+    /// The resource starts using memory that was previously used by another resource.
+    MemoryAliasing,
+
+    /// The resource was accessed outside of the source scope of the barrier of an event that is
+    /// set for it, and the barrier brings the access into that scope.
+    EventSourceScope,
+}
+
 #[doc(hidden)]
 #[derive(Debug, Default)]
 pub struct SecondaryCommandBufferResourcesUsage {
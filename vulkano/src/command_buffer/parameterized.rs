@@ -0,0 +1,624 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Command buffers that are recorded once for each set of resources that they are used with.
+//!
+//! A command buffer that is submitted every frame usually records the same commands each time,
+//! and only differs in a few of the resources that it uses, such as the framebuffer of the
+//! swapchain image that is drawn to, or the uniform buffer of the frame in flight. The handles of
+//! these resources are part of the recorded commands, so Vulkan doesn't allow changing them
+//! without recording the command buffer again.
+//!
+//! A [`ParameterizedCommandBuffer`] records its commands with a closure that takes these
+//! resources from indirection slots. The resources that are bound to the slots can be changed
+//! before each submission. The command buffer is only recorded the first time that a set of
+//! resources is bound, and the command buffer that was built for it is reused afterwards,
+//! together with the information about the resources it uses that was computed when building it.
+//! Switching between the swapchain images therefore records as many command buffers as there are
+//! swapchain images, after which submitting doesn't record any command or track any resource.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use vulkano::buffer::Subbuffer;
+//! # use vulkano::command_buffer::{
+//! #     allocator::StandardCommandBufferAllocator, ParameterizedCommandBuffer,
+//! #     ParameterizedCommandBufferCreateInfo, PrimaryCommandBufferAbstract, RenderPassBeginInfo,
+//! #     SubpassContents,
+//! # };
+//! # use vulkano::descriptor_set::PersistentDescriptorSet;
+//! # use vulkano::pipeline::{GraphicsPipeline, Pipeline, PipelineBindPoint};
+//! # use vulkano::render_pass::Framebuffer;
+//! # use vulkano::sync::GpuFuture;
+//! # let queue: Arc<vulkano::device::Queue> = return;
+//! # let command_buffer_allocator: StandardCommandBufferAllocator = return;
+//! # let pipeline: Arc<GraphicsPipeline> = return;
+//! # let vertex_buffer: Subbuffer<[[f32; 2]]> = return;
+//! # let framebuffers: Vec<Arc<Framebuffer>> = return;
+//! # let uniform_sets: Vec<Arc<PersistentDescriptorSet>> = return;
+//! # let (image_index, frame_index): (usize, usize) = return;
+//! #
+//! let mut command_buffer = ParameterizedCommandBuffer::new(
+//!     command_buffer_allocator,
+//!     queue.queue_family_index(),
+//!     ParameterizedCommandBufferCreateInfo {
+//!         descriptor_set_slots: 1,
+//!         framebuffer_slots: 1,
+//!         ..Default::default()
+//!     },
+//!     move |builder, bindings| {
+//!         builder
+//!             .begin_render_pass(
+//!                 RenderPassBeginInfo {
+//!                     clear_values: vec![Some([0.0, 0.0, 0.0, 1.0].into())],
+//!                     ..RenderPassBeginInfo::framebuffer(bindings.framebuffer(0))
+//!                 },
+//!                 SubpassContents::Inline,
+//!             )?
+//!             .bind_pipeline_graphics(pipeline.clone())
+//!             .bind_descriptor_sets(
+//!                 PipelineBindPoint::Graphics,
+//!                 pipeline.layout().clone(),
+//!                 0,
+//!                 bindings.descriptor_set(0),
+//!             )
+//!             .bind_vertex_buffers(0, vertex_buffer.clone())
+//!             .draw(vertex_buffer.len() as u32, 1, 0, 0)?
+//!             .end_render_pass()?;
+//!
+//!         Ok(())
+//!     },
+//! );
+//!
+//! // Every frame, point the slots at the resources of the frame.
+//! command_buffer
+//!     .bind_framebuffer(0, framebuffers[image_index].clone())
+//!     .bind_descriptor_set(0, uniform_sets[frame_index].clone());
+//!
+//! let future = command_buffer
+//!     .command_buffer()
+//!     .unwrap()
+//!     .execute(queue.clone())
+//!     .unwrap()
+//!     .then_signal_fence_and_flush()
+//!     .unwrap();
+//! ```
+
+use super::{
+    allocator::{CommandBufferAllocator, StandardCommandBufferAllocator},
+    AutoCommandBufferBuilder, BuildError, CommandBufferBeginError, CommandBufferUsage,
+    PrimaryAutoCommandBuffer,
+};
+use crate::{
+    buffer::Subbuffer, descriptor_set::DescriptorSetWithOffsets, render_pass::Framebuffer,
+    DeviceSize, VulkanObject,
+};
+use smallvec::SmallVec;
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    ops::Range,
+    sync::Arc,
+};
+
+/// A primary command buffer whose descriptor sets, vertex buffers and framebuffers can be changed
+/// between submissions.
+///
+/// See the [module-level documentation] for more information.
+///
+/// [module-level documentation]: self
+pub struct ParameterizedCommandBuffer<A = StandardCommandBufferAllocator>
+where
+    A: CommandBufferAllocator,
+{
+    allocator: A,
+    queue_family_index: u32,
+    usage: CommandBufferUsage,
+    max_cached_command_buffers: usize,
+    record: Box<RecordFn<A>>,
+    bindings: SlotBindings,
+    // The command buffers that were built for each set of bindings, from the least recently used
+    // to the most recently used.
+    cache: VecDeque<(BindingsKey, Arc<PrimaryAutoCommandBuffer<A::Alloc>>)>,
+}
+
+type RecordFn<A> = dyn FnMut(
+        &mut AutoCommandBufferBuilder<
+            PrimaryAutoCommandBuffer<<A as CommandBufferAllocator>::Alloc>,
+            A,
+        >,
+        &SlotBindings,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>
+    + Send;
+
+impl<A> ParameterizedCommandBuffer<A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Creates a new `ParameterizedCommandBuffer`, whose commands are recorded by `record`.
+    ///
+    /// `record` is called each time that the command buffer is used with a set of bindings that
+    /// it wasn't recorded with yet, and must take the resources of the slots from the
+    /// [`SlotBindings`] that it is given. It must record the same commands each time that it is
+    /// given the same bindings; if something else that it depends on changes, call
+    /// [`clear_cache`] so that the command buffers are recorded again.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.usage` is [`CommandBufferUsage::OneTimeSubmit`].
+    /// - Panics if `create_info.max_cached_command_buffers` is `0`.
+    ///
+    /// [`clear_cache`]: Self::clear_cache
+    pub fn new<F>(
+        allocator: A,
+        queue_family_index: u32,
+        create_info: ParameterizedCommandBufferCreateInfo,
+        record: F,
+    ) -> Self
+    where
+        F: FnMut(
+                &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer<A::Alloc>, A>,
+                &SlotBindings,
+            ) -> Result<(), Box<dyn Error + Send + Sync>>
+            + Send
+            + 'static,
+    {
+        let ParameterizedCommandBufferCreateInfo {
+            usage,
+            descriptor_set_slots,
+            vertex_buffer_slots,
+            framebuffer_slots,
+            max_cached_command_buffers,
+            _ne: _,
+        } = create_info;
+
+        assert!(usage != CommandBufferUsage::OneTimeSubmit);
+        assert!(max_cached_command_buffers != 0);
+
+        ParameterizedCommandBuffer {
+            allocator,
+            queue_family_index,
+            usage,
+            max_cached_command_buffers,
+            record: Box::new(record),
+            bindings: SlotBindings {
+                descriptor_sets: vec![None; descriptor_set_slots as usize],
+                vertex_buffers: vec![None; vertex_buffer_slots as usize],
+                framebuffers: vec![None; framebuffer_slots as usize],
+            },
+            cache: VecDeque::new(),
+        }
+    }
+
+    /// Binds `descriptor_set` to the descriptor set slot `slot`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `slot` is not less than
+    ///   [`ParameterizedCommandBufferCreateInfo::descriptor_set_slots`].
+    #[inline]
+    pub fn bind_descriptor_set(
+        &mut self,
+        slot: u32,
+        descriptor_set: impl Into<DescriptorSetWithOffsets>,
+    ) -> &mut Self {
+        self.bindings.descriptor_sets[slot as usize] = Some(descriptor_set.into());
+
+        self
+    }
+
+    /// Binds `buffer` to the vertex buffer slot `slot`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `slot` is not less than
+    ///   [`ParameterizedCommandBufferCreateInfo::vertex_buffer_slots`].
+    #[inline]
+    pub fn bind_vertex_buffer<T: ?Sized>(&mut self, slot: u32, buffer: Subbuffer<T>) -> &mut Self {
+        self.bindings.vertex_buffers[slot as usize] = Some(buffer.into_bytes());
+
+        self
+    }
+
+    /// Binds `framebuffer` to the framebuffer slot `slot`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `slot` is not less than
+    ///   [`ParameterizedCommandBufferCreateInfo::framebuffer_slots`].
+    #[inline]
+    pub fn bind_framebuffer(&mut self, slot: u32, framebuffer: Arc<Framebuffer>) -> &mut Self {
+        self.bindings.framebuffers[slot as usize] = Some(framebuffer);
+
+        self
+    }
+
+    /// Returns the resources that are currently bound to the slots.
+    #[inline]
+    pub fn bindings(&self) -> &SlotBindings {
+        &self.bindings
+    }
+
+    /// Returns the command buffer for the resources that are currently bound to the slots.
+    ///
+    /// If a command buffer was already built for these resources, it is returned as is.
+    /// Otherwise the command buffer is recorded and built, and kept for the next time that the
+    /// same resources are bound. When more than
+    /// [`ParameterizedCommandBufferCreateInfo::max_cached_command_buffers`] command buffers are
+    /// kept, the one that was used least recently is dropped.
+    ///
+    /// Every slot must have a resource bound to it.
+    pub fn command_buffer(
+        &mut self,
+    ) -> Result<Arc<PrimaryAutoCommandBuffer<A::Alloc>>, ParameterizedCommandBufferError> {
+        let key = self.bindings.key()?;
+
+        if let Some(index) = self.cache.iter().position(|(k, _)| *k == key) {
+            let entry = self.cache.remove(index).unwrap();
+            let command_buffer = entry.1.clone();
+            self.cache.push_back(entry);
+
+            return Ok(command_buffer);
+        }
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &self.allocator,
+            self.queue_family_index,
+            self.usage,
+        )?;
+        (self.record)(&mut builder, &self.bindings)
+            .map_err(|error| ParameterizedCommandBufferError::RecordError(error.into()))?;
+        let command_buffer = Arc::new(builder.build()?);
+
+        if self.cache.len() == self.max_cached_command_buffers {
+            self.cache.pop_front();
+        }
+
+        self.cache.push_back((key, command_buffer.clone()));
+
+        Ok(command_buffer)
+    }
+
+    /// Returns the number of command buffers that are currently kept.
+    #[inline]
+    pub fn cached_command_buffers(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Drops all the command buffers that are kept, so that they are recorded again the next time
+    /// that they are used.
+    ///
+    /// The command buffers keep the resources that they were recorded with alive, so this also
+    /// releases the resources that are no longer bound, such as the framebuffers of a swapchain
+    /// that was recreated.
+    #[inline]
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+}
+
+/// Parameters to create a new [`ParameterizedCommandBuffer`].
+#[derive(Clone, Debug)]
+pub struct ParameterizedCommandBufferCreateInfo {
+    /// How the command buffers are going to be used.
+    ///
+    /// Must not be [`CommandBufferUsage::OneTimeSubmit`]. The same command buffer is submitted
+    /// again each time that the same resources are bound, so it must be
+    /// [`CommandBufferUsage::SimultaneousUse`] if it can be submitted again before the previous
+    /// submission has completed.
+    ///
+    /// The default value is [`CommandBufferUsage::SimultaneousUse`].
+    pub usage: CommandBufferUsage,
+
+    /// The number of descriptor set slots.
+    ///
+    /// The default value is `0`.
+    pub descriptor_set_slots: u32,
+
+    /// The number of vertex buffer slots.
+    ///
+    /// The default value is `0`.
+    pub vertex_buffer_slots: u32,
+
+    /// The number of framebuffer slots.
+    ///
+    /// The default value is `0`.
+    pub framebuffer_slots: u32,
+
+    /// The maximum number of command buffers that are kept at once.
+    ///
+    /// This should be at least the number of different sets of resources that are bound in
+    /// turn, such as the number of swapchain images, or the command buffers are recorded again
+    /// every time.
+    ///
+    /// The default value is `8`.
+    pub max_cached_command_buffers: usize,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for ParameterizedCommandBufferCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            usage: CommandBufferUsage::SimultaneousUse,
+            descriptor_set_slots: 0,
+            vertex_buffer_slots: 0,
+            framebuffer_slots: 0,
+            max_cached_command_buffers: 8,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// The resources that are bound to the slots of a [`ParameterizedCommandBuffer`].
+#[derive(Clone)]
+pub struct SlotBindings {
+    descriptor_sets: Vec<Option<DescriptorSetWithOffsets>>,
+    vertex_buffers: Vec<Option<Subbuffer<[u8]>>>,
+    framebuffers: Vec<Option<Arc<Framebuffer>>>,
+}
+
+impl SlotBindings {
+    /// Returns the descriptor set that is bound to the descriptor set slot `slot`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `slot` is out of range, or if no descriptor set is bound to it.
+    #[inline]
+    pub fn descriptor_set(&self, slot: u32) -> DescriptorSetWithOffsets {
+        self.descriptor_sets[slot as usize].clone().unwrap()
+    }
+
+    /// Returns the buffer that is bound to the vertex buffer slot `slot`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `slot` is out of range, or if no buffer is bound to it.
+    #[inline]
+    pub fn vertex_buffer(&self, slot: u32) -> Subbuffer<[u8]> {
+        self.vertex_buffers[slot as usize].clone().unwrap()
+    }
+
+    /// Returns the framebuffer that is bound to the framebuffer slot `slot`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `slot` is out of range, or if no framebuffer is bound to it.
+    #[inline]
+    pub fn framebuffer(&self, slot: u32) -> Arc<Framebuffer> {
+        self.framebuffers[slot as usize].clone().unwrap()
+    }
+
+    // Identifies the bound resources. The cached command buffers keep their resources alive, so
+    // the handles can't be reused by other resources while they are in the cache.
+    fn key(&self) -> Result<BindingsKey, ParameterizedCommandBufferError> {
+        let descriptor_sets: SmallVec<_> = self
+            .descriptor_sets
+            .iter()
+            .enumerate()
+            .map(|(slot, descriptor_set)| {
+                let (descriptor_set, dynamic_offsets) = descriptor_set
+                    .as_ref()
+                    .ok_or(ParameterizedCommandBufferError::DescriptorSetSlotNotBound {
+                        slot: slot as u32,
+                    })?
+                    .as_ref();
+
+                Ok((
+                    descriptor_set.inner().handle(),
+                    SmallVec::from_slice(dynamic_offsets),
+                ))
+            })
+            .collect::<Result<_, ParameterizedCommandBufferError>>()?;
+        let vertex_buffers: SmallVec<_> = self
+            .vertex_buffers
+            .iter()
+            .enumerate()
+            .map(|(slot, buffer)| {
+                let buffer = buffer.as_ref().ok_or(
+                    ParameterizedCommandBufferError::VertexBufferSlotNotBound { slot: slot as u32 },
+                )?;
+
+                Ok((
+                    buffer.buffer().handle(),
+                    buffer.offset()..buffer.offset() + buffer.size(),
+                ))
+            })
+            .collect::<Result<_, ParameterizedCommandBufferError>>()?;
+        let framebuffers: SmallVec<_> = self
+            .framebuffers
+            .iter()
+            .enumerate()
+            .map(|(slot, framebuffer)| {
+                framebuffer
+                    .as_ref()
+                    .map(|framebuffer| framebuffer.handle())
+                    .ok_or(ParameterizedCommandBufferError::FramebufferSlotNotBound {
+                        slot: slot as u32,
+                    })
+            })
+            .collect::<Result<_, ParameterizedCommandBufferError>>()?;
+
+        Ok(BindingsKey {
+            descriptor_sets,
+            vertex_buffers,
+            framebuffers,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BindingsKey {
+    descriptor_sets: SmallVec<[(ash::vk::DescriptorSet, SmallVec<[u32; 4]>); 4]>,
+    vertex_buffers: SmallVec<[(ash::vk::Buffer, Range<DeviceSize>); 4]>,
+    framebuffers: SmallVec<[ash::vk::Framebuffer; 1]>,
+}
+
+/// Error that can happen when getting the command buffer of a [`ParameterizedCommandBuffer`].
+#[derive(Clone, Debug)]
+pub enum ParameterizedCommandBufferError {
+    /// No descriptor set is bound to a descriptor set slot.
+    DescriptorSetSlotNotBound { slot: u32 },
+
+    /// No buffer is bound to a vertex buffer slot.
+    VertexBufferSlotNotBound { slot: u32 },
+
+    /// No framebuffer is bound to a framebuffer slot.
+    FramebufferSlotNotBound { slot: u32 },
+
+    /// Beginning the command buffer failed.
+    CommandBufferBeginError(CommandBufferBeginError),
+
+    /// Recording the commands failed.
+    RecordError(Arc<dyn Error + Send + Sync>),
+
+    /// Building the command buffer failed.
+    BuildError(BuildError),
+}
+
+impl Error for ParameterizedCommandBufferError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::CommandBufferBeginError(err) => Some(err),
+            Self::RecordError(err) => Some(err.as_ref()),
+            Self::BuildError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ParameterizedCommandBufferError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::DescriptorSetSlotNotBound { slot } => write!(
+                f,
+                "no descriptor set is bound to the descriptor set slot {}",
+                slot,
+            ),
+            Self::VertexBufferSlotNotBound { slot } => {
+                write!(f, "no buffer is bound to the vertex buffer slot {}", slot)
+            }
+            Self::FramebufferSlotNotBound { slot } => {
+                write!(
+                    f,
+                    "no framebuffer is bound to the framebuffer slot {}",
+                    slot
+                )
+            }
+            Self::CommandBufferBeginError(_) => write!(f, "beginning the command buffer failed"),
+            Self::RecordError(_) => write!(f, "recording the commands failed"),
+            Self::BuildError(_) => write!(f, "building the command buffer failed"),
+        }
+    }
+}
+
+impl From<CommandBufferBeginError> for ParameterizedCommandBufferError {
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBeginError(err)
+    }
+}
+
+impl From<BuildError> for ParameterizedCommandBufferError {
+    fn from(err: BuildError) -> Self {
+        Self::BuildError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ParameterizedCommandBuffer, ParameterizedCommandBufferCreateInfo,
+        ParameterizedCommandBufferError,
+    };
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        command_buffer::allocator::StandardCommandBufferAllocator,
+        memory::allocator::{AllocationCreateInfo, MemoryUsage, StandardMemoryAllocator},
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn reuse_per_bindings() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = StandardMemoryAllocator::new_default(device.clone());
+        let buffers: Vec<_> = (0..3)
+            .map(|_| {
+                Buffer::new_slice::<u32>(
+                    &memory_allocator,
+                    BufferCreateInfo {
+                        usage: BufferUsage::TRANSFER_DST,
+                        ..Default::default()
+                    },
+                    AllocationCreateInfo {
+                        usage: MemoryUsage::DeviceOnly,
+                        ..Default::default()
+                    },
+                    16,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        let recordings = Arc::new(AtomicUsize::new(0));
+        let mut command_buffer = ParameterizedCommandBuffer::new(
+            StandardCommandBufferAllocator::new(device, Default::default()),
+            queue.queue_family_index(),
+            ParameterizedCommandBufferCreateInfo {
+                vertex_buffer_slots: 1,
+                max_cached_command_buffers: 2,
+                ..Default::default()
+            },
+            {
+                let recordings = recordings.clone();
+                move |builder, bindings| {
+                    recordings.fetch_add(1, Ordering::Relaxed);
+                    builder.fill_buffer(bindings.vertex_buffer(0).cast_aligned(), 0)?;
+
+                    Ok(())
+                }
+            },
+        );
+
+        assert!(matches!(
+            command_buffer.command_buffer(),
+            Err(ParameterizedCommandBufferError::VertexBufferSlotNotBound { slot: 0 })
+        ));
+
+        command_buffer.bind_vertex_buffer(0, buffers[0].clone());
+        let first = command_buffer.command_buffer().unwrap();
+        command_buffer.bind_vertex_buffer(0, buffers[1].clone());
+        let second = command_buffer.command_buffer().unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+
+        command_buffer.bind_vertex_buffer(0, buffers[0].clone());
+        assert!(Arc::ptr_eq(
+            &command_buffer.command_buffer().unwrap(),
+            &first
+        ));
+        assert_eq!(recordings.load(Ordering::Relaxed), 2);
+
+        // The second command buffer is the least recently used one, so it is dropped.
+        command_buffer.bind_vertex_buffer(0, buffers[2].clone());
+        command_buffer.command_buffer().unwrap();
+        assert_eq!(command_buffer.cached_command_buffers(), 2);
+        command_buffer.bind_vertex_buffer(0, buffers[1].clone());
+        assert!(!Arc::ptr_eq(
+            &command_buffer.command_buffer().unwrap(),
+            &second
+        ));
+        assert_eq!(recordings.load(Ordering::Relaxed), 4);
+    }
+}
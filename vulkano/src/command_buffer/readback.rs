@@ -0,0 +1,626 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Reading the contents of buffers and images back to the host.
+//!
+//! A [`ReadbackAllocator`] records a copy of a buffer or of an image subresource into a
+//! host-cached staging buffer, taken from a pool of reused arenas. Once the command buffer is
+//! submitted, the returned [`Readback`] can be combined with the fence of the submission into a
+//! [`ReadbackFuture`], which can either be waited on or polled as a [`std::future::Future`], and
+//! resolves to the data as a `Vec`. Since the data is then read without locking the buffer, it is
+//! up to the caller to pass the future of the right submission, which is why [`Readback::after`]
+//! is unsafe.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use vulkano::buffer::Subbuffer;
+//! # use vulkano::command_buffer::{
+//! #     allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+//! #     PrimaryCommandBufferAbstract, ReadbackAllocator,
+//! # };
+//! # use vulkano::image::ImageAccess;
+//! # use vulkano::sync::GpuFuture;
+//! # let queue: Arc<vulkano::device::Queue> = return;
+//! # let memory_allocator: Arc<vulkano::memory::allocator::StandardMemoryAllocator> = return;
+//! # let command_buffer_allocator: StandardCommandBufferAllocator = return;
+//! # let results: Subbuffer<[u32]> = return;
+//! # let image: Arc<dyn ImageAccess> = return;
+//! #
+//! let readback_allocator = ReadbackAllocator::new(memory_allocator.clone());
+//!
+//! let mut builder = AutoCommandBufferBuilder::primary(
+//!     &command_buffer_allocator,
+//!     queue.queue_family_index(),
+//!     CommandBufferUsage::OneTimeSubmit,
+//! )
+//! .unwrap();
+//!
+//! let results_readback = readback_allocator.read_buffer(&mut builder, results).unwrap();
+//! let image_readback = readback_allocator
+//!     .read_image(&mut builder, image.clone(), image.subresource_layers())
+//!     .unwrap();
+//!
+//! let future = builder
+//!     .build()
+//!     .unwrap()
+//!     .execute(queue.clone())
+//!     .unwrap()
+//!     .then_signal_fence_and_flush()
+//!     .unwrap();
+//!
+//! // The copies were recorded into the command buffer that `future` signals the fence after.
+//! let results: Vec<u32> = unsafe { results_readback.after(&future) }
+//!     .wait(None)
+//!     .unwrap();
+//!
+//! let layout = *image_readback.image_layout().unwrap();
+//! let texels: Vec<u8> = unsafe { image_readback.after(&future) }
+//!     .wait(None)
+//!     .unwrap();
+//! let first_row = &texels[..layout.row_pitch as usize];
+//! ```
+
+use super::{
+    allocator::CommandBufferAllocator, AutoCommandBufferBuilder, BufferImageCopy, CopyBufferInfo,
+    CopyError, CopyImageToBufferInfo,
+};
+use crate::{
+    buffer::{
+        allocator::{SubbufferAllocator, SubbufferAllocatorCreateInfo},
+        BufferContents, BufferError, BufferMemory, BufferUsage, Subbuffer,
+    },
+    format::Format,
+    image::{ImageAccess, ImageAspects, ImageSubresourceLayers},
+    memory::allocator::{
+        align_down, align_up, AllocationCreationError, DeviceLayout, MemoryAllocator, MemoryUsage,
+        StandardMemoryAllocator,
+    },
+    sync::{
+        fence::{Fence, FenceError},
+        future::FenceSignalFuture,
+        GpuFuture,
+    },
+    DeviceSize, OomError,
+};
+use std::{
+    cmp,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Records copies of buffers and images into pooled host-cached buffers, to read them back to the
+/// host.
+///
+/// See the [module-level documentation] for more information.
+///
+/// [module-level documentation]: self
+#[derive(Debug)]
+pub struct ReadbackAllocator<M = Arc<StandardMemoryAllocator>> {
+    buffer_allocator: SubbufferAllocator<M>,
+}
+
+impl<M> ReadbackAllocator<M>
+where
+    M: MemoryAllocator,
+{
+    /// Creates a new `ReadbackAllocator` that allocates its buffers from `memory_allocator`.
+    pub fn new(memory_allocator: M) -> Self {
+        ReadbackAllocator {
+            buffer_allocator: SubbufferAllocator::new(
+                memory_allocator,
+                SubbufferAllocatorCreateInfo {
+                    buffer_usage: BufferUsage::TRANSFER_DST,
+                    memory_usage: MemoryUsage::Download,
+                    ..Default::default()
+                },
+            ),
+        }
+    }
+
+    /// Records a copy of `src_buffer` into `builder`, to be read back once the command buffer has
+    /// completed.
+    ///
+    /// To read back a buffer with contents that are not a slice, use [`Subbuffer::into_bytes`].
+    pub fn read_buffer<T, L, A>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L, A>,
+        src_buffer: Subbuffer<[T]>,
+    ) -> Result<Readback<T>, ReadbackError>
+    where
+        T: BufferContents,
+        A: CommandBufferAllocator,
+    {
+        let buffer = self
+            .buffer_allocator
+            .allocate_slice::<T>(src_buffer.len())?;
+        builder.copy_buffer(CopyBufferInfo::buffers(src_buffer, buffer.clone()))?;
+
+        Ok(Readback {
+            buffer,
+            image_layout: None,
+        })
+    }
+
+    /// Records a copy of `subresource` of `src_image` into `builder`, to be read back once the
+    /// command buffer has completed.
+    ///
+    /// `subresource` must have exactly one aspect. The whole extent of the mip level is copied,
+    /// and the texel blocks are tightly packed in the result, as described by the
+    /// [`image_layout`] of the returned `Readback`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `subresource.aspects` doesn't contain exactly one aspect.
+    /// - Panics if `subresource.mip_level` is not less than the number of mip levels of
+    ///   `src_image`.
+    ///
+    /// [`image_layout`]: Readback::image_layout
+    pub fn read_image<L, A>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L, A>,
+        src_image: Arc<dyn ImageAccess>,
+        subresource: ImageSubresourceLayers,
+    ) -> Result<Readback<u8>, ReadbackError>
+    where
+        A: CommandBufferAllocator,
+    {
+        assert!(subresource.aspects.count() == 1);
+
+        let image_layout = ImageReadbackLayout::new(src_image.as_ref(), &subresource);
+
+        // The offset in the buffer must be a multiple of both the texel block size and 4, which
+        // isn't always a power of two, so we allocate a bit more and align the offset ourselves.
+        let alignment = lcm(image_layout.block_size, 4);
+        let buffer = self.buffer_allocator.allocate(
+            DeviceLayout::from_size_alignment(image_layout.size() + alignment - 1, 4).unwrap(),
+        )?;
+        let padding = (alignment - buffer.offset() % alignment) % alignment;
+        let buffer = buffer.slice(padding..padding + image_layout.size());
+
+        builder.copy_image_to_buffer(CopyImageToBufferInfo {
+            regions: [BufferImageCopy {
+                image_subresource: subresource,
+                image_extent: image_layout.extent,
+                ..Default::default()
+            }]
+            .into_iter()
+            .collect(),
+            ..CopyImageToBufferInfo::image_buffer(src_image, buffer.clone())
+        })?;
+
+        Ok(Readback {
+            buffer,
+            image_layout: Some(image_layout),
+        })
+    }
+}
+
+/// The layout of the data of an image subresource that was read back with
+/// [`ReadbackAllocator::read_image`].
+///
+/// The texel blocks are tightly packed: each row of blocks follows the previous one, each depth
+/// slice follows the previous one, and each array layer follows the previous one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ImageReadbackLayout {
+    /// The format of the data. For multi-planar formats, this is the format of the plane.
+    pub format: Format,
+
+    /// The extent of the subresource in texels.
+    pub extent: [u32; 3],
+
+    /// The number of array layers.
+    pub array_layers: u32,
+
+    /// The size in bytes of one texel block.
+    pub block_size: DeviceSize,
+
+    /// The number of bytes between the start of successive rows of texel blocks.
+    pub row_pitch: DeviceSize,
+
+    /// The number of bytes between the start of successive depth slices.
+    pub depth_pitch: DeviceSize,
+
+    /// The number of bytes between the start of successive array layers.
+    pub array_pitch: DeviceSize,
+}
+
+impl ImageReadbackLayout {
+    fn new(image: &dyn ImageAccess, subresource: &ImageSubresourceLayers) -> Self {
+        let aspects = subresource.aspects;
+        let image_format = image.format();
+
+        let (format, extent) = if aspects.intersects(ImageAspects::PLANE_0) {
+            (
+                image_format.planes()[0],
+                image.dimensions().width_height_depth(),
+            )
+        } else if aspects.intersects(ImageAspects::PLANE_1 | ImageAspects::PLANE_2) {
+            let plane = if aspects.intersects(ImageAspects::PLANE_1) {
+                1
+            } else {
+                2
+            };
+
+            (
+                image_format.planes()[plane],
+                image_format
+                    .ycbcr_chroma_sampling()
+                    .unwrap()
+                    .subsampled_extent(image.dimensions().width_height_depth()),
+            )
+        } else {
+            (
+                image_format,
+                image
+                    .dimensions()
+                    .mip_level_dimensions(subresource.mip_level)
+                    .unwrap()
+                    .width_height_depth(),
+            )
+        };
+
+        // https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkBufferImageCopy.html#_description
+        let block_size = if aspects.intersects(ImageAspects::STENCIL) {
+            1
+        } else if aspects.intersects(ImageAspects::DEPTH) {
+            match format {
+                Format::D16_UNORM | Format::D16_UNORM_S8_UINT => 2,
+                Format::D32_SFLOAT
+                | Format::D32_SFLOAT_S8_UINT
+                | Format::X8_D24_UNORM_PACK32
+                | Format::D24_UNORM_S8_UINT => 4,
+                _ => unreachable!(),
+            }
+        } else {
+            format.block_size().unwrap()
+        };
+
+        // Scale down from texels to texel blocks, rounding up if needed.
+        let block_extent = format.block_extent();
+        let blocks =
+            [0, 1, 2].map(|i| ((extent[i] + block_extent[i] - 1) / block_extent[i]) as DeviceSize);
+
+        let row_pitch = blocks[0] * block_size;
+        let depth_pitch = blocks[1] * row_pitch;
+        let array_pitch = blocks[2] * depth_pitch;
+
+        ImageReadbackLayout {
+            format,
+            extent,
+            array_layers: subresource.array_layers.end - subresource.array_layers.start,
+            block_size,
+            row_pitch,
+            depth_pitch,
+            array_pitch,
+        }
+    }
+
+    /// Returns the total size in bytes of the data.
+    #[inline]
+    pub fn size(&self) -> DeviceSize {
+        self.array_layers as DeviceSize * self.array_pitch
+    }
+}
+
+fn lcm(a: DeviceSize, b: DeviceSize) -> DeviceSize {
+    fn gcd(a: DeviceSize, b: DeviceSize) -> DeviceSize {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    a / gcd(a, b) * b
+}
+
+/// Data that is being read back from the device, as returned by [`ReadbackAllocator`].
+#[derive(Debug)]
+pub struct Readback<T> {
+    buffer: Subbuffer<[T]>,
+    image_layout: Option<ImageReadbackLayout>,
+}
+
+impl<T> Readback<T>
+where
+    T: BufferContents + Clone,
+{
+    /// Returns the host-visible buffer that the data is copied into.
+    #[inline]
+    pub fn buffer(&self) -> &Subbuffer<[T]> {
+        &self.buffer
+    }
+
+    /// Returns the layout of the data, if it was read back from an image.
+    #[inline]
+    pub fn image_layout(&self) -> Option<&ImageReadbackLayout> {
+        self.image_layout.as_ref()
+    }
+
+    /// Returns a copy of the data.
+    ///
+    /// This returns an error if the buffer is still in use by the device, which includes the
+    /// case where the future of the command buffer that copies the data has not been cleaned up
+    /// yet. Use [`after`] to wait for the data instead.
+    ///
+    /// [`after`]: Self::after
+    pub fn read(&self) -> Result<Vec<T>, ReadbackError> {
+        Ok(self.buffer.read()?.to_vec())
+    }
+
+    /// Returns a future that resolves to the data once the fence of `future` is signaled.
+    ///
+    /// The returned future reads the data without locking the buffer, because the lock is only
+    /// released once `future` is cleaned up.
+    ///
+    /// # Safety
+    ///
+    /// - `future` must signal its fence after the submission of the command buffer that the copy
+    ///   was recorded into, on the same queue.
+    /// - If the fence of `future` was already signaled and cleaned up, the command buffer must
+    ///   have completed execution.
+    ///
+    /// `future` must also have been flushed, otherwise the returned future never resolves.
+    pub unsafe fn after<F>(self, future: &FenceSignalFuture<F>) -> ReadbackFuture<T>
+    where
+        F: GpuFuture,
+    {
+        ReadbackFuture {
+            readback: self,
+            fence: future.fence(),
+        }
+    }
+
+    // Reads the data without going through the locking of the buffer, which is only released
+    // once the future of the copy is cleaned up.
+    //
+    // Safety: the device must be done writing to the buffer, which is what the contract of `after`
+    // guarantees once the fence is signaled.
+    unsafe fn read_unchecked(&self) -> Result<Vec<T>, ReadbackError> {
+        let allocation = match self.buffer.buffer().memory() {
+            BufferMemory::Normal(a) => a,
+            BufferMemory::Sparse => unreachable!(),
+        };
+
+        if let Some(atom_size) = allocation.atom_size() {
+            // The suballocators align allocations to the non-coherent atom size when the memory is
+            // host-visible but not host-coherent. The arenas are never written by the host, so it
+            // doesn't matter if this invalidates more than our own range.
+            let start = align_down(self.buffer.offset(), atom_size);
+            let end = cmp::min(
+                align_up(self.buffer.offset() + self.buffer.size(), atom_size),
+                allocation.size(),
+            );
+            allocation
+                .invalidate_range(start..end)
+                .map_err(BufferError::from)?;
+        }
+
+        let mapped_ptr = self
+            .buffer
+            .mapped_ptr()
+            .ok_or(BufferError::MemoryNotHostVisible)?;
+        let data = &*<[T]>::from_ffi(mapped_ptr.as_ptr(), self.buffer.size() as usize);
+
+        Ok(data.to_vec())
+    }
+}
+
+/// A future that resolves to the data of a [`Readback`] once the device is done copying it.
+///
+/// This implements [`std::future::Future`], so it can be awaited in an async context. It can
+/// also be waited on synchronously with [`wait`].
+///
+/// [`wait`]: Self::wait
+#[derive(Debug)]
+pub struct ReadbackFuture<T> {
+    readback: Readback<T>,
+    // `None` if the fence was already signaled and cleaned up.
+    fence: Option<Arc<Fence>>,
+}
+
+impl<T> ReadbackFuture<T>
+where
+    T: BufferContents + Clone,
+{
+    /// Returns the layout of the data, if it is being read back from an image.
+    #[inline]
+    pub fn image_layout(&self) -> Option<&ImageReadbackLayout> {
+        self.readback.image_layout()
+    }
+
+    /// Returns whether the data is ready to be read.
+    pub fn is_ready(&self) -> Result<bool, ReadbackError> {
+        match &self.fence {
+            Some(fence) => Ok(fence.is_signaled()?),
+            None => Ok(true),
+        }
+    }
+
+    /// Blocks the current thread until the data is ready, and returns it.
+    ///
+    /// If `timeout` is `None`, then the wait is infinite. Otherwise the thread will unblock after
+    /// the specified timeout has elapsed and an error will be returned.
+    pub fn wait(self, timeout: Option<Duration>) -> Result<Vec<T>, ReadbackError> {
+        if let Some(fence) = &self.fence {
+            fence.wait(timeout)?;
+        }
+
+        unsafe { self.readback.read_unchecked() }
+    }
+}
+
+impl<T> Future for ReadbackFuture<T>
+where
+    T: BufferContents + Clone,
+{
+    type Output = Result<Vec<T>, ReadbackError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(fence) = &self.fence {
+            if let Poll::Ready(result) = fence.poll_impl(cx) {
+                result?;
+            } else {
+                return Poll::Pending;
+            }
+        }
+
+        Poll::Ready(unsafe { self.readback.read_unchecked() })
+    }
+}
+
+/// Error that can happen when reading back data.
+#[derive(Clone, Debug)]
+pub enum ReadbackError {
+    /// Allocating a buffer to copy the data into failed.
+    AllocError(AllocationCreationError),
+
+    /// Recording a copy command failed.
+    CopyError(CopyError),
+
+    /// Accessing the buffer that the data was copied into failed.
+    BufferError(BufferError),
+
+    /// Waiting for the copy to complete failed.
+    FenceError(FenceError),
+}
+
+impl Error for ReadbackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::AllocError(err) => Some(err),
+            Self::CopyError(err) => Some(err),
+            Self::BufferError(err) => Some(err),
+            Self::FenceError(err) => Some(err),
+        }
+    }
+}
+
+impl Display for ReadbackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::AllocError(_) => write!(f, "allocating a buffer to copy the data into failed"),
+            Self::CopyError(_) => write!(f, "recording a copy command failed"),
+            Self::BufferError(_) => {
+                write!(
+                    f,
+                    "accessing the buffer that the data was copied into failed"
+                )
+            }
+            Self::FenceError(_) => write!(f, "waiting for the copy to complete failed"),
+        }
+    }
+}
+
+impl From<AllocationCreationError> for ReadbackError {
+    fn from(err: AllocationCreationError) -> Self {
+        Self::AllocError(err)
+    }
+}
+
+impl From<CopyError> for ReadbackError {
+    fn from(err: CopyError) -> Self {
+        Self::CopyError(err)
+    }
+}
+
+impl From<BufferError> for ReadbackError {
+    fn from(err: BufferError) -> Self {
+        Self::BufferError(err)
+    }
+}
+
+impl From<FenceError> for ReadbackError {
+    fn from(err: FenceError) -> Self {
+        Self::FenceError(err)
+    }
+}
+
+impl From<OomError> for ReadbackError {
+    fn from(err: OomError) -> Self {
+        Self::FenceError(err.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lcm, ReadbackAllocator};
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            CommandBufferUsage, PrimaryCommandBufferAbstract,
+        },
+        memory::allocator::{AllocationCreateInfo, MemoryUsage, StandardMemoryAllocator},
+        sync::GpuFuture,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn lcm_of_block_sizes() {
+        assert_eq!(lcm(1, 4), 4);
+        assert_eq!(lcm(3, 4), 12);
+        assert_eq!(lcm(6, 4), 12);
+        assert_eq!(lcm(16, 4), 16);
+    }
+
+    #[test]
+    fn read_buffer() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator =
+            StandardCommandBufferAllocator::new(device, Default::default());
+        let readback_allocator = ReadbackAllocator::new(memory_allocator.clone());
+
+        let source = Buffer::from_iter(
+            &memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Upload,
+                ..Default::default()
+            },
+            0..64u32,
+        )
+        .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        let readback = readback_allocator
+            .read_buffer(&mut builder, source)
+            .unwrap();
+        assert!(readback.image_layout().is_none());
+
+        let future = builder
+            .build()
+            .unwrap()
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+
+        let data = unsafe { readback.after(&future) }.wait(None).unwrap();
+        assert_eq!(data, (0..64u32).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,748 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Batched uploads of data to buffers and images.
+//!
+//! Getting data into device-local memory requires writing it to a host-visible staging buffer
+//! first, and then recording a copy from the staging buffer to the destination. Doing this one
+//! resource at a time, with a staging buffer and a command buffer of its own for each, wastes
+//! memory and submissions. A [`StagingBelt`] instead writes the data into reused staging arenas,
+//! and records the copies of many uploads into a single command buffer, which is submitted to a
+//! dedicated transfer queue when [flushed].
+//!
+//! # Queue family ownership
+//!
+//! When the resources are used on a queue of a different queue family than the one the uploads
+//! are submitted to, resources with [`Sharing::Exclusive`] must have their ownership transferred
+//! to the other queue family. If [`StagingBeltCreateInfo::dst_queue_family_index`] is set, the
+//! belt releases ownership of such resources at the end of its command buffer, and the
+//! corresponding acquire must be recorded on the other queue family with
+//! [`StagingUpload::record_acquire`] before the resource is used.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use vulkano::buffer::Subbuffer;
+//! # use vulkano::command_buffer::{
+//! #     allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+//! #     PrimaryCommandBufferAbstract, StagingBelt, StagingBeltCreateInfo,
+//! # };
+//! # use vulkano::sync::GpuFuture;
+//! # let device: Arc<vulkano::device::Device> = return;
+//! # let transfer_queue: Arc<vulkano::device::Queue> = return;
+//! # let graphics_queue: Arc<vulkano::device::Queue> = return;
+//! # let memory_allocator: Arc<vulkano::memory::allocator::StandardMemoryAllocator> = return;
+//! # let vertex_buffer: Subbuffer<[[f32; 2]]> = return;
+//! #
+//! let mut staging_belt = StagingBelt::new(
+//!     transfer_queue.clone(),
+//!     memory_allocator.clone(),
+//!     StandardCommandBufferAllocator::new(device.clone(), Default::default()),
+//!     StagingBeltCreateInfo {
+//!         dst_queue_family_index: Some(graphics_queue.queue_family_index()),
+//!         ..Default::default()
+//!     },
+//! );
+//!
+//! let upload = staging_belt
+//!     .upload_buffer_iter([[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]], vertex_buffer.clone())
+//!     .unwrap();
+//! staging_belt.flush().unwrap();
+//!
+//! // Later, on the graphics queue.
+//! # let command_buffer_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+//! let mut builder = AutoCommandBufferBuilder::primary(
+//!     &command_buffer_allocator,
+//!     graphics_queue.queue_family_index(),
+//!     CommandBufferUsage::OneTimeSubmit,
+//! )
+//! .unwrap();
+//! upload.record_acquire(&mut builder).unwrap();
+//! // Use `vertex_buffer`...
+//!
+//! builder
+//!     .build()
+//!     .unwrap()
+//!     .execute_after(upload.future().unwrap(), graphics_queue)
+//!     .unwrap()
+//!     .then_signal_fence_and_flush()
+//!     .unwrap();
+//! ```
+//!
+//! [flushed]: StagingBelt::flush
+//! [`Sharing::Exclusive`]: crate::sync::Sharing::Exclusive
+
+use super::{
+    allocator::{CommandBufferAllocator, StandardCommandBufferAllocator},
+    AutoCommandBufferBuilder, BarrierReason, BuildError, CommandBufferBeginError,
+    CommandBufferExecError, CommandBufferExecFuture, CommandBufferUsage, CopyBufferInfo,
+    CopyBufferToImageInfo, CopyError, PrimaryAutoCommandBuffer, PrimaryCommandBufferAbstract,
+};
+use crate::{
+    buffer::{
+        allocator::{SubbufferAllocator, SubbufferAllocatorCreateInfo},
+        BufferContents, BufferError, BufferUsage, Subbuffer,
+    },
+    device::Queue,
+    image::ImageAccess,
+    memory::allocator::{
+        AllocationCreationError, MemoryAllocator, MemoryUsage, StandardMemoryAllocator,
+    },
+    sync::{
+        future::{FenceSignalFuture, FlushError, NowFuture},
+        AccessFlags, BufferMemoryBarrier, DependencyInfo, GpuFuture, ImageMemoryBarrier,
+        PipelineStages, QueueFamilyOwnershipTransfer, Sharing,
+    },
+    DeviceSize,
+};
+use parking_lot::Mutex;
+use smallvec::SmallVec;
+use std::{
+    error::Error,
+    fmt::{Debug, Display, Error as FmtError, Formatter},
+    mem::replace,
+    ptr,
+    sync::Arc,
+};
+
+/// The future of a batch of uploads that was submitted by [`StagingBelt::flush`].
+pub type StagingFuture = Arc<FenceSignalFuture<CommandBufferExecFuture<NowFuture>>>;
+
+/// Writes data into staging arenas, and batches the copies to the destination buffers and images
+/// into a single command buffer.
+///
+/// See the [module-level documentation] for more information.
+///
+/// [module-level documentation]: self
+pub struct StagingBelt<M = Arc<StandardMemoryAllocator>, C = StandardCommandBufferAllocator>
+where
+    M: MemoryAllocator,
+    C: CommandBufferAllocator,
+{
+    queue: Arc<Queue>,
+    buffer_allocator: SubbufferAllocator<M>,
+    command_buffer_allocator: C,
+    dst_queue_family_index: Option<u32>,
+    // The command buffer that the uploads since the last flush are recorded into, if there are
+    // any.
+    builder: Option<AutoCommandBufferBuilder<PrimaryAutoCommandBuffer<C::Alloc>, C>>,
+    // Shared with the uploads of the current batch, to hand them the future once flushed.
+    batch: Arc<StagingBatch>,
+}
+
+#[derive(Default)]
+struct StagingBatch {
+    future: Mutex<Option<StagingFuture>>,
+}
+
+impl Debug for StagingBatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        f.debug_struct("StagingBatch")
+            .field("flushed", &self.future.lock().is_some())
+            .finish()
+    }
+}
+
+impl<M, C> StagingBelt<M, C>
+where
+    M: MemoryAllocator,
+    C: CommandBufferAllocator,
+{
+    /// Creates a new `StagingBelt` that submits its uploads to `queue`.
+    ///
+    /// The staging arenas are allocated from `memory_allocator`, and the command buffers from
+    /// `command_buffer_allocator`.
+    pub fn new(
+        queue: Arc<Queue>,
+        memory_allocator: M,
+        command_buffer_allocator: C,
+        create_info: StagingBeltCreateInfo,
+    ) -> Self {
+        let StagingBeltCreateInfo {
+            arena_size,
+            dst_queue_family_index,
+            _ne: _,
+        } = create_info;
+        let dst_queue_family_index =
+            dst_queue_family_index.filter(|&index| index != queue.queue_family_index());
+
+        StagingBelt {
+            queue,
+            buffer_allocator: SubbufferAllocator::new(
+                memory_allocator,
+                SubbufferAllocatorCreateInfo {
+                    arena_size,
+                    buffer_usage: BufferUsage::TRANSFER_SRC,
+                    memory_usage: MemoryUsage::Upload,
+                    ..Default::default()
+                },
+            ),
+            command_buffer_allocator,
+            dst_queue_family_index,
+            builder: None,
+            batch: Arc::new(StagingBatch::default()),
+        }
+    }
+
+    /// Returns the queue that the uploads are submitted to.
+    #[inline]
+    pub fn queue(&self) -> &Arc<Queue> {
+        &self.queue
+    }
+
+    /// Uploads `data` to `dst_buffer`.
+    pub fn upload_buffer_data<T>(
+        &mut self,
+        data: T,
+        dst_buffer: Subbuffer<T>,
+    ) -> Result<StagingUpload, StagingError>
+    where
+        T: BufferContents,
+    {
+        let staging_buffer = self.buffer_allocator.allocate_sized::<T>()?;
+        *staging_buffer.write()? = data;
+
+        self.record_buffer_upload(staging_buffer.into_bytes(), dst_buffer.into_bytes())
+    }
+
+    /// Uploads the elements of `iter` to `dst_buffer`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `iter` is empty.
+    pub fn upload_buffer_iter<T, I>(
+        &mut self,
+        iter: I,
+        dst_buffer: Subbuffer<[T]>,
+    ) -> Result<StagingUpload, StagingError>
+    where
+        T: BufferContents,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let staging_buffer = self.allocate_iter(iter)?;
+
+        self.record_buffer_upload(staging_buffer.into_bytes(), dst_buffer.into_bytes())
+    }
+
+    /// Uploads the texels of `iter` to the first mip level of all array layers of `dst_image`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `iter` is empty.
+    pub fn upload_image_iter<Px, I>(
+        &mut self,
+        iter: I,
+        dst_image: Arc<dyn ImageAccess>,
+    ) -> Result<StagingUpload, StagingError>
+    where
+        Px: BufferContents,
+        I: IntoIterator<Item = Px>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let staging_buffer = self.allocate_iter(iter)?;
+
+        self.builder()?
+            .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(
+                staging_buffer,
+                dst_image.clone(),
+            ))?;
+
+        let inner = dst_image.inner();
+        let ownership_transfer = self.ownership_transfer(inner.image.sharing());
+        let acquire = ownership_transfer.map(|queue_family_ownership_transfer| {
+            // The builder fills in the layout that the command buffer leaves the image in, which
+            // is the layout that the image is handed over in.
+            let release = unsafe {
+                self.builder
+                    .as_mut()
+                    .unwrap()
+                    .inner
+                    .pipeline_barrier_at_end(DependencyInfo {
+                        image_memory_barriers: [ImageMemoryBarrier {
+                            src_stages: PipelineStages::ALL_TRANSFER,
+                            src_access: AccessFlags::TRANSFER_WRITE,
+                            queue_family_ownership_transfer: Some(queue_family_ownership_transfer),
+                            subresource_range: dst_image.subresource_range(),
+                            ..ImageMemoryBarrier::image(inner.image.clone())
+                        }]
+                        .into_iter()
+                        .collect(),
+                        ..Default::default()
+                    })
+            };
+
+            DependencyInfo {
+                image_memory_barriers: release
+                    .image_memory_barriers
+                    .into_iter()
+                    .map(|barrier| ImageMemoryBarrier {
+                        src_stages: PipelineStages::empty(),
+                        src_access: AccessFlags::empty(),
+                        dst_stages: PipelineStages::ALL_COMMANDS,
+                        dst_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                        ..barrier
+                    })
+                    .collect(),
+                ..Default::default()
+            }
+        });
+
+        Ok(StagingUpload {
+            batch: self.batch.clone(),
+            acquire,
+        })
+    }
+
+    /// Submits the uploads that were recorded since the last flush to the queue, and returns the
+    /// future that is signaled once they have completed, or `None` if there were no uploads.
+    ///
+    /// The same future is handed to each of the uploads of the batch, and can be retrieved with
+    /// [`StagingUpload::future`].
+    pub fn flush(&mut self) -> Result<Option<StagingFuture>, StagingError> {
+        let builder = match self.builder.take() {
+            Some(builder) => builder,
+            None => return Ok(None),
+        };
+        let batch = replace(&mut self.batch, Arc::new(StagingBatch::default()));
+
+        let future = Arc::new(
+            builder
+                .build()?
+                .execute(self.queue.clone())?
+                .then_signal_fence_and_flush()?,
+        );
+        *batch.future.lock() = Some(future.clone());
+
+        Ok(Some(future))
+    }
+
+    fn builder(
+        &mut self,
+    ) -> Result<&mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer<C::Alloc>, C>, StagingError>
+    {
+        if self.builder.is_none() {
+            self.builder = Some(AutoCommandBufferBuilder::primary(
+                &self.command_buffer_allocator,
+                self.queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )?);
+        }
+
+        Ok(self.builder.as_mut().unwrap())
+    }
+
+    fn allocate_iter<T, I>(&mut self, iter: I) -> Result<Subbuffer<[T]>, StagingError>
+    where
+        T: BufferContents,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let staging_buffer = self
+            .buffer_allocator
+            .allocate_slice::<T>(iter.len() as DeviceSize)?;
+
+        for (o, i) in staging_buffer.write()?.iter_mut().zip(iter) {
+            unsafe { ptr::write(o, i) };
+        }
+
+        Ok(staging_buffer)
+    }
+
+    fn record_buffer_upload(
+        &mut self,
+        staging_buffer: Subbuffer<[u8]>,
+        dst_buffer: Subbuffer<[u8]>,
+    ) -> Result<StagingUpload, StagingError> {
+        self.builder()?
+            .copy_buffer(CopyBufferInfo::buffers(staging_buffer, dst_buffer.clone()))?;
+
+        let ownership_transfer = self.ownership_transfer(dst_buffer.buffer().sharing());
+        let acquire = ownership_transfer.map(|queue_family_ownership_transfer| {
+            let range = dst_buffer.offset()..dst_buffer.offset() + dst_buffer.size();
+            let barrier = BufferMemoryBarrier {
+                queue_family_ownership_transfer: Some(queue_family_ownership_transfer),
+                range,
+                ..BufferMemoryBarrier::buffer(dst_buffer.buffer().clone())
+            };
+
+            unsafe {
+                self.builder
+                    .as_mut()
+                    .unwrap()
+                    .inner
+                    .pipeline_barrier_at_end(DependencyInfo {
+                        buffer_memory_barriers: [BufferMemoryBarrier {
+                            src_stages: PipelineStages::ALL_TRANSFER,
+                            src_access: AccessFlags::TRANSFER_WRITE,
+                            ..barrier.clone()
+                        }]
+                        .into_iter()
+                        .collect(),
+                        ..Default::default()
+                    });
+            }
+
+            DependencyInfo {
+                buffer_memory_barriers: [BufferMemoryBarrier {
+                    dst_stages: PipelineStages::ALL_COMMANDS,
+                    dst_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                    ..barrier
+                }]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            }
+        });
+
+        Ok(StagingUpload {
+            batch: self.batch.clone(),
+            acquire,
+        })
+    }
+
+    // Returns the ownership transfer that a resource with the given sharing mode needs, if any.
+    fn ownership_transfer(
+        &self,
+        sharing: &Sharing<SmallVec<[u32; 4]>>,
+    ) -> Option<QueueFamilyOwnershipTransfer> {
+        match sharing {
+            Sharing::Exclusive => self.dst_queue_family_index.map(|dst_index| {
+                QueueFamilyOwnershipTransfer::ExclusiveBetweenLocal {
+                    src_index: self.queue.queue_family_index(),
+                    dst_index,
+                }
+            }),
+            Sharing::Concurrent(_) => None,
+        }
+    }
+}
+
+/// Parameters to create a new [`StagingBelt`].
+#[derive(Clone, Debug)]
+pub struct StagingBeltCreateInfo {
+    /// The initial size in bytes of the staging arenas.
+    ///
+    /// Ideally this should fit all the data that is uploaded between two flushes. See
+    /// [`SubbufferAllocatorCreateInfo::arena_size`].
+    ///
+    /// The default value is `0`.
+    pub arena_size: DeviceSize,
+
+    /// The queue family that the uploaded resources are going to be used on.
+    ///
+    /// If this is `Some` and different from the queue family of the queue of the belt, ownership
+    /// of resources with [`Sharing::Exclusive`] is transferred to this queue family.
+    ///
+    /// The default value is `None`.
+    pub dst_queue_family_index: Option<u32>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for StagingBeltCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            arena_size: 0,
+            dst_queue_family_index: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// An upload that was recorded into a [`StagingBelt`].
+#[derive(Debug)]
+pub struct StagingUpload {
+    batch: Arc<StagingBatch>,
+    // The barrier that acquires ownership of the resource on the destination queue family.
+    acquire: Option<DependencyInfo>,
+}
+
+impl StagingUpload {
+    /// Returns the future that is signaled once the upload has completed, or `None` if the
+    /// [`StagingBelt`] wasn't flushed since the upload was recorded.
+    #[inline]
+    pub fn future(&self) -> Option<StagingFuture> {
+        self.batch.future.lock().clone()
+    }
+
+    /// Returns whether the upload has completed.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        match self.future() {
+            Some(future) => future.is_signaled().unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Returns whether the resource needs its ownership to be acquired with [`record_acquire`]
+    /// before it is used.
+    ///
+    /// [`record_acquire`]: Self::record_acquire
+    #[inline]
+    pub fn needs_acquire(&self) -> bool {
+        self.acquire.is_some()
+    }
+
+    /// Records the acquire of ownership of the resource into `builder`, if the upload transferred
+    /// its ownership to another queue family. Otherwise this does nothing.
+    ///
+    /// This must be recorded before any command that uses the resource, and the command buffer
+    /// must be executed after the [future] of the upload. The builder tracks the resource from
+    /// the acquire on, so the commands that use it afterwards are synchronized with it.
+    ///
+    /// [future]: Self::future
+    pub fn record_acquire<L, A>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L, A>,
+    ) -> Result<(), StagingError>
+    where
+        A: CommandBufferAllocator,
+    {
+        let acquire = match &self.acquire {
+            Some(acquire) => acquire,
+            None => return Ok(()),
+        };
+
+        if builder.render_pass_state.is_some() {
+            return Err(StagingError::ForbiddenInsideRenderPass);
+        }
+
+        let dst_index = match acquire
+            .buffer_memory_barriers
+            .iter()
+            .map(|barrier| barrier.queue_family_ownership_transfer)
+            .chain(
+                acquire
+                    .image_memory_barriers
+                    .iter()
+                    .map(|barrier| barrier.queue_family_ownership_transfer),
+            )
+            .next()
+        {
+            Some(Some(QueueFamilyOwnershipTransfer::ExclusiveBetweenLocal {
+                dst_index, ..
+            })) => dst_index,
+            _ => unreachable!(),
+        };
+
+        if builder.queue_family_index != dst_index {
+            return Err(StagingError::QueueFamilyIndexMismatch {
+                required: dst_index,
+                provided: builder.queue_family_index,
+            });
+        }
+
+        if builder.inner.is_any_barrier_resource_used(acquire) {
+            return Err(StagingError::AcquireAfterUse);
+        }
+
+        unsafe {
+            builder.inner.pipeline_barrier_immediate(
+                acquire.clone(),
+                BarrierReason::QueueFamilyOwnershipTransfer,
+            )
+        };
+
+        Ok(())
+    }
+}
+
+/// Error that can happen when uploading with a [`StagingBelt`].
+#[derive(Clone, Debug)]
+pub enum StagingError {
+    /// Allocating a staging buffer failed.
+    AllocError(AllocationCreationError),
+
+    /// Writing to a staging buffer failed.
+    BufferError(BufferError),
+
+    /// Beginning a command buffer failed.
+    BeginError(CommandBufferBeginError),
+
+    /// Recording a copy command failed.
+    CopyError(CopyError),
+
+    /// Building the command buffer failed.
+    BuildError(BuildError),
+
+    /// Executing the command buffer failed.
+    ExecError(CommandBufferExecError),
+
+    /// Submitting the command buffer failed.
+    FlushError(FlushError),
+
+    /// An acquire of ownership was recorded inside a render pass.
+    ForbiddenInsideRenderPass,
+
+    /// An acquire of ownership was recorded after the resource was already used by the command
+    /// buffer.
+    AcquireAfterUse,
+
+    /// An acquire of ownership was recorded into a command buffer of a different queue family than
+    /// the one that ownership was transferred to.
+    QueueFamilyIndexMismatch { required: u32, provided: u32 },
+}
+
+impl Error for StagingError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::AllocError(err) => Some(err),
+            Self::BufferError(err) => Some(err),
+            Self::BeginError(err) => Some(err),
+            Self::CopyError(err) => Some(err),
+            Self::BuildError(err) => Some(err),
+            Self::ExecError(err) => Some(err),
+            Self::FlushError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for StagingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::AllocError(_) => write!(f, "allocating a staging buffer failed"),
+            Self::BufferError(_) => write!(f, "writing to a staging buffer failed"),
+            Self::BeginError(_) => write!(f, "beginning a command buffer failed"),
+            Self::CopyError(_) => write!(f, "recording a copy command failed"),
+            Self::BuildError(_) => write!(f, "building the command buffer failed"),
+            Self::ExecError(_) => write!(f, "executing the command buffer failed"),
+            Self::FlushError(_) => write!(f, "submitting the command buffer failed"),
+            Self::ForbiddenInsideRenderPass => write!(
+                f,
+                "an acquire of ownership was recorded inside a render pass",
+            ),
+            Self::AcquireAfterUse => write!(
+                f,
+                "an acquire of ownership was recorded after the resource was already used by the \
+                command buffer",
+            ),
+            Self::QueueFamilyIndexMismatch { required, provided } => write!(
+                f,
+                "ownership was transferred to queue family {}, but the command buffer is for \
+                queue family {}",
+                required, provided,
+            ),
+        }
+    }
+}
+
+impl From<AllocationCreationError> for StagingError {
+    fn from(err: AllocationCreationError) -> Self {
+        Self::AllocError(err)
+    }
+}
+
+impl From<BufferError> for StagingError {
+    fn from(err: BufferError) -> Self {
+        Self::BufferError(err)
+    }
+}
+
+impl From<CommandBufferBeginError> for StagingError {
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::BeginError(err)
+    }
+}
+
+impl From<CopyError> for StagingError {
+    fn from(err: CopyError) -> Self {
+        Self::CopyError(err)
+    }
+}
+
+impl From<BuildError> for StagingError {
+    fn from(err: BuildError) -> Self {
+        Self::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for StagingError {
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::ExecError(err)
+    }
+}
+
+impl From<FlushError> for StagingError {
+    fn from(err: FlushError) -> Self {
+        Self::FlushError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StagingBelt, StagingBeltCreateInfo};
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        command_buffer::allocator::StandardCommandBufferAllocator,
+        memory::allocator::{AllocationCreateInfo, MemoryUsage, StandardMemoryAllocator},
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn batched_buffer_uploads() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let mut staging_belt = StagingBelt::new(
+            queue,
+            memory_allocator.clone(),
+            StandardCommandBufferAllocator::new(device, Default::default()),
+            StagingBeltCreateInfo::default(),
+        );
+
+        let buffers: Vec<_> = (0..4)
+            .map(|_| {
+                Buffer::new_slice::<u32>(
+                    &memory_allocator,
+                    BufferCreateInfo {
+                        usage: BufferUsage::TRANSFER_DST,
+                        ..Default::default()
+                    },
+                    AllocationCreateInfo {
+                        usage: MemoryUsage::Download,
+                        ..Default::default()
+                    },
+                    16,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        let uploads: Vec<_> = buffers
+            .iter()
+            .enumerate()
+            .map(|(i, buffer)| {
+                staging_belt
+                    .upload_buffer_iter([i as u32; 16], buffer.clone())
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(uploads.iter().all(|upload| upload.future().is_none()));
+        assert!(uploads.iter().all(|upload| !upload.needs_acquire()));
+
+        let future = staging_belt.flush().unwrap().unwrap();
+        assert!(staging_belt.flush().unwrap().is_none());
+        future.wait(None).unwrap();
+
+        for upload in &uploads {
+            assert!(Arc::ptr_eq(&upload.future().unwrap(), &future));
+            assert!(upload.is_complete());
+        }
+
+        for (i, buffer) in buffers.iter().enumerate() {
+            assert_eq!(*buffer.read().unwrap(), [i as u32; 16]);
+        }
+    }
+}
@@ -84,7 +84,7 @@ where
         // VUID-vkCmdBindDescriptorSets-commandBuffer-cmdpool
         // VUID-vkCmdBindDescriptorSets-pipelineBindPoint-00361
         match pipeline_bind_point {
-            PipelineBindPoint::Compute => {
+            PipelineBindPoint::Compute | PipelineBindPoint::RayTracing => {
                 if !queue_family_properties
                     .queue_flags
                     .intersects(QueueFlags::COMPUTE)
@@ -805,7 +805,7 @@ where
         // VUID-vkCmdPushDescriptorSetKHR-commandBuffer-cmdpool
         // VUID-vkCmdPushDescriptorSetKHR-pipelineBindPoint-00363
         match pipeline_bind_point {
-            PipelineBindPoint::Compute => {
+            PipelineBindPoint::Compute | PipelineBindPoint::RayTracing => {
                 if !queue_family_properties
                     .queue_flags
                     .intersects(QueueFlags::COMPUTE)
@@ -861,7 +861,7 @@ where
 
         debug_assert!(self.device().enabled_extensions().khr_push_descriptor);
 
-        let (infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = descriptor_writes
+        let (mut infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = descriptor_writes
             .iter()
             .map(|write| {
                 let binding =
@@ -879,7 +879,7 @@ where
         }
 
         // Set the info pointers separately.
-        for (info, write) in infos.iter().zip(writes.iter_mut()) {
+        for (info, write) in infos.iter_mut().zip(writes.iter_mut()) {
             match info {
                 DescriptorWriteInfo::Image(info) => {
                     write.descriptor_count = info.len() as u32;
@@ -893,6 +893,15 @@ where
                     write.descriptor_count = info.len() as u32;
                     write.p_texel_buffer_view = info.as_ptr();
                 }
+                DescriptorWriteInfo::AccelerationStructure(info, info_vk) => {
+                    *info_vk = ash::vk::WriteDescriptorSetAccelerationStructureKHR {
+                        acceleration_structure_count: info.len() as u32,
+                        p_acceleration_structures: info.as_ptr(),
+                        ..Default::default()
+                    };
+                    write.descriptor_count = info.len() as u32;
+                    write.p_next = info_vk as *const _ as *const _;
+                }
             }
 
             debug_assert!(write.descriptor_count != 0);
@@ -19,6 +19,7 @@ pub use crate::command_buffer::{
     DebugUtilsError, ExecuteCommandsError, ImageBlit, ImageCopy, ImageResolve,
     PipelineExecutionError, QueryError, RenderPassBeginInfo, RenderPassError,
     RenderingAttachmentInfo, RenderingAttachmentResolveInfo, RenderingInfo, ResolveImageInfo,
+    SynchronizationError,
 };
 use crate::{
     buffer::{Buffer, Subbuffer},
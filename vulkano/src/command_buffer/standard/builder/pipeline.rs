@@ -862,6 +862,413 @@ where
         self
     }
 
+    /// Perform multiple draw operations using a graphics pipeline, reading the number of draws
+    /// from a buffer.
+    ///
+    /// One draw is performed for each [`DrawIndirectCommand`] struct in `indirect_buffer`, up to
+    /// the number of draws that is read from `count_buffer` when the command is executed.
+    /// `max_draw_count` is an upper bound on the number of draws, and must not be greater than the
+    /// number of commands in `indirect_buffer`.
+    ///
+    /// The [`draw_indirect_count`] feature or the [`khr_draw_indirect_count`] extension must be
+    /// enabled on the device. `count_buffer` must have the [`INDIRECT_BUFFER`] usage, and its
+    /// offset must be a multiple of 4.
+    ///
+    /// A graphics pipeline must have been bound using [`bind_pipeline_graphics`]. Any resources
+    /// used by the graphics pipeline, such as descriptor sets, vertex buffers and dynamic state,
+    /// must have been set beforehand. If the bound graphics pipeline uses vertex buffers, then the
+    /// vertex and instance ranges of each `DrawIndirectCommand` in the indirect buffer must be in
+    /// range of the bound vertex buffers.
+    ///
+    /// # Safety
+    ///
+    /// - Appropriate synchronization must be provided for all buffers and images
+    ///   that are accessed by the command.
+    /// - All images that are accessed by the command must be in the expected image layout.
+    ///
+    /// [`draw_indirect_count`]: crate::device::Features::draw_indirect_count
+    /// [`khr_draw_indirect_count`]: crate::device::DeviceExtensions::khr_draw_indirect_count
+    /// [`INDIRECT_BUFFER`]: BufferUsage::INDIRECT_BUFFER
+    /// [`bind_pipeline_graphics`]: Self::bind_pipeline_graphics
+    #[inline]
+    pub unsafe fn draw_indirect_count(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        let stride = size_of::<DrawIndirectCommand>() as u32;
+        self.validate_draw_indirect_count(
+            indirect_buffer.as_bytes(),
+            count_buffer.as_bytes(),
+            max_draw_count,
+            stride,
+        )?;
+
+        unsafe {
+            Ok(self.draw_indirect_count_unchecked(
+                indirect_buffer,
+                count_buffer,
+                max_draw_count,
+                stride,
+            ))
+        }
+    }
+
+    fn validate_draw_indirect_count(
+        &self,
+        indirect_buffer: &Subbuffer<[u8]>,
+        count_buffer: &Subbuffer<[u8]>,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID-vkCmdDrawIndirectCount-None-04445
+        if !(self.device().enabled_features().draw_indirect_count
+            || self.device().enabled_extensions().khr_draw_indirect_count)
+        {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`CommandBufferBuilder::draw_indirect_count`",
+                requires_one_of: RequiresOneOf {
+                    features: &["draw_indirect_count"],
+                    device_extensions: &["khr_draw_indirect_count"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdDrawIndirectCount-renderpass
+        let render_pass_state = self
+            .builder_state
+            .render_pass
+            .as_ref()
+            .ok_or(PipelineExecutionError::ForbiddenOutsideRenderPass)?;
+
+        // VUID-vkCmdDrawIndirectCount-None-02700
+        let pipeline = self
+            .builder_state
+            .pipeline_graphics
+            .as_ref()
+            .ok_or(PipelineExecutionError::PipelineNotBound)?
+            .as_ref();
+
+        // VUID-vkCmdDrawIndirectCount-stage-06481
+        if pipeline.shader(ShaderStage::Mesh).is_some() {
+            return Err(PipelineExecutionError::PipelineMeshShaderNotAllowed);
+        }
+
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+        self.validate_pipeline_graphics_dynamic_state(pipeline)?;
+        self.validate_pipeline_graphics_render_pass(pipeline, render_pass_state)?;
+        self.validate_pipeline_graphics_vertex_buffers(pipeline, None, None)?;
+
+        self.validate_indirect_buffer(indirect_buffer)?;
+        self.validate_indirect_count_buffer(count_buffer)?;
+        self.validate_indirect_buffer_max_draw_count(indirect_buffer, max_draw_count, stride)?;
+
+        // TODO: sync check
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn draw_indirect_count_unchecked(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> &mut Self {
+        let fns = self.device().fns();
+
+        if self.device().enabled_features().draw_indirect_count {
+            (fns.v1_2.cmd_draw_indirect_count)(
+                self.handle(),
+                indirect_buffer.buffer().handle(),
+                indirect_buffer.offset(),
+                count_buffer.buffer().handle(),
+                count_buffer.offset(),
+                max_draw_count,
+                stride,
+            );
+        } else {
+            (fns.khr_draw_indirect_count.cmd_draw_indirect_count_khr)(
+                self.handle(),
+                indirect_buffer.buffer().handle(),
+                indirect_buffer.offset(),
+                count_buffer.buffer().handle(),
+                count_buffer.offset(),
+                max_draw_count,
+                stride,
+            );
+        }
+
+        let command_index = self.next_command_index;
+        let command_name = "draw_indirect_count";
+        let pipeline = self
+            .builder_state
+            .pipeline_graphics
+            .as_ref()
+            .unwrap()
+            .as_ref();
+        record_descriptor_sets_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            &self.builder_state.descriptor_sets,
+            pipeline,
+        );
+        record_vertex_buffers_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            &self.builder_state.vertex_buffers,
+            pipeline,
+        );
+        record_indirect_buffer_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            indirect_buffer.as_bytes(),
+        );
+        record_indirect_count_buffer_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            count_buffer.as_bytes(),
+        );
+        record_subpass_attachments_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            self.builder_state.render_pass.as_ref().unwrap(),
+            &self.builder_state,
+            pipeline,
+        );
+
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.builder_state.render_pass.as_mut().unwrap().render_pass
+        {
+            state.pipeline_used = true;
+        }
+
+        self.resources.push(Box::new(indirect_buffer));
+        self.resources.push(Box::new(count_buffer));
+
+        self.next_command_index += 1;
+        self
+    }
+
+    /// Perform multiple draw operations using a graphics pipeline, using an index buffer, and
+    /// reading the number of draws from a buffer.
+    ///
+    /// One draw is performed for each [`DrawIndexedIndirectCommand`] struct in `indirect_buffer`,
+    /// up to the number of draws that is read from `count_buffer` when the command is executed.
+    /// `max_draw_count` is an upper bound on the number of draws, and must not be greater than the
+    /// number of commands in `indirect_buffer`.
+    ///
+    /// The [`draw_indirect_count`] feature or the [`khr_draw_indirect_count`] extension must be
+    /// enabled on the device. `count_buffer` must have the [`INDIRECT_BUFFER`] usage, and its
+    /// offset must be a multiple of 4.
+    ///
+    /// An index buffer must have been bound using [`bind_index_buffer`], and the index ranges of
+    /// each `DrawIndexedIndirectCommand` in the indirect buffer must be in range of the bound
+    /// index buffer.
+    ///
+    /// A graphics pipeline must have been bound using [`bind_pipeline_graphics`]. Any resources
+    /// used by the graphics pipeline, such as descriptor sets, vertex buffers and dynamic state,
+    /// must have been set beforehand. If the bound graphics pipeline uses vertex buffers, then the
+    /// instance ranges of each `DrawIndexedIndirectCommand` in the indirect buffer must be in
+    /// range of the bound vertex buffers.
+    ///
+    /// # Safety
+    ///
+    /// - Appropriate synchronization must be provided for all buffers and images
+    ///   that are accessed by the command.
+    /// - All images that are accessed by the command must be in the expected image layout.
+    ///
+    /// [`draw_indirect_count`]: crate::device::Features::draw_indirect_count
+    /// [`khr_draw_indirect_count`]: crate::device::DeviceExtensions::khr_draw_indirect_count
+    /// [`INDIRECT_BUFFER`]: BufferUsage::INDIRECT_BUFFER
+    /// [`bind_index_buffer`]: Self::bind_index_buffer
+    /// [`bind_pipeline_graphics`]: Self::bind_pipeline_graphics
+    #[inline]
+    pub unsafe fn draw_indexed_indirect_count(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawIndexedIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        let stride = size_of::<DrawIndexedIndirectCommand>() as u32;
+        self.validate_draw_indexed_indirect_count(
+            indirect_buffer.as_bytes(),
+            count_buffer.as_bytes(),
+            max_draw_count,
+            stride,
+        )?;
+
+        unsafe {
+            Ok(self.draw_indexed_indirect_count_unchecked(
+                indirect_buffer,
+                count_buffer,
+                max_draw_count,
+                stride,
+            ))
+        }
+    }
+
+    fn validate_draw_indexed_indirect_count(
+        &self,
+        indirect_buffer: &Subbuffer<[u8]>,
+        count_buffer: &Subbuffer<[u8]>,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID-vkCmdDrawIndexedIndirectCount-None-04445
+        if !(self.device().enabled_features().draw_indirect_count
+            || self.device().enabled_extensions().khr_draw_indirect_count)
+        {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`CommandBufferBuilder::draw_indexed_indirect_count`",
+                requires_one_of: RequiresOneOf {
+                    features: &["draw_indirect_count"],
+                    device_extensions: &["khr_draw_indirect_count"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdDrawIndexedIndirectCount-renderpass
+        let render_pass_state = self
+            .builder_state
+            .render_pass
+            .as_ref()
+            .ok_or(PipelineExecutionError::ForbiddenOutsideRenderPass)?;
+
+        // VUID-vkCmdDrawIndexedIndirectCount-None-02700
+        let pipeline = self
+            .builder_state
+            .pipeline_graphics
+            .as_ref()
+            .ok_or(PipelineExecutionError::PipelineNotBound)?
+            .as_ref();
+
+        // VUID-vkCmdDrawIndexedIndirectCount-stage-06481
+        if pipeline.shader(ShaderStage::Mesh).is_some() {
+            return Err(PipelineExecutionError::PipelineMeshShaderNotAllowed);
+        }
+
+        self.validate_pipeline_descriptor_sets(pipeline)?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+        self.validate_pipeline_graphics_dynamic_state(pipeline)?;
+        self.validate_pipeline_graphics_render_pass(pipeline, render_pass_state)?;
+        self.validate_pipeline_graphics_vertex_buffers(pipeline, None, None)?;
+
+        self.validate_index_buffer(None)?;
+        self.validate_indirect_buffer(indirect_buffer)?;
+        self.validate_indirect_count_buffer(count_buffer)?;
+        self.validate_indirect_buffer_max_draw_count(indirect_buffer, max_draw_count, stride)?;
+
+        // TODO: sync check
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn draw_indexed_indirect_count_unchecked(
+        &mut self,
+        indirect_buffer: Subbuffer<[DrawIndexedIndirectCommand]>,
+        count_buffer: Subbuffer<u32>,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> &mut Self {
+        let fns = self.device().fns();
+
+        if self.device().enabled_features().draw_indirect_count {
+            (fns.v1_2.cmd_draw_indexed_indirect_count)(
+                self.handle(),
+                indirect_buffer.buffer().handle(),
+                indirect_buffer.offset(),
+                count_buffer.buffer().handle(),
+                count_buffer.offset(),
+                max_draw_count,
+                stride,
+            );
+        } else {
+            (fns.khr_draw_indirect_count
+                .cmd_draw_indexed_indirect_count_khr)(
+                self.handle(),
+                indirect_buffer.buffer().handle(),
+                indirect_buffer.offset(),
+                count_buffer.buffer().handle(),
+                count_buffer.offset(),
+                max_draw_count,
+                stride,
+            );
+        }
+
+        let command_index = self.next_command_index;
+        let command_name = "draw_indexed_indirect_count";
+        let pipeline = self
+            .builder_state
+            .pipeline_graphics
+            .as_ref()
+            .unwrap()
+            .as_ref();
+        record_descriptor_sets_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            &self.builder_state.descriptor_sets,
+            pipeline,
+        );
+        record_vertex_buffers_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            &self.builder_state.vertex_buffers,
+            pipeline,
+        );
+        record_index_buffer_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            &self.builder_state.index_buffer,
+        );
+        record_indirect_buffer_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            indirect_buffer.as_bytes(),
+        );
+        record_indirect_count_buffer_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            count_buffer.as_bytes(),
+        );
+        record_subpass_attachments_access(
+            &mut self.resources_usage_state,
+            command_index,
+            command_name,
+            self.builder_state.render_pass.as_ref().unwrap(),
+            &self.builder_state,
+            pipeline,
+        );
+
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.builder_state.render_pass.as_mut().unwrap().render_pass
+        {
+            state.pipeline_used = true;
+        }
+
+        self.resources.push(Box::new(indirect_buffer));
+        self.resources.push(Box::new(count_buffer));
+
+        self.next_command_index += 1;
+        self
+    }
+
     fn validate_index_buffer(
         &self,
         indices: Option<(u32, u32)>,
@@ -910,6 +1317,53 @@ where
         Ok(())
     }
 
+    fn validate_indirect_count_buffer(
+        &self,
+        count_buffer: &Subbuffer<[u8]>,
+    ) -> Result<(), PipelineExecutionError> {
+        // VUID-vkCmdDrawIndirectCount-commonparent
+        assert_eq!(self.device(), count_buffer.device());
+
+        // VUID-vkCmdDrawIndirectCount-countBuffer-02715
+        if !count_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::INDIRECT_BUFFER)
+        {
+            return Err(PipelineExecutionError::IndirectCountBufferMissingUsage);
+        }
+
+        // VUID-vkCmdDrawIndirectCount-countBufferOffset-02716
+        if count_buffer.offset() % 4 != 0 {
+            return Err(
+                PipelineExecutionError::IndirectCountBufferOffsetNotAligned {
+                    offset: count_buffer.offset(),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    fn validate_indirect_buffer_max_draw_count(
+        &self,
+        indirect_buffer: &Subbuffer<[u8]>,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> Result<(), PipelineExecutionError> {
+        let commands_in_buffer = indirect_buffer.size() / stride as DeviceSize;
+
+        // VUID-vkCmdDrawIndirectCount-maxDrawCount-03142
+        if max_draw_count as DeviceSize > commands_in_buffer {
+            return Err(PipelineExecutionError::IndirectBufferDrawCountOutOfBounds {
+                max_draw_count,
+                commands_in_buffer,
+            });
+        }
+
+        Ok(())
+    }
+
     fn validate_pipeline_descriptor_sets(
         &self,
         pipeline: &impl Pipeline,
@@ -1307,6 +1761,15 @@ where
                         check_sampler,
                     )?;
                 }
+                DescriptorBindingResources::AccelerationStructure(elements) => {
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        binding_reqs,
+                        elements,
+                        |_, _| Ok(()),
+                    )?;
+                }
             }
         }
 
@@ -1486,6 +1949,12 @@ where
                     }
                 }
                 DynamicState::PrimitiveRestartEnable => {
+                    // Pipelines with a mesh shader have no input assembly state.
+                    let input_assembly_state = match pipeline.input_assembly_state() {
+                        Some(x) => x,
+                        None => continue,
+                    };
+
                     // VUID-vkCmdDraw-None-04879
                     let primitive_restart_enable =
                         if let Some(enable) = self.builder_state.primitive_restart_enable {
@@ -1495,7 +1964,7 @@ where
                         };
 
                     if primitive_restart_enable {
-                        let topology = match pipeline.input_assembly_state().topology {
+                        let topology = match input_assembly_state.topology {
                             PartialStateMode::Fixed(topology) => topology,
                             PartialStateMode::Dynamic(_) => {
                                 if let Some(topology) = self.builder_state.primitive_topology {
@@ -1551,6 +2020,11 @@ where
                     }
                 }
                 DynamicState::PrimitiveTopology => {
+                    let input_assembly_state = match pipeline.input_assembly_state() {
+                        Some(x) => x,
+                        None => continue,
+                    };
+
                     // VUID-vkCmdDraw-primitiveTopology-03420
                     let topology = if let Some(topology) = self.builder_state.primitive_topology {
                         topology
@@ -1574,7 +2048,7 @@ where
                         }
                     }
 
-                    let required_topology_class = match pipeline.input_assembly_state().topology {
+                    let required_topology_class = match input_assembly_state.topology {
                         PartialStateMode::Dynamic(topology_class) => topology_class,
                         _ => unreachable!(),
                     };
@@ -1898,11 +2372,14 @@ where
         vertices: Option<(u32, u32)>,
         instances: Option<(u32, u32)>,
     ) -> Result<(), PipelineExecutionError> {
-        let vertex_input = pipeline.vertex_input_state();
+        let vertex_input_bindings = pipeline
+            .vertex_input_state()
+            .into_iter()
+            .flat_map(|state| &state.bindings);
         let mut vertices_in_buffers: Option<u64> = None;
         let mut instances_in_buffers: Option<u64> = None;
 
-        for (&binding_num, binding_desc) in &vertex_input.bindings {
+        for (&binding_num, binding_desc) in vertex_input_bindings {
             // VUID-vkCmdDraw-None-04007
             let vertex_buffer = match self.builder_state.vertex_buffers.get(&binding_num) {
                 Some(x) => x,
@@ -2188,6 +2665,25 @@ fn record_descriptor_sets_access(
                 }
             }
             DescriptorBindingResources::Sampler(_) => (),
+            DescriptorBindingResources::AccelerationStructure(elements) => {
+                for (index, element) in elements.iter().enumerate() {
+                    if let Some(acceleration_structure) = element {
+                        let buffer = acceleration_structure.buffer();
+                        let (use_ref, stage_access_iter) = use_iter(index as u32);
+
+                        let range = buffer.offset()..buffer.offset() + buffer.size();
+
+                        for stage_access in stage_access_iter {
+                            resources_usage_state.record_buffer_access(
+                                &use_ref,
+                                buffer.buffer(),
+                                range.clone(),
+                                stage_access,
+                            );
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -2199,7 +2695,11 @@ fn record_vertex_buffers_access(
     vertex_buffers_state: &HashMap<u32, Subbuffer<[u8]>>,
     pipeline: &GraphicsPipeline,
 ) {
-    for &binding in pipeline.vertex_input_state().bindings.keys() {
+    for &binding in pipeline
+        .vertex_input_state()
+        .into_iter()
+        .flat_map(|state| state.bindings.keys())
+    {
         let buffer = &vertex_buffers_state[&binding];
         let use_ref = ResourceUseRef {
             command_index,
@@ -2269,6 +2769,30 @@ fn record_indirect_buffer_access(
     );
 }
 
+fn record_indirect_count_buffer_access(
+    resources_usage_state: &mut ResourcesState,
+    command_index: usize,
+    command_name: &'static str,
+    buffer: &Subbuffer<[u8]>,
+) {
+    let use_ref = ResourceUseRef {
+        command_index,
+        command_name,
+        resource_in_command: ResourceInCommand::IndirectCountBuffer,
+        secondary_use_ref: None,
+    };
+
+    let mut range = 0..buffer.size();
+    range.start += buffer.offset();
+    range.end += buffer.offset();
+    resources_usage_state.record_buffer_access(
+        &use_ref,
+        buffer.buffer(),
+        range,
+        PipelineStageAccess::DrawIndirect_IndirectCommandRead,
+    );
+}
+
 fn record_subpass_attachments_access(
     resources_usage_state: &mut ResourcesState,
     command_index: usize,
@@ -118,6 +118,100 @@ where
             }
             // VUID-vkCmdBeginQuery-queryType-02804
             QueryType::Timestamp => return Err(QueryError::NotPermitted),
+            // VUID-vkCmdBeginQuery-queryType-04728
+            QueryType::AccelerationStructureCompactedSize
+            | QueryType::AccelerationStructureSerializationSize => {
+                return Err(QueryError::NotPermitted)
+            }
+            QueryType::TransformFeedbackStream => {
+                // VUID-vkCmdBeginQuery-commandBuffer-cmdpool
+                // VUID-vkCmdBeginQuery-queryType-02327
+                if !queue_family_properties
+                    .queue_flags
+                    .intersects(QueueFlags::GRAPHICS)
+                {
+                    return Err(QueryError::NotSupportedByQueueFamily);
+                }
+
+                // VUID-vkCmdBeginQuery-queryType-02328
+                if !device
+                    .physical_device()
+                    .properties()
+                    .transform_feedback_queries
+                    .unwrap_or(false)
+                {
+                    return Err(QueryError::NotPermitted);
+                }
+
+                // VUID-vkCmdBeginQuery-queryType-00800
+                if flags.intersects(QueryControlFlags::PRECISE) {
+                    return Err(QueryError::InvalidFlags);
+                }
+            }
+            QueryType::PrimitivesGenerated => {
+                // VUID-vkCmdBeginQuery-commandBuffer-cmdpool
+                // VUID-vkCmdBeginQuery-queryType-06687
+                if !queue_family_properties
+                    .queue_flags
+                    .intersects(QueueFlags::GRAPHICS)
+                {
+                    return Err(QueryError::NotSupportedByQueueFamily);
+                }
+
+                // VUID-vkCmdBeginQuery-queryType-06688
+                if !device.enabled_features().primitives_generated_query {
+                    return Err(QueryError::RequirementNotMet {
+                        required_for: "`query_pool.query_type()` is \
+                            `QueryType::PrimitivesGenerated`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["primitives_generated_query"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-vkCmdBeginQuery-queryType-00800
+                if flags.intersects(QueryControlFlags::PRECISE) {
+                    return Err(QueryError::InvalidFlags);
+                }
+            }
+            QueryType::MeshPrimitivesGenerated => {
+                // VUID-vkCmdBeginQuery-commandBuffer-cmdpool
+                // VUID-vkCmdBeginQuery-queryType-07070
+                if !queue_family_properties
+                    .queue_flags
+                    .intersects(QueueFlags::GRAPHICS)
+                {
+                    return Err(QueryError::NotSupportedByQueueFamily);
+                }
+
+                // VUID-vkCmdBeginQuery-queryType-00800
+                if flags.intersects(QueryControlFlags::PRECISE) {
+                    return Err(QueryError::InvalidFlags);
+                }
+            }
+            QueryType::Performance => {
+                // VUID-vkCmdBeginQuery-queryPool-07289
+                if query_pool
+                    .performance_query()
+                    .map_or(true, |performance_query| {
+                        performance_query.queue_family_index != self.queue_family_index
+                    })
+                {
+                    return Err(QueryError::NotSupportedByQueueFamily);
+                }
+
+                // VUID-vkCmdBeginQuery-queryType-00800
+                if flags.intersects(QueryControlFlags::PRECISE) {
+                    return Err(QueryError::InvalidFlags);
+                }
+
+                // VUID-vkCmdBeginQuery-queryPool-03223
+                // VUID-vkCmdBeginQuery-queryPool-03224
+                // VUID-vkCmdBeginQuery-queryPool-03225
+                // VUID-vkCmdBeginQuery-queryPool-03226
+                // Not checked, therefore unsafe.
+            }
         }
 
         // VUID-vkCmdBeginQuery-queryPool-01922
@@ -505,10 +599,13 @@ where
 
     /// Copies the results of a range of queries to a buffer on the GPU.
     ///
-    /// [`query_pool.ty().result_len()`] elements will be written for each query in the range, plus
+    /// [`query_pool.result_len()`] elements will be written for each query in the range, plus
     /// 1 extra element per query if [`QueryResultFlags::WITH_AVAILABILITY`] is enabled.
     /// The provided buffer must be large enough to hold the data.
     ///
+    /// For [`QueryType::Performance`] pools, `T` must be [`PerformanceCounterResult`], and the
+    /// [`allow_command_buffer_query_copies`] property must be `true`.
+    ///
     /// See also [`get_results`].
     ///
     /// # Safety
@@ -516,7 +613,9 @@ where
     /// - Appropriate synchronization must be provided for all buffers
     ///   that are accessed by the command.
     ///
-    /// [`query_pool.ty().result_len()`]: crate::query::QueryType::result_len
+    /// [`query_pool.result_len()`]: crate::query::QueryPool::result_len
+    /// [`PerformanceCounterResult`]: crate::query::PerformanceCounterResult
+    /// [`allow_command_buffer_query_copies`]: crate::device::Properties::allow_command_buffer_query_copies
     /// [`QueryResultFlags::WITH_AVAILABILITY`]: crate::query::QueryResultFlags::WITH_AVAILABILITY
     /// [`get_results`]: crate::query::QueriesRange::get_results
     pub unsafe fn copy_query_pool_results<T>(
@@ -529,7 +628,7 @@ where
     where
         T: QueryResultElement,
     {
-        self.validate_copy_query_pool_results(
+        self.validate_copy_query_pool_results::<T>(
             &query_pool,
             queries.clone(),
             dst_buffer.as_bytes(),
@@ -538,7 +637,7 @@ where
         )?;
 
         unsafe {
-            let per_query_len = query_pool.query_type().result_len()
+            let per_query_len = query_pool.result_len()
                 + flags.intersects(QueryResultFlags::WITH_AVAILABILITY) as DeviceSize;
             let stride = per_query_len * std::mem::size_of::<T>() as DeviceSize;
             Ok(self
@@ -546,14 +645,17 @@ where
         }
     }
 
-    fn validate_copy_query_pool_results(
+    fn validate_copy_query_pool_results<T>(
         &self,
         query_pool: &QueryPool,
         queries: Range<u32>,
         dst_buffer: &Subbuffer<[u8]>,
         element_size: DeviceSize,
         flags: QueryResultFlags,
-    ) -> Result<(), QueryError> {
+    ) -> Result<(), QueryError>
+    where
+        T: QueryResultElement,
+    {
         let queue_family_properties = self.queue_family_properties();
 
         // VUID-vkCmdCopyQueryPoolResults-commandBuffer-cmdpool
@@ -587,8 +689,13 @@ where
             .queries_range(queries.clone())
             .ok_or(QueryError::OutOfRange)?;
 
+        // VUID-vkCmdCopyQueryPoolResults-queryType-03232
+        if !T::is_valid_for(query_pool.query_type()) {
+            return Err(QueryError::InvalidResultElement);
+        }
+
         let count = queries.end - queries.start;
-        let per_query_len = query_pool.query_type().result_len()
+        let per_query_len = query_pool.result_len()
             + flags.intersects(QueryResultFlags::WITH_AVAILABILITY) as DeviceSize;
         let required_len = per_query_len * count as DeviceSize;
 
@@ -616,6 +723,23 @@ where
             return Err(QueryError::InvalidFlags);
         }
 
+        if matches!(query_pool.query_type(), QueryType::Performance) {
+            // VUID-vkCmdCopyQueryPoolResults-queryType-03232
+            if !device
+                .physical_device()
+                .properties()
+                .allow_command_buffer_query_copies
+                .unwrap_or(false)
+            {
+                return Err(QueryError::NotPermitted);
+            }
+
+            // VUID-vkCmdCopyQueryPoolResults-queryType-03233
+            if flags.intersects(QueryResultFlags::WITH_AVAILABILITY | QueryResultFlags::PARTIAL) {
+                return Err(QueryError::InvalidFlags);
+            }
+        }
+
         // TODO: sync check
 
         Ok(())
@@ -312,7 +312,13 @@ where
                         );
                     }
                 }
-                QueryType::Timestamp => (),
+                QueryType::Timestamp
+                | QueryType::AccelerationStructureCompactedSize
+                | QueryType::TransformFeedbackStream
+                | QueryType::PrimitivesGenerated
+                | QueryType::MeshPrimitivesGenerated
+                | QueryType::AccelerationStructureSerializationSize
+                | QueryType::Performance => (),
             }
         }
 
@@ -7,7 +7,7 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use super::{CommandBufferBuilder, RenderPassStateType};
+use super::{CommandBufferBuilder, RenderPassStateType, SynchronizationError};
 use crate::{
     command_buffer::allocator::CommandBufferAllocator,
     device::{DeviceOwned, QueueFlags},
@@ -16,16 +16,10 @@ use crate::{
         event::Event, AccessFlags, BufferMemoryBarrier, DependencyFlags, DependencyInfo,
         ImageMemoryBarrier, MemoryBarrier, PipelineStages, QueueFamilyOwnershipTransfer, Sharing,
     },
-    DeviceSize, RequirementNotMet, RequiresOneOf, Version, VulkanObject,
+    RequiresOneOf, Version, VulkanObject,
 };
 use smallvec::SmallVec;
-use std::{
-    cmp::max,
-    error::Error,
-    fmt::{Display, Error as FmtError, Formatter},
-    ptr,
-    sync::Arc,
-};
+use std::{cmp::max, ptr, sync::Arc};
 
 impl<L, A> CommandBufferBuilder<L, A>
 where
@@ -1425,7 +1419,9 @@ where
         dependency_flags.validate_device(device)?;
 
         // VUID-vkCmdSetEvent2-dependencyFlags-03825
-        assert!(dependency_flags.is_empty());
+        if !dependency_flags.is_empty() {
+            return Err(SynchronizationError::DependencyFlagsNotAllowed);
+        }
 
         let check_stages_access = |ty: char,
                                    barrier_index: usize,
@@ -4071,389 +4067,3 @@ where
         self
     }
 }
-
-/// Error that can happen when recording a synchronization command.
-#[derive(Clone, Debug)]
-pub enum SynchronizationError {
-    RequirementNotMet {
-        required_for: &'static str,
-        requires_one_of: RequiresOneOf,
-    },
-
-    /// One or more accesses of a buffer memory barrier are not supported by the corresponding
-    /// pipeline stages.
-    BufferMemoryBarrierAccessNotSupportedByStages { barrier_index: usize },
-
-    /// Buffer memory barriers are forbidden inside a render pass instance.
-    BufferMemoryBarrierForbiddenInsideRenderPass,
-
-    /// The end of `range` of a buffer memory barrier is greater than the size of `buffer`.
-    BufferMemoryBarrierOutOfRange {
-        barrier_index: usize,
-        range_end: DeviceSize,
-        buffer_size: DeviceSize,
-    },
-
-    /// A buffer memory barrier contains a queue family ownership transfer, but either the
-    /// `src_stages` or `dst_stages` contain [`HOST`].
-    ///
-    /// [`HOST`]: crate::sync::PipelineStages::HOST
-    BufferMemoryBarrierOwnershipTransferHostNotAllowed { barrier_index: usize },
-
-    /// The provided `src_index` or `dst_index` in the queue family ownership transfer of a
-    /// buffer memory barrier is not less than the number of queue families in the physical device.
-    BufferMemoryBarrierOwnershipTransferIndexOutOfRange {
-        barrier_index: usize,
-        provided_queue_family_index: u32,
-        queue_family_count: u32,
-    },
-
-    /// The provided `queue_family_ownership_transfer` value of a buffer memory barrier does not
-    /// match the sharing mode of `buffer`.
-    BufferMemoryBarrierOwnershipTransferSharingMismatch { barrier_index: usize },
-
-    /// One or more pipeline stages of a buffer memory barrier are not supported by the queue
-    /// family of the command buffer.
-    BufferMemoryBarrierStageNotSupported { barrier_index: usize },
-
-    /// A render pass instance is not active, and the `VIEW_LOCAL` dependency flag was provided.
-    DependencyFlagsViewLocalNotAllowed,
-
-    /// Operation forbidden inside a render pass.
-    ForbiddenInsideRenderPass,
-
-    /// Operation forbidden inside a render pass instance that was begun with `begin_rendering`.
-    ForbiddenWithBeginRendering,
-
-    /// One or more accesses of an image memory barrier are not supported by the corresponding
-    /// pipeline stages.
-    ImageMemoryBarrierAccessNotSupportedByStages { barrier_index: usize },
-
-    /// The end of the range of array layers of the subresource range of an image memory barrier
-    /// is greater than the number of array layers in the image.
-    ImageMemoryBarrierArrayLayersOutOfRange {
-        barrier_index: usize,
-        array_layers_range_end: u32,
-        image_array_layers: u32,
-    },
-
-    /// The aspects of the subresource range of an image memory barrier contain aspects that are
-    /// not present in the image, or that are not allowed.
-    ImageMemoryBarrierAspectsNotAllowed {
-        barrier_index: usize,
-        aspects: ImageAspects,
-    },
-
-    /// For the `old_layout` or `new_layout` of an image memory barrier, `image` does not have a
-    /// usage that is required.
-    ImageMemoryBarrierImageMissingUsageForLayout {
-        barrier_index: usize,
-        layout: ImageLayout,
-        requires_one_of_usage: ImageUsage,
-    },
-
-    /// An image memory barrier contains an image layout transition, but a render pass
-    /// instance is active.
-    ImageMemoryBarrierLayoutTransitionForbiddenInsideRenderPass { barrier_index: usize },
-
-    /// The end of the range of mip levels of the subresource range of an image memory barrier
-    /// is greater than the number of mip levels in the image.
-    ImageMemoryBarrierMipLevelsOutOfRange {
-        barrier_index: usize,
-        mip_levels_range_end: u32,
-        image_mip_levels: u32,
-    },
-
-    /// The `new_layout` of an image memory barrier is `Undefined` or `Preinitialized`.
-    ImageMemoryBarrierNewLayoutInvalid { barrier_index: usize },
-
-    /// A render pass instance is active, and the image of an image memory barrier is not a color
-    /// or depth/stencil attachment of the current subpass.
-    ImageMemoryBarrierNotColorDepthStencilAttachment { barrier_index: usize },
-
-    /// A render pass instance is active, and the image of an image memory barrier is not an input
-    /// attachment of the current subpass.
-    ImageMemoryBarrierNotInputAttachment { barrier_index: usize },
-
-    /// The `src_stages` of an image memory barrier contains [`HOST`], but `old_layout` is not
-    /// `Preinitialized`, `Undefined` or `General`.
-    ///
-    /// [`HOST`]: crate::sync::PipelineStages::HOST
-    ImageMemoryBarrierOldLayoutFromHostInvalid {
-        barrier_index: usize,
-        old_layout: ImageLayout,
-    },
-
-    /// An image memory barrier contains a queue family ownership transfer, but a render pass
-    /// instance is active.
-    ImageMemoryBarrierOwnershipTransferForbiddenInsideRenderPass { barrier_index: usize },
-
-    /// An image memory barrier contains a queue family ownership transfer, but either the
-    /// `src_stages` or `dst_stages` contain [`HOST`].
-    ///
-    /// [`HOST`]: crate::sync::PipelineStages::HOST
-    ImageMemoryBarrierOwnershipTransferHostForbidden { barrier_index: usize },
-
-    /// The provided `src_index` or `dst_index` in the queue family ownership transfer of an
-    /// image memory barrier is not less than the number of queue families in the physical device.
-    ImageMemoryBarrierOwnershipTransferIndexOutOfRange {
-        barrier_index: usize,
-        provided_queue_family_index: u32,
-        queue_family_count: u32,
-    },
-
-    /// The provided `queue_family_ownership_transfer` value of an image memory barrier does not
-    /// match the sharing mode of `image`.
-    ImageMemoryBarrierOwnershipTransferSharingMismatch { barrier_index: usize },
-
-    /// One or more pipeline stages of an image memory barrier are not supported by the queue
-    /// family of the command buffer.
-    ImageMemoryBarrierStageNotSupported { barrier_index: usize },
-
-    /// One or more accesses of a memory barrier are not supported by the corresponding
-    /// pipeline stages.
-    MemoryBarrierAccessNotSupportedByStages { barrier_index: usize },
-
-    /// A render pass instance is active, but the render pass does not have a subpass
-    /// self-dependency for the current subpass that is a superset of the barriers.
-    MemoryBarrierNoMatchingSubpassSelfDependency,
-
-    /// One or more pipeline stages of a memory barrier are not supported by the queue
-    /// family of the command buffer.
-    MemoryBarrierStageNotSupported { barrier_index: usize },
-
-    /// The queue family doesn't allow this operation.
-    NotSupportedByQueueFamily,
-}
-
-impl Error for SynchronizationError {}
-
-impl Display for SynchronizationError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        match self {
-            Self::RequirementNotMet {
-                required_for,
-                requires_one_of,
-            } => write!(
-                f,
-                "a requirement was not met for: {}; requires one of: {}",
-                required_for, requires_one_of,
-            ),
-
-            Self::BufferMemoryBarrierAccessNotSupportedByStages { barrier_index } => write!(
-                f,
-                "one or more accesses of buffer memory barrier {} are not supported by the \
-                corresponding pipeline stages",
-                barrier_index,
-            ),
-            Self::BufferMemoryBarrierForbiddenInsideRenderPass => write!(
-                f,
-                "buffer memory barriers are forbidden inside a render pass instance",
-            ),
-            Self::BufferMemoryBarrierOutOfRange {
-                barrier_index,
-                range_end,
-                buffer_size,
-            } => write!(
-                f,
-                "the end of `range` ({}) of buffer memory barrier {} is greater than the size of \
-                `buffer` ({})",
-                range_end, barrier_index, buffer_size,
-            ),
-            Self::BufferMemoryBarrierOwnershipTransferHostNotAllowed { barrier_index } => write!(
-                f,
-                "buffer memory barrier {} contains a queue family ownership transfer, but either \
-                the `src_stages` or `dst_stages` contain `HOST`",
-                barrier_index,
-            ),
-            Self::BufferMemoryBarrierOwnershipTransferIndexOutOfRange {
-                barrier_index,
-                provided_queue_family_index,
-                queue_family_count,
-            } => write!(
-                f,
-                "the provided `src_index` or `dst_index` ({}) in the queue family ownership \
-                transfer of buffer memory barrier {} is not less than the number of queue \
-                families in the physical device ({})",
-                provided_queue_family_index, barrier_index, queue_family_count,
-            ),
-            Self::BufferMemoryBarrierOwnershipTransferSharingMismatch { barrier_index } => write!(
-                f,
-                "the provided `queue_family_ownership_transfer` value of buffer memory barrier {} \
-                does not match the sharing mode of `buffer`",
-                barrier_index,
-            ),
-            Self::BufferMemoryBarrierStageNotSupported { barrier_index } => write!(
-                f,
-                "one or more pipeline stages of buffer memory barrier {} are not supported by the \
-                queue family of the command buffer",
-                barrier_index,
-            ),
-            Self::DependencyFlagsViewLocalNotAllowed => write!(
-                f,
-                "a render pass instance is not active, and the `VIEW_LOCAL` dependency flag was \
-                provided",
-            ),
-            Self::ForbiddenInsideRenderPass => {
-                write!(f, "operation forbidden inside a render pass")
-            }
-            Self::ForbiddenWithBeginRendering => write!(
-                f,
-                "operation forbidden inside a render pass instance that was begun with \
-                `begin_rendering`",
-            ),
-            Self::ImageMemoryBarrierAccessNotSupportedByStages { barrier_index } => write!(
-                f,
-                "one or more accesses of image memory barrier {} are not supported by the \
-                corresponding pipeline stages",
-                barrier_index,
-            ),
-            Self::ImageMemoryBarrierArrayLayersOutOfRange {
-                barrier_index,
-                array_layers_range_end,
-                image_array_layers,
-            } => write!(
-                f,
-                "the end of the range of array layers ({}) of the subresource range of image \
-                memory barrier {} is greater than the number of array layers in the image ({})",
-                array_layers_range_end, barrier_index, image_array_layers,
-            ),
-            Self::ImageMemoryBarrierAspectsNotAllowed {
-                barrier_index,
-                aspects,
-            } => write!(
-                f,
-                "the aspects of the subresource range of image memory barrier {} contain aspects \
-                that are not present in the image, or that are not allowed ({:?})",
-                barrier_index, aspects,
-            ),
-            Self::ImageMemoryBarrierImageMissingUsageForLayout {
-                barrier_index,
-                layout,
-                requires_one_of_usage,
-            } => write!(
-                f,
-                "for the `old_layout` or `new_layout` ({:?}) of image memory barrier {}, `image` \
-                does not have a usage that is required ({}{:?})",
-                layout,
-                barrier_index,
-                if requires_one_of_usage.count() > 1 {
-                    "one of "
-                } else {
-                    ""
-                },
-                requires_one_of_usage,
-            ),
-            Self::ImageMemoryBarrierLayoutTransitionForbiddenInsideRenderPass { barrier_index } => {
-                write!(
-                    f,
-                    "image memory barrier {} contains an image layout transition, but a render \
-                    pass instance is active",
-                    barrier_index,
-                )
-            }
-            Self::ImageMemoryBarrierMipLevelsOutOfRange {
-                barrier_index,
-                mip_levels_range_end,
-                image_mip_levels,
-            } => write!(
-                f,
-                "the end of the range of mip levels ({}) of the subresource range of image \
-                memory barrier {} is greater than the number of mip levels in the image ({})",
-                mip_levels_range_end, barrier_index, image_mip_levels,
-            ),
-            Self::ImageMemoryBarrierNewLayoutInvalid { barrier_index } => write!(
-                f,
-                "the `new_layout` of image memory barrier {} is `Undefined` or `Preinitialized`",
-                barrier_index,
-            ),
-            Self::ImageMemoryBarrierNotColorDepthStencilAttachment { barrier_index } => write!(
-                f,
-                "a render pass instance is active, and the image of image memory barrier {} is \
-                not a color or depth/stencil attachment of the current subpass",
-                barrier_index,
-            ),
-            Self::ImageMemoryBarrierNotInputAttachment { barrier_index } => write!(
-                f,
-                "a render pass instance is active, and the image of image memory barrier {} is \
-                not an input attachment of the current subpass",
-                barrier_index,
-            ),
-            Self::ImageMemoryBarrierOldLayoutFromHostInvalid {
-                barrier_index,
-                old_layout,
-            } => write!(
-                f,
-                "the `src_stages` of image memory barrier {} contains `HOST`, but `old_layout`
-                ({:?}) is not `Preinitialized`, `Undefined` or `General`",
-                barrier_index, old_layout,
-            ),
-            Self::ImageMemoryBarrierOwnershipTransferForbiddenInsideRenderPass {
-                barrier_index,
-            } => write!(
-                f,
-                "image memory barrier {} contains a queue family ownership transfer, but a render \
-                pass instance is active",
-                barrier_index,
-            ),
-            Self::ImageMemoryBarrierOwnershipTransferHostForbidden { barrier_index } => write!(
-                f,
-                "image memory barrier {} contains a queue family ownership transfer, but either \
-                the `src_stages` or `dst_stages` contain `HOST`",
-                barrier_index,
-            ),
-            Self::ImageMemoryBarrierOwnershipTransferIndexOutOfRange {
-                barrier_index,
-                provided_queue_family_index,
-                queue_family_count,
-            } => write!(
-                f,
-                "the provided `src_index` or `dst_index` ({}) in the queue family ownership \
-                transfer of image memory barrier {} is not less than the number of queue
-                families in the physical device ({})",
-                provided_queue_family_index, barrier_index, queue_family_count,
-            ),
-            Self::ImageMemoryBarrierOwnershipTransferSharingMismatch { barrier_index } => write!(
-                f,
-                "the provided `queue_family_ownership_transfer` value of image memory barrier {} \
-                does not match the sharing mode of `image`",
-                barrier_index,
-            ),
-            Self::ImageMemoryBarrierStageNotSupported { barrier_index } => write!(
-                f,
-                "one or more pipeline stages of image memory barrier {} are not supported by the \
-                queue family of the command buffer",
-                barrier_index,
-            ),
-            Self::MemoryBarrierAccessNotSupportedByStages { barrier_index } => write!(
-                f,
-                "one or more accesses of memory barrier {} are not supported by the \
-                corresponding pipeline stages",
-                barrier_index,
-            ),
-            Self::MemoryBarrierNoMatchingSubpassSelfDependency => write!(
-                f,
-                "a render pass instance is active, but the render pass does not have a subpass \
-                self-dependency for the current subpass that is a superset of the barriers",
-            ),
-            Self::MemoryBarrierStageNotSupported { barrier_index } => write!(
-                f,
-                "one or more pipeline stages of memory barrier {} are not supported by the \
-                queue family of the command buffer",
-                barrier_index,
-            ),
-            Self::NotSupportedByQueueFamily => {
-                write!(f, "the queue family doesn't allow this operation")
-            }
-        }
-    }
-}
-
-impl From<RequirementNotMet> for SynchronizationError {
-    fn from(err: RequirementNotMet) -> Self {
-        Self::RequirementNotMet {
-            required_for: err.required_for,
-            requires_one_of: err.requires_one_of,
-        }
-    }
-}
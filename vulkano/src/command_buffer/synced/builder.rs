@@ -19,13 +19,15 @@ use crate::{
     command_buffer::{
         pool::CommandPoolAlloc,
         sys::{CommandBufferBeginInfo, UnsafeCommandBufferBuilder},
-        CommandBufferBufferRangeUsage, CommandBufferBufferUsage, CommandBufferExecError,
-        CommandBufferImageRangeUsage, CommandBufferImageUsage, CommandBufferLevel,
-        CommandBufferResourcesUsage, ResourceUseRef, SecondaryCommandBufferBufferUsage,
+        BarrierReason, BarrierReportEntry, BarrierResource, CommandBufferBufferRangeUsage,
+        CommandBufferBufferUsage, CommandBufferExecError, CommandBufferImageRangeUsage,
+        CommandBufferImageUsage, CommandBufferLevel, CommandBufferResourcesUsage,
+        CommandBufferUsage, ResourceInCommand, ResourceUseRef, SecondaryCommandBufferBufferUsage,
         SecondaryCommandBufferImageUsage, SecondaryCommandBufferResourcesUsage,
+        SynchronizationError,
     },
     descriptor_set::{DescriptorSetResources, DescriptorSetWithOffsets},
-    device::{Device, DeviceOwned},
+    device::{Device, DeviceOwned, QueueFlags},
     image::{sys::Image, ImageAccess, ImageAspects, ImageLayout, ImageSubresourceRange},
     pipeline::{
         graphics::{
@@ -36,18 +38,20 @@ use crate::{
             viewport::{Scissor, Viewport},
         },
         ComputePipeline, DynamicState, GraphicsPipeline, PipelineBindPoint, PipelineLayout,
+        RayTracingPipeline,
     },
     range_map::RangeMap,
     range_set::RangeSet,
     sync::{
-        AccessFlags, BufferMemoryBarrier, DependencyInfo, ImageMemoryBarrier, PipelineMemoryAccess,
-        PipelineStages,
+        event::Event, AccessFlags, BufferMemoryBarrier, DependencyInfo, ImageMemoryBarrier,
+        MemoryBarrier, PipelineMemoryAccess, PipelineStages,
     },
     DeviceSize, OomError, VulkanObject,
 };
 use ahash::HashMap;
 use smallvec::SmallVec;
 use std::{
+    cmp,
     collections::hash_map::Entry,
     error::Error,
     fmt::{Debug, Display, Error as FmtError, Formatter},
@@ -55,6 +59,12 @@ use std::{
     sync::Arc,
 };
 
+// The minimum number of commands between the last use of a resource and the command that needs a
+// barrier for it, for the barrier to be split into setting an event and waiting on it. With fewer
+// commands in between, there is too little work to overlap with the barrier for the event to be
+// worth it.
+const SPLIT_BARRIER_MIN_COMMANDS: usize = 4;
+
 /// Wrapper around `UnsafeCommandBufferBuilder` that handles synchronization for you.
 ///
 /// Each method of the `UnsafeCommandBufferBuilder` has an equivalent in this wrapper, except
@@ -79,10 +89,28 @@ pub struct SyncCommandBufferBuilder {
     // in `commands`.
     pending_barrier: DependencyInfo,
 
+    // Barrier that is recorded after the final layout transitions, when building the command
+    // buffer. Used to release ownership of resources to another queue family. The resources must
+    // be kept alive by the commands that use them.
+    final_barrier: DependencyInfo,
+
     // Locations within commands that pipeline barriers were inserted. For debugging purposes.
     // TODO: present only in cfg(debug_assertions)?
     barriers: Vec<usize>,
 
+    // Barriers that are split into setting an event after the last use of a resource, and waiting
+    // on it before the command that needs the barrier, so that the commands in between can run
+    // while the barrier is pending. Ordered by the index of the command that waits.
+    split_barriers: Vec<SplitBarrier>,
+
+    // The events of the split barriers that were sent to the inner builder. They must be kept
+    // alive as long as the command buffer.
+    events: Vec<Event>,
+
+    // Events that were set with `set_event` and not waited on yet, with the dependency that they
+    // were set with.
+    set_events: Vec<(Arc<Event>, DependencyInfo)>,
+
     // Only the commands before `first_unflushed` have already been sent to the inner
     // `UnsafeCommandBufferBuilder`.
     first_unflushed: usize,
@@ -91,6 +119,15 @@ pub struct SyncCommandBufferBuilder {
     // command.
     pub(in crate::command_buffer) latest_render_pass_enter: Option<usize>,
 
+    // The index of the command after the end of the last render pass. Barriers can't be moved
+    // before it, as they could end up inside the render pass.
+    pub(in crate::command_buffer) latest_render_pass_exit: usize,
+
+    // If the barrier report is enabled, the barriers that were inserted so far, and those that
+    // are in `pending_barrier`, whose position is only known once they are flushed.
+    barrier_report: Option<Vec<BarrierReportEntry>>,
+    pending_report: Vec<BarrierReportEntry>,
+
     // Stores the current state of buffers and images that are in use by the command buffer.
     buffers2: HashMap<Arc<Buffer>, RangeMap<DeviceSize, BufferState>>,
     images2: HashMap<Arc<Image>, RangeMap<DeviceSize, ImageState>>,
@@ -153,9 +190,16 @@ impl SyncCommandBufferBuilder {
             level,
             commands: Vec::new(),
             pending_barrier: DependencyInfo::default(),
+            final_barrier: DependencyInfo::default(),
             barriers: Vec::new(),
+            split_barriers: Vec::new(),
+            events: Vec::new(),
+            set_events: Vec::new(),
             first_unflushed: 0,
             latest_render_pass_enter,
+            latest_render_pass_exit: 0,
+            barrier_report: None,
+            pending_report: Vec::new(),
             buffers2: HashMap::default(),
             images2: HashMap::default(),
             secondary_resources_usage: Default::default(),
@@ -180,6 +224,530 @@ impl SyncCommandBufferBuilder {
         self.current_state = Default::default();
     }
 
+    /// Starts listing every barrier that the builder inserts from now on, along with why it was
+    /// inserted. The list can be retrieved from the built command buffer.
+    #[inline]
+    pub fn enable_barrier_report(&mut self) {
+        self.barrier_report.get_or_insert_with(Vec::new);
+    }
+
+    /// Records `dependency_info` right away, before the barriers that are needed by any commands
+    /// that are added afterwards. This is used to acquire ownership of resources from another
+    /// queue family, or to make resources use memory that other resources used before, which
+    /// must happen before anything else touches them. `reason` is what the barrier report lists
+    /// as the reason for the barriers.
+    ///
+    /// The resources of the barriers are tracked from then on as if they were written by the
+    /// barrier, within its destination scope, and images are in the `new_layout` of their
+    /// barrier. Images that weren't used by the builder before are expected to be in the
+    /// `old_layout` of their barrier when the command buffer starts, and are transitioned back to
+    /// the `new_layout` at the end.
+    ///
+    /// # Safety
+    ///
+    /// - The builder must not be inside a render pass.
+    /// - The resources of the barriers must not have been used by earlier commands.
+    pub(in crate::command_buffer) unsafe fn pipeline_barrier_immediate(
+        &mut self,
+        dependency_info: DependencyInfo,
+        reason: BarrierReason,
+    ) {
+        debug_assert!(!self.is_any_barrier_resource_used(&dependency_info));
+
+        let command_index = self.commands.len();
+
+        if self.barrier_report.is_some() {
+            self.report_dependency_info(&dependency_info, reason, command_index);
+        }
+
+        let use_ref = |resource_in_command| ResourceUseRef {
+            command_index,
+            command_name: "pipeline_barrier",
+            resource_in_command,
+            secondary_use_ref: None,
+        };
+
+        for (index, barrier) in dependency_info.buffer_memory_barriers.iter().enumerate() {
+            let range_map = self
+                .buffers2
+                .entry(barrier.buffer.clone())
+                .or_insert_with(|| {
+                    [(
+                        0..barrier.buffer.size(),
+                        BufferState {
+                            resource_uses: Vec::new(),
+                            memory: PipelineMemoryAccess::default(),
+                            exclusive_any: false,
+                            awaiting_event: false,
+                            event_scope: None,
+                        },
+                    )]
+                    .into_iter()
+                    .collect()
+                });
+            range_map.split_at(&barrier.range.start);
+            range_map.split_at(&barrier.range.end);
+
+            for (_range, state) in range_map.range_mut(&barrier.range) {
+                state
+                    .resource_uses
+                    .push(use_ref(ResourceInCommand::BufferMemoryBarrier {
+                        index: index as u32,
+                    }));
+                state.memory = PipelineMemoryAccess {
+                    stages: barrier.dst_stages,
+                    access: barrier.dst_access,
+                    exclusive: true,
+                };
+                state.exclusive_any = true;
+                state.event_scope = Some((barrier.dst_stages, barrier.dst_access));
+            }
+        }
+
+        for (index, barrier) in dependency_info.image_memory_barriers.iter().enumerate() {
+            let range_map = self
+                .images2
+                .entry(barrier.image.clone())
+                .or_insert_with(|| {
+                    [(
+                        0..barrier.image.range_size(),
+                        ImageState {
+                            resource_uses: Vec::new(),
+                            memory: PipelineMemoryAccess::default(),
+                            exclusive_any: false,
+                            awaiting_event: false,
+                            event_scope: None,
+                            initial_layout: barrier.new_layout,
+                            current_layout: barrier.new_layout,
+                            final_layout: barrier.new_layout,
+                        },
+                    )]
+                    .into_iter()
+                    .collect()
+                });
+
+            for range in barrier.image.iter_ranges(barrier.subresource_range.clone()) {
+                range_map.split_at(&range.start);
+                range_map.split_at(&range.end);
+
+                for (_range, state) in range_map.range_mut(&range) {
+                    state
+                        .resource_uses
+                        .push(use_ref(ResourceInCommand::ImageMemoryBarrier {
+                            index: index as u32,
+                        }));
+                    state.memory = PipelineMemoryAccess {
+                        stages: barrier.dst_stages,
+                        access: barrier.dst_access,
+                        exclusive: true,
+                    };
+                    state.exclusive_any = true;
+                    state.event_scope = Some((barrier.dst_stages, barrier.dst_access));
+                    state.initial_layout = barrier.old_layout;
+                    state.current_layout = barrier.new_layout;
+                }
+            }
+        }
+
+        // Like the barrier before the first use of a resource, synchronize with the command
+        // buffers that were previously submitted to the same queue, which the source scope of an
+        // acquire of ownership doesn't.
+        if self.level == CommandBufferLevel::Primary {
+            self.pending_barrier.memory_barriers.push(MemoryBarrier {
+                src_stages: PipelineStages::ALL_COMMANDS,
+                src_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                dst_stages: PipelineStages::ALL_COMMANDS,
+                dst_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                ..Default::default()
+            });
+        }
+
+        self.record_barrier_immediate(dependency_info);
+    }
+
+    /// Returns whether any of the resources of the barriers of `dependency_info` were used by
+    /// earlier commands.
+    pub(in crate::command_buffer) fn is_any_barrier_resource_used(
+        &self,
+        dependency_info: &DependencyInfo,
+    ) -> bool {
+        let buffer_used = dependency_info
+            .buffer_memory_barriers
+            .iter()
+            .any(|barrier| {
+                let range_map = match self.buffers2.get(&barrier.buffer) {
+                    Some(x) => x,
+                    None => return false,
+                };
+
+                range_map
+                    .range(&barrier.range)
+                    .any(|(_range, state)| !state.resource_uses.is_empty())
+            });
+        let image_used = dependency_info.image_memory_barriers.iter().any(|barrier| {
+            let range_map = match self.images2.get(&barrier.image) {
+                Some(x) => x,
+                None => return false,
+            };
+
+            barrier
+                .image
+                .iter_ranges(barrier.subresource_range.clone())
+                .any(|range| {
+                    range_map
+                        .range(&range)
+                        .any(|(_range, state)| !state.resource_uses.is_empty())
+                })
+        });
+
+        buffer_used || image_used
+    }
+
+    unsafe fn record_barrier_immediate(&mut self, dependency_info: DependencyInfo) {
+        struct Cmd {
+            dependency_info: DependencyInfo,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "pipeline_barrier"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.pipeline_barrier(&self.dependency_info);
+            }
+        }
+
+        debug_assert!(self.latest_render_pass_enter.is_none());
+
+        self.commands.push(Box::new(Cmd { dependency_info }));
+
+        // Flush everything, including the new command, so that the barriers of the commands that
+        // come after are recorded after it.
+        self.flush(self.commands.len());
+    }
+
+    /// Transitions the ranges of `image` that were used by earlier commands to their final layout
+    /// right away, instead of at the end of the command buffer. This is used when the memory of
+    /// the image is about to be reused by another resource, after which the image can't be
+    /// touched anymore.
+    ///
+    /// # Safety
+    ///
+    /// - The builder must be for a primary command buffer, and must not be inside a render pass.
+    /// - `image` must not be used by any commands that are added afterwards.
+    pub(in crate::command_buffer) unsafe fn transition_to_final_layout_immediate(
+        &mut self,
+        image: &Arc<Image>,
+    ) {
+        debug_assert!(self.level == CommandBufferLevel::Primary);
+
+        let range_map = match self.images2.get_mut(image) {
+            Some(x) => x,
+            None => return,
+        };
+
+        let mut report: SmallVec<[_; 8]> = SmallVec::new();
+        let image_memory_barriers: SmallVec<[_; 8]> = range_map
+            .iter_mut()
+            .filter(|(_range, state)| {
+                !state.resource_uses.is_empty() && state.final_layout != state.current_layout
+            })
+            .map(|(range, state)| {
+                let barrier = ImageMemoryBarrier {
+                    src_stages: state.memory.stages,
+                    src_access: state.memory.access,
+                    dst_stages: PipelineStages::ALL_COMMANDS,
+                    dst_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                    old_layout: state.current_layout,
+                    new_layout: state.final_layout,
+                    subresource_range: image.range_to_subresources(range.clone()),
+                    ..ImageMemoryBarrier::image(image.clone())
+                };
+
+                report.push(state.resource_uses.last().copied());
+                state.current_layout = state.final_layout;
+                state.exclusive_any = true;
+
+                barrier
+            })
+            .collect();
+
+        if !image_memory_barriers.is_empty() {
+            if let Some(barrier_report) = &mut self.barrier_report {
+                let position = self.commands.len();
+                barrier_report.extend(image_memory_barriers.iter().zip(report).map(
+                    |(barrier, previous_use_ref)| BarrierReportEntry {
+                        previous_use_ref,
+                        ..image_report_entry(barrier, BarrierReason::FinalLayout, None, position)
+                    },
+                ));
+            }
+
+            self.record_barrier_immediate(DependencyInfo {
+                image_memory_barriers,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Adds barriers to be recorded at the very end of the command buffer, after images have
+    /// been transitioned to their final layout. This is used to release ownership of resources
+    /// to another queue family.
+    ///
+    /// The `old_layout` and `new_layout` of the image barriers are replaced with the layout that
+    /// the builder leaves the image in, and the barriers are split where that layout differs
+    /// between subresources. Returns the barriers as they will be recorded, so that the matching
+    /// acquire barriers can be made from them.
+    ///
+    /// # Safety
+    ///
+    /// - The builder must be for a primary command buffer.
+    /// - The resources of the barriers must be used by other commands of the command buffer.
+    pub(in crate::command_buffer) unsafe fn pipeline_barrier_at_end(
+        &mut self,
+        dependency_info: DependencyInfo,
+    ) -> DependencyInfo {
+        debug_assert!(self.level == CommandBufferLevel::Primary);
+
+        let DependencyInfo {
+            dependency_flags,
+            memory_barriers,
+            buffer_memory_barriers,
+            image_memory_barriers,
+            _ne: _,
+        } = dependency_info;
+
+        let mut final_image_memory_barriers: SmallVec<[_; 8]> = SmallVec::new();
+
+        for barrier in image_memory_barriers {
+            let range_map = match self.images2.get(&barrier.image) {
+                Some(x) => x,
+                None => {
+                    final_image_memory_barriers.push(barrier);
+                    continue;
+                }
+            };
+
+            // The final layout of a range never changes after its state is created, so the
+            // layout is already known here.
+            for range in barrier.image.iter_ranges(barrier.subresource_range.clone()) {
+                for (state_range, state) in range_map.range(&range) {
+                    let range = cmp::max(state_range.start, range.start)
+                        ..cmp::min(state_range.end, range.end);
+
+                    final_image_memory_barriers.push(ImageMemoryBarrier {
+                        old_layout: state.final_layout,
+                        new_layout: state.final_layout,
+                        subresource_range: barrier.image.range_to_subresources(range),
+                        ..barrier.clone()
+                    });
+                }
+            }
+        }
+
+        let dependency_info = DependencyInfo {
+            dependency_flags,
+            memory_barriers,
+            buffer_memory_barriers,
+            image_memory_barriers: final_image_memory_barriers,
+            ..Default::default()
+        };
+
+        self.final_barrier.dependency_flags |= dependency_info.dependency_flags;
+        self.final_barrier
+            .memory_barriers
+            .extend(dependency_info.memory_barriers.iter().cloned());
+        self.final_barrier
+            .buffer_memory_barriers
+            .extend(dependency_info.buffer_memory_barriers.iter().cloned());
+        self.final_barrier
+            .image_memory_barriers
+            .extend(dependency_info.image_memory_barriers.iter().cloned());
+
+        dependency_info
+    }
+
+    // Adds the buffer and image barriers of `dependency_info` to the barrier report.
+    fn report_dependency_info(
+        &mut self,
+        dependency_info: &DependencyInfo,
+        reason: BarrierReason,
+        position: usize,
+    ) {
+        if let Some(barrier_report) = &mut self.barrier_report {
+            barrier_report.extend(
+                (dependency_info.buffer_memory_barriers.iter())
+                    .map(|barrier| buffer_report_entry(barrier, reason, None, position))
+                    .chain(
+                        (dependency_info.image_memory_barriers.iter())
+                            .map(|barrier| image_report_entry(barrier, reason, None, position)),
+                    ),
+            );
+        }
+    }
+
+    // Records the pending barrier, followed by the commands up to `end`. The split barriers are
+    // set and waited on as their commands are reached.
+    unsafe fn flush(&mut self, end: usize) {
+        merge_barriers(&mut self.pending_barrier);
+        self.inner.pipeline_barrier(&self.pending_barrier);
+        self.pending_barrier.clear();
+        self.barriers.push(self.first_unflushed); // Track inserted barriers
+
+        if let Some(barrier_report) = &mut self.barrier_report {
+            barrier_report.extend(
+                self.pending_report
+                    .drain(..)
+                    .map(|entry| BarrierReportEntry {
+                        position: self.first_unflushed,
+                        ..entry
+                    }),
+            );
+        }
+
+        for command_index in self.first_unflushed..end {
+            for split_barrier in self
+                .split_barriers
+                .iter_mut()
+                .filter(|split_barrier| split_barrier.set_position == command_index)
+            {
+                merge_barriers(&mut split_barrier.dependency_info);
+                self.inner
+                    .set_event(&split_barrier.event, &split_barrier.dependency_info);
+            }
+
+            let waits = self
+                .split_barriers
+                .iter()
+                .take_while(|split_barrier| split_barrier.wait_position == command_index)
+                .count();
+
+            if waits != 0 {
+                self.inner.wait_events(
+                    self.split_barriers[..waits].iter().map(|split_barrier| {
+                        (&split_barrier.event, &split_barrier.dependency_info)
+                    }),
+                );
+                self.events.extend(
+                    self.split_barriers
+                        .drain(..waits)
+                        .map(|split_barrier| split_barrier.event),
+                );
+            }
+
+            self.commands[command_index].send(&mut self.inner);
+        }
+
+        self.first_unflushed = end;
+    }
+
+    // Inserts a barrier that is needed before the current command, after the commands that
+    // previously used the resource, as early as possible.
+    unsafe fn insert_barrier(&mut self, request: BarrierRequest) {
+        let BarrierRequest {
+            barrier,
+            reason,
+            use_ref,
+            previous_use_ref,
+        } = request;
+
+        let report_entry = self.barrier_report.is_some().then(|| {
+            let entry = match &barrier {
+                Barrier::Buffer(barrier) => buffer_report_entry(barrier, reason, Some(use_ref), 0),
+                Barrier::Image(barrier) => image_report_entry(barrier, reason, Some(use_ref), 0),
+            };
+
+            BarrierReportEntry {
+                previous_use_ref,
+                ..entry
+            }
+        });
+
+        let previous_use_ref = match previous_use_ref {
+            Some(x) => x,
+            None => {
+                // This is the first use, the barrier only needs to come before the current
+                // command, so it can be batched with the other pending barriers.
+                barrier.push_to(&mut self.pending_barrier);
+                self.pending_report.extend(report_entry);
+
+                return;
+            }
+        };
+
+        // Barriers work differently in render passes, so if we're in one, we can only insert a
+        // barrier before the start of the render pass.
+        let current_index = self.commands.len() - 1;
+        let last_allowed_barrier_index = self.latest_render_pass_enter.unwrap_or(current_index);
+
+        // The earliest point where the barrier can go, right after the previous use.
+        let earliest_barrier_index = (previous_use_ref.command_index + 1)
+            .max(self.first_unflushed)
+            .max(self.latest_render_pass_exit);
+
+        if self.latest_render_pass_enter.is_none()
+            && self.inner.usage() == CommandBufferUsage::OneTimeSubmit
+            && current_index.saturating_sub(earliest_barrier_index) >= SPLIT_BARRIER_MIN_COMMANDS
+        {
+            let split_barrier_index = match self.split_barriers.iter().position(|split_barrier| {
+                split_barrier.set_position == earliest_barrier_index
+                    && split_barrier.wait_position == current_index
+            }) {
+                Some(index) => Some(index),
+                None => {
+                    let device = self.inner.device.clone();
+                    let events_supported = !device.enabled_extensions().khr_portability_subset
+                        || device.enabled_features().events;
+
+                    // If the event can't be created, fall back to a regular barrier.
+                    events_supported
+                        .then(|| Event::from_pool(device).ok())
+                        .flatten()
+                        .map(|event| {
+                            self.split_barriers.push(SplitBarrier {
+                                set_position: earliest_barrier_index,
+                                wait_position: current_index,
+                                event,
+                                dependency_info: DependencyInfo::default(),
+                            });
+
+                            self.split_barriers.len() - 1
+                        })
+                }
+            };
+
+            if let Some(index) = split_barrier_index {
+                barrier.push_to(&mut self.split_barriers[index].dependency_info);
+
+                if let (Some(barrier_report), Some(entry)) =
+                    (&mut self.barrier_report, report_entry)
+                {
+                    barrier_report.push(BarrierReportEntry {
+                        position: current_index,
+                        split_position: Some(earliest_barrier_index),
+                        ..entry
+                    });
+                }
+
+                return;
+            }
+        }
+
+        // The pending barrier is going to be recorded before the unflushed commands, so if the
+        // previous use hasn't been flushed yet, the commands up to it must be flushed first.
+        // Barriers within the same pipeline barrier aren't ordered either, so the same goes if
+        // the pending barrier already transitions some of the same subresources.
+        if previous_use_ref.command_index >= self.first_unflushed
+            || barrier.overlaps_image_barriers(&self.pending_barrier)
+        {
+            self.flush(earliest_barrier_index.min(last_allowed_barrier_index));
+        }
+
+        barrier.push_to(&mut self.pending_barrier);
+        self.pending_report.extend(report_entry);
+    }
+
     pub(in crate::command_buffer) fn check_resource_conflicts(
         &self,
         resource: &(ResourceUseRef, Resource),
@@ -258,6 +826,11 @@ impl SyncCommandBufferBuilder {
                 .iter()
                 .all(|resource_use| resource_use.command_index <= self.commands.len()));
 
+            // The resource can't be used until the event that it's waiting on is waited on.
+            if state.awaiting_event {
+                return state.resource_uses.last().copied();
+            }
+
             if memory.exclusive || state.memory.exclusive {
                 // If there is a resource use at a position beyond where we can insert a
                 // barrier, then there is an unsolvable conflict.
@@ -305,6 +878,11 @@ impl SyncCommandBufferBuilder {
                     .iter()
                     .all(|resource_use| resource_use.command_index <= self.commands.len()));
 
+                // The image can't be used until the event that it's waiting on is waited on.
+                if state.awaiting_event {
+                    return state.resource_uses.last().copied();
+                }
+
                 // If the command expects the image to be undefined, then we can't
                 // transition it, so use the current layout for both old and new layout.
                 let start_layout = if start_layout == ImageLayout::Undefined {
@@ -388,12 +966,6 @@ impl SyncCommandBufferBuilder {
                 memory,
             });
 
-        // Barriers work differently in render passes, so if we're in one, we can only insert a
-        // barrier before the start of the render pass.
-        let last_allowed_barrier_index = self
-            .latest_render_pass_enter
-            .unwrap_or(self.commands.len() - 1);
-
         range.start += buffer.offset();
         range.end += buffer.offset();
 
@@ -407,6 +979,8 @@ impl SyncCommandBufferBuilder {
                         resource_uses: Vec::new(),
                         memory: PipelineMemoryAccess::default(),
                         exclusive_any: false,
+                        awaiting_event: false,
+                        event_scope: None,
                     },
                 )]
                 .into_iter()
@@ -415,6 +989,8 @@ impl SyncCommandBufferBuilder {
         range_map.split_at(&range.start);
         range_map.split_at(&range.end);
 
+        let mut barrier_requests: SmallVec<[BarrierRequest; 2]> = SmallVec::new();
+
         for (range, state) in range_map.range_mut(&range) {
             if state.resource_uses.is_empty() {
                 // This is the first time we use this resource range in this command buffer.
@@ -443,53 +1019,45 @@ impl SyncCommandBufferBuilder {
                             ..BufferMemoryBarrier::buffer(buffer.buffer().clone())
                         };
 
-                        self.pending_barrier.buffer_memory_barriers.push(barrier);
+                        barrier_requests.push(BarrierRequest {
+                            barrier: Barrier::Buffer(barrier),
+                            reason: BarrierReason::FirstUse,
+                            use_ref,
+                            previous_use_ref: None,
+                        });
                     }
                     CommandBufferLevel::Secondary => (),
                 }
             } else {
                 // This resource range was used before in this command buffer.
 
-                // Find out if we have a collision with the pending commands.
-                if memory.exclusive || state.memory.exclusive {
-                    // Collision found between `latest_command_id` and `collision_cmd_id`.
-
-                    // We now want to modify the current pipeline barrier in order to handle the
-                    // collision. But since the pipeline barrier is going to be submitted before
-                    // the flushed commands, it would be a mistake if `collision_cmd_id` hasn't
-                    // been flushed yet.
-                    if state
-                        .resource_uses
-                        .iter()
-                        .any(|resource_use| resource_use.command_index >= self.first_unflushed)
-                    {
-                        unsafe {
-                            // Flush the pending barrier.
-                            self.inner.pipeline_barrier(&self.pending_barrier);
-                            self.pending_barrier.clear();
-                            self.barriers.push(self.first_unflushed); // Track inserted barriers
-
-                            for command in
-                                &mut self.commands[self.first_unflushed..last_allowed_barrier_index]
-                            {
-                                command.send(&mut self.inner);
-                            }
-
-                            self.first_unflushed = last_allowed_barrier_index;
-                        }
+                // If the resource was last in a barrier of an event that was waited on, and the
+                // access is within its destination scope, the event already synchronizes it.
+                if let Some((stages, access)) = state.event_scope.take() {
+                    if scope_contains(stages, access, &memory, false) {
+                        state.memory = memory;
+                        state.exclusive_any |= memory.exclusive;
+                        state.resource_uses.push(use_ref);
+                        continue;
                     }
+                }
 
-                    // Modify the pipeline barrier to handle the collision.
-                    self.pending_barrier
-                        .buffer_memory_barriers
-                        .push(BufferMemoryBarrier {
+                // Find out if we have a collision with the pending commands.
+                if memory.exclusive || state.memory.exclusive {
+                    // Collision found between the previous uses and the current command.
+                    barrier_requests.push(BarrierRequest {
+                        barrier: Barrier::Buffer(BufferMemoryBarrier {
                             src_stages: state.memory.stages,
                             src_access: state.memory.access,
                             dst_stages: memory.stages,
                             dst_access: memory.access,
                             range: range.clone(),
                             ..BufferMemoryBarrier::buffer(buffer.buffer().clone())
-                        });
+                        }),
+                        reason: hazard_reason(&state.memory, &memory),
+                        use_ref,
+                        previous_use_ref: state.resource_uses.last().copied(),
+                    });
 
                     // Update state.
                     state.memory = memory;
@@ -503,6 +1071,10 @@ impl SyncCommandBufferBuilder {
                 state.resource_uses.push(use_ref);
             }
         }
+
+        for request in barrier_requests {
+            unsafe { self.insert_barrier(request) };
+        }
     }
 
     fn add_image(
@@ -525,12 +1097,6 @@ impl SyncCommandBufferBuilder {
                 end_layout,
             });
 
-        // Barriers work differently in render passes, so if we're in one, we can only insert a
-        // barrier before the start of the render pass.
-        let last_allowed_barrier_index = self
-            .latest_render_pass_enter
-            .unwrap_or(self.commands.len() - 1);
-
         let inner = image.inner();
         subresource_range.array_layers.start += inner.first_layer;
         subresource_range.array_layers.end += inner.first_layer;
@@ -571,6 +1137,8 @@ impl SyncCommandBufferBuilder {
                             resource_uses: Vec::new(),
                             memory: PipelineMemoryAccess::default(),
                             exclusive_any: false,
+                            awaiting_event: false,
+                            event_scope: None,
                             initial_layout,
                             current_layout: initial_layout,
                             final_layout: image.final_layout_requirement(),
@@ -583,6 +1151,8 @@ impl SyncCommandBufferBuilder {
                             resource_uses: Vec::new(),
                             memory: PipelineMemoryAccess::default(),
                             exclusive_any: false,
+                            awaiting_event: false,
+                            event_scope: None,
                             initial_layout: ImageLayout::Undefined,
                             current_layout: ImageLayout::Undefined,
                             final_layout: ImageLayout::Undefined,
@@ -594,6 +1164,8 @@ impl SyncCommandBufferBuilder {
             .collect()
         });
 
+        let mut barrier_requests: SmallVec<[BarrierRequest; 2]> = SmallVec::new();
+
         for range in inner.image.iter_ranges(subresource_range) {
             range_map.split_at(&range.start);
             range_map.split_at(&range.end);
@@ -687,7 +1259,12 @@ impl SyncCommandBufferBuilder {
                                 state.exclusive_any = true;
                             }
 
-                            self.pending_barrier.image_memory_barriers.push(barrier);
+                            barrier_requests.push(BarrierRequest {
+                                barrier: Barrier::Image(barrier),
+                                reason: BarrierReason::FirstUse,
+                                use_ref,
+                                previous_use_ref: None,
+                            });
                         }
                         CommandBufferLevel::Secondary => {
                             state.initial_layout = start_layout;
@@ -704,42 +1281,30 @@ impl SyncCommandBufferBuilder {
                         start_layout
                     };
 
+                    // If the image was last in a barrier of an event that was waited on, and the
+                    // access is within its destination scope, the event already synchronizes it.
+                    if let Some((stages, access)) = state.event_scope.take() {
+                        if state.current_layout == start_layout
+                            && scope_contains(stages, access, &memory, false)
+                        {
+                            state.memory = memory;
+                            state.exclusive_any |= memory.exclusive;
+                            if memory.exclusive || end_layout != ImageLayout::Undefined {
+                                state.current_layout = end_layout;
+                            }
+                            state.resource_uses.push(use_ref);
+                            continue;
+                        }
+                    }
+
                     // Find out if we have a collision with the pending commands.
                     if memory.exclusive
                         || state.memory.exclusive
                         || state.current_layout != start_layout
                     {
-                        // Collision found between `latest_command_id` and `collision_cmd_id`.
-
-                        // We now want to modify the current pipeline barrier in order to handle the
-                        // collision. But since the pipeline barrier is going to be submitted before
-                        // the flushed commands, it would be a mistake if `collision_cmd_id` hasn't
-                        // been flushed yet.
-                        if state
-                            .resource_uses
-                            .iter()
-                            .any(|resource_use| resource_use.command_index >= self.first_unflushed)
-                            || state.current_layout != start_layout
-                        {
-                            unsafe {
-                                // Flush the pending barrier.
-                                self.inner.pipeline_barrier(&self.pending_barrier);
-                                self.pending_barrier.clear();
-                                self.barriers.push(self.first_unflushed); // Track inserted barriers
-
-                                for command in &mut self.commands
-                                    [self.first_unflushed..last_allowed_barrier_index]
-                                {
-                                    command.send(&mut self.inner);
-                                }
-                                self.first_unflushed = last_allowed_barrier_index;
-                            }
-                        }
-
-                        // Modify the pipeline barrier to handle the collision.
-                        self.pending_barrier
-                            .image_memory_barriers
-                            .push(ImageMemoryBarrier {
+                        // Collision found between the previous uses and the current command.
+                        barrier_requests.push(BarrierRequest {
+                            barrier: Barrier::Image(ImageMemoryBarrier {
                                 src_stages: state.memory.stages,
                                 src_access: state.memory.access,
                                 dst_stages: memory.stages,
@@ -748,7 +1313,11 @@ impl SyncCommandBufferBuilder {
                                 new_layout: start_layout,
                                 subresource_range: inner.image.range_to_subresources(range.clone()),
                                 ..ImageMemoryBarrier::image(inner.image.clone())
-                            });
+                            }),
+                            reason: hazard_reason(&state.memory, &memory),
+                            use_ref,
+                            previous_use_ref: state.resource_uses.last().copied(),
+                        });
 
                         // Update state.
                         state.memory = memory;
@@ -769,24 +1338,312 @@ impl SyncCommandBufferBuilder {
                 }
             }
         }
+
+        for request in barrier_requests {
+            unsafe { self.insert_barrier(request) };
+        }
+    }
+
+    /// Returns whether any event was set with `set_event`, and not waited on yet.
+    pub(in crate::command_buffer) fn has_set_events(&self) -> bool {
+        !self.set_events.is_empty()
+    }
+
+    /// Returns whether `event` was set with `set_event`, and not waited on yet.
+    pub(in crate::command_buffer) fn is_event_set(&self, event: &Event) -> bool {
+        self.set_events
+            .iter()
+            .any(|(set_event, _)| set_event.handle() == event.handle())
+    }
+
+    /// Checks that `event` can be set with the barriers of `dependency_info`, given the state of
+    /// their resources after the commands that were added so far.
+    pub(in crate::command_buffer) fn validate_set_event_barriers(
+        &self,
+        event: &Event,
+        dependency_info: &DependencyInfo,
+    ) -> Result<(), SynchronizationError> {
+        if self.is_event_set(event) {
+            return Err(SynchronizationError::EventAlreadySet);
+        }
+
+        for (barrier_index, barrier) in dependency_info.image_memory_barriers.iter().enumerate() {
+            let range_map = self.images2.get(&barrier.image);
+
+            for range in barrier.image.iter_ranges(barrier.subresource_range.clone()) {
+                let states = range_map.into_iter().flat_map(|range_map| {
+                    range_map
+                        .range(&range)
+                        .filter(|(_range, state)| !state.resource_uses.is_empty())
+                });
+                let mut used_len = 0;
+
+                for (used_range, state) in states {
+                    used_len += cmp::min(used_range.end, range.end)
+                        - cmp::max(used_range.start, range.start);
+
+                    if barrier.old_layout != ImageLayout::Undefined
+                        && barrier.old_layout != state.current_layout
+                    {
+                        return Err(SynchronizationError::ImageMemoryBarrierOldLayoutMismatch {
+                            barrier_index,
+                            current_layout: state.current_layout,
+                        });
+                    }
+                }
+
+                // Without an earlier use, the layout transition would not be synchronized with
+                // the command buffers that were submitted before.
+                if used_len != range.end - range.start && barrier.old_layout != barrier.new_layout {
+                    return Err(SynchronizationError::ImageMemoryBarrierNotPreviouslyUsed {
+                        barrier_index,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the resources of the barriers of `dependency_info` aren't waiting on another
+    /// event. `use_refs` contains the use of each buffer memory barrier, followed by each image
+    /// memory barrier.
+    pub(in crate::command_buffer) fn check_event_barrier_conflicts(
+        &self,
+        use_refs: &[ResourceUseRef],
+        dependency_info: &DependencyInfo,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        let (buffer_use_refs, image_use_refs) =
+            use_refs.split_at(dependency_info.buffer_memory_barriers.len());
+
+        for (barrier, &current_use_ref) in
+            (dependency_info.buffer_memory_barriers.iter()).zip(buffer_use_refs)
+        {
+            if let Some(range_map) = self.buffers2.get(&barrier.buffer) {
+                for (_range, state) in range_map.range(&barrier.range) {
+                    if state.awaiting_event {
+                        return Err(SyncCommandBufferBuilderError::Conflict {
+                            current_use_ref,
+                            previous_use_ref: *state.resource_uses.last().unwrap(),
+                        });
+                    }
+                }
+            }
+        }
+
+        for (barrier, &current_use_ref) in
+            (dependency_info.image_memory_barriers.iter()).zip(image_use_refs)
+        {
+            if let Some(range_map) = self.images2.get(&barrier.image) {
+                for range in barrier.image.iter_ranges(barrier.subresource_range.clone()) {
+                    for (_range, state) in range_map.range(&range) {
+                        if state.awaiting_event {
+                            return Err(SyncCommandBufferBuilderError::Conflict {
+                                current_use_ref,
+                                previous_use_ref: *state.resource_uses.last().unwrap(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Marks the resources of the barriers of `dependency_info` as waiting on `event`, after the
+    /// `set_event` command was added. If earlier accesses of a resource are not within the source
+    /// scope of its barrier, a barrier is inserted before the command to make them so.
+    pub(in crate::command_buffer) fn add_set_event(
+        &mut self,
+        use_refs: SmallVec<[ResourceUseRef; 8]>,
+        event: Arc<Event>,
+        dependency_info: DependencyInfo,
+    ) {
+        let (buffer_use_refs, image_use_refs) =
+            use_refs.split_at(dependency_info.buffer_memory_barriers.len());
+        let mut barrier_requests: SmallVec<[BarrierRequest; 2]> = SmallVec::new();
+
+        for (barrier, &use_ref) in
+            (dependency_info.buffer_memory_barriers.iter()).zip(buffer_use_refs)
+        {
+            // Ranges that weren't used before don't need to be synchronized with anything in
+            // this command buffer, so they aren't tracked.
+            let range_map = match self.buffers2.get_mut(&barrier.buffer) {
+                Some(x) => x,
+                None => continue,
+            };
+            range_map.split_at(&barrier.range.start);
+            range_map.split_at(&barrier.range.end);
+
+            for (range, state) in range_map
+                .range_mut(&barrier.range)
+                .filter(|(_range, state)| !state.resource_uses.is_empty())
+            {
+                if !scope_contains(barrier.src_stages, barrier.src_access, &state.memory, true) {
+                    barrier_requests.push(BarrierRequest {
+                        barrier: Barrier::Buffer(BufferMemoryBarrier {
+                            src_stages: state.memory.stages,
+                            src_access: state.memory.access,
+                            dst_stages: barrier.src_stages,
+                            dst_access: barrier.src_access,
+                            range: range.clone(),
+                            ..BufferMemoryBarrier::buffer(barrier.buffer.clone())
+                        }),
+                        reason: BarrierReason::EventSourceScope,
+                        use_ref,
+                        previous_use_ref: state.resource_uses.last().copied(),
+                    });
+                }
+
+                state.awaiting_event = true;
+                state.event_scope = None;
+                state.resource_uses.push(use_ref);
+            }
+        }
+
+        for (barrier, &use_ref) in
+            (dependency_info.image_memory_barriers.iter()).zip(image_use_refs)
+        {
+            let range_map = match self.images2.get_mut(&barrier.image) {
+                Some(x) => x,
+                None => continue,
+            };
+
+            for range in barrier.image.iter_ranges(barrier.subresource_range.clone()) {
+                range_map.split_at(&range.start);
+                range_map.split_at(&range.end);
+
+                for (range, state) in range_map
+                    .range_mut(&range)
+                    .filter(|(_range, state)| !state.resource_uses.is_empty())
+                {
+                    if !scope_contains(barrier.src_stages, barrier.src_access, &state.memory, true)
+                    {
+                        barrier_requests.push(BarrierRequest {
+                            barrier: Barrier::Image(ImageMemoryBarrier {
+                                src_stages: state.memory.stages,
+                                src_access: state.memory.access,
+                                dst_stages: barrier.src_stages,
+                                dst_access: barrier.src_access,
+                                old_layout: state.current_layout,
+                                new_layout: state.current_layout,
+                                subresource_range: barrier
+                                    .image
+                                    .range_to_subresources(range.clone()),
+                                ..ImageMemoryBarrier::image(barrier.image.clone())
+                            }),
+                            reason: BarrierReason::EventSourceScope,
+                            use_ref,
+                            previous_use_ref: state.resource_uses.last().copied(),
+                        });
+                    }
+
+                    state.awaiting_event = true;
+                    state.event_scope = None;
+                    state.resource_uses.push(use_ref);
+                }
+            }
+        }
+
+        self.set_events.push((event, dependency_info));
+
+        for request in barrier_requests {
+            unsafe { self.insert_barrier(request) };
+        }
+    }
+
+    /// Removes `event` from the events that were set, and returns the dependency that it was set
+    /// with.
+    ///
+    /// Panics if `event` wasn't set.
+    pub(in crate::command_buffer) fn take_set_event(&mut self, event: &Event) -> DependencyInfo {
+        let index = self
+            .set_events
+            .iter()
+            .position(|(set_event, _)| set_event.handle() == event.handle())
+            .expect("the event wasn't set");
+
+        self.set_events.remove(index).1
+    }
+
+    /// Marks the resources of the barriers of `dependency_info` as synchronized with the commands
+    /// that come after the `wait_events` command that was added, within the destination scope of
+    /// their barrier.
+    pub(in crate::command_buffer) fn add_wait_event(
+        &mut self,
+        use_refs: SmallVec<[ResourceUseRef; 8]>,
+        dependency_info: DependencyInfo,
+    ) {
+        let (buffer_use_refs, image_use_refs) =
+            use_refs.split_at(dependency_info.buffer_memory_barriers.len());
+
+        for (barrier, &use_ref) in
+            (dependency_info.buffer_memory_barriers.iter()).zip(buffer_use_refs)
+        {
+            let range_map = match self.buffers2.get_mut(&barrier.buffer) {
+                Some(x) => x,
+                None => continue,
+            };
+
+            for (_range, state) in range_map
+                .range_mut(&barrier.range)
+                .filter(|(_range, state)| state.awaiting_event)
+            {
+                state.awaiting_event = false;
+                state.event_scope = Some((barrier.dst_stages, barrier.dst_access));
+                state.memory = PipelineMemoryAccess {
+                    stages: barrier.dst_stages,
+                    access: barrier.dst_access,
+                    exclusive: true,
+                };
+                state.resource_uses.push(use_ref);
+            }
+        }
+
+        for (barrier, &use_ref) in
+            (dependency_info.image_memory_barriers.iter()).zip(image_use_refs)
+        {
+            let range_map = match self.images2.get_mut(&barrier.image) {
+                Some(x) => x,
+                None => continue,
+            };
+
+            for range in barrier.image.iter_ranges(barrier.subresource_range.clone()) {
+                for (_range, state) in range_map
+                    .range_mut(&range)
+                    .filter(|(_range, state)| state.awaiting_event)
+                {
+                    state.awaiting_event = false;
+                    state.event_scope = Some((barrier.dst_stages, barrier.dst_access));
+                    state.memory = PipelineMemoryAccess {
+                        stages: barrier.dst_stages,
+                        access: barrier.dst_access,
+                        exclusive: true,
+                    };
+
+                    if barrier.old_layout != barrier.new_layout {
+                        state.current_layout = barrier.new_layout;
+                        state.exclusive_any = true;
+                    }
+
+                    state.resource_uses.push(use_ref);
+                }
+            }
+        }
     }
 
     /// Builds the command buffer and turns it into a `SyncCommandBuffer`.
     #[inline]
     pub fn build(mut self) -> Result<SyncCommandBuffer, OomError> {
         debug_assert!(self.latest_render_pass_enter.is_none() || self.pending_barrier.is_empty());
+        debug_assert!(self.set_events.is_empty());
 
         // The commands that haven't been sent to the inner command buffer yet need to be sent.
         unsafe {
-            self.inner.pipeline_barrier(&self.pending_barrier);
-            self.pending_barrier.clear();
-            let start = self.first_unflushed;
-            self.barriers.push(start); // Track inserted barriers
-
-            for command in &mut self.commands[start..] {
-                command.send(&mut self.inner);
-            }
+            self.flush(self.commands.len());
         }
+        debug_assert!(self.split_barriers.is_empty());
 
         // Transition images to their desired final layout.
         if self.level == CommandBufferLevel::Primary {
@@ -796,24 +1653,37 @@ impl SyncCommandBufferBuilder {
                         .iter_mut()
                         .filter(|(_range, state)| state.final_layout != state.current_layout)
                     {
-                        self.pending_barrier
-                            .image_memory_barriers
-                            .push(ImageMemoryBarrier {
-                                src_stages: state.memory.stages,
-                                src_access: state.memory.access,
-                                dst_stages: PipelineStages::TOP_OF_PIPE,
-                                dst_access: AccessFlags::empty(),
-                                old_layout: state.current_layout,
-                                new_layout: state.final_layout,
-                                subresource_range: image.range_to_subresources(range.clone()),
-                                ..ImageMemoryBarrier::image(image.clone())
+                        let barrier = ImageMemoryBarrier {
+                            src_stages: state.memory.stages,
+                            src_access: state.memory.access,
+                            dst_stages: PipelineStages::TOP_OF_PIPE,
+                            dst_access: AccessFlags::empty(),
+                            old_layout: state.current_layout,
+                            new_layout: state.final_layout,
+                            subresource_range: image.range_to_subresources(range.clone()),
+                            ..ImageMemoryBarrier::image(image.clone())
+                        };
+
+                        if let Some(barrier_report) = &mut self.barrier_report {
+                            barrier_report.push(BarrierReportEntry {
+                                previous_use_ref: state.resource_uses.last().copied(),
+                                ..image_report_entry(
+                                    &barrier,
+                                    BarrierReason::FinalLayout,
+                                    None,
+                                    self.commands.len(),
+                                )
                             });
+                        }
 
+                        self.pending_barrier.image_memory_barriers.push(barrier);
                         state.exclusive_any = true;
                     }
                 }
 
+                merge_barriers(&mut self.pending_barrier);
                 self.inner.pipeline_barrier(&self.pending_barrier);
+                self.inner.pipeline_barrier(&self.final_barrier);
             }
         }
 
@@ -887,12 +1757,19 @@ impl SyncCommandBufferBuilder {
             .map(|(index, usage)| (usage.image.clone(), index))
             .collect();
 
+        let barrier_report = self.barrier_report.map(|mut barrier_report| {
+            barrier_report.sort_by_key(|entry| entry.position);
+            barrier_report
+        });
+
         Ok(SyncCommandBuffer {
             inner: self.inner.build()?,
             resources_usage: resource_usage,
             secondary_resources_usage: self.secondary_resources_usage,
+            barrier_report,
             _commands: self.commands,
             _barriers: self.barriers,
+            _events: self.events,
         })
     }
 }
@@ -939,6 +1816,228 @@ impl From<CommandBufferExecError> for SyncCommandBufferBuilderError {
     }
 }
 
+// A barrier that is split into setting `event` before the command at `set_position`, and waiting
+// on it before the command at `wait_position`.
+struct SplitBarrier {
+    set_position: usize,
+    wait_position: usize,
+    event: Event,
+    dependency_info: DependencyInfo,
+}
+
+// A barrier that is needed before the command of `use_ref`.
+struct BarrierRequest {
+    barrier: Barrier,
+    reason: BarrierReason,
+    use_ref: ResourceUseRef,
+    previous_use_ref: Option<ResourceUseRef>,
+}
+
+enum Barrier {
+    Buffer(BufferMemoryBarrier),
+    Image(ImageMemoryBarrier),
+}
+
+impl Barrier {
+    fn push_to(self, dependency_info: &mut DependencyInfo) {
+        match self {
+            Barrier::Buffer(barrier) => dependency_info.buffer_memory_barriers.push(barrier),
+            Barrier::Image(barrier) => dependency_info.image_memory_barriers.push(barrier),
+        }
+    }
+
+    // Returns whether `self` is an image barrier for any of the same subresources as one of the
+    // image barriers of `dependency_info`.
+    fn overlaps_image_barriers(&self, dependency_info: &DependencyInfo) -> bool {
+        let barrier = match self {
+            Barrier::Buffer(_) => return false,
+            Barrier::Image(barrier) => barrier,
+        };
+
+        dependency_info.image_memory_barriers.iter().any(|other| {
+            Arc::ptr_eq(&barrier.image, &other.image)
+                && barrier
+                    .subresource_range
+                    .aspects
+                    .intersects(other.subresource_range.aspects)
+                && ranges_overlap(
+                    &barrier.subresource_range.mip_levels,
+                    &other.subresource_range.mip_levels,
+                )
+                && ranges_overlap(
+                    &barrier.subresource_range.array_layers,
+                    &other.subresource_range.array_layers,
+                )
+        })
+    }
+}
+
+fn ranges_overlap(a: &Range<u32>, b: &Range<u32>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+// Returns whether the accesses of `memory` are within a scope of a barrier. For the source scope,
+// only writes need to be within the access scope, because reads don't need to be made available.
+fn scope_contains(
+    stages: PipelineStages,
+    access: AccessFlags,
+    memory: &PipelineMemoryAccess,
+    source: bool,
+) -> bool {
+    let stages_contained = stages.intersects(PipelineStages::ALL_COMMANDS)
+        || !memory.stages.intersects(PipelineStages::ALL_COMMANDS)
+            && (stages.expand(QueueFlags::empty()))
+                .contains(memory.stages.expand(QueueFlags::empty()));
+    let access_contained = access.contains(AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE)
+        || source && (!memory.exclusive || access.intersects(AccessFlags::MEMORY_WRITE))
+        || access.expand().contains(memory.access.expand());
+
+    stages_contained && access_contained
+}
+
+// Returns why a barrier is needed between a previous and a current access of a resource.
+fn hazard_reason(previous: &PipelineMemoryAccess, current: &PipelineMemoryAccess) -> BarrierReason {
+    match (previous.exclusive, current.exclusive) {
+        (true, true) => BarrierReason::WriteAfterWrite,
+        (true, false) => BarrierReason::ReadAfterWrite,
+        (false, true) => BarrierReason::WriteAfterRead,
+        (false, false) => BarrierReason::LayoutTransition,
+    }
+}
+
+// Reduces the number of barriers in `dependency_info`, without weakening any of them.
+//
+// Buffer barriers that don't transfer queue family ownership are replaced with one global memory
+// barrier for each combination of stages and accesses, which covers every buffer. Image barriers
+// for the same image, with the same stages, accesses and layouts, are coalesced if their
+// subresource ranges are adjacent.
+fn merge_barriers(dependency_info: &mut DependencyInfo) {
+    let DependencyInfo {
+        memory_barriers,
+        buffer_memory_barriers,
+        image_memory_barriers,
+        ..
+    } = dependency_info;
+
+    buffer_memory_barriers.retain(|barrier| {
+        if barrier.queue_family_ownership_transfer.is_some() {
+            return true;
+        }
+
+        match memory_barriers.iter_mut().find(|memory_barrier| {
+            memory_barrier.src_stages == barrier.src_stages
+                && memory_barrier.dst_stages == barrier.dst_stages
+        }) {
+            Some(memory_barrier) => {
+                memory_barrier.src_access |= barrier.src_access;
+                memory_barrier.dst_access |= barrier.dst_access;
+            }
+            None => memory_barriers.push(MemoryBarrier {
+                src_stages: barrier.src_stages,
+                src_access: barrier.src_access,
+                dst_stages: barrier.dst_stages,
+                dst_access: barrier.dst_access,
+                ..Default::default()
+            }),
+        }
+
+        false
+    });
+
+    let mut merged: SmallVec<[ImageMemoryBarrier; 8]> = SmallVec::new();
+
+    'outer: for barrier in image_memory_barriers.drain(..) {
+        if barrier.queue_family_ownership_transfer.is_none() {
+            for other in merged.iter_mut().rev() {
+                if !(Arc::ptr_eq(&barrier.image, &other.image)
+                    && other.queue_family_ownership_transfer.is_none()
+                    && barrier.src_stages == other.src_stages
+                    && barrier.src_access == other.src_access
+                    && barrier.dst_stages == other.dst_stages
+                    && barrier.dst_access == other.dst_access
+                    && barrier.old_layout == other.old_layout
+                    && barrier.new_layout == other.new_layout
+                    && barrier.subresource_range.aspects == other.subresource_range.aspects)
+                {
+                    continue;
+                }
+
+                let (a, b) = (&barrier.subresource_range, &mut other.subresource_range);
+
+                if a.mip_levels == b.mip_levels {
+                    if a.array_layers.end == b.array_layers.start {
+                        b.array_layers.start = a.array_layers.start;
+                        continue 'outer;
+                    } else if b.array_layers.end == a.array_layers.start {
+                        b.array_layers.end = a.array_layers.end;
+                        continue 'outer;
+                    }
+                } else if a.array_layers == b.array_layers {
+                    if a.mip_levels.end == b.mip_levels.start {
+                        b.mip_levels.start = a.mip_levels.start;
+                        continue 'outer;
+                    } else if b.mip_levels.end == a.mip_levels.start {
+                        b.mip_levels.end = a.mip_levels.end;
+                        continue 'outer;
+                    }
+                }
+            }
+        }
+
+        merged.push(barrier);
+    }
+
+    image_memory_barriers.extend(merged);
+}
+
+fn buffer_report_entry(
+    barrier: &BufferMemoryBarrier,
+    reason: BarrierReason,
+    use_ref: Option<ResourceUseRef>,
+    position: usize,
+) -> BarrierReportEntry {
+    BarrierReportEntry {
+        resource: BarrierResource::Buffer {
+            buffer: barrier.buffer.clone(),
+            range: barrier.range.clone(),
+        },
+        reason,
+        use_ref,
+        previous_use_ref: None,
+        src_stages: barrier.src_stages,
+        src_access: barrier.src_access,
+        dst_stages: barrier.dst_stages,
+        dst_access: barrier.dst_access,
+        position,
+        split_position: None,
+    }
+}
+
+fn image_report_entry(
+    barrier: &ImageMemoryBarrier,
+    reason: BarrierReason,
+    use_ref: Option<ResourceUseRef>,
+    position: usize,
+) -> BarrierReportEntry {
+    BarrierReportEntry {
+        resource: BarrierResource::Image {
+            image: barrier.image.clone(),
+            subresource_range: barrier.subresource_range.clone(),
+            old_layout: barrier.old_layout,
+            new_layout: barrier.new_layout,
+        },
+        reason,
+        use_ref,
+        previous_use_ref: None,
+        src_stages: barrier.src_stages,
+        src_access: barrier.src_access,
+        dst_stages: barrier.dst_stages,
+        dst_access: barrier.dst_access,
+        position,
+        split_position: None,
+    }
+}
+
 // State of a resource during the building of the command buffer.
 #[derive(Clone, PartialEq, Eq)]
 struct BufferState {
@@ -951,6 +2050,13 @@ struct BufferState {
     // True if the resource was used in exclusive mode at any point during the building of the
     // command buffer. Also true if an image layout transition or queue transfer has been performed.
     exclusive_any: bool,
+
+    // True if the resource is in a barrier of an event that was set, but not waited on yet.
+    awaiting_event: bool,
+
+    // If the resource was last used by waiting on an event, the destination scope of the
+    // barrier of the event. The next access doesn't need another barrier if it's within it.
+    event_scope: Option<(PipelineStages, AccessFlags)>,
 }
 
 // State of a resource during the building of the command buffer.
@@ -966,6 +2072,13 @@ struct ImageState {
     // command buffer. Also true if an image layout transition or queue transfer has been performed.
     exclusive_any: bool,
 
+    // True if the resource is in a barrier of an event that was set, but not waited on yet.
+    awaiting_event: bool,
+
+    // If the resource was last used by waiting on an event, the destination scope of the
+    // barrier of the event. The next access doesn't need another barrier if it's within it.
+    event_scope: Option<(PipelineStages, AccessFlags)>,
+
     // The layout that the image range must have when this command buffer is executed.
     // Can be `Undefined` if we don't care.
     initial_layout: ImageLayout,
@@ -981,10 +2094,14 @@ struct ImageState {
 /// Holds the current binding and setting state.
 #[derive(Default)]
 pub(in crate::command_buffer) struct CurrentState {
+    pub(in crate::command_buffer) descriptor_buffers: SmallVec<[Subbuffer<[u8]>; 2]>,
     pub(in crate::command_buffer) descriptor_sets: HashMap<PipelineBindPoint, DescriptorSetState>,
+    pub(in crate::command_buffer) device_address_buffers:
+        HashMap<PipelineBindPoint, SmallVec<[Subbuffer<[u8]>; 4]>>,
     pub(in crate::command_buffer) index_buffer: Option<(Subbuffer<[u8]>, IndexType)>,
     pub(in crate::command_buffer) pipeline_compute: Option<Arc<ComputePipeline>>,
     pub(in crate::command_buffer) pipeline_graphics: Option<Arc<GraphicsPipeline>>,
+    pub(in crate::command_buffer) pipeline_ray_tracing: Option<Arc<RayTracingPipeline>>,
     pub(in crate::command_buffer) vertex_buffers: HashMap<u32, Subbuffer<[u8]>>,
 
     pub(in crate::command_buffer) push_constants: RangeSet<u32>,
@@ -1166,14 +2283,22 @@ pub(in crate::command_buffer) struct DescriptorSetState {
 pub enum SetOrPush {
     Set(DescriptorSetWithOffsets),
     Push(DescriptorSetResources),
+    /// A descriptor set that is stored in a bound descriptor buffer.
+    DescriptorBuffer {
+        buffer_index: u32,
+        offset: DeviceSize,
+    },
 }
 
 impl SetOrPush {
+    /// Returns the resources of the descriptor set, or `None` if the descriptor set is stored in
+    /// a descriptor buffer.
     #[inline]
-    pub fn resources(&self) -> &DescriptorSetResources {
+    pub fn resources(&self) -> Option<&DescriptorSetResources> {
         match self {
-            Self::Set(set) => set.as_ref().0.resources(),
-            Self::Push(resources) => resources,
+            Self::Set(set) => Some(set.as_ref().0.resources()),
+            Self::Push(resources) => Some(resources),
+            Self::DescriptorBuffer { .. } => None,
         }
     }
 
@@ -1181,7 +2306,7 @@ impl SetOrPush {
     pub fn dynamic_offsets(&self) -> &[u32] {
         match self {
             Self::Set(set) => set.as_ref().1,
-            Self::Push(_) => &[],
+            Self::Push(_) | Self::DescriptorBuffer { .. } => &[],
         }
     }
 }
@@ -1222,6 +2347,12 @@ impl<'a> CommandBufferBuilderState<'a> {
             .map(|state| &state.pipeline_layout)
     }
 
+    /// Returns the descriptor buffers currently bound.
+    #[inline]
+    pub fn descriptor_buffers(&self) -> &'a [Subbuffer<[u8]>] {
+        &self.current_state.descriptor_buffers
+    }
+
     /// Returns the index buffer currently bound, or `None` if nothing has been bound yet.
     #[inline]
     pub fn index_buffer(&self) -> Option<(&'a Subbuffer<[u8]>, IndexType)> {
@@ -1243,6 +2374,12 @@ impl<'a> CommandBufferBuilderState<'a> {
         self.current_state.pipeline_graphics.as_ref()
     }
 
+    /// Returns the ray tracing pipeline currently bound, or `None` if nothing has been bound yet.
+    #[inline]
+    pub fn pipeline_ray_tracing(&self) -> Option<&'a Arc<RayTracingPipeline>> {
+        self.current_state.pipeline_ray_tracing.as_ref()
+    }
+
     /// Returns the vertex buffer currently bound to a given binding slot number, or `None` if
     /// nothing has been bound yet.
     #[inline]
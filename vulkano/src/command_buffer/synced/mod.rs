@@ -61,8 +61,28 @@
 //!
 //! Whenever you add a command, the builder will find out whether a barrier is needed before the
 //! command. If so, it will try to merge this barrier with the prototype and add the command to the
-//! queue. If not possible, the queue will be entirely flushed and the command added to a fresh new
-//! queue with a fresh new barrier prototype.
+//! queue. If not possible, the queue will be flushed up to the command that previously used the
+//! resource, and the barrier added to a fresh new barrier prototype. This way, a barrier is
+//! recorded as early as possible: layout transitions in particular happen right after the last
+//! command that used the image in its old layout, rather than right before the command that needs
+//! the new layout. A barrier is never moved into or across a render pass.
+//!
+//! Before a barrier prototype is recorded, its barriers are merged. Buffer barriers are replaced
+//! with global memory barriers, and image barriers for adjacent subresources of the same image are
+//! coalesced.
+//!
+//! ## Split barriers
+//!
+//! In command buffers that are only submitted once, when there are enough commands between the
+//! previous use of a resource and the command that needs a barrier for it, the barrier is split
+//! into setting an event after the previous use and waiting on the event before the command. The
+//! commands in between can then run while the barrier is pending.
+//!
+//! ## Barrier report
+//!
+//! When enabled with `enable_barrier_report`, the builder lists every barrier that it inserts,
+//! along with the resource and the command that needed it, and the reason why. This can be used
+//! to find out which commands could be reordered to need fewer barriers.
 
 pub use self::builder::{
     CommandBufferBuilderState, SetOrPush, StencilOpStateDynamic, StencilStateDynamic,
@@ -72,13 +92,13 @@ pub use self::builder::{
 };
 use super::{
     sys::{UnsafeCommandBuffer, UnsafeCommandBufferBuilder},
-    CommandBufferResourcesUsage, SecondaryCommandBufferResourcesUsage,
+    BarrierReportEntry, CommandBufferResourcesUsage, SecondaryCommandBufferResourcesUsage,
 };
 use crate::{
     buffer::Subbuffer,
     device::{Device, DeviceOwned},
     image::{ImageAccess, ImageLayout, ImageSubresourceRange},
-    sync::PipelineMemoryAccess,
+    sync::{event::Event, PipelineMemoryAccess},
     DeviceSize,
 };
 use std::{
@@ -103,6 +123,12 @@ pub struct SyncCommandBuffer {
     // TODO: present only in cfg(debug_assertions)?
     _barriers: Vec<usize>,
 
+    // The events of the split barriers, which must be kept alive as long as the command buffer.
+    _events: Vec<Event>,
+
+    // If enabled when building, every barrier that was inserted, ordered by position.
+    barrier_report: Option<Vec<BarrierReportEntry>>,
+
     // Resources accessed by this command buffer.
     resources_usage: CommandBufferResourcesUsage,
 
@@ -120,6 +146,11 @@ impl SyncCommandBuffer {
     pub(super) fn secondary_resources_usage(&self) -> &SecondaryCommandBufferResourcesUsage {
         &self.secondary_resources_usage
     }
+
+    #[inline]
+    pub(super) fn barrier_report(&self) -> Option<&[BarrierReportEntry]> {
+        self.barrier_report.as_deref()
+    }
 }
 
 impl AsRef<UnsafeCommandBuffer> for SyncCommandBuffer {
@@ -205,6 +205,12 @@ impl UnsafeCommandBufferBuilder {
             })
         }
     }
+
+    /// Returns the usage that the command buffer was begun with.
+    #[inline]
+    pub fn usage(&self) -> CommandBufferUsage {
+        self.usage
+    }
 }
 
 unsafe impl VulkanObject for UnsafeCommandBufferBuilder {
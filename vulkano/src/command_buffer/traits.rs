@@ -249,11 +249,11 @@ where
                     SubmitInfo {
                         wait_semaphores: semaphores
                             .into_iter()
-                            .map(|semaphore| {
+                            .map(|semaphore_submit_info| {
                                 SemaphoreSubmitInfo {
                                     // TODO: correct stages ; hard
                                     stages: PipelineStages::ALL_COMMANDS,
-                                    ..SemaphoreSubmitInfo::semaphore(semaphore)
+                                    ..semaphore_submit_info
                                 }
                             })
                             .collect(),
@@ -266,6 +266,7 @@ impl FixedPool {
                     .iter()
                     .map(|(&ty, &count)| (ty, count * set_count as u32))
                     .collect(),
+                update_after_bind: layout.update_after_bind_pool(),
                 ..Default::default()
             },
         )?;
@@ -402,6 +403,7 @@ impl VariablePool {
                     .iter()
                     .map(|(&ty, &count)| (ty, count * MAX_SETS as u32))
                     .collect(),
+                update_after_bind: layout.update_after_bind_pool(),
                 ..Default::default()
             },
         )
@@ -0,0 +1,736 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A long-lived descriptor set whose array elements are handed out as individual slots.
+//!
+//! In a "bindless" renderer, all textures and buffers that shaders may access are put into a few
+//! large descriptor arrays, and shaders index into these arrays with indices that are passed in
+//! push constants or buffers. The descriptor set stays bound, while elements of the arrays are
+//! filled in and released as resources are loaded and unloaded.
+//!
+//! A [`BindlessDescriptorSet`] manages such a descriptor set. For each binding, it hands out the
+//! indices of unused array elements with [`allocate`], which can then be written with [`write`]
+//! and eventually released with [`free`]. To bind the descriptor set to a command buffer, a
+//! [`BindlessDescriptorSetSnapshot`] is taken with [`snapshot`], which holds on to the resources
+//! that were written at that time.
+//!
+//! A freed element is not handed out again until all snapshots that could still refer to its old
+//! contents have been dropped. Command buffers keep the snapshots that were bound to them alive
+//! until they are destroyed, which happens only after the GPU has finished executing them.
+//!
+//! The descriptor set layout should usually be created with
+//! [`update_after_bind_pool`](super::layout::DescriptorSetLayoutCreateInfo::update_after_bind_pool)
+//! enabled, and with the array bindings having `update_after_bind`, `update_unused_while_pending`
+//! and `partially_bound` enabled. Without `update_after_bind` and `update_unused_while_pending`,
+//! a binding can only be written while no snapshot of the descriptor set is alive. Without
+//! `partially_bound`, every element of the binding must be written before the descriptor set can
+//! be used by a shader that accesses the binding.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use vulkano::descriptor_set::layout::DescriptorSetLayout;
+//! # use vulkano::image::view::ImageView;
+//! # use vulkano::image::ImmutableImage;
+//! # use vulkano::sampler::Sampler;
+//! # let layout: Arc<DescriptorSetLayout> = return;
+//! # let texture: Arc<ImageView<ImmutableImage>> = return;
+//! # let sampler: Arc<Sampler> = return;
+//! use vulkano::descriptor_set::{bindless::BindlessDescriptorSet, WriteDescriptorSet};
+//!
+//! let mut descriptor_set = BindlessDescriptorSet::new(layout, 1024).unwrap();
+//!
+//! // Hand out an index in binding 0, and write the texture there.
+//! let index = descriptor_set.allocate(0).unwrap();
+//! descriptor_set
+//!     .write([WriteDescriptorSet::image_view_sampler_array(
+//!         0,
+//!         index,
+//!         [(texture as _, sampler)],
+//!     )])
+//!     .unwrap();
+//!
+//! // Bind `snapshot` to a command buffer, and pass `index` to the shader.
+//! let snapshot = descriptor_set.snapshot();
+//!
+//! // When the texture is no longer needed, release the index. It will be reused once all
+//! // command buffers that use `snapshot` have been destroyed.
+//! descriptor_set.free(0, index).unwrap();
+//! ```
+//!
+//! [`allocate`]: BindlessDescriptorSet::allocate
+//! [`write`]: BindlessDescriptorSet::write
+//! [`free`]: BindlessDescriptorSet::free
+//! [`snapshot`]: BindlessDescriptorSet::snapshot
+
+use super::{
+    check_descriptor_write,
+    layout::{DescriptorSetLayout, DescriptorType},
+    pool::{
+        DescriptorPool, DescriptorPoolAllocError, DescriptorPoolCreateInfo,
+        DescriptorSetAllocateInfo,
+    },
+    sys::{self, UnsafeDescriptorSet},
+    DescriptorSet, DescriptorSetResources, DescriptorSetUpdateError, WriteDescriptorSet,
+};
+use crate::{
+    device::{Device, DeviceOwned},
+    OomError, VulkanObject,
+};
+use ahash::HashMap;
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    hash::{Hash, Hasher},
+    sync::{Arc, Weak},
+};
+
+/// A long-lived descriptor set that hands out individual array elements of its bindings.
+///
+/// See the [module-level documentation](self) for more information.
+pub struct BindlessDescriptorSet {
+    alloc: Arc<BindlessDescriptorSetAlloc>,
+    variable_descriptor_count: u32,
+    resources: DescriptorSetResources,
+    bindings: HashMap<u32, BindingSlots>,
+
+    // Incremented every time a new snapshot is taken.
+    generation: u64,
+    // The snapshots that have been taken, oldest first, with their generation.
+    snapshots: VecDeque<(u64, Weak<BindlessDescriptorSetSnapshot>)>,
+    // The most recent snapshot, if the resources haven't changed since it was taken.
+    current_snapshot: Weak<BindlessDescriptorSetSnapshot>,
+}
+
+struct BindlessDescriptorSetAlloc {
+    inner: UnsafeDescriptorSet,
+    layout: Arc<DescriptorSetLayout>,
+    // The pool must be kept alive for as long as the descriptor set is in use, as destroying the
+    // pool frees the descriptor set.
+    _pool: DescriptorPool,
+}
+
+// This is needed because of the blanket impl of `Send` on `Arc<T>`, which requires that `T` is
+// `Send + Sync`. `DescriptorPool` is `!Sync`, but the pool is never accessed after the descriptor
+// set has been allocated.
+unsafe impl Send for BindlessDescriptorSetAlloc {}
+unsafe impl Sync for BindlessDescriptorSetAlloc {}
+
+struct BindingSlots {
+    states: Vec<SlotState>,
+    // Free indices, the lowest one last.
+    free: Vec<u32>,
+    // Freed indices, with the generation at the time they were freed, oldest first.
+    pending: VecDeque<(u32, u64)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SlotState {
+    Free,
+    Allocated {
+        // The generation at the time the element was last written, if it was written.
+        written_at: Option<u64>,
+    },
+    Pending,
+}
+
+impl BindlessDescriptorSet {
+    /// Creates a new `BindlessDescriptorSet`, allocating it from a descriptor pool of its own.
+    ///
+    /// If the layout has a variable-count binding, it is allocated with
+    /// `variable_descriptor_count` elements. Otherwise, `variable_descriptor_count` must be `0`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `layout` was created for push descriptors or for descriptor buffers.
+    /// - Panics if `variable_descriptor_count` is too large for the given `layout`.
+    /// - Panics if `layout` has no descriptors.
+    pub fn new(
+        layout: Arc<DescriptorSetLayout>,
+        variable_descriptor_count: u32,
+    ) -> Result<BindlessDescriptorSet, OomError> {
+        assert!(
+            !layout.push_descriptor(),
+            "the provided descriptor set layout is for push descriptors, and cannot be used to \
+            build a descriptor set object",
+        );
+        assert!(
+            !layout.descriptor_buffer(),
+            "the provided descriptor set layout is for descriptor buffers, and cannot be used to \
+            build a descriptor set object",
+        );
+
+        let max_count = layout.variable_descriptor_count();
+
+        assert!(
+            variable_descriptor_count <= max_count,
+            "the provided variable_descriptor_count ({}) is greater than the maximum number of \
+            variable count descriptors in the layout ({})",
+            variable_descriptor_count,
+            max_count,
+        );
+
+        let binding_counts: HashMap<u32, u32> = layout
+            .bindings()
+            .iter()
+            .map(|(&binding_num, binding)| {
+                let count = if binding.variable_descriptor_count {
+                    variable_descriptor_count
+                } else {
+                    binding.descriptor_count
+                };
+
+                (binding_num, count)
+            })
+            .collect();
+
+        let mut pool_sizes: HashMap<DescriptorType, u32> = HashMap::default();
+
+        for (binding_num, binding) in layout.bindings() {
+            let count = binding_counts[binding_num];
+
+            if count != 0 {
+                *pool_sizes.entry(binding.descriptor_type).or_default() += count;
+            }
+        }
+
+        assert!(
+            !pool_sizes.is_empty(),
+            "the provided descriptor set layout has no descriptors",
+        );
+
+        let pool = DescriptorPool::new(
+            layout.device().clone(),
+            DescriptorPoolCreateInfo {
+                max_sets: 1,
+                pool_sizes,
+                update_after_bind: layout.update_after_bind_pool(),
+                ..Default::default()
+            },
+        )?;
+
+        let allocate_info = DescriptorSetAllocateInfo {
+            layout: &layout,
+            variable_descriptor_count,
+        };
+
+        let inner = match unsafe { pool.allocate_descriptor_sets([allocate_info]) } {
+            Ok(mut sets) => sets.next().unwrap(),
+            Err(DescriptorPoolAllocError::OutOfHostMemory) => {
+                return Err(OomError::OutOfHostMemory);
+            }
+            Err(DescriptorPoolAllocError::OutOfDeviceMemory) => {
+                return Err(OomError::OutOfDeviceMemory);
+            }
+            Err(DescriptorPoolAllocError::FragmentedPool) => {
+                // This can't happen as we don't free individual sets.
+                unreachable!();
+            }
+            Err(DescriptorPoolAllocError::OutOfPoolMemory) => {
+                // We created the pool with an exact size.
+                unreachable!();
+            }
+        };
+
+        let resources = DescriptorSetResources::new(&layout, variable_descriptor_count);
+        let bindings = binding_counts
+            .into_iter()
+            .map(|(binding_num, count)| {
+                let slots = BindingSlots {
+                    states: vec![SlotState::Free; count as usize],
+                    free: (0..count).rev().collect(),
+                    pending: VecDeque::new(),
+                };
+
+                (binding_num, slots)
+            })
+            .collect();
+
+        Ok(BindlessDescriptorSet {
+            alloc: Arc::new(BindlessDescriptorSetAlloc {
+                inner,
+                layout,
+                _pool: pool,
+            }),
+            variable_descriptor_count,
+            resources,
+            bindings,
+            generation: 0,
+            snapshots: VecDeque::new(),
+            current_snapshot: Weak::new(),
+        })
+    }
+
+    /// Returns the layout of the descriptor set.
+    #[inline]
+    pub fn layout(&self) -> &Arc<DescriptorSetLayout> {
+        &self.alloc.layout
+    }
+
+    /// Returns the variable descriptor count that the descriptor set was allocated with.
+    #[inline]
+    pub fn variable_descriptor_count(&self) -> u32 {
+        self.variable_descriptor_count
+    }
+
+    /// Returns the resources that are currently written to the descriptor set.
+    #[inline]
+    pub fn resources(&self) -> &DescriptorSetResources {
+        &self.resources
+    }
+
+    /// Returns the number of elements of `binding_num` that can currently be allocated, not
+    /// counting freed elements that are still waiting for their snapshots to be dropped.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `binding_num` does not exist in the layout.
+    pub fn free_count(&self, binding_num: u32) -> u32 {
+        self.binding_slots(binding_num).free.len() as u32
+    }
+
+    /// Allocates an unused array element of `binding_num`, and returns its index.
+    ///
+    /// Elements that have been freed are reused only after all snapshots that were taken before
+    /// they were freed have been dropped.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `binding_num` does not exist in the layout.
+    pub fn allocate(&mut self, binding_num: u32) -> Result<u32, BindlessDescriptorSetError> {
+        if self.binding_slots(binding_num).free.is_empty() {
+            self.reclaim();
+        }
+
+        let slots = self.bindings.get_mut(&binding_num).unwrap();
+        let index = slots
+            .free
+            .pop()
+            .ok_or(BindlessDescriptorSetError::BindingFull { binding_num })?;
+        slots.states[index as usize] = SlotState::Allocated { written_at: None };
+
+        Ok(index)
+    }
+
+    /// Writes to array elements of the descriptor set.
+    ///
+    /// Every element that is written must have been allocated with [`allocate`], and must not be
+    /// used by a snapshot that is still alive. If any snapshot of the descriptor set is still
+    /// alive, then the binding must have `update_after_bind` and `update_unused_while_pending`
+    /// enabled.
+    ///
+    /// The writes are validated before any of them is performed, so if an error is returned,
+    /// the descriptor set is left unchanged.
+    ///
+    /// [`allocate`]: Self::allocate
+    pub fn write(
+        &mut self,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+    ) -> Result<(), BindlessDescriptorSetError> {
+        let descriptor_writes: Vec<_> = descriptor_writes.into_iter().collect();
+
+        self.prune_snapshots();
+        let newest_live_generation = self.snapshots.back().map(|&(generation, _)| generation);
+
+        for write in &descriptor_writes {
+            let layout_binding =
+                check_descriptor_write(write, &self.alloc.layout, self.variable_descriptor_count)?;
+            let binding_num = write.binding();
+
+            if newest_live_generation.is_some()
+                && !(layout_binding.update_after_bind && layout_binding.update_unused_while_pending)
+            {
+                return Err(BindlessDescriptorSetError::BindingNotUpdatable { binding_num });
+            }
+
+            let slots = &self.bindings[&binding_num];
+            let first = write.first_array_element();
+
+            for index in first..first + write.elements().len() {
+                match slots.states[index as usize] {
+                    SlotState::Allocated { written_at } => {
+                        // A snapshot taken after the element was last written refers to its
+                        // current contents.
+                        if let (Some(written_at), Some(newest_live_generation)) =
+                            (written_at, newest_live_generation)
+                        {
+                            if newest_live_generation > written_at {
+                                return Err(BindlessDescriptorSetError::ElementInUse {
+                                    binding_num,
+                                    index,
+                                });
+                            }
+                        }
+                    }
+                    SlotState::Free | SlotState::Pending => {
+                        return Err(BindlessDescriptorSetError::ElementNotAllocated {
+                            binding_num,
+                            index,
+                        });
+                    }
+                }
+            }
+        }
+
+        if descriptor_writes.is_empty() {
+            return Ok(());
+        }
+
+        unsafe {
+            sys::write_descriptor_set(
+                self.alloc.inner.handle(),
+                &self.alloc.layout,
+                &descriptor_writes,
+            );
+        }
+
+        for write in &descriptor_writes {
+            self.resources.update(write);
+
+            let slots = self.bindings.get_mut(&write.binding()).unwrap();
+            let first = write.first_array_element();
+
+            for index in first..first + write.elements().len() {
+                slots.states[index as usize] = SlotState::Allocated {
+                    written_at: Some(self.generation),
+                };
+            }
+        }
+
+        self.current_snapshot = Weak::new();
+
+        Ok(())
+    }
+
+    /// Frees an array element that was previously allocated, and releases the resource that was
+    /// written to it.
+    ///
+    /// The index is not handed out again by [`allocate`] until all snapshots that were taken
+    /// before this call have been dropped.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `binding_num` does not exist in the layout.
+    ///
+    /// [`allocate`]: Self::allocate
+    pub fn free(&mut self, binding_num: u32, index: u32) -> Result<(), BindlessDescriptorSetError> {
+        let generation = self.generation;
+        let slots = self
+            .bindings
+            .get_mut(&binding_num)
+            .expect("binding_num does not exist in the layout");
+
+        let written = match slots.states.get(index as usize) {
+            Some(&SlotState::Allocated { written_at }) => written_at.is_some(),
+            _ => {
+                return Err(BindlessDescriptorSetError::ElementNotAllocated { binding_num, index })
+            }
+        };
+
+        slots.states[index as usize] = SlotState::Pending;
+        slots.pending.push_back((index, generation));
+
+        if written {
+            self.resources
+                .invalidate(binding_num, index as usize..index as usize + 1);
+            self.current_snapshot = Weak::new();
+        }
+
+        Ok(())
+    }
+
+    /// Returns a snapshot of the descriptor set, that can be bound to a command buffer.
+    ///
+    /// The snapshot keeps the resources that are currently written to the descriptor set alive.
+    /// If nothing was written or freed since the previous snapshot was taken, and that snapshot
+    /// is still alive, then it is returned again.
+    pub fn snapshot(&mut self) -> Arc<BindlessDescriptorSetSnapshot> {
+        if let Some(snapshot) = self.current_snapshot.upgrade() {
+            return snapshot;
+        }
+
+        self.prune_snapshots();
+        self.generation += 1;
+
+        let snapshot = Arc::new(BindlessDescriptorSetSnapshot {
+            alloc: self.alloc.clone(),
+            variable_descriptor_count: self.variable_descriptor_count,
+            resources: self.resources.clone(),
+        });
+        self.current_snapshot = Arc::downgrade(&snapshot);
+        self.snapshots
+            .push_back((self.generation, self.current_snapshot.clone()));
+
+        snapshot
+    }
+
+    fn binding_slots(&self, binding_num: u32) -> &BindingSlots {
+        self.bindings
+            .get(&binding_num)
+            .expect("binding_num does not exist in the layout")
+    }
+
+    // Removes the snapshots that are no longer alive.
+    fn prune_snapshots(&mut self) {
+        self.snapshots
+            .retain(|(_, snapshot)| snapshot.strong_count() != 0);
+    }
+
+    // Makes the freed elements that are no longer referred to by any snapshot available again.
+    fn reclaim(&mut self) {
+        self.prune_snapshots();
+        let oldest_live_generation = self.snapshots.front().map(|&(generation, _)| generation);
+
+        for slots in self.bindings.values_mut() {
+            while let Some(&(index, freed_at)) = slots.pending.front() {
+                // The snapshots that were taken before the element was freed have a generation
+                // that is less than or equal to `freed_at`.
+                if oldest_live_generation.map_or(false, |oldest| oldest <= freed_at) {
+                    break;
+                }
+
+                slots.pending.pop_front();
+                slots.states[index as usize] = SlotState::Free;
+                slots.free.push(index);
+            }
+
+            // Keep handing out the lowest indices first.
+            slots.free.sort_unstable_by(|a, b| b.cmp(a));
+        }
+    }
+}
+
+unsafe impl DeviceOwned for BindlessDescriptorSet {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.alloc.layout.device()
+    }
+}
+
+/// The contents of a [`BindlessDescriptorSet`] at a certain point in time, that can be bound to a
+/// command buffer.
+pub struct BindlessDescriptorSetSnapshot {
+    alloc: Arc<BindlessDescriptorSetAlloc>,
+    variable_descriptor_count: u32,
+    resources: DescriptorSetResources,
+}
+
+unsafe impl DescriptorSet for BindlessDescriptorSetSnapshot {
+    #[inline]
+    fn inner(&self) -> &UnsafeDescriptorSet {
+        &self.alloc.inner
+    }
+
+    #[inline]
+    fn layout(&self) -> &Arc<DescriptorSetLayout> {
+        &self.alloc.layout
+    }
+
+    #[inline]
+    fn variable_descriptor_count(&self) -> u32 {
+        self.variable_descriptor_count
+    }
+
+    #[inline]
+    fn resources(&self) -> &DescriptorSetResources {
+        &self.resources
+    }
+}
+
+unsafe impl DeviceOwned for BindlessDescriptorSetSnapshot {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.alloc.layout.device()
+    }
+}
+
+impl PartialEq for BindlessDescriptorSetSnapshot {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner()
+    }
+}
+
+impl Eq for BindlessDescriptorSetSnapshot {}
+
+impl Hash for BindlessDescriptorSetSnapshot {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().hash(state);
+    }
+}
+
+/// Error that can happen when using a [`BindlessDescriptorSet`].
+#[derive(Clone, Debug)]
+pub enum BindlessDescriptorSetError {
+    DescriptorSetUpdateError(DescriptorSetUpdateError),
+
+    /// All array elements of the binding are allocated, or are waiting for snapshots to be
+    /// dropped.
+    BindingFull {
+        binding_num: u32,
+    },
+
+    /// A snapshot of the descriptor set is still alive, but the binding was not created with
+    /// both `update_after_bind` and `update_unused_while_pending` enabled.
+    BindingNotUpdatable {
+        binding_num: u32,
+    },
+
+    /// The array element is used by a snapshot that is still alive.
+    ElementInUse {
+        binding_num: u32,
+        index: u32,
+    },
+
+    /// The array element is not currently allocated.
+    ElementNotAllocated {
+        binding_num: u32,
+        index: u32,
+    },
+}
+
+impl Error for BindlessDescriptorSetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::DescriptorSetUpdateError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for BindlessDescriptorSetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::DescriptorSetUpdateError(_) => {
+                write!(f, "an error occurred while updating the descriptor set")
+            }
+            Self::BindingFull { binding_num } => write!(
+                f,
+                "all array elements of binding {} are allocated, or are waiting for snapshots to \
+                be dropped",
+                binding_num,
+            ),
+            Self::BindingNotUpdatable { binding_num } => write!(
+                f,
+                "a snapshot of the descriptor set is still alive, but binding {} was not created \
+                with both `update_after_bind` and `update_unused_while_pending` enabled",
+                binding_num,
+            ),
+            Self::ElementInUse { binding_num, index } => write!(
+                f,
+                "array element {} of binding {} is used by a snapshot that is still alive",
+                index, binding_num,
+            ),
+            Self::ElementNotAllocated { binding_num, index } => write!(
+                f,
+                "array element {} of binding {} is not currently allocated",
+                index, binding_num,
+            ),
+        }
+    }
+}
+
+impl From<DescriptorSetUpdateError> for BindlessDescriptorSetError {
+    fn from(err: DescriptorSetUpdateError) -> Self {
+        Self::DescriptorSetUpdateError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BindlessDescriptorSet, BindlessDescriptorSetError};
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        descriptor_set::{
+            layout::{
+                DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+                DescriptorType,
+            },
+            WriteDescriptorSet,
+        },
+        memory::allocator::{AllocationCreateInfo, MemoryUsage, StandardMemoryAllocator},
+        shader::ShaderStages,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn slot_recycling() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let layout = DescriptorSetLayout::new(
+            device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        descriptor_count: 2,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::StorageBuffer)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let memory_allocator = StandardMemoryAllocator::new_default(device);
+        let buffer = Buffer::from_data(
+            &memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::STORAGE_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Upload,
+                ..Default::default()
+            },
+            0u32,
+        )
+        .unwrap();
+
+        let mut set = BindlessDescriptorSet::new(layout, 0).unwrap();
+        assert_eq!(set.allocate(0).unwrap(), 0);
+        assert_eq!(set.allocate(0).unwrap(), 1);
+        assert!(matches!(
+            set.allocate(0),
+            Err(BindlessDescriptorSetError::BindingFull { binding_num: 0 }),
+        ));
+
+        set.write([WriteDescriptorSet::buffer_array(0, 0, [buffer.clone()])])
+            .unwrap();
+
+        let snapshot = set.snapshot();
+        assert!(Arc::ptr_eq(&set.snapshot(), &snapshot));
+
+        // The binding can't be updated while the snapshot is alive.
+        assert!(matches!(
+            set.write([WriteDescriptorSet::buffer_array(0, 1, [buffer.clone()])]),
+            Err(BindlessDescriptorSetError::BindingNotUpdatable { binding_num: 0 }),
+        ));
+
+        // The freed element is not reused while the snapshot is alive.
+        set.free(0, 0).unwrap();
+        assert!(matches!(
+            set.free(0, 0),
+            Err(BindlessDescriptorSetError::ElementNotAllocated {
+                binding_num: 0,
+                index: 0,
+            }),
+        ));
+        assert!(set.allocate(0).is_err());
+
+        drop(snapshot);
+        assert_eq!(set.allocate(0).unwrap(), 0);
+        set.write([WriteDescriptorSet::buffer_array(0, 1, [buffer])])
+            .unwrap();
+    }
+}
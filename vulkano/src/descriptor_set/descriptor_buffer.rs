@@ -0,0 +1,490 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Descriptor sets that are stored directly in a buffer.
+//!
+//! With the [`ext_descriptor_buffer`] extension, descriptors do not need to be allocated from a
+//! descriptor pool. Instead, the descriptor data is written by the host into a buffer, which is
+//! then bound to a command buffer with [`bind_descriptor_buffers`]. Descriptor sets are selected
+//! by specifying an offset into one of the bound buffers with [`set_descriptor_buffer_offsets`].
+//!
+//! The descriptor set layouts used for this must have been created with
+//! [`descriptor_buffer`](super::layout::DescriptorSetLayoutCreateInfo::descriptor_buffer)
+//! enabled. The size that a descriptor set occupies in the buffer, and the offset of each binding,
+//! can be queried from the layout with [`DescriptorSetLayout::descriptor_buffer_size`] and
+//! [`DescriptorSetLayout::descriptor_buffer_binding_offset`].
+//!
+//! A [`DescriptorBufferSet`] writes descriptors into a region of a host-visible buffer, using the
+//! same [`WriteDescriptorSet`] values that are used for regular descriptor sets. Buffer
+//! descriptors are written using the device address of the buffer, so buffers that are written
+//! must have the [`SHADER_DEVICE_ADDRESS`](crate::buffer::BufferUsage::SHADER_DEVICE_ADDRESS)
+//! usage.
+//!
+//! [`ext_descriptor_buffer`]: crate::device::DeviceExtensions::ext_descriptor_buffer
+//! [`bind_descriptor_buffers`]: crate::command_buffer::AutoCommandBufferBuilder::bind_descriptor_buffers
+//! [`set_descriptor_buffer_offsets`]: crate::command_buffer::AutoCommandBufferBuilder::set_descriptor_buffer_offsets
+
+use super::{
+    check_descriptor_write,
+    layout::{DescriptorSetLayout, DescriptorType},
+    DescriptorSetResources, DescriptorSetUpdateError, WriteDescriptorSet,
+    WriteDescriptorSetElements,
+};
+use crate::{
+    buffer::{BufferError, Subbuffer},
+    device::{Device, DeviceOwned},
+    DeviceSize, VulkanObject,
+};
+use smallvec::SmallVec;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+/// Returns the size in bytes of a single descriptor of type `descriptor_type` in a descriptor
+/// buffer.
+///
+/// Returns `None` if the [`ext_descriptor_buffer`] extension is not enabled on `device`, or if
+/// descriptors of `descriptor_type` can not be stored in a descriptor buffer.
+///
+/// [`ext_descriptor_buffer`]: crate::device::DeviceExtensions::ext_descriptor_buffer
+pub fn descriptor_size(device: &Device, descriptor_type: DescriptorType) -> Option<DeviceSize> {
+    if !device.enabled_extensions().ext_descriptor_buffer {
+        return None;
+    }
+
+    let properties = device.physical_device().properties();
+    let robust = device.enabled_features().robust_buffer_access;
+
+    let size = match descriptor_type {
+        DescriptorType::Sampler => properties.sampler_descriptor_size,
+        DescriptorType::CombinedImageSampler => properties.combined_image_sampler_descriptor_size,
+        DescriptorType::SampledImage => properties.sampled_image_descriptor_size,
+        DescriptorType::StorageImage => properties.storage_image_descriptor_size,
+        DescriptorType::UniformTexelBuffer if robust => {
+            properties.robust_uniform_texel_buffer_descriptor_size
+        }
+        DescriptorType::UniformTexelBuffer => properties.uniform_texel_buffer_descriptor_size,
+        DescriptorType::StorageTexelBuffer if robust => {
+            properties.robust_storage_texel_buffer_descriptor_size
+        }
+        DescriptorType::StorageTexelBuffer => properties.storage_texel_buffer_descriptor_size,
+        DescriptorType::UniformBuffer if robust => properties.robust_uniform_buffer_descriptor_size,
+        DescriptorType::UniformBuffer => properties.uniform_buffer_descriptor_size,
+        DescriptorType::StorageBuffer if robust => properties.robust_storage_buffer_descriptor_size,
+        DescriptorType::StorageBuffer => properties.storage_buffer_descriptor_size,
+        DescriptorType::InputAttachment => properties.input_attachment_descriptor_size,
+        DescriptorType::AccelerationStructure => properties.acceleration_structure_descriptor_size,
+        DescriptorType::UniformBufferDynamic | DescriptorType::StorageBufferDynamic => None,
+    };
+
+    size.map(|size| size as DeviceSize)
+}
+
+/// A descriptor set whose descriptors are stored in a region of a descriptor buffer.
+///
+/// The descriptor set keeps the resources that were written to it alive, but the buffer region
+/// is not otherwise managed. It's up to the user to place descriptor sets at offsets that are
+/// multiples of the
+/// [`descriptor_buffer_offset_alignment`](crate::device::Properties::descriptor_buffer_offset_alignment)
+/// limit from the start of the bound descriptor buffer, and to keep the descriptor set alive for
+/// as long as the GPU can access it.
+pub struct DescriptorBufferSet {
+    buffer: Subbuffer<[u8]>,
+    layout: Arc<DescriptorSetLayout>,
+    variable_descriptor_count: u32,
+    resources: DescriptorSetResources,
+}
+
+impl DescriptorBufferSet {
+    /// Creates a new descriptor set with a variable descriptor count of 0, stored in `buffer`.
+    ///
+    /// See [`new_variable`](Self::new_variable) for more.
+    #[inline]
+    pub fn new(
+        buffer: Subbuffer<[u8]>,
+        layout: Arc<DescriptorSetLayout>,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+    ) -> Result<DescriptorBufferSet, DescriptorBufferSetError> {
+        Self::new_variable(buffer, layout, 0, descriptor_writes)
+    }
+
+    /// Creates a new descriptor set with the requested variable descriptor count, stored in
+    /// `buffer`, and writes `descriptor_writes` to it.
+    ///
+    /// `buffer` must be host-visible, and must be at least
+    /// [`layout.descriptor_buffer_size()`](DescriptorSetLayout::descriptor_buffer_size) bytes
+    /// large. The descriptors of any immutable samplers in `layout` are written automatically.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `buffer` and `layout` do not belong to the same device.
+    /// - Panics if `variable_descriptor_count` is too large for the given `layout`.
+    pub fn new_variable(
+        buffer: Subbuffer<[u8]>,
+        layout: Arc<DescriptorSetLayout>,
+        variable_descriptor_count: u32,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+    ) -> Result<DescriptorBufferSet, DescriptorBufferSetError> {
+        assert_eq!(buffer.device(), layout.device());
+
+        let max_count = layout.variable_descriptor_count();
+
+        assert!(
+            variable_descriptor_count <= max_count,
+            "the provided variable_descriptor_count ({}) is greater than the maximum number of \
+            variable count descriptors in the layout ({})",
+            variable_descriptor_count,
+            max_count,
+        );
+
+        if !layout.descriptor_buffer() {
+            return Err(DescriptorBufferSetError::LayoutNotDescriptorBuffer);
+        }
+
+        if layout.push_descriptor() {
+            return Err(DescriptorBufferSetError::LayoutPushDescriptor);
+        }
+
+        let required_size = layout.descriptor_buffer_size().unwrap();
+
+        if buffer.size() < required_size {
+            return Err(DescriptorBufferSetError::BufferTooSmall {
+                required_size,
+                provided_size: buffer.size(),
+            });
+        }
+
+        let mut set = DescriptorBufferSet {
+            resources: DescriptorSetResources::new(&layout, variable_descriptor_count),
+            buffer,
+            layout,
+            variable_descriptor_count,
+        };
+
+        // Unlike with descriptor pools, immutable samplers are not written implicitly.
+        let immutable_samplers: SmallVec<[_; 8]> = set
+            .layout
+            .bindings()
+            .iter()
+            .filter(|(_, binding)| binding.descriptor_type == DescriptorType::Sampler)
+            .flat_map(|(&binding_num, binding)| {
+                (0..)
+                    .zip(&binding.immutable_samplers)
+                    .map(move |(index, sampler)| {
+                        (
+                            DescriptorType::Sampler,
+                            binding_num,
+                            index,
+                            DescriptorData::Sampler(sampler.handle()),
+                        )
+                    })
+            })
+            .collect();
+
+        set.write_data(immutable_samplers)?;
+        set.write(descriptor_writes)?;
+
+        Ok(set)
+    }
+
+    /// Writes descriptors to the descriptor set, overwriting the previous contents of the
+    /// written array elements.
+    ///
+    /// The buffer must not be in use by the device.
+    pub fn write(
+        &mut self,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+    ) -> Result<(), DescriptorBufferSetError> {
+        let descriptor_writes: SmallVec<[_; 8]> = descriptor_writes.into_iter().collect();
+        let mut data: SmallVec<[_; 8]> = SmallVec::new();
+
+        for write in &descriptor_writes {
+            let layout_binding =
+                check_descriptor_write(write, &self.layout, self.variable_descriptor_count)?;
+            let descriptor_type = layout_binding.descriptor_type;
+            let binding_num = write.binding();
+            let first = write.first_array_element();
+
+            let elements: SmallVec<[_; 1]> = match write.elements() {
+                WriteDescriptorSetElements::None(_) => unreachable!(),
+                WriteDescriptorSetElements::Buffer(elements) => elements
+                    .iter()
+                    .map(|(buffer, range)| {
+                        Ok(DescriptorData::Address(ash::vk::DescriptorAddressInfoEXT {
+                            address: buffer.device_address()?.get() + range.start,
+                            range: range.end - range.start,
+                            format: ash::vk::Format::UNDEFINED,
+                            ..Default::default()
+                        }))
+                    })
+                    .collect::<Result<_, BufferError>>()?,
+                WriteDescriptorSetElements::BufferView(elements) => elements
+                    .iter()
+                    .map(|buffer_view| {
+                        let range = buffer_view.range();
+
+                        Ok(DescriptorData::Address(ash::vk::DescriptorAddressInfoEXT {
+                            address: buffer_view.buffer().device_address()?.get() + range.start,
+                            range: range.end - range.start,
+                            format: buffer_view.format().unwrap().into(),
+                            ..Default::default()
+                        }))
+                    })
+                    .collect::<Result<_, BufferError>>()?,
+                WriteDescriptorSetElements::ImageView(elements) => (first..)
+                    .zip(elements)
+                    .map(|(index, image_view)| {
+                        let layouts = image_view.image().descriptor_layouts().expect(
+                            "descriptor_layouts must return Some when used in an image view",
+                        );
+
+                        DescriptorData::Image(ash::vk::DescriptorImageInfo {
+                            // Combined image samplers can have immutable samplers.
+                            sampler: layout_binding
+                                .immutable_samplers
+                                .get(index as usize)
+                                .map_or_else(ash::vk::Sampler::null, |sampler| sampler.handle()),
+                            image_view: image_view.handle(),
+                            image_layout: layouts.layout_for(descriptor_type).into(),
+                        })
+                    })
+                    .collect(),
+                WriteDescriptorSetElements::ImageViewSampler(elements) => elements
+                    .iter()
+                    .map(|(image_view, sampler)| {
+                        let layouts = image_view.image().descriptor_layouts().expect(
+                            "descriptor_layouts must return Some when used in an image view",
+                        );
+
+                        DescriptorData::Image(ash::vk::DescriptorImageInfo {
+                            sampler: sampler.handle(),
+                            image_view: image_view.handle(),
+                            image_layout: layouts.layout_for(descriptor_type).into(),
+                        })
+                    })
+                    .collect(),
+                WriteDescriptorSetElements::Sampler(elements) => elements
+                    .iter()
+                    .map(|sampler| DescriptorData::Sampler(sampler.handle()))
+                    .collect(),
+                WriteDescriptorSetElements::AccelerationStructure(elements) => elements
+                    .iter()
+                    .map(|acceleration_structure| {
+                        DescriptorData::AccelerationStructure(
+                            acceleration_structure.device_address().get(),
+                        )
+                    })
+                    .collect(),
+            };
+
+            data.extend(
+                (first..)
+                    .zip(elements)
+                    .map(|(index, element)| (descriptor_type, binding_num, index, element)),
+            );
+        }
+
+        self.write_data(data)?;
+
+        for write in &descriptor_writes {
+            self.resources.update(write);
+        }
+
+        Ok(())
+    }
+
+    fn write_data(
+        &self,
+        data: impl IntoIterator<Item = (DescriptorType, u32, u32, DescriptorData)>,
+    ) -> Result<(), DescriptorBufferSetError> {
+        let mut data = data.into_iter().peekable();
+
+        if data.peek().is_none() {
+            return Ok(());
+        }
+
+        let device = self.layout.device();
+        let fns = device.fns();
+        let mut mapped = self.buffer.write()?;
+
+        for (descriptor_type, binding_num, index, data) in data {
+            let descriptor_size = descriptor_size(device, descriptor_type).unwrap();
+            let offset = self
+                .layout
+                .descriptor_buffer_binding_offset(binding_num)
+                .unwrap()
+                + index as DeviceSize * descriptor_size;
+            let dst = &mut mapped[offset as usize..(offset + descriptor_size) as usize];
+
+            let data_vk = match &data {
+                DescriptorData::Sampler(sampler) => {
+                    ash::vk::DescriptorDataEXT { p_sampler: sampler }
+                }
+                DescriptorData::Image(info) => match descriptor_type {
+                    DescriptorType::CombinedImageSampler => ash::vk::DescriptorDataEXT {
+                        p_combined_image_sampler: info,
+                    },
+                    DescriptorType::SampledImage => ash::vk::DescriptorDataEXT {
+                        p_sampled_image: info,
+                    },
+                    DescriptorType::StorageImage => ash::vk::DescriptorDataEXT {
+                        p_storage_image: info,
+                    },
+                    DescriptorType::InputAttachment => ash::vk::DescriptorDataEXT {
+                        p_input_attachment_image: info,
+                    },
+                    _ => unreachable!(),
+                },
+                DescriptorData::Address(info) => match descriptor_type {
+                    DescriptorType::UniformTexelBuffer => ash::vk::DescriptorDataEXT {
+                        p_uniform_texel_buffer: info,
+                    },
+                    DescriptorType::StorageTexelBuffer => ash::vk::DescriptorDataEXT {
+                        p_storage_texel_buffer: info,
+                    },
+                    DescriptorType::UniformBuffer => ash::vk::DescriptorDataEXT {
+                        p_uniform_buffer: info,
+                    },
+                    DescriptorType::StorageBuffer => ash::vk::DescriptorDataEXT {
+                        p_storage_buffer: info,
+                    },
+                    _ => unreachable!(),
+                },
+                &DescriptorData::AccelerationStructure(address) => ash::vk::DescriptorDataEXT {
+                    acceleration_structure: address,
+                },
+            };
+            let info_vk = ash::vk::DescriptorGetInfoEXT {
+                ty: descriptor_type.into(),
+                data: data_vk,
+                ..Default::default()
+            };
+
+            unsafe {
+                (fns.ext_descriptor_buffer.get_descriptor_ext)(
+                    device.handle(),
+                    &info_vk,
+                    dst.len(),
+                    dst.as_mut_ptr().cast(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the buffer that the descriptor set is stored in.
+    #[inline]
+    pub fn buffer(&self) -> &Subbuffer<[u8]> {
+        &self.buffer
+    }
+
+    /// Returns the layout of the descriptor set.
+    #[inline]
+    pub fn layout(&self) -> &Arc<DescriptorSetLayout> {
+        &self.layout
+    }
+
+    /// Returns the variable descriptor count that the descriptor set was created with.
+    #[inline]
+    pub fn variable_descriptor_count(&self) -> u32 {
+        self.variable_descriptor_count
+    }
+
+    /// Returns the resources that have been written to the descriptor set.
+    #[inline]
+    pub fn resources(&self) -> &DescriptorSetResources {
+        &self.resources
+    }
+}
+
+unsafe impl DeviceOwned for DescriptorBufferSet {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.layout.device()
+    }
+}
+
+enum DescriptorData {
+    Sampler(ash::vk::Sampler),
+    Image(ash::vk::DescriptorImageInfo),
+    Address(ash::vk::DescriptorAddressInfoEXT),
+    AccelerationStructure(ash::vk::DeviceAddress),
+}
+
+/// Error that can happen when writing a descriptor set to a descriptor buffer.
+#[derive(Clone, Debug)]
+pub enum DescriptorBufferSetError {
+    BufferError(BufferError),
+    DescriptorSetUpdateError(DescriptorSetUpdateError),
+
+    /// The buffer is smaller than the size of a descriptor set with the given layout.
+    BufferTooSmall {
+        required_size: DeviceSize,
+        provided_size: DeviceSize,
+    },
+
+    /// The descriptor set layout was not created with `descriptor_buffer` enabled.
+    LayoutNotDescriptorBuffer,
+
+    /// The descriptor set layout was created with `push_descriptor` enabled.
+    LayoutPushDescriptor,
+}
+
+impl Error for DescriptorBufferSetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::BufferError(err) => Some(err),
+            Self::DescriptorSetUpdateError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DescriptorBufferSetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::BufferError(_) => write!(f, "a buffer error occurred"),
+            Self::DescriptorSetUpdateError(_) => {
+                write!(f, "an error occurred while updating the descriptor set")
+            }
+            Self::BufferTooSmall {
+                required_size,
+                provided_size,
+            } => write!(
+                f,
+                "the buffer size ({}) is smaller than the size of a descriptor set with the given \
+                layout ({})",
+                provided_size, required_size,
+            ),
+            Self::LayoutNotDescriptorBuffer => write!(
+                f,
+                "the descriptor set layout was not created with `descriptor_buffer` enabled",
+            ),
+            Self::LayoutPushDescriptor => write!(
+                f,
+                "the descriptor set layout was created with `push_descriptor` enabled",
+            ),
+        }
+    }
+}
+
+impl From<BufferError> for DescriptorBufferSetError {
+    fn from(err: BufferError) -> Self {
+        Self::BufferError(err)
+    }
+}
+
+impl From<DescriptorSetUpdateError> for DescriptorBufferSetError {
+    fn from(err: DescriptorSetUpdateError) -> Self {
+        Self::DescriptorSetUpdateError(err)
+    }
+}
@@ -16,7 +16,7 @@ use crate::{
     macros::{impl_id_counter, vulkan_enum},
     sampler::Sampler,
     shader::{DescriptorBindingRequirements, ShaderStages},
-    OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
+    DeviceSize, OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
 use ahash::HashMap;
 use std::{
@@ -38,8 +38,12 @@ pub struct DescriptorSetLayout {
 
     bindings: BTreeMap<u32, DescriptorSetLayoutBinding>,
     push_descriptor: bool,
+    descriptor_buffer: bool,
+    update_after_bind_pool: bool,
 
     descriptor_counts: HashMap<DescriptorType, u32>,
+    descriptor_buffer_size: DeviceSize,
+    descriptor_buffer_binding_offsets: BTreeMap<u32, DeviceSize>,
 }
 
 impl DescriptorSetLayout {
@@ -60,6 +64,8 @@ impl DescriptorSetLayout {
         let &DescriptorSetLayoutCreateInfo {
             ref bindings,
             push_descriptor,
+            descriptor_buffer,
+            update_after_bind_pool,
             _ne: _,
         } = create_info;
 
@@ -75,6 +81,36 @@ impl DescriptorSetLayout {
             }
         }
 
+        if descriptor_buffer {
+            // VUID?
+            if !device.enabled_features().descriptor_buffer {
+                return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                    required_for: "`create_info.descriptor_buffer` is set",
+                    requires_one_of: RequiresOneOf {
+                        features: &["descriptor_buffer"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID?
+            if push_descriptor && !device.enabled_features().descriptor_buffer_push_descriptors {
+                return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                    required_for: "`create_info.push_descriptor` and \
+                        `create_info.descriptor_buffer` are both set",
+                    requires_one_of: RequiresOneOf {
+                        features: &["descriptor_buffer_push_descriptors"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID?
+            if update_after_bind_pool {
+                return Err(DescriptorSetLayoutCreationError::DescriptorBufferUpdateAfterBindPool);
+            }
+        }
+
         let mut descriptor_counts: HashMap<DescriptorType, u32> = HashMap::default();
         let highest_binding_num = bindings.keys().copied().next_back();
 
@@ -83,6 +119,9 @@ impl DescriptorSetLayout {
                 descriptor_type,
                 descriptor_count,
                 variable_descriptor_count,
+                update_after_bind,
+                update_unused_while_pending,
+                partially_bound,
                 stages,
                 ref immutable_samplers,
                 _ne: _,
@@ -119,6 +158,15 @@ impl DescriptorSetLayout {
                         },
                     );
                 }
+
+                // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-flags-03003
+                if update_after_bind || update_unused_while_pending {
+                    return Err(
+                        DescriptorSetLayoutCreationError::PushDescriptorUpdateAfterBind {
+                            binding_num,
+                        },
+                    );
+                }
             }
 
             if !immutable_samplers.is_empty() {
@@ -161,6 +209,20 @@ impl DescriptorSetLayout {
             // VUID-VkDescriptorSetLayoutBinding-descriptorType-01510
             // If descriptorType is VK_DESCRIPTOR_TYPE_INPUT_ATTACHMENT and descriptorCount is not 0, then stageFlags must be 0 or VK_SHADER_STAGE_FRAGMENT_BIT
 
+            if descriptor_buffer {
+                // VUID-VkDescriptorSetLayoutCreateInfo-flags-08000
+                if matches!(
+                    descriptor_type,
+                    DescriptorType::StorageBufferDynamic | DescriptorType::UniformBufferDynamic
+                ) {
+                    return Err(
+                        DescriptorSetLayoutCreationError::DescriptorBufferDescriptorTypeIncompatible {
+                            binding_num,
+                        },
+                    );
+                }
+            }
+
             if variable_descriptor_count {
                 // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingVariableDescriptorCount-03014
                 if !device
@@ -199,6 +261,125 @@ impl DescriptorSetLayout {
                     );
                 }
             }
+
+            if update_after_bind_pool {
+                // VUID-VkDescriptorSetLayoutCreateInfo-descriptorType-03001
+                if matches!(
+                    descriptor_type,
+                    DescriptorType::UniformBufferDynamic | DescriptorType::StorageBufferDynamic
+                ) {
+                    return Err(
+                        DescriptorSetLayoutCreationError::UpdateAfterBindPoolDescriptorTypeIncompatible {
+                            binding_num,
+                        },
+                    );
+                }
+            }
+
+            if update_after_bind {
+                // VUID-VkDescriptorSetLayoutCreateInfo-flags-03000
+                if !update_after_bind_pool {
+                    return Err(
+                        DescriptorSetLayoutCreationError::UpdateAfterBindPoolNotEnabled {
+                            binding_num,
+                        },
+                    );
+                }
+
+                let enabled_features = device.enabled_features();
+                let (feature_enabled, required_features) = match descriptor_type {
+                    DescriptorType::UniformBuffer => (
+                        // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUniformBufferUpdateAfterBind-03005
+                        enabled_features.descriptor_binding_uniform_buffer_update_after_bind,
+                        &["descriptor_binding_uniform_buffer_update_after_bind"],
+                    ),
+                    DescriptorType::Sampler
+                    | DescriptorType::CombinedImageSampler
+                    | DescriptorType::SampledImage => (
+                        // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingSampledImageUpdateAfterBind-03006
+                        enabled_features.descriptor_binding_sampled_image_update_after_bind,
+                        &["descriptor_binding_sampled_image_update_after_bind"],
+                    ),
+                    DescriptorType::StorageImage => (
+                        // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageImageUpdateAfterBind-03007
+                        enabled_features.descriptor_binding_storage_image_update_after_bind,
+                        &["descriptor_binding_storage_image_update_after_bind"],
+                    ),
+                    DescriptorType::StorageBuffer => (
+                        // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageBufferUpdateAfterBind-03008
+                        enabled_features.descriptor_binding_storage_buffer_update_after_bind,
+                        &["descriptor_binding_storage_buffer_update_after_bind"],
+                    ),
+                    DescriptorType::UniformTexelBuffer => (
+                        // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUniformTexelBufferUpdateAfterBind-03009
+                        enabled_features.descriptor_binding_uniform_texel_buffer_update_after_bind,
+                        &["descriptor_binding_uniform_texel_buffer_update_after_bind"],
+                    ),
+                    DescriptorType::StorageTexelBuffer => (
+                        // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageTexelBufferUpdateAfterBind-03010
+                        enabled_features.descriptor_binding_storage_texel_buffer_update_after_bind,
+                        &["descriptor_binding_storage_texel_buffer_update_after_bind"],
+                    ),
+                    DescriptorType::AccelerationStructure => (
+                        // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingAccelerationStructureUpdateAfterBind-03570
+                        enabled_features
+                            .descriptor_binding_acceleration_structure_update_after_bind,
+                        &["descriptor_binding_acceleration_structure_update_after_bind"],
+                    ),
+                    DescriptorType::UniformBufferDynamic
+                    | DescriptorType::StorageBufferDynamic
+                    | DescriptorType::InputAttachment => {
+                        // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-None-03011
+                        return Err(
+                            DescriptorSetLayoutCreationError::UpdateAfterBindDescriptorTypeIncompatible {
+                                binding_num,
+                            },
+                        );
+                    }
+                };
+
+                if !feature_enabled {
+                    return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                        required_for: "`create_info.bindings` has an element where \
+                            `update_after_bind` is set",
+                        requires_one_of: RequiresOneOf {
+                            features: required_features,
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+
+            if update_unused_while_pending {
+                // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUpdateUnusedWhilePending-03012
+                if !device
+                    .enabled_features()
+                    .descriptor_binding_update_unused_while_pending
+                {
+                    return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                        required_for: "`create_info.bindings` has an element where \
+                            `update_unused_while_pending` is set",
+                        requires_one_of: RequiresOneOf {
+                            features: &["descriptor_binding_update_unused_while_pending"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+
+            if partially_bound {
+                // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingPartiallyBound-03013
+                if !device.enabled_features().descriptor_binding_partially_bound {
+                    return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                        required_for: "`create_info.bindings` has an element where \
+                            `partially_bound` is set",
+                        requires_one_of: RequiresOneOf {
+                            features: &["descriptor_binding_partially_bound"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
         }
 
         // VUID-VkDescriptorSetLayoutCreateInfo-flags-00281
@@ -233,6 +414,8 @@ impl DescriptorSetLayout {
         let &DescriptorSetLayoutCreateInfo {
             ref bindings,
             push_descriptor,
+            descriptor_buffer,
+            update_after_bind_pool,
             _ne: _,
         } = &create_info;
 
@@ -245,6 +428,14 @@ impl DescriptorSetLayout {
             flags |= ash::vk::DescriptorSetLayoutCreateFlags::PUSH_DESCRIPTOR_KHR;
         }
 
+        if descriptor_buffer {
+            flags |= ash::vk::DescriptorSetLayoutCreateFlags::DESCRIPTOR_BUFFER_EXT;
+        }
+
+        if update_after_bind_pool {
+            flags |= ash::vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL;
+        }
+
         for (&binding_num, binding) in bindings.iter() {
             let mut binding_flags = ash::vk::DescriptorBindingFlags::empty();
 
@@ -267,6 +458,18 @@ impl DescriptorSetLayout {
                 binding_flags |= ash::vk::DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT;
             }
 
+            if binding.update_after_bind {
+                binding_flags |= ash::vk::DescriptorBindingFlags::UPDATE_AFTER_BIND;
+            }
+
+            if binding.update_unused_while_pending {
+                binding_flags |= ash::vk::DescriptorBindingFlags::UPDATE_UNUSED_WHILE_PENDING;
+            }
+
+            if binding.partially_bound {
+                binding_flags |= ash::vk::DescriptorBindingFlags::PARTIALLY_BOUND;
+            }
+
             // VUID-VkDescriptorSetLayoutCreateInfo-binding-00279
             // Guaranteed by BTreeMap
             bindings_vk.push(ash::vk::DescriptorSetLayoutBinding {
@@ -336,6 +539,8 @@ impl DescriptorSetLayout {
         let DescriptorSetLayoutCreateInfo {
             bindings,
             push_descriptor,
+            descriptor_buffer,
+            update_after_bind_pool,
             _ne: _,
         } = create_info;
 
@@ -348,13 +553,42 @@ impl DescriptorSetLayout {
             }
         }
 
+        let mut descriptor_buffer_size = 0;
+        let mut descriptor_buffer_binding_offsets = BTreeMap::new();
+
+        if descriptor_buffer {
+            let fns = device.fns();
+
+            (fns.ext_descriptor_buffer.get_descriptor_set_layout_size_ext)(
+                device.handle(),
+                handle,
+                &mut descriptor_buffer_size,
+            );
+
+            for &binding_num in bindings.keys() {
+                let mut offset = 0;
+                (fns.ext_descriptor_buffer
+                    .get_descriptor_set_layout_binding_offset_ext)(
+                    device.handle(),
+                    handle,
+                    binding_num,
+                    &mut offset,
+                );
+                descriptor_buffer_binding_offsets.insert(binding_num, offset);
+            }
+        }
+
         Arc::new(DescriptorSetLayout {
             handle,
             device,
             id: Self::next_id(),
             bindings,
             push_descriptor,
+            descriptor_buffer,
+            update_after_bind_pool,
             descriptor_counts,
+            descriptor_buffer_size,
+            descriptor_buffer_binding_offsets,
         })
     }
 
@@ -375,6 +609,43 @@ impl DescriptorSetLayout {
         self.push_descriptor
     }
 
+    /// Returns whether the descriptor set layout is for descriptor sets that are stored in a
+    /// descriptor buffer.
+    #[inline]
+    pub fn descriptor_buffer(&self) -> bool {
+        self.descriptor_buffer
+    }
+
+    /// Returns whether descriptor sets with this layout must be allocated from a descriptor pool
+    /// that was created with `update_after_bind` enabled.
+    #[inline]
+    pub fn update_after_bind_pool(&self) -> bool {
+        self.update_after_bind_pool
+    }
+
+    /// If the descriptor set layout is for descriptor buffers, returns the number of bytes that a
+    /// descriptor set with this layout occupies in a descriptor buffer.
+    ///
+    /// If the highest-numbered binding has a variable count, this is the size for the maximum
+    /// count in the layout.
+    #[inline]
+    pub fn descriptor_buffer_size(&self) -> Option<DeviceSize> {
+        self.descriptor_buffer
+            .then_some(self.descriptor_buffer_size)
+    }
+
+    /// If the descriptor set layout is for descriptor buffers, returns the offset in bytes of
+    /// binding `binding_num` from the start of a descriptor set with this layout.
+    ///
+    /// Returns `None` if the layout is not for descriptor buffers, or if `binding_num` does not
+    /// exist in the layout.
+    #[inline]
+    pub fn descriptor_buffer_binding_offset(&self, binding_num: u32) -> Option<DeviceSize> {
+        self.descriptor_buffer_binding_offsets
+            .get(&binding_num)
+            .copied()
+    }
+
     /// Returns the number of descriptors of each type.
     ///
     /// The map is guaranteed to not contain any elements with a count of `0`.
@@ -408,7 +679,10 @@ impl DescriptorSetLayout {
     #[inline]
     pub fn is_compatible_with(&self, other: &DescriptorSetLayout) -> bool {
         self == other
-            || (self.bindings == other.bindings && self.push_descriptor == other.push_descriptor)
+            || (self.bindings == other.bindings
+                && self.push_descriptor == other.push_descriptor
+                && self.descriptor_buffer == other.descriptor_buffer
+                && self.update_after_bind_pool == other.update_after_bind_pool)
     }
 }
 
@@ -455,6 +729,12 @@ pub enum DescriptorSetLayoutCreationError {
         requires_one_of: RequiresOneOf,
     },
 
+    /// `descriptor_buffer` is enabled, but a binding has an incompatible `descriptor_type`.
+    DescriptorBufferDescriptorTypeIncompatible { binding_num: u32 },
+
+    /// `descriptor_buffer` and `update_after_bind_pool` are both enabled.
+    DescriptorBufferUpdateAfterBindPool,
+
     /// A binding includes immutable samplers but their number differs from  `descriptor_count`.
     ImmutableSamplersCountMismatch {
         binding_num: u32,
@@ -472,9 +752,22 @@ pub enum DescriptorSetLayoutCreationError {
     /// `push_descriptor` is enabled, but a binding has an incompatible `descriptor_type`.
     PushDescriptorDescriptorTypeIncompatible { binding_num: u32 },
 
+    /// `push_descriptor` is enabled, but a binding has `update_after_bind` or
+    /// `update_unused_while_pending` enabled.
+    PushDescriptorUpdateAfterBind { binding_num: u32 },
+
     /// `push_descriptor` is enabled, but a binding has `variable_descriptor_count` enabled.
     PushDescriptorVariableDescriptorCount { binding_num: u32 },
 
+    /// A binding has `update_after_bind` enabled, but it has an incompatible `descriptor_type`.
+    UpdateAfterBindDescriptorTypeIncompatible { binding_num: u32 },
+
+    /// `update_after_bind_pool` is enabled, but a binding has an incompatible `descriptor_type`.
+    UpdateAfterBindPoolDescriptorTypeIncompatible { binding_num: u32 },
+
+    /// A binding has `update_after_bind` enabled, but `update_after_bind_pool` is not enabled.
+    UpdateAfterBindPoolNotEnabled { binding_num: u32 },
+
     /// A binding has `variable_descriptor_count` enabled, but it is not the highest-numbered
     /// binding.
     VariableDescriptorCountBindingNotHighest {
@@ -509,6 +802,16 @@ impl Display for DescriptorSetLayoutCreationError {
                 "a requirement was not met for: {}; requires one of: {}",
                 required_for, requires_one_of,
             ),
+            Self::DescriptorBufferDescriptorTypeIncompatible { binding_num } => write!(
+                f,
+                "`descriptor_buffer` is enabled, but binding {} has an incompatible \
+                `descriptor_type`",
+                binding_num,
+            ),
+            Self::DescriptorBufferUpdateAfterBindPool => write!(
+                f,
+                "`descriptor_buffer` and `update_after_bind_pool` are both enabled",
+            ),
             Self::ImmutableSamplersCountMismatch {
                 binding_num,
                 sampler_count,
@@ -540,12 +843,36 @@ impl Display for DescriptorSetLayoutCreationError {
                 `descriptor_type`",
                 binding_num,
             ),
+            Self::PushDescriptorUpdateAfterBind { binding_num } => write!(
+                f,
+                "`push_descriptor` is enabled, but binding {} has `update_after_bind` or \
+                `update_unused_while_pending` enabled",
+                binding_num,
+            ),
             Self::PushDescriptorVariableDescriptorCount { binding_num } => write!(
                 f,
                 "`push_descriptor` is enabled, but binding {} has `variable_descriptor_count` \
                 enabled",
                 binding_num,
             ),
+            Self::UpdateAfterBindDescriptorTypeIncompatible { binding_num } => write!(
+                f,
+                "binding {} has `update_after_bind` enabled, but it has an incompatible \
+                `descriptor_type`",
+                binding_num,
+            ),
+            Self::UpdateAfterBindPoolDescriptorTypeIncompatible { binding_num } => write!(
+                f,
+                "`update_after_bind_pool` is enabled, but binding {} has an incompatible \
+                `descriptor_type`",
+                binding_num,
+            ),
+            Self::UpdateAfterBindPoolNotEnabled { binding_num } => write!(
+                f,
+                "binding {} has `update_after_bind` enabled, but `update_after_bind_pool` is not \
+                enabled",
+                binding_num,
+            ),
             Self::VariableDescriptorCountBindingNotHighest {
                 binding_num,
                 highest_binding_num,
@@ -602,6 +929,41 @@ pub struct DescriptorSetLayoutCreateInfo {
     /// The default value is `false`.
     pub push_descriptor: bool,
 
+    /// Whether the descriptor set layout should be created for descriptor sets that are stored in
+    /// a descriptor buffer.
+    ///
+    /// If `true`, descriptors are not allocated from a descriptor pool, but are written directly
+    /// into a buffer using [`DescriptorBufferSet`], and the buffer is then bound with
+    /// [`bind_descriptor_buffers`] and [`set_descriptor_buffer_offsets`]. The layout can not be
+    /// used to allocate regular descriptor sets.
+    ///
+    /// If set to `true`, the [`descriptor_buffer`](crate::device::Features::descriptor_buffer)
+    /// feature must be enabled on the device, and there must be no bindings with a type of
+    /// [`DescriptorType::UniformBufferDynamic`] or [`DescriptorType::StorageBufferDynamic`].
+    /// If `push_descriptor` is also set, the
+    /// [`descriptor_buffer_push_descriptors`](crate::device::Features::descriptor_buffer_push_descriptors)
+    /// feature must be enabled as well.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`DescriptorBufferSet`]: crate::descriptor_set::descriptor_buffer::DescriptorBufferSet
+    /// [`bind_descriptor_buffers`]: crate::command_buffer::AutoCommandBufferBuilder::bind_descriptor_buffers
+    /// [`set_descriptor_buffer_offsets`]: crate::command_buffer::AutoCommandBufferBuilder::set_descriptor_buffer_offsets
+    pub descriptor_buffer: bool,
+
+    /// Whether descriptor sets with this layout must be allocated from a descriptor pool that
+    /// was created with [`update_after_bind`] enabled.
+    ///
+    /// This must be set to `true` if any of the bindings has `update_after_bind` enabled. If set
+    /// to `true`, there must be no bindings with a type of
+    /// [`DescriptorType::UniformBufferDynamic`] or [`DescriptorType::StorageBufferDynamic`], and
+    /// `descriptor_buffer` must be `false`.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`update_after_bind`]: crate::descriptor_set::pool::DescriptorPoolCreateInfo::update_after_bind
+    pub update_after_bind_pool: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -611,6 +973,8 @@ impl Default for DescriptorSetLayoutCreateInfo {
         Self {
             bindings: BTreeMap::new(),
             push_descriptor: false,
+            descriptor_buffer: false,
+            update_after_bind_pool: false,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -646,6 +1010,42 @@ pub struct DescriptorSetLayoutBinding {
     /// [`descriptor_binding_variable_descriptor_count`]: crate::device::Features::descriptor_binding_variable_descriptor_count
     pub variable_descriptor_count: bool,
 
+    /// Whether the descriptors in the binding can be updated after a descriptor set with this
+    /// layout has been bound to a command buffer, without invalidating the command buffer. The
+    /// updates are then visible to command buffers that are submitted afterwards.
+    ///
+    /// If set to `true`, the `update_after_bind_pool` of the layout must also be `true`, and
+    /// the `descriptor_binding_*_update_after_bind` feature corresponding to `descriptor_type`
+    /// must be enabled. The `descriptor_type` must not be
+    /// [`DescriptorType::UniformBufferDynamic`], [`DescriptorType::StorageBufferDynamic`] or
+    /// [`DescriptorType::InputAttachment`].
+    ///
+    /// The default value is `false`.
+    pub update_after_bind: bool,
+
+    /// Whether descriptors in the binding that are not used by any pending command buffer can be
+    /// updated while a descriptor set with this layout is in use.
+    ///
+    /// If set to `true`, the [`descriptor_binding_update_unused_while_pending`] feature must be
+    /// enabled.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`descriptor_binding_update_unused_while_pending`]: crate::device::Features::descriptor_binding_update_unused_while_pending
+    pub update_unused_while_pending: bool,
+
+    /// Whether descriptors in the binding that are not dynamically used by shaders are allowed to
+    /// be left unwritten.
+    ///
+    /// If set to `true`, the [`descriptor_binding_partially_bound`] feature must be enabled.
+    /// Vulkano will then not check that every element of the binding is written when the binding
+    /// is used in a dispatch or draw command.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`descriptor_binding_partially_bound`]: crate::device::Features::descriptor_binding_partially_bound
+    pub partially_bound: bool,
+
     /// Which shader stages are going to access the descriptors in this binding.
     ///
     /// The default value is [`ShaderStages::empty()`], which must be overridden.
@@ -673,6 +1073,9 @@ impl DescriptorSetLayoutBinding {
             descriptor_type,
             descriptor_count: 1,
             variable_descriptor_count: false,
+            update_after_bind: false,
+            update_unused_while_pending: false,
+            partially_bound: false,
             stages: ShaderStages::empty(),
             immutable_samplers: Vec::new(),
             _ne: crate::NonExhaustive(()),
@@ -731,6 +1134,9 @@ impl From<&DescriptorBindingRequirements> for DescriptorSetLayoutBinding {
             descriptor_type: reqs.descriptor_types[0],
             descriptor_count: reqs.descriptor_count.unwrap_or(0),
             variable_descriptor_count: false,
+            update_after_bind: false,
+            update_unused_while_pending: false,
+            partially_bound: false,
             stages: reqs.stages,
             immutable_samplers: Vec::new(),
             _ne: crate::NonExhaustive(()),
@@ -833,11 +1239,11 @@ vulkan_enum! {
         device_extensions: [ext_inline_uniform_block],
     },*/
 
-    /* TODO: enable
-    // TODO: document
+    /// Gives read-only access to an acceleration structure, for performing ray queries and ray
+    /// tracing.
     AccelerationStructure = ACCELERATION_STRUCTURE_KHR {
         device_extensions: [khr_acceleration_structure],
-    },*/
+    },
 
     /* TODO: enable
     // TODO: document
@@ -869,9 +1275,10 @@ mod tests {
     use crate::{
         descriptor_set::layout::{
             DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
-            DescriptorType,
+            DescriptorSetLayoutCreationError, DescriptorType,
         },
         shader::ShaderStages,
+        RequiresOneOf,
     };
     use ahash::HashMap;
 
@@ -908,4 +1315,76 @@ mod tests {
                 .collect::<HashMap<_, _>>(),
         );
     }
+
+    #[test]
+    fn missing_feature_descriptor_buffer() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                descriptor_buffer: true,
+                ..Default::default()
+            },
+        ) {
+            Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                requires_one_of: RequiresOneOf { features, .. },
+                ..
+            }) if features.contains(&"descriptor_buffer") => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn update_after_bind_pool_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        update_after_bind: true,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::SampledImage)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        ) {
+            Err(DescriptorSetLayoutCreationError::UpdateAfterBindPoolNotEnabled {
+                binding_num: 0,
+            }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn missing_feature_partially_bound() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        partially_bound: true,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::SampledImage)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        ) {
+            Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                requires_one_of: RequiresOneOf { features, .. },
+                ..
+            }) if features.contains(&"descriptor_binding_partially_bound") => (),
+            _ => panic!(),
+        }
+    }
 }
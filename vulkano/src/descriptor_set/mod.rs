@@ -86,6 +86,7 @@ pub use self::{
 };
 use self::{layout::DescriptorSetLayout, sys::UnsafeDescriptorSet};
 use crate::{
+    acceleration_structure::AccelerationStructure,
     buffer::{view::BufferView, Subbuffer},
     descriptor_set::layout::DescriptorType,
     device::DeviceOwned,
@@ -105,7 +106,9 @@ use std::{
 };
 
 pub mod allocator;
+pub mod bindless;
 mod collection;
+pub mod descriptor_buffer;
 pub mod layout;
 pub mod persistent;
 pub mod pool;
@@ -202,7 +205,7 @@ impl DescriptorSetInner {
 
         if !write_descriptor_set.is_empty() {
             for (info, write) in descriptor_write_info
-                .iter()
+                .iter_mut()
                 .zip(write_descriptor_set.iter_mut())
             {
                 match info {
@@ -218,6 +221,15 @@ impl DescriptorSetInner {
                         write.descriptor_count = info.len() as u32;
                         write.p_texel_buffer_view = info.as_ptr();
                     }
+                    DescriptorWriteInfo::AccelerationStructure(info, info_vk) => {
+                        *info_vk = ash::vk::WriteDescriptorSetAccelerationStructureKHR {
+                            acceleration_structure_count: info.len() as u32,
+                            p_acceleration_structures: info.as_ptr(),
+                            ..Default::default()
+                        };
+                        write.descriptor_count = info.len() as u32;
+                        write.p_next = info_vk as *const _ as *const _;
+                    }
                 }
             }
         }
@@ -308,6 +320,9 @@ impl DescriptorSetResources {
                             DescriptorBindingResources::None(smallvec![Some(()); count])
                         }
                     }
+                    DescriptorType::AccelerationStructure => {
+                        DescriptorBindingResources::AccelerationStructure(smallvec![None; count])
+                    }
                 };
                 (binding_num, binding_resources)
             })
@@ -336,6 +351,50 @@ impl DescriptorSetResources {
     pub fn binding(&self, binding: u32) -> Option<&DescriptorBindingResources> {
         self.binding_resources.get(&binding)
     }
+
+    /// Marks a range of elements of a binding as no longer written, releasing the resources that
+    /// were bound to them.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `binding` does not exist in the resources, or if the range goes out of bounds.
+    pub(crate) fn invalidate(&mut self, binding: u32, elements: Range<usize>) {
+        fn invalidate_resources<T>(resources: &mut [Option<T>], elements: Range<usize>) {
+            resources
+                .get_mut(elements)
+                .expect("descriptor invalidation for binding out of bounds")
+                .iter_mut()
+                .for_each(|resource| *resource = None);
+        }
+
+        match self
+            .binding_resources
+            .get_mut(&binding)
+            .expect("descriptor invalidation has invalid binding number")
+        {
+            DescriptorBindingResources::None(resources) => {
+                invalidate_resources(resources, elements)
+            }
+            DescriptorBindingResources::Buffer(resources) => {
+                invalidate_resources(resources, elements)
+            }
+            DescriptorBindingResources::BufferView(resources) => {
+                invalidate_resources(resources, elements)
+            }
+            DescriptorBindingResources::ImageView(resources) => {
+                invalidate_resources(resources, elements)
+            }
+            DescriptorBindingResources::ImageViewSampler(resources) => {
+                invalidate_resources(resources, elements)
+            }
+            DescriptorBindingResources::Sampler(resources) => {
+                invalidate_resources(resources, elements)
+            }
+            DescriptorBindingResources::AccelerationStructure(resources) => {
+                invalidate_resources(resources, elements)
+            }
+        }
+    }
 }
 
 /// The resources that are bound to a single descriptor set binding.
@@ -347,6 +406,7 @@ pub enum DescriptorBindingResources {
     ImageView(Elements<Arc<dyn ImageViewAbstract>>),
     ImageViewSampler(Elements<(Arc<dyn ImageViewAbstract>, Arc<Sampler>)>),
     Sampler(Elements<Arc<Sampler>>),
+    AccelerationStructure(Elements<Arc<AccelerationStructure>>),
 }
 
 type Elements<T> = SmallVec<[Option<T>; 1]>;
@@ -406,6 +466,10 @@ impl DescriptorBindingResources {
                 DescriptorBindingResources::Sampler(resources),
                 WriteDescriptorSetElements::Sampler(elements),
             ) => write_resources(first, resources, elements),
+            (
+                DescriptorBindingResources::AccelerationStructure(resources),
+                WriteDescriptorSetElements::AccelerationStructure(elements),
+            ) => write_resources(first, resources, elements),
             _ => panic!(
                 "descriptor write for binding {} has wrong resource type",
                 write.binding(),
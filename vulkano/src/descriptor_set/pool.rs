@@ -42,6 +42,7 @@ pub struct DescriptorPool {
     max_sets: u32,
     pool_sizes: HashMap<DescriptorType, u32>,
     can_free_descriptor_sets: bool,
+    update_after_bind: bool,
     // Unimplement `Sync`, as Vulkan descriptor pools are not thread safe.
     _marker: PhantomData<Cell<ash::vk::DescriptorPool>>,
 }
@@ -62,6 +63,7 @@ impl DescriptorPool {
             max_sets,
             pool_sizes,
             can_free_descriptor_sets,
+            update_after_bind,
             _ne: _,
         } = create_info;
 
@@ -91,6 +93,10 @@ impl DescriptorPool {
                 flags |= ash::vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET;
             }
 
+            if update_after_bind {
+                flags |= ash::vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND;
+            }
+
             let create_info = ash::vk::DescriptorPoolCreateInfo {
                 flags,
                 max_sets,
@@ -121,6 +127,7 @@ impl DescriptorPool {
             max_sets,
             pool_sizes,
             can_free_descriptor_sets,
+            update_after_bind,
             _marker: PhantomData,
         })
     }
@@ -141,6 +148,7 @@ impl DescriptorPool {
             max_sets,
             pool_sizes,
             can_free_descriptor_sets,
+            update_after_bind,
             _ne: _,
         } = create_info;
 
@@ -151,6 +159,7 @@ impl DescriptorPool {
             max_sets,
             pool_sizes,
             can_free_descriptor_sets,
+            update_after_bind,
             _marker: PhantomData,
         }
     }
@@ -173,6 +182,13 @@ impl DescriptorPool {
         self.can_free_descriptor_sets
     }
 
+    /// Returns whether descriptor sets with a layout that has `update_after_bind_pool` enabled
+    /// can be allocated from the pool.
+    #[inline]
+    pub fn update_after_bind(&self) -> bool {
+        self.update_after_bind
+    }
+
     /// Allocates descriptor sets from the pool, one for each element in `create_info`.
     /// Returns an iterator to the allocated sets, or an error.
     ///
@@ -189,6 +205,8 @@ impl DescriptorPool {
     ///
     /// - The total descriptors of the layouts must fit in the pool.
     /// - The total number of descriptor sets allocated from the pool must not overflow the pool.
+    /// - If one of the layouts has `update_after_bind_pool` enabled, then the pool must have been
+    ///   created with `update_after_bind` enabled.
     /// - You must ensure that the allocated descriptor sets are no longer in use when the pool
     ///   is destroyed, as destroying the pool is equivalent to freeing all the sets.
     pub unsafe fn allocate_descriptor_sets<'a>(
@@ -201,6 +219,8 @@ impl DescriptorPool {
                 .map(|info| {
                     assert_eq!(self.device.handle(), info.layout.device().handle(),);
                     debug_assert!(!info.layout.push_descriptor());
+                    // VUID-VkDescriptorSetAllocateInfo-pSetLayouts-03044
+                    debug_assert!(!info.layout.update_after_bind_pool() || self.update_after_bind);
                     debug_assert!(
                         info.variable_descriptor_count <= info.layout.variable_descriptor_count()
                     );
@@ -368,6 +388,13 @@ pub struct DescriptorPoolCreateInfo {
     /// The default value is `false`.
     pub can_free_descriptor_sets: bool,
 
+    /// Whether descriptor sets with a layout that has
+    /// [`update_after_bind_pool`](crate::descriptor_set::layout::DescriptorSetLayoutCreateInfo::update_after_bind_pool)
+    /// enabled can be allocated from the pool.
+    ///
+    /// The default value is `false`.
+    pub update_after_bind: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -378,6 +405,7 @@ impl Default for DescriptorPoolCreateInfo {
             max_sets: 0,
             pool_sizes: HashMap::default(),
             can_free_descriptor_sets: false,
+            update_after_bind: false,
             _ne: crate::NonExhaustive(()),
         }
     }
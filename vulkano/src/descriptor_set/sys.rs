@@ -61,53 +61,7 @@ impl UnsafeDescriptorSet {
         layout: &DescriptorSetLayout,
         writes: impl IntoIterator<Item = &'a WriteDescriptorSet>,
     ) {
-        let (infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = writes
-            .into_iter()
-            .map(|write| {
-                let descriptor_type = layout.bindings()[&write.binding()].descriptor_type;
-
-                (
-                    write.to_vulkan_info(descriptor_type),
-                    write.to_vulkan(self.handle, descriptor_type),
-                )
-            })
-            .unzip();
-
-        // It is forbidden to call `vkUpdateDescriptorSets` with 0 writes, so we need to perform
-        // this emptiness check.
-        if writes.is_empty() {
-            return;
-        }
-
-        // Set the info pointers separately.
-        for (info, write) in infos.iter().zip(writes.iter_mut()) {
-            match info {
-                DescriptorWriteInfo::Image(info) => {
-                    write.descriptor_count = info.len() as u32;
-                    write.p_image_info = info.as_ptr();
-                }
-                DescriptorWriteInfo::Buffer(info) => {
-                    write.descriptor_count = info.len() as u32;
-                    write.p_buffer_info = info.as_ptr();
-                }
-                DescriptorWriteInfo::BufferView(info) => {
-                    write.descriptor_count = info.len() as u32;
-                    write.p_texel_buffer_view = info.as_ptr();
-                }
-            }
-
-            debug_assert!(write.descriptor_count != 0);
-        }
-
-        let fns = layout.device().fns();
-
-        (fns.v1_0.update_descriptor_sets)(
-            layout.device().handle(),
-            writes.len() as u32,
-            writes.as_ptr(),
-            0,
-            ptr::null(),
-        );
+        write_descriptor_set(self.handle, layout, writes)
     }
 
     // TODO: add copying from other descriptor sets
@@ -131,3 +85,71 @@ impl Debug for UnsafeDescriptorSet {
 }
 
 impl_id_counter!(UnsafeDescriptorSet);
+
+/// Performs the writes on the descriptor set with the given handle.
+///
+/// # Safety
+///
+/// The same requirements apply as for [`UnsafeDescriptorSet::write`].
+pub(crate) unsafe fn write_descriptor_set<'a>(
+    handle: ash::vk::DescriptorSet,
+    layout: &DescriptorSetLayout,
+    writes: impl IntoIterator<Item = &'a WriteDescriptorSet>,
+) {
+    let (mut infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = writes
+        .into_iter()
+        .map(|write| {
+            let descriptor_type = layout.bindings()[&write.binding()].descriptor_type;
+
+            (
+                write.to_vulkan_info(descriptor_type),
+                write.to_vulkan(handle, descriptor_type),
+            )
+        })
+        .unzip();
+
+    // It is forbidden to call `vkUpdateDescriptorSets` with 0 writes, so we need to perform
+    // this emptiness check.
+    if writes.is_empty() {
+        return;
+    }
+
+    // Set the info pointers separately.
+    for (info, write) in infos.iter_mut().zip(writes.iter_mut()) {
+        match info {
+            DescriptorWriteInfo::Image(info) => {
+                write.descriptor_count = info.len() as u32;
+                write.p_image_info = info.as_ptr();
+            }
+            DescriptorWriteInfo::Buffer(info) => {
+                write.descriptor_count = info.len() as u32;
+                write.p_buffer_info = info.as_ptr();
+            }
+            DescriptorWriteInfo::BufferView(info) => {
+                write.descriptor_count = info.len() as u32;
+                write.p_texel_buffer_view = info.as_ptr();
+            }
+            DescriptorWriteInfo::AccelerationStructure(info, info_vk) => {
+                *info_vk = ash::vk::WriteDescriptorSetAccelerationStructureKHR {
+                    acceleration_structure_count: info.len() as u32,
+                    p_acceleration_structures: info.as_ptr(),
+                    ..Default::default()
+                };
+                write.descriptor_count = info.len() as u32;
+                write.p_next = info_vk as *const _ as *const _;
+            }
+        }
+
+        debug_assert!(write.descriptor_count != 0);
+    }
+
+    let fns = layout.device().fns();
+
+    (fns.v1_0.update_descriptor_sets)(
+        layout.device().handle(),
+        writes.len() as u32,
+        writes.as_ptr(),
+        0,
+        ptr::null(),
+    );
+}
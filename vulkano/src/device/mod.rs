@@ -108,15 +108,23 @@ pub use self::{
     properties::Properties,
     queue::{Queue, QueueError, QueueFamilyProperties, QueueFlags, QueueGuard},
 };
+use crate::{
+    acceleration_structure::{
+        AccelerationStructureBuildGeometryInfo, AccelerationStructureBuildRangeInfo,
+        AccelerationStructureBuildSizesInfo, AccelerationStructureBuildType,
+        AccelerationStructureError, CopyAccelerationStructureInfo,
+    },
+    deferred::DeferredOperation,
+    instance::Instance,
+    macros::impl_id_counter,
+    memory::ExternalMemoryHandleType,
+    DeviceSize, OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
+};
 pub use crate::{
     device::extensions::DeviceExtensions,
     extensions::{ExtensionRestriction, ExtensionRestrictionError},
     fns::DeviceFunctions,
 };
-use crate::{
-    instance::Instance, macros::impl_id_counter, memory::ExternalMemoryHandleType, OomError,
-    RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
-};
 use ash::vk::Handle;
 use parking_lot::Mutex;
 use smallvec::SmallVec;
@@ -130,9 +138,10 @@ use std::{
     ops::Deref,
     ptr,
     sync::{
-        atomic::{AtomicU32, Ordering},
+        atomic::{AtomicU32, AtomicU64, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 pub(crate) mod extensions;
@@ -159,6 +168,9 @@ pub struct Device {
     // This is required for validation in `memory::device_memory`, the count must only be modified
     // in that module.
     pub(crate) allocation_count: AtomicU32,
+    // The number of bytes of `DeviceMemory` allocated from each memory heap. The same rules apply
+    // as for `allocation_count`.
+    pub(crate) memory_heap_usage: [AtomicU64; ash::vk::MAX_MEMORY_HEAPS],
     fence_pool: Mutex<Vec<ash::vk::Fence>>,
     semaphore_pool: Mutex<Vec<ash::vk::Semaphore>>,
     event_pool: Mutex<Vec<ash::vk::Event>>,
@@ -415,6 +427,7 @@ impl Device {
             enabled_features,
             active_queue_family_indices,
             allocation_count: AtomicU32::new(0),
+            memory_heap_usage: Default::default(),
             fence_pool: Mutex::new(Vec::new()),
             semaphore_pool: Mutex::new(Vec::new()),
             event_pool: Mutex::new(Vec::new()),
@@ -499,6 +512,27 @@ impl Device {
         self.allocation_count.load(Ordering::Acquire)
     }
 
+    /// Returns the current number of bytes of [`DeviceMemory`] that the device has allocated from
+    /// the memory heap with index `heap_index`.
+    ///
+    /// This only includes memory that was allocated through vulkano. See also
+    /// [`PhysicalDevice::memory_budget`], which reports the usage of the whole process.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `heap_index` is not less than the number of memory heaps of the physical
+    ///   device.
+    ///
+    /// [`DeviceMemory`]: crate::memory::DeviceMemory
+    #[inline]
+    pub fn memory_heap_usage(&self, heap_index: u32) -> DeviceSize {
+        assert!(
+            (heap_index as usize) < self.physical_device.memory_properties().memory_heaps.len()
+        );
+
+        self.memory_heap_usage[heap_index as usize].load(Ordering::Acquire)
+    }
+
     pub(crate) fn fence_pool(&self) -> &Mutex<Vec<ash::vk::Fence>> {
         &self.fence_pool
     }
@@ -511,6 +545,372 @@ impl Device {
         &self.event_pool
     }
 
+    /// For the given acceleration structure build info and primitive counts, returns the
+    /// minimum size required to build the acceleration structure, and the minimum size of the
+    /// scratch buffer used during the build operation.
+    ///
+    /// The [`acceleration_structure`] feature must be enabled on the device.
+    ///
+    /// `build_info` does not need to have its `dst_acceleration_structure` or `scratch_data`
+    /// set, and the data in its geometries may be `None`. `max_primitive_counts` must contain
+    /// one element for each geometry in `build_info`, specifying the maximum number of primitives
+    /// that will be built for that geometry.
+    ///
+    /// [`acceleration_structure`]: Features::acceleration_structure
+    #[inline]
+    pub fn acceleration_structure_build_sizes(
+        &self,
+        build_type: AccelerationStructureBuildType,
+        build_info: &AccelerationStructureBuildGeometryInfo,
+        max_primitive_counts: &[u32],
+    ) -> Result<AccelerationStructureBuildSizesInfo, AccelerationStructureError> {
+        self.validate_acceleration_structure_build_sizes(
+            build_type,
+            build_info,
+            max_primitive_counts,
+        )?;
+
+        unsafe {
+            Ok(self.acceleration_structure_build_sizes_unchecked(
+                build_type,
+                build_info,
+                max_primitive_counts,
+            ))
+        }
+    }
+
+    fn validate_acceleration_structure_build_sizes(
+        &self,
+        build_type: AccelerationStructureBuildType,
+        build_info: &AccelerationStructureBuildGeometryInfo,
+        max_primitive_counts: &[u32],
+    ) -> Result<(), AccelerationStructureError> {
+        // VUID-vkGetAccelerationStructureBuildSizesKHR-accelerationStructure-08933
+        if !self.enabled_features().acceleration_structure {
+            return Err(AccelerationStructureError::RequirementNotMet {
+                required_for: "`Device::acceleration_structure_build_sizes`",
+                requires_one_of: RequiresOneOf {
+                    features: &["acceleration_structure"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkGetAccelerationStructureBuildSizesKHR-buildType-parameter
+        build_type.validate_device(self)?;
+
+        // VUID-vkGetAccelerationStructureBuildSizesKHR-pBuildInfo-parameter
+        build_info.validate(self, build_type, None)?;
+
+        // VUID-vkGetAccelerationStructureBuildSizesKHR-pBuildInfo-03619
+        if max_primitive_counts.len() != build_info.geometries.len() {
+            return Err(AccelerationStructureError::PrimitiveCountsCountMismatch {
+                geometry_count: build_info.geometries.len() as u64,
+                primitive_counts_count: max_primitive_counts.len() as u64,
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn acceleration_structure_build_sizes_unchecked(
+        &self,
+        build_type: AccelerationStructureBuildType,
+        build_info: &AccelerationStructureBuildGeometryInfo,
+        max_primitive_counts: &[u32],
+    ) -> AccelerationStructureBuildSizesInfo {
+        let (mut build_info_vk, geometries_vk) = build_info.to_vulkan(false);
+        build_info_vk.p_geometries = geometries_vk.as_ptr();
+
+        let mut build_sizes_info_vk = ash::vk::AccelerationStructureBuildSizesInfoKHR::default();
+
+        let fns = self.fns();
+        (fns.khr_acceleration_structure
+            .get_acceleration_structure_build_sizes_khr)(
+            self.handle,
+            build_type.into(),
+            &build_info_vk,
+            max_primitive_counts.as_ptr(),
+            &mut build_sizes_info_vk,
+        );
+
+        AccelerationStructureBuildSizesInfo {
+            acceleration_structure_size: build_sizes_info_vk.acceleration_structure_size,
+            update_scratch_size: build_sizes_info_vk.update_scratch_size,
+            build_scratch_size: build_sizes_info_vk.build_scratch_size,
+        }
+    }
+
+    /// Returns whether a serialized acceleration structure with the specified version data
+    /// is compatible with this device.
+    ///
+    /// The version data is the first `2 * UUID_SIZE` bytes of data that was serialized with
+    /// [`CopyAccelerationStructureMode::Serialize`].
+    ///
+    /// The [`acceleration_structure`] feature must be enabled on the device.
+    ///
+    /// [`CopyAccelerationStructureMode::Serialize`]: crate::acceleration_structure::CopyAccelerationStructureMode::Serialize
+    /// [`acceleration_structure`]: Features::acceleration_structure
+    #[inline]
+    pub fn acceleration_structure_is_compatible(
+        &self,
+        version_data: &[u8; 2 * ash::vk::UUID_SIZE],
+    ) -> Result<bool, AccelerationStructureError> {
+        // VUID-vkGetDeviceAccelerationStructureCompatibilityKHR-accelerationStructure-08928
+        if !self.enabled_features().acceleration_structure {
+            return Err(AccelerationStructureError::RequirementNotMet {
+                required_for: "`Device::acceleration_structure_is_compatible`",
+                requires_one_of: RequiresOneOf {
+                    features: &["acceleration_structure"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let version_info_vk = ash::vk::AccelerationStructureVersionInfoKHR {
+            p_version_data: version_data,
+            ..Default::default()
+        };
+        let mut compatibility_vk = ash::vk::AccelerationStructureCompatibilityKHR::default();
+
+        unsafe {
+            let fns = self.fns();
+            (fns.khr_acceleration_structure
+                .get_device_acceleration_structure_compatibility_khr)(
+                self.handle,
+                &version_info_vk,
+                &mut compatibility_vk,
+            );
+        }
+
+        Ok(compatibility_vk == ash::vk::AccelerationStructureCompatibilityKHR::COMPATIBLE)
+    }
+
+    /// Acquires the profiling lock, which must be held while recording and executing command
+    /// buffers that use [`QueryType::Performance`] queries.
+    ///
+    /// If the lock is held by another process, this waits until it is released or until
+    /// `timeout` expires. If `timeout` is `None`, this waits indefinitely.
+    ///
+    /// The [`performance_counter_query_pools`] feature must be enabled on the device.
+    ///
+    /// [`QueryType::Performance`]: crate::query::QueryType::Performance
+    /// [`performance_counter_query_pools`]: Features::performance_counter_query_pools
+    #[inline]
+    pub fn acquire_profiling_lock(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<(), ProfilingLockError> {
+        self.validate_acquire_profiling_lock()?;
+
+        unsafe { self.acquire_profiling_lock_unchecked(timeout) }
+    }
+
+    fn validate_acquire_profiling_lock(&self) -> Result<(), ProfilingLockError> {
+        if !self.enabled_features().performance_counter_query_pools {
+            return Err(ProfilingLockError::RequirementNotMet {
+                required_for: "`Device::acquire_profiling_lock`",
+                requires_one_of: RequiresOneOf {
+                    features: &["performance_counter_query_pools"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn acquire_profiling_lock_unchecked(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<(), ProfilingLockError> {
+        let timeout_ns = timeout.map_or(u64::MAX, |timeout| {
+            timeout
+                .as_secs()
+                .saturating_mul(1_000_000_000)
+                .saturating_add(timeout.subsec_nanos() as u64)
+        });
+
+        let info_vk = ash::vk::AcquireProfilingLockInfoKHR {
+            flags: ash::vk::AcquireProfilingLockFlagsKHR::empty(),
+            timeout: timeout_ns,
+            ..Default::default()
+        };
+
+        let fns = self.fns();
+        match (fns.khr_performance_query.acquire_profiling_lock_khr)(self.handle, &info_vk) {
+            ash::vk::Result::SUCCESS => Ok(()),
+            ash::vk::Result::TIMEOUT => Err(ProfilingLockError::Timeout),
+            err => Err(VulkanError::from(err).into()),
+        }
+    }
+
+    /// Builds an acceleration structure on the host.
+    ///
+    /// The [`acceleration_structure_host_commands`] feature must be enabled on the device. All
+    /// buffers that are used by the build must be mapped host-visible memory.
+    ///
+    /// If `deferred_operation` is `Some`, then the implementation may defer the build, and this
+    /// function may return before the build is complete. Use the deferred operation to wait for
+    /// it to finish.
+    ///
+    /// # Safety
+    ///
+    /// - The buffers referenced by `info` must not be accessed by the device or by other host
+    ///   operations while the build is in progress.
+    /// - The contents of the geometry buffers must be valid for `build_range_infos`.
+    /// - If `deferred_operation` is `Some`, then `info` and all the objects it refers to must
+    ///   be kept alive until the deferred operation has completed.
+    ///
+    /// [`acceleration_structure_host_commands`]: Features::acceleration_structure_host_commands
+    #[inline]
+    pub unsafe fn build_acceleration_structure(
+        &self,
+        deferred_operation: Option<Arc<DeferredOperation>>,
+        info: &AccelerationStructureBuildGeometryInfo,
+        build_range_infos: &[AccelerationStructureBuildRangeInfo],
+    ) -> Result<(), AccelerationStructureError> {
+        // VUID-vkBuildAccelerationStructuresKHR-accelerationStructureHostCommands-03581
+        if !self.enabled_features().acceleration_structure_host_commands {
+            return Err(AccelerationStructureError::RequirementNotMet {
+                required_for: "`Device::build_acceleration_structure`",
+                requires_one_of: RequiresOneOf {
+                    features: &["acceleration_structure_host_commands"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if let Some(deferred_operation) = &deferred_operation {
+            assert_eq!(self, deferred_operation.device().as_ref());
+        }
+
+        info.validate(
+            self,
+            AccelerationStructureBuildType::Host,
+            Some(build_range_infos),
+        )?;
+
+        Ok(self.build_acceleration_structure_unchecked(
+            deferred_operation,
+            info,
+            build_range_infos,
+        )?)
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn build_acceleration_structure_unchecked(
+        &self,
+        deferred_operation: Option<Arc<DeferredOperation>>,
+        info: &AccelerationStructureBuildGeometryInfo,
+        build_range_infos: &[AccelerationStructureBuildRangeInfo],
+    ) -> Result<(), VulkanError> {
+        let (mut info_vk, geometries_vk) = info.to_vulkan(true);
+        info_vk.p_geometries = geometries_vk.as_ptr();
+
+        let build_range_infos_vk: SmallVec<[_; 8]> = build_range_infos
+            .iter()
+            .copied()
+            .map(ash::vk::AccelerationStructureBuildRangeInfoKHR::from)
+            .collect();
+
+        let fns = self.fns();
+        let result = (fns
+            .khr_acceleration_structure
+            .build_acceleration_structures_khr)(
+            self.handle,
+            deferred_operation
+                .as_ref()
+                .map_or_else(ash::vk::DeferredOperationKHR::null, |op| op.handle()),
+            1,
+            &info_vk,
+            &build_range_infos_vk.as_ptr(),
+        );
+
+        match result {
+            ash::vk::Result::SUCCESS
+            | ash::vk::Result::OPERATION_DEFERRED_KHR
+            | ash::vk::Result::OPERATION_NOT_DEFERRED_KHR => Ok(()),
+            err => Err(VulkanError::from(err)),
+        }
+    }
+
+    /// Copies an acceleration structure into another on the host.
+    ///
+    /// The [`acceleration_structure_host_commands`] feature must be enabled on the device. Both
+    /// acceleration structures must be stored in mapped host-visible memory.
+    ///
+    /// If `deferred_operation` is `Some`, then the implementation may defer the copy, and this
+    /// function may return before the copy is complete. Use the deferred operation to wait for
+    /// it to finish.
+    ///
+    /// # Safety
+    ///
+    /// - `info.src` must have been built, and must not be accessed by the device or by other
+    ///   host operations while the copy is in progress.
+    /// - If `deferred_operation` is `Some`, then `info` and all the objects it refers to must
+    ///   be kept alive until the deferred operation has completed.
+    ///
+    /// [`acceleration_structure_host_commands`]: Features::acceleration_structure_host_commands
+    #[inline]
+    pub unsafe fn copy_acceleration_structure(
+        &self,
+        deferred_operation: Option<Arc<DeferredOperation>>,
+        info: &CopyAccelerationStructureInfo,
+    ) -> Result<(), AccelerationStructureError> {
+        // VUID-vkCopyAccelerationStructureKHR-accelerationStructureHostCommands-03582
+        if !self.enabled_features().acceleration_structure_host_commands {
+            return Err(AccelerationStructureError::RequirementNotMet {
+                required_for: "`Device::copy_acceleration_structure`",
+                requires_one_of: RequiresOneOf {
+                    features: &["acceleration_structure_host_commands"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if let Some(deferred_operation) = &deferred_operation {
+            assert_eq!(self, deferred_operation.device().as_ref());
+        }
+
+        info.validate(self, true)?;
+
+        Ok(self.copy_acceleration_structure_unchecked(deferred_operation, info)?)
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn copy_acceleration_structure_unchecked(
+        &self,
+        deferred_operation: Option<Arc<DeferredOperation>>,
+        info: &CopyAccelerationStructureInfo,
+    ) -> Result<(), VulkanError> {
+        let info_vk = info.to_vulkan();
+
+        let fns = self.fns();
+        let result = (fns
+            .khr_acceleration_structure
+            .copy_acceleration_structure_khr)(
+            self.handle,
+            deferred_operation
+                .as_ref()
+                .map_or_else(ash::vk::DeferredOperationKHR::null, |op| op.handle()),
+            &info_vk,
+        );
+
+        match result {
+            ash::vk::Result::SUCCESS
+            | ash::vk::Result::OPERATION_DEFERRED_KHR
+            | ash::vk::Result::OPERATION_NOT_DEFERRED_KHR => Ok(()),
+            err => Err(VulkanError::from(err)),
+        }
+    }
+
     /// Retrieves the properties of an external file descriptor when imported as a given external
     /// handle type.
     ///
@@ -521,7 +921,6 @@ impl Device {
     /// # Safety
     ///
     /// - `file` must be a handle to external memory that was created outside the Vulkan API.
-    #[cfg_attr(not(unix), allow(unused_variables))]
     #[inline]
     pub unsafe fn memory_fd_properties(
         &self,
@@ -532,28 +931,40 @@ impl Device {
             return Err(MemoryFdPropertiesError::NotSupported);
         }
 
+        // VUID-vkGetMemoryFdPropertiesKHR-handleType-parameter
+        handle_type.validate_device(self)?;
+
+        // VUID-vkGetMemoryFdPropertiesKHR-handleType-00674
+        if handle_type == ExternalMemoryHandleType::OpaqueFd {
+            return Err(MemoryFdPropertiesError::InvalidExternalHandleType);
+        }
+
+        Ok(self.memory_fd_properties_unchecked(handle_type, &file)?)
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    #[inline]
+    pub unsafe fn memory_fd_properties_unchecked(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+        file: &File,
+    ) -> Result<MemoryFdProperties, VulkanError> {
         #[cfg(not(unix))]
         unreachable!("`khr_external_memory_fd` was somehow enabled on a non-Unix system");
 
         #[cfg(unix)]
         {
-            use std::os::unix::io::IntoRawFd;
-
-            // VUID-vkGetMemoryFdPropertiesKHR-handleType-parameter
-            handle_type.validate_device(self)?;
-
-            // VUID-vkGetMemoryFdPropertiesKHR-handleType-00674
-            if handle_type == ExternalMemoryHandleType::OpaqueFd {
-                return Err(MemoryFdPropertiesError::InvalidExternalHandleType);
-            }
+            use std::os::unix::io::AsRawFd;
 
             let mut memory_fd_properties = ash::vk::MemoryFdPropertiesKHR::default();
 
+            // The implementation does not take ownership of the file descriptor.
             let fns = self.fns();
             (fns.khr_external_memory_fd.get_memory_fd_properties_khr)(
                 self.handle,
                 handle_type.into(),
-                file.into_raw_fd(),
+                file.as_raw_fd(),
                 &mut memory_fd_properties,
             )
             .result()
@@ -565,6 +976,22 @@ impl Device {
         }
     }
 
+    /// Releases the profiling lock that was previously acquired with
+    /// [`acquire_profiling_lock`](Self::acquire_profiling_lock).
+    ///
+    /// # Safety
+    ///
+    /// - The profiling lock must currently be held by this device.
+    /// - No command buffers that use [`QueryType::Performance`] queries may be recording or
+    ///   executing.
+    ///
+    /// [`QueryType::Performance`]: crate::query::QueryType::Performance
+    #[inline]
+    pub unsafe fn release_profiling_lock(&self) {
+        let fns = self.fns();
+        (fns.khr_performance_query.release_profiling_lock_khr)(self.handle);
+    }
+
     /// Assigns a human-readable name to `object` for debugging purposes.
     ///
     /// If `object_name` is `None`, a previously set object name is removed.
@@ -910,6 +1337,49 @@ impl From<RequirementNotMet> for MemoryFdPropertiesError {
     }
 }
 
+/// Error that can happen when calling [`Device::acquire_profiling_lock`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfilingLockError {
+    /// No memory available on the host.
+    OutOfHostMemory,
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// The timeout expired before the lock could be acquired.
+    Timeout,
+}
+
+impl Error for ProfilingLockError {}
+
+impl Display for ProfilingLockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::OutOfHostMemory => write!(f, "no memory available on the host"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::Timeout => write!(f, "the timeout expired before the lock could be acquired"),
+        }
+    }
+}
+
+impl From<VulkanError> for ProfilingLockError {
+    fn from(err: VulkanError) -> Self {
+        match err {
+            VulkanError::OutOfHostMemory => Self::OutOfHostMemory,
+            _ => panic!("Unexpected error value"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::device::{
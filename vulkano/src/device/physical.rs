@@ -12,21 +12,23 @@ use crate::{
     buffer::{ExternalBufferInfo, ExternalBufferProperties},
     cache::OnceCache,
     device::{properties::Properties, DeviceExtensions, Features, FeaturesFfi, PropertiesFfi},
-    format::{Format, FormatProperties},
+    format::{DrmFormatModifierProperties, Format, FormatProperties},
     image::{
-        ImageAspects, ImageFormatInfo, ImageFormatProperties, ImageUsage, SparseImageFormatInfo,
-        SparseImageFormatProperties,
+        ImageAspects, ImageDrmFormatModifierInfo, ImageFormatInfo, ImageFormatProperties,
+        ImageTiling, ImageUsage, SparseImageFormatInfo, SparseImageFormatProperties,
     },
     instance::Instance,
     macros::{impl_id_counter, vulkan_bitflags, vulkan_enum},
-    memory::{ExternalMemoryHandleType, MemoryProperties},
+    memory::{ExternalMemoryHandleType, MemoryHeapBudget, MemoryProperties},
+    query::{PerformanceCounter, QueryPoolPerformanceCreateInfo},
     swapchain::{
         ColorSpace, FullScreenExclusive, PresentMode, Surface, SurfaceApi, SurfaceCapabilities,
         SurfaceInfo, SurfaceTransforms,
     },
     sync::{
         fence::{ExternalFenceInfo, ExternalFenceProperties},
-        semaphore::{ExternalSemaphoreInfo, ExternalSemaphoreProperties},
+        semaphore::{ExternalSemaphoreInfo, ExternalSemaphoreProperties, SemaphoreType},
+        Sharing,
     },
     ExtensionProperties, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
@@ -81,6 +83,7 @@ pub struct PhysicalDevice {
     external_fence_properties: OnceCache<ExternalFenceInfo, ExternalFenceProperties>,
     external_semaphore_properties: OnceCache<ExternalSemaphoreInfo, ExternalSemaphoreProperties>,
     format_properties: OnceCache<Format, FormatProperties>,
+    drm_format_modifier_properties: OnceCache<Format, Vec<DrmFormatModifierProperties>>,
     image_format_properties: OnceCache<ImageFormatInfo, Option<ImageFormatProperties>>,
     sparse_image_format_properties:
         OnceCache<SparseImageFormatInfo, Vec<SparseImageFormatProperties>>,
@@ -144,6 +147,7 @@ impl PhysicalDevice {
             external_fence_properties: OnceCache::new(),
             external_semaphore_properties: OnceCache::new(),
             format_properties: OnceCache::new(),
+            drm_format_modifier_properties: OnceCache::new(),
             image_format_properties: OnceCache::new(),
             sparse_image_format_properties: OnceCache::new(),
         }))
@@ -742,12 +746,16 @@ impl PhysicalDevice {
 
         let &ExternalSemaphoreInfo {
             handle_type,
+            semaphore_type,
             _ne: _,
         } = info;
 
         // VUID-VkPhysicalDeviceExternalSemaphoreInfo-handleType-parameter
         handle_type.validate_physical_device(self)?;
 
+        // VUID-VkSemaphoreTypeCreateInfo-semaphoreType-parameter
+        semaphore_type.validate_physical_device(self)?;
+
         Ok(())
     }
 
@@ -763,13 +771,26 @@ impl PhysicalDevice {
 
                 let &ExternalSemaphoreInfo {
                     handle_type,
+                    semaphore_type,
                     _ne: _,
                 } = info;
 
-                let external_semaphore_info = ash::vk::PhysicalDeviceExternalSemaphoreInfo {
+                let mut external_semaphore_info = ash::vk::PhysicalDeviceExternalSemaphoreInfo {
                     handle_type: handle_type.into(),
                     ..Default::default()
                 };
+                let mut semaphore_type_create_info = None;
+
+                if semaphore_type != SemaphoreType::Binary {
+                    let next =
+                        semaphore_type_create_info.insert(ash::vk::SemaphoreTypeCreateInfo {
+                            semaphore_type: semaphore_type.into(),
+                            ..Default::default()
+                        });
+
+                    next.p_next = external_semaphore_info.p_next;
+                    external_semaphore_info.p_next = next as *const _ as *const _;
+                }
 
                 /* Output */
 
@@ -901,6 +922,114 @@ impl PhysicalDevice {
         })
     }
 
+    /// Retrieves the DRM format modifiers that are supported by this physical device for a
+    /// format, along with their properties.
+    ///
+    /// The [`ext_image_drm_format_modifier`] extension must be supported by the physical device.
+    ///
+    /// The results of this function are cached, so that future calls with the same arguments
+    /// do not need to make a call to the Vulkan API again.
+    ///
+    /// [`ext_image_drm_format_modifier`]: crate::device::DeviceExtensions::ext_image_drm_format_modifier
+    #[inline]
+    pub fn drm_format_modifier_properties(
+        &self,
+        format: Format,
+    ) -> Result<Vec<DrmFormatModifierProperties>, PhysicalDeviceError> {
+        self.validate_drm_format_modifier_properties(format)?;
+
+        unsafe { Ok(self.drm_format_modifier_properties_unchecked(format)) }
+    }
+
+    fn validate_drm_format_modifier_properties(
+        &self,
+        format: Format,
+    ) -> Result<(), PhysicalDeviceError> {
+        if !(self.supported_extensions().ext_image_drm_format_modifier
+            && (self.api_version() >= Version::V1_1
+                || self
+                    .instance
+                    .enabled_extensions()
+                    .khr_get_physical_device_properties2))
+        {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`PhysicalDevice::drm_format_modifier_properties`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_image_drm_format_modifier"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkGetPhysicalDeviceFormatProperties2-format-parameter
+        format.validate_physical_device(self)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn drm_format_modifier_properties_unchecked(
+        &self,
+        format: Format,
+    ) -> Vec<DrmFormatModifierProperties> {
+        self.drm_format_modifier_properties
+            .get_or_insert(format, |&format| {
+                let fns = self.instance.fns();
+                let get_format_properties2 =
+                    |format_properties2: &mut ash::vk::FormatProperties2| {
+                        if self.api_version() >= Version::V1_1 {
+                            (fns.v1_1.get_physical_device_format_properties2)(
+                                self.handle,
+                                format.into(),
+                                format_properties2,
+                            );
+                        } else {
+                            (fns.khr_get_physical_device_properties2
+                                .get_physical_device_format_properties2_khr)(
+                                self.handle,
+                                format.into(),
+                                format_properties2,
+                            );
+                        }
+                    };
+
+                let mut modifier_properties_list =
+                    ash::vk::DrmFormatModifierPropertiesListEXT::default();
+                let mut format_properties2 = ash::vk::FormatProperties2 {
+                    p_next: &mut modifier_properties_list as *mut _ as *mut _,
+                    ..Default::default()
+                };
+                get_format_properties2(&mut format_properties2);
+
+                let mut modifier_properties =
+                    Vec::with_capacity(modifier_properties_list.drm_format_modifier_count as usize);
+                modifier_properties_list.p_drm_format_modifier_properties =
+                    modifier_properties.as_mut_ptr();
+                let mut format_properties2 = ash::vk::FormatProperties2 {
+                    p_next: &mut modifier_properties_list as *mut _ as *mut _,
+                    ..Default::default()
+                };
+                get_format_properties2(&mut format_properties2);
+                modifier_properties
+                    .set_len(modifier_properties_list.drm_format_modifier_count as usize);
+
+                modifier_properties
+                    .into_iter()
+                    .map(|properties: ash::vk::DrmFormatModifierPropertiesEXT| {
+                        DrmFormatModifierProperties {
+                            drm_format_modifier: properties.drm_format_modifier,
+                            drm_format_modifier_plane_count: properties
+                                .drm_format_modifier_plane_count,
+                            drm_format_modifier_tiling_features: properties
+                                .drm_format_modifier_tiling_features
+                                .into(),
+                        }
+                    })
+                    .collect()
+            })
+    }
+
     /// Returns the properties supported for images with a given image configuration.
     ///
     /// `Some` is returned if the configuration is supported, `None` if it is not.
@@ -911,6 +1040,9 @@ impl PhysicalDevice {
     /// # Panics
     ///
     /// - Panics if `image_format_info.format` is `None`.
+    /// - Panics if `image_format_info.drm_format_modifier_info` is `Some` but
+    ///   `image_format_info.tiling` is not [`ImageTiling::DrmFormatModifier`], or the other way
+    ///   around.
     #[inline]
     pub fn image_format_properties(
         &self,
@@ -935,6 +1067,7 @@ impl PhysicalDevice {
             mut stencil_usage,
             external_memory_handle_type,
             image_view_type,
+            ref drm_format_modifier_info,
             _ne: _,
         } = image_format_info;
 
@@ -1023,6 +1156,39 @@ impl PhysicalDevice {
             image_view_type.validate_physical_device(self)?;
         }
 
+        // VUID-VkPhysicalDeviceImageFormatInfo2-tiling-02249
+        assert_eq!(
+            tiling == ImageTiling::DrmFormatModifier,
+            drm_format_modifier_info.is_some(),
+        );
+
+        if let Some(drm_format_modifier_info) = drm_format_modifier_info {
+            if !self.supported_extensions().ext_image_drm_format_modifier {
+                return Err(PhysicalDeviceError::RequirementNotMet {
+                    required_for: "`image_format_info.drm_format_modifier_info` is `Some`",
+                    requires_one_of: RequiresOneOf {
+                        device_extensions: &["ext_image_drm_format_modifier"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            if let Sharing::Concurrent(queue_family_indices) = &drm_format_modifier_info.sharing {
+                // VUID-VkPhysicalDeviceImageDrmFormatModifierInfoEXT-sharingMode-02315
+                assert!(queue_family_indices.len() >= 2);
+
+                for &queue_family_index in queue_family_indices {
+                    // VUID-VkPhysicalDeviceImageDrmFormatModifierInfoEXT-sharingMode-02316
+                    if queue_family_index >= self.queue_family_properties.len() as u32 {
+                        return Err(PhysicalDeviceError::QueueFamilyIndexOutOfRange {
+                            queue_family_index,
+                            queue_family_count: self.queue_family_properties.len() as u32,
+                        });
+                    }
+                }
+            }
+        }
+
         // TODO:  VUID-VkPhysicalDeviceImageFormatInfo2-tiling-02313
         // Currently there is nothing in Vulkano for for adding a VkImageFormatListCreateInfo.
 
@@ -1064,6 +1230,7 @@ impl PhysicalDevice {
                     stencil_usage,
                     external_memory_handle_type,
                     image_view_type,
+                    ref drm_format_modifier_info,
                     _ne: _,
                 } = image_format_info;
 
@@ -1080,6 +1247,7 @@ impl PhysicalDevice {
                 let mut external_info_vk = None;
                 let mut image_view_info_vk = None;
                 let mut stencil_usage_info_vk = None;
+                let mut drm_format_modifier_info_vk = None;
 
                 if let Some(handle_type) = external_memory_handle_type {
                     let next =
@@ -1114,6 +1282,37 @@ impl PhysicalDevice {
                     info2_vk.p_next = next as *const _ as *const _;
                 }
 
+                if let Some(drm_format_modifier_info) = drm_format_modifier_info {
+                    let ImageDrmFormatModifierInfo {
+                        drm_format_modifier,
+                        ref sharing,
+                        _ne: _,
+                    } = *drm_format_modifier_info;
+
+                    let (sharing_mode, queue_family_index_count, p_queue_family_indices) =
+                        match sharing {
+                            Sharing::Exclusive => (ash::vk::SharingMode::EXCLUSIVE, 0, ptr::null()),
+                            Sharing::Concurrent(queue_family_indices) => (
+                                ash::vk::SharingMode::CONCURRENT,
+                                queue_family_indices.len() as u32,
+                                queue_family_indices.as_ptr(),
+                            ),
+                        };
+
+                    let next = drm_format_modifier_info_vk.insert(
+                        ash::vk::PhysicalDeviceImageDrmFormatModifierInfoEXT {
+                            drm_format_modifier,
+                            sharing_mode,
+                            queue_family_index_count,
+                            p_queue_family_indices,
+                            ..Default::default()
+                        },
+                    );
+
+                    next.p_next = info2_vk.p_next as *mut _;
+                    info2_vk.p_next = next as *const _ as *const _;
+                }
+
                 /* Output */
 
                 let mut properties2_vk = ash::vk::ImageFormatProperties2::default();
@@ -1203,6 +1402,88 @@ impl PhysicalDevice {
             })
     }
 
+    /// Retrieves the current memory budget and usage of each memory heap of the physical device.
+    ///
+    /// The returned `Vec` has one element for each element of
+    /// [`memory_properties().memory_heaps`](MemoryProperties::memory_heaps). The values may change
+    /// during runtime, so the result only reflects the current situation and is not cached.
+    ///
+    /// The [`ext_memory_budget`](crate::device::DeviceExtensions::ext_memory_budget) extension
+    /// must be supported by the physical device, and the instance API version must be at least
+    /// 1.1 or the [`khr_get_physical_device_properties2`] extension must be enabled on the
+    /// instance.
+    ///
+    /// [`khr_get_physical_device_properties2`]: crate::instance::InstanceExtensions::khr_get_physical_device_properties2
+    #[inline]
+    pub fn memory_budget(&self) -> Result<Vec<MemoryHeapBudget>, PhysicalDeviceError> {
+        self.validate_memory_budget()?;
+
+        unsafe { Ok(self.memory_budget_unchecked()) }
+    }
+
+    fn validate_memory_budget(&self) -> Result<(), PhysicalDeviceError> {
+        if !self.supported_extensions().ext_memory_budget {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`PhysicalDevice::memory_budget`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_memory_budget"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if !(self.instance.api_version() >= Version::V1_1
+            || self
+                .instance
+                .enabled_extensions()
+                .khr_get_physical_device_properties2)
+        {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`PhysicalDevice::memory_budget`",
+                requires_one_of: RequiresOneOf {
+                    api_version: Some(Version::V1_1),
+                    instance_extensions: &["khr_get_physical_device_properties2"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn memory_budget_unchecked(&self) -> Vec<MemoryHeapBudget> {
+        let mut memory_budget_properties_vk =
+            ash::vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+        let mut memory_properties2_vk = ash::vk::PhysicalDeviceMemoryProperties2KHR {
+            p_next: &mut memory_budget_properties_vk as *mut _ as *mut _,
+            ..Default::default()
+        };
+
+        let fns = self.instance.fns();
+
+        if self.instance.api_version() >= Version::V1_1 {
+            (fns.v1_1.get_physical_device_memory_properties2)(
+                self.handle,
+                &mut memory_properties2_vk,
+            );
+        } else {
+            (fns.khr_get_physical_device_properties2
+                .get_physical_device_memory_properties2_khr)(
+                self.handle,
+                &mut memory_properties2_vk,
+            );
+        }
+
+        (0..memory_properties2_vk.memory_properties.memory_heap_count as usize)
+            .map(|heap_index| MemoryHeapBudget {
+                budget: memory_budget_properties_vk.heap_budget[heap_index],
+                usage: memory_budget_properties_vk.heap_usage[heap_index],
+            })
+            .collect()
+    }
+
     /// Queries whether the physical device supports presenting to QNX Screen surfaces from queues
     /// of the given queue family.
     ///
@@ -1263,6 +1544,170 @@ impl PhysicalDevice {
         ) != 0
     }
 
+    /// Returns the performance counters that can be gathered by [`QueryType::Performance`]
+    /// queries on queues of the given queue family.
+    ///
+    /// The indices of the returned counters are used in
+    /// [`QueryPoolPerformanceCreateInfo::counter_indices`].
+    ///
+    /// The [`khr_performance_query`] extension must be supported by the physical device.
+    ///
+    /// [`QueryType::Performance`]: crate::query::QueryType::Performance
+    /// [`khr_performance_query`]: crate::device::DeviceExtensions::khr_performance_query
+    #[inline]
+    pub fn queue_family_performance_query_counters(
+        &self,
+        queue_family_index: u32,
+    ) -> Result<Vec<PerformanceCounter>, PhysicalDeviceError> {
+        self.validate_queue_family_performance_query_counters(queue_family_index)?;
+
+        unsafe { Ok(self.queue_family_performance_query_counters_unchecked(queue_family_index)?) }
+    }
+
+    fn validate_queue_family_performance_query_counters(
+        &self,
+        queue_family_index: u32,
+    ) -> Result<(), PhysicalDeviceError> {
+        if !self.supported_extensions().khr_performance_query {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`PhysicalDevice::queue_family_performance_query_counters`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["khr_performance_query"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID?
+        if queue_family_index >= self.queue_family_properties.len() as u32 {
+            return Err(PhysicalDeviceError::QueueFamilyIndexOutOfRange {
+                queue_family_index,
+                queue_family_count: self.queue_family_properties.len() as u32,
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn queue_family_performance_query_counters_unchecked(
+        &self,
+        queue_family_index: u32,
+    ) -> Result<Vec<PerformanceCounter>, VulkanError> {
+        let fns = self.instance.fns();
+
+        loop {
+            let mut count = 0;
+            (fns.khr_performance_query
+                .enumerate_physical_device_queue_family_performance_query_counters_khr)(
+                self.handle,
+                queue_family_index,
+                &mut count,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+
+            let mut counters = vec![ash::vk::PerformanceCounterKHR::default(); count as usize];
+            let mut descriptions =
+                vec![ash::vk::PerformanceCounterDescriptionKHR::default(); count as usize];
+            let result = (fns
+                .khr_performance_query
+                .enumerate_physical_device_queue_family_performance_query_counters_khr)(
+                self.handle,
+                queue_family_index,
+                &mut count,
+                counters.as_mut_ptr(),
+                descriptions.as_mut_ptr(),
+            );
+
+            match result {
+                ash::vk::Result::INCOMPLETE => (),
+                ash::vk::Result::SUCCESS => {
+                    counters.truncate(count as usize);
+                    descriptions.truncate(count as usize);
+
+                    return Ok(counters
+                        .into_iter()
+                        .zip(descriptions)
+                        .map(|(counter, description)| PerformanceCounter {
+                            unit: counter.unit.try_into().unwrap(),
+                            scope: counter.scope.try_into().unwrap(),
+                            storage: counter.storage.try_into().unwrap(),
+                            uuid: counter.uuid,
+                            flags: description.flags.into(),
+                            name: {
+                                let bytes = cast_slice(description.name.as_slice());
+                                let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                                String::from_utf8_lossy(&bytes[0..end]).into()
+                            },
+                            category: {
+                                let bytes = cast_slice(description.category.as_slice());
+                                let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                                String::from_utf8_lossy(&bytes[0..end]).into()
+                            },
+                            description: {
+                                let bytes = cast_slice(description.description.as_slice());
+                                let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                                String::from_utf8_lossy(&bytes[0..end]).into()
+                            },
+                        })
+                        .collect());
+                }
+                err => return Err(VulkanError::from(err)),
+            }
+        }
+    }
+
+    /// Returns the number of passes that are needed to gather the performance counters in
+    /// `performance_query_info`.
+    ///
+    /// The [`khr_performance_query`] extension must be supported by the physical device.
+    ///
+    /// [`khr_performance_query`]: crate::device::DeviceExtensions::khr_performance_query
+    #[inline]
+    pub fn queue_family_performance_query_passes(
+        &self,
+        performance_query_info: &QueryPoolPerformanceCreateInfo,
+    ) -> Result<u32, PhysicalDeviceError> {
+        self.validate_queue_family_performance_query_counters(
+            performance_query_info.queue_family_index,
+        )?;
+
+        unsafe { Ok(self.queue_family_performance_query_passes_unchecked(performance_query_info)) }
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn queue_family_performance_query_passes_unchecked(
+        &self,
+        performance_query_info: &QueryPoolPerformanceCreateInfo,
+    ) -> u32 {
+        let &QueryPoolPerformanceCreateInfo {
+            queue_family_index,
+            ref counter_indices,
+            _ne: _,
+        } = performance_query_info;
+
+        let performance_query_info_vk = ash::vk::QueryPoolPerformanceCreateInfoKHR {
+            queue_family_index,
+            counter_index_count: counter_indices.len() as u32,
+            p_counter_indices: counter_indices.as_ptr(),
+            ..Default::default()
+        };
+
+        let fns = self.instance.fns();
+        let mut num_passes = 0;
+        (fns.khr_performance_query
+            .get_physical_device_queue_family_performance_query_passes_khr)(
+            self.handle,
+            &performance_query_info_vk,
+            &mut num_passes,
+        );
+
+        num_passes
+    }
+
     /// Returns the properties of sparse images with a given image configuration.
     ///
     /// The results of this function are cached, so that future calls with the same arguments
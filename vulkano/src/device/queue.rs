@@ -24,7 +24,7 @@ use crate::{
     sync::{
         fence::{Fence, FenceState},
         future::{AccessCheckError, FlushError, GpuFuture},
-        semaphore::SemaphoreState,
+        semaphore::{SemaphoreState, SemaphoreType},
     },
     OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
@@ -453,13 +453,25 @@ impl<'a> QueueGuard<'a> {
         for bind_info in bind_infos {
             let BindSparseInfo {
                 wait_semaphores,
-                buffer_binds: _,
-                image_opaque_binds: _,
-                image_binds: _,
+                buffer_binds,
+                image_opaque_binds,
+                image_binds,
                 signal_semaphores,
                 _ne: _,
             } = bind_info;
 
+            for (buffer, memory_binds) in buffer_binds {
+                buffer.buffer().sparse_bind(memory_binds);
+            }
+
+            for (image, memory_binds) in image_opaque_binds {
+                image.inner().image.sparse_bind_opaque(memory_binds);
+            }
+
+            for (image, memory_binds) in image_binds {
+                image.inner().image.sparse_bind_blocks(memory_binds);
+            }
+
             for semaphore in wait_semaphores {
                 let state = states.semaphores.get_mut(&semaphore.handle()).unwrap();
                 state.add_queue_wait(self.queue);
@@ -810,13 +822,14 @@ impl<'a> QueueGuard<'a> {
                             .map(|semaphore_submit_info| {
                                 let &SemaphoreSubmitInfo {
                                     ref semaphore,
+                                    value,
                                     stages,
                                     _ne: _,
                                 } = semaphore_submit_info;
 
                                 ash::vk::SemaphoreSubmitInfo {
                                     semaphore: semaphore.handle(),
-                                    value: 0, // TODO:
+                                    value,
                                     stage_mask: stages.into(),
                                     device_index: 0, // TODO:
                                     ..Default::default()
@@ -838,13 +851,14 @@ impl<'a> QueueGuard<'a> {
                             .map(|semaphore_submit_info| {
                                 let &SemaphoreSubmitInfo {
                                     ref semaphore,
+                                    value,
                                     stages,
                                     _ne: _,
                                 } = semaphore_submit_info;
 
                                 ash::vk::SemaphoreSubmitInfo {
                                     semaphore: semaphore.handle(),
-                                    value: 0, // TODO:
+                                    value,
                                     stage_mask: stages.into(),
                                     device_index: 0, // TODO:
                                     ..Default::default()
@@ -919,12 +933,15 @@ impl<'a> QueueGuard<'a> {
         } else {
             struct PerSubmitInfo {
                 wait_semaphores_vk: SmallVec<[ash::vk::Semaphore; 4]>,
+                wait_semaphore_values_vk: SmallVec<[u64; 4]>,
                 wait_dst_stage_mask_vk: SmallVec<[ash::vk::PipelineStageFlags; 4]>,
                 command_buffers_vk: SmallVec<[ash::vk::CommandBuffer; 4]>,
                 signal_semaphores_vk: SmallVec<[ash::vk::Semaphore; 4]>,
+                signal_semaphore_values_vk: SmallVec<[u64; 4]>,
+                timeline_semaphore_submit_info_vk: Option<ash::vk::TimelineSemaphoreSubmitInfo>,
             }
 
-            let (mut submit_info_vk, per_submit_vk): (SmallVec<[_; 4]>, SmallVec<[_; 4]>) =
+            let (mut submit_info_vk, mut per_submit_vk): (SmallVec<[_; 4]>, SmallVec<[_; 4]>) =
                 submit_infos
                     .iter()
                     .map(|submit_info| {
@@ -935,34 +952,48 @@ impl<'a> QueueGuard<'a> {
                             _ne: _,
                         } = submit_info;
 
-                        let (wait_semaphores_vk, wait_dst_stage_mask_vk) = wait_semaphores
-                            .iter()
-                            .map(|semaphore_submit_info| {
-                                let &SemaphoreSubmitInfo {
-                                    ref semaphore,
-                                    stages,
-                                    _ne: _,
-                                } = semaphore_submit_info;
+                        let mut has_timeline_semaphores = false;
+                        let mut wait_semaphores_vk = SmallVec::new();
+                        let mut wait_semaphore_values_vk = SmallVec::new();
+                        let mut wait_dst_stage_mask_vk = SmallVec::new();
+
+                        for semaphore_submit_info in wait_semaphores {
+                            let &SemaphoreSubmitInfo {
+                                ref semaphore,
+                                value,
+                                stages,
+                                _ne: _,
+                            } = semaphore_submit_info;
+
+                            if semaphore.semaphore_type() == SemaphoreType::Timeline {
+                                has_timeline_semaphores = true;
+                            }
 
-                                (semaphore.handle(), stages.into())
-                            })
-                            .unzip();
+                            wait_semaphores_vk.push(semaphore.handle());
+                            wait_semaphore_values_vk.push(value);
+                            wait_dst_stage_mask_vk.push(stages.into());
+                        }
 
                         let command_buffers_vk =
                             command_buffers.iter().map(|cb| cb.handle()).collect();
 
-                        let signal_semaphores_vk = signal_semaphores
+                        let (signal_semaphores_vk, signal_semaphore_values_vk) = signal_semaphores
                             .iter()
                             .map(|semaphore_submit_info| {
                                 let &SemaphoreSubmitInfo {
                                     ref semaphore,
+                                    value,
                                     stages: _,
                                     _ne: _,
                                 } = semaphore_submit_info;
 
-                                semaphore.handle()
+                                if semaphore.semaphore_type() == SemaphoreType::Timeline {
+                                    has_timeline_semaphores = true;
+                                }
+
+                                (semaphore.handle(), value)
                             })
-                            .collect();
+                            .unzip();
 
                         (
                             ash::vk::SubmitInfo {
@@ -977,9 +1008,13 @@ impl<'a> QueueGuard<'a> {
                             },
                             PerSubmitInfo {
                                 wait_semaphores_vk,
+                                wait_semaphore_values_vk,
                                 wait_dst_stage_mask_vk,
                                 command_buffers_vk,
                                 signal_semaphores_vk,
+                                signal_semaphore_values_vk,
+                                timeline_semaphore_submit_info_vk: has_timeline_semaphores
+                                    .then(Default::default),
                             },
                         )
                     })
@@ -989,11 +1024,14 @@ impl<'a> QueueGuard<'a> {
                 submit_info_vk,
                 PerSubmitInfo {
                     wait_semaphores_vk,
+                    wait_semaphore_values_vk,
                     wait_dst_stage_mask_vk,
                     command_buffers_vk,
                     signal_semaphores_vk,
+                    signal_semaphore_values_vk,
+                    timeline_semaphore_submit_info_vk,
                 },
-            ) in (submit_info_vk.iter_mut()).zip(per_submit_vk.iter())
+            ) in (submit_info_vk.iter_mut()).zip(per_submit_vk.iter_mut())
             {
                 *submit_info_vk = ash::vk::SubmitInfo {
                     wait_semaphore_count: wait_semaphores_vk.len() as u32,
@@ -1005,6 +1043,19 @@ impl<'a> QueueGuard<'a> {
                     p_signal_semaphores: signal_semaphores_vk.as_ptr(),
                     ..*submit_info_vk
                 };
+
+                if let Some(next) = timeline_semaphore_submit_info_vk {
+                    *next = ash::vk::TimelineSemaphoreSubmitInfo {
+                        wait_semaphore_value_count: wait_semaphore_values_vk.len() as u32,
+                        p_wait_semaphore_values: wait_semaphore_values_vk.as_ptr(),
+                        signal_semaphore_value_count: signal_semaphore_values_vk.len() as u32,
+                        p_signal_semaphore_values: signal_semaphore_values_vk.as_ptr(),
+                        ..Default::default()
+                    };
+
+                    next.p_next = submit_info_vk.p_next;
+                    submit_info_vk.p_next = next as *const _ as *const _;
+                }
             }
 
             let fns = self.queue.device.fns();
@@ -1028,7 +1079,13 @@ impl<'a> QueueGuard<'a> {
                 _ne: _,
             } = submit_info;
 
+            // Timeline semaphores can be waited on and signaled any number of times, so only
+            // binary semaphores have their pending operations tracked.
             for semaphore_submit_info in wait_semaphores {
+                if semaphore_submit_info.semaphore.semaphore_type() == SemaphoreType::Timeline {
+                    continue;
+                }
+
                 let state = states
                     .semaphores
                     .get_mut(&semaphore_submit_info.semaphore.handle())
@@ -1076,6 +1133,10 @@ impl<'a> QueueGuard<'a> {
             }
 
             for semaphore_submit_info in signal_semaphores {
+                if semaphore_submit_info.semaphore.semaphore_type() == SemaphoreType::Timeline {
+                    continue;
+                }
+
                 let state = states
                     .semaphores
                     .get_mut(&semaphore_submit_info.semaphore.handle())
@@ -1346,14 +1407,19 @@ impl QueueOperation {
             QueueOperation::Submit(submit_infos) => {
                 for submit_info in submit_infos {
                     for semaphore_submit_info in submit_info.wait_semaphores {
-                        semaphore_submit_info.semaphore.state().set_wait_finished();
+                        let semaphore = semaphore_submit_info.semaphore;
+
+                        if semaphore.semaphore_type() == SemaphoreType::Binary {
+                            semaphore.state().set_wait_finished();
+                        }
                     }
 
                     for semaphore_submit_info in submit_info.signal_semaphores {
-                        semaphore_submit_info
-                            .semaphore
-                            .state()
-                            .set_signal_finished();
+                        let semaphore = semaphore_submit_info.semaphore;
+
+                        if semaphore.semaphore_type() == SemaphoreType::Binary {
+                            semaphore.state().set_signal_finished();
+                        }
                     }
 
                     for command_buffer in submit_info.command_buffers {
@@ -742,6 +742,23 @@ impl FormatProperties {
     }
 }
 
+/// The properties of a DRM format modifier that is supported by a physical device for a
+/// particular format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DrmFormatModifierProperties {
+    /// The Linux DRM format modifier.
+    pub drm_format_modifier: u64,
+
+    /// The number of memory planes that an image created with this modifier has.
+    ///
+    /// This is not necessarily equal to the number of format planes.
+    pub drm_format_modifier_plane_count: u32,
+
+    /// Features available for images created with this modifier.
+    pub drm_format_modifier_tiling_features: FormatFeatures,
+}
+
 vulkan_bitflags! {
     #[non_exhaustive]
 
@@ -20,7 +20,7 @@ use crate::{
     memory::{
         allocator::{
             AllocationCreateInfo, AllocationType, MemoryAllocatePreference, MemoryAllocator,
-            MemoryUsage,
+            MemoryBudgetBehavior, MemoryUsage,
         },
         is_aligned, DedicatedAllocation, DeviceMemoryError, ExternalMemoryHandleType,
         ExternalMemoryHandleTypes,
@@ -423,6 +423,8 @@ impl AttachmentImage {
                 AllocationCreateInfo {
                     usage: MemoryUsage::DeviceOnly,
                     allocate_preference: MemoryAllocatePreference::Unknown,
+                    budget_behavior: MemoryBudgetBehavior::Ignore,
+                    priority: 0.5,
                     _ne: crate::NonExhaustive(()),
                 },
                 Some(DedicatedAllocation::Image(&raw_image)),
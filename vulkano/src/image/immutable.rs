@@ -25,7 +25,7 @@ use crate::{
     memory::{
         allocator::{
             AllocationCreateInfo, AllocationCreationError, AllocationType,
-            MemoryAllocatePreference, MemoryAllocator, MemoryUsage,
+            MemoryAllocatePreference, MemoryAllocator, MemoryBudgetBehavior, MemoryUsage,
         },
         is_aligned, DedicatedAllocation,
     },
@@ -145,6 +145,8 @@ impl ImmutableImage {
                 AllocationCreateInfo {
                     usage: MemoryUsage::DeviceOnly,
                     allocate_preference: MemoryAllocatePreference::Unknown,
+                    budget_behavior: MemoryBudgetBehavior::Ignore,
+                    priority: 0.5,
                     _ne: crate::NonExhaustive(()),
                 },
                 Some(DedicatedAllocation::Image(&raw_image)),
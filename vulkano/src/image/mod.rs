@@ -60,14 +60,16 @@ pub use self::{
 };
 
 #[cfg(target_os = "linux")]
-pub use self::storage::SubresourceData;
+pub use self::{storage::SubresourceData, sys::DmaBuf};
 
 use crate::{
     format::Format,
     macros::{vulkan_bitflags, vulkan_bitflags_enum, vulkan_enum},
     memory::{ExternalMemoryHandleType, ExternalMemoryProperties},
+    sync::Sharing,
     DeviceSize,
 };
+use smallvec::SmallVec;
 use std::{cmp, ops::Range};
 
 mod aspect;
@@ -87,7 +89,6 @@ vulkan_bitflags! {
     /// Flags that can be set when creating a new image.
     ImageCreateFlags = ImageCreateFlags(u32);
 
-    /* TODO: enable
     /// The image will be backed by sparse memory binding (through queue commands) instead of
     /// regular binding (through [`bind_memory`]).
     ///
@@ -95,38 +96,35 @@ vulkan_bitflags! {
     ///
     /// [`bind_memory`]: sys::RawImage::bind_memory
     /// [`sparse_binding`]: crate::device::Features::sparse_binding
-    SPARSE_BINDING = SPARSE_BINDING,*/
+    SPARSE_BINDING = SPARSE_BINDING,
 
-    /* TODO: enable
     /// The image can be used without being fully resident in memory at the time of use.
     ///
     /// This requires the `sparse_binding` flag as well.
     ///
     /// Depending on the image dimensions, either the [`sparse_residency_image2_d`] or the
     /// [`sparse_residency_image3_d`] feature must be enabled on the device.
-    /// For a multisampled image, the one of the features [`sparse_residency2_samples`],
+    /// For a multisampled image, one of the features [`sparse_residency2_samples`],
     /// [`sparse_residency4_samples`], [`sparse_residency8_samples`] or
     /// [`sparse_residency16_samples`], corresponding to the sample count of the image, must
     /// be enabled on the device.
     ///
-    /// [`sparse_binding`]: crate::device::Features::sparse_binding
     /// [`sparse_residency_image2_d`]: crate::device::Features::sparse_residency_image2_d
-    /// [`sparse_residency_image2_3`]: crate::device::Features::sparse_residency_image3_d
+    /// [`sparse_residency_image3_d`]: crate::device::Features::sparse_residency_image3_d
     /// [`sparse_residency2_samples`]: crate::device::Features::sparse_residency2_samples
     /// [`sparse_residency4_samples`]: crate::device::Features::sparse_residency4_samples
     /// [`sparse_residency8_samples`]: crate::device::Features::sparse_residency8_samples
     /// [`sparse_residency16_samples`]: crate::device::Features::sparse_residency16_samples
-    SPARSE_RESIDENCY = SPARSE_RESIDENCY,*/
+    SPARSE_RESIDENCY = SPARSE_RESIDENCY,
 
-    /* TODO: enable
-    /// The buffer's memory can alias with another image or a different part of the same image.
+    /// The image's memory can alias with another image or a different part of the same image.
     ///
     /// This requires the `sparse_binding` flag as well.
     ///
     /// The [`sparse_residency_aliased`] feature must be enabled on the device.
     ///
     /// [`sparse_residency_aliased`]: crate::device::Features::sparse_residency_aliased
-    SPARSE_ALIASED = SPARSE_ALIASED,*/
+    SPARSE_ALIASED = SPARSE_ALIASED,
 
     /// For non-multi-planar formats, whether an image view wrapping the image can have a
     /// different format.
@@ -786,6 +784,13 @@ pub struct ImageFormatInfo {
     /// The default value is `None`.
     pub image_view_type: Option<ImageViewType>,
 
+    /// The DRM format modifier that the image will be created with.
+    ///
+    /// This must be `Some` if and only if `tiling` is [`ImageTiling::DrmFormatModifier`].
+    ///
+    /// The default value is `None`.
+    pub drm_format_modifier_info: Option<ImageDrmFormatModifierInfo>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -801,6 +806,34 @@ impl Default for ImageFormatInfo {
             stencil_usage: ImageUsage::empty(),
             external_memory_handle_type: None,
             image_view_type: None,
+            drm_format_modifier_info: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// The DRM format modifier to query in [`ImageFormatInfo`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ImageDrmFormatModifierInfo {
+    /// The Linux DRM format modifier that the image will be created with.
+    ///
+    /// The default value is `0`, which is `DRM_FORMAT_MOD_LINEAR`.
+    pub drm_format_modifier: u64,
+
+    /// The `sharing` that the image will have.
+    ///
+    /// The default value is [`Sharing::Exclusive`].
+    pub sharing: Sharing<SmallVec<[u32; 4]>>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for ImageDrmFormatModifierInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            drm_format_modifier: 0,
+            sharing: Sharing::Exclusive,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -924,6 +957,57 @@ pub struct SparseImageFormatProperties {
     pub flags: SparseImageFormatFlags,
 }
 
+impl SparseImageFormatProperties {
+    /// Returns the number of sparse image blocks along each dimension that are needed to cover
+    /// a subresource of the given `extent`.
+    ///
+    /// Blocks at the edge of the subresource may be only partially covered by texels.
+    #[inline]
+    pub fn block_count(&self, extent: [u32; 3]) -> [u32; 3] {
+        let [width, height, depth] = self.image_granularity;
+
+        [
+            (extent[0] + width - 1) / width,
+            (extent[1] + height - 1) / height,
+            (extent[2] + depth - 1) / depth,
+        ]
+    }
+
+    /// Returns the offset and extent of the sparse image block with the given block coordinates,
+    /// within a subresource of the given `extent`. The returned region can be used directly in a
+    /// [`SparseImageMemoryBind`].
+    ///
+    /// The region is clipped to `extent`, so blocks at the edge of the subresource may be smaller
+    /// than `image_granularity`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `block` is not less than [`block_count(extent)`](Self::block_count) for any
+    ///   dimension.
+    ///
+    /// [`SparseImageMemoryBind`]: crate::memory::SparseImageMemoryBind
+    #[inline]
+    pub fn block_region(&self, block: [u32; 3], extent: [u32; 3]) -> ([u32; 3], [u32; 3]) {
+        let block_count = self.block_count(extent);
+        assert!(
+            block[0] < block_count[0] && block[1] < block_count[1] && block[2] < block_count[2]
+        );
+
+        let offset = [
+            block[0] * self.image_granularity[0],
+            block[1] * self.image_granularity[1],
+            block[2] * self.image_granularity[2],
+        ];
+        let region_extent = [
+            self.image_granularity[0].min(extent[0] - offset[0]),
+            self.image_granularity[1].min(extent[1] - offset[1]),
+            self.image_granularity[2].min(extent[2] - offset[2]),
+        ];
+
+        (offset, region_extent)
+    }
+}
+
 vulkan_bitflags! {
     #[non_exhaustive]
 
@@ -967,6 +1051,25 @@ pub struct SparseImageMemoryRequirements {
     pub image_mip_tail_stride: Option<DeviceSize>,
 }
 
+impl SparseImageMemoryRequirements {
+    /// Returns the range in the image's opaque memory space that the mip tail region of
+    /// `array_layer` occupies. This range must be bound with a [`SparseImageOpaqueMemoryBind`].
+    ///
+    /// If `format_properties.flags.single_miptail` is set, then all array layers share the same
+    /// mip tail region, and `array_layer` is ignored.
+    ///
+    /// [`SparseImageOpaqueMemoryBind`]: crate::memory::SparseImageOpaqueMemoryBind
+    #[inline]
+    pub fn mip_tail_range(&self, array_layer: u32) -> Range<DeviceSize> {
+        let start = self.image_mip_tail_offset
+            + self
+                .image_mip_tail_stride
+                .map_or(0, |stride| array_layer as DeviceSize * stride);
+
+        start..start + self.image_mip_tail_size
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -974,7 +1077,10 @@ mod tests {
             allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
         },
         format::Format,
-        image::{ImageAccess, ImageDimensions, ImmutableImage, MipmapsCount},
+        image::{
+            ImageAccess, ImageAspects, ImageDimensions, ImmutableImage, MipmapsCount,
+            SparseImageFormatFlags, SparseImageFormatProperties,
+        },
         memory::allocator::StandardMemoryAllocator,
     };
 
@@ -1002,6 +1108,25 @@ mod tests {
         assert_eq!(dims.max_mip_levels(), 10);
     }
 
+    #[test]
+    fn sparse_block_region() {
+        let properties = SparseImageFormatProperties {
+            aspects: ImageAspects::COLOR,
+            image_granularity: [128, 128, 1],
+            flags: SparseImageFormatFlags::empty(),
+        };
+
+        assert_eq!(properties.block_count([1000, 256, 1]), [8, 2, 1]);
+        assert_eq!(
+            properties.block_region([1, 1, 0], [1000, 256, 1]),
+            ([128, 128, 0], [128, 128, 1]),
+        );
+        assert_eq!(
+            properties.block_region([7, 0, 0], [1000, 256, 1]),
+            ([896, 0, 0], [104, 128, 1]),
+        );
+    }
+
     #[test]
     fn mip_level_dimensions() {
         let dims = ImageDimensions::Dim2d {
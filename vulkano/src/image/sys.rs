@@ -21,17 +21,24 @@ use super::{
 use crate::{
     buffer::subbuffer::{ReadLockError, WriteLockError},
     cache::OnceCache,
-    device::{Device, DeviceOwned},
-    format::{ChromaSampling, Format, FormatFeatures, NumericType},
+    device::{physical::PhysicalDeviceError, Device, DeviceOwned},
+    format::{ChromaSampling, DrmFormatModifierProperties, Format, FormatFeatures, NumericType},
     image::{
-        view::ImageViewCreationError, ImageFormatInfo, ImageFormatProperties, ImageType,
-        SparseImageFormatProperties,
+        view::ImageViewCreationError, ImageDrmFormatModifierInfo, ImageFormatInfo,
+        ImageFormatProperties, ImageType, SparseImageFormatProperties,
     },
     macros::impl_id_counter,
     memory::{
-        allocator::{AllocationCreationError, AllocationType, DeviceLayout, MemoryAlloc},
-        is_aligned, DedicatedTo, DeviceAlignment, ExternalMemoryHandleType,
-        ExternalMemoryHandleTypes, MemoryPropertyFlags, MemoryRequirements,
+        allocator::{
+            AllocationCreationError, AllocationType, DeviceLayout, MemoryAlloc, MemoryAllocator,
+            MemoryUsage,
+        },
+        is_aligned,
+        sparse::SparseImageResidency,
+        DedicatedAllocation, DedicatedTo, DeviceAlignment, DeviceMemory, DeviceMemoryError,
+        ExternalMemoryHandleType, ExternalMemoryHandleTypes, MemoryAllocateInfo, MemoryImportInfo,
+        MemoryPropertyFlags, MemoryRequirements, SparseImageMemoryBind,
+        SparseImageOpaqueMemoryBind,
     },
     range_map::RangeMap,
     swapchain::Swapchain,
@@ -44,6 +51,7 @@ use smallvec::{smallvec, SmallVec};
 use std::{
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
+    fs::File,
     hash::{Hash, Hasher},
     iter::{FusedIterator, Peekable},
     mem::{size_of_val, MaybeUninit},
@@ -76,6 +84,7 @@ pub struct RawImage {
     sharing: Sharing<SmallVec<[u32; 4]>>,
     stencil_usage: ImageUsage,
     external_memory_handle_types: ExternalMemoryHandleTypes,
+    drm_format_modifier: Option<DrmFormatModifierProperties>,
 
     memory_requirements: SmallVec<[MemoryRequirements; 3]>,
     needs_destruction: bool, // `vkDestroyImage` is called only if true.
@@ -136,6 +145,7 @@ impl RawImage {
             external_memory_handle_types,
             _ne: _,
             image_drm_format_modifier_create_info,
+            ref drm_format_modifiers,
         } = create_info;
 
         let physical_device = device.physical_device();
@@ -211,7 +221,8 @@ impl RawImage {
         // VUID-VkImageCreateInfo-tiling-02261
         // VUID-VkImageCreateInfo-pNext-02262
         if (tiling == ImageTiling::DrmFormatModifier)
-            != image_drm_format_modifier_create_info.is_some()
+            != (image_drm_format_modifier_create_info.is_some() || !drm_format_modifiers.is_empty())
+            || image_drm_format_modifier_create_info.is_some() && !drm_format_modifiers.is_empty()
         {
             return Err(ImageError::DrmFormatModifierRequiresCreateInfo);
         }
@@ -223,7 +234,32 @@ impl RawImage {
             match tiling {
                 ImageTiling::Linear => format_properties.linear_tiling_features,
                 ImageTiling::Optimal => format_properties.optimal_tiling_features,
-                ImageTiling::DrmFormatModifier => format_properties.linear_tiling_features, // TODO: Improve
+                ImageTiling::DrmFormatModifier => {
+                    // The implementation picks one of the modifiers, so only the features shared
+                    // by all of them can be relied on.
+                    let candidates: SmallVec<[u64; 4]> = image_drm_format_modifier_create_info
+                        .map(|info| info.drm_format_modifier)
+                        .into_iter()
+                        .chain(drm_format_modifiers.iter().copied())
+                        .collect();
+                    let modifier_properties =
+                        unsafe { physical_device.drm_format_modifier_properties_unchecked(format) };
+
+                    candidates
+                        .iter()
+                        .map(|&drm_format_modifier| {
+                            modifier_properties
+                                .iter()
+                                .find(|properties| {
+                                    properties.drm_format_modifier == drm_format_modifier
+                                })
+                                .map_or_else(FormatFeatures::empty, |properties| {
+                                    properties.drm_format_modifier_tiling_features
+                                })
+                        })
+                        .reduce(|features, modifier_features| features & modifier_features)
+                        .unwrap_or_default()
+                }
             }
         };
 
@@ -572,6 +608,113 @@ impl RawImage {
             }
         }
 
+        if flags.intersects(ImageCreateFlags::SPARSE_BINDING) {
+            // VUID-VkImageCreateInfo-flags-00969
+            if !device.enabled_features().sparse_binding {
+                return Err(ImageError::RequirementNotMet {
+                    required_for: "`create_info.flags` contains `ImageCreateFlags::SPARSE_BINDING`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["sparse_binding"],
+                        ..Default::default()
+                    },
+                });
+            }
+        } else if flags
+            .intersects(ImageCreateFlags::SPARSE_RESIDENCY | ImageCreateFlags::SPARSE_ALIASED)
+        {
+            // VUID-VkImageCreateInfo-flags-00987
+            return Err(ImageError::SparseBindingFlagMissing);
+        }
+
+        if flags.intersects(ImageCreateFlags::SPARSE_RESIDENCY) {
+            match image_type {
+                // VUID-VkImageCreateInfo-imageType-00970
+                ImageType::Dim1d => return Err(ImageError::SparseResidency1d),
+                ImageType::Dim2d => {
+                    // VUID-VkImageCreateInfo-imageType-00971
+                    if !device.enabled_features().sparse_residency_image2_d {
+                        return Err(ImageError::RequirementNotMet {
+                            required_for: "`create_info.flags` contains \
+                                `ImageCreateFlags::SPARSE_RESIDENCY`, and \
+                                `create_info.dimensions` is `ImageDimensions::Dim2d`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["sparse_residency_image2_d"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+                }
+                ImageType::Dim3d => {
+                    // VUID-VkImageCreateInfo-imageType-00972
+                    if !device.enabled_features().sparse_residency_image3_d {
+                        return Err(ImageError::RequirementNotMet {
+                            required_for: "`create_info.flags` contains \
+                                `ImageCreateFlags::SPARSE_RESIDENCY`, and \
+                                `create_info.dimensions` is `ImageDimensions::Dim3d`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["sparse_residency_image3_d"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+                }
+            }
+
+            // VUID-VkImageCreateInfo-tiling-04121
+            if tiling == ImageTiling::Linear {
+                return Err(ImageError::SparseResidencyLinearTiling);
+            }
+
+            // VUID-VkImageCreateInfo-imageType-00973
+            // VUID-VkImageCreateInfo-imageType-00974
+            // VUID-VkImageCreateInfo-imageType-00975
+            // VUID-VkImageCreateInfo-imageType-00976
+            let samples_feature: Option<(bool, &'static [&'static str])> = match samples {
+                SampleCount::Sample2 => Some((
+                    device.enabled_features().sparse_residency2_samples,
+                    &["sparse_residency2_samples"],
+                )),
+                SampleCount::Sample4 => Some((
+                    device.enabled_features().sparse_residency4_samples,
+                    &["sparse_residency4_samples"],
+                )),
+                SampleCount::Sample8 => Some((
+                    device.enabled_features().sparse_residency8_samples,
+                    &["sparse_residency8_samples"],
+                )),
+                SampleCount::Sample16 => Some((
+                    device.enabled_features().sparse_residency16_samples,
+                    &["sparse_residency16_samples"],
+                )),
+                _ => None,
+            };
+
+            if let Some((false, features)) = samples_feature {
+                return Err(ImageError::RequirementNotMet {
+                    required_for: "`create_info.flags` contains \
+                        `ImageCreateFlags::SPARSE_RESIDENCY`, and `create_info.samples` is not \
+                        `SampleCount::Sample1`",
+                    requires_one_of: RequiresOneOf {
+                        features,
+                        ..Default::default()
+                    },
+                });
+            }
+        }
+
+        // VUID-VkImageCreateInfo-flags-01924
+        if flags.intersects(ImageCreateFlags::SPARSE_ALIASED)
+            && !device.enabled_features().sparse_residency_aliased
+        {
+            return Err(ImageError::RequirementNotMet {
+                required_for: "`create_info.flags` contains `ImageCreateFlags::SPARSE_ALIASED`",
+                requires_one_of: RequiresOneOf {
+                    features: &["sparse_residency_aliased"],
+                    ..Default::default()
+                },
+            });
+        }
+
         /* Check sharing mode and queue families */
 
         match sharing {
@@ -780,7 +923,8 @@ impl RawImage {
             || mip_levels_must_query()
             || array_layers_must_query()
             || samples_must_query()
-            || linear_must_query();
+            || linear_must_query()
+            || tiling == ImageTiling::DrmFormatModifier;
 
         // We determined that we must query the device in order to be sure that the image
         // configuration is supported.
@@ -794,72 +938,100 @@ impl RawImage {
                     smallvec![None]
                 };
 
-            for external_memory_handle_type in external_memory_handle_types {
-                // Use unchecked, because all validation has been done above.
-                let image_format_properties = unsafe {
-                    device
-                        .physical_device()
-                        .image_format_properties_unchecked(ImageFormatInfo {
-                            flags,
-                            format: Some(format),
-                            image_type,
-                            tiling,
-                            usage,
-                            external_memory_handle_type,
-                            ..Default::default()
-                        })?
+            // VUID-VkImageDrmFormatModifierListCreateInfoEXT-pDrmFormatModifiers-02263
+            let drm_format_modifiers: SmallVec<[Option<u64>; 4]> =
+                if tiling == ImageTiling::DrmFormatModifier {
+                    image_drm_format_modifier_create_info
+                        .map(|info| info.drm_format_modifier)
+                        .into_iter()
+                        .chain(drm_format_modifiers.iter().copied())
+                        .map(Some)
+                        .collect()
+                } else {
+                    smallvec![None]
                 };
 
-                let ImageFormatProperties {
-                    max_extent,
-                    max_mip_levels,
-                    max_array_layers,
-                    sample_counts,
-                    max_resource_size: _,
-                    ..
-                } = match image_format_properties {
-                    Some(x) => x,
-                    None => return Err(ImageError::ImageFormatPropertiesNotSupported),
-                };
+            for external_memory_handle_type in external_memory_handle_types {
+                let check_image_format_properties = |drm_format_modifier: Option<u64>| {
+                    // Use unchecked, because all validation has been done above.
+                    let image_format_properties = unsafe {
+                        device.physical_device().image_format_properties_unchecked(
+                            ImageFormatInfo {
+                                flags,
+                                format: Some(format),
+                                image_type,
+                                tiling,
+                                usage,
+                                external_memory_handle_type,
+                                drm_format_modifier_info: drm_format_modifier.map(
+                                    |drm_format_modifier| ImageDrmFormatModifierInfo {
+                                        drm_format_modifier,
+                                        sharing: sharing.clone(),
+                                        ..Default::default()
+                                    },
+                                ),
+                                ..Default::default()
+                            },
+                        )?
+                    };
+
+                    let ImageFormatProperties {
+                        max_extent,
+                        max_mip_levels,
+                        max_array_layers,
+                        sample_counts,
+                        max_resource_size: _,
+                        ..
+                    } = match image_format_properties {
+                        Some(x) => x,
+                        None => return Err(ImageError::ImageFormatPropertiesNotSupported),
+                    };
+
+                    // VUID-VkImageCreateInfo-extent-02252
+                    // VUID-VkImageCreateInfo-extent-02253
+                    // VUID-VkImageCreateInfo-extent-02254
+                    if extent[0] > max_extent[0]
+                        || extent[1] > max_extent[1]
+                        || extent[2] > max_extent[2]
+                    {
+                        return Err(ImageError::MaxDimensionsExceeded {
+                            extent,
+                            max: max_extent,
+                        });
+                    }
 
-                // VUID-VkImageCreateInfo-extent-02252
-                // VUID-VkImageCreateInfo-extent-02253
-                // VUID-VkImageCreateInfo-extent-02254
-                if extent[0] > max_extent[0]
-                    || extent[1] > max_extent[1]
-                    || extent[2] > max_extent[2]
-                {
-                    return Err(ImageError::MaxDimensionsExceeded {
-                        extent,
-                        max: max_extent,
-                    });
-                }
+                    // VUID-VkImageCreateInfo-mipLevels-02255
+                    if mip_levels > max_mip_levels {
+                        return Err(ImageError::MaxMipLevelsExceeded {
+                            mip_levels,
+                            max: max_mip_levels,
+                        });
+                    }
 
-                // VUID-VkImageCreateInfo-mipLevels-02255
-                if mip_levels > max_mip_levels {
-                    return Err(ImageError::MaxMipLevelsExceeded {
-                        mip_levels,
-                        max: max_mip_levels,
-                    });
-                }
+                    // VUID-VkImageCreateInfo-arrayLayers-02256
+                    if array_layers > max_array_layers {
+                        return Err(ImageError::MaxArrayLayersExceeded {
+                            array_layers,
+                            max: max_array_layers,
+                        });
+                    }
 
-                // VUID-VkImageCreateInfo-arrayLayers-02256
-                if array_layers > max_array_layers {
-                    return Err(ImageError::MaxArrayLayersExceeded {
-                        array_layers,
-                        max: max_array_layers,
-                    });
-                }
+                    // VUID-VkImageCreateInfo-samples-02258
+                    if !sample_counts.contains_enum(samples) {
+                        return Err(ImageError::SampleCountNotSupported {
+                            samples,
+                            supported: sample_counts,
+                        });
+                    }
 
-                // VUID-VkImageCreateInfo-samples-02258
-                if !sample_counts.contains_enum(samples) {
-                    return Err(ImageError::SampleCountNotSupported {
-                        samples,
-                        supported: sample_counts,
-                    });
-                }
+                    // TODO: check resource size?
 
-                // TODO: check resource size?
+                    Ok(())
+                };
+
+                for &drm_format_modifier in &drm_format_modifiers {
+                    check_image_format_properties(drm_format_modifier)?;
+                }
             }
         }
 
@@ -886,6 +1058,7 @@ impl RawImage {
             external_memory_handle_types,
             _ne: _,
             mut image_drm_format_modifier_create_info,
+            ref drm_format_modifiers,
         } = &create_info;
 
         let aspects = format.map_or_else(Default::default, |format| format.aspects());
@@ -947,6 +1120,7 @@ impl RawImage {
         };
         let mut external_memory_info_vk = None;
         let mut stencil_usage_info_vk = None;
+        let mut drm_format_modifier_list_info_vk = None;
 
         if !external_memory_handle_types.is_empty() {
             let next = external_memory_info_vk.insert(ash::vk::ExternalMemoryImageCreateInfo {
@@ -968,8 +1142,19 @@ impl RawImage {
             info_vk.p_next = next as *const _ as *const _;
         }
 
-        if external_memory_handle_types.intersects(ExternalMemoryHandleTypes::DMA_BUF) {
-            let next = image_drm_format_modifier_create_info.as_mut().unwrap();
+        if let Some(next) = image_drm_format_modifier_create_info.as_mut() {
+            next.p_next = info_vk.p_next;
+            info_vk.p_next = next as *const _ as *const _;
+        }
+
+        if !drm_format_modifiers.is_empty() {
+            let next = drm_format_modifier_list_info_vk.insert(
+                ash::vk::ImageDrmFormatModifierListCreateInfoEXT {
+                    drm_format_modifier_count: drm_format_modifiers.len() as u32,
+                    p_drm_format_modifiers: drm_format_modifiers.as_ptr(),
+                    ..Default::default()
+                },
+            );
 
             next.p_next = info_vk.p_next;
             info_vk.p_next = next as *const _ as *const _;
@@ -1023,6 +1208,7 @@ impl RawImage {
             external_memory_handle_types,
             _ne: _,
             image_drm_format_modifier_create_info: _,
+            drm_format_modifiers: _,
         } = create_info;
 
         let aspects = format.map_or_else(Default::default, |format| format.aspects());
@@ -1033,6 +1219,31 @@ impl RawImage {
             stencil_usage = usage;
         }
 
+        // The implementation picks the modifier when the image is created, so it must be queried
+        // from the image itself.
+        let drm_format_modifier = (tiling == ImageTiling::DrmFormatModifier).then(|| {
+            let fns = device.fns();
+            let mut properties_vk = ash::vk::ImageDrmFormatModifierPropertiesEXT::default();
+            (fns.ext_image_drm_format_modifier
+                .get_image_drm_format_modifier_properties_ext)(
+                device.handle(),
+                handle,
+                &mut properties_vk,
+            )
+            .result()
+            .map_err(VulkanError::from)
+            .unwrap();
+
+            device
+                .physical_device()
+                .drm_format_modifier_properties_unchecked(format.unwrap())
+                .into_iter()
+                .find(|properties| {
+                    properties.drm_format_modifier == properties_vk.drm_format_modifier
+                })
+                .unwrap()
+        });
+
         // Get format features
         let format_features = {
             // Use unchecked, because `create_info` is assumed to match the info of the handle, and
@@ -1043,7 +1254,11 @@ impl RawImage {
             match tiling {
                 ImageTiling::Linear => format_properties.linear_tiling_features,
                 ImageTiling::Optimal => format_properties.optimal_tiling_features,
-                ImageTiling::DrmFormatModifier => format_properties.linear_tiling_features, // TODO: improve
+                ImageTiling::DrmFormatModifier => {
+                    drm_format_modifier
+                        .unwrap()
+                        .drm_format_modifier_tiling_features
+                }
             }
         };
 
@@ -1075,6 +1290,7 @@ impl RawImage {
             stencil_usage,
             sharing,
             external_memory_handle_types,
+            drm_format_modifier,
             memory_requirements,
             needs_destruction,
             subresource_layout: OnceCache::new(),
@@ -1175,7 +1391,6 @@ impl RawImage {
     }
 
     #[inline]
-    #[allow(dead_code)] // Remove when sparse memory is implemented
     fn get_sparse_memory_requirements(&self) -> Vec<SparseImageMemoryRequirements> {
         let device = &self.device;
 
@@ -1432,8 +1647,9 @@ impl RawImage {
             // Ensured by taking ownership of `RawImage`.
 
             // VUID-VkBindImageMemoryInfo-image-01045
-            // Currently ensured by not having sparse binding flags, but this needs to be checked
-            // once those are enabled.
+            if self.flags.intersects(ImageCreateFlags::SPARSE_BINDING) {
+                return Err(ImageError::SparseBindingFlagNotAllowed);
+            }
 
             // VUID-VkBindImageMemoryInfo-memoryOffset-01046
             // Assume that `allocation` was created correctly.
@@ -1713,6 +1929,13 @@ impl RawImage {
         self.external_memory_handle_types
     }
 
+    /// Returns the DRM format modifier that the implementation picked for this image, along with
+    /// its properties, if the image has [`ImageTiling::DrmFormatModifier`] tiling.
+    #[inline]
+    pub fn drm_format_modifier(&self) -> Option<DrmFormatModifierProperties> {
+        self.drm_format_modifier
+    }
+
     /// Returns an `ImageSubresourceLayers` covering the first mip level of the image. All aspects
     /// of the image are selected, or `plane0` if the image is multi-planar.
     #[inline]
@@ -1802,6 +2025,29 @@ impl RawImage {
             });
         }
 
+        // VUID-vkGetImageSubresourceLayout-tiling-02271
+        if let Some(drm_format_modifier) = self.drm_format_modifier {
+            let allowed_aspects = [
+                ImageAspects::MEMORY_PLANE_0,
+                ImageAspects::MEMORY_PLANE_1,
+                ImageAspects::MEMORY_PLANE_2,
+            ]
+            .into_iter()
+            .take(drm_format_modifier.drm_format_modifier_plane_count as usize)
+            .fold(ImageAspects::empty(), |allowed_aspects, aspect| {
+                allowed_aspects | aspect
+            });
+
+            if !allowed_aspects.contains(aspect.into()) {
+                return Err(ImageError::AspectNotAllowed {
+                    provided_aspect: aspect,
+                    allowed_aspects,
+                });
+            }
+
+            return Ok(());
+        }
+
         let mut allowed_aspects = self.format.unwrap().aspects();
 
         // Follows from the combination of these three VUIDs. See:
@@ -1819,11 +2065,6 @@ impl RawImage {
             allowed_aspects -= ImageAspects::COLOR;
         }
 
-        // TODO:  VUID-vkGetImageSubresourceLayout-tiling-02271
-        //if self.tiling == ImageTiling::DrmFormatModifier {
-        // Only one-plane image importing is possible for now.
-        //}
-
         // VUID-vkGetImageSubresourceLayout-format-04461
         // VUID-vkGetImageSubresourceLayout-format-04462
         // VUID-vkGetImageSubresourceLayout-format-04463
@@ -1997,6 +2238,19 @@ pub struct ImageCreateInfo {
     /// Specify that an image be created with the provided DRM format modifier and explicit memory layout
     pub image_drm_format_modifier_create_info: Option<ImageDrmFormatModifierExplicitCreateInfoEXT>,
 
+    /// The DRM format modifiers that the implementation can choose from when creating the image.
+    ///
+    /// If this is not empty, then `tiling` must be [`ImageTiling::DrmFormatModifier`], and
+    /// `image_drm_format_modifier_create_info` must be `None`. Every modifier must be supported
+    /// for the rest of the image configuration, which can be checked with
+    /// [`PhysicalDevice::image_format_properties`]. The modifier that was picked can be retrieved
+    /// with [`RawImage::drm_format_modifier`] once the image is created.
+    ///
+    /// The default value is empty.
+    ///
+    /// [`PhysicalDevice::image_format_properties`]: crate::device::physical::PhysicalDevice::image_format_properties
+    pub drm_format_modifiers: Vec<u64>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -2020,6 +2274,7 @@ impl Default for ImageCreateInfo {
             initial_layout: ImageLayout::Undefined,
             external_memory_handle_types: ExternalMemoryHandleTypes::empty(),
             image_drm_format_modifier_create_info: None,
+            drm_format_modifiers: Vec::new(),
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -2038,6 +2293,7 @@ pub struct Image {
     mip_level_size: DeviceSize,
     range_size: DeviceSize,
     state: Mutex<ImageState>,
+    sparse_residency: Option<Mutex<SparseImageResidency>>,
 }
 
 /// The type of backing memory that an image can have.
@@ -2048,9 +2304,11 @@ pub enum ImageMemory {
     /// [`bind_memory`]: RawImage::bind_memory
     Normal(SmallVec<[MemoryAlloc; 3]>),
 
-    /// The image is backed by sparse memory, bound with [`bind_sparse`].
+    /// The image is backed by sparse memory, bound with sparse bind operations on a queue.
     ///
-    /// [`bind_sparse`]: crate::device::QueueGuard::bind_sparse
+    /// See [`BindSparseInfo`] for more information.
+    ///
+    /// [`BindSparseInfo`]: crate::memory::BindSparseInfo
     Sparse(Vec<SparseImageMemoryRequirements>),
 
     /// The image is backed by memory owned by a [`Swapchain`].
@@ -2061,6 +2319,307 @@ pub enum ImageMemory {
 }
 
 impl Image {
+    /// Creates a new `Image` that is backed by sparse memory.
+    ///
+    /// No memory is bound to the image when it is created. Memory must be bound to it with
+    /// sparse bind operations on a queue, using [`BindSparseInfo`], before the bound parts can be
+    /// used.
+    ///
+    /// If `create_info.flags` contains [`ImageCreateFlags::SPARSE_RESIDENCY`], then memory can be
+    /// bound to individual sparse image blocks, as described by [`sparse_memory_requirements`].
+    /// Which blocks currently have memory bound to them can be queried with
+    /// [`is_sparse_block_resident`] and [`is_sparse_mip_tail_resident`].
+    ///
+    /// [`BindSparseInfo`]: crate::memory::BindSparseInfo
+    /// [`sparse_memory_requirements`]: Self::sparse_memory_requirements
+    /// [`is_sparse_block_resident`]: Self::is_sparse_block_resident
+    /// [`is_sparse_mip_tail_resident`]: Self::is_sparse_mip_tail_resident
+    pub fn new_sparse(
+        device: Arc<Device>,
+        create_info: ImageCreateInfo,
+    ) -> Result<Arc<Self>, ImageError> {
+        if !create_info
+            .flags
+            .intersects(ImageCreateFlags::SPARSE_BINDING)
+        {
+            return Err(ImageError::SparseBindingFlagMissing);
+        }
+
+        let raw_image = RawImage::new(device, create_info)?;
+        let sparse_memory_requirements = raw_image.get_sparse_memory_requirements();
+
+        Ok(Arc::new(Image::from_raw(
+            raw_image,
+            ImageMemory::Sparse(sparse_memory_requirements),
+        )))
+    }
+
+    /// Creates a new `Image` whose memory can be exported to other processes or APIs, using
+    /// one of `export_handle_types`.
+    ///
+    /// The image is always given a dedicated allocation, so that the exported memory contains
+    /// nothing but the image.
+    ///
+    /// If `create_info.tiling` is [`ImageTiling::DrmFormatModifier`], then the DRM format
+    /// modifier is negotiated: `create_info.drm_format_modifiers` is narrowed down to the
+    /// modifiers that the physical device supports for the image configuration and all of
+    /// `export_handle_types`. If it is empty, then all modifiers supported by the physical device
+    /// for the format are considered. The implementation picks one of the remaining modifiers,
+    /// which can be retrieved with [`drm_format_modifier`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.flags` contains [`ImageCreateFlags::DISJOINT`] or
+    ///   [`ImageCreateFlags::SPARSE_BINDING`].
+    /// - Panics if `create_info.external_memory_handle_types` is not empty.
+    /// - Panics if `export_handle_types` is empty.
+    ///
+    /// [`drm_format_modifier`]: Self::drm_format_modifier
+    pub fn new_exportable(
+        allocator: &(impl MemoryAllocator + ?Sized),
+        mut create_info: ImageCreateInfo,
+        export_handle_types: ExternalMemoryHandleTypes,
+    ) -> Result<Arc<Self>, ImageError> {
+        assert!(!create_info
+            .flags
+            .intersects(ImageCreateFlags::DISJOINT | ImageCreateFlags::SPARSE_BINDING));
+        assert!(create_info.external_memory_handle_types.is_empty());
+        assert!(!export_handle_types.is_empty());
+
+        let device = allocator.device();
+        let physical_device = device.physical_device();
+        let format = create_info.format.unwrap();
+
+        let is_exportable = |drm_format_modifier: Option<u64>| {
+            export_handle_types
+                .into_iter()
+                .try_fold(true, |is_exportable, handle_type| {
+                    let image_format_properties =
+                        physical_device.image_format_properties(ImageFormatInfo {
+                            flags: create_info.flags,
+                            format: Some(format),
+                            image_type: create_info.dimensions.image_type(),
+                            tiling: create_info.tiling,
+                            usage: create_info.usage,
+                            stencil_usage: create_info.stencil_usage,
+                            external_memory_handle_type: Some(handle_type),
+                            drm_format_modifier_info: drm_format_modifier.map(
+                                |drm_format_modifier| ImageDrmFormatModifierInfo {
+                                    drm_format_modifier,
+                                    sharing: create_info.sharing.clone(),
+                                    ..Default::default()
+                                },
+                            ),
+                            ..Default::default()
+                        })?;
+
+                    Ok::<_, ImageError>(
+                        is_exportable
+                            && image_format_properties.map_or(false, |properties| {
+                                properties.external_memory_properties.exportable
+                            }),
+                    )
+                })
+        };
+
+        if create_info.tiling == ImageTiling::DrmFormatModifier {
+            let supported_modifiers = physical_device.drm_format_modifier_properties(format)?;
+            let mut drm_format_modifiers = if create_info.drm_format_modifiers.is_empty() {
+                supported_modifiers
+                    .iter()
+                    .map(|properties| properties.drm_format_modifier)
+                    .collect()
+            } else {
+                create_info.drm_format_modifiers.clone()
+            };
+
+            let mut result = Ok(());
+            drm_format_modifiers.retain(|&drm_format_modifier| {
+                supported_modifiers
+                    .iter()
+                    .any(|properties| properties.drm_format_modifier == drm_format_modifier)
+                    && match is_exportable(Some(drm_format_modifier)) {
+                        Ok(is_exportable) => is_exportable,
+                        Err(err) => {
+                            result = Err(err);
+                            false
+                        }
+                    }
+            });
+            result?;
+
+            if drm_format_modifiers.is_empty() {
+                return Err(ImageError::DrmFormatModifierNotSupported);
+            }
+
+            create_info.drm_format_modifiers = drm_format_modifiers;
+        } else if !is_exportable(None)? {
+            return Err(ImageError::ExternalMemoryHandleTypesNotSupported {
+                handle_types: export_handle_types,
+            });
+        }
+
+        create_info.external_memory_handle_types = export_handle_types;
+
+        let raw_image = RawImage::new(device.clone(), create_info)?;
+        let requirements = raw_image.memory_requirements()[0];
+        let memory_type_index = allocator
+            .find_memory_type_index(
+                requirements.memory_type_bits,
+                MemoryUsage::DeviceOnly.into(),
+            )
+            .ok_or(ImageError::NoSuitableMemoryType)?;
+
+        // VUID-VkMemoryAllocateInfo-pNext-00639
+        // Guaranteed because we always create a dedicated allocation.
+        let allocation = unsafe {
+            allocator.allocate_dedicated_unchecked(
+                memory_type_index,
+                requirements.layout.size(),
+                Some(DedicatedAllocation::Image(&raw_image)),
+                export_handle_types,
+            )
+        }?;
+        debug_assert!(is_aligned(
+            allocation.offset(),
+            requirements.layout.alignment(),
+        ));
+        debug_assert!(allocation.size() == requirements.layout.size());
+
+        unsafe { raw_image.bind_memory_unchecked([allocation]) }
+            .map(Arc::new)
+            .map_err(|(err, _, _)| err.into())
+    }
+
+    /// Creates a new `Image` from memory that is imported from a Unix file descriptor.
+    ///
+    /// The memory is imported as a dedicated allocation of the image.
+    ///
+    /// # Safety
+    ///
+    /// - `file` must contain memory that is valid for `handle_type`.
+    /// - If `handle_type` is [`ExternalMemoryHandleType::OpaqueFd`], then `file` must have been
+    ///   exported from an image that was created with [`Image::new_exportable`], on the same
+    ///   physical device, with the same `create_info`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.flags` contains [`ImageCreateFlags::DISJOINT`] or
+    ///   [`ImageCreateFlags::SPARSE_BINDING`].
+    /// - Panics if `create_info.external_memory_handle_types` is not empty.
+    pub unsafe fn from_fd(
+        allocator: &(impl MemoryAllocator + ?Sized),
+        mut create_info: ImageCreateInfo,
+        handle_type: ExternalMemoryHandleType,
+        file: File,
+    ) -> Result<Arc<Self>, ImageError> {
+        assert!(!create_info
+            .flags
+            .intersects(ImageCreateFlags::DISJOINT | ImageCreateFlags::SPARSE_BINDING));
+        assert!(create_info.external_memory_handle_types.is_empty());
+
+        create_info.external_memory_handle_types = handle_type.into();
+
+        let device = allocator.device();
+        let raw_image = RawImage::new(device.clone(), create_info)?;
+        let requirements = raw_image.memory_requirements()[0];
+        let mut memory_type_bits = requirements.memory_type_bits;
+
+        // VUID-vkGetMemoryFdPropertiesKHR-handleType-00674
+        if handle_type != ExternalMemoryHandleType::OpaqueFd {
+            memory_type_bits &= device
+                .memory_fd_properties_unchecked(handle_type, &file)?
+                .memory_type_bits;
+        }
+
+        let memory_type_index = allocator
+            .find_memory_type_index(memory_type_bits, MemoryUsage::DeviceOnly.into())
+            .ok_or(ImageError::NoSuitableMemoryType)?;
+
+        let memory = DeviceMemory::import(
+            device.clone(),
+            MemoryAllocateInfo {
+                allocation_size: requirements.layout.size(),
+                memory_type_index,
+                dedicated_allocation: (device.api_version() >= Version::V1_1
+                    || device.enabled_extensions().khr_dedicated_allocation)
+                    .then_some(DedicatedAllocation::Image(&raw_image)),
+                ..Default::default()
+            },
+            MemoryImportInfo::Fd { handle_type, file },
+        )?;
+        let allocation = MemoryAlloc::new(memory)?;
+
+        raw_image
+            .bind_memory_unchecked([allocation])
+            .map(Arc::new)
+            .map_err(|(err, _, _)| err.into())
+    }
+
+    /// Creates a new `Image` from a Linux dma-buf, for example one that was exported from
+    /// another process with [`export_dma_buf`], or by another API.
+    ///
+    /// `create_info.tiling` is set to [`ImageTiling::DrmFormatModifier`], and the image is
+    /// created with the modifier and plane layouts of `dma_buf`.
+    ///
+    /// # Safety
+    ///
+    /// - `dma_buf.file` must be a valid dma-buf, that contains an image with the configuration
+    ///   given by `create_info`, `dma_buf.drm_format_modifier` and `dma_buf.planes`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.flags` contains [`ImageCreateFlags::DISJOINT`] or
+    ///   [`ImageCreateFlags::SPARSE_BINDING`].
+    /// - Panics if `create_info.external_memory_handle_types` is not empty.
+    /// - Panics if `dma_buf.planes` is empty.
+    ///
+    /// [`export_dma_buf`]: Self::export_dma_buf
+    #[cfg(target_os = "linux")]
+    pub unsafe fn from_dma_buf(
+        allocator: &(impl MemoryAllocator + ?Sized),
+        mut create_info: ImageCreateInfo,
+        dma_buf: DmaBuf,
+    ) -> Result<Arc<Self>, ImageError> {
+        let DmaBuf {
+            file,
+            drm_format_modifier,
+            planes,
+        } = dma_buf;
+        assert!(!planes.is_empty());
+
+        // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-size-02267
+        // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-arrayPitch-02268
+        // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-depthPitch-02269
+        let plane_layouts_vk: SmallVec<[_; 4]> = planes
+            .iter()
+            .map(|plane| ash::vk::SubresourceLayout {
+                offset: plane.offset,
+                size: 0,
+                row_pitch: plane.row_pitch,
+                array_pitch: plane.array_pitch.unwrap_or(0),
+                depth_pitch: plane.depth_pitch.unwrap_or(0),
+            })
+            .collect();
+
+        create_info.tiling = ImageTiling::DrmFormatModifier;
+        create_info.drm_format_modifiers = Vec::new();
+        create_info.image_drm_format_modifier_create_info =
+            Some(ImageDrmFormatModifierExplicitCreateInfoEXT {
+                drm_format_modifier,
+                drm_format_modifier_plane_count: plane_layouts_vk.len() as u32,
+                p_plane_layouts: plane_layouts_vk.as_ptr(),
+                ..Default::default()
+            });
+
+        Self::from_fd(
+            allocator,
+            create_info,
+            ExternalMemoryHandleType::DmaBuf,
+            file,
+        )
+    }
+
     fn from_raw(inner: RawImage, memory: ImageMemory) -> Self {
         let aspects = inner.format.unwrap().aspects();
         let aspect_list: SmallVec<[ImageAspect; 4]> = aspects.into_iter().collect();
@@ -2068,6 +2627,16 @@ impl Image {
         let aspect_size = mip_level_size * inner.mip_levels as DeviceSize;
         let range_size = aspect_list.len() as DeviceSize * aspect_size;
         let state = Mutex::new(ImageState::new(range_size, inner.initial_layout));
+        let sparse_residency = match &memory {
+            ImageMemory::Sparse(sparse_memory_requirements) => {
+                Some(Mutex::new(SparseImageResidency::new(
+                    inner.dimensions,
+                    inner.mip_levels,
+                    sparse_memory_requirements,
+                )))
+            }
+            _ => None,
+        };
 
         Image {
             inner,
@@ -2078,6 +2647,7 @@ impl Image {
             mip_level_size,
             range_size,
             state,
+            sparse_residency,
         }
     }
 
@@ -2135,6 +2705,18 @@ impl Image {
         &self.inner.memory_requirements
     }
 
+    /// Returns the sparse memory requirements for this image.
+    ///
+    /// If the image does not have the [`ImageCreateFlags::SPARSE_RESIDENCY`] flag, this returns
+    /// an empty slice.
+    #[inline]
+    pub fn sparse_memory_requirements(&self) -> &[SparseImageMemoryRequirements] {
+        match &self.memory {
+            ImageMemory::Sparse(sparse_memory_requirements) => sparse_memory_requirements,
+            _ => &[],
+        }
+    }
+
     /// Returns the flags the image was created with.
     #[inline]
     pub fn flags(&self) -> ImageCreateFlags {
@@ -2207,6 +2789,68 @@ impl Image {
         self.inner.external_memory_handle_types
     }
 
+    /// Returns the DRM format modifier that the implementation picked for this image, along with
+    /// its properties, if the image has [`ImageTiling::DrmFormatModifier`] tiling.
+    #[inline]
+    pub fn drm_format_modifier(&self) -> Option<DrmFormatModifierProperties> {
+        self.inner.drm_format_modifier
+    }
+
+    /// Exports the memory of the image as a Unix file descriptor, so that it can be imported in
+    /// another process or API.
+    ///
+    /// The image must have been created with [`Image::new_exportable`] or [`Image::from_fd`],
+    /// and `handle_type` must be one of the external memory handle types of the image.
+    pub fn export_fd(&self, handle_type: ExternalMemoryHandleType) -> Result<File, ImageError> {
+        if !self
+            .external_memory_handle_types()
+            .contains_enum(handle_type)
+        {
+            return Err(ImageError::ExternalMemoryHandleTypesNotSupported {
+                handle_types: handle_type.into(),
+            });
+        }
+
+        let allocation = match &self.memory {
+            ImageMemory::Normal(allocations) if allocations.len() == 1 => &allocations[0],
+            _ => {
+                return Err(ImageError::ExternalMemoryHandleTypesNotSupported {
+                    handle_types: handle_type.into(),
+                })
+            }
+        };
+
+        Ok(allocation.device_memory().export_fd(handle_type)?)
+    }
+
+    /// Exports the memory of the image as a Linux dma-buf, along with the information that is
+    /// needed to import it again with [`Image::from_dma_buf`], or in another API.
+    ///
+    /// The image must have [`ImageTiling::DrmFormatModifier`] tiling, and
+    /// [`ExternalMemoryHandleType::DmaBuf`] must be one of its external memory handle types.
+    #[cfg(target_os = "linux")]
+    pub fn export_dma_buf(&self) -> Result<DmaBuf, ImageError> {
+        let drm_format_modifier = self
+            .drm_format_modifier()
+            .ok_or(ImageError::DrmFormatModifierNotSupported)?;
+        let file = self.export_fd(ExternalMemoryHandleType::DmaBuf)?;
+        let planes = [
+            ImageAspect::MemoryPlane0,
+            ImageAspect::MemoryPlane1,
+            ImageAspect::MemoryPlane2,
+        ]
+        .into_iter()
+        .take(drm_format_modifier.drm_format_modifier_plane_count as usize)
+        .map(|aspect| self.subresource_layout(aspect, 0, 0))
+        .collect::<Result<_, _>>()?;
+
+        Ok(DmaBuf {
+            file,
+            drm_format_modifier: drm_format_modifier.drm_format_modifier,
+            planes,
+        })
+    }
+
     /// Returns an `ImageSubresourceLayers` covering the first mip level of the image. All aspects
     /// of the image are selected, or `plane0` if the image is multi-planar.
     #[inline]
@@ -2256,6 +2900,137 @@ impl Image {
             .subresource_layout_unchecked(aspect, mip_level, array_layer)
     }
 
+    /// Returns whether memory is currently bound to the whole of `range` of the image's opaque
+    /// memory range.
+    ///
+    /// This reflects the sparse bind operations that have been submitted to a queue so far, not
+    /// only those that have finished executing. Images that are not sparse always return `true`.
+    #[inline]
+    pub fn is_sparse_range_resident(&self, range: Range<DeviceSize>) -> bool {
+        self.sparse_residency
+            .as_ref()
+            .map_or(true, |residency| residency.lock().is_opaque_resident(range))
+    }
+
+    /// Returns whether memory is currently bound to a sparse image block.
+    ///
+    /// `block` is given in units of sparse image blocks, as returned by
+    /// [`SparseImageFormatProperties::block_count`]. If `mip_level` is part of the mip tail
+    /// region, then `block` is ignored and this returns whether memory is bound to the whole mip
+    /// tail region of `array_layer`.
+    ///
+    /// This reflects the sparse bind operations that have been submitted to a queue so far, not
+    /// only those that have finished executing.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the image does not have the [`ImageCreateFlags::SPARSE_RESIDENCY`] flag.
+    /// - Panics if [`sparse_memory_requirements`] has no element that contains `aspects`.
+    /// - Panics if `mip_level` or `array_layer` are out of range.
+    /// - Panics if `block` is out of range for `mip_level`.
+    ///
+    /// [`sparse_memory_requirements`]: Self::sparse_memory_requirements
+    pub fn is_sparse_block_resident(
+        &self,
+        aspects: ImageAspects,
+        mip_level: u32,
+        array_layer: u32,
+        block: [u32; 3],
+    ) -> bool {
+        assert!(self.flags().intersects(ImageCreateFlags::SPARSE_RESIDENCY));
+        assert!(mip_level < self.mip_levels());
+        assert!(array_layer < self.dimensions().array_layers());
+
+        let sparse_memory_requirements = self
+            .sparse_memory_requirements()
+            .iter()
+            .find(|requirements| requirements.format_properties.aspects.contains(aspects))
+            .expect("the image has no sparse memory requirements for `aspects`");
+
+        if mip_level < sparse_memory_requirements.image_mip_tail_first_lod {
+            let extent = self
+                .dimensions()
+                .mip_level_dimensions(mip_level)
+                .unwrap()
+                .width_height_depth();
+            let block_count = sparse_memory_requirements
+                .format_properties
+                .block_count(extent);
+            assert!(
+                block[0] < block_count[0] && block[1] < block_count[1] && block[2] < block_count[2]
+            );
+        }
+
+        self.sparse_residency
+            .as_ref()
+            .unwrap()
+            .lock()
+            .is_block_resident(aspects, mip_level, array_layer, block)
+            .unwrap()
+    }
+
+    /// Returns whether memory is currently bound to the whole mip tail region of `array_layer`.
+    ///
+    /// If the mip tail region is shared by all array layers, then `array_layer` is ignored.
+    ///
+    /// This reflects the sparse bind operations that have been submitted to a queue so far, not
+    /// only those that have finished executing.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the image does not have the [`ImageCreateFlags::SPARSE_RESIDENCY`] flag.
+    /// - Panics if [`sparse_memory_requirements`] has no element that contains `aspects`.
+    /// - Panics if `array_layer` is out of range.
+    ///
+    /// [`sparse_memory_requirements`]: Self::sparse_memory_requirements
+    pub fn is_sparse_mip_tail_resident(&self, aspects: ImageAspects, array_layer: u32) -> bool {
+        assert!(self.flags().intersects(ImageCreateFlags::SPARSE_RESIDENCY));
+        assert!(array_layer < self.dimensions().array_layers());
+
+        self.sparse_residency
+            .as_ref()
+            .unwrap()
+            .lock()
+            .is_mip_tail_resident(aspects, array_layer)
+            .expect("the image has no sparse memory requirements for `aspects`")
+    }
+
+    /// Records the effect of opaque sparse bind operations on the image, after they have been
+    /// submitted to a queue.
+    pub(crate) fn sparse_bind_opaque(&self, memory_binds: &[SparseImageOpaqueMemoryBind]) {
+        let mut residency = match &self.sparse_residency {
+            Some(residency) => residency.lock(),
+            None => return,
+        };
+
+        for memory_bind in memory_binds {
+            let &SparseImageOpaqueMemoryBind {
+                offset,
+                size,
+                ref memory,
+                metadata: _,
+            } = memory_bind;
+
+            residency.bind_opaque(
+                offset..offset + size,
+                memory.as_ref().map(|(memory, _)| memory),
+            );
+        }
+    }
+
+    /// Records the effect of sparse image block bind operations on the image, after they have
+    /// been submitted to a queue.
+    pub(crate) fn sparse_bind_blocks(&self, memory_binds: &[SparseImageMemoryBind]) {
+        let mut residency = match &self.sparse_residency {
+            Some(residency) => residency.lock(),
+            None => return,
+        };
+
+        for memory_bind in memory_binds {
+            residency.bind_blocks(memory_bind);
+        }
+    }
+
     pub(crate) fn range_size(&self) -> DeviceSize {
         self.range_size
     }
@@ -2763,6 +3538,22 @@ impl Iterator for SubresourceRangeIterator {
 
 impl FusedIterator for SubresourceRangeIterator {}
 
+/// A Linux dma-buf that holds the memory of an image, along with the layout of the image in it.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct DmaBuf {
+    /// The dma-buf file descriptor.
+    pub file: File,
+
+    /// The Linux DRM format modifier of the image.
+    pub drm_format_modifier: u64,
+
+    /// The layout of each memory plane of the image, relative to the start of the dma-buf.
+    ///
+    /// The `size` of each plane is ignored when importing.
+    pub planes: SmallVec<[SubresourceLayout; 4]>,
+}
+
 /// Describes the memory layout of a single subresource of an image.
 ///
 /// The address of a texel at `(x, y, z, layer)` is `layer * array_pitch + z * depth_pitch +
@@ -2797,6 +3588,9 @@ pub enum ImageError {
     /// Allocating memory failed.
     AllocError(AllocationCreationError),
 
+    /// Importing or exporting memory failed.
+    DeviceMemoryError(DeviceMemoryError),
+
     RequirementNotMet {
         required_for: &'static str,
         requires_one_of: RequiresOneOf,
@@ -2852,6 +3646,16 @@ pub enum ImageError {
     /// not support disjoint images.
     DisjointFormatNotSupported,
 
+    /// None of the provided DRM format modifiers are supported for the image configuration, or
+    /// the image does not have a DRM format modifier.
+    DrmFormatModifierNotSupported,
+
+    /// The external memory handle types are not supported for exporting the image
+    /// configuration, or are not among the handle types of the image.
+    ExternalMemoryHandleTypesNotSupported {
+        handle_types: ExternalMemoryHandleTypes,
+    },
+
     /// One or more external memory handle types were provided, but the initial layout was not
     /// `Undefined`.
     ExternalMemoryInvalidInitialLayout,
@@ -2959,6 +3763,10 @@ pub enum ImageError {
     /// Multisampling was enabled, but the image type was not 2D.
     MultisampleNot2d,
 
+    /// None of the memory types that the image can be bound to are suitable, or compatible with
+    /// the imported memory.
+    NoSuitableMemoryType,
+
     /// The image has optimal tiling, which is not supported for this operation.
     OptimalTilingNotSupported,
 
@@ -2975,6 +3783,19 @@ pub enum ImageError {
         queue_family_count: u32,
     },
 
+    /// The `sparse_residency` or `sparse_aliased` flag was enabled, or a sparse operation was
+    /// performed, but the image does not have the `sparse_binding` flag.
+    SparseBindingFlagMissing,
+
+    /// The image has the `sparse_binding` flag, which is not allowed for this operation.
+    SparseBindingFlagNotAllowed,
+
+    /// The `sparse_residency` flag was enabled, but the image type was 1D.
+    SparseResidency1d,
+
+    /// The `sparse_residency` flag was enabled, and tiling was `Linear`.
+    SparseResidencyLinearTiling,
+
     /// The provided `usage` and `stencil_usage` have different values for
     /// `depth_stencil_attachment` or `transient_attachment`.
     StencilUsageMismatch {
@@ -2997,7 +3818,7 @@ pub enum ImageError {
 
     DirectImageViewCreationFailed(ImageViewCreationError),
 
-    /// If and only if tiling is `DRMFormatModifier`, then `image_drm_format_modifier_create_info` must not be `None`.
+    /// If and only if tiling is `DRMFormatModifier`, then either `image_drm_format_modifier_create_info` must not be `None` or `drm_format_modifiers` must not be empty, but not both.
     DrmFormatModifierRequiresCreateInfo,
 }
 
@@ -3005,6 +3826,7 @@ impl Error for ImageError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             ImageError::AllocError(err) => Some(err),
+            ImageError::DeviceMemoryError(err) => Some(err),
             _ => None,
         }
     }
@@ -3015,6 +3837,7 @@ impl Display for ImageError {
         match self {
             Self::VulkanError(_) => write!(f, "a runtime error occurred"),
             Self::AllocError(_) => write!(f, "allocating memory failed"),
+            Self::DeviceMemoryError(_) => write!(f, "importing or exporting memory failed"),
             Self::RequirementNotMet {
                 required_for,
                 requires_one_of,
@@ -3089,6 +3912,17 @@ impl Display for ImageError {
                 "the `disjoint` flag was enabled, but the given format is either not multi-planar, \
                 or does not support disjoint images",
             ),
+            Self::DrmFormatModifierNotSupported => write!(
+                f,
+                "none of the provided DRM format modifiers are supported for the image \
+                configuration, or the image does not have a DRM format modifier",
+            ),
+            Self::ExternalMemoryHandleTypesNotSupported { handle_types } => write!(
+                f,
+                "the external memory handle types {:?} are not supported for exporting the image \
+                configuration, or are not among the handle types of the image",
+                handle_types,
+            ),
             Self::ExternalMemoryInvalidInitialLayout => write!(
                 f,
                 "one or more external memory handle types were provided, but the initial layout \
@@ -3221,6 +4055,11 @@ impl Display for ImageError {
                 f,
                 "multisampling was enabled, but the image type was not 2D",
             ),
+            Self::NoSuitableMemoryType => write!(
+                f,
+                "none of the memory types that the image can be bound to are suitable, or \
+                compatible with the imported memory",
+            ),
             Self::OptimalTilingNotSupported => write!(
                 f,
                 "the image has optimal tiling, which is not supported for this operation",
@@ -3234,6 +4073,23 @@ impl Display for ImageError {
                 "the sharing mode was set to `Concurrent`, but one of the specified queue family \
                 indices was out of range",
             ),
+            Self::SparseBindingFlagMissing => write!(
+                f,
+                "the `sparse_residency` or `sparse_aliased` flag was enabled, or a sparse \
+                operation was performed, but the image does not have the `sparse_binding` flag",
+            ),
+            Self::SparseBindingFlagNotAllowed => write!(
+                f,
+                "the image has the `sparse_binding` flag, which is not allowed for this operation",
+            ),
+            Self::SparseResidency1d => write!(
+                f,
+                "the `sparse_residency` flag was enabled, but the image type was 1D",
+            ),
+            Self::SparseResidencyLinearTiling => write!(
+                f,
+                "the `sparse_residency` flag was enabled, and tiling was `Linear`",
+            ),
             Self::StencilUsageMismatch {
                 usage: _,
                 stencil_usage: _,
@@ -3258,7 +4114,7 @@ impl Display for ImageError {
                 write!(f, "a YCbCr format was given, but the image type was not 2D")
             }
             Self::DirectImageViewCreationFailed(e) => write!(f, "Image view creation failed {}", e),
-	    Self::DrmFormatModifierRequiresCreateInfo => write!(f, "If and only if tiling is `DRMFormatModifier`, then `image_drm_format_modifier_create_info` must be `Some`"),
+	    Self::DrmFormatModifierRequiresCreateInfo => write!(f, "If and only if tiling is `DRMFormatModifier`, then either `image_drm_format_modifier_create_info` must be `Some` or `drm_format_modifiers` must not be empty, but not both"),
         }
     }
 }
@@ -3275,6 +4131,35 @@ impl From<AllocationCreationError> for ImageError {
     }
 }
 
+impl From<DeviceMemoryError> for ImageError {
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::DeviceMemoryError(err)
+    }
+}
+
+impl From<PhysicalDeviceError> for ImageError {
+    fn from(err: PhysicalDeviceError) -> Self {
+        match err {
+            PhysicalDeviceError::VulkanError(err) => Self::VulkanError(err),
+            PhysicalDeviceError::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            },
+            PhysicalDeviceError::QueueFamilyIndexOutOfRange {
+                queue_family_index,
+                queue_family_count,
+            } => Self::SharingQueueFamilyIndexOutOfRange {
+                queue_family_index,
+                queue_family_count,
+            },
+            _ => panic!("unexpected error value"),
+        }
+    }
+}
+
 impl From<RequirementNotMet> for ImageError {
     fn from(err: RequirementNotMet) -> Self {
         Self::RequirementNotMet {
@@ -3286,17 +4171,70 @@ impl From<RequirementNotMet> for ImageError {
 
 #[cfg(test)]
 mod tests {
-    use super::{ImageCreateInfo, ImageError, ImageUsage, RawImage};
+    use super::{Image, ImageCreateInfo, ImageError, ImageUsage, RawImage};
     use crate::{
         format::Format,
         image::{
             sys::SubresourceRangeIterator, ImageAspect, ImageAspects, ImageCreateFlags,
-            ImageDimensions, ImageSubresourceRange, SampleCount,
+            ImageDimensions, ImageSubresourceRange, ImageTiling, SampleCount,
         },
+        memory::{allocator::StandardMemoryAllocator, ExternalMemoryHandleTypes},
         DeviceSize, RequiresOneOf,
     };
     use smallvec::SmallVec;
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn export_import_dma_buf() {
+        let (exporter, _) = gfx_dev_and_queue!(extensions: [
+            khr_external_memory_fd,
+            ext_external_memory_dma_buf,
+            ext_image_drm_format_modifier
+        ];);
+        let (importer, _) = gfx_dev_and_queue!(extensions: [
+            khr_external_memory_fd,
+            ext_external_memory_dma_buf,
+            ext_image_drm_format_modifier
+        ];);
+        let exporter_allocator = StandardMemoryAllocator::new_default(exporter);
+        let importer_allocator = StandardMemoryAllocator::new_default(importer);
+        let create_info = || ImageCreateInfo {
+            dimensions: ImageDimensions::Dim2d {
+                width: 64,
+                height: 64,
+                array_layers: 1,
+            },
+            format: Some(Format::R8G8B8A8_UNORM),
+            tiling: ImageTiling::DrmFormatModifier,
+            usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+            ..Default::default()
+        };
+
+        let exported = match Image::new_exportable(
+            &exporter_allocator,
+            create_info(),
+            ExternalMemoryHandleTypes::DMA_BUF,
+        ) {
+            Ok(image) => image,
+            Err(ImageError::DrmFormatModifierNotSupported) => return,
+            Err(err) => panic!("{}", err),
+        };
+        let drm_format_modifier = exported.drm_format_modifier().unwrap();
+        let dma_buf = exported.export_dma_buf().unwrap();
+        assert_eq!(
+            dma_buf.drm_format_modifier,
+            drm_format_modifier.drm_format_modifier
+        );
+        assert_eq!(
+            dma_buf.planes.len(),
+            drm_format_modifier.drm_format_modifier_plane_count as usize,
+        );
+
+        let imported =
+            unsafe { Image::from_dma_buf(&importer_allocator, create_info(), dma_buf) }.unwrap();
+        assert_eq!(imported.drm_format_modifier(), Some(drm_format_modifier));
+    }
+
     #[test]
     fn create_sampled() {
         let (device, _) = gfx_dev_and_queue!();
@@ -169,6 +169,7 @@ pub use {extensions::ExtensionProperties, version::Version};
 mod tests;
 #[macro_use]
 mod extensions;
+pub mod acceleration_structure;
 pub mod buffer;
 pub mod command_buffer;
 pub mod deferred;
@@ -0,0 +1,872 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Compaction of the memory blocks of a [`GenericMemoryAllocator`].
+//!
+//! Over time, as resources of different sizes and lifetimes come and go, the `DeviceMemory`
+//! blocks of an allocator get riddled with holes (see [external fragmentation]). Because
+//! allocations can't be moved while resources are bound to them, the only way to compact the
+//! blocks is to create new resources in a better location, copy the contents over, and have the
+//! owners of the old resources switch to the new ones. This is what
+//! [`GenericMemoryAllocator::defragment`] helps with.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use vulkano::buffer::Buffer;
+//! # use vulkano::command_buffer::{
+//! #     allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+//! # };
+//! # use vulkano::memory::allocator::{DefragmentationInfo, StandardMemoryAllocator};
+//! # let device: Arc<vulkano::device::Device> = return;
+//! # let queue: Arc<vulkano::device::Queue> = return;
+//! # let memory_allocator: StandardMemoryAllocator = return;
+//! # let command_buffer_allocator: StandardCommandBufferAllocator = return;
+//! # let mut buffers: Vec<Arc<Buffer>> = return;
+//! #
+//! let mut builder = AutoCommandBufferBuilder::primary(
+//!     &command_buffer_allocator,
+//!     queue.queue_family_index(),
+//!     CommandBufferUsage::OneTimeSubmit,
+//! )
+//! .unwrap();
+//!
+//! let remap = memory_allocator
+//!     .defragment(&mut builder, DefragmentationInfo::buffers(buffers.iter().cloned()))
+//!     .unwrap();
+//!
+//! // Switch to the new buffers. The old ones must be kept alive until the copies have completed,
+//! // which the command buffer takes care of.
+//! for buffer in &mut buffers {
+//!     if let Some(new_buffer) = remap.get_buffer(buffer) {
+//!         *buffer = new_buffer.clone();
+//!     }
+//! }
+//!
+//! // Submit the command buffer, and once it has completed and the old buffers were dropped, give
+//! // the emptied blocks back to the device.
+//! memory_allocator.release_unused_blocks();
+//! ```
+//!
+//! [external fragmentation]: super#external-fragmentation
+
+use super::{
+    AllocationType, GenericMemoryAllocator, MemoryAlloc, SuballocationCreateInfo, Suballocator,
+};
+use crate::{
+    buffer::{
+        sys::{BufferCreateInfo, RawBuffer},
+        Buffer, BufferError, BufferMemory, BufferUsage, Subbuffer,
+    },
+    command_buffer::{
+        allocator::CommandBufferAllocator, AutoCommandBufferBuilder, CopyBufferInfo, CopyError,
+        CopyImageInfo, ImageCopy,
+    },
+    device::{Device, DeviceOwned},
+    image::{
+        sys::{Image, ImageCreateInfo, ImageMemory, RawImage},
+        traits::ImageContent,
+        ImageAccess, ImageAspects, ImageDescriptorLayouts, ImageError, ImageInner, ImageLayout,
+        ImageSubresourceLayers, ImageTiling, ImageUsage,
+    },
+    memory::MemoryRequirements,
+    DeviceSize, VulkanObject,
+};
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+impl<S: Suballocator> GenericMemoryAllocator<S> {
+    /// Moves buffers and images out of the least used blocks of the allocator and into the free
+    /// space of fuller blocks, so that the emptied blocks can be [released].
+    ///
+    /// For every resource that is moved, a new resource is created with the same parameters,
+    /// bound to the new location, and a copy from the old resource to the new one is recorded into
+    /// `builder`. The returned [`DefragmentationRemap`] maps the old resources to the new ones. It
+    /// is up to the owners of the resources to switch to the new ones; the old resources keep
+    /// their memory allocated until they are dropped.
+    ///
+    /// Only resources that were suballocated from this allocator are considered, which excludes
+    /// dedicated allocations, sparse resources and images with more than one memory plane.
+    /// Resources that were not created with both the `TRANSFER_SRC` and `TRANSFER_DST` usages
+    /// can't be copied and are skipped, as are images whose [`ImageAccess`] only covers part of
+    /// the image. No new `DeviceMemory` blocks are allocated; resources for which there isn't
+    /// enough free space in a fuller block stay where they are.
+    ///
+    /// The new locations are aligned according to the memory requirements of the new resources.
+    ///
+    /// > **Note**: The new buffers have a different device address than the old ones, if the
+    /// > buffers were created with the [`SHADER_DEVICE_ADDRESS`] usage. Any copies of the old
+    /// > device addresses must be updated as well.
+    ///
+    /// [released]: Self::release_unused_blocks
+    /// [`SHADER_DEVICE_ADDRESS`]: BufferUsage::SHADER_DEVICE_ADDRESS
+    pub fn defragment<L, A>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L, A>,
+        defragmentation_info: DefragmentationInfo,
+    ) -> Result<DefragmentationRemap, DefragmentationError>
+    where
+        A: CommandBufferAllocator,
+    {
+        let DefragmentationInfo {
+            mut buffers,
+            mut images,
+            max_bytes_to_move,
+            max_allocations_to_move,
+            _ne: _,
+        } = defragmentation_info;
+
+        buffers.sort_unstable_by_key(|buffer| buffer.handle());
+        buffers.dedup_by_key(|buffer| buffer.handle());
+        buffers.retain(|buffer| {
+            buffer
+                .usage()
+                .contains(BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST)
+        });
+
+        images.sort_unstable_by_key(|image| image.inner().image.handle());
+        images.dedup_by_key(|image| image.inner().image.handle());
+        images.retain(|image| {
+            let ImageInner {
+                image,
+                first_layer,
+                num_layers,
+                first_mipmap_level,
+                num_mipmap_levels,
+            } = image.inner();
+
+            (image.usage() | image.stencil_usage())
+                .contains(ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST)
+                && !image
+                    .format()
+                    .unwrap()
+                    .aspects()
+                    .intersects(ImageAspects::PLANE_0)
+                && first_layer == 0
+                && num_layers == image.dimensions().array_layers()
+                && first_mipmap_level == 0
+                && num_mipmap_levels == image.mip_levels()
+        });
+
+        let mut remap = DefragmentationRemap {
+            buffers: Vec::new(),
+            images: Vec::new(),
+            bytes_moved: 0,
+        };
+
+        for pool in self.pools.iter() {
+            let blocks = pool.blocks.read();
+
+            if blocks.len() < 2 {
+                continue;
+            }
+
+            // The least used blocks are emptied first, and their resources are moved into the
+            // fullest blocks that still have room for them.
+            let mut order: Vec<_> = (0..blocks.len()).collect();
+            order.sort_by_key(|&index| blocks[index].region().size() - blocks[index].free_size());
+
+            for (position, &source) in order.iter().enumerate() {
+                let source_memory = blocks[source].region().root();
+                let targets = &order[position + 1..];
+
+                for buffer in &buffers {
+                    if remap.len() >= max_allocations_to_move as usize {
+                        return Ok(remap);
+                    }
+
+                    let allocation = match buffer.memory() {
+                        BufferMemory::Normal(allocation) => allocation,
+                        BufferMemory::Sparse => continue,
+                    };
+
+                    match (allocation.root(), source_memory) {
+                        (Some(a), Some(b)) if Arc::ptr_eq(a, b) => (),
+                        _ => continue,
+                    }
+
+                    let size = allocation.size();
+
+                    if max_bytes_to_move - remap.bytes_moved < size {
+                        continue;
+                    }
+
+                    let raw_buffer = RawBuffer::new(
+                        self.device.clone(),
+                        BufferCreateInfo {
+                            flags: buffer.flags(),
+                            sharing: buffer.sharing().clone(),
+                            size: buffer.size(),
+                            usage: buffer.usage(),
+                            external_memory_handle_types: buffer.external_memory_handle_types(),
+                            ..Default::default()
+                        },
+                    )?;
+
+                    let mut new_allocation = match allocate_in_blocks(
+                        &blocks,
+                        targets,
+                        raw_buffer.memory_requirements(),
+                        allocation.device_memory().memory_type_index(),
+                        AllocationType::Linear,
+                    ) {
+                        Some(new_allocation) => new_allocation,
+                        None => continue,
+                    };
+                    new_allocation.shrink(buffer.size());
+
+                    let new_buffer = Arc::new(
+                        unsafe { raw_buffer.bind_memory_unchecked(new_allocation) }
+                            .map_err(|(err, _, _)| BufferError::from(err))?,
+                    );
+
+                    builder.copy_buffer(CopyBufferInfo::buffers(
+                        Subbuffer::from(buffer.clone()),
+                        Subbuffer::from(new_buffer.clone()),
+                    ))?;
+
+                    remap.bytes_moved += size;
+                    remap.buffers.push((buffer.clone(), new_buffer));
+                }
+
+                for image in &images {
+                    if remap.len() >= max_allocations_to_move as usize {
+                        return Ok(remap);
+                    }
+
+                    let inner = image.inner().image;
+                    let allocation = match inner.memory() {
+                        ImageMemory::Normal(allocations) if allocations.len() == 1 => {
+                            &allocations[0]
+                        }
+                        _ => continue,
+                    };
+
+                    match (allocation.root(), source_memory) {
+                        (Some(a), Some(b)) if Arc::ptr_eq(a, b) => (),
+                        _ => continue,
+                    }
+
+                    let size = allocation.size();
+
+                    if max_bytes_to_move - remap.bytes_moved < size {
+                        continue;
+                    }
+
+                    let allocation_type = match inner.tiling() {
+                        ImageTiling::Optimal => AllocationType::NonLinear,
+                        ImageTiling::Linear => AllocationType::Linear,
+                        ImageTiling::DrmFormatModifier => continue,
+                    };
+
+                    let raw_image = RawImage::new(
+                        self.device.clone(),
+                        ImageCreateInfo {
+                            flags: inner.flags(),
+                            dimensions: inner.dimensions(),
+                            format: inner.format(),
+                            mip_levels: inner.mip_levels(),
+                            samples: inner.samples(),
+                            tiling: inner.tiling(),
+                            usage: inner.usage(),
+                            stencil_usage: inner.stencil_usage(),
+                            sharing: inner.sharing().clone(),
+                            external_memory_handle_types: inner.external_memory_handle_types(),
+                            ..Default::default()
+                        },
+                    )?;
+
+                    let new_allocation = match allocate_in_blocks(
+                        &blocks,
+                        targets,
+                        &raw_image.memory_requirements()[0],
+                        allocation.device_memory().memory_type_index(),
+                        allocation_type,
+                    ) {
+                        Some(new_allocation) => new_allocation,
+                        None => continue,
+                    };
+
+                    let new_image = Arc::new(DefragmentedImage {
+                        inner: Arc::new(
+                            unsafe { raw_image.bind_memory_unchecked([new_allocation]) }
+                                .map_err(|(err, _, _)| ImageError::from(err))?,
+                        ),
+                        initial_layout: image.initial_layout_requirement(),
+                        final_layout: image.final_layout_requirement(),
+                        descriptor_layouts: image.descriptor_layouts(),
+                        layout_initialized: AtomicBool::new(false),
+                    });
+
+                    builder.copy_image(CopyImageInfo {
+                        regions: (0..inner.mip_levels())
+                            .map(|mip_level| ImageCopy {
+                                src_subresource: ImageSubresourceLayers {
+                                    mip_level,
+                                    ..inner.subresource_layers()
+                                },
+                                dst_subresource: ImageSubresourceLayers {
+                                    mip_level,
+                                    ..inner.subresource_layers()
+                                },
+                                extent: inner
+                                    .dimensions()
+                                    .mip_level_dimensions(mip_level)
+                                    .unwrap()
+                                    .width_height_depth(),
+                                ..Default::default()
+                            })
+                            .collect(),
+                        ..CopyImageInfo::images(image.clone(), new_image.clone())
+                    })?;
+
+                    remap.bytes_moved += size;
+                    remap.images.push((image.clone(), new_image));
+                }
+            }
+        }
+
+        Ok(remap)
+    }
+
+    /// Frees the `DeviceMemory` blocks of the allocator that no longer have any allocations in
+    /// them.
+    ///
+    /// The allocator never frees blocks on its own, so this can be used to give memory back to
+    /// the device after [defragmenting] the allocator, or after a large number of resources were
+    /// dropped.
+    ///
+    /// [defragmenting]: Self::defragment
+    pub fn release_unused_blocks(&self) {
+        for pool in self.pools.iter() {
+            let mut blocks = pool.blocks.write();
+
+            *blocks = blocks
+                .drain(..)
+                .filter_map(|block| block.try_into_region().err())
+                .collect();
+        }
+    }
+}
+
+/// Allocates memory for a moved resource in the first of `targets` that has room for it, trying
+/// the fullest block first.
+fn allocate_in_blocks<S: Suballocator>(
+    blocks: &[S],
+    targets: &[usize],
+    requirements: &MemoryRequirements,
+    memory_type_index: u32,
+    allocation_type: AllocationType,
+) -> Option<MemoryAlloc> {
+    if requirements.requires_dedicated_allocation
+        || requirements.memory_type_bits & (1 << memory_type_index) == 0
+    {
+        return None;
+    }
+
+    let create_info = SuballocationCreateInfo {
+        layout: requirements.layout,
+        allocation_type,
+        _ne: crate::NonExhaustive(()),
+    };
+
+    targets
+        .iter()
+        .rev()
+        .find_map(|&target| blocks[target].allocate(create_info.clone()).ok())
+}
+
+/// Parameters to defragment a [`GenericMemoryAllocator`].
+#[derive(Clone, Debug)]
+pub struct DefragmentationInfo {
+    /// The buffers that may be moved.
+    ///
+    /// The default value is empty.
+    pub buffers: Vec<Arc<Buffer>>,
+
+    /// The images that may be moved.
+    ///
+    /// The default value is empty.
+    pub images: Vec<Arc<dyn ImageAccess>>,
+
+    /// The maximum total size of the allocations that may be moved.
+    ///
+    /// The default value is [`DeviceSize::MAX`].
+    pub max_bytes_to_move: DeviceSize,
+
+    /// The maximum number of allocations that may be moved.
+    ///
+    /// The default value is [`u32::MAX`].
+    pub max_allocations_to_move: u32,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl DefragmentationInfo {
+    /// Returns a `DefragmentationInfo` with the specified `buffers`.
+    #[inline]
+    pub fn buffers(buffers: impl IntoIterator<Item = Arc<Buffer>>) -> Self {
+        Self {
+            buffers: buffers.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns a `DefragmentationInfo` with the specified `images`.
+    #[inline]
+    pub fn images(images: impl IntoIterator<Item = Arc<dyn ImageAccess>>) -> Self {
+        Self {
+            images: images.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for DefragmentationInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            buffers: Vec::new(),
+            images: Vec::new(),
+            max_bytes_to_move: DeviceSize::MAX,
+            max_allocations_to_move: u32::MAX,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// The resources that were moved by [`GenericMemoryAllocator::defragment`].
+#[derive(Clone, Debug)]
+pub struct DefragmentationRemap {
+    buffers: Vec<(Arc<Buffer>, Arc<Buffer>)>,
+    images: Vec<(Arc<dyn ImageAccess>, Arc<DefragmentedImage>)>,
+    bytes_moved: DeviceSize,
+}
+
+impl DefragmentationRemap {
+    /// Returns the pairs of old and new buffers.
+    #[inline]
+    pub fn buffers(&self) -> &[(Arc<Buffer>, Arc<Buffer>)] {
+        &self.buffers
+    }
+
+    /// Returns the pairs of old and new images.
+    #[inline]
+    pub fn images(&self) -> &[(Arc<dyn ImageAccess>, Arc<DefragmentedImage>)] {
+        &self.images
+    }
+
+    /// Returns the new buffer that `buffer` was moved to, or `None` if it wasn't moved.
+    #[inline]
+    pub fn get_buffer(&self, buffer: &Buffer) -> Option<&Arc<Buffer>> {
+        self.buffers
+            .iter()
+            .find_map(|(old, new)| (old.as_ref() == buffer).then_some(new))
+    }
+
+    /// Returns the new image that `image` was moved to, or `None` if it wasn't moved.
+    #[inline]
+    pub fn get_image(&self, image: &Image) -> Option<&Arc<DefragmentedImage>> {
+        self.images
+            .iter()
+            .find_map(|(old, new)| (old.inner().image.as_ref() == image).then_some(new))
+    }
+
+    /// Returns the total size of the allocations that were moved.
+    #[inline]
+    pub fn bytes_moved(&self) -> DeviceSize {
+        self.bytes_moved
+    }
+
+    /// Returns the number of resources that were moved.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffers.len() + self.images.len()
+    }
+
+    /// Returns whether no resources were moved.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty() && self.images.is_empty()
+    }
+}
+
+/// An image that was created by [`GenericMemoryAllocator::defragment`] to replace an image that
+/// was moved.
+///
+/// The image has the same parameters as the old image, and it expects the same layouts as the old
+/// image at the start and end of command buffers.
+#[derive(Debug)]
+pub struct DefragmentedImage {
+    inner: Arc<Image>,
+    initial_layout: ImageLayout,
+    final_layout: ImageLayout,
+    descriptor_layouts: Option<ImageDescriptorLayouts>,
+
+    // If false, then the contents haven't been copied over from the old image yet, and the image
+    // is still `Undefined`.
+    layout_initialized: AtomicBool,
+}
+
+unsafe impl ImageAccess for DefragmentedImage {
+    #[inline]
+    fn inner(&self) -> ImageInner<'_> {
+        ImageInner {
+            image: &self.inner,
+            first_layer: 0,
+            num_layers: self.inner.dimensions().array_layers(),
+            first_mipmap_level: 0,
+            num_mipmap_levels: self.inner.mip_levels(),
+        }
+    }
+
+    #[inline]
+    fn initial_layout_requirement(&self) -> ImageLayout {
+        self.initial_layout
+    }
+
+    #[inline]
+    fn final_layout_requirement(&self) -> ImageLayout {
+        self.final_layout
+    }
+
+    #[inline]
+    unsafe fn layout_initialized(&self) {
+        self.layout_initialized.store(true, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn is_layout_initialized(&self) -> bool {
+        self.layout_initialized.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    fn descriptor_layouts(&self) -> Option<ImageDescriptorLayouts> {
+        self.descriptor_layouts
+    }
+}
+
+unsafe impl DeviceOwned for DefragmentedImage {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+unsafe impl<P> ImageContent<P> for DefragmentedImage {
+    fn matches_format(&self) -> bool {
+        true // FIXME:
+    }
+}
+
+impl PartialEq for DefragmentedImage {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner()
+    }
+}
+
+impl Eq for DefragmentedImage {}
+
+impl Hash for DefragmentedImage {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().hash(state);
+    }
+}
+
+/// Error that can happen when defragmenting a [`GenericMemoryAllocator`].
+#[derive(Clone, Debug)]
+pub enum DefragmentationError {
+    /// Creating a new buffer failed.
+    BufferError(BufferError),
+
+    /// Creating a new image failed.
+    ImageError(ImageError),
+
+    /// Recording a copy command failed.
+    CopyError(CopyError),
+}
+
+impl Error for DefragmentationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::BufferError(err) => Some(err),
+            Self::ImageError(err) => Some(err),
+            Self::CopyError(err) => Some(err),
+        }
+    }
+}
+
+impl Display for DefragmentationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::BufferError(_) => write!(f, "creating a new buffer failed"),
+            Self::ImageError(_) => write!(f, "creating a new image failed"),
+            Self::CopyError(_) => write!(f, "recording a copy command failed"),
+        }
+    }
+}
+
+impl From<BufferError> for DefragmentationError {
+    fn from(err: BufferError) -> Self {
+        Self::BufferError(err)
+    }
+}
+
+impl From<ImageError> for DefragmentationError {
+    fn from(err: ImageError) -> Self {
+        Self::ImageError(err)
+    }
+}
+
+impl From<CopyError> for DefragmentationError {
+    fn from(err: CopyError) -> Self {
+        Self::CopyError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DefragmentationInfo;
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferMemory, BufferUsage, Subbuffer},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            ClearColorImageInfo, CommandBufferUsage, CopyImageToBufferInfo,
+            PrimaryCommandBufferAbstract,
+        },
+        format::Format,
+        image::{
+            sys::ImageMemory, ImageAccess, ImageCreateFlags, ImageDimensions, ImageUsage,
+            StorageImage,
+        },
+        memory::allocator::{
+            AllocationCreateInfo, GenericMemoryAllocatorCreateInfo, MemoryUsage,
+            StandardMemoryAllocator,
+        },
+        sync::GpuFuture,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn move_into_fuller_block() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = StandardMemoryAllocator::new(
+            device.clone(),
+            GenericMemoryAllocatorCreateInfo {
+                block_sizes: &[(0, 8192)],
+                dedicated_allocation: false,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // 8 buffers per block, in 2 blocks.
+        let mut buffers: Vec<_> = (0..16u32)
+            .map(|i| {
+                Buffer::from_iter(
+                    &memory_allocator,
+                    BufferCreateInfo {
+                        usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                        ..Default::default()
+                    },
+                    AllocationCreateInfo {
+                        usage: MemoryUsage::Upload,
+                        ..Default::default()
+                    },
+                    (0..256).map(|j| i * 256 + j),
+                )
+                .unwrap()
+            })
+            .collect();
+        let memory_type_index = match buffers[0].buffer().memory() {
+            BufferMemory::Normal(allocation) => allocation.device_memory().memory_type_index(),
+            BufferMemory::Sparse => unreachable!(),
+        };
+        assert_eq!(
+            memory_allocator.pools[memory_type_index as usize]
+                .blocks
+                .read()
+                .len(),
+            2,
+        );
+
+        // Leave only one buffer in the first block, and one hole in the second.
+        let kept = buffers.remove(0);
+        buffers.drain(..8);
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        let remap = memory_allocator
+            .defragment(
+                &mut cbb,
+                DefragmentationInfo::buffers([kept.buffer().clone()]),
+            )
+            .unwrap();
+        assert_eq!(remap.buffers().len(), 1);
+        assert_eq!(remap.bytes_moved(), 1024);
+
+        let moved = remap.get_buffer(kept.buffer()).unwrap().clone();
+        drop(remap);
+
+        cbb.build()
+            .unwrap()
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        drop(kept);
+
+        memory_allocator.release_unused_blocks();
+        assert_eq!(
+            memory_allocator.pools[memory_type_index as usize]
+                .blocks
+                .read()
+                .len(),
+            1,
+        );
+
+        let moved = Subbuffer::from(moved).try_cast_slice::<u32>().unwrap();
+        assert!(moved.read().unwrap().iter().copied().eq(0..256));
+    }
+
+    #[test]
+    fn move_image_into_fuller_block() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = StandardMemoryAllocator::new(
+            device.clone(),
+            GenericMemoryAllocatorCreateInfo {
+                block_sizes: &[(0, 1 << 20)],
+                dedicated_allocation: false,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // 4 images per block, in 2 blocks.
+        let mut images: Vec<_> = (0..8)
+            .map(|_| {
+                StorageImage::with_usage(
+                    &memory_allocator,
+                    ImageDimensions::Dim2d {
+                        width: 256,
+                        height: 256,
+                        array_layers: 1,
+                    },
+                    Format::R8G8B8A8_UNORM,
+                    ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+                    ImageCreateFlags::empty(),
+                    [queue.queue_family_index()],
+                )
+                .unwrap()
+            })
+            .collect();
+        let memory_type_index = match images[0].inner().image.memory() {
+            ImageMemory::Normal(allocations) => allocations[0].device_memory().memory_type_index(),
+            _ => unreachable!(),
+        };
+        if memory_allocator.pools[memory_type_index as usize]
+            .blocks
+            .read()
+            .len()
+            != 2
+        {
+            // The images are too big for 4 of them to fit in a block on this implementation.
+            return;
+        }
+
+        // Leave only one image in the first block, and one hole in the second.
+        let kept = images.remove(0);
+        images.drain(..4);
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device, Default::default());
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        cbb.clear_color_image(ClearColorImageInfo {
+            clear_value: [1.0, 0.0, 0.0, 1.0].into(),
+            ..ClearColorImageInfo::image(kept.clone())
+        })
+        .unwrap();
+
+        let remap = memory_allocator
+            .defragment(&mut cbb, DefragmentationInfo::images([kept.clone() as _]))
+            .unwrap();
+        assert!(remap.buffers().is_empty());
+        assert_eq!(remap.images().len(), 1);
+
+        let moved = remap.get_image(kept.inner().image).unwrap().clone();
+        drop(remap);
+
+        let buffer = Buffer::from_iter(
+            &memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Download,
+                ..Default::default()
+            },
+            (0..256 * 256).map(|_| [0u8; 4]),
+        )
+        .unwrap();
+        cbb.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+            moved as Arc<dyn ImageAccess>,
+            buffer.clone(),
+        ))
+        .unwrap();
+
+        cbb.build()
+            .unwrap()
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        drop(kept);
+
+        memory_allocator.release_unused_blocks();
+        assert_eq!(
+            memory_allocator.pools[memory_type_index as usize]
+                .blocks
+                .read()
+                .len(),
+            1,
+        );
+
+        assert!(buffer
+            .read()
+            .unwrap()
+            .iter()
+            .all(|&texel| texel == [255, 0, 0, 255]));
+    }
+}
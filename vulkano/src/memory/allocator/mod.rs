@@ -216,21 +216,35 @@
 //! [`mem::forget`]: std::mem::forget
 //! [region]: Suballocator#regions
 
+mod defragmentation;
 mod layout;
+mod residency;
+mod statistics;
 pub mod suballocator;
+mod transient;
 
 use self::array_vec::ArrayVec;
 pub use self::{
+    defragmentation::{
+        DefragmentationError, DefragmentationInfo, DefragmentationRemap, DefragmentedImage,
+    },
     layout::DeviceLayout,
+    residency::{ResidencyError, ResidencyId, ResidencyManager, ResidentImage, ResidentResource},
+    statistics::{BlockDump, MemoryAllocatorDump, MemoryTypeDump, MemoryTypeStatistics},
     suballocator::{
         AllocationType, BuddyAllocator, BumpAllocator, FreeListAllocator, MemoryAlloc,
-        PoolAllocator, SuballocationCreateInfo, SuballocationCreationError, Suballocator,
+        PoolAllocator, SuballocationCreateInfo, SuballocationCreationError, SuballocationNode,
+        SuballocationType, Suballocator, SuballocatorStatistics,
+    },
+    transient::{
+        TransientBufferCreateInfo, TransientImage, TransientImageCreateInfo, TransientPool,
+        TransientPoolCreateInfo, TransientPoolError,
     },
 };
 use super::{
     DedicatedAllocation, DeviceAlignment, DeviceMemory, ExternalMemoryHandleTypes,
-    MemoryAllocateFlags, MemoryAllocateInfo, MemoryProperties, MemoryPropertyFlags,
-    MemoryRequirements, MemoryType,
+    MemoryAllocateFlags, MemoryAllocateInfo, MemoryHeapBudget, MemoryProperties,
+    MemoryPropertyFlags, MemoryRequirements, MemoryType,
 };
 use crate::{
     device::{Device, DeviceOwned},
@@ -432,6 +446,23 @@ pub struct AllocationCreateInfo {
     /// The default value is [`MemoryAllocatePreference::Unknown`].
     pub allocate_preference: MemoryAllocatePreference,
 
+    /// What the allocator should do if allocating [`DeviceMemory`] would exceed the budget of
+    /// the memory heap.
+    ///
+    /// The default value is [`MemoryBudgetBehavior::Ignore`].
+    pub budget_behavior: MemoryBudgetBehavior,
+
+    /// The priority of the allocation, between 0.0 and 1.0, relative to other allocations.
+    ///
+    /// This is only applied when a dedicated [`DeviceMemory`] block is allocated for the
+    /// allocation, because suballocations share the priority of their block. It is ignored if the
+    /// [`memory_priority`] feature is not enabled on the device.
+    ///
+    /// The default value is `0.5`.
+    ///
+    /// [`memory_priority`]: crate::device::Features::memory_priority
+    pub priority: f32,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -441,6 +472,8 @@ impl Default for AllocationCreateInfo {
         AllocationCreateInfo {
             usage: MemoryUsage::DeviceOnly,
             allocate_preference: MemoryAllocatePreference::Unknown,
+            budget_behavior: MemoryBudgetBehavior::Ignore,
+            priority: 0.5,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -518,6 +551,35 @@ pub enum MemoryAllocatePreference {
     AlwaysAllocate,
 }
 
+/// Describes what to do when allocating [`DeviceMemory`] would exceed the budget of a memory
+/// heap.
+///
+/// The budget of a heap is taken from [`PhysicalDevice::memory_budget`] if the
+/// [`ext_memory_budget`] extension is enabled on the device. Otherwise it is estimated to be 80%
+/// of the size of the heap, and the usage of the heap is what the device has allocated through
+/// vulkano.
+///
+/// Suballocating from existing blocks never affects the usage of a heap, so this only has an
+/// effect when the allocator would need to allocate a new block or a dedicated allocation.
+///
+/// [`PhysicalDevice::memory_budget`]: crate::device::physical::PhysicalDevice::memory_budget
+/// [`ext_memory_budget`]: crate::device::DeviceExtensions::ext_memory_budget
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MemoryBudgetBehavior {
+    /// The budget is not taken into account. Allocating can exceed the budget, in which case it
+    /// may fail or degrade performance.
+    Ignore,
+
+    /// If the budget of the heap of the preferred memory type would be exceeded, the allocator
+    /// falls back to the next suitable memory type, like it does when running out of memory.
+    FallBack,
+
+    /// If the budget of the heap of the preferred memory type would be exceeded, the allocation
+    /// fails with [`AllocationCreationError::BudgetExceeded`].
+    Fail,
+}
+
 /// Error that can be returned when creating an [allocation] using a [memory allocator].
 ///
 /// [allocation]: MemoryAlloc
@@ -549,6 +611,10 @@ pub enum AllocationCreationError {
     /// This is returned when using [`GenericMemoryAllocator<Arc<PoolAllocator<BLOCK_SIZE>>>`] if
     /// the allocation size exceeded `BLOCK_SIZE`.
     SuballocatorBlockSizeExceeded,
+
+    /// Allocating [`DeviceMemory`] would have exceeded the budget of the memory heap, and
+    /// [`AllocationCreateInfo::budget_behavior`] did not allow this.
+    BudgetExceeded,
 }
 
 impl Error for AllocationCreationError {
@@ -578,6 +644,10 @@ impl Display for AllocationCreationError {
                 f,
                 "the allocation size was greater than the suballocator's block size",
             ),
+            Self::BudgetExceeded => write!(
+                f,
+                "allocating device memory would have exceeded the budget of the memory heap",
+            ),
         }
     }
 }
@@ -858,6 +928,64 @@ impl<S: Suballocator> GenericMemoryAllocator<S> {
         }
     }
 
+    /// Returns the memory usage and budget of each memory heap.
+    ///
+    /// The returned `Vec` has one element for each element of
+    /// [`memory_properties().memory_heaps`](MemoryProperties::memory_heaps) of the physical
+    /// device. See [`MemoryBudgetBehavior`] for how the usage and budget are determined.
+    pub fn heap_usage(&self) -> Vec<MemoryHeapUsage> {
+        let mut heap_usage: Vec<_> = self
+            .heap_budgets()
+            .into_iter()
+            .map(|MemoryHeapBudget { budget, usage, .. }| MemoryHeapUsage {
+                block_bytes: 0,
+                allocated_bytes: 0,
+                usage,
+                budget,
+            })
+            .collect();
+
+        for pool in self.pools.iter() {
+            let usage = &mut heap_usage[pool.memory_type.heap_index as usize];
+
+            for block in pool.blocks.read().iter() {
+                let size = block.region().size();
+                usage.block_bytes += size;
+                usage.allocated_bytes += size - block.free_size();
+            }
+        }
+
+        heap_usage
+    }
+
+    fn heap_budgets(&self) -> Vec<MemoryHeapBudget> {
+        let physical_device = self.device.physical_device();
+
+        if self.device.enabled_extensions().ext_memory_budget {
+            let mut heap_budgets = unsafe { physical_device.memory_budget_unchecked() };
+
+            // The implementation may not have picked up on our latest allocations yet.
+            for (heap_index, heap_budget) in heap_budgets.iter_mut().enumerate() {
+                heap_budget.usage = heap_budget
+                    .usage
+                    .max(self.device.memory_heap_usage(heap_index as u32));
+            }
+
+            heap_budgets
+        } else {
+            physical_device
+                .memory_properties()
+                .memory_heaps
+                .iter()
+                .enumerate()
+                .map(|(heap_index, heap)| MemoryHeapBudget {
+                    budget: heap.size / 10 * 8,
+                    usage: self.device.memory_heap_usage(heap_index as u32),
+                })
+                .collect()
+        }
+    }
+
     fn validate_allocate_from_type(&self, memory_type_index: u32) {
         let memory_type = &self.pools[usize::try_from(memory_type_index).unwrap()].memory_type;
 
@@ -917,6 +1045,45 @@ impl<S: Suballocator> GenericMemoryAllocator<S> {
         // VUID-VkExportMemoryAllocateInfo-handleTypes-00656
         // Can't validate, must be ensured by user
     }
+
+    unsafe fn allocate_dedicated_with_priority(
+        &self,
+        memory_type_index: u32,
+        allocation_size: DeviceSize,
+        mut dedicated_allocation: Option<DedicatedAllocation<'_>>,
+        export_handle_types: ExternalMemoryHandleTypes,
+        mut priority: f32,
+    ) -> Result<MemoryAlloc, AllocationCreationError> {
+        // Providers of `VkMemoryDedicatedAllocateInfo`
+        if !(self.device.api_version() >= Version::V1_1
+            || self.device.enabled_extensions().khr_dedicated_allocation)
+        {
+            dedicated_allocation = None;
+        }
+
+        // Provider of `VkMemoryPriorityAllocateInfoEXT`
+        if !self.device.enabled_features().memory_priority {
+            priority = 0.5;
+        }
+
+        let allocate_info = MemoryAllocateInfo {
+            allocation_size,
+            memory_type_index,
+            dedicated_allocation,
+            export_handle_types,
+            flags: self.flags,
+            priority,
+            ..Default::default()
+        };
+        let mut allocation = MemoryAlloc::new(DeviceMemory::allocate_unchecked(
+            self.device.clone(),
+            allocate_info,
+            None,
+        )?)?;
+        allocation.set_allocation_type(self.allocation_type);
+
+        Ok(allocation)
+    }
 }
 
 unsafe impl<S: Suballocator> MemoryAllocator for GenericMemoryAllocator<S> {
@@ -1186,12 +1353,16 @@ unsafe impl<S: Suballocator> MemoryAllocator for GenericMemoryAllocator<S> {
     /// - Returns [`SuballocatorBlockSizeExceeded`] if `S` is `PoolAllocator<BLOCK_SIZE>` and
     ///   `create_info.size` is greater than `BLOCK_SIZE` and a dedicated allocation was not
     ///   created.
+    /// - Returns [`BudgetExceeded`] if `create_info.budget_behavior` is not
+    ///   [`MemoryBudgetBehavior::Ignore`] and allocating `DeviceMemory` would exceed the budget of
+    ///   the heap of every suitable memory type that was tried.
     ///
     /// [`TooManyObjects`]: VulkanError::TooManyObjects
     /// [`OutOfPoolMemory`]: AllocationCreationError::OutOfPoolMemory
     /// [`DedicatedAllocationRequired`]: AllocationCreationError::DedicatedAllocationRequired
     /// [`BlockSizeExceeded`]: AllocationCreationError::BlockSizeExceeded
     /// [`SuballocatorBlockSizeExceeded`]: AllocationCreationError::SuballocatorBlockSizeExceeded
+    /// [`BudgetExceeded`]: AllocationCreationError::BudgetExceeded
     fn allocate(
         &self,
         requirements: MemoryRequirements,
@@ -1227,6 +1398,8 @@ unsafe impl<S: Suballocator> MemoryAllocator for GenericMemoryAllocator<S> {
         let AllocationCreateInfo {
             usage,
             allocate_preference,
+            budget_behavior,
+            priority,
             _ne: _,
         } = create_info;
 
@@ -1258,77 +1431,119 @@ unsafe impl<S: Suballocator> MemoryAllocator for GenericMemoryAllocator<S> {
             let memory_type = self.pools[memory_type_index as usize].memory_type;
             let block_size = self.block_sizes[memory_type.heap_index as usize];
 
-            let res = match allocate_preference {
-                MemoryAllocatePreference::Unknown => {
-                    if requires_dedicated_allocation {
-                        self.allocate_dedicated_unchecked(
-                            memory_type_index,
-                            size,
-                            dedicated_allocation,
-                            export_handle_types,
-                        )
+            let within_budget = match (budget_behavior, allocate_preference) {
+                (MemoryBudgetBehavior::Ignore, _)
+                | (_, MemoryAllocatePreference::NeverAllocate) => true,
+                _ => {
+                    // The size of the `DeviceMemory` that would be allocated if the allocation
+                    // doesn't fit in any of the existing blocks.
+                    let allocation_size = if requires_dedicated_allocation
+                        || allocate_preference == MemoryAllocatePreference::AlwaysAllocate
+                        || size > block_size / 2
+                    {
+                        size
                     } else {
-                        if size > block_size / 2 {
-                            prefers_dedicated_allocation = true;
-                        }
-                        if self.device.allocation_count() > self.max_allocations
-                            && size <= block_size
-                        {
-                            prefers_dedicated_allocation = false;
-                        }
+                        block_size
+                    };
+                    let MemoryHeapBudget { budget, usage, .. } =
+                        self.heap_budgets()[memory_type.heap_index as usize];
 
-                        if prefers_dedicated_allocation {
-                            self.allocate_dedicated_unchecked(
+                    usage.saturating_add(allocation_size) <= budget
+                }
+            };
+
+            let res = if !within_budget {
+                // Suballocating doesn't change the usage of the heap, so that's still fine.
+                if requires_dedicated_allocation
+                    || allocate_preference == MemoryAllocatePreference::AlwaysAllocate
+                {
+                    Err(AllocationCreationError::BudgetExceeded)
+                } else {
+                    self.allocate_from_type_unchecked(memory_type_index, create_info.clone(), true)
+                        .map_err(|_| AllocationCreationError::BudgetExceeded)
+                }
+            } else {
+                match allocate_preference {
+                    MemoryAllocatePreference::Unknown => {
+                        if requires_dedicated_allocation {
+                            self.allocate_dedicated_with_priority(
                                 memory_type_index,
                                 size,
                                 dedicated_allocation,
                                 export_handle_types,
+                                priority,
                             )
-                            // Fall back to suballocation.
-                            .or_else(|err| {
-                                if size <= block_size {
-                                    self.allocate_from_type_unchecked(
-                                        memory_type_index,
-                                        create_info.clone(),
-                                        true, // A dedicated allocation already failed.
-                                    )
-                                    .map_err(|_| err)
-                                } else {
-                                    Err(err)
-                                }
-                            })
                         } else {
-                            self.allocate_from_type_unchecked(
-                                memory_type_index,
-                                create_info.clone(),
-                                false,
-                            )
-                            // Fall back to dedicated allocation. It is possible that the 1/8 block
-                            // size tried was greater than the allocation size, so there's hope.
-                            .or_else(|_| {
-                                self.allocate_dedicated_unchecked(
+                            if size > block_size / 2 {
+                                prefers_dedicated_allocation = true;
+                            }
+                            if self.device.allocation_count() > self.max_allocations
+                                && size <= block_size
+                            {
+                                prefers_dedicated_allocation = false;
+                            }
+
+                            if prefers_dedicated_allocation {
+                                self.allocate_dedicated_with_priority(
                                     memory_type_index,
                                     size,
                                     dedicated_allocation,
                                     export_handle_types,
+                                    priority,
                                 )
-                            })
+                                // Fall back to suballocation.
+                                .or_else(|err| {
+                                    if size <= block_size {
+                                        self.allocate_from_type_unchecked(
+                                            memory_type_index,
+                                            create_info.clone(),
+                                            true, // A dedicated allocation already failed.
+                                        )
+                                        .map_err(|_| err)
+                                    } else {
+                                        Err(err)
+                                    }
+                                })
+                            } else {
+                                self.allocate_from_type_unchecked(
+                                    memory_type_index,
+                                    create_info.clone(),
+                                    false,
+                                )
+                                // Fall back to dedicated allocation. It is possible that the 1/8 block
+                                // size tried was greater than the allocation size, so there's hope.
+                                .or_else(|_| {
+                                    self.allocate_dedicated_with_priority(
+                                        memory_type_index,
+                                        size,
+                                        dedicated_allocation,
+                                        export_handle_types,
+                                        priority,
+                                    )
+                                })
+                            }
                         }
                     }
-                }
-                MemoryAllocatePreference::NeverAllocate => {
-                    if requires_dedicated_allocation {
-                        return Err(AllocationCreationError::DedicatedAllocationRequired);
-                    }
+                    MemoryAllocatePreference::NeverAllocate => {
+                        if requires_dedicated_allocation {
+                            return Err(AllocationCreationError::DedicatedAllocationRequired);
+                        }
 
-                    self.allocate_from_type_unchecked(memory_type_index, create_info.clone(), true)
+                        self.allocate_from_type_unchecked(
+                            memory_type_index,
+                            create_info.clone(),
+                            true,
+                        )
+                    }
+                    MemoryAllocatePreference::AlwaysAllocate => self
+                        .allocate_dedicated_with_priority(
+                            memory_type_index,
+                            size,
+                            dedicated_allocation,
+                            export_handle_types,
+                            priority,
+                        ),
                 }
-                MemoryAllocatePreference::AlwaysAllocate => self.allocate_dedicated_unchecked(
-                    memory_type_index,
-                    size,
-                    dedicated_allocation,
-                    export_handle_types,
-                ),
             };
 
             match res {
@@ -1337,6 +1552,11 @@ unsafe impl<S: Suballocator> MemoryAllocator for GenericMemoryAllocator<S> {
                 Err(AllocationCreationError::SuballocatorBlockSizeExceeded) => {
                     return Err(AllocationCreationError::SuballocatorBlockSizeExceeded);
                 }
+                Err(AllocationCreationError::BudgetExceeded)
+                    if budget_behavior == MemoryBudgetBehavior::Fail =>
+                {
+                    return Err(AllocationCreationError::BudgetExceeded);
+                }
                 // Try a different memory type.
                 Err(err) => {
                     memory_type_bits &= !(1 << memory_type_index);
@@ -1352,32 +1572,16 @@ unsafe impl<S: Suballocator> MemoryAllocator for GenericMemoryAllocator<S> {
         &self,
         memory_type_index: u32,
         allocation_size: DeviceSize,
-        mut dedicated_allocation: Option<DedicatedAllocation<'_>>,
+        dedicated_allocation: Option<DedicatedAllocation<'_>>,
         export_handle_types: ExternalMemoryHandleTypes,
     ) -> Result<MemoryAlloc, AllocationCreationError> {
-        // Providers of `VkMemoryDedicatedAllocateInfo`
-        if !(self.device.api_version() >= Version::V1_1
-            || self.device.enabled_extensions().khr_dedicated_allocation)
-        {
-            dedicated_allocation = None;
-        }
-
-        let allocate_info = MemoryAllocateInfo {
-            allocation_size,
+        self.allocate_dedicated_with_priority(
             memory_type_index,
+            allocation_size,
             dedicated_allocation,
             export_handle_types,
-            flags: self.flags,
-            ..Default::default()
-        };
-        let mut allocation = MemoryAlloc::new(DeviceMemory::allocate_unchecked(
-            self.device.clone(),
-            allocate_info,
-            None,
-        )?)?;
-        allocation.set_allocation_type(self.allocation_type);
-
-        Ok(allocation)
+            0.5,
+        )
     }
 }
 
@@ -1459,6 +1663,26 @@ unsafe impl<S: Suballocator> DeviceOwned for GenericMemoryAllocator<S> {
     }
 }
 
+/// The memory usage of a memory heap, as returned by [`GenericMemoryAllocator::heap_usage`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MemoryHeapUsage {
+    /// The total size of the `DeviceMemory` blocks that the allocator has allocated from the heap.
+    /// This doesn't include dedicated allocations.
+    pub block_bytes: DeviceSize,
+
+    /// The number of bytes that are suballocated from the blocks of the allocator.
+    pub allocated_bytes: DeviceSize,
+
+    /// The number of bytes of the heap that are in use, including dedicated allocations and
+    /// memory allocated by other allocators.
+    pub usage: DeviceSize,
+
+    /// The number of bytes of the heap that can be used before allocations may fail or cause
+    /// performance degradation.
+    pub budget: DeviceSize,
+}
+
 /// Parameters to create a new [`GenericMemoryAllocator`].
 #[derive(Clone, Debug)]
 pub struct GenericMemoryAllocatorCreateInfo<'b, 'e> {
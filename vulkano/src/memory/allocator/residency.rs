@@ -0,0 +1,1335 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Moving resources out of device-local memory when it runs short, and back in when they are
+//! needed again.
+//!
+//! Applications that stream in more data than fits in device-local memory at once, such as open
+//! world games, need to decide which resources stay device-local and which can live in host
+//! memory for a while. A [`ResidencyManager`] keeps track of a set of buffers and images along
+//! with their priority and when the GPU last used them, and when a heap goes over its [budget],
+//! it evicts the resources that are least likely to be needed: it copies them to host memory and
+//! lets go of the device-local resource. When an evicted resource is needed again, it can be
+//! [made resident] again, which copies it back into a new device-local resource.
+//!
+//! Because evicted resources are replaced with new ones when they are made resident, the
+//! resources must always be looked up through the manager with [`ResidencyManager::buffer`] and
+//! [`ResidencyManager::image`], instead of holding on to them.
+//!
+//! The manager finds out which resources the GPU uses from the futures of the command buffers
+//! that were submitted: a resource is in use for as long as a future that uses it hasn't been
+//! cleaned up. This is checked every time resources are evicted, so the eviction functions should
+//! be called regularly, for example once per frame, for the manager to know which resources were
+//! used recently.
+//!
+//! When the [`memory_priority`] feature is enabled on the device, resources that are made
+//! resident get a dedicated allocation with their priority, which the implementation uses to
+//! decide what to keep in device-local memory. When the [`pageable_device_local_memory`] feature
+//! is enabled as well, the priority of registered resources with a dedicated allocation is kept up
+//! to date with [`DeviceMemory::set_priority`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use vulkano::buffer::Buffer;
+//! # use vulkano::command_buffer::{
+//! #     allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+//! # };
+//! # use vulkano::image::ImageAccess;
+//! # use vulkano::memory::allocator::{ResidencyManager, StandardMemoryAllocator};
+//! # let queue: Arc<vulkano::device::Queue> = return;
+//! # let memory_allocator: Arc<StandardMemoryAllocator> = return;
+//! # let command_buffer_allocator: StandardCommandBufferAllocator = return;
+//! # let terrain_buffer: Arc<Buffer> = return;
+//! # let terrain_texture: Arc<dyn ImageAccess> = return;
+//! #
+//! let residency_manager = ResidencyManager::new(memory_allocator.clone());
+//! let terrain = residency_manager.register_buffer(terrain_buffer, 0.25);
+//! let texture = residency_manager.register_image(terrain_texture, 0.25);
+//!
+//! // Every frame...
+//! let mut builder = AutoCommandBufferBuilder::primary(
+//!     &command_buffer_allocator,
+//!     queue.queue_family_index(),
+//!     CommandBufferUsage::OneTimeSubmit,
+//! )
+//! .unwrap();
+//!
+//! // Make room for this frame if we went over budget.
+//! residency_manager.evict_to_budget(&mut builder).unwrap();
+//!
+//! // Bring the terrain back in if it was evicted.
+//! residency_manager.make_resident(&mut builder, terrain).unwrap();
+//! residency_manager.make_resident(&mut builder, texture).unwrap();
+//! let terrain_buffer = residency_manager.buffer(terrain).unwrap();
+//! let terrain_texture = residency_manager.image(texture).unwrap();
+//! ```
+//!
+//! [budget]: crate::device::physical::PhysicalDevice::memory_budget
+//! [made resident]: ResidencyManager::make_resident
+//! [`memory_priority`]: crate::device::Features::memory_priority
+//! [`pageable_device_local_memory`]: crate::device::Features::pageable_device_local_memory
+//! [`DeviceMemory::set_priority`]: crate::memory::DeviceMemory::set_priority
+
+use super::{
+    AllocationCreateInfo, AllocationCreationError, AllocationType, DedicatedAllocation,
+    GenericMemoryAllocator, MemoryAlloc, MemoryAllocatePreference, MemoryAllocator,
+    MemoryBudgetBehavior, MemoryTypeFilter, MemoryUsage, Suballocator,
+};
+use crate::{
+    buffer::{
+        sys::RawBuffer, Buffer, BufferCreateFlags, BufferCreateInfo, BufferError, BufferMemory,
+        BufferUsage, Subbuffer,
+    },
+    command_buffer::{
+        allocator::CommandBufferAllocator, AutoCommandBufferBuilder, BufferImageCopy,
+        CopyBufferInfo, CopyBufferToImageInfo, CopyError, CopyImageToBufferInfo,
+    },
+    device::{Device, DeviceOwned},
+    image::{
+        sys::{Image, ImageCreateInfo, ImageMemory, RawImage},
+        traits::ImageContent,
+        ImageAccess, ImageAspects, ImageDescriptorLayouts, ImageError, ImageInner, ImageLayout,
+        ImageSubresourceLayers, ImageTiling, ImageUsage,
+    },
+    memory::MemoryPropertyFlags,
+    DeviceSize,
+};
+use ahash::HashMap;
+use parking_lot::Mutex;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+};
+
+/// Keeps track of the residency of a set of buffers and images, and evicts them to host memory
+/// when memory runs short.
+///
+/// See the [module-level documentation] for more information.
+///
+/// [module-level documentation]: self
+#[derive(Debug)]
+pub struct ResidencyManager<S: Suballocator> {
+    allocator: Arc<GenericMemoryAllocator<S>>,
+    state: Mutex<ResidencyState>,
+}
+
+#[derive(Debug, Default)]
+struct ResidencyState {
+    entries: HashMap<ResidencyId, ResidencyEntry>,
+    next_id: u64,
+    // Incremented each time resources are evicted. Resources that the GPU is using at that point
+    // are marked as used in the new epoch, to order the resources by how recently the GPU used
+    // them.
+    epoch: u64,
+}
+
+#[derive(Debug)]
+struct ResidencyEntry {
+    priority: f32,
+    last_use: u64,
+    kind: ResourceKind,
+    residency: Residency,
+}
+
+// The parameters to recreate the resource with.
+#[derive(Debug)]
+enum ResourceKind {
+    Buffer {
+        create_info: BufferCreateInfo,
+    },
+    Image {
+        create_info: ImageCreateInfo,
+        initial_layout: ImageLayout,
+        final_layout: ImageLayout,
+        descriptor_layouts: Option<ImageDescriptorLayouts>,
+        // The regions of the host buffer that each subresource is copied to, and the size of the
+        // host buffer.
+        regions: Vec<BufferImageCopy>,
+        host_size: DeviceSize,
+    },
+}
+
+#[derive(Debug)]
+enum Residency {
+    Resident(ResidentResource),
+    Evicted {
+        host_buffer: Subbuffer<[u8]>,
+        // The evicted device-local resource, which is kept alive by the command buffer that
+        // copies it to `host_buffer`. Its memory isn't reclaimed until that command buffer is
+        // dropped.
+        device_resource: WeakResource,
+        heap_index: u32,
+        size: DeviceSize,
+    },
+}
+
+#[derive(Debug)]
+enum WeakResource {
+    Buffer(Weak<Buffer>),
+    Image(Weak<Image>),
+}
+
+impl<S: Suballocator> ResidencyManager<S> {
+    /// Creates a new `ResidencyManager` that allocates new resources from `allocator`.
+    ///
+    /// The resources that are registered with the manager don't have to be allocated from
+    /// `allocator`, but only the resources that are can be taken into account when comparing the
+    /// usage of a heap against its budget in [`evict_to_budget`].
+    ///
+    /// [`evict_to_budget`]: Self::evict_to_budget
+    #[inline]
+    pub fn new(allocator: Arc<GenericMemoryAllocator<S>>) -> Self {
+        ResidencyManager {
+            allocator,
+            state: Mutex::new(ResidencyState::default()),
+        }
+    }
+
+    /// Returns the allocator that the manager allocates new resources from.
+    #[inline]
+    pub fn allocator(&self) -> &Arc<GenericMemoryAllocator<S>> {
+        &self.allocator
+    }
+
+    /// Registers `buffer` with the manager, with the given `priority`, and returns the
+    /// identifier to look it up with.
+    ///
+    /// `priority` is between 0.0 and 1.0. Resources with a lower priority are evicted first.
+    ///
+    /// Only buffers that are bound to [`DEVICE_LOCAL`] memory are ever evicted. Buffers that
+    /// aren't are still tracked, so that it doesn't matter for the users of the manager where
+    /// they ended up being allocated.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `priority` is not between 0.0 and 1.0 inclusive.
+    /// - Panics if `buffer` was not created with both the [`TRANSFER_SRC`] and [`TRANSFER_DST`]
+    ///   usages.
+    /// - Panics if `buffer` is a sparse buffer.
+    ///
+    /// [`DEVICE_LOCAL`]: MemoryPropertyFlags::DEVICE_LOCAL
+    /// [`TRANSFER_SRC`]: BufferUsage::TRANSFER_SRC
+    /// [`TRANSFER_DST`]: BufferUsage::TRANSFER_DST
+    pub fn register_buffer(&self, buffer: Arc<Buffer>, priority: f32) -> ResidencyId {
+        assert!((0.0..=1.0).contains(&priority));
+        assert!(buffer
+            .usage()
+            .contains(BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST));
+        assert!(!buffer.flags().intersects(BufferCreateFlags::SPARSE_BINDING));
+
+        let kind = ResourceKind::Buffer {
+            create_info: BufferCreateInfo {
+                flags: buffer.flags(),
+                sharing: buffer.sharing().clone(),
+                size: buffer.size(),
+                usage: buffer.usage(),
+                external_memory_handle_types: buffer.external_memory_handle_types(),
+                ..Default::default()
+            },
+        };
+        let resource = ResidentResource::Buffer(buffer);
+        resource.set_memory_priority(priority);
+
+        self.state.lock().insert(priority, kind, resource)
+    }
+
+    /// Registers `image` with the manager, with the given `priority`, and returns the
+    /// identifier to look it up with.
+    ///
+    /// `priority` is between 0.0 and 1.0. Resources with a lower priority are evicted first.
+    ///
+    /// Only images that are bound to [`DEVICE_LOCAL`] memory are ever evicted. Images that
+    /// aren't are still tracked, so that it doesn't matter for the users of the manager where
+    /// they ended up being allocated. The images that replace `image` when it is made resident
+    /// again expect the same layouts at the start and end of command buffers as `image`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `priority` is not between 0.0 and 1.0 inclusive.
+    /// - Panics if `image` doesn't cover all array layers and mip levels of its inner image.
+    /// - Panics if `image` was not created with both the [`TRANSFER_SRC`] and [`TRANSFER_DST`]
+    ///   usages.
+    /// - Panics if `image` is not bound to a single allocation, which is the case for sparse
+    ///   images and images with a multi-planar format.
+    /// - Panics if `image` was created with [`ImageTiling::DrmFormatModifier`].
+    ///
+    /// [`DEVICE_LOCAL`]: MemoryPropertyFlags::DEVICE_LOCAL
+    /// [`TRANSFER_SRC`]: ImageUsage::TRANSFER_SRC
+    /// [`TRANSFER_DST`]: ImageUsage::TRANSFER_DST
+    pub fn register_image(&self, image: Arc<dyn ImageAccess>, priority: f32) -> ResidencyId {
+        assert!((0.0..=1.0).contains(&priority));
+
+        let ImageInner {
+            image: inner,
+            first_layer,
+            num_layers,
+            first_mipmap_level,
+            num_mipmap_levels,
+        } = image.inner();
+        assert!(first_layer == 0 && num_layers == inner.dimensions().array_layers());
+        assert!(first_mipmap_level == 0 && num_mipmap_levels == inner.mip_levels());
+        assert!((inner.usage() | inner.stencil_usage())
+            .contains(ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST));
+        assert!(
+            matches!(inner.memory(), ImageMemory::Normal(allocations) if allocations.len() == 1)
+        );
+        assert!(!inner
+            .format()
+            .unwrap()
+            .aspects()
+            .intersects(ImageAspects::PLANE_0));
+        assert!(inner.tiling() != ImageTiling::DrmFormatModifier);
+
+        let (regions, host_size) = image_regions(inner);
+        let kind = ResourceKind::Image {
+            create_info: ImageCreateInfo {
+                flags: inner.flags(),
+                dimensions: inner.dimensions(),
+                format: inner.format(),
+                mip_levels: inner.mip_levels(),
+                samples: inner.samples(),
+                tiling: inner.tiling(),
+                usage: inner.usage(),
+                stencil_usage: inner.stencil_usage(),
+                sharing: inner.sharing().clone(),
+                external_memory_handle_types: inner.external_memory_handle_types(),
+                ..Default::default()
+            },
+            initial_layout: image.initial_layout_requirement(),
+            final_layout: image.final_layout_requirement(),
+            descriptor_layouts: image.descriptor_layouts(),
+            regions,
+            host_size,
+        };
+        let resource = ResidentResource::Image(image);
+        resource.set_memory_priority(priority);
+
+        self.state.lock().insert(priority, kind, resource)
+    }
+
+    /// Stops tracking the resource with the given `id`, and returns it if it is resident.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `id` is not registered with the manager.
+    pub fn unregister(&self, id: ResidencyId) -> Option<ResidentResource> {
+        let entry = self
+            .state
+            .lock()
+            .entries
+            .remove(&id)
+            .expect("`id` is not registered with the manager");
+
+        match entry.residency {
+            Residency::Resident(resource) => Some(resource),
+            Residency::Evicted { .. } => None,
+        }
+    }
+
+    /// Returns the buffer with the given `id`, or `None` if it is evicted.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `id` is not registered with the manager.
+    /// - Panics if `id` doesn't identify a buffer.
+    pub fn buffer(&self, id: ResidencyId) -> Option<Arc<Buffer>> {
+        let mut state = self.state.lock();
+        let entry = state.entry_mut(id);
+        assert!(matches!(entry.kind, ResourceKind::Buffer { .. }));
+
+        match &entry.residency {
+            Residency::Resident(ResidentResource::Buffer(buffer)) => Some(buffer.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the image with the given `id`, or `None` if it is evicted.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `id` is not registered with the manager.
+    /// - Panics if `id` doesn't identify an image.
+    pub fn image(&self, id: ResidencyId) -> Option<Arc<dyn ImageAccess>> {
+        let mut state = self.state.lock();
+        let entry = state.entry_mut(id);
+        assert!(matches!(entry.kind, ResourceKind::Image { .. }));
+
+        match &entry.residency {
+            Residency::Resident(ResidentResource::Image(image)) => Some(image.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the resource with the given `id` is resident.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `id` is not registered with the manager.
+    pub fn is_resident(&self, id: ResidencyId) -> bool {
+        let mut state = self.state.lock();
+
+        matches!(state.entry_mut(id).residency, Residency::Resident(_))
+    }
+
+    /// Changes the priority of the resource with the given `id`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `priority` is not between 0.0 and 1.0 inclusive.
+    /// - Panics if `id` is not registered with the manager.
+    pub fn set_priority(&self, id: ResidencyId, priority: f32) {
+        assert!((0.0..=1.0).contains(&priority));
+
+        let mut state = self.state.lock();
+        let entry = state.entry_mut(id);
+        entry.priority = priority;
+
+        if let Residency::Resident(resource) = &entry.residency {
+            resource.set_memory_priority(priority);
+        }
+    }
+
+    /// Makes the resource with the given `id` resident, if it is evicted, and returns it.
+    ///
+    /// A new device-local resource is created with the same parameters as the original
+    /// resource, and a copy of the evicted contents into it is recorded into `builder`. The new
+    /// resource is allocated with [`MemoryBudgetBehavior::Fail`], so that making a resource
+    /// resident never pushes a heap over its budget. If the allocation fails, more resources must
+    /// be evicted before trying again. If the [`memory_priority`] feature is enabled on the
+    /// device, the resource gets a dedicated allocation with the priority of the resource.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `id` is not registered with the manager.
+    ///
+    /// [`memory_priority`]: crate::device::Features::memory_priority
+    pub fn make_resident<L, A>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L, A>,
+        id: ResidencyId,
+    ) -> Result<ResidentResource, ResidencyError>
+    where
+        A: CommandBufferAllocator,
+    {
+        let mut state = self.state.lock();
+        let epoch = state.epoch;
+        let entry = state.entry_mut(id);
+
+        let host_buffer = match &entry.residency {
+            Residency::Resident(resource) => return Ok(resource.clone()),
+            Residency::Evicted { host_buffer, .. } => host_buffer.clone(),
+        };
+
+        let device = self.allocator.device();
+        let create_info = AllocationCreateInfo {
+            usage: MemoryUsage::DeviceOnly,
+            allocate_preference: if device.enabled_features().memory_priority {
+                MemoryAllocatePreference::AlwaysAllocate
+            } else {
+                MemoryAllocatePreference::Unknown
+            },
+            budget_behavior: MemoryBudgetBehavior::Fail,
+            priority: entry.priority,
+            ..Default::default()
+        };
+
+        let resource = match &entry.kind {
+            ResourceKind::Buffer {
+                create_info: buffer_info,
+            } => {
+                let raw_buffer = RawBuffer::new(device.clone(), buffer_info.clone())?;
+                let allocation = unsafe {
+                    self.allocator.allocate_unchecked(
+                        *raw_buffer.memory_requirements(),
+                        AllocationType::Linear,
+                        create_info,
+                        Some(DedicatedAllocation::Buffer(&raw_buffer)),
+                    )
+                }?;
+                let buffer = Arc::new(
+                    unsafe { raw_buffer.bind_memory_unchecked(allocation) }
+                        .map_err(|(err, _, _)| BufferError::from(err))?,
+                );
+
+                builder.copy_buffer(CopyBufferInfo::buffers(
+                    host_buffer,
+                    Subbuffer::from(buffer.clone()),
+                ))?;
+
+                ResidentResource::Buffer(buffer)
+            }
+            ResourceKind::Image {
+                create_info: image_info,
+                initial_layout,
+                final_layout,
+                descriptor_layouts,
+                regions,
+                host_size: _,
+            } => {
+                let raw_image = RawImage::new(device.clone(), image_info.clone())?;
+                let allocation_type = match image_info.tiling {
+                    ImageTiling::Linear => AllocationType::Linear,
+                    _ => AllocationType::NonLinear,
+                };
+                let allocation = unsafe {
+                    self.allocator.allocate_unchecked(
+                        raw_image.memory_requirements()[0],
+                        allocation_type,
+                        create_info,
+                        Some(DedicatedAllocation::Image(&raw_image)),
+                    )
+                }?;
+                let image = Arc::new(ResidentImage {
+                    inner: Arc::new(
+                        unsafe { raw_image.bind_memory_unchecked([allocation]) }
+                            .map_err(|(err, _, _)| ImageError::from(err))?,
+                    ),
+                    initial_layout: *initial_layout,
+                    final_layout: *final_layout,
+                    descriptor_layouts: *descriptor_layouts,
+                    layout_initialized: AtomicBool::new(false),
+                });
+
+                builder.copy_buffer_to_image(CopyBufferToImageInfo {
+                    regions: regions.iter().cloned().collect(),
+                    ..CopyBufferToImageInfo::buffer_image(host_buffer, image.clone())
+                })?;
+
+                ResidentResource::Image(image)
+            }
+        };
+
+        resource.set_memory_priority(entry.priority);
+        entry.last_use = epoch;
+        entry.residency = Residency::Resident(resource.clone());
+
+        Ok(resource)
+    }
+
+    /// Evicts resources from the heap with index `heap_index` until at least `bytes` bytes were
+    /// evicted, or there are no more resources that can be evicted, and returns the number of
+    /// bytes that were evicted.
+    ///
+    /// The resources with the lowest priority are evicted first, and among resources of the same
+    /// priority, the ones that the GPU used least recently. For each evicted resource, a buffer
+    /// in host-visible memory that isn't device-local is created, and a copy of the device-local
+    /// resource into it is recorded into `builder`. If the device has no such memory, nothing is
+    /// evicted. The device-local resource is kept alive by the command buffer
+    /// until it is dropped, after which its memory can be reused.
+    ///
+    /// Resources that are still in use by the GPU, according to the futures of the command
+    /// buffers that use them, are not evicted, and are marked as used recently. The futures only
+    /// release the resources once they are cleaned up, so [`cleanup_finished`] should be called
+    /// on them regularly. How recently the GPU used a resource is only as precise as the
+    /// interval at which this function is called.
+    ///
+    /// [`cleanup_finished`]: crate::sync::GpuFuture::cleanup_finished
+    pub fn evict<L, A>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L, A>,
+        heap_index: u32,
+        bytes: DeviceSize,
+    ) -> Result<DeviceSize, ResidencyError>
+    where
+        A: CommandBufferAllocator,
+    {
+        let mut state = self.state.lock();
+        state.epoch += 1;
+        let epoch = state.epoch;
+        let memory_types = &self
+            .allocator
+            .device
+            .physical_device()
+            .memory_properties()
+            .memory_types;
+
+        let mut candidates = Vec::new();
+
+        for (&id, entry) in &mut state.entries {
+            let resource = match &entry.residency {
+                Residency::Resident(resource) => resource,
+                Residency::Evicted { .. } => continue,
+            };
+
+            if resource.is_in_use() {
+                entry.last_use = epoch;
+                continue;
+            }
+
+            let allocation = match resource.allocation() {
+                Some(allocation) => allocation,
+                None => continue,
+            };
+            let memory_type =
+                &memory_types[allocation.device_memory().memory_type_index() as usize];
+
+            if memory_type.heap_index == heap_index
+                && memory_type
+                    .property_flags
+                    .intersects(MemoryPropertyFlags::DEVICE_LOCAL)
+            {
+                candidates.push((id, entry.priority, entry.last_use));
+            }
+        }
+
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.2.cmp(&b.2)));
+
+        let mut evicted = 0;
+
+        for (id, _, _) in candidates {
+            if evicted >= bytes {
+                break;
+            }
+
+            let entry = state.entry_mut(id);
+            let resource = match &entry.residency {
+                Residency::Resident(resource) => resource.clone(),
+                Residency::Evicted { .. } => unreachable!(),
+            };
+            let size = resource.allocation().unwrap().size();
+
+            let host_size = match &entry.kind {
+                ResourceKind::Buffer { create_info } => create_info.size,
+                ResourceKind::Image { host_size, .. } => *host_size,
+            };
+            let raw_buffer = RawBuffer::new(
+                self.allocator.device().clone(),
+                BufferCreateInfo {
+                    size: host_size,
+                    usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+            )?;
+            let mut requirements = *raw_buffer.memory_requirements();
+
+            // The copy must not end up in device-local memory, or evicting the resource wouldn't
+            // free anything. Without a host-visible memory type that isn't device-local, nothing
+            // can be evicted.
+            let memory_type_index = match self.allocator.find_memory_type_index(
+                requirements.memory_type_bits,
+                MemoryTypeFilter {
+                    required_flags: MemoryPropertyFlags::HOST_VISIBLE,
+                    preferred_flags: MemoryPropertyFlags::HOST_CACHED,
+                    not_preferred_flags: MemoryPropertyFlags::DEVICE_LOCAL,
+                },
+            ) {
+                Some(index)
+                    if !memory_types[index as usize]
+                        .property_flags
+                        .intersects(MemoryPropertyFlags::DEVICE_LOCAL) =>
+                {
+                    index
+                }
+                _ => break,
+            };
+            requirements.memory_type_bits = 1 << memory_type_index;
+
+            let mut allocation = unsafe {
+                self.allocator.allocate_unchecked(
+                    requirements,
+                    AllocationType::Linear,
+                    AllocationCreateInfo {
+                        usage: MemoryUsage::Download,
+                        ..Default::default()
+                    },
+                    Some(DedicatedAllocation::Buffer(&raw_buffer)),
+                )
+            }?;
+            allocation.shrink(host_size);
+            let host_buffer = Subbuffer::from(Arc::new(
+                unsafe { raw_buffer.bind_memory_unchecked(allocation) }
+                    .map_err(|(err, _, _)| BufferError::from(err))?,
+            ));
+
+            let device_resource = match (&resource, &entry.kind) {
+                (ResidentResource::Buffer(buffer), _) => {
+                    builder.copy_buffer(CopyBufferInfo::buffers(
+                        Subbuffer::from(buffer.clone()),
+                        host_buffer.clone(),
+                    ))?;
+
+                    WeakResource::Buffer(Arc::downgrade(buffer))
+                }
+                (ResidentResource::Image(image), ResourceKind::Image { regions, .. }) => {
+                    builder.copy_image_to_buffer(CopyImageToBufferInfo {
+                        regions: regions.iter().cloned().collect(),
+                        ..CopyImageToBufferInfo::image_buffer(image.clone(), host_buffer.clone())
+                    })?;
+
+                    WeakResource::Image(Arc::downgrade(image.inner().image))
+                }
+                _ => unreachable!(),
+            };
+
+            evicted += size;
+            entry.residency = Residency::Evicted {
+                host_buffer,
+                device_resource,
+                heap_index,
+                size,
+            };
+        }
+
+        Ok(evicted)
+    }
+
+    /// Evicts resources from every heap that is over its budget, until its usage is back within
+    /// the budget, and returns the number of bytes that were evicted.
+    ///
+    /// The usage of a heap is taken to be the memory of the heap that is in use, minus the free
+    /// space in the blocks of the allocator, which can be reused without allocating more memory.
+    /// Resources that were evicted earlier, but whose memory wasn't reclaimed yet because the
+    /// command buffer that copies them is still alive, are subtracted from the usage as well, so
+    /// that calling this every frame doesn't evict more than needed.
+    ///
+    /// The heap budgets are those returned by [`GenericMemoryAllocator::heap_usage`]. See
+    /// [`evict`] for how the resources to evict are chosen.
+    ///
+    /// [`evict`]: Self::evict
+    pub fn evict_to_budget<L, A>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L, A>,
+    ) -> Result<DeviceSize, ResidencyError>
+    where
+        A: CommandBufferAllocator,
+    {
+        let heap_usages = self.allocator.heap_usage();
+        let mut pending = vec![0; heap_usages.len()];
+
+        for entry in self.state.lock().entries.values() {
+            if let Residency::Evicted {
+                device_resource,
+                heap_index,
+                size,
+                ..
+            } = &entry.residency
+            {
+                if device_resource.is_alive() {
+                    pending[*heap_index as usize] += size;
+                }
+            }
+        }
+
+        let mut evicted = 0;
+
+        for (heap_index, heap_usage) in heap_usages.into_iter().enumerate() {
+            let usage = heap_usage
+                .usage
+                .saturating_sub(heap_usage.block_bytes - heap_usage.allocated_bytes)
+                .saturating_sub(pending[heap_index]);
+
+            if usage > heap_usage.budget {
+                evicted += self.evict(builder, heap_index as u32, usage - heap_usage.budget)?;
+            }
+        }
+
+        Ok(evicted)
+    }
+}
+
+impl ResidencyState {
+    fn insert(
+        &mut self,
+        priority: f32,
+        kind: ResourceKind,
+        resource: ResidentResource,
+    ) -> ResidencyId {
+        let id = ResidencyId(self.next_id);
+        self.next_id += 1;
+
+        let entry = ResidencyEntry {
+            priority,
+            last_use: self.epoch,
+            kind,
+            residency: Residency::Resident(resource),
+        };
+        self.entries.insert(id, entry);
+
+        id
+    }
+
+    fn entry_mut(&mut self, id: ResidencyId) -> &mut ResidencyEntry {
+        self.entries
+            .get_mut(&id)
+            .expect("`id` is not registered with the manager")
+    }
+}
+
+impl WeakResource {
+    fn is_alive(&self) -> bool {
+        match self {
+            Self::Buffer(buffer) => buffer.strong_count() != 0,
+            Self::Image(image) => image.strong_count() != 0,
+        }
+    }
+}
+
+/// Returns the regions to copy every subresource of `image` to and from a buffer with, and the
+/// size of that buffer. Each aspect is copied separately, as required for depth/stencil formats.
+fn image_regions(image: &Image) -> (Vec<BufferImageCopy>, DeviceSize) {
+    let format = image.format().unwrap();
+    let aspects = format.aspects();
+    let mut regions = Vec::new();
+    let mut buffer_offset = 0;
+
+    for aspect in [
+        ImageAspects::COLOR,
+        ImageAspects::DEPTH,
+        ImageAspects::STENCIL,
+    ] {
+        if !aspects.intersects(aspect) {
+            continue;
+        }
+
+        // The offset must be a multiple of both the texel block size and 4 for color aspects,
+        // and of 4 for depth/stencil aspects. The block size isn't necessarily a power of two,
+        // so this is the least common multiple of the two.
+        let alignment = match format.block_size() {
+            Some(block_size) if aspect == ImageAspects::COLOR => match block_size % 4 {
+                0 => block_size,
+                2 => block_size * 2,
+                _ => block_size * 4,
+            },
+            _ => 4,
+        };
+
+        for mip_level in 0..image.mip_levels() {
+            let region = BufferImageCopy {
+                buffer_offset: buffer_offset + (alignment - buffer_offset % alignment) % alignment,
+                image_subresource: ImageSubresourceLayers {
+                    aspects: aspect,
+                    mip_level,
+                    array_layers: 0..image.dimensions().array_layers(),
+                },
+                image_extent: image
+                    .dimensions()
+                    .mip_level_dimensions(mip_level)
+                    .unwrap()
+                    .width_height_depth(),
+                ..Default::default()
+            };
+            buffer_offset = region.buffer_offset + region.buffer_copy_size(format);
+            regions.push(region);
+        }
+    }
+
+    (regions, buffer_offset)
+}
+
+/// A resource that is registered with a [`ResidencyManager`] and is resident.
+#[derive(Clone, Debug)]
+pub enum ResidentResource {
+    Buffer(Arc<Buffer>),
+    Image(Arc<dyn ImageAccess>),
+}
+
+impl ResidentResource {
+    fn allocation(&self) -> Option<&MemoryAlloc> {
+        match self {
+            Self::Buffer(buffer) => match buffer.memory() {
+                BufferMemory::Normal(allocation) => Some(allocation),
+                BufferMemory::Sparse => None,
+            },
+            Self::Image(image) => match image.inner().image.memory() {
+                ImageMemory::Normal(allocations) => allocations.first(),
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns whether a submitted command buffer that uses the resource hasn't been cleaned up.
+    fn is_in_use(&self) -> bool {
+        match self {
+            Self::Buffer(buffer) => buffer.state().check_cpu_write(0..buffer.size()).is_err(),
+            Self::Image(image) => {
+                let image = image.inner().image;
+
+                image
+                    .state()
+                    .check_cpu_write(0..image.range_size())
+                    .is_err()
+            }
+        }
+    }
+
+    /// Hands `priority` to the implementation, if the implementation is able to make use of it.
+    /// Only dedicated allocations are given a priority, because the other allocations share
+    /// their `DeviceMemory` block with other resources.
+    fn set_memory_priority(&self, priority: f32) {
+        if let Some(allocation) = self.allocation() {
+            let device_memory = allocation.device_memory();
+
+            if allocation.is_dedicated()
+                && device_memory
+                    .device()
+                    .enabled_features()
+                    .pageable_device_local_memory
+            {
+                unsafe { device_memory.set_priority_unchecked(priority) };
+            }
+        }
+    }
+}
+
+/// An image that was created by [`ResidencyManager::make_resident`] to replace an image that was
+/// evicted.
+///
+/// The image has the same parameters as the evicted image, and it expects the same layouts as the
+/// evicted image at the start and end of command buffers.
+#[derive(Debug)]
+pub struct ResidentImage {
+    inner: Arc<Image>,
+    initial_layout: ImageLayout,
+    final_layout: ImageLayout,
+    descriptor_layouts: Option<ImageDescriptorLayouts>,
+
+    // If false, then the contents haven't been copied over from host memory yet, and the image is
+    // still `Undefined`.
+    layout_initialized: AtomicBool,
+}
+
+unsafe impl ImageAccess for ResidentImage {
+    #[inline]
+    fn inner(&self) -> ImageInner<'_> {
+        ImageInner {
+            image: &self.inner,
+            first_layer: 0,
+            num_layers: self.inner.dimensions().array_layers(),
+            first_mipmap_level: 0,
+            num_mipmap_levels: self.inner.mip_levels(),
+        }
+    }
+
+    #[inline]
+    fn initial_layout_requirement(&self) -> ImageLayout {
+        self.initial_layout
+    }
+
+    #[inline]
+    fn final_layout_requirement(&self) -> ImageLayout {
+        self.final_layout
+    }
+
+    #[inline]
+    unsafe fn layout_initialized(&self) {
+        self.layout_initialized.store(true, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn is_layout_initialized(&self) -> bool {
+        self.layout_initialized.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    fn descriptor_layouts(&self) -> Option<ImageDescriptorLayouts> {
+        self.descriptor_layouts
+    }
+}
+
+unsafe impl DeviceOwned for ResidentImage {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+unsafe impl<P> ImageContent<P> for ResidentImage {
+    fn matches_format(&self) -> bool {
+        true // FIXME:
+    }
+}
+
+impl PartialEq for ResidentImage {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner()
+    }
+}
+
+impl Eq for ResidentImage {}
+
+impl Hash for ResidentImage {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().hash(state);
+    }
+}
+
+/// Identifies a resource that is registered with a [`ResidencyManager`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ResidencyId(u64);
+
+/// Error that can happen when evicting a resource or making it resident.
+#[derive(Clone, Debug)]
+pub enum ResidencyError {
+    /// Allocating memory for a new resource failed.
+    AllocationCreationError(AllocationCreationError),
+
+    /// Creating a new buffer failed.
+    BufferError(BufferError),
+
+    /// Creating a new image failed.
+    ImageError(ImageError),
+
+    /// Recording a copy command failed.
+    CopyError(CopyError),
+}
+
+impl Error for ResidencyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::AllocationCreationError(err) => Some(err),
+            Self::BufferError(err) => Some(err),
+            Self::ImageError(err) => Some(err),
+            Self::CopyError(err) => Some(err),
+        }
+    }
+}
+
+impl Display for ResidencyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::AllocationCreationError(_) => {
+                write!(f, "allocating memory for a new resource failed")
+            }
+            Self::BufferError(_) => write!(f, "creating a new buffer failed"),
+            Self::ImageError(_) => write!(f, "creating a new image failed"),
+            Self::CopyError(_) => write!(f, "recording a copy command failed"),
+        }
+    }
+}
+
+impl From<AllocationCreationError> for ResidencyError {
+    fn from(err: AllocationCreationError) -> Self {
+        Self::AllocationCreationError(err)
+    }
+}
+
+impl From<BufferError> for ResidencyError {
+    fn from(err: BufferError) -> Self {
+        Self::BufferError(err)
+    }
+}
+
+impl From<ImageError> for ResidencyError {
+    fn from(err: ImageError) -> Self {
+        Self::ImageError(err)
+    }
+}
+
+impl From<CopyError> for ResidencyError {
+    fn from(err: CopyError) -> Self {
+        Self::CopyError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{image_regions, ResidencyManager};
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferMemory, BufferUsage},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            ClearColorImageInfo, CommandBufferUsage, CopyImageToBufferInfo,
+            PrimaryCommandBufferAbstract,
+        },
+        device::Device,
+        format::Format,
+        image::{
+            sys::ImageMemory, ImageAccess, ImageCreateFlags, ImageDimensions, ImageLayout,
+            ImageUsage, ImmutableImage, MipmapsCount, StorageImage,
+        },
+        memory::{
+            allocator::{AllocationCreateInfo, MemoryUsage, StandardMemoryAllocator},
+            MemoryPropertyFlags,
+        },
+        sync::GpuFuture,
+    };
+    use std::sync::Arc;
+
+    // Resources can only be evicted to host-visible memory that isn't device-local.
+    fn has_host_only_memory(device: &Device) -> bool {
+        device
+            .physical_device()
+            .memory_properties()
+            .memory_types
+            .iter()
+            .any(|memory_type| {
+                memory_type
+                    .property_flags
+                    .intersects(MemoryPropertyFlags::HOST_VISIBLE)
+                    && !memory_type
+                        .property_flags
+                        .intersects(MemoryPropertyFlags::DEVICE_LOCAL)
+            })
+    }
+
+    #[test]
+    fn evict_and_restore() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator =
+            StandardCommandBufferAllocator::new(device.clone(), Default::default());
+        let residency_manager = ResidencyManager::new(memory_allocator.clone());
+
+        let new_buffer = |value: u32| {
+            Buffer::from_iter(
+                memory_allocator.as_ref(),
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    usage: MemoryUsage::Upload,
+                    ..Default::default()
+                },
+                [value; 256],
+            )
+            .unwrap()
+        };
+
+        let low = residency_manager.register_buffer(new_buffer(1).buffer().clone(), 0.0);
+        let high = residency_manager.register_buffer(new_buffer(2).buffer().clone(), 1.0);
+
+        // Upload memory isn't necessarily device-local, in which case nothing is evicted.
+        let heap_index = {
+            let buffer = residency_manager.buffer(low).unwrap();
+            let memory_type_index = match buffer.memory() {
+                BufferMemory::Normal(allocation) => allocation.device_memory().memory_type_index(),
+                BufferMemory::Sparse => unreachable!(),
+            };
+            let memory_type = &device.physical_device().memory_properties().memory_types
+                [memory_type_index as usize];
+
+            if !memory_type
+                .property_flags
+                .intersects(MemoryPropertyFlags::DEVICE_LOCAL)
+                || !has_host_only_memory(&device)
+            {
+                return;
+            }
+
+            memory_type.heap_index
+        };
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        // Only the low-priority buffer is needed to free 1 byte.
+        let evicted = residency_manager
+            .evict(&mut builder, heap_index, 1)
+            .unwrap();
+        assert_eq!(evicted, 1024);
+        assert!(!residency_manager.is_resident(low));
+        assert!(residency_manager.is_resident(high));
+
+        builder
+            .build()
+            .unwrap()
+            .execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        residency_manager.make_resident(&mut builder, low).unwrap();
+        assert!(residency_manager.is_resident(low));
+
+        builder
+            .build()
+            .unwrap()
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert_eq!(residency_manager.buffer(low).unwrap().size(), 1024);
+    }
+
+    #[test]
+    fn evict_and_restore_image() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator =
+            StandardCommandBufferAllocator::new(device.clone(), Default::default());
+        let residency_manager = ResidencyManager::new(memory_allocator.clone());
+
+        let image = StorageImage::with_usage(
+            memory_allocator.as_ref(),
+            ImageDimensions::Dim2d {
+                width: 16,
+                height: 16,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+            ImageCreateFlags::empty(),
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+
+        let heap_index = {
+            let memory_type_index = match image.inner().image.memory() {
+                ImageMemory::Normal(allocations) => {
+                    allocations[0].device_memory().memory_type_index()
+                }
+                _ => unreachable!(),
+            };
+            let memory_type = &device.physical_device().memory_properties().memory_types
+                [memory_type_index as usize];
+
+            if !memory_type
+                .property_flags
+                .intersects(MemoryPropertyFlags::DEVICE_LOCAL)
+                || !has_host_only_memory(&device)
+            {
+                return;
+            }
+
+            memory_type.heap_index
+        };
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder
+            .clear_color_image(ClearColorImageInfo {
+                clear_value: [1.0, 0.0, 0.0, 1.0].into(),
+                ..ClearColorImageInfo::image(image.clone())
+            })
+            .unwrap();
+        builder
+            .build()
+            .unwrap()
+            .execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        let id = residency_manager.register_image(image, 0.0);
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        assert!(
+            residency_manager
+                .evict(&mut builder, heap_index, 1)
+                .unwrap()
+                > 0
+        );
+        assert!(!residency_manager.is_resident(id));
+        residency_manager.make_resident(&mut builder, id).unwrap();
+
+        let readback = Buffer::new_slice::<[u8; 4]>(
+            memory_allocator.as_ref(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Download,
+                ..Default::default()
+            },
+            16 * 16,
+        )
+        .unwrap();
+        builder
+            .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+                residency_manager.image(id).unwrap(),
+                readback.clone(),
+            ))
+            .unwrap();
+        builder
+            .build()
+            .unwrap()
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert!(readback
+            .read()
+            .unwrap()
+            .iter()
+            .all(|&texel| texel == [255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn image_regions_non_power_of_two_block_size() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = StandardMemoryAllocator::new_default(device);
+
+        // The texel block size of this format is 3, so the offset of each mip level must be a
+        // multiple of 12.
+        let image = match ImmutableImage::uninitialized(
+            &memory_allocator,
+            ImageDimensions::Dim2d {
+                width: 15,
+                height: 15,
+                array_layers: 1,
+            },
+            Format::R8G8B8_UNORM,
+            MipmapsCount::Log2,
+            ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+            ImageCreateFlags::empty(),
+            ImageLayout::TransferSrcOptimal,
+            [queue.queue_family_index()],
+        ) {
+            Ok((image, _)) => image,
+            // The format isn't required to be supported.
+            Err(_) => return,
+        };
+
+        let (regions, host_size) = image_regions(image.inner().image);
+        assert_eq!(regions.len(), 4);
+
+        let mut end = 0;
+
+        for region in &regions {
+            assert_eq!(region.buffer_offset % 12, 0);
+            assert!(region.buffer_offset >= end);
+            end = region.buffer_offset + region.buffer_copy_size(Format::R8G8B8_UNORM);
+        }
+
+        assert_eq!(host_size, end);
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Statistics about the memory usage of a [`GenericMemoryAllocator`], and dumps of the layout of
+//! its blocks.
+//!
+//! With the `serde` feature enabled, [`MemoryAllocatorDump`] implements `Serialize`, so that it
+//! can be written out in a format such as JSON and inspected offline, for example to track down
+//! leaks or to visualise fragmentation.
+
+use super::{
+    suballocator::fragmentation, GenericMemoryAllocator, SuballocationNode, Suballocator,
+    SuballocatorStatistics,
+};
+use crate::{memory::MemoryPropertyFlags, DeviceSize};
+#[cfg(feature = "serde")]
+use {
+    super::SuballocationType,
+    serde::{ser::SerializeStruct, Serialize, Serializer},
+};
+
+impl<S: Suballocator> GenericMemoryAllocator<S> {
+    /// Returns statistics about the blocks of each memory type.
+    ///
+    /// The returned `Vec` has one element for each element of
+    /// [`memory_properties().memory_types`](crate::memory::MemoryProperties::memory_types) of the
+    /// physical device. Dedicated allocations are not included.
+    pub fn statistics(&self) -> Vec<MemoryTypeStatistics> {
+        self.pools
+            .iter()
+            .map(|pool| {
+                pool.blocks
+                    .read()
+                    .iter()
+                    .map(Suballocator::statistics)
+                    .fold(MemoryTypeStatistics::default(), MemoryTypeStatistics::add)
+            })
+            .collect()
+    }
+
+    /// Returns the statistics and the layout of every block of the allocator.
+    ///
+    /// Memory types from which the allocator has no blocks are omitted. Dedicated allocations are
+    /// not included.
+    ///
+    /// This locks each pool of blocks for the duration of the dump of its blocks, so it is not
+    /// meant to be called often.
+    pub fn dump(&self) -> MemoryAllocatorDump {
+        let memory_types = self
+            .pools
+            .iter()
+            .enumerate()
+            .filter_map(|(memory_type_index, pool)| {
+                let blocks = pool.blocks.read();
+
+                if blocks.is_empty() {
+                    return None;
+                }
+
+                let blocks: Vec<_> = blocks
+                    .iter()
+                    .map(|block| BlockDump {
+                        statistics: block.statistics(),
+                        suballocations: block.suballocations(),
+                    })
+                    .collect();
+
+                Some(MemoryTypeDump {
+                    memory_type_index: memory_type_index as u32,
+                    heap_index: pool.memory_type.heap_index,
+                    property_flags: pool.memory_type.property_flags.into(),
+                    statistics: blocks
+                        .iter()
+                        .map(|block| block.statistics)
+                        .fold(MemoryTypeStatistics::default(), MemoryTypeStatistics::add),
+                    blocks,
+                })
+            })
+            .collect();
+
+        MemoryAllocatorDump { memory_types }
+    }
+}
+
+/// Statistics about the blocks of one memory type of a [`GenericMemoryAllocator`], as returned by
+/// [`GenericMemoryAllocator::statistics`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MemoryTypeStatistics {
+    /// The number of `DeviceMemory` blocks.
+    pub block_count: usize,
+
+    /// The total size of the blocks.
+    pub block_size: DeviceSize,
+
+    /// The number of suballocations that are currently in use.
+    pub allocation_count: usize,
+
+    /// The total amount of free space in the blocks.
+    pub free_size: DeviceSize,
+
+    /// The number of free ranges that the free space is divided into.
+    pub free_range_count: usize,
+
+    /// The size of the largest free range in any of the blocks.
+    pub largest_free_range: DeviceSize,
+}
+
+impl MemoryTypeStatistics {
+    /// Returns the [external fragmentation] of the blocks, as a number between 0 and 1.
+    ///
+    /// See [`SuballocatorStatistics::fragmentation`] for details.
+    ///
+    /// [external fragmentation]: super#external-fragmentation
+    #[inline]
+    pub fn fragmentation(&self) -> f64 {
+        fragmentation(self.free_size, self.largest_free_range)
+    }
+
+    fn add(self, block: SuballocatorStatistics) -> Self {
+        MemoryTypeStatistics {
+            block_count: self.block_count + 1,
+            block_size: self.block_size + block.region_size,
+            allocation_count: self.allocation_count + block.allocation_count,
+            free_size: self.free_size + block.free_size,
+            free_range_count: self.free_range_count + block.free_range_count,
+            largest_free_range: self.largest_free_range.max(block.largest_free_range),
+        }
+    }
+}
+
+/// A dump of the blocks of a [`GenericMemoryAllocator`], as returned by
+/// [`GenericMemoryAllocator::dump`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MemoryAllocatorDump {
+    /// The memory types that the allocator has blocks of.
+    pub memory_types: Vec<MemoryTypeDump>,
+}
+
+/// A dump of the blocks of one memory type of a [`GenericMemoryAllocator`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MemoryTypeDump {
+    /// The index of the memory type.
+    pub memory_type_index: u32,
+
+    /// The index of the heap that the memory type corresponds to.
+    pub heap_index: u32,
+
+    /// The properties of the memory type.
+    pub property_flags: MemoryPropertyFlags,
+
+    /// The statistics of all the blocks combined.
+    pub statistics: MemoryTypeStatistics,
+
+    /// The blocks of the memory type.
+    pub blocks: Vec<BlockDump>,
+}
+
+/// A dump of one `DeviceMemory` block of a [`GenericMemoryAllocator`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct BlockDump {
+    /// The statistics of the block.
+    pub statistics: SuballocatorStatistics,
+
+    /// The suballocations and free ranges of the block, as returned by
+    /// [`Suballocator::suballocations`].
+    pub suballocations: Vec<SuballocationNode>,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for MemoryAllocatorDump {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("MemoryAllocatorDump", 1)?;
+        state.serialize_field("memory_types", &self.memory_types)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for MemoryTypeDump {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("MemoryTypeDump", 5)?;
+        state.serialize_field("memory_type_index", &self.memory_type_index)?;
+        state.serialize_field("heap_index", &self.heap_index)?;
+        state.serialize_field(
+            "property_flags",
+            &ash::vk::MemoryPropertyFlags::from(self.property_flags).as_raw(),
+        )?;
+        state.serialize_field("statistics", &self.statistics)?;
+        state.serialize_field("blocks", &self.blocks)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for BlockDump {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("BlockDump", 2)?;
+        state.serialize_field("statistics", &self.statistics)?;
+        state.serialize_field("suballocations", &self.suballocations)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for MemoryTypeStatistics {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("MemoryTypeStatistics", 7)?;
+        state.serialize_field("block_count", &self.block_count)?;
+        state.serialize_field("block_size", &self.block_size)?;
+        state.serialize_field("allocation_count", &self.allocation_count)?;
+        state.serialize_field("free_size", &self.free_size)?;
+        state.serialize_field("free_range_count", &self.free_range_count)?;
+        state.serialize_field("largest_free_range", &self.largest_free_range)?;
+        state.serialize_field("fragmentation", &self.fragmentation())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SuballocatorStatistics {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("SuballocatorStatistics", 6)?;
+        state.serialize_field("region_size", &self.region_size)?;
+        state.serialize_field("allocation_count", &self.allocation_count)?;
+        state.serialize_field("free_size", &self.free_size)?;
+        state.serialize_field("free_range_count", &self.free_range_count)?;
+        state.serialize_field("largest_free_range", &self.largest_free_range)?;
+        state.serialize_field("fragmentation", &self.fragmentation())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SuballocationNode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("SuballocationNode", 3)?;
+        state.serialize_field("offset", &self.offset)?;
+        state.serialize_field("size", &self.size)?;
+        state.serialize_field("ty", &self.ty)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SuballocationType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (variant_index, variant) = match self {
+            SuballocationType::Unknown => (0, "Unknown"),
+            SuballocationType::Linear => (1, "Linear"),
+            SuballocationType::NonLinear => (2, "NonLinear"),
+            SuballocationType::Free => (3, "Free"),
+        };
+
+        serializer.serialize_unit_variant("SuballocationType", variant_index, variant)
+    }
+}
@@ -37,7 +37,7 @@ use std::{
     ptr::{self, NonNull},
     slice,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
 };
@@ -440,7 +440,7 @@ impl MemoryAlloc {
         })
     }
 
-    fn root(&self) -> Option<&Arc<DeviceMemory>> {
+    pub(super) fn root(&self) -> Option<&Arc<DeviceMemory>> {
         match &self.parent {
             AllocParent::FreeList { allocator, .. } => Some(&allocator.device_memory),
             AllocParent::Buddy { allocator, .. } => Some(&allocator.device_memory),
@@ -707,6 +707,43 @@ pub unsafe trait Suballocator: DeviceOwned {
     /// [region]: Self#regions
     fn free_size(&self) -> DeviceSize;
 
+    /// Returns statistics about the usage of the [region].
+    ///
+    /// The default implementation derives the statistics from [`suballocations`]. If that returns
+    /// no nodes, only the `region_size` and `free_size` are filled in.
+    ///
+    /// [region]: Self#regions
+    /// [`suballocations`]: Self::suballocations
+    fn statistics(&self) -> SuballocatorStatistics {
+        let mut statistics = SuballocatorStatistics {
+            region_size: self.region().size(),
+            free_size: self.free_size(),
+            ..Default::default()
+        };
+
+        for node in self.suballocations() {
+            if node.ty == SuballocationType::Free {
+                statistics.free_range_count += 1;
+                statistics.largest_free_range = cmp::max(statistics.largest_free_range, node.size);
+            } else {
+                statistics.allocation_count += 1;
+            }
+        }
+
+        statistics
+    }
+
+    /// Returns the suballocations and free ranges within the [region], sorted by offset.
+    ///
+    /// Depending on the allocator, a node may cover more than one suballocation, or the nodes may
+    /// not be available at all. See the documentation of the implementations for details. The
+    /// default implementation returns an empty `Vec`.
+    ///
+    /// [region]: Self#regions
+    fn suballocations(&self) -> Vec<SuballocationNode> {
+        Vec::new()
+    }
+
     /// Tries to free some space, if applicable.
     fn cleanup(&mut self);
 }
@@ -779,6 +816,106 @@ impl From<ImageTiling> for AllocationType {
     }
 }
 
+/// Tells us if a suballocation is free, and if not, whether it is linear or not.
+///
+/// The [`FreeListAllocator`] needs this in order to be able to respect the [buffer-image
+/// granularity]. It is also used to describe the layout of a [region] in
+/// [`Suballocator::suballocations`].
+///
+/// [buffer-image granularity]: super#buffer-image-granularity
+/// [region]: Suballocator#regions
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SuballocationType {
+    /// The suballocation is in use, and the type of resource is unknown.
+    Unknown,
+
+    /// The suballocation is in use by a linear resource.
+    Linear,
+
+    /// The suballocation is in use by a non-linear resource.
+    NonLinear,
+
+    /// The suballocation is free.
+    Free,
+}
+
+impl From<AllocationType> for SuballocationType {
+    #[inline]
+    fn from(ty: AllocationType) -> Self {
+        match ty {
+            AllocationType::Unknown => SuballocationType::Unknown,
+            AllocationType::Linear => SuballocationType::Linear,
+            AllocationType::NonLinear => SuballocationType::NonLinear,
+        }
+    }
+}
+
+/// A suballocation or free range within a [region], as returned by
+/// [`Suballocator::suballocations`].
+///
+/// [region]: Suballocator#regions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SuballocationNode {
+    /// The offset of the suballocation within the [`DeviceMemory`] block.
+    pub offset: DeviceSize,
+
+    /// The size of the suballocation.
+    pub size: DeviceSize,
+
+    /// Whether the suballocation is free, and if not, the type of resource it is in use by.
+    pub ty: SuballocationType,
+}
+
+/// Statistics about the [region] of a [suballocator], as returned by
+/// [`Suballocator::statistics`].
+///
+/// [region]: Suballocator#regions
+/// [suballocator]: Suballocator
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SuballocatorStatistics {
+    /// The size of the region.
+    pub region_size: DeviceSize,
+
+    /// The number of suballocations that are currently in use.
+    pub allocation_count: usize,
+
+    /// The total amount of free space in the region. This is the same as
+    /// [`Suballocator::free_size`].
+    pub free_size: DeviceSize,
+
+    /// The number of free ranges that the free space is divided into.
+    pub free_range_count: usize,
+
+    /// The size of the largest free range. This is an upper bound for the size of a new
+    /// suballocation.
+    pub largest_free_range: DeviceSize,
+}
+
+impl SuballocatorStatistics {
+    /// Returns the [external fragmentation] of the region, as a number between 0 and 1.
+    ///
+    /// This is the fraction of the free space that is not part of the largest free range, so 0
+    /// means that all free space is contiguous, and values close to 1 mean that the free space is
+    /// scattered across many small ranges.
+    ///
+    /// [external fragmentation]: super#external-fragmentation
+    #[inline]
+    pub fn fragmentation(&self) -> f64 {
+        fragmentation(self.free_size, self.largest_free_range)
+    }
+}
+
+pub(super) fn fragmentation(free_size: DeviceSize, largest_free_range: DeviceSize) -> f64 {
+    if free_size == 0 {
+        0.0
+    } else {
+        1.0 - largest_free_range as f64 / free_size as f64
+    }
+}
+
 /// Error that can be returned when using a [suballocator].
 ///
 /// [suballocator]: Suballocator
@@ -1199,6 +1336,47 @@ unsafe impl Suballocator for Arc<FreeListAllocator> {
         self.free_size.load(Ordering::Acquire)
     }
 
+    fn statistics(&self) -> SuballocatorStatistics {
+        let state = self.state.lock();
+
+        SuballocatorStatistics {
+            region_size: self.region.size,
+            allocation_count: state
+                .nodes
+                .iter()
+                .filter(|node| node.ty != SuballocationType::Free)
+                .count(),
+            free_size: self.free_size(),
+            free_range_count: state.free_list.len(),
+            // SAFETY: The free-list only contains IDs of suballocations allocated by `self`.
+            largest_free_range: state
+                .free_list
+                .last()
+                .map_or(0, |&id| unsafe { state.nodes.get(id) }.size),
+        }
+    }
+
+    /// Returns the suballocations and free ranges within the [region], sorted by offset.
+    ///
+    /// Every suballocation has its own node, and free ranges are always coalesced.
+    ///
+    /// [region]: Suballocator#regions
+    fn suballocations(&self) -> Vec<SuballocationNode> {
+        let state = self.state.lock();
+        let mut nodes: Vec<_> = state
+            .nodes
+            .iter()
+            .map(|node| SuballocationNode {
+                offset: node.offset,
+                size: node.size,
+                ty: node.ty,
+            })
+            .collect();
+        nodes.sort_unstable_by_key(|node| node.offset);
+
+        nodes
+    }
+
     #[inline]
     fn cleanup(&mut self) {}
 }
@@ -1227,26 +1405,6 @@ struct SuballocationListNode {
     ty: SuballocationType,
 }
 
-/// Tells us if a suballocation is free, and if not, whether it is linear or not. This is needed in
-/// order to be able to respect the buffer-image granularity.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum SuballocationType {
-    Unknown,
-    Linear,
-    NonLinear,
-    Free,
-}
-
-impl From<AllocationType> for SuballocationType {
-    fn from(ty: AllocationType) -> Self {
-        match ty {
-            AllocationType::Unknown => SuballocationType::Unknown,
-            AllocationType::Linear => SuballocationType::Linear,
-            AllocationType::NonLinear => SuballocationType::NonLinear,
-        }
-    }
-}
-
 impl FreeListAllocatorState {
     /// Removes the target suballocation from the free-list.
     ///
@@ -1597,7 +1755,10 @@ impl BuddyAllocator {
             ArrayVec::new(max_order + 1, [EMPTY_FREE_LIST; BuddyAllocator::MAX_ORDERS]);
         // The root node has the lowest offset and highest order, so it's the whole region.
         free_list[max_order].push(region.offset);
-        let state = Mutex::new(BuddyAllocatorState { free_list });
+        let state = Mutex::new(BuddyAllocatorState {
+            free_list,
+            allocation_count: 0,
+        });
 
         Arc::new(BuddyAllocator {
             region,
@@ -1618,6 +1779,8 @@ impl BuddyAllocator {
 
         debug_assert!(!state.free_list[order].contains(&offset));
 
+        state.allocation_count -= 1;
+
         // Try to coalesce nodes while incrementing the order.
         for (order, free_list) in state.free_list.iter_mut().enumerate().skip(min_order) {
             // This can't discard any bits because `order` is confined to the range
@@ -1755,6 +1918,7 @@ unsafe impl Suballocator for Arc<BuddyAllocator> {
                     // This can't overflow because suballocation sizes in the free-list are
                     // constrained by the remaining size of the region.
                     self.free_size.fetch_sub(size, Ordering::Release);
+                    state.allocation_count += 1;
 
                     let mapped_ptr = self.region.mapped_ptr.map(|ptr| {
                         // This can't overflow because offsets in the free-list are confined to the
@@ -1814,6 +1978,75 @@ unsafe impl Suballocator for Arc<BuddyAllocator> {
         self.free_size.load(Ordering::Acquire)
     }
 
+    fn statistics(&self) -> SuballocatorStatistics {
+        let state = self.state.lock();
+
+        SuballocatorStatistics {
+            region_size: self.region.size,
+            allocation_count: state.allocation_count,
+            free_size: self.free_size(),
+            free_range_count: state.free_list.iter().map(Vec::len).sum(),
+            // This can't discard any bits because `order` is confined to the range
+            // [0, log(region.size / BuddyAllocator::MIN_NODE_SIZE)].
+            largest_free_range: state
+                .free_list
+                .iter()
+                .rposition(|free_list| !free_list.is_empty())
+                .map_or(0, |order| BuddyAllocator::MIN_NODE_SIZE << order),
+        }
+    }
+
+    /// Returns the suballocations and free ranges within the [region], sorted by offset.
+    ///
+    /// Only the free nodes are tracked, so neighboring suballocations are merged into one node of
+    /// type [`SuballocationType::Unknown`], which includes their [internal fragmentation]. Free
+    /// nodes that are not buddies are not coalesced, even if they are adjacent.
+    ///
+    /// [region]: Suballocator#regions
+    /// [internal fragmentation]: super#internal-fragmentation
+    fn suballocations(&self) -> Vec<SuballocationNode> {
+        let state = self.state.lock();
+        let mut free_nodes: Vec<_> = state
+            .free_list
+            .iter()
+            .enumerate()
+            .flat_map(|(order, free_list)| {
+                free_list.iter().map(move |&offset| SuballocationNode {
+                    offset,
+                    // This can't discard any bits for the same reason as above.
+                    size: BuddyAllocator::MIN_NODE_SIZE << order,
+                    ty: SuballocationType::Free,
+                })
+            })
+            .collect();
+        free_nodes.sort_unstable_by_key(|node| node.offset);
+
+        let mut nodes = Vec::with_capacity(free_nodes.len() * 2 + 1);
+        let mut offset = self.region.offset;
+
+        for free_node in free_nodes.into_iter().chain(Some(SuballocationNode {
+            offset: self.region.offset + self.region.size,
+            size: 0,
+            ty: SuballocationType::Free,
+        })) {
+            if free_node.offset > offset {
+                nodes.push(SuballocationNode {
+                    offset,
+                    size: free_node.offset - offset,
+                    ty: SuballocationType::Unknown,
+                });
+            }
+
+            if free_node.size > 0 {
+                nodes.push(free_node);
+            }
+
+            offset = free_node.offset + free_node.size;
+        }
+
+        nodes
+    }
+
     #[inline]
     fn cleanup(&mut self) {}
 }
@@ -1831,6 +2064,7 @@ struct BuddyAllocatorState {
     // Each free-list is sorted by offset because we want to find the first-fit as this strategy
     // minimizes external fragmentation.
     free_list: ArrayVec<Vec<DeviceSize>, { BuddyAllocator::MAX_ORDERS }>,
+    allocation_count: usize,
 }
 
 /// A [suballocator] using a pool of fixed-size blocks as a [free-list].
@@ -2067,6 +2301,45 @@ unsafe impl<const BLOCK_SIZE: DeviceSize> Suballocator for Arc<PoolAllocator<BLO
         self.free_count() as DeviceSize * self.block_size()
     }
 
+    #[inline]
+    fn statistics(&self) -> SuballocatorStatistics {
+        let free_count = self.free_count();
+
+        SuballocatorStatistics {
+            region_size: self.inner.region.size,
+            allocation_count: self.block_count() - free_count,
+            free_size: self.free_size(),
+            free_range_count: free_count,
+            largest_free_range: if free_count > 0 { self.block_size() } else { 0 },
+        }
+    }
+
+    /// Returns the blocks within the [region], sorted by offset.
+    ///
+    /// Every block has its own node, covering the whole block, and free blocks are not coalesced.
+    /// Blocks that are in use have the allocation type of the region.
+    ///
+    /// [region]: Suballocator#regions
+    fn suballocations(&self) -> Vec<SuballocationNode> {
+        let block_size = self.block_size();
+        let ty = self.inner.region.allocation_type.into();
+
+        self.inner
+            .allocated
+            .iter()
+            .enumerate()
+            .map(|(index, allocated)| SuballocationNode {
+                offset: self.inner.region.offset + index as DeviceSize * block_size,
+                size: block_size,
+                ty: if allocated.load(Ordering::Acquire) {
+                    ty
+                } else {
+                    SuballocationType::Free
+                },
+            })
+            .collect()
+    }
+
     #[inline]
     fn cleanup(&mut self) {}
 }
@@ -2086,6 +2359,8 @@ struct PoolAllocatorInner {
     block_size: DeviceSize,
     // Unsorted list of free block indices.
     free_list: ArrayQueue<DeviceSize>,
+    // Whether each block is in use, for reporting purposes only.
+    allocated: Box<[AtomicBool]>,
 }
 
 impl PoolAllocatorInner {
@@ -2114,6 +2389,7 @@ impl PoolAllocatorInner {
         for i in 0..block_count {
             free_list.push(i).unwrap();
         }
+        let allocated = (0..block_count).map(|_| AtomicBool::new(false)).collect();
 
         PoolAllocatorInner {
             region,
@@ -2121,6 +2397,7 @@ impl PoolAllocatorInner {
             atom_size,
             block_size,
             free_list,
+            allocated,
         }
     }
 
@@ -2169,6 +2446,8 @@ impl PoolAllocatorInner {
             unsafe { NonNull::new_unchecked(ptr) }
         });
 
+        self.allocated[index as usize].store(true, Ordering::Release);
+
         Ok(MemoryAlloc {
             offset,
             size,
@@ -2186,6 +2465,7 @@ impl PoolAllocatorInner {
     ///
     /// - `index` must refer to an occupied suballocation allocated by `self`.
     unsafe fn free(&self, index: DeviceSize) {
+        self.allocated[index as usize].store(false, Ordering::Release);
         let _ = self.free_list.push(index);
     }
 }
@@ -2252,6 +2532,8 @@ pub struct BumpAllocator {
     // Encodes the previous allocation type in the 2 least signifficant bits and the free start in
     // the rest.
     state: AtomicU64,
+    // Number of allocations made since the last reset.
+    allocation_count: AtomicUsize,
 }
 
 impl BumpAllocator {
@@ -2287,6 +2569,7 @@ impl BumpAllocator {
             buffer_image_granularity,
             atom_size,
             state,
+            allocation_count: AtomicUsize::new(0),
         })
     }
 
@@ -2299,6 +2582,7 @@ impl BumpAllocator {
         Arc::get_mut(self)
             .map(|allocator| {
                 *allocator.state.get_mut() = allocator.region.allocation_type as DeviceSize;
+                *allocator.allocation_count.get_mut() = 0;
             })
             .ok_or(BumpAllocatorResetError)
     }
@@ -2326,6 +2610,7 @@ impl BumpAllocator {
     pub unsafe fn reset_unchecked(&self) {
         self.state
             .store(self.region.allocation_type as DeviceSize, Ordering::Release);
+        self.allocation_count.store(0, Ordering::Release);
     }
 }
 
@@ -2436,6 +2721,8 @@ unsafe impl Suballocator for Arc<BumpAllocator> {
                 Ordering::Relaxed,
             ) {
                 Ok(_) => {
+                    self.allocation_count.fetch_add(1, Ordering::Release);
+
                     let mapped_ptr = self.region.mapped_ptr.map(|ptr| {
                         // SAFETY: Allocation sizes are guaranteed to not exceed `isize::MAX` when
                         // they have a mapped pointer, and the original pointer was handed to us
@@ -2478,6 +2765,51 @@ unsafe impl Suballocator for Arc<BumpAllocator> {
         self.region.size - (self.state.load(Ordering::Acquire) >> 2)
     }
 
+    /// Returns statistics about the usage of the [region].
+    ///
+    /// The allocation count is the number of allocations made since the allocator was last reset,
+    /// since the memory of allocations is only reclaimed by resetting.
+    ///
+    /// [region]: Suballocator#regions
+    #[inline]
+    fn statistics(&self) -> SuballocatorStatistics {
+        let free_size = self.free_size();
+
+        SuballocatorStatistics {
+            region_size: self.region.size,
+            allocation_count: self.allocation_count.load(Ordering::Acquire),
+            free_size,
+            free_range_count: (free_size > 0) as usize,
+            largest_free_range: free_size,
+        }
+    }
+
+    /// Returns the suballocations and free ranges within the [region], sorted by offset.
+    ///
+    /// All the allocations made since the allocator was last reset are merged into one node of
+    /// type [`SuballocationType::Unknown`], followed by the free range.
+    ///
+    /// [region]: Suballocator#regions
+    fn suballocations(&self) -> Vec<SuballocationNode> {
+        let free_start = self.state.load(Ordering::Acquire) >> 2;
+
+        [
+            SuballocationNode {
+                offset: self.region.offset,
+                size: free_start,
+                ty: SuballocationType::Unknown,
+            },
+            SuballocationNode {
+                offset: self.region.offset + free_start,
+                size: self.region.size - free_start,
+                ty: SuballocationType::Free,
+            },
+        ]
+        .into_iter()
+        .filter(|node| node.size > 0)
+        .collect()
+    }
+
     #[inline]
     fn cleanup(&mut self) {
         let _ = self.try_reset();
@@ -2582,6 +2914,20 @@ mod host {
             self.free_list.push(id);
         }
 
+        /// Returns an iterator over the occupied slots, in no particular order.
+        pub fn iter(&self) -> impl Iterator<Item = &T> {
+            let mut is_free = vec![false; self.pool.len()];
+
+            for id in &self.free_list {
+                is_free[id.0.get() - 1] = true;
+            }
+
+            self.pool
+                .iter()
+                .zip(is_free)
+                .filter_map(|(val, is_free)| (!is_free).then_some(val))
+        }
+
         /// Returns a mutable reference to the slot with the given ID.
         ///
         /// # Safety
@@ -2746,6 +3092,41 @@ mod tests {
         assert!(allocator.allocate(dummy_info_linear!()).is_err());
     }
 
+    #[test]
+    fn free_list_allocator_statistics() {
+        const REGION_SIZE: DeviceSize = 1024;
+
+        let allocator = dummy_allocator!(FreeListAllocator, REGION_SIZE);
+
+        let _alloc1 = allocator.allocate(dummy_info!(256)).unwrap();
+        let alloc2 = allocator.allocate(dummy_info!(256)).unwrap();
+        let _alloc3 = allocator.allocate(dummy_info!(256)).unwrap();
+        drop(alloc2);
+
+        let statistics = allocator.statistics();
+        assert_eq!(statistics.region_size, REGION_SIZE);
+        assert_eq!(statistics.allocation_count, 2);
+        assert_eq!(statistics.free_size, 512);
+        assert_eq!(statistics.free_range_count, 2);
+        assert_eq!(statistics.largest_free_range, 256);
+        assert_eq!(statistics.fragmentation(), 0.5);
+
+        let types: Vec<_> = allocator
+            .suballocations()
+            .into_iter()
+            .map(|node| (node.offset, node.size, node.ty))
+            .collect();
+        assert_eq!(
+            types,
+            [
+                (0, 256, SuballocationType::Unknown),
+                (256, 256, SuballocationType::Free),
+                (512, 256, SuballocationType::Unknown),
+                (768, 256, SuballocationType::Free),
+            ],
+        );
+    }
+
     #[test]
     fn pool_allocator_capacity() {
         const BLOCK_SIZE: DeviceSize = 1024;
@@ -2800,6 +3181,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pool_allocator_statistics() {
+        const BLOCK_SIZE: DeviceSize = 256;
+
+        let allocator = {
+            let (device, _) = gfx_dev_and_queue!();
+            let device_memory = DeviceMemory::allocate(
+                device,
+                MemoryAllocateInfo {
+                    allocation_size: 4 * BLOCK_SIZE,
+                    memory_type_index: 0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            PoolAllocator::<BLOCK_SIZE>::new(
+                MemoryAlloc::new(device_memory).unwrap(),
+                DeviceAlignment::new(1).unwrap(),
+            )
+        };
+
+        // Block indices are inserted into the free-list in order.
+        let _alloc1 = allocator.allocate(dummy_info!()).unwrap();
+        let alloc2 = allocator.allocate(dummy_info!()).unwrap();
+        let _alloc3 = allocator.allocate(dummy_info!()).unwrap();
+        drop(alloc2);
+
+        let statistics = allocator.statistics();
+        assert_eq!(statistics.allocation_count, 2);
+        assert_eq!(statistics.free_size, 2 * BLOCK_SIZE);
+
+        let types: Vec<_> = allocator
+            .suballocations()
+            .into_iter()
+            .map(|node| (node.offset, node.size, node.ty))
+            .collect();
+        assert_eq!(
+            types,
+            [
+                (0, 256, SuballocationType::Unknown),
+                (256, 256, SuballocationType::Free),
+                (512, 256, SuballocationType::Unknown),
+                (768, 256, SuballocationType::Free),
+            ],
+        );
+    }
+
     #[test]
     fn pool_allocator_respects_alignment() {
         const BLOCK_SIZE: DeviceSize = 1024 + 128;
@@ -2980,6 +3409,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn buddy_allocator_statistics() {
+        const REGION_SIZE: DeviceSize = 1024;
+
+        let allocator = dummy_allocator!(BuddyAllocator, REGION_SIZE);
+
+        let alloc1 = allocator.allocate(dummy_info!(256)).unwrap();
+        let _alloc2 = allocator.allocate(dummy_info!(200)).unwrap();
+        drop(alloc1);
+
+        let statistics = allocator.statistics();
+        assert_eq!(statistics.allocation_count, 1);
+        assert_eq!(statistics.free_size, 768);
+        assert_eq!(statistics.free_range_count, 2);
+        assert_eq!(statistics.largest_free_range, 512);
+
+        let types: Vec<_> = allocator
+            .suballocations()
+            .into_iter()
+            .map(|node| (node.offset, node.size, node.ty))
+            .collect();
+        assert_eq!(
+            types,
+            [
+                (0, 256, SuballocationType::Free),
+                (256, 256, SuballocationType::Unknown),
+                (512, 512, SuballocationType::Free),
+            ],
+        );
+    }
+
     #[test]
     fn bump_allocator_respects_alignment() {
         const ALIGNMENT: DeviceSize = 16;
@@ -0,0 +1,999 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Sharing memory between resources that are never in use at the same time.
+//!
+//! Within a frame, many images and buffers are only needed for a few passes: a G-buffer is
+//! written and then read by the lighting pass, a bloom chain is only needed during
+//! post-processing, and so on. A [`TransientPool`] is created from a description of all of these
+//! resources together with their *lifetime*, the range of passes during which each resource is
+//! used. Resources whose lifetimes don't overlap are placed in the same range of the same
+//! [`DeviceMemory`] block, so the pool only needs as much memory as the resources that are alive
+//! at the same time.
+//!
+//! The contents of transient resources don't survive from one frame to the next, nor from one
+//! resource to another one that shares its memory. At the start of each pass, [`activate`] must
+//! be called on the command buffer builder. For each resource whose lifetime starts at this pass
+//! and whose memory was used by another resource before, it records the barriers that make the
+//! new resource safe to use: the resources that previously used the memory are transitioned to
+//! their final layout, and images are transitioned from the [`Undefined`] layout.
+//!
+//! Since the pool can't see which commands use its resources, accessing them with [`image`] and
+//! [`buffer`] is unsafe: the caller must only use each resource during its lifetime, after it was
+//! activated.
+//!
+//! Images that have the [`TRANSIENT_ATTACHMENT`] usage are placed in lazily allocated memory when
+//! the device has it, in which case the implementation may not need to back them with any real
+//! memory at all.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use vulkano::command_buffer::{
+//! #     allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+//! # };
+//! # use vulkano::format::Format;
+//! # use vulkano::image::{sys::ImageCreateInfo, ImageDimensions, ImageLayout, ImageUsage};
+//! # use vulkano::memory::allocator::{
+//! #     TransientImageCreateInfo, TransientPool, TransientPoolCreateInfo,
+//! # };
+//! # let queue: Arc<vulkano::device::Queue> = return;
+//! # let command_buffer_allocator: StandardCommandBufferAllocator = return;
+//! #
+//! let image_info = |format, usage| ImageCreateInfo {
+//!     dimensions: ImageDimensions::Dim2d {
+//!         width: 1920,
+//!         height: 1080,
+//!         array_layers: 1,
+//!     },
+//!     format: Some(format),
+//!     usage,
+//!     ..Default::default()
+//! };
+//!
+//! let pool = TransientPool::new(
+//!     queue.device().clone(),
+//!     TransientPoolCreateInfo {
+//!         images: vec![
+//!             // The scene color is written in pass 0 and read in pass 1.
+//!             TransientImageCreateInfo {
+//!                 lifetime: 0..2,
+//!                 layout: ImageLayout::ColorAttachmentOptimal,
+//!                 ..TransientImageCreateInfo::image(image_info(
+//!                     Format::R16G16B16A16_SFLOAT,
+//!                     ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+//!                 ))
+//!             },
+//!             // The blurred image is written in pass 2 and can reuse the scene color's memory.
+//!             TransientImageCreateInfo {
+//!                 lifetime: 2..3,
+//!                 layout: ImageLayout::General,
+//!                 ..TransientImageCreateInfo::image(image_info(
+//!                     Format::R16G16B16A16_SFLOAT,
+//!                     ImageUsage::STORAGE | ImageUsage::SAMPLED,
+//!                 ))
+//!             },
+//!         ],
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//!
+//! let mut builder = AutoCommandBufferBuilder::primary(
+//!     &command_buffer_allocator,
+//!     queue.queue_family_index(),
+//!     CommandBufferUsage::OneTimeSubmit,
+//! )
+//! .unwrap();
+//!
+//! for pass in 0..3 {
+//!     pool.activate(&mut builder, pass).unwrap();
+//!
+//!     // Record the commands of the pass, using `unsafe { pool.image(0) }` in passes 0 and 1, and
+//!     // `unsafe { pool.image(1) }` in pass 2...
+//! }
+//! ```
+//!
+//! [`activate`]: TransientPool::activate
+//! [`image`]: TransientPool::image
+//! [`buffer`]: TransientPool::buffer
+//! [`Undefined`]: ImageLayout::Undefined
+//! [`TRANSIENT_ATTACHMENT`]: ImageUsage::TRANSIENT_ATTACHMENT
+
+use super::{align_up, AllocationCreationError, MemoryAlloc};
+use crate::{
+    buffer::{
+        sys::{BufferCreateInfo, RawBuffer},
+        BufferCreateFlags, BufferError, BufferUsage, Subbuffer,
+    },
+    command_buffer::{
+        allocator::CommandBufferAllocator, AutoCommandBufferBuilder, PrimaryAutoCommandBuffer,
+    },
+    device::{Device, DeviceOwned},
+    image::{
+        sys::{Image, ImageCreateInfo, RawImage},
+        traits::ImageContent,
+        ImageAccess, ImageCreateFlags, ImageDescriptorLayouts, ImageError, ImageInner, ImageLayout,
+        ImageTiling, ImageUsage,
+    },
+    memory::{
+        DeviceAlignment, DeviceMemory, DeviceMemoryError, MemoryAllocateFlags, MemoryAllocateInfo,
+        MemoryPropertyFlags, MemoryRequirements,
+    },
+    sync::{AccessFlags, BufferMemoryBarrier, DependencyInfo, ImageMemoryBarrier, PipelineStages},
+    DeviceSize,
+};
+use smallvec::SmallVec;
+use std::{
+    cmp,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    hash::{Hash, Hasher},
+    ops::Range,
+    sync::Arc,
+};
+
+/// A set of images and buffers that share memory when their lifetimes don't overlap.
+///
+/// See the [module-level documentation] for more information.
+///
+/// [module-level documentation]: self
+#[derive(Debug)]
+pub struct TransientPool {
+    device: Arc<Device>,
+    resources: Vec<TransientResource>,
+    image_count: usize,
+    blocks: Vec<TransientBlock>,
+}
+
+#[derive(Debug)]
+struct TransientResource {
+    kind: TransientResourceKind,
+    lifetime: Range<u32>,
+    block: usize,
+    offset: DeviceSize,
+    size: DeviceSize,
+    // The indices of the resources that share some of the memory of this one.
+    aliases: SmallVec<[usize; 4]>,
+}
+
+#[derive(Debug)]
+enum TransientResourceKind {
+    Image(Arc<TransientImage>),
+    Buffer(Subbuffer<[u8]>),
+}
+
+#[derive(Debug)]
+struct TransientBlock {
+    memory_type_index: u32,
+    size: DeviceSize,
+}
+
+// A resource that has been created, but whose memory hasn't been bound yet.
+enum RawResource {
+    Image(RawImage, ImageLayout),
+    Buffer(RawBuffer),
+}
+
+impl TransientPool {
+    /// Creates all the resources of the pool, and allocates and binds their memory.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the `lifetime` of any resource is empty.
+    /// - Panics if any image is created with the [`DISJOINT`] flag, or if any image or buffer is
+    ///   created with the [`SPARSE_BINDING`] flag.
+    ///
+    /// [`DISJOINT`]: ImageCreateFlags::DISJOINT
+    /// [`SPARSE_BINDING`]: ImageCreateFlags::SPARSE_BINDING
+    pub fn new(
+        device: Arc<Device>,
+        create_info: TransientPoolCreateInfo,
+    ) -> Result<Self, TransientPoolError> {
+        let TransientPoolCreateInfo {
+            images,
+            buffers,
+            lazily_allocated,
+            _ne: _,
+        } = create_info;
+
+        let image_count = images.len();
+        let mut raw_resources = Vec::with_capacity(images.len() + buffers.len());
+        let mut lifetimes = Vec::with_capacity(images.len() + buffers.len());
+
+        for image_info in images {
+            let TransientImageCreateInfo {
+                image_create_info,
+                layout,
+                lifetime,
+                _ne: _,
+            } = image_info;
+
+            assert!(!lifetime.is_empty());
+            assert!(!image_create_info
+                .flags
+                .intersects(ImageCreateFlags::DISJOINT | ImageCreateFlags::SPARSE_BINDING));
+
+            let raw_image = RawImage::new(device.clone(), image_create_info)?;
+            raw_resources.push(RawResource::Image(raw_image, layout));
+            lifetimes.push(lifetime);
+        }
+
+        for buffer_info in buffers {
+            let TransientBufferCreateInfo {
+                buffer_create_info,
+                lifetime,
+                _ne: _,
+            } = buffer_info;
+
+            assert!(!lifetime.is_empty());
+            assert!(!buffer_create_info
+                .flags
+                .intersects(BufferCreateFlags::SPARSE_BINDING));
+
+            let raw_buffer = RawBuffer::new(device.clone(), buffer_create_info)?;
+            raw_resources.push(RawResource::Buffer(raw_buffer));
+            lifetimes.push(lifetime);
+        }
+
+        // Pick a memory type for each resource. Resources can only share memory with resources
+        // that ended up with the same memory type.
+        let memory_properties = device.physical_device().memory_properties();
+        let memory_type_indices = raw_resources
+            .iter()
+            .enumerate()
+            .map(|(index, raw_resource)| {
+                let requirements = raw_resource.memory_requirements();
+
+                if requirements.requires_dedicated_allocation {
+                    return Err(TransientPoolError::DedicatedAllocationRequired { index });
+                }
+
+                let allows_lazy = lazily_allocated
+                    && matches!(raw_resource, RawResource::Image(raw_image, _)
+                        if raw_image.usage().intersects(ImageUsage::TRANSIENT_ATTACHMENT));
+                let memory_type_bits = requirements.memory_type_bits;
+                let find = |required_flags: MemoryPropertyFlags| {
+                    (0..memory_properties.memory_types.len() as u32).find(|&index| {
+                        let property_flags =
+                            memory_properties.memory_types[index as usize].property_flags;
+
+                        memory_type_bits & (1 << index) != 0
+                            && property_flags.contains(required_flags)
+                            && !property_flags.intersects(MemoryPropertyFlags::PROTECTED)
+                            && (required_flags.intersects(MemoryPropertyFlags::LAZILY_ALLOCATED)
+                                || !property_flags
+                                    .intersects(MemoryPropertyFlags::LAZILY_ALLOCATED))
+                    })
+                };
+
+                allows_lazy
+                    .then(|| find(MemoryPropertyFlags::LAZILY_ALLOCATED))
+                    .flatten()
+                    .or_else(|| find(MemoryPropertyFlags::DEVICE_LOCAL))
+                    .or_else(|| find(MemoryPropertyFlags::empty()))
+                    .ok_or(TransientPoolError::NoSuitableMemoryType { index })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Place the resources of each memory type in their own block, largest first. Each
+        // resource goes at the lowest offset that doesn't overlap any resource with an overlapping
+        // lifetime that was already placed.
+        let buffer_image_granularity = device
+            .physical_device()
+            .properties()
+            .buffer_image_granularity;
+        let mut blocks: Vec<TransientBlock> = Vec::new();
+        let mut placements = vec![(0, 0, 0); raw_resources.len()];
+
+        for memory_type_index in 0..memory_properties.memory_types.len() as u32 {
+            let mut indices: Vec<usize> = (0..raw_resources.len())
+                .filter(|&index| memory_type_indices[index] == memory_type_index)
+                .collect();
+
+            if indices.is_empty() {
+                continue;
+            }
+
+            // Linear and non-linear resources that are next to each other must be separated by
+            // the buffer-image granularity. To keep things simple, we align all resources to it
+            // when both kinds end up in the same block.
+            let has_linear = indices
+                .iter()
+                .any(|&index| raw_resources[index].is_linear());
+            let has_non_linear = indices
+                .iter()
+                .any(|&index| !raw_resources[index].is_linear());
+            let min_alignment = if has_linear && has_non_linear {
+                buffer_image_granularity
+            } else {
+                DeviceAlignment::MIN
+            };
+
+            indices.sort_by_key(|&index| {
+                cmp::Reverse(raw_resources[index].memory_requirements().layout.size())
+            });
+
+            let block = blocks.len();
+            let mut placed: Vec<(usize, Range<DeviceSize>)> = Vec::with_capacity(indices.len());
+            let mut block_size = 0;
+
+            for index in indices {
+                let layout = raw_resources[index].memory_requirements().layout;
+                let alignment = cmp::max(layout.alignment(), min_alignment);
+                let size = layout.size();
+
+                let conflicts: SmallVec<[Range<DeviceSize>; 8]> = placed
+                    .iter()
+                    .filter(|(other, _)| ranges_overlap(&lifetimes[index], &lifetimes[*other]))
+                    .map(|(_, range)| range.clone())
+                    .collect();
+                let offset = [0]
+                    .into_iter()
+                    .chain(conflicts.iter().map(|range| range.end))
+                    .map(|offset| align_up(offset, alignment))
+                    .filter(|&offset| {
+                        conflicts
+                            .iter()
+                            .all(|range| !ranges_overlap(&(offset..offset + size), range))
+                    })
+                    .min()
+                    .unwrap();
+
+                placed.push((index, offset..offset + size));
+                placements[index] = (block, offset, size);
+                block_size = cmp::max(block_size, offset + size);
+            }
+
+            blocks.push(TransientBlock {
+                memory_type_index,
+                size: block_size,
+            });
+        }
+
+        // Allocate the blocks.
+        let mut block_allocs = Vec::with_capacity(blocks.len());
+
+        for (block_index, block) in blocks.iter().enumerate() {
+            let device_address = raw_resources
+                .iter()
+                .enumerate()
+                .any(|(index, raw_resource)| {
+                    placements[index].0 == block_index
+                        && matches!(raw_resource, RawResource::Buffer(raw_buffer)
+                        if raw_buffer.usage().intersects(BufferUsage::SHADER_DEVICE_ADDRESS))
+                });
+
+            let device_memory = DeviceMemory::allocate(
+                device.clone(),
+                MemoryAllocateInfo {
+                    allocation_size: block.size,
+                    memory_type_index: block.memory_type_index,
+                    flags: if device_address {
+                        MemoryAllocateFlags::DEVICE_ADDRESS
+                    } else {
+                        MemoryAllocateFlags::empty()
+                    },
+                    ..MemoryAllocateInfo::default()
+                },
+            )?;
+            block_allocs.push(MemoryAlloc::new(device_memory)?);
+        }
+
+        // Bind each resource to its part of its block.
+        let resources = raw_resources
+            .into_iter()
+            .zip(lifetimes)
+            .enumerate()
+            .map(|(index, (raw_resource, lifetime))| {
+                let (block, offset, size) = placements[index];
+
+                // SAFETY: The resources can only be accessed through `image` and `buffer`, whose
+                // callers must only use them during their lifetime, after `activate` has inserted
+                // the barriers that are needed when switching between aliasing resources.
+                let mut allocation = unsafe { block_allocs[block].alias() }.unwrap();
+                allocation.shift(offset);
+                allocation.shrink(size);
+
+                let kind = match raw_resource {
+                    RawResource::Image(raw_image, layout) => {
+                        let image = raw_image
+                            .bind_memory([allocation])
+                            .map_err(|(err, _, _)| err)?;
+
+                        TransientResourceKind::Image(Arc::new(TransientImage {
+                            inner: Arc::new(image),
+                            layout,
+                        }))
+                    }
+                    RawResource::Buffer(raw_buffer) => {
+                        let buffer = raw_buffer
+                            .bind_memory(allocation)
+                            .map_err(|(err, _, _)| err)?;
+
+                        TransientResourceKind::Buffer(Subbuffer::new(Arc::new(buffer)))
+                    }
+                };
+
+                let aliases = (0..placements.len())
+                    .filter(|&other| {
+                        let (other_block, other_offset, other_size) = placements[other];
+
+                        other != index
+                            && other_block == block
+                            && ranges_overlap(
+                                &(offset..offset + size),
+                                &(other_offset..other_offset + other_size),
+                            )
+                    })
+                    .collect();
+
+                Ok(TransientResource {
+                    kind,
+                    lifetime,
+                    block,
+                    offset,
+                    size,
+                    aliases,
+                })
+            })
+            .collect::<Result<Vec<_>, TransientPoolError>>()?;
+
+        Ok(TransientPool {
+            device,
+            resources,
+            image_count,
+            blocks,
+        })
+    }
+
+    /// Returns the image at `index` in [`TransientPoolCreateInfo::images`].
+    ///
+    /// # Safety
+    ///
+    /// - The image must only be used by commands recorded during the passes of its `lifetime`,
+    ///   after [`activate`] was called for the pass in the same command buffer.
+    /// - The image must not be used by a command buffer that can execute at the same time as
+    ///   another command buffer that uses a resource of the pool that shares its memory.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `index` is out of range.
+    ///
+    /// [`activate`]: Self::activate
+    #[inline]
+    pub unsafe fn image(&self, index: usize) -> &Arc<TransientImage> {
+        assert!(index < self.image_count);
+
+        match &self.resources[index].kind {
+            TransientResourceKind::Image(image) => image,
+            TransientResourceKind::Buffer(_) => unreachable!(),
+        }
+    }
+
+    /// Returns the buffer at `index` in [`TransientPoolCreateInfo::buffers`].
+    ///
+    /// # Safety
+    ///
+    /// - The buffer must only be used by commands recorded during the passes of its `lifetime`,
+    ///   after [`activate`] was called for the pass in the same command buffer.
+    /// - The buffer must not be used by a command buffer that can execute at the same time as
+    ///   another command buffer that uses a resource of the pool that shares its memory.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `index` is out of range.
+    ///
+    /// [`activate`]: Self::activate
+    #[inline]
+    pub unsafe fn buffer(&self, index: usize) -> &Subbuffer<[u8]> {
+        assert!(index < self.resources.len() - self.image_count);
+
+        match &self.resources[self.image_count + index].kind {
+            TransientResourceKind::Buffer(buffer) => buffer,
+            TransientResourceKind::Image(_) => unreachable!(),
+        }
+    }
+
+    /// Returns the total number of bytes of device memory that the pool allocated.
+    #[inline]
+    pub fn memory_size(&self) -> DeviceSize {
+        self.blocks.iter().map(|block| block.size).sum()
+    }
+
+    /// Returns the number of bytes that the resources of the pool would need if they didn't
+    /// share any memory.
+    #[inline]
+    pub fn unaliased_size(&self) -> DeviceSize {
+        self.resources.iter().map(|resource| resource.size).sum()
+    }
+
+    /// Records the barriers that are needed before the resources whose lifetime starts at `pass`
+    /// can be used.
+    ///
+    /// This must be called at the start of every pass, outside of a render pass, before any
+    /// command of the pass is recorded. It records nothing if no resource starts reusing memory
+    /// at `pass`.
+    ///
+    /// The resources that previously used the memory are transitioned to their final layout and
+    /// must not be used anymore by the command buffer. The new images are transitioned from the
+    /// [`Undefined`] layout, so their contents are discarded.
+    ///
+    /// [`Undefined`]: ImageLayout::Undefined
+    pub fn activate<A>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer<A::Alloc>, A>,
+        pass: u32,
+    ) -> Result<(), TransientPoolError>
+    where
+        A: CommandBufferAllocator,
+    {
+        assert_eq!(self.device(), builder.device());
+
+        let mut retired_images: SmallVec<[Arc<Image>; 4]> = SmallVec::new();
+        let mut dependency_info = DependencyInfo::default();
+
+        for resource in self
+            .resources
+            .iter()
+            .filter(|resource| resource.lifetime.start == pass && !resource.aliases.is_empty())
+        {
+            for &alias in &resource.aliases {
+                let alias = &self.resources[alias];
+
+                if alias.lifetime.end <= pass {
+                    if let TransientResourceKind::Image(image) = &alias.kind {
+                        if !retired_images.contains(&image.inner) {
+                            retired_images.push(image.inner.clone());
+                        }
+                    }
+                }
+            }
+
+            match &resource.kind {
+                TransientResourceKind::Image(image) => {
+                    dependency_info
+                        .image_memory_barriers
+                        .push(ImageMemoryBarrier {
+                            src_stages: PipelineStages::ALL_COMMANDS,
+                            src_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                            dst_stages: PipelineStages::ALL_COMMANDS,
+                            dst_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                            old_layout: ImageLayout::Undefined,
+                            new_layout: image.layout,
+                            subresource_range: image.inner.subresource_range(),
+                            ..ImageMemoryBarrier::image(image.inner.clone())
+                        });
+                }
+                TransientResourceKind::Buffer(buffer) => {
+                    dependency_info
+                        .buffer_memory_barriers
+                        .push(BufferMemoryBarrier {
+                            src_stages: PipelineStages::ALL_COMMANDS,
+                            src_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                            dst_stages: PipelineStages::ALL_COMMANDS,
+                            dst_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                            range: 0..buffer.size(),
+                            ..BufferMemoryBarrier::buffer(buffer.buffer().clone())
+                        });
+                }
+            }
+        }
+
+        if dependency_info.image_memory_barriers.is_empty()
+            && dependency_info.buffer_memory_barriers.is_empty()
+        {
+            return Ok(());
+        }
+
+        // SAFETY: The callers of `image` and `buffer` only use the resources during their
+        // lifetime, so the retired resources are not used anymore, and the new resources are only
+        // used after the barriers, starting from `Undefined`.
+        if unsafe { builder.aliasing_barrier(&retired_images, dependency_info) } {
+            Ok(())
+        } else {
+            Err(TransientPoolError::ForbiddenInsideRenderPass)
+        }
+    }
+
+    /// Returns the index of the memory block that the resource at `index` was placed in, and the
+    /// range of bytes that it occupies in that block. Images come first, in the order of
+    /// [`TransientPoolCreateInfo::images`], and buffers come after them.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `index` is out of range.
+    #[inline]
+    pub fn placement(&self, index: usize) -> (usize, Range<DeviceSize>) {
+        let resource = &self.resources[index];
+
+        (
+            resource.block,
+            resource.offset..resource.offset + resource.size,
+        )
+    }
+}
+
+unsafe impl DeviceOwned for TransientPool {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+impl RawResource {
+    fn memory_requirements(&self) -> &MemoryRequirements {
+        match self {
+            RawResource::Image(raw_image, _) => &raw_image.memory_requirements()[0],
+            RawResource::Buffer(raw_buffer) => raw_buffer.memory_requirements(),
+        }
+    }
+
+    fn is_linear(&self) -> bool {
+        match self {
+            RawResource::Image(raw_image, _) => raw_image.tiling() == ImageTiling::Linear,
+            RawResource::Buffer(_) => true,
+        }
+    }
+}
+
+fn ranges_overlap<T: Ord>(a: &Range<T>, b: &Range<T>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Parameters to create a new `TransientPool`.
+#[derive(Clone, Debug)]
+pub struct TransientPoolCreateInfo {
+    /// The images to create.
+    ///
+    /// The default value is empty.
+    pub images: Vec<TransientImageCreateInfo>,
+
+    /// The buffers to create.
+    ///
+    /// The default value is empty.
+    pub buffers: Vec<TransientBufferCreateInfo>,
+
+    /// Whether to place images with the [`TRANSIENT_ATTACHMENT`] usage in lazily allocated
+    /// memory, if the device has a memory type for it.
+    ///
+    /// The default value is `true`.
+    ///
+    /// [`TRANSIENT_ATTACHMENT`]: ImageUsage::TRANSIENT_ATTACHMENT
+    pub lazily_allocated: bool,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for TransientPoolCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            images: Vec::new(),
+            buffers: Vec::new(),
+            lazily_allocated: true,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Parameters to create an image in a [`TransientPool`].
+#[derive(Clone, Debug)]
+pub struct TransientImageCreateInfo {
+    /// The parameters of the image.
+    pub image_create_info: ImageCreateInfo,
+
+    /// The layout that the image is in between commands.
+    ///
+    /// The default value is [`ImageLayout::General`].
+    pub layout: ImageLayout,
+
+    /// The range of passes during which the image is used.
+    ///
+    /// The default value is `0..1`.
+    pub lifetime: Range<u32>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl TransientImageCreateInfo {
+    /// Returns a `TransientImageCreateInfo` with the specified `image_create_info`.
+    #[inline]
+    pub fn image(image_create_info: ImageCreateInfo) -> Self {
+        Self {
+            image_create_info,
+            layout: ImageLayout::General,
+            lifetime: 0..1,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Parameters to create a buffer in a [`TransientPool`].
+#[derive(Clone, Debug)]
+pub struct TransientBufferCreateInfo {
+    /// The parameters of the buffer.
+    pub buffer_create_info: BufferCreateInfo,
+
+    /// The range of passes during which the buffer is used.
+    ///
+    /// The default value is `0..1`.
+    pub lifetime: Range<u32>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl TransientBufferCreateInfo {
+    /// Returns a `TransientBufferCreateInfo` with the specified `buffer_create_info`.
+    #[inline]
+    pub fn buffer(buffer_create_info: BufferCreateInfo) -> Self {
+        Self {
+            buffer_create_info,
+            lifetime: 0..1,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// An image that was created by a [`TransientPool`].
+///
+/// The contents of the image are never preserved between command buffers: the image always
+/// starts out in the [`Undefined`] layout.
+///
+/// [`Undefined`]: ImageLayout::Undefined
+#[derive(Debug)]
+pub struct TransientImage {
+    inner: Arc<Image>,
+    layout: ImageLayout,
+}
+
+impl TransientImage {
+    /// Returns the layout that the image is in between commands.
+    #[inline]
+    pub fn layout(&self) -> ImageLayout {
+        self.layout
+    }
+}
+
+unsafe impl ImageAccess for TransientImage {
+    #[inline]
+    fn inner(&self) -> ImageInner<'_> {
+        ImageInner {
+            image: &self.inner,
+            first_layer: 0,
+            num_layers: self.inner.dimensions().array_layers(),
+            first_mipmap_level: 0,
+            num_mipmap_levels: self.inner.mip_levels(),
+        }
+    }
+
+    #[inline]
+    fn initial_layout_requirement(&self) -> ImageLayout {
+        ImageLayout::Undefined
+    }
+
+    #[inline]
+    fn final_layout_requirement(&self) -> ImageLayout {
+        self.layout
+    }
+
+    #[inline]
+    fn descriptor_layouts(&self) -> Option<ImageDescriptorLayouts> {
+        Some(ImageDescriptorLayouts {
+            storage_image: ImageLayout::General,
+            combined_image_sampler: ImageLayout::ShaderReadOnlyOptimal,
+            sampled_image: ImageLayout::ShaderReadOnlyOptimal,
+            input_attachment: ImageLayout::ShaderReadOnlyOptimal,
+        })
+    }
+}
+
+unsafe impl DeviceOwned for TransientImage {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+unsafe impl<P> ImageContent<P> for TransientImage {
+    fn matches_format(&self) -> bool {
+        true // FIXME:
+    }
+}
+
+impl PartialEq for TransientImage {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner()
+    }
+}
+
+impl Eq for TransientImage {}
+
+impl Hash for TransientImage {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().hash(state);
+    }
+}
+
+/// Error that can happen when creating a [`TransientPool`] or activating its resources.
+#[derive(Clone, Debug)]
+pub enum TransientPoolError {
+    /// Creating an image or binding its memory failed.
+    ImageError(ImageError),
+
+    /// Creating a buffer or binding its memory failed.
+    BufferError(BufferError),
+
+    /// Allocating device memory failed.
+    DeviceMemoryError(DeviceMemoryError),
+
+    /// Mapping the allocated device memory failed.
+    AllocError(AllocationCreationError),
+
+    /// A resource requires a dedicated allocation, so it can't share memory.
+    DedicatedAllocationRequired { index: usize },
+
+    /// None of the memory types that a resource supports are usable.
+    NoSuitableMemoryType { index: usize },
+
+    /// Resources can't be activated while a render pass is active.
+    ForbiddenInsideRenderPass,
+}
+
+impl Error for TransientPoolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ImageError(err) => Some(err),
+            Self::BufferError(err) => Some(err),
+            Self::DeviceMemoryError(err) => Some(err),
+            Self::AllocError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TransientPoolError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::ImageError(_) => write!(f, "creating an image or binding its memory failed"),
+            Self::BufferError(_) => write!(f, "creating a buffer or binding its memory failed"),
+            Self::DeviceMemoryError(_) => write!(f, "allocating device memory failed"),
+            Self::AllocError(_) => write!(f, "mapping the allocated device memory failed"),
+            Self::DedicatedAllocationRequired { index } => write!(
+                f,
+                "resource {} requires a dedicated allocation, so it can't share memory",
+                index,
+            ),
+            Self::NoSuitableMemoryType { index } => write!(
+                f,
+                "none of the memory types that resource {} supports are usable",
+                index,
+            ),
+            Self::ForbiddenInsideRenderPass => write!(
+                f,
+                "resources can't be activated while a render pass is active",
+            ),
+        }
+    }
+}
+
+impl From<ImageError> for TransientPoolError {
+    fn from(err: ImageError) -> Self {
+        Self::ImageError(err)
+    }
+}
+
+impl From<BufferError> for TransientPoolError {
+    fn from(err: BufferError) -> Self {
+        Self::BufferError(err)
+    }
+}
+
+impl From<DeviceMemoryError> for TransientPoolError {
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::DeviceMemoryError(err)
+    }
+}
+
+impl From<AllocationCreationError> for TransientPoolError {
+    fn from(err: AllocationCreationError) -> Self {
+        Self::AllocError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ranges_overlap, TransientBufferCreateInfo, TransientImageCreateInfo, TransientPool,
+        TransientPoolCreateInfo,
+    };
+    use crate::{
+        buffer::{sys::BufferCreateInfo, BufferUsage},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+        },
+        format::Format,
+        image::{sys::ImageCreateInfo, ImageDimensions, ImageUsage},
+    };
+
+    #[test]
+    fn overlap() {
+        assert!(ranges_overlap(&(0..2), &(1..3)));
+        assert!(!ranges_overlap(&(0..2), &(2..3)));
+        assert!(!ranges_overlap(&(2..3), &(0..2)));
+        assert!(ranges_overlap(&(0..4), &(1..2)));
+    }
+
+    #[test]
+    fn aliasing_placement() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let image_info = |lifetime| TransientImageCreateInfo {
+            lifetime,
+            ..TransientImageCreateInfo::image(ImageCreateInfo {
+                dimensions: ImageDimensions::Dim2d {
+                    width: 64,
+                    height: 64,
+                    array_layers: 1,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+                ..Default::default()
+            })
+        };
+        let buffer_info = |lifetime| TransientBufferCreateInfo {
+            lifetime,
+            ..TransientBufferCreateInfo::buffer(BufferCreateInfo {
+                size: 4096,
+                usage: BufferUsage::STORAGE_BUFFER,
+                ..Default::default()
+            })
+        };
+
+        let pool = TransientPool::new(
+            device.clone(),
+            TransientPoolCreateInfo {
+                images: vec![image_info(0..2), image_info(1..3), image_info(2..4)],
+                buffers: vec![buffer_info(0..4)],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Resources with overlapping lifetimes never share memory.
+        for (a, b) in [(0, 1), (1, 2), (0, 3), (1, 3), (2, 3)] {
+            let (block_a, range_a) = pool.placement(a);
+            let (block_b, range_b) = pool.placement(b);
+            assert!(block_a != block_b || !ranges_overlap(&range_a, &range_b));
+        }
+
+        // The first and last images can share memory.
+        if pool.placement(0).0 == pool.placement(2).0 {
+            assert_eq!(pool.placement(0).1, pool.placement(2).1);
+            assert!(pool.memory_size() < pool.unaliased_size());
+        }
+
+        let command_buffer_allocator =
+            StandardCommandBufferAllocator::new(device, Default::default());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        for pass in 0..4 {
+            pool.activate(&mut builder, pass).unwrap();
+        }
+
+        builder.build().unwrap();
+    }
+}
@@ -102,9 +102,13 @@ impl DeviceMemory {
             dedicated_allocation,
             export_handle_types,
             flags,
+            priority: _,
             _ne: _,
         } = allocate_info;
 
+        device.memory_heap_usage[Self::heap_index(&device, memory_type_index)]
+            .fetch_add(allocation_size, Ordering::Release);
+
         DeviceMemory {
             handle,
             device,
@@ -152,6 +156,7 @@ impl DeviceMemory {
             ref mut dedicated_allocation,
             export_handle_types,
             flags,
+            priority,
             _ne: _,
         } = allocate_info;
 
@@ -423,6 +428,19 @@ impl DeviceMemory {
             }
         }
 
+        // VUID-VkMemoryPriorityAllocateInfoEXT-priority-02602
+        assert!((0.0..=1.0).contains(&priority));
+
+        if priority != 0.5 && !device.enabled_features().memory_priority {
+            return Err(DeviceMemoryError::RequirementNotMet {
+                required_for: "`allocate_info.priority` is not `0.5`",
+                requires_one_of: RequiresOneOf {
+                    features: &["memory_priority"],
+                    ..Default::default()
+                },
+            });
+        }
+
         Ok(())
     }
 
@@ -439,6 +457,7 @@ impl DeviceMemory {
             dedicated_allocation,
             export_handle_types,
             flags,
+            priority,
             _ne: _,
         } = allocate_info;
 
@@ -527,6 +546,15 @@ impl DeviceMemory {
             allocate_info = allocate_info.push_next(&mut flags_info);
         }
 
+        let mut priority_info = ash::vk::MemoryPriorityAllocateInfoEXT {
+            priority,
+            ..Default::default()
+        };
+
+        if priority != 0.5 {
+            allocate_info = allocate_info.push_next(&mut priority_info);
+        }
+
         // VUID-vkAllocateMemory-maxMemoryAllocationCount-04101
         let max_allocations = device
             .physical_device()
@@ -557,6 +585,9 @@ impl DeviceMemory {
             output.assume_init()
         };
 
+        device.memory_heap_usage[Self::heap_index(&device, memory_type_index)]
+            .fetch_add(allocation_size, Ordering::Release);
+
         Ok(DeviceMemory {
             handle,
             device,
@@ -570,6 +601,11 @@ impl DeviceMemory {
         })
     }
 
+    fn heap_index(device: &Device, memory_type_index: u32) -> usize {
+        device.physical_device().memory_properties().memory_types[memory_type_index as usize]
+            .heap_index as usize
+    }
+
     /// Returns the index of the memory type that this memory was allocated from.
     #[inline]
     pub fn memory_type_index(&self) -> u32 {
@@ -658,6 +694,54 @@ impl DeviceMemory {
         output
     }
 
+    /// Changes the priority of the memory, relative to other memory allocations, which the
+    /// implementation uses to decide which allocations to move out of device-local memory when it
+    /// runs out.
+    ///
+    /// `priority` must be between 0.0 and 1.0, where higher values mean that the memory is more
+    /// likely to stay device-local.
+    ///
+    /// The [`pageable_device_local_memory`] feature must be enabled on the device.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `priority` is not between 0.0 and 1.0 inclusive.
+    ///
+    /// [`pageable_device_local_memory`]: crate::device::Features::pageable_device_local_memory
+    #[inline]
+    pub fn set_priority(&self, priority: f32) -> Result<(), DeviceMemoryError> {
+        self.validate_set_priority(priority)?;
+
+        unsafe { self.set_priority_unchecked(priority) };
+
+        Ok(())
+    }
+
+    fn validate_set_priority(&self, priority: f32) -> Result<(), DeviceMemoryError> {
+        // VUID-vkSetDeviceMemoryPriorityEXT-priority-06258
+        assert!((0.0..=1.0).contains(&priority));
+
+        if !self.device.enabled_features().pageable_device_local_memory {
+            return Err(DeviceMemoryError::RequirementNotMet {
+                required_for: "`DeviceMemory::set_priority`",
+                requires_one_of: RequiresOneOf {
+                    features: &["pageable_device_local_memory"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn set_priority_unchecked(&self, priority: f32) {
+        let fns = self.device.fns();
+        (fns.ext_pageable_device_local_memory
+            .set_device_memory_priority_ext)(self.device.handle(), self.handle, priority);
+    }
+
     /// Exports the device memory into a Unix file descriptor. The caller owns the returned `File`.
     ///
     /// # Panics
@@ -728,6 +812,8 @@ impl Drop for DeviceMemory {
             let fns = self.device.fns();
             (fns.v1_0.free_memory)(self.device.handle(), self.handle, ptr::null());
             self.device.allocation_count.fetch_sub(1, Ordering::Release);
+            self.device.memory_heap_usage[Self::heap_index(&self.device, self.memory_type_index)]
+                .fetch_sub(self.allocation_size, Ordering::Release);
         }
     }
 }
@@ -783,6 +869,17 @@ pub struct MemoryAllocateInfo<'d> {
     /// The default value is [`MemoryAllocateFlags::empty()`].
     pub flags: MemoryAllocateFlags,
 
+    /// The priority of the memory, relative to other memory allocations, which the
+    /// implementation can use to decide which memory to keep in device-local memory when memory
+    /// runs short.
+    ///
+    /// The priority must be between 0.0 and 1.0. If it is not 0.5, then the
+    /// [`memory_priority`](crate::device::Features::memory_priority) feature must be enabled on
+    /// the device.
+    ///
+    /// The default value is `0.5`.
+    pub priority: f32,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -795,6 +892,7 @@ impl Default for MemoryAllocateInfo<'static> {
             dedicated_allocation: None,
             export_handle_types: ExternalMemoryHandleTypes::empty(),
             flags: MemoryAllocateFlags::empty(),
+            priority: 0.5,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -810,6 +908,7 @@ impl<'d> MemoryAllocateInfo<'d> {
             dedicated_allocation: Some(dedicated_allocation),
             export_handle_types: ExternalMemoryHandleTypes::empty(),
             flags: MemoryAllocateFlags::empty(),
+            priority: 0.5,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -1715,4 +1814,34 @@ mod tests {
         }
         assert_eq!(device.allocation_count(), 1);
     }
+
+    #[test]
+    fn memory_heap_usage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let heap_index = device.physical_device().memory_properties().memory_types[0].heap_index;
+        assert_eq!(device.memory_heap_usage(heap_index), 0);
+        let _mem1 = DeviceMemory::allocate(
+            device.clone(),
+            MemoryAllocateInfo {
+                allocation_size: 256,
+                memory_type_index: 0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(device.memory_heap_usage(heap_index), 256);
+        {
+            let _mem2 = DeviceMemory::allocate(
+                device.clone(),
+                MemoryAllocateInfo {
+                    allocation_size: 512,
+                    memory_type_index: 0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            assert_eq!(device.memory_heap_usage(heap_index), 768);
+        }
+        assert_eq!(device.memory_heap_usage(heap_index), 256);
+    }
 }
@@ -113,6 +113,7 @@ use std::{
 mod alignment;
 pub mod allocator;
 mod device_memory;
+pub(crate) mod sparse;
 
 /// Properties of the memory in a physical device.
 #[derive(Clone, Debug)]
@@ -285,6 +286,21 @@ pub struct MemoryHeap {
     pub flags: MemoryHeapFlags,
 }
 
+/// The memory budget and usage of a memory heap, as reported by
+/// [`PhysicalDevice::memory_budget`].
+///
+/// [`PhysicalDevice::memory_budget`]: crate::device::physical::PhysicalDevice::memory_budget
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MemoryHeapBudget {
+    /// An estimate of how much memory the process can allocate from the heap before allocations
+    /// may fail or cause performance degradation.
+    pub budget: DeviceSize,
+
+    /// An estimate of how much memory the process is currently using in the heap.
+    pub usage: DeviceSize,
+}
+
 vulkan_bitflags! {
     #[non_exhaustive]
 
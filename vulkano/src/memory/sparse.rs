@@ -0,0 +1,297 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Bookkeeping of the memory that is bound to sparse resources.
+
+use super::{DeviceMemory, SparseImageMemoryBind};
+use crate::{
+    image::{ImageAspects, ImageDimensions, SparseImageMemoryRequirements},
+    range_map::RangeMap,
+    DeviceSize,
+};
+use std::{ops::Range, sync::Arc};
+
+/// Keeps track of which parts of a range have memory bound to them.
+///
+/// The bound memory is kept alive for as long as it remains bound.
+#[derive(Debug, Default)]
+pub(crate) struct SparseResidency {
+    bound: RangeMap<DeviceSize, Arc<DeviceMemory>>,
+}
+
+impl SparseResidency {
+    /// Binds `memory` to `range`, or unbinds the memory in `range` if `memory` is `None`.
+    pub(crate) fn bind(&mut self, range: Range<DeviceSize>, memory: Option<&Arc<DeviceMemory>>) {
+        if range.is_empty() {
+            return;
+        }
+
+        match memory {
+            Some(memory) => self.bound.insert(range, memory.clone()),
+            None => self.bound.remove(range),
+        }
+    }
+
+    /// Returns whether the whole of `range` has memory bound to it.
+    pub(crate) fn is_resident(&self, range: Range<DeviceSize>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+
+        let mut covered_end = range.start;
+
+        for (bound_range, _) in self.bound.range(&range) {
+            if bound_range.start > covered_end {
+                return false;
+            }
+
+            covered_end = bound_range.end;
+
+            if covered_end >= range.end {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// Keeps track of the memory that is bound to a sparse image.
+#[derive(Debug)]
+pub(crate) struct SparseImageResidency {
+    opaque: SparseResidency,
+    aspects: Vec<SparseImageAspectResidency>,
+}
+
+/// The sparse image blocks of one set of aspects of a sparse image, indexed linearly by array
+/// layer, then by mip level, then by block coordinate.
+#[derive(Debug)]
+struct SparseImageAspectResidency {
+    requirements: SparseImageMemoryRequirements,
+    // For each mip level that is not in the mip tail: the number of blocks in each dimension,
+    // and the index of the level's first block within an array layer.
+    mip_levels: Vec<([u32; 3], DeviceSize)>,
+    array_layer_block_count: DeviceSize,
+    blocks: SparseResidency,
+}
+
+impl SparseImageResidency {
+    pub(crate) fn new(
+        dimensions: ImageDimensions,
+        mip_levels: u32,
+        sparse_memory_requirements: &[SparseImageMemoryRequirements],
+    ) -> Self {
+        let aspects = sparse_memory_requirements
+            .iter()
+            .map(|requirements| {
+                // The metadata aspect can only be bound as part of the opaque memory range.
+                let tail_first_lod = if requirements
+                    .format_properties
+                    .aspects
+                    .intersects(ImageAspects::METADATA)
+                {
+                    0
+                } else {
+                    requirements.image_mip_tail_first_lod.min(mip_levels)
+                };
+
+                let mut array_layer_block_count = 0;
+                let mip_levels = (0..tail_first_lod)
+                    .map(|mip_level| {
+                        let extent = dimensions
+                            .mip_level_dimensions(mip_level)
+                            .unwrap()
+                            .width_height_depth();
+                        let block_count = requirements.format_properties.block_count(extent);
+                        let first_block = array_layer_block_count;
+                        array_layer_block_count += block_count[0] as DeviceSize
+                            * block_count[1] as DeviceSize
+                            * block_count[2] as DeviceSize;
+
+                        (block_count, first_block)
+                    })
+                    .collect();
+
+                SparseImageAspectResidency {
+                    requirements: requirements.clone(),
+                    mip_levels,
+                    array_layer_block_count,
+                    blocks: SparseResidency::default(),
+                }
+            })
+            .collect();
+
+        SparseImageResidency {
+            opaque: SparseResidency::default(),
+            aspects,
+        }
+    }
+
+    /// Binds or unbinds memory in the opaque memory range of the image.
+    pub(crate) fn bind_opaque(
+        &mut self,
+        range: Range<DeviceSize>,
+        memory: Option<&Arc<DeviceMemory>>,
+    ) {
+        self.opaque.bind(range, memory);
+    }
+
+    /// Binds or unbinds memory for a region of sparse image blocks.
+    pub(crate) fn bind_blocks(&mut self, memory_bind: &SparseImageMemoryBind) {
+        let &SparseImageMemoryBind {
+            aspects,
+            mip_level,
+            array_layer,
+            offset,
+            extent,
+            ref memory,
+        } = memory_bind;
+
+        let aspect = match self.aspect_mut(aspects) {
+            Some(aspect) => aspect,
+            None => return,
+        };
+        let (block_count, first_block) = match aspect.mip_levels.get(mip_level as usize) {
+            Some(&mip_level) => mip_level,
+            None => return,
+        };
+        let granularity = aspect.requirements.format_properties.image_granularity;
+        let block_range = |i: usize| {
+            let start = (offset[i] / granularity[i]).min(block_count[i]);
+            let end =
+                ((offset[i] + extent[i] + granularity[i] - 1) / granularity[i]).min(block_count[i]);
+
+            start as DeviceSize..end as DeviceSize
+        };
+        let (x_range, y_range, z_range) = (block_range(0), block_range(1), block_range(2));
+        let layer_start = array_layer as DeviceSize * aspect.array_layer_block_count + first_block;
+
+        for z in z_range {
+            for y in y_range.clone() {
+                let row_start = layer_start
+                    + (z * block_count[1] as DeviceSize + y) * block_count[0] as DeviceSize;
+
+                aspect.blocks.bind(
+                    row_start + x_range.start..row_start + x_range.end,
+                    memory.as_ref().map(|(memory, _)| memory),
+                );
+            }
+        }
+    }
+
+    /// Returns whether the whole of `range` in the opaque memory range of the image has memory
+    /// bound to it.
+    pub(crate) fn is_opaque_resident(&self, range: Range<DeviceSize>) -> bool {
+        self.opaque.is_resident(range)
+    }
+
+    /// Returns whether the given sparse image block has memory bound to it. If `mip_level` is in
+    /// the mip tail, returns whether the mip tail has memory bound to it.
+    ///
+    /// Returns `None` if the image has no sparse memory requirements for `aspects`.
+    pub(crate) fn is_block_resident(
+        &self,
+        aspects: ImageAspects,
+        mip_level: u32,
+        array_layer: u32,
+        block: [u32; 3],
+    ) -> Option<bool> {
+        let aspect = self.aspect(aspects)?;
+
+        Some(match aspect.mip_levels.get(mip_level as usize) {
+            Some(&(block_count, first_block)) => {
+                let index = array_layer as DeviceSize * aspect.array_layer_block_count
+                    + first_block
+                    + (block[2] as DeviceSize * block_count[1] as DeviceSize
+                        + block[1] as DeviceSize)
+                        * block_count[0] as DeviceSize
+                    + block[0] as DeviceSize;
+
+                aspect.blocks.is_resident(index..index + 1)
+            }
+            None => self
+                .opaque
+                .is_resident(aspect.requirements.mip_tail_range(array_layer)),
+        })
+    }
+
+    /// Returns whether the mip tail region of `array_layer` has memory bound to it.
+    ///
+    /// Returns `None` if the image has no sparse memory requirements for `aspects`.
+    pub(crate) fn is_mip_tail_resident(
+        &self,
+        aspects: ImageAspects,
+        array_layer: u32,
+    ) -> Option<bool> {
+        self.aspect(aspects).map(|aspect| {
+            self.opaque
+                .is_resident(aspect.requirements.mip_tail_range(array_layer))
+        })
+    }
+
+    fn aspect(&self, aspects: ImageAspects) -> Option<&SparseImageAspectResidency> {
+        self.aspects.iter().find(|aspect| {
+            aspect
+                .requirements
+                .format_properties
+                .aspects
+                .contains(aspects)
+        })
+    }
+
+    fn aspect_mut(&mut self, aspects: ImageAspects) -> Option<&mut SparseImageAspectResidency> {
+        self.aspects.iter_mut().find(|aspect| {
+            aspect
+                .requirements
+                .format_properties
+                .aspects
+                .contains(aspects)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseResidency;
+    use crate::memory::{DeviceMemory, MemoryAllocateInfo};
+    use std::sync::Arc;
+
+    #[test]
+    fn residency_ranges() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory = Arc::new(
+            DeviceMemory::allocate(
+                device,
+                MemoryAllocateInfo {
+                    allocation_size: 1024,
+                    memory_type_index: 0,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        );
+
+        let mut residency = SparseResidency::default();
+        assert!(residency.is_resident(0..0));
+        assert!(!residency.is_resident(0..64));
+
+        residency.bind(0..64, Some(&memory));
+        residency.bind(64..128, Some(&memory));
+        residency.bind(192..256, Some(&memory));
+        assert!(residency.is_resident(0..128));
+        assert!(residency.is_resident(16..32));
+        assert!(!residency.is_resident(0..256));
+        assert!(residency.is_resident(192..256));
+
+        residency.bind(32..64, None);
+        assert!(!residency.is_resident(0..128));
+        assert!(residency.is_resident(0..32));
+        assert!(residency.is_resident(64..128));
+    }
+}
@@ -227,8 +227,14 @@ impl ComputePipeline {
             };
         }
 
+        let mut flags_vk = ash::vk::PipelineCreateFlags::from(flags);
+
+        if layout.descriptor_buffer() {
+            flags_vk |= ash::vk::PipelineCreateFlags::DESCRIPTOR_BUFFER_EXT;
+        }
+
         let create_infos_vk = ash::vk::ComputePipelineCreateInfo {
-            flags: flags.into(),
+            flags: flags_vk,
             stage: stage_vk,
             layout: layout.handle(),
             base_pipeline_handle: ash::vk::Pipeline::null(),
@@ -47,6 +47,11 @@
 //!    logical operations can be applied to combine incoming pixel data with data already present
 //!    in the framebuffer.
 //!
+//! Instead of steps 1 to 4, a graphics pipeline can also use mesh shading. In this case, there
+//! is no vertex input. An optional task shader decides how many mesh shader workgroups to launch,
+//! and the mesh shader invocations generate the vertices and primitives that are passed on to
+//! the rasterizer. Pipelines using mesh shading are executed with a `draw_mesh_tasks` command.
+//!
 //! A graphics pipeline contains many configuration options, which are grouped into collections of
 //! "state". Often, these directly correspond to one or more steps in the graphics pipeline. Each
 //! state collection has a dedicated submodule.
@@ -137,8 +142,8 @@ pub struct GraphicsPipeline {
     num_used_descriptor_sets: u32,
     fragment_tests_stages: Option<FragmentTestsStages>,
 
-    vertex_input_state: VertexInputState,
-    input_assembly_state: InputAssemblyState,
+    vertex_input_state: Option<VertexInputState>,
+    input_assembly_state: Option<InputAssemblyState>,
     tessellation_state: Option<TessellationState>,
     viewport_state: Option<ViewportState>,
     rasterization_state: RasterizationState,
@@ -202,6 +207,8 @@ impl GraphicsPipeline {
         let mut tessellation_control_stage = None;
         let mut tessellation_evaluation_stage = None;
         let mut geometry_stage = None;
+        let mut task_stage = None;
+        let mut mesh_stage = None;
         let mut fragment_stage = None;
 
         for (stage_index, stage) in stages.iter().enumerate() {
@@ -225,6 +232,8 @@ impl GraphicsPipeline {
                 ShaderStage::TessellationControl => &mut tessellation_control_stage,
                 ShaderStage::TessellationEvaluation => &mut tessellation_evaluation_stage,
                 ShaderStage::Geometry => &mut geometry_stage,
+                ShaderStage::Task => &mut task_stage,
+                ShaderStage::Mesh => &mut mesh_stage,
                 ShaderStage::Fragment => &mut fragment_stage,
                 _ => {
                     return Err(GraphicsPipelineCreationError::ShaderStageInvalid {
@@ -280,18 +289,44 @@ impl GraphicsPipeline {
                 .rasterizer_discard_enable
                 != StateMode::Fixed(true);
 
+        // VUID-VkGraphicsPipelineCreateInfo-pStages-02095
+        if stages_present.intersects(ShaderStages::TASK | ShaderStages::MESH)
+            && stages_present.intersects(
+                ShaderStages::VERTEX
+                    | ShaderStages::TESSELLATION_CONTROL
+                    | ShaderStages::TESSELLATION_EVALUATION
+                    | ShaderStages::GEOMETRY,
+            )
+        {
+            return Err(GraphicsPipelineCreationError::MeshAndPrimitiveShaderStagesMixed);
+        }
+
         // VUID-VkGraphicsPipelineCreateInfo-stage-02096
         // VUID-VkGraphicsPipelineCreateInfo-pStages-06895
-        match (vertex_stage.is_some(), need_pre_rasterization_shader_state) {
+        match (
+            vertex_stage.is_some() || mesh_stage.is_some(),
+            need_pre_rasterization_shader_state,
+        ) {
             (true, false) => {
                 return Err(GraphicsPipelineCreationError::ShaderStageUnused {
-                    stage: ShaderStage::Vertex,
+                    stage: if vertex_stage.is_some() {
+                        ShaderStage::Vertex
+                    } else {
+                        ShaderStage::Mesh
+                    },
                 })
             }
             (false, true) => return Err(GraphicsPipelineCreationError::VertexShaderStageMissing),
             _ => (),
         }
 
+        // VUID-VkGraphicsPipelineCreateInfo-pStages-06895
+        if task_stage.is_some() && !need_pre_rasterization_shader_state {
+            return Err(GraphicsPipelineCreationError::ShaderStageUnused {
+                stage: ShaderStage::Task,
+            });
+        }
+
         // VUID-VkGraphicsPipelineCreateInfo-pStages-06895
         match (
             tessellation_control_stage.is_some(),
@@ -604,6 +639,30 @@ impl GraphicsPipeline {
                     // VUID-VkPipelineShaderStageCreateInfo-stage-00715
                     // TODO:
                 }
+                ShaderStage::Task => {
+                    // VUID-VkPipelineShaderStageCreateInfo-stage-02092
+                    if !device.enabled_features().task_shader {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "`stages` contains a `Task` shader stage",
+                            requires_one_of: RequiresOneOf {
+                                features: &["task_shader"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+                }
+                ShaderStage::Mesh => {
+                    // VUID-VkPipelineShaderStageCreateInfo-stage-02091
+                    if !device.enabled_features().mesh_shader {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "`stages` contains a `Mesh` shader stage",
+                            requires_one_of: RequiresOneOf {
+                                features: &["mesh_shader"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+                }
                 ShaderStage::Fragment => {
                     fragment_stage = Some(stage);
 
@@ -657,6 +716,7 @@ impl GraphicsPipeline {
             tessellation_control_stage,
             tessellation_evaluation_stage,
             geometry_stage,
+            mesh_stage,
             fragment_stage,
         ]
         .into_iter()
@@ -1298,6 +1358,7 @@ impl GraphicsPipeline {
                 line_width,
                 line_rasterization_mode,
                 line_stipple,
+                rasterization_stream,
             } = rasterization_state;
 
             // VUID-VkPipelineRasterizationStateCreateInfo-polygonMode-parameter
@@ -1608,6 +1669,52 @@ impl GraphicsPipeline {
                 }
             }
 
+            if rasterization_stream != 0 {
+                if !device.enabled_extensions().ext_transform_feedback {
+                    return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                        required_for: "`rasterization_state.rasterization_stream` is not `0`",
+                        requires_one_of: RequiresOneOf {
+                            device_extensions: &["ext_transform_feedback"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkPipelineRasterizationStateStreamCreateInfoEXT-geometryStreams-02324
+                if !device.enabled_features().geometry_streams {
+                    return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                        required_for: "`rasterization_state.rasterization_stream` is not `0`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["geometry_streams"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                let max_transform_feedback_streams =
+                    properties.max_transform_feedback_streams.unwrap_or(0);
+
+                // VUID-VkPipelineRasterizationStateStreamCreateInfoEXT-rasterizationStream-02325
+                if rasterization_stream >= max_transform_feedback_streams {
+                    return Err(
+                        GraphicsPipelineCreationError::MaxTransformFeedbackStreamsExceeded {
+                            rasterization_stream,
+                            max: max_transform_feedback_streams,
+                        },
+                    );
+                }
+
+                // VUID-VkPipelineRasterizationStateStreamCreateInfoEXT-rasterizationStream-02326
+                if !properties
+                    .transform_feedback_rasterization_stream_select
+                    .unwrap_or(false)
+                {
+                    return Err(
+                        GraphicsPipelineCreationError::RasterizationStreamSelectNotSupported,
+                    );
+                }
+            }
+
             // TODO:
             // VUID-VkGraphicsPipelineCreateInfo-pStages-00740
             // VUID-VkGraphicsPipelineCreateInfo-renderPass-06049
@@ -2105,6 +2212,20 @@ impl GraphicsPipeline {
                                 },
                             });
                         }
+
+                        // VUID-VkGraphicsPipelineCreateInfo-renderPass-07064
+                        if stages_present.intersects(ShaderStages::MESH)
+                            && !device.enabled_features().multiview_mesh_shader
+                        {
+                            return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                                required_for: "`stages` contains a `Mesh` shader stage and \
+                                    `render_pass` has a subpass where `view_mask` is not `0`",
+                                requires_one_of: RequiresOneOf {
+                                    features: &["multiview_mesh_shader"],
+                                    ..Default::default()
+                                },
+                            });
+                        }
                     }
                 }
                 PipelineSubpassType::BeginRendering(rendering_info) => {
@@ -2268,6 +2389,32 @@ impl GraphicsPipeline {
                                 },
                             });
                         }
+
+                        if stages_present.intersects(ShaderStages::MESH) {
+                            // VUID-VkGraphicsPipelineCreateInfo-renderPass-07064
+                            if !device.enabled_features().multiview_mesh_shader {
+                                return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                                    required_for: "`stages` contains a `Mesh` shader stage and \
+                                        `render_pass` has a subpass where `view_mask` is not `0`",
+                                    requires_one_of: RequiresOneOf {
+                                        features: &["multiview_mesh_shader"],
+                                        ..Default::default()
+                                    },
+                                });
+                            }
+
+                            let max = properties.max_mesh_multiview_view_count.unwrap_or(0);
+
+                            // VUID-VkGraphicsPipelineCreateInfo-renderPass-07720
+                            if view_count > max {
+                                return Err(
+                                    GraphicsPipelineCreationError::MaxMeshMultiviewViewCountExceeded {
+                                        view_count,
+                                        max,
+                                    },
+                                );
+                            }
+                        }
                     }
                 }
             }
@@ -2945,6 +3092,7 @@ impl GraphicsPipeline {
 
         let mut rasterization_state_vk = None;
         let mut rasterization_line_state_vk = None;
+        let mut rasterization_stream_state_vk = None;
 
         if let Some(rasterization_state) = rasterization_state {
             let &RasterizationState {
@@ -2957,6 +3105,7 @@ impl GraphicsPipeline {
                 line_width,
                 line_rasterization_mode,
                 line_stipple,
+                rasterization_stream,
             } = rasterization_state;
 
             let rasterizer_discard_enable = match rasterizer_discard_enable {
@@ -3076,6 +3225,19 @@ impl GraphicsPipeline {
                     },
                 ) as *const _ as *const _;
             }
+
+            if device.enabled_extensions().ext_transform_feedback {
+                let rasterization_stream_state = rasterization_stream_state_vk.insert(
+                    ash::vk::PipelineRasterizationStateStreamCreateInfoEXT {
+                        flags: ash::vk::PipelineRasterizationStateStreamCreateFlagsEXT::empty(),
+                        rasterization_stream,
+                        ..Default::default()
+                    },
+                );
+
+                rasterization_stream_state.p_next = rasterization_state.p_next;
+                rasterization_state.p_next = rasterization_stream_state as *const _ as *const _;
+            }
         }
 
         let mut multisample_state_vk = None;
@@ -3494,8 +3656,14 @@ impl GraphicsPipeline {
             Create
         */
 
+        let mut flags_vk = ash::vk::PipelineCreateFlags::from(flags);
+
+        if layout.descriptor_buffer() {
+            flags_vk |= ash::vk::PipelineCreateFlags::DESCRIPTOR_BUFFER_EXT;
+        }
+
         let mut create_info_vk = ash::vk::GraphicsPipelineCreateInfo {
-            flags: flags.into(),
+            flags: flags_vk,
             stage_count: stages_vk.len() as u32,
             p_stages: stages_vk.as_ptr(),
             p_vertex_input_state: vertex_input_state_vk
@@ -4033,8 +4201,8 @@ impl GraphicsPipeline {
             num_used_descriptor_sets,
             fragment_tests_stages,
 
-            vertex_input_state: vertex_input_state.unwrap(), // Can be None if there's a mesh shader, but we don't support that yet
-            input_assembly_state: input_assembly_state.unwrap(), // Can be None if there's a mesh shader, but we don't support that yet
+            vertex_input_state,
+            input_assembly_state,
             tessellation_state,
             viewport_state,
             rasterization_state: rasterization_state.unwrap(), // Can be None for pipeline libraries, but we don't support that yet
@@ -4067,16 +4235,26 @@ impl GraphicsPipeline {
         self.shaders.get(&stage).copied()
     }
 
+    /// Returns the shader stages that the pipeline contains.
+    #[inline]
+    pub(crate) fn shader_stages(&self) -> ShaderStages {
+        self.shaders.keys().copied().collect()
+    }
+
     /// Returns the vertex input state used to create this pipeline.
+    ///
+    /// `None` is returned if the pipeline uses a mesh shader.
     #[inline]
-    pub fn vertex_input_state(&self) -> &VertexInputState {
-        &self.vertex_input_state
+    pub fn vertex_input_state(&self) -> Option<&VertexInputState> {
+        self.vertex_input_state.as_ref()
     }
 
     /// Returns the input assembly state used to create this pipeline.
+    ///
+    /// `None` is returned if the pipeline uses a mesh shader.
     #[inline]
-    pub fn input_assembly_state(&self) -> &InputAssemblyState {
-        &self.input_assembly_state
+    pub fn input_assembly_state(&self) -> Option<&InputAssemblyState> {
+        self.input_assembly_state.as_ref()
     }
 
     /// Returns the tessellation state used to create this pipeline.
@@ -4217,21 +4395,25 @@ pub struct GraphicsPipelineCreateInfo {
 
     /// The shader stages to use.
     ///
-    /// A vertex shader must always be included. Other stages are optional.
+    /// Either a vertex shader or a mesh shader must always be included. Other stages are
+    /// optional, but stages of the mesh shading pipeline (task and mesh shaders) can't be mixed
+    /// with stages of the primitive shading pipeline (vertex, tessellation and geometry shaders).
     ///
     /// The default value is empty.
     pub stages: SmallVec<[PipelineShaderStageCreateInfo; 5]>,
 
     /// The vertex input state.
     ///
-    /// This state is always used, and must be provided.
+    /// This state must be provided if `stages` contains a vertex shader, and must be `None`
+    /// otherwise.
     ///
     /// The default value is `None`.
     pub vertex_input_state: Option<VertexInputState>,
 
     /// The input assembly state.
     ///
-    /// This state is always used, and must be provided.
+    /// This state must be provided if `stages` contains a vertex shader, and must be `None`
+    /// otherwise.
     ///
     /// The default value is `None`.
     pub input_assembly_state: Option<InputAssemblyState>,
@@ -4375,9 +4557,16 @@ pub enum GraphicsPipelineCreationError {
         obtained: u32,
     },
 
+    /// The highest view index in a view mask of a pipeline with a mesh shader exceeds the
+    /// `max_mesh_multiview_view_count` limit.
+    MaxMeshMultiviewViewCountExceeded { view_count: u32, max: u32 },
+
     /// The `max_multiview_view_count` limit has been exceeded.
     MaxMultiviewViewCountExceeded { view_count: u32, max: u32 },
 
+    /// The rasterization stream is not less than the `max_transform_feedback_streams` limit.
+    MaxTransformFeedbackStreamsExceeded { rasterization_stream: u32, max: u32 },
+
     /// The maximum value for the instance rate divisor has been exceeded.
     MaxVertexAttribDivisorExceeded {
         /// Index of the faulty binding.
@@ -4445,6 +4634,10 @@ pub enum GraphicsPipelineCreationError {
     /// The maximum dimensions of viewports has been exceeded.
     MaxViewportDimensionsExceeded,
 
+    /// Shader stages from both the mesh shading pipeline (task and mesh shaders) and the primitive
+    /// shading pipeline (vertex, tessellation and geometry shaders) were provided.
+    MeshAndPrimitiveShaderStagesMixed,
+
     /// The number of attachments specified in the blending does not match the number of
     /// attachments in the subpass.
     MismatchBlendingAttachmentsCount,
@@ -4467,6 +4660,11 @@ pub enum GraphicsPipelineCreationError {
     /// Only one tessellation shader stage was provided, the other was not.
     OtherTessellationShaderStageMissing,
 
+    /// The rasterization stream is not `0`, but the
+    /// [`transform_feedback_rasterization_stream_select`](crate::device::Properties::transform_feedback_rasterization_stream_select)
+    /// device property was `false`.
+    RasterizationStreamSelectNotSupported,
+
     /// The value provided for a shader specialization constant has a
     /// different type than the constant's default value.
     ShaderSpecializationConstantTypeMismatch {
@@ -4532,7 +4730,7 @@ pub enum GraphicsPipelineCreationError {
     /// The format specified by a vertex input attribute is not supported for vertex buffers.
     VertexInputAttributeUnsupportedFormat { location: u32, format: Format },
 
-    /// No vertex shader stage was provided.
+    /// No vertex or mesh shader stage was provided.
     VertexShaderStageMissing,
 
     /// The minimum or maximum bounds of viewports have been exceeded.
@@ -4610,9 +4808,21 @@ impl Display for GraphicsPipelineCreationError {
                 f,
                 "the maximum number of discard rectangles has been exceeded",
             ),
+            Self::MaxMeshMultiviewViewCountExceeded { .. } => {
+                write!(f, "the `max_mesh_multiview_view_count` limit has been exceeded")
+            }
             Self::MaxMultiviewViewCountExceeded { .. } => {
                 write!(f, "the `max_multiview_view_count` limit has been exceeded")
             }
+            Self::MaxTransformFeedbackStreamsExceeded {
+                rasterization_stream,
+                max,
+            } => write!(
+                f,
+                "the rasterization stream ({}) is not less than the \
+                `max_transform_feedback_streams` limit ({})",
+                rasterization_stream, max,
+            ),
             Self::MaxVertexAttribDivisorExceeded { .. } => write!(
                 f,
                 "the maximum value for the instance rate divisor has been exceeded",
@@ -4643,6 +4853,11 @@ impl Display for GraphicsPipelineCreationError {
                 f,
                 "the `min_vertex_input_binding_stride_alignment` limit has been exceeded",
             ),
+            Self::MeshAndPrimitiveShaderStagesMixed => write!(
+                f,
+                "shader stages from both the mesh shading pipeline and the primitive shading \
+                pipeline were provided",
+            ),
             Self::MismatchBlendingAttachmentsCount => write!(
                 f,
                 "the number of attachments specified in the blending does not match the number of \
@@ -4666,6 +4881,11 @@ impl Display for GraphicsPipelineCreationError {
                 f,
                 "only one tessellation shader stage was provided, the other was not",
             ),
+            Self::RasterizationStreamSelectNotSupported => write!(
+                f,
+                "the rasterization stream is not 0, but the \
+                transform_feedback_rasterization_stream_select device property was false",
+            ),
             Self::ShaderSpecializationConstantTypeMismatch {
                 stage_index,
                 constant_id,
@@ -4761,7 +4981,7 @@ impl Display for GraphicsPipelineCreationError {
             ),
             Self::VertexShaderStageMissing => write!(
                 f,
-                "no vertex shader stage was provided",
+                "no vertex or mesh shader stage was provided",
             ),
             Self::ViewportBoundsExceeded => write!(
                 f,
@@ -4803,3 +5023,167 @@ impl From<RequirementNotMet> for GraphicsPipelineCreationError {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{
+        input_assembly::InputAssemblyState, rasterization::RasterizationState,
+        vertex_input::VertexInputState, GraphicsPipeline, GraphicsPipelineCreateInfo,
+        GraphicsPipelineCreationError,
+    };
+    use crate::{
+        device::DeviceOwned,
+        pipeline::{layout::PipelineLayoutCreateInfo, PipelineLayout, StateMode},
+        render_pass::{RenderPass, Subpass},
+        shader::{PipelineShaderStageCreateInfo, ShaderModule, ShaderStage},
+    };
+
+    /*
+    #version 460
+    #extension GL_EXT_mesh_shader : require
+
+    layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+    void main() {
+        EmitMeshTasksEXT(0, 0, 0);
+    }
+    */
+    pub(crate) const TASK_MODULE: [u8; 208] = [
+        3, 2, 35, 7, 0, 4, 1, 0, 0, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0, 17, 0, 2, 0, 163, 20, 0, 0,
+        10, 0, 6, 0, 83, 80, 86, 95, 69, 88, 84, 95, 109, 101, 115, 104, 95, 115, 104, 97, 100,
+        101, 114, 0, 14, 0, 3, 0, 0, 0, 0, 0, 1, 0, 0, 0, 15, 0, 5, 0, 244, 20, 0, 0, 1, 0, 0, 0,
+        109, 97, 105, 110, 0, 0, 0, 0, 16, 0, 6, 0, 1, 0, 0, 0, 17, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0,
+        0, 1, 0, 0, 0, 19, 0, 2, 0, 2, 0, 0, 0, 33, 0, 3, 0, 3, 0, 0, 0, 2, 0, 0, 0, 21, 0, 4, 0,
+        4, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 43, 0, 4, 0, 4, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0, 54,
+        0, 5, 0, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 248, 0, 2, 0, 6, 0, 0, 0, 174, 20,
+        4, 0, 5, 0, 0, 0, 5, 0, 0, 0, 5, 0, 0, 0, 56, 0, 1, 0,
+    ];
+
+    /*
+    #version 460
+    #extension GL_EXT_mesh_shader : require
+
+    layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+    layout(triangles, max_vertices = 3, max_primitives = 1) out;
+
+    void main() {
+        SetMeshOutputsEXT(0, 0);
+    }
+    */
+    pub(crate) const MESH_MODULE: [u8; 252] = [
+        3, 2, 35, 7, 0, 4, 1, 0, 0, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0, 17, 0, 2, 0, 163, 20, 0, 0,
+        10, 0, 6, 0, 83, 80, 86, 95, 69, 88, 84, 95, 109, 101, 115, 104, 95, 115, 104, 97, 100,
+        101, 114, 0, 14, 0, 3, 0, 0, 0, 0, 0, 1, 0, 0, 0, 15, 0, 5, 0, 245, 20, 0, 0, 1, 0, 0, 0,
+        109, 97, 105, 110, 0, 0, 0, 0, 16, 0, 6, 0, 1, 0, 0, 0, 17, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0,
+        0, 1, 0, 0, 0, 16, 0, 4, 0, 1, 0, 0, 0, 26, 0, 0, 0, 3, 0, 0, 0, 16, 0, 4, 0, 1, 0, 0, 0,
+        150, 20, 0, 0, 1, 0, 0, 0, 16, 0, 3, 0, 1, 0, 0, 0, 178, 20, 0, 0, 19, 0, 2, 0, 2, 0, 0, 0,
+        33, 0, 3, 0, 3, 0, 0, 0, 2, 0, 0, 0, 21, 0, 4, 0, 4, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 43,
+        0, 4, 0, 4, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0, 54, 0, 5, 0, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0,
+        0, 3, 0, 0, 0, 248, 0, 2, 0, 6, 0, 0, 0, 175, 20, 3, 0, 5, 0, 0, 0, 5, 0, 0, 0, 253, 0, 1,
+        0, 56, 0, 1, 0,
+    ];
+
+    /*
+    #version 450
+
+    void main() {}
+    */
+    pub(crate) const VERTEX_MODULE: [u8; 116] = [
+        3, 2, 35, 7, 0, 0, 1, 0, 0, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0, 17, 0, 2, 0, 1, 0, 0, 0, 14,
+        0, 3, 0, 0, 0, 0, 0, 1, 0, 0, 0, 15, 0, 5, 0, 0, 0, 0, 0, 1, 0, 0, 0, 109, 97, 105, 110, 0,
+        0, 0, 0, 19, 0, 2, 0, 2, 0, 0, 0, 33, 0, 3, 0, 3, 0, 0, 0, 2, 0, 0, 0, 54, 0, 5, 0, 2, 0,
+        0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 248, 0, 2, 0, 4, 0, 0, 0, 253, 0, 1, 0, 56, 0, 1,
+        0,
+    ];
+
+    /// Returns the create info of a pipeline that discards all primitives, with a shader stage
+    /// for each module in `modules`.
+    pub(crate) fn discard_pipeline_create_info(
+        subpass: Subpass,
+        modules: &[&[u8]],
+    ) -> GraphicsPipelineCreateInfo {
+        let device = subpass.render_pass().device().clone();
+        let stages = modules
+            .iter()
+            .map(|bytes| {
+                let module = unsafe { ShaderModule::from_bytes(device.clone(), bytes).unwrap() };
+                PipelineShaderStageCreateInfo::entry_point(module.entry_point("main").unwrap())
+            })
+            .collect();
+        let layout = PipelineLayout::new(device, PipelineLayoutCreateInfo::default()).unwrap();
+
+        GraphicsPipelineCreateInfo {
+            stages,
+            rasterization_state: Some(RasterizationState {
+                rasterizer_discard_enable: StateMode::Fixed(true),
+                ..Default::default()
+            }),
+            subpass: Some(subpass.into()),
+            ..GraphicsPipelineCreateInfo::layout(layout)
+        }
+    }
+
+    #[test]
+    fn mesh_pipeline_without_vertex_input() {
+        let (device, _queue) = gfx_dev_and_queue!(extensions: [ext_mesh_shader, khr_spirv_1_4]; mesh_shader, task_shader);
+
+        let render_pass = RenderPass::empty_single_pass(device.clone()).unwrap();
+        let subpass = Subpass::from(render_pass, 0).unwrap();
+        let pipeline = GraphicsPipeline::new(
+            device,
+            None,
+            discard_pipeline_create_info(subpass, &[&TASK_MODULE, &MESH_MODULE]),
+        )
+        .unwrap();
+
+        assert!(pipeline.shader(ShaderStage::Task).is_some());
+        assert!(pipeline.shader(ShaderStage::Mesh).is_some());
+        assert!(pipeline.vertex_input_state().is_none());
+        assert!(pipeline.input_assembly_state().is_none());
+    }
+
+    #[test]
+    fn mesh_pipeline_with_input_assembly_state() {
+        let (device, _queue) =
+            gfx_dev_and_queue!(extensions: [ext_mesh_shader, khr_spirv_1_4]; mesh_shader);
+
+        let render_pass = RenderPass::empty_single_pass(device.clone()).unwrap();
+        let subpass = Subpass::from(render_pass, 0).unwrap();
+
+        match GraphicsPipeline::new(
+            device,
+            None,
+            GraphicsPipelineCreateInfo {
+                input_assembly_state: Some(InputAssemblyState::new()),
+                ..discard_pipeline_create_info(subpass, &[&MESH_MODULE])
+            },
+        ) {
+            Err(GraphicsPipelineCreationError::StateUnused {
+                state: "input_assembly_state",
+            }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn mesh_and_vertex_stages_mixed() {
+        let (device, _queue) =
+            gfx_dev_and_queue!(extensions: [ext_mesh_shader, khr_spirv_1_4]; mesh_shader);
+
+        let render_pass = RenderPass::empty_single_pass(device.clone()).unwrap();
+        let subpass = Subpass::from(render_pass, 0).unwrap();
+
+        match GraphicsPipeline::new(
+            device,
+            None,
+            GraphicsPipelineCreateInfo {
+                vertex_input_state: Some(VertexInputState::new()),
+                input_assembly_state: Some(InputAssemblyState::new()),
+                ..discard_pipeline_create_info(subpass, &[&VERTEX_MODULE, &MESH_MODULE])
+            },
+        ) {
+            Err(GraphicsPipelineCreationError::MeshAndPrimitiveShaderStagesMixed) => (),
+            _ => panic!(),
+        }
+    }
+}
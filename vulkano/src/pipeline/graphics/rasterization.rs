@@ -78,12 +78,23 @@ pub struct RasterizationState {
     /// [`ext_line_rasterization`](crate::device::DeviceExtensions::ext_line_rasterization)
     /// extension and an additional feature must be enabled on the device.
     pub line_stipple: Option<StateMode<LineStipple>>,
+
+    /// The vertex stream that is rasterized, if the geometry shader emits vertices to multiple
+    /// streams.
+    ///
+    /// If this is not `0`, the
+    /// [`ext_transform_feedback`](crate::device::DeviceExtensions::ext_transform_feedback)
+    /// extension and the [`geometry_streams`](crate::device::Features::geometry_streams) feature
+    /// must be enabled on the device, and the
+    /// [`transform_feedback_rasterization_stream_select`](crate::device::Properties::transform_feedback_rasterization_stream_select)
+    /// device property must be `true`.
+    pub rasterization_stream: u32,
 }
 
 impl RasterizationState {
     /// Creates a `RasterizationState` with depth clamping, discard, depth biasing and line
-    /// stippling disabled, filled polygons, no culling, counterclockwise front face, and the
-    /// default line width and line rasterization mode.
+    /// stippling disabled, filled polygons, no culling, counterclockwise front face, the
+    /// default line width and line rasterization mode, and rasterization of vertex stream 0.
     #[inline]
     pub fn new() -> Self {
         Self {
@@ -96,6 +107,7 @@ impl RasterizationState {
             line_width: StateMode::Fixed(1.0),
             line_rasterization_mode: Default::default(),
             line_stipple: None,
+            rasterization_stream: 0,
         }
     }
 
@@ -150,11 +150,20 @@ impl PipelineLayout {
             let mut num_sampled_images = Counter::default();
             let mut num_storage_images = Counter::default();
             let mut num_input_attachments = Counter::default();
+            let mut num_acceleration_structures = Counter::default();
             let mut push_descriptor_set = None;
+            let descriptor_buffer = set_layouts
+                .first()
+                .map_or(false, |set_layout| set_layout.descriptor_buffer());
 
             for (set_num, set_layout) in set_layouts.iter().enumerate() {
                 let set_num = set_num as u32;
 
+                // VUID-VkPipelineLayoutCreateInfo-pSetLayouts-08008
+                if set_layout.descriptor_buffer() != descriptor_buffer {
+                    return Err(PipelineLayoutCreationError::SetLayoutsDescriptorBufferMismatch);
+                }
+
                 if set_layout.push_descriptor() {
                     // VUID-VkPipelineLayoutCreateInfo-pSetLayouts-00293
                     if push_descriptor_set.is_some() {
@@ -208,6 +217,10 @@ impl PipelineLayout {
                             num_input_attachments
                                 .increment(layout_binding.descriptor_count, layout_binding.stages);
                         }
+                        DescriptorType::AccelerationStructure => {
+                            num_acceleration_structures
+                                .increment(layout_binding.descriptor_count, layout_binding.stages);
+                        }
                     }
                 }
             }
@@ -368,6 +381,38 @@ impl PipelineLayout {
                     },
                 );
             }
+
+            // VUID-VkPipelineLayoutCreateInfo-descriptorType-03571
+            if num_acceleration_structures.max_per_stage()
+                > properties
+                    .max_per_stage_descriptor_acceleration_structures
+                    .unwrap_or(0)
+            {
+                return Err(
+                    PipelineLayoutCreationError::MaxPerStageDescriptorAccelerationStructuresExceeded {
+                        provided: num_acceleration_structures.max_per_stage(),
+                        max_supported: properties
+                            .max_per_stage_descriptor_acceleration_structures
+                            .unwrap_or(0),
+                    },
+                );
+            }
+
+            // VUID-VkPipelineLayoutCreateInfo-descriptorType-03573
+            if num_acceleration_structures.total
+                > properties
+                    .max_descriptor_set_acceleration_structures
+                    .unwrap_or(0)
+            {
+                return Err(
+                    PipelineLayoutCreationError::MaxDescriptorSetAccelerationStructuresExceeded {
+                        provided: num_acceleration_structures.total,
+                        max_supported: properties
+                            .max_descriptor_set_acceleration_structures
+                            .unwrap_or(0),
+                    },
+                );
+            }
         }
 
         /* Check push constant ranges */
@@ -543,6 +588,15 @@ impl PipelineLayout {
         &self.set_layouts
     }
 
+    /// Returns whether the descriptor set layouts of this pipeline layout are for descriptor sets
+    /// that are stored in descriptor buffers.
+    #[inline]
+    pub fn descriptor_buffer(&self) -> bool {
+        self.set_layouts
+            .first()
+            .map_or(false, |set_layout| set_layout.descriptor_buffer())
+    }
+
     /// Returns a slice containing the push constant ranges this pipeline layout was created from.
     ///
     /// The ranges are guaranteed to be sorted deterministically by offset, size, then stages.
@@ -736,6 +790,12 @@ pub enum PipelineLayoutCreationError {
     /// limit.
     MaxDescriptorSetInputAttachmentsExceeded { provided: u32, max_supported: u32 },
 
+    /// The `set_layouts` contain more [`DescriptorType::AccelerationStructure`] descriptors than
+    /// the
+    /// [`max_descriptor_set_acceleration_structures`](crate::device::Properties::max_descriptor_set_acceleration_structures)
+    /// limit.
+    MaxDescriptorSetAccelerationStructuresExceeded { provided: u32, max_supported: u32 },
+
     /// The `set_layouts` contain more bound resources in a single stage than the
     /// [`max_per_stage_resources`](crate::device::Properties::max_per_stage_resources)
     /// limit.
@@ -778,6 +838,12 @@ pub enum PipelineLayoutCreationError {
     /// limit.
     MaxPerStageDescriptorInputAttachmentsExceeded { provided: u32, max_supported: u32 },
 
+    /// The `set_layouts` contain more [`DescriptorType::AccelerationStructure`] descriptors in a
+    /// single stage than the
+    /// [`max_per_stage_descriptor_acceleration_structures`](crate::device::Properties::max_per_stage_descriptor_acceleration_structures)
+    /// limit.
+    MaxPerStageDescriptorAccelerationStructuresExceeded { provided: u32, max_supported: u32 },
+
     /// An element in `push_constant_ranges` has an `offset + size` greater than the
     /// [`max_push_constants_size`](crate::device::Properties::max_push_constants_size) limit.
     MaxPushConstantsSizeExceeded { provided: u32, max_supported: u32 },
@@ -785,6 +851,9 @@ pub enum PipelineLayoutCreationError {
     /// A shader stage appears in multiple elements of `push_constant_ranges`.
     PushConstantRangesStageMultiple,
 
+    /// Some elements of `set_layouts` have `descriptor_buffer` enabled, but not all of them.
+    SetLayoutsDescriptorBufferMismatch,
+
     /// Multiple elements of `set_layouts` have `push_descriptor` enabled.
     SetLayoutsPushDescriptorMultiple,
 }
@@ -894,6 +963,15 @@ impl Display for PipelineLayoutCreationError {
                 than the `max_descriptor_set_input_attachments` limit ({})",
                 provided, max_supported,
             ),
+            Self::MaxDescriptorSetAccelerationStructuresExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::AccelerationStructure` \
+                descriptors ({}) than the `max_descriptor_set_acceleration_structures` limit ({})",
+                provided, max_supported,
+            ),
             Self::MaxPerStageResourcesExceeded {
                 provided,
                 max_supported,
@@ -964,6 +1042,16 @@ impl Display for PipelineLayoutCreationError {
                 ({})",
                 provided, max_supported,
             ),
+            Self::MaxPerStageDescriptorAccelerationStructuresExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::AccelerationStructure` \
+                descriptors ({}) in a single stage than the \
+                `max_per_stage_descriptor_acceleration_structures` limit ({})",
+                provided, max_supported,
+            ),
             Self::MaxPushConstantsSizeExceeded {
                 provided,
                 max_supported,
@@ -977,6 +1065,11 @@ impl Display for PipelineLayoutCreationError {
                 f,
                 "a shader stage appears in multiple elements of `push_constant_ranges`",
             ),
+            Self::SetLayoutsDescriptorBufferMismatch => write!(
+                f,
+                "some elements of `set_layouts` have `descriptor_buffer` enabled, but not all of \
+                them",
+            ),
             Self::SetLayoutsPushDescriptorMultiple => write!(
                 f,
                 "multiple elements of `set_layouts` have `push_descriptor` enabled",
@@ -17,7 +17,10 @@
 //! the CPU). Consequently it is a CPU-intensive operation that should be performed at
 //! initialization or during a loading screen.
 
-pub use self::{compute::ComputePipeline, graphics::GraphicsPipeline, layout::PipelineLayout};
+pub use self::{
+    compute::ComputePipeline, graphics::GraphicsPipeline, layout::PipelineLayout,
+    ray_tracing::RayTracingPipeline,
+};
 use crate::{
     device::DeviceOwned,
     macros::{vulkan_bitflags, vulkan_enum},
@@ -30,6 +33,7 @@ pub mod cache;
 pub mod compute;
 pub mod graphics;
 pub mod layout;
+pub mod ray_tracing;
 
 /// A trait for operations shared between pipeline types.
 pub trait Pipeline: DeviceOwned {
@@ -66,11 +70,10 @@ vulkan_enum! {
     // TODO: document
     Graphics = GRAPHICS,
 
-    /* TODO: enable
-    // TODO: document
+    /// The bind point of ray tracing pipelines.
     RayTracing = RAY_TRACING_KHR {
         device_extensions: [khr_ray_tracing_pipeline, nv_ray_tracing],
-    },*/
+    },
 
     /* TODO: enable
     // TODO: document
@@ -137,41 +140,35 @@ vulkan_bitflags! {
         // Provided by VK_KHR_dynamic_rendering with VK_EXT_fragment_density_map
     },*/
 
-    /* TODO: enable
-    // TODO: document
+    /// Any hit shaders in the shader binding table must not be null.
     RAY_TRACING_NO_NULL_ANY_HIT_SHADERS = RAY_TRACING_NO_NULL_ANY_HIT_SHADERS_KHR {
         device_extensions: [khr_ray_tracing_pipeline],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// Closest hit shaders in the shader binding table must not be null.
     RAY_TRACING_NO_NULL_CLOSEST_HIT_SHADERS = RAY_TRACING_NO_NULL_CLOSEST_HIT_SHADERS_KHR {
         device_extensions: [khr_ray_tracing_pipeline],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// Miss shaders in the shader binding table must not be null.
     RAY_TRACING_NO_NULL_MISS_SHADERS = RAY_TRACING_NO_NULL_MISS_SHADERS_KHR {
         device_extensions: [khr_ray_tracing_pipeline],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// Intersection shaders in the shader binding table must not be null.
     RAY_TRACING_NO_NULL_INTERSECTION_SHADERS = RAY_TRACING_NO_NULL_INTERSECTION_SHADERS_KHR {
         device_extensions: [khr_ray_tracing_pipeline],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// Triangle primitives are skipped during traversal.
     RAY_TRACING_SKIP_TRIANGLES = RAY_TRACING_SKIP_TRIANGLES_KHR {
         device_extensions: [khr_ray_tracing_pipeline],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// AABB primitives are skipped during traversal.
     RAY_TRACING_SKIP_AABBS = RAY_TRACING_SKIP_AABBS_KHR {
         device_extensions: [khr_ray_tracing_pipeline],
-    },*/
+    },
 
     /* TODO: enable
     // TODO: document
@@ -203,17 +200,21 @@ vulkan_bitflags! {
         device_extensions: [nv_device_generated_commands],
     },*/
 
-    /* TODO: enable
-    // TODO: document
+    /// The pipeline is a pipeline library, which can not be bound by itself, but can be linked
+    /// into other pipelines.
     LIBRARY = LIBRARY_KHR {
         device_extensions: [khr_pipeline_library],
-    },*/
+    },
 
-    /* TODO: enable
-    // TODO: document
+    /// The pipeline will be used with descriptor sets that are stored in descriptor buffers.
+    ///
+    /// This flag is added automatically when creating a pipeline whose layout uses descriptor set
+    /// layouts with [`descriptor_buffer`] enabled.
+    ///
+    /// [`descriptor_buffer`]: crate::descriptor_set::layout::DescriptorSetLayoutCreateInfo::descriptor_buffer
     DESCRIPTOR_BUFFER = DESCRIPTOR_BUFFER_EXT {
         device_extensions: [ext_descriptor_buffer],
-    },*/
+    },
 
     /* TODO: enable
     // TODO: document
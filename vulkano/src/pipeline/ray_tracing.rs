@@ -0,0 +1,1347 @@
+// Copyright (c) 2023 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A pipeline that traces rays through acceleration structures.
+//!
+//! A ray tracing pipeline consists of a collection of ray tracing shaders, which are organized
+//! into *shader groups*. There are three kinds of shader group:
+//!
+//! - A *general* group contains a single ray generation, miss or callable shader.
+//! - A *triangles hit* group contains an optional closest hit shader and an optional any hit
+//!   shader, which are invoked when a ray intersects a triangle geometry.
+//! - A *procedural hit* group contains an intersection shader, and an optional closest hit and
+//!   any hit shader, which are invoked when a ray intersects an AABB geometry.
+//!
+//! Which shader group is invoked for a given ray is determined by the *shader binding table*,
+//! a buffer containing handles of the groups of the pipeline. The shader binding table is passed
+//! to the `trace_rays` command when tracing rays. The [`ShaderBindingTable`] type can create a
+//! shader binding table that contains every group in a pipeline.
+//!
+//! Ray tracing pipelines can also be created as a *pipeline library*, by specifying
+//! [`PipelineCreateFlags::LIBRARY`]. A pipeline library cannot be bound by itself, but its
+//! shaders and groups can be linked into other ray tracing pipelines, by adding it to the
+//! `libraries` of their create info.
+
+use super::PipelineCreateFlags;
+use crate::{
+    buffer::{Buffer, BufferCreateInfo, BufferError, BufferUsage, Subbuffer},
+    device::{Device, DeviceOwned},
+    macros::impl_id_counter,
+    memory::{
+        allocator::{align_up, AllocationCreateInfo, MemoryAllocator, MemoryUsage},
+        DeviceAlignment,
+    },
+    pipeline::{
+        cache::PipelineCache,
+        layout::{PipelineLayout, PipelineLayoutSupersetError},
+        Pipeline, PipelineBindPoint,
+    },
+    shader::{
+        DescriptorBindingRequirements, PipelineShaderStageCreateInfo, ShaderStage,
+        SpecializationConstant,
+    },
+    DeviceSize, OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
+};
+use ahash::HashMap;
+use smallvec::SmallVec;
+use std::{
+    collections::hash_map::Entry,
+    error::Error,
+    ffi::CString,
+    fmt::{Debug, Display, Error as FmtError, Formatter},
+    mem::MaybeUninit,
+    num::NonZeroU64,
+    ptr,
+    sync::Arc,
+};
+
+/// A pipeline object that describes to the Vulkan implementation how it should perform ray
+/// tracing operations.
+pub struct RayTracingPipeline {
+    handle: ash::vk::Pipeline,
+    device: Arc<Device>,
+    id: NonZeroU64,
+
+    flags: PipelineCreateFlags,
+    groups: SmallVec<[RayTracingShaderGroupCreateInfo; 5]>,
+    group_kinds: Vec<ShaderGroupKind>,
+    max_pipeline_ray_recursion_depth: u32,
+    layout: Arc<PipelineLayout>,
+    descriptor_binding_requirements: HashMap<(u32, u32), DescriptorBindingRequirements>,
+    num_used_descriptor_sets: u32,
+}
+
+impl RayTracingPipeline {
+    /// Creates a new `RayTracingPipeline`.
+    #[inline]
+    pub fn new(
+        device: Arc<Device>,
+        cache: Option<Arc<PipelineCache>>,
+        create_info: RayTracingPipelineCreateInfo,
+    ) -> Result<Arc<RayTracingPipeline>, RayTracingPipelineCreationError> {
+        Self::validate_new(&device, cache.as_ref().map(AsRef::as_ref), &create_info)?;
+
+        unsafe { Ok(Self::new_unchecked(device, cache, create_info)?) }
+    }
+
+    fn validate_new(
+        device: &Device,
+        cache: Option<&PipelineCache>,
+        create_info: &RayTracingPipelineCreateInfo,
+    ) -> Result<(), RayTracingPipelineCreationError> {
+        // VUID-vkCreateRayTracingPipelinesKHR-rayTracingPipeline-03586
+        if !device.enabled_features().ray_tracing_pipeline {
+            return Err(RayTracingPipelineCreationError::RequirementNotMet {
+                required_for: "`RayTracingPipeline::new`",
+                requires_one_of: RequiresOneOf {
+                    features: &["ray_tracing_pipeline"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCreateRayTracingPipelinesKHR-pipelineCache-parent
+        if let Some(cache) = &cache {
+            assert_eq!(device, cache.device().as_ref());
+        }
+
+        let &RayTracingPipelineCreateInfo {
+            flags,
+            ref stages,
+            ref groups,
+            max_pipeline_ray_recursion_depth,
+            ref libraries,
+            ref library_interface,
+            ref layout,
+            _ne: _,
+        } = create_info;
+
+        let properties = device.physical_device().properties();
+
+        // VUID-VkRayTracingPipelineCreateInfoKHR-flags-parameter
+        flags.validate_device(device)?;
+
+        // VUID-VkRayTracingPipelineCreateInfoKHR-commonparent
+        assert_eq!(device, layout.device().as_ref());
+
+        // VUID-VkRayTracingPipelineCreateInfoKHR-maxPipelineRayRecursionDepth-03589
+        let max_ray_recursion_depth = properties.max_ray_recursion_depth.unwrap_or(0);
+
+        if max_pipeline_ray_recursion_depth > max_ray_recursion_depth {
+            return Err(
+                RayTracingPipelineCreationError::MaxRayRecursionDepthExceeded {
+                    provided: max_pipeline_ray_recursion_depth,
+                    max: max_ray_recursion_depth,
+                },
+            );
+        }
+
+        let mut has_raygen_stage = false;
+
+        for (stage_index, stage) in stages.iter().enumerate() {
+            let &PipelineShaderStageCreateInfo {
+                flags,
+                ref entry_point,
+                ref specialization_info,
+                _ne: _,
+            } = stage;
+
+            // VUID-VkPipelineShaderStageCreateInfo-flags-parameter
+            flags.validate_device(device)?;
+
+            let entry_point_info = entry_point.info();
+            let stage_enum = ShaderStage::from(&entry_point_info.execution);
+
+            // VUID-VkRayTracingPipelineCreateInfoKHR-stage-06899
+            // VUID-VkPipelineShaderStageCreateInfo-stage-parameter
+            match stage_enum {
+                ShaderStage::Raygen => has_raygen_stage = true,
+                ShaderStage::AnyHit
+                | ShaderStage::ClosestHit
+                | ShaderStage::Miss
+                | ShaderStage::Intersection
+                | ShaderStage::Callable => (),
+                _ => {
+                    return Err(RayTracingPipelineCreationError::ShaderStageInvalid {
+                        stage_index,
+                        stage: stage_enum,
+                    })
+                }
+            }
+
+            for (&constant_id, provided_value) in specialization_info {
+                // Per `VkSpecializationMapEntry` spec:
+                // "If a constantID value is not a specialization constant ID used in the shader,
+                // that map entry does not affect the behavior of the pipeline."
+                // We *may* want to be stricter than this for the sake of catching user errors?
+                if let Some(default_value) =
+                    entry_point_info.specialization_constants.get(&constant_id)
+                {
+                    // VUID-VkSpecializationMapEntry-constantID-00776
+                    // Check for equal types rather than only equal size.
+                    if !provided_value.eq_type(default_value) {
+                        return Err(
+                            RayTracingPipelineCreationError::ShaderSpecializationConstantTypeMismatch {
+                                stage_index,
+                                constant_id,
+                                default_value: *default_value,
+                                provided_value: *provided_value,
+                            },
+                        );
+                    }
+                }
+            }
+
+            // VUID-VkRayTracingPipelineCreateInfoKHR-layout-03427
+            // VUID-VkRayTracingPipelineCreateInfoKHR-layout-03428
+            layout.ensure_compatible_with_shader(
+                entry_point_info
+                    .descriptor_binding_requirements
+                    .iter()
+                    .map(|(k, v)| (*k, v)),
+                entry_point_info.push_constant_requirements.as_ref(),
+            )?;
+        }
+
+        for (group_index, group) in groups.iter().enumerate() {
+            let group_index = group_index as u32;
+
+            // Returns the stage of the shader at `shader_index`, if it is in range.
+            let shader_stage = |shader_index: u32| {
+                stages
+                    .get(shader_index as usize)
+                    .map(|stage| ShaderStage::from(&stage.entry_point.info().execution))
+                    .ok_or(
+                        RayTracingPipelineCreationError::ShaderGroupShaderIndexOutOfRange {
+                            group_index,
+                            shader_index,
+                        },
+                    )
+            };
+
+            match *group {
+                RayTracingShaderGroupCreateInfo::General { general_shader } => {
+                    // VUID-VkRayTracingShaderGroupCreateInfoKHR-type-03474
+                    let stage = shader_stage(general_shader)?;
+
+                    if !matches!(
+                        stage,
+                        ShaderStage::Raygen | ShaderStage::Miss | ShaderStage::Callable
+                    ) {
+                        return Err(
+                            RayTracingPipelineCreationError::ShaderGroupShaderStageInvalid {
+                                group_index,
+                                stage,
+                            },
+                        );
+                    }
+                }
+                RayTracingShaderGroupCreateInfo::TrianglesHit {
+                    closest_hit_shader,
+                    any_hit_shader,
+                } => {
+                    // VUID-VkRayTracingShaderGroupCreateInfoKHR-closestHitShader-03477
+                    if let Some(closest_hit_shader) = closest_hit_shader {
+                        let stage = shader_stage(closest_hit_shader)?;
+
+                        if stage != ShaderStage::ClosestHit {
+                            return Err(
+                                RayTracingPipelineCreationError::ShaderGroupShaderStageInvalid {
+                                    group_index,
+                                    stage,
+                                },
+                            );
+                        }
+                    }
+
+                    // VUID-VkRayTracingShaderGroupCreateInfoKHR-anyHitShader-03479
+                    if let Some(any_hit_shader) = any_hit_shader {
+                        let stage = shader_stage(any_hit_shader)?;
+
+                        if stage != ShaderStage::AnyHit {
+                            return Err(
+                                RayTracingPipelineCreationError::ShaderGroupShaderStageInvalid {
+                                    group_index,
+                                    stage,
+                                },
+                            );
+                        }
+                    }
+                }
+                RayTracingShaderGroupCreateInfo::ProceduralHit {
+                    closest_hit_shader,
+                    any_hit_shader,
+                    intersection_shader,
+                } => {
+                    // VUID-VkRayTracingShaderGroupCreateInfoKHR-type-03476
+                    let stage = shader_stage(intersection_shader)?;
+
+                    if stage != ShaderStage::Intersection {
+                        return Err(
+                            RayTracingPipelineCreationError::ShaderGroupShaderStageInvalid {
+                                group_index,
+                                stage,
+                            },
+                        );
+                    }
+
+                    // VUID-VkRayTracingShaderGroupCreateInfoKHR-closestHitShader-03477
+                    if let Some(closest_hit_shader) = closest_hit_shader {
+                        let stage = shader_stage(closest_hit_shader)?;
+
+                        if stage != ShaderStage::ClosestHit {
+                            return Err(
+                                RayTracingPipelineCreationError::ShaderGroupShaderStageInvalid {
+                                    group_index,
+                                    stage,
+                                },
+                            );
+                        }
+                    }
+
+                    // VUID-VkRayTracingShaderGroupCreateInfoKHR-anyHitShader-03479
+                    if let Some(any_hit_shader) = any_hit_shader {
+                        let stage = shader_stage(any_hit_shader)?;
+
+                        if stage != ShaderStage::AnyHit {
+                            return Err(
+                                RayTracingPipelineCreationError::ShaderGroupShaderStageInvalid {
+                                    group_index,
+                                    stage,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        if !libraries.is_empty() {
+            if !device.enabled_extensions().khr_pipeline_library {
+                return Err(RayTracingPipelineCreationError::RequirementNotMet {
+                    required_for: "`create_info.libraries` is not empty",
+                    requires_one_of: RequiresOneOf {
+                        device_extensions: &["khr_pipeline_library"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            for (library_index, library) in libraries.iter().enumerate() {
+                // VUID-VkPipelineLibraryCreateInfoKHR-pLibraries-parameter
+                assert_eq!(device, library.device().as_ref());
+
+                // VUID-VkPipelineLibraryCreateInfoKHR-pLibraries-03381
+                if !library.flags().intersects(PipelineCreateFlags::LIBRARY) {
+                    return Err(RayTracingPipelineCreationError::LibraryNotALibrary {
+                        library_index,
+                    });
+                }
+
+                // VUID-VkRayTracingPipelineCreateInfoKHR-pLibraryInfo-03591
+                if library.max_pipeline_ray_recursion_depth() != max_pipeline_ray_recursion_depth {
+                    return Err(
+                        RayTracingPipelineCreationError::LibraryRayRecursionDepthMismatch {
+                            library_index,
+                        },
+                    );
+                }
+
+                has_raygen_stage |= library
+                    .group_kinds
+                    .contains(&ShaderGroupKind::RayGeneration);
+            }
+        }
+
+        // VUID-VkRayTracingPipelineCreateInfoKHR-stage-03425
+        if !flags.intersects(PipelineCreateFlags::LIBRARY) && !has_raygen_stage {
+            return Err(RayTracingPipelineCreationError::RayGenerationShaderMissing);
+        }
+
+        if flags.intersects(PipelineCreateFlags::LIBRARY) || !libraries.is_empty() {
+            // VUID-VkRayTracingPipelineCreateInfoKHR-flags-03465
+            let library_interface = library_interface
+                .as_ref()
+                .ok_or(RayTracingPipelineCreationError::LibraryInterfaceMissing)?;
+
+            // VUID-VkRayTracingPipelineInterfaceCreateInfoKHR-maxPipelineRayHitAttributeSize-03605
+            let max_ray_hit_attribute_size = properties.max_ray_hit_attribute_size.unwrap_or(0);
+
+            if library_interface.max_pipeline_ray_hit_attribute_size > max_ray_hit_attribute_size {
+                return Err(
+                    RayTracingPipelineCreationError::MaxRayHitAttributeSizeExceeded {
+                        provided: library_interface.max_pipeline_ray_hit_attribute_size,
+                        max: max_ray_hit_attribute_size,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn new_unchecked(
+        device: Arc<Device>,
+        cache: Option<Arc<PipelineCache>>,
+        create_info: RayTracingPipelineCreateInfo,
+    ) -> Result<Arc<RayTracingPipeline>, VulkanError> {
+        let &RayTracingPipelineCreateInfo {
+            flags,
+            ref stages,
+            ref groups,
+            max_pipeline_ray_recursion_depth,
+            ref libraries,
+            ref library_interface,
+            ref layout,
+            _ne: _,
+        } = &create_info;
+
+        struct PerPipelineShaderStageCreateInfo {
+            name_vk: CString,
+            specialization_info_vk: ash::vk::SpecializationInfo,
+            specialization_map_entries_vk: Vec<ash::vk::SpecializationMapEntry>,
+            specialization_data_vk: Vec<u8>,
+        }
+
+        let (mut stages_vk, mut per_stage_vk): (SmallVec<[_; 5]>, SmallVec<[_; 5]>) = stages
+            .iter()
+            .map(|stage| {
+                let &PipelineShaderStageCreateInfo {
+                    flags,
+                    ref entry_point,
+                    ref specialization_info,
+                    _ne: _,
+                } = stage;
+
+                let entry_point_info = entry_point.info();
+                let stage = ShaderStage::from(&entry_point_info.execution);
+
+                let mut specialization_data_vk: Vec<u8> = Vec::new();
+                let specialization_map_entries_vk: Vec<_> = specialization_info
+                    .iter()
+                    .map(|(&constant_id, value)| {
+                        let data = value.as_bytes();
+                        let offset = specialization_data_vk.len() as u32;
+                        let size = data.len();
+                        specialization_data_vk.extend(data);
+
+                        ash::vk::SpecializationMapEntry {
+                            constant_id,
+                            offset,
+                            size,
+                        }
+                    })
+                    .collect();
+
+                (
+                    ash::vk::PipelineShaderStageCreateInfo {
+                        flags: flags.into(),
+                        stage: stage.into(),
+                        module: entry_point.module().handle(),
+                        p_name: ptr::null(),
+                        p_specialization_info: ptr::null(),
+                        ..Default::default()
+                    },
+                    PerPipelineShaderStageCreateInfo {
+                        name_vk: CString::new(entry_point_info.name.as_str()).unwrap(),
+                        specialization_info_vk: ash::vk::SpecializationInfo {
+                            map_entry_count: specialization_map_entries_vk.len() as u32,
+                            p_map_entries: ptr::null(),
+                            data_size: specialization_data_vk.len(),
+                            p_data: ptr::null(),
+                        },
+                        specialization_map_entries_vk,
+                        specialization_data_vk,
+                    },
+                )
+            })
+            .unzip();
+
+        for (
+            stage_vk,
+            PerPipelineShaderStageCreateInfo {
+                name_vk,
+                specialization_info_vk,
+                specialization_map_entries_vk,
+                specialization_data_vk,
+            },
+        ) in (stages_vk.iter_mut()).zip(per_stage_vk.iter_mut())
+        {
+            *stage_vk = ash::vk::PipelineShaderStageCreateInfo {
+                p_name: name_vk.as_ptr(),
+                p_specialization_info: specialization_info_vk,
+                ..*stage_vk
+            };
+
+            *specialization_info_vk = ash::vk::SpecializationInfo {
+                p_map_entries: specialization_map_entries_vk.as_ptr(),
+                p_data: specialization_data_vk.as_ptr() as _,
+                ..*specialization_info_vk
+            };
+        }
+
+        let groups_vk: SmallVec<[_; 5]> = groups.iter().map(|group| group.to_vulkan()).collect();
+
+        let libraries_vk: SmallVec<[_; 4]> =
+            libraries.iter().map(|library| library.handle()).collect();
+        let library_info_vk = ash::vk::PipelineLibraryCreateInfoKHR {
+            library_count: libraries_vk.len() as u32,
+            p_libraries: libraries_vk.as_ptr(),
+            ..Default::default()
+        };
+        let library_interface_vk = library_interface.map(|library_interface| {
+            let RayTracingPipelineInterfaceCreateInfo {
+                max_pipeline_ray_payload_size,
+                max_pipeline_ray_hit_attribute_size,
+            } = library_interface;
+
+            ash::vk::RayTracingPipelineInterfaceCreateInfoKHR {
+                max_pipeline_ray_payload_size,
+                max_pipeline_ray_hit_attribute_size,
+                ..Default::default()
+            }
+        });
+
+        let mut flags_vk = ash::vk::PipelineCreateFlags::from(flags);
+
+        if layout.descriptor_buffer() {
+            flags_vk |= ash::vk::PipelineCreateFlags::DESCRIPTOR_BUFFER_EXT;
+        }
+
+        let create_info_vk = ash::vk::RayTracingPipelineCreateInfoKHR {
+            flags: flags_vk,
+            stage_count: stages_vk.len() as u32,
+            p_stages: stages_vk.as_ptr(),
+            group_count: groups_vk.len() as u32,
+            p_groups: groups_vk.as_ptr(),
+            max_pipeline_ray_recursion_depth,
+            p_library_info: if libraries_vk.is_empty() {
+                ptr::null()
+            } else {
+                &library_info_vk
+            },
+            p_library_interface: library_interface_vk
+                .as_ref()
+                .map_or(ptr::null(), |library_interface_vk| library_interface_vk),
+            p_dynamic_state: ptr::null(),
+            layout: layout.handle(),
+            base_pipeline_handle: ash::vk::Pipeline::null(),
+            base_pipeline_index: 0,
+            ..Default::default()
+        };
+
+        let handle = {
+            let fns = device.fns();
+            let mut output = MaybeUninit::uninit();
+            (fns.khr_ray_tracing_pipeline
+                .create_ray_tracing_pipelines_khr)(
+                device.handle(),
+                ash::vk::DeferredOperationKHR::null(),
+                cache.as_ref().map_or(Default::default(), |c| c.handle()),
+                1,
+                &create_info_vk,
+                ptr::null(),
+                output.as_mut_ptr(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+            output.assume_init()
+        };
+
+        Ok(Self::from_handle(device, handle, create_info))
+    }
+
+    /// Creates a new `RayTracingPipeline` from a raw object handle.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid Vulkan object handle created from `device`.
+    /// - `create_info` must match the info used to create the object.
+    #[inline]
+    pub unsafe fn from_handle(
+        device: Arc<Device>,
+        handle: ash::vk::Pipeline,
+        create_info: RayTracingPipelineCreateInfo,
+    ) -> Arc<RayTracingPipeline> {
+        let RayTracingPipelineCreateInfo {
+            flags,
+            stages,
+            groups,
+            max_pipeline_ray_recursion_depth,
+            libraries,
+            library_interface: _,
+            layout,
+            _ne: _,
+        } = create_info;
+
+        let mut descriptor_binding_requirements: HashMap<
+            (u32, u32),
+            DescriptorBindingRequirements,
+        > = HashMap::default();
+
+        let stage_reqs = stages
+            .iter()
+            .map(|stage| &stage.entry_point.info().descriptor_binding_requirements);
+        let library_reqs = libraries
+            .iter()
+            .map(|library| &library.descriptor_binding_requirements);
+
+        for reqs in stage_reqs.chain(library_reqs) {
+            for (&loc, reqs) in reqs {
+                match descriptor_binding_requirements.entry(loc) {
+                    Entry::Occupied(entry) => {
+                        entry.into_mut().merge(reqs).expect("Could not produce an intersection of the shader descriptor requirements");
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(reqs.clone());
+                    }
+                }
+            }
+        }
+
+        let num_used_descriptor_sets = descriptor_binding_requirements
+            .keys()
+            .map(|loc| loc.0)
+            .max()
+            .map(|x| x + 1)
+            .unwrap_or(0);
+
+        // The groups of the libraries come after the groups of the pipeline itself, in the order
+        // that the libraries were provided.
+        let group_kinds = groups
+            .iter()
+            .map(|group| match *group {
+                RayTracingShaderGroupCreateInfo::General { general_shader } => {
+                    match ShaderStage::from(
+                        &stages[general_shader as usize].entry_point.info().execution,
+                    ) {
+                        ShaderStage::Raygen => ShaderGroupKind::RayGeneration,
+                        ShaderStage::Miss => ShaderGroupKind::Miss,
+                        _ => ShaderGroupKind::Callable,
+                    }
+                }
+                RayTracingShaderGroupCreateInfo::TrianglesHit { .. }
+                | RayTracingShaderGroupCreateInfo::ProceduralHit { .. } => ShaderGroupKind::Hit,
+            })
+            .chain(
+                libraries
+                    .iter()
+                    .flat_map(|library| library.group_kinds.iter().copied()),
+            )
+            .collect();
+
+        Arc::new(RayTracingPipeline {
+            handle,
+            device,
+            id: Self::next_id(),
+
+            flags,
+            groups,
+            group_kinds,
+            max_pipeline_ray_recursion_depth,
+            layout,
+            descriptor_binding_requirements,
+            num_used_descriptor_sets,
+        })
+    }
+
+    /// Returns the `Device` this ray tracing pipeline was created with.
+    #[inline]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the flags that the pipeline was created with.
+    #[inline]
+    pub fn flags(&self) -> PipelineCreateFlags {
+        self.flags
+    }
+
+    /// Returns the shader groups that were specified when the pipeline was created.
+    ///
+    /// This does not include the groups of any linked pipeline libraries.
+    #[inline]
+    pub fn groups(&self) -> &[RayTracingShaderGroupCreateInfo] {
+        &self.groups
+    }
+
+    /// Returns the total number of shader groups in the pipeline, including those of any linked
+    /// pipeline libraries.
+    #[inline]
+    pub fn group_count(&self) -> u32 {
+        self.group_kinds.len() as u32
+    }
+
+    /// Returns the maximum recursion depth that the pipeline was created with.
+    #[inline]
+    pub fn max_pipeline_ray_recursion_depth(&self) -> u32 {
+        self.max_pipeline_ray_recursion_depth
+    }
+
+    /// Returns the opaque handles of all the shader groups in the pipeline, including those of any
+    /// linked pipeline libraries.
+    ///
+    /// These handles are what is written into a shader binding table.
+    pub fn group_handles(&self) -> Result<ShaderGroupHandles, VulkanError> {
+        let handle_size = self
+            .device
+            .physical_device()
+            .properties()
+            .shader_group_handle_size
+            .unwrap();
+        let group_count = self.group_count();
+        let mut data = vec![0u8; handle_size as usize * group_count as usize];
+
+        unsafe {
+            let fns = self.device.fns();
+            (fns.khr_ray_tracing_pipeline
+                .get_ray_tracing_shader_group_handles_khr)(
+                self.device.handle(),
+                self.handle,
+                0,
+                group_count,
+                data.len(),
+                data.as_mut_ptr() as *mut _,
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+        }
+
+        Ok(ShaderGroupHandles { data, handle_size })
+    }
+}
+
+impl Pipeline for RayTracingPipeline {
+    #[inline]
+    fn bind_point(&self) -> PipelineBindPoint {
+        PipelineBindPoint::RayTracing
+    }
+
+    #[inline]
+    fn layout(&self) -> &Arc<PipelineLayout> {
+        &self.layout
+    }
+
+    #[inline]
+    fn num_used_descriptor_sets(&self) -> u32 {
+        self.num_used_descriptor_sets
+    }
+
+    #[inline]
+    fn descriptor_binding_requirements(
+        &self,
+    ) -> &HashMap<(u32, u32), DescriptorBindingRequirements> {
+        &self.descriptor_binding_requirements
+    }
+}
+
+impl Debug for RayTracingPipeline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "<Vulkan ray tracing pipeline {:?}>", self.handle)
+    }
+}
+
+impl_id_counter!(RayTracingPipeline);
+
+unsafe impl VulkanObject for RayTracingPipeline {
+    type Handle = ash::vk::Pipeline;
+
+    #[inline]
+    fn handle(&self) -> Self::Handle {
+        self.handle
+    }
+}
+
+unsafe impl DeviceOwned for RayTracingPipeline {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.device()
+    }
+}
+
+impl Drop for RayTracingPipeline {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let fns = self.device.fns();
+            (fns.v1_0.destroy_pipeline)(self.device.handle(), self.handle, ptr::null());
+        }
+    }
+}
+
+/// Parameters to create a new `RayTracingPipeline`.
+#[derive(Clone, Debug)]
+pub struct RayTracingPipelineCreateInfo {
+    /// Specifies how to create the pipeline.
+    ///
+    /// The default value is empty.
+    pub flags: PipelineCreateFlags,
+
+    /// The ray tracing shader stages to use.
+    ///
+    /// The default value is empty.
+    pub stages: SmallVec<[PipelineShaderStageCreateInfo; 5]>,
+
+    /// The shader groups of the pipeline. The shaders of each group are referenced by their index
+    /// in `stages`.
+    ///
+    /// The default value is empty.
+    pub groups: SmallVec<[RayTracingShaderGroupCreateInfo; 5]>,
+
+    /// The maximum recursion depth of shaders executed by the pipeline.
+    ///
+    /// This must not be greater than the
+    /// [`max_ray_recursion_depth`](crate::device::Properties::max_ray_recursion_depth) limit.
+    ///
+    /// The default value is 1.
+    pub max_pipeline_ray_recursion_depth: u32,
+
+    /// Pipeline libraries whose shaders and groups will be linked into the pipeline.
+    ///
+    /// If this is not empty, then the
+    /// [`khr_pipeline_library`](crate::device::DeviceExtensions::khr_pipeline_library) extension
+    /// must be enabled on the device, and `library_interface` must be `Some`.
+    ///
+    /// The default value is empty.
+    pub libraries: Vec<Arc<RayTracingPipeline>>,
+
+    /// The maximum sizes of the payloads and hit attributes used by the pipeline and its
+    /// libraries.
+    ///
+    /// This must be `Some` if `flags` contains [`PipelineCreateFlags::LIBRARY`], or if
+    /// `libraries` is not empty. The value must be the same for a pipeline and all libraries
+    /// that are linked into it.
+    ///
+    /// The default value is `None`.
+    pub library_interface: Option<RayTracingPipelineInterfaceCreateInfo>,
+
+    /// The pipeline layout to use.
+    ///
+    /// There is no default value.
+    pub layout: Arc<PipelineLayout>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl RayTracingPipelineCreateInfo {
+    /// Returns a `RayTracingPipelineCreateInfo` with the specified `layout`.
+    #[inline]
+    pub fn layout(layout: Arc<PipelineLayout>) -> Self {
+        Self {
+            flags: PipelineCreateFlags::empty(),
+            stages: SmallVec::new(),
+            groups: SmallVec::new(),
+            max_pipeline_ray_recursion_depth: 1,
+            libraries: Vec::new(),
+            library_interface: None,
+            layout,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// A shader group in a ray tracing pipeline.
+///
+/// The shaders are specified as indices into the `stages` of the pipeline create info.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RayTracingShaderGroupCreateInfo {
+    /// A group that contains a single ray generation, miss or callable shader.
+    General { general_shader: u32 },
+
+    /// A group that is invoked when a ray hits a triangle geometry.
+    TrianglesHit {
+        closest_hit_shader: Option<u32>,
+        any_hit_shader: Option<u32>,
+    },
+
+    /// A group that is invoked when a ray hits an AABB geometry. The intersection shader
+    /// determines whether a ray actually intersects the procedural primitive inside the AABB.
+    ProceduralHit {
+        closest_hit_shader: Option<u32>,
+        any_hit_shader: Option<u32>,
+        intersection_shader: u32,
+    },
+}
+
+impl RayTracingShaderGroupCreateInfo {
+    pub(crate) fn to_vulkan(&self) -> ash::vk::RayTracingShaderGroupCreateInfoKHR {
+        let unused = ash::vk::SHADER_UNUSED_KHR;
+
+        match *self {
+            Self::General { general_shader } => ash::vk::RayTracingShaderGroupCreateInfoKHR {
+                ty: ash::vk::RayTracingShaderGroupTypeKHR::GENERAL,
+                general_shader,
+                closest_hit_shader: unused,
+                any_hit_shader: unused,
+                intersection_shader: unused,
+                ..Default::default()
+            },
+            Self::TrianglesHit {
+                closest_hit_shader,
+                any_hit_shader,
+            } => ash::vk::RayTracingShaderGroupCreateInfoKHR {
+                ty: ash::vk::RayTracingShaderGroupTypeKHR::TRIANGLES_HIT_GROUP,
+                general_shader: unused,
+                closest_hit_shader: closest_hit_shader.unwrap_or(unused),
+                any_hit_shader: any_hit_shader.unwrap_or(unused),
+                intersection_shader: unused,
+                ..Default::default()
+            },
+            Self::ProceduralHit {
+                closest_hit_shader,
+                any_hit_shader,
+                intersection_shader,
+            } => ash::vk::RayTracingShaderGroupCreateInfoKHR {
+                ty: ash::vk::RayTracingShaderGroupTypeKHR::PROCEDURAL_HIT_GROUP,
+                general_shader: unused,
+                closest_hit_shader: closest_hit_shader.unwrap_or(unused),
+                any_hit_shader: any_hit_shader.unwrap_or(unused),
+                intersection_shader,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// The interface between a ray tracing pipeline and the pipeline libraries that are linked into
+/// it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RayTracingPipelineInterfaceCreateInfo {
+    /// The maximum size in bytes of a ray payload used by any shader in the pipeline.
+    pub max_pipeline_ray_payload_size: u32,
+
+    /// The maximum size in bytes of the hit attributes used by any shader in the pipeline.
+    ///
+    /// This must not be greater than the
+    /// [`max_ray_hit_attribute_size`](crate::device::Properties::max_ray_hit_attribute_size)
+    /// limit.
+    pub max_pipeline_ray_hit_attribute_size: u32,
+}
+
+// Which region of a shader binding table a shader group belongs in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ShaderGroupKind {
+    RayGeneration,
+    Miss,
+    Hit,
+    Callable,
+}
+
+/// The opaque handles of the shader groups of a ray tracing pipeline.
+#[derive(Clone, Debug)]
+pub struct ShaderGroupHandles {
+    data: Vec<u8>,
+    handle_size: u32,
+}
+
+impl ShaderGroupHandles {
+    /// Returns the size of each handle in bytes.
+    #[inline]
+    pub fn handle_size(&self) -> u32 {
+        self.handle_size
+    }
+
+    /// Returns the raw data of all handles, tightly packed.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns an iterator over the handles of each group, in group order.
+    #[inline]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &[u8]> {
+        self.data.chunks_exact(self.handle_size as usize)
+    }
+}
+
+/// A region of a shader binding table, consisting of equally spaced group handles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StridedDeviceAddressRegion {
+    /// The device address of the start of the region, or 0 if the region is unused.
+    pub device_address: DeviceSize,
+
+    /// The number of bytes between consecutive group handles.
+    pub stride: DeviceSize,
+
+    /// The size of the region in bytes.
+    pub size: DeviceSize,
+}
+
+impl From<StridedDeviceAddressRegion> for ash::vk::StridedDeviceAddressRegionKHR {
+    #[inline]
+    fn from(val: StridedDeviceAddressRegion) -> Self {
+        let StridedDeviceAddressRegion {
+            device_address,
+            stride,
+            size,
+        } = val;
+
+        Self {
+            device_address,
+            stride,
+            size,
+        }
+    }
+}
+
+/// The regions of a shader binding table that are used by a `trace_rays` command.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ShaderBindingTableAddresses {
+    /// The ray generation shader group to invoke. The `size` must be equal to the `stride`.
+    pub raygen: StridedDeviceAddressRegion,
+
+    /// The miss shader groups.
+    pub miss: StridedDeviceAddressRegion,
+
+    /// The hit shader groups.
+    pub hit: StridedDeviceAddressRegion,
+
+    /// The callable shader groups.
+    pub callable: StridedDeviceAddressRegion,
+}
+
+/// A shader binding table that contains the handles of every shader group of a ray tracing
+/// pipeline.
+///
+/// The groups are sorted into a ray generation, miss, hit and callable region. Within each
+/// region, the groups are ordered the same as in the pipeline, so the `n`th miss group of the
+/// pipeline can be selected with miss index `n` in the shader, and similarly for the hit and
+/// callable groups. The ray generation region contains only the first ray generation group of
+/// the pipeline.
+#[derive(Clone, Debug)]
+pub struct ShaderBindingTable {
+    addresses: ShaderBindingTableAddresses,
+    buffer: Subbuffer<[u8]>,
+}
+
+impl ShaderBindingTable {
+    /// Creates a shader binding table for `pipeline`, using the handle size and alignments that
+    /// the physical device requires.
+    ///
+    /// The buffer is allocated from `allocator` with host-visible memory, and requires the
+    /// [`buffer_device_address`](crate::device::Features::buffer_device_address) feature to be
+    /// enabled on the device.
+    pub fn new(
+        allocator: &(impl MemoryAllocator + ?Sized),
+        pipeline: &RayTracingPipeline,
+    ) -> Result<Self, BufferError> {
+        let properties = pipeline.device().physical_device().properties();
+        let handle_size = properties.shader_group_handle_size.unwrap() as DeviceSize;
+        let handle_alignment =
+            DeviceAlignment::new(properties.shader_group_handle_alignment.unwrap() as DeviceSize)
+                .unwrap();
+        let base_alignment =
+            DeviceAlignment::new(properties.shader_group_base_alignment.unwrap() as DeviceSize)
+                .unwrap();
+
+        let handles = pipeline.group_handles()?;
+        let handle_stride = align_up(handle_size, handle_alignment);
+
+        let count_of = |kind| {
+            pipeline
+                .group_kinds
+                .iter()
+                .filter(|&&group_kind| group_kind == kind)
+                .count() as DeviceSize
+        };
+        let raygen_count = count_of(ShaderGroupKind::RayGeneration).min(1);
+        let miss_count = count_of(ShaderGroupKind::Miss);
+        let hit_count = count_of(ShaderGroupKind::Hit);
+        let callable_count = count_of(ShaderGroupKind::Callable);
+
+        // Offsets of each region, relative to the aligned start of the table.
+        let raygen_offset = 0;
+        let miss_offset = align_up(raygen_offset + raygen_count * handle_stride, base_alignment);
+        let hit_offset = align_up(miss_offset + miss_count * handle_stride, base_alignment);
+        let callable_offset = align_up(hit_offset + hit_count * handle_stride, base_alignment);
+        let table_size = callable_offset + callable_count * handle_stride;
+
+        // The buffer may not be aligned to the base alignment, so allocate extra room to align
+        // the start of the table manually.
+        let buffer = Buffer::new_slice::<u8>(
+            allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::SHADER_BINDING_TABLE | BufferUsage::SHADER_DEVICE_ADDRESS,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Upload,
+                ..Default::default()
+            },
+            table_size + base_alignment.as_devicesize() - 1,
+        )?;
+        let buffer_address = buffer.device_address()?.get();
+        let start = align_up(buffer_address, base_alignment) - buffer_address;
+        let buffer = buffer.slice(start..start + table_size);
+        let table_address = buffer_address + start;
+
+        {
+            let mut data = buffer.write()?;
+            let (mut next_raygen, mut next_miss, mut next_hit, mut next_callable) =
+                (raygen_offset, miss_offset, hit_offset, callable_offset);
+
+            for (&kind, handle) in pipeline.group_kinds.iter().zip(handles.iter()) {
+                let next_offset = match kind {
+                    ShaderGroupKind::RayGeneration => &mut next_raygen,
+                    ShaderGroupKind::Miss => &mut next_miss,
+                    ShaderGroupKind::Hit => &mut next_hit,
+                    ShaderGroupKind::Callable => &mut next_callable,
+                };
+
+                if kind == ShaderGroupKind::RayGeneration && *next_offset != raygen_offset {
+                    continue;
+                }
+
+                let offset = *next_offset as usize;
+                data[offset..offset + handle.len()].copy_from_slice(handle);
+                *next_offset += handle_stride;
+            }
+        }
+
+        let region = |offset: DeviceSize, count: DeviceSize| {
+            if count == 0 {
+                StridedDeviceAddressRegion::default()
+            } else {
+                StridedDeviceAddressRegion {
+                    device_address: table_address + offset,
+                    stride: handle_stride,
+                    size: count * handle_stride,
+                }
+            }
+        };
+
+        Ok(ShaderBindingTable {
+            addresses: ShaderBindingTableAddresses {
+                raygen: region(raygen_offset, raygen_count),
+                miss: region(miss_offset, miss_count),
+                hit: region(hit_offset, hit_count),
+                callable: region(callable_offset, callable_count),
+            },
+            buffer,
+        })
+    }
+
+    /// Returns the addresses of the regions of the shader binding table.
+    #[inline]
+    pub fn addresses(&self) -> &ShaderBindingTableAddresses {
+        &self.addresses
+    }
+
+    /// Returns the buffer that contains the shader binding table.
+    #[inline]
+    pub fn buffer(&self) -> &Subbuffer<[u8]> {
+        &self.buffer
+    }
+}
+
+/// Error that can happen when creating a ray tracing pipeline.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RayTracingPipelineCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// The pipeline layout is not compatible with what the shaders expect.
+    IncompatiblePipelineLayout(PipelineLayoutSupersetError),
+
+    /// The pipeline is a library or has libraries, but `library_interface` was not provided.
+    LibraryInterfaceMissing,
+
+    /// A pipeline in `libraries` was not created with [`PipelineCreateFlags::LIBRARY`].
+    LibraryNotALibrary { library_index: usize },
+
+    /// A pipeline in `libraries` was created with a different `max_pipeline_ray_recursion_depth`
+    /// than the pipeline being created.
+    LibraryRayRecursionDepthMismatch { library_index: usize },
+
+    /// The `max_ray_hit_attribute_size` limit has been exceeded.
+    MaxRayHitAttributeSizeExceeded { provided: u32, max: u32 },
+
+    /// The `max_ray_recursion_depth` limit has been exceeded.
+    MaxRayRecursionDepthExceeded { provided: u32, max: u32 },
+
+    /// The pipeline is not a library, but neither it nor its libraries contain a ray generation
+    /// shader.
+    RayGenerationShaderMissing,
+
+    /// A shader group refers to a shader index that is not in `stages`.
+    ShaderGroupShaderIndexOutOfRange { group_index: u32, shader_index: u32 },
+
+    /// A shader group contains a shader of a stage that is not allowed in that position of the
+    /// group.
+    ShaderGroupShaderStageInvalid {
+        group_index: u32,
+        stage: ShaderStage,
+    },
+
+    /// The value provided for a shader specialization constant has a
+    /// different type than the constant's default value.
+    ShaderSpecializationConstantTypeMismatch {
+        stage_index: usize,
+        constant_id: u32,
+        default_value: SpecializationConstant,
+        provided_value: SpecializationConstant,
+    },
+
+    /// A shader stage is not a ray tracing shader.
+    ShaderStageInvalid {
+        stage_index: usize,
+        stage: ShaderStage,
+    },
+}
+
+impl Error for RayTracingPipelineCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OomError(err) => Some(err),
+            Self::IncompatiblePipelineLayout(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for RayTracingPipelineCreationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::IncompatiblePipelineLayout(_) => write!(
+                f,
+                "the pipeline layout is not compatible with what the shaders expect",
+            ),
+            Self::LibraryInterfaceMissing => write!(
+                f,
+                "the pipeline is a library or has libraries, but `library_interface` was not \
+                provided",
+            ),
+            Self::LibraryNotALibrary { library_index } => write!(
+                f,
+                "library {} was not created with `PipelineCreateFlags::LIBRARY`",
+                library_index,
+            ),
+            Self::LibraryRayRecursionDepthMismatch { library_index } => write!(
+                f,
+                "library {} was created with a different `max_pipeline_ray_recursion_depth` than \
+                the pipeline being created",
+                library_index,
+            ),
+            Self::MaxRayHitAttributeSizeExceeded { .. } => write!(
+                f,
+                "the `max_ray_hit_attribute_size` limit has been exceeded",
+            ),
+            Self::MaxRayRecursionDepthExceeded { .. } => {
+                write!(f, "the `max_ray_recursion_depth` limit has been exceeded",)
+            }
+            Self::RayGenerationShaderMissing => write!(
+                f,
+                "the pipeline is not a library, but neither it nor its libraries contain a ray \
+                generation shader",
+            ),
+            Self::ShaderGroupShaderIndexOutOfRange {
+                group_index,
+                shader_index,
+            } => write!(
+                f,
+                "shader group {} refers to shader index {}, which is not in `stages`",
+                group_index, shader_index,
+            ),
+            Self::ShaderGroupShaderStageInvalid { group_index, stage } => write!(
+                f,
+                "shader group {} contains a shader of stage {:?}, which is not allowed in that \
+                position of the group",
+                group_index, stage,
+            ),
+            Self::ShaderSpecializationConstantTypeMismatch {
+                stage_index,
+                constant_id,
+                default_value,
+                provided_value,
+            } => write!(
+                f,
+                "the value provided for shader {} specialization constant id {} ({:?}) has a \
+                different type than the constant's default value ({:?})",
+                stage_index, constant_id, provided_value, default_value,
+            ),
+            Self::ShaderStageInvalid { stage_index, stage } => write!(
+                f,
+                "the shader stage at index {} ({:?}) is not a ray tracing shader",
+                stage_index, stage,
+            ),
+        }
+    }
+}
+
+impl From<OomError> for RayTracingPipelineCreationError {
+    fn from(err: OomError) -> RayTracingPipelineCreationError {
+        Self::OomError(err)
+    }
+}
+
+impl From<RequirementNotMet> for RayTracingPipelineCreationError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
+impl From<PipelineLayoutSupersetError> for RayTracingPipelineCreationError {
+    fn from(err: PipelineLayoutSupersetError) -> Self {
+        Self::IncompatiblePipelineLayout(err)
+    }
+}
+
+impl From<VulkanError> for RayTracingPipelineCreationError {
+    fn from(err: VulkanError) -> RayTracingPipelineCreationError {
+        match err {
+            err @ VulkanError::OutOfHostMemory => Self::OomError(OomError::from(err)),
+            err @ VulkanError::OutOfDeviceMemory => Self::OomError(OomError::from(err)),
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RayTracingPipeline, RayTracingPipelineCreateInfo, RayTracingPipelineCreationError,
+    };
+    use crate::{
+        pipeline::{layout::PipelineLayoutCreateInfo, PipelineLayout},
+        RequiresOneOf,
+    };
+
+    #[test]
+    fn requires_ray_tracing_pipeline_feature() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let layout =
+            PipelineLayout::new(device.clone(), PipelineLayoutCreateInfo::default()).unwrap();
+
+        match RayTracingPipeline::new(device, None, RayTracingPipelineCreateInfo::layout(layout)) {
+            Err(RayTracingPipelineCreationError::RequirementNotMet {
+                requires_one_of: RequiresOneOf { features, .. },
+                ..
+            }) if features.contains(&"ray_tracing_pipeline") => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -16,9 +16,10 @@
 use crate::{
     buffer::BufferContents,
     device::{Device, DeviceOwned},
-    macros::{impl_id_counter, vulkan_bitflags},
+    macros::{impl_id_counter, vulkan_bitflags, vulkan_enum},
     DeviceSize, OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
 };
+use bytemuck::{Pod, Zeroable};
 use std::{
     error::Error,
     ffi::c_void,
@@ -39,6 +40,7 @@ pub struct QueryPool {
 
     query_type: QueryType,
     query_count: u32,
+    performance_query: Option<QueryPoolPerformanceCreateInfo>,
 }
 
 impl QueryPool {
@@ -47,6 +49,7 @@ impl QueryPool {
     /// # Panics
     ///
     /// - Panics if `create_info.query_count` is `0`.
+    /// - Panics if `create_info.performance_query` is `Some` and its `counter_indices` is empty.
     pub fn new(
         device: Arc<Device>,
         create_info: QueryPoolCreateInfo,
@@ -54,6 +57,7 @@ impl QueryPool {
         let QueryPoolCreateInfo {
             query_type,
             query_count,
+            performance_query,
             _ne: _,
         } = create_info;
 
@@ -67,15 +71,154 @@ impl QueryPool {
                     return Err(QueryPoolCreationError::PipelineStatisticsQueryFeatureNotEnabled);
                 }
 
+                // VUID-VkQueryPoolCreateInfo-meshShaderQueries-07069
+                if flags.intersects(
+                    QueryPipelineStatisticFlags::TASK_SHADER_INVOCATIONS
+                        | QueryPipelineStatisticFlags::MESH_SHADER_INVOCATIONS,
+                ) && !device.enabled_features().mesh_shader_queries
+                {
+                    return Err(QueryPoolCreationError::RequirementNotMet {
+                        required_for: "`create_info.query_type` is \
+                            `QueryType::PipelineStatistics`, and the flags contain \
+                            `QueryPipelineStatisticFlags::TASK_SHADER_INVOCATIONS` or \
+                            `QueryPipelineStatisticFlags::MESH_SHADER_INVOCATIONS`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["mesh_shader_queries"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
                 // VUID-VkQueryPoolCreateInfo-queryType-00792
                 flags.into()
             }
+            QueryType::AccelerationStructureCompactedSize
+            | QueryType::AccelerationStructureSerializationSize => {
+                // VUID-VkQueryPoolCreateInfo-queryType-parameter
+                if !device.enabled_extensions().khr_acceleration_structure {
+                    return Err(QueryPoolCreationError::RequirementNotMet {
+                        required_for: "`create_info.query_type` is \
+                            `QueryType::AccelerationStructureCompactedSize` or \
+                            `QueryType::AccelerationStructureSerializationSize`",
+                        requires_one_of: RequiresOneOf {
+                            device_extensions: &["khr_acceleration_structure"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                ash::vk::QueryPipelineStatisticFlags::empty()
+            }
+            QueryType::TransformFeedbackStream => {
+                // VUID-VkQueryPoolCreateInfo-queryType-parameter
+                if !device.enabled_extensions().ext_transform_feedback {
+                    return Err(QueryPoolCreationError::RequirementNotMet {
+                        required_for: "`create_info.query_type` is \
+                            `QueryType::TransformFeedbackStream`",
+                        requires_one_of: RequiresOneOf {
+                            device_extensions: &["ext_transform_feedback"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                ash::vk::QueryPipelineStatisticFlags::empty()
+            }
+            QueryType::PrimitivesGenerated => {
+                // VUID-VkQueryPoolCreateInfo-queryType-parameter
+                if !device.enabled_extensions().ext_primitives_generated_query {
+                    return Err(QueryPoolCreationError::RequirementNotMet {
+                        required_for: "`create_info.query_type` is \
+                            `QueryType::PrimitivesGenerated`",
+                        requires_one_of: RequiresOneOf {
+                            device_extensions: &["ext_primitives_generated_query"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                ash::vk::QueryPipelineStatisticFlags::empty()
+            }
+            QueryType::MeshPrimitivesGenerated => {
+                // VUID-VkQueryPoolCreateInfo-meshShaderQueries-07068
+                if !device.enabled_features().mesh_shader_queries {
+                    return Err(QueryPoolCreationError::RequirementNotMet {
+                        required_for: "`create_info.query_type` is \
+                            `QueryType::MeshPrimitivesGenerated`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["mesh_shader_queries"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                ash::vk::QueryPipelineStatisticFlags::empty()
+            }
+            QueryType::Performance => {
+                // VUID-VkQueryPoolPerformanceCreateInfoKHR-performanceCounterQueryPools-03237
+                if !device.enabled_features().performance_counter_query_pools {
+                    return Err(QueryPoolCreationError::RequirementNotMet {
+                        required_for: "`create_info.query_type` is `QueryType::Performance`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["performance_counter_query_pools"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkQueryPoolCreateInfo-queryType-03222
+                let performance_query = performance_query
+                    .as_ref()
+                    .ok_or(QueryPoolCreationError::PerformanceQueryCreateInfoMissing)?;
+                let &QueryPoolPerformanceCreateInfo {
+                    queue_family_index,
+                    ref counter_indices,
+                    _ne: _,
+                } = performance_query;
+
+                let physical_device = device.physical_device();
+                let queue_family_count = physical_device.queue_family_properties().len() as u32;
+
+                // VUID-VkQueryPoolPerformanceCreateInfoKHR-queueFamilyIndex-03236
+                if queue_family_index >= queue_family_count {
+                    return Err(QueryPoolCreationError::QueueFamilyIndexOutOfRange {
+                        queue_family_index,
+                        queue_family_count,
+                    });
+                }
+
+                // VUID-VkQueryPoolPerformanceCreateInfoKHR-counterIndexCount-arraylength
+                assert!(!counter_indices.is_empty());
+
+                let counter_count = unsafe {
+                    physical_device
+                        .queue_family_performance_query_counters_unchecked(queue_family_index)?
+                        .len() as u32
+                };
+
+                // VUID-VkQueryPoolPerformanceCreateInfoKHR-pCounterIndices-03321
+                if let Some(&counter_index) = counter_indices
+                    .iter()
+                    .find(|&&counter_index| counter_index >= counter_count)
+                {
+                    return Err(QueryPoolCreationError::PerformanceCounterIndexOutOfRange {
+                        counter_index,
+                        counter_count,
+                    });
+                }
+
+                ash::vk::QueryPipelineStatisticFlags::empty()
+            }
             QueryType::Occlusion | QueryType::Timestamp => {
                 ash::vk::QueryPipelineStatisticFlags::empty()
             }
         };
 
-        let create_info = ash::vk::QueryPoolCreateInfo {
+        // Only keep the performance query info for performance query pools.
+        let performance_query =
+            performance_query.filter(|_| matches!(query_type, QueryType::Performance));
+
+        let mut create_info = ash::vk::QueryPoolCreateInfo {
             flags: ash::vk::QueryPoolCreateFlags::empty(),
             query_type: query_type.into(),
             query_count,
@@ -83,6 +226,19 @@ impl QueryPool {
             ..Default::default()
         };
 
+        let performance_create_info_vk = performance_query.as_ref().map(|performance_query| {
+            ash::vk::QueryPoolPerformanceCreateInfoKHR {
+                queue_family_index: performance_query.queue_family_index,
+                counter_index_count: performance_query.counter_indices.len() as u32,
+                p_counter_indices: performance_query.counter_indices.as_ptr(),
+                ..Default::default()
+            }
+        });
+
+        if let Some(performance_create_info_vk) = performance_create_info_vk.as_ref() {
+            create_info.p_next = performance_create_info_vk as *const _ as *const _;
+        }
+
         let handle = unsafe {
             let fns = device.fns();
             let mut output = MaybeUninit::uninit();
@@ -103,6 +259,7 @@ impl QueryPool {
             id: Self::next_id(),
             query_type,
             query_count,
+            performance_query,
         }))
     }
 
@@ -121,6 +278,7 @@ impl QueryPool {
         let QueryPoolCreateInfo {
             query_type,
             query_count,
+            performance_query,
             _ne: _,
         } = create_info;
 
@@ -130,6 +288,7 @@ impl QueryPool {
             id: Self::next_id(),
             query_type,
             query_count,
+            performance_query,
         })
     }
 
@@ -145,6 +304,26 @@ impl QueryPool {
         self.query_count
     }
 
+    /// Returns the performance query parameters of the pool, if it is a
+    /// [`QueryType::Performance`] pool.
+    #[inline]
+    pub fn performance_query(&self) -> Option<&QueryPoolPerformanceCreateInfo> {
+        self.performance_query.as_ref()
+    }
+
+    /// Returns the number of [`QueryResultElement`]s that are needed to hold the result of a
+    /// single query in this pool.
+    ///
+    /// This is the same as [`QueryType::result_len`], except for [`QueryType::Performance`]
+    /// pools, where it returns the number of counters that the pool was created with.
+    #[inline]
+    pub fn result_len(&self) -> DeviceSize {
+        match &self.performance_query {
+            Some(performance_query) => performance_query.counter_indices.len() as DeviceSize,
+            None => self.query_type.result_len(),
+        }
+    }
+
     /// Returns a reference to a single query slot, or `None` if the index is out of range.
     #[inline]
     pub fn query(&self, index: u32) -> Option<Query<'_>> {
@@ -213,6 +392,13 @@ pub struct QueryPoolCreateInfo {
     /// The default value is `0`, which must be overridden.
     pub query_count: u32,
 
+    /// The performance counters to gather, if `query_type` is [`QueryType::Performance`].
+    ///
+    /// This must be `Some` for performance query pools, and is ignored for other query types.
+    ///
+    /// The default value is `None`.
+    pub performance_query: Option<QueryPoolPerformanceCreateInfo>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -223,6 +409,51 @@ impl QueryPoolCreateInfo {
         Self {
             query_type,
             query_count: 0,
+            performance_query: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Parameters for a performance query pool.
+///
+/// The counters are retrieved for a given queue family with
+/// [`PhysicalDevice::queue_family_performance_query_counters`]. Only counter sets that can be
+/// gathered in a single pass, as reported by
+/// [`PhysicalDevice::queue_family_performance_query_passes`], are currently usable, since
+/// vulkano does not yet support submitting command buffers with a counter pass index.
+///
+/// Before recording a command buffer that uses a performance query pool, the profiling lock
+/// must be held with [`Device::acquire_profiling_lock`].
+///
+/// [`PhysicalDevice::queue_family_performance_query_counters`]: crate::device::physical::PhysicalDevice::queue_family_performance_query_counters
+/// [`PhysicalDevice::queue_family_performance_query_passes`]: crate::device::physical::PhysicalDevice::queue_family_performance_query_passes
+#[derive(Clone, Debug)]
+pub struct QueryPoolPerformanceCreateInfo {
+    /// The queue family whose counters are being queried. Queries from the pool can only be
+    /// begun in command buffers for this queue family.
+    ///
+    /// The default value is `0`.
+    pub queue_family_index: u32,
+
+    /// The indices of the counters to gather, into the list returned by
+    /// [`PhysicalDevice::queue_family_performance_query_counters`].
+    ///
+    /// The default value is empty, which must be overridden.
+    ///
+    /// [`PhysicalDevice::queue_family_performance_query_counters`]: crate::device::physical::PhysicalDevice::queue_family_performance_query_counters
+    pub counter_indices: Vec<u32>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl QueryPoolPerformanceCreateInfo {
+    /// Returns a `QueryPoolPerformanceCreateInfo` with the specified `queue_family_index`.
+    #[inline]
+    pub fn queue_family_index(queue_family_index: u32) -> Self {
+        Self {
+            queue_family_index,
+            counter_indices: Vec::new(),
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -233,8 +464,30 @@ impl QueryPoolCreateInfo {
 pub enum QueryPoolCreationError {
     /// Not enough memory.
     OomError(OomError),
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// A performance counter index is not less than the number of counters available for the
+    /// queue family.
+    PerformanceCounterIndexOutOfRange {
+        counter_index: u32,
+        counter_count: u32,
+    },
+
+    /// The query type is [`QueryType::Performance`], but `performance_query` was not provided.
+    PerformanceQueryCreateInfoMissing,
+
     /// A pipeline statistics pool was requested but the corresponding feature wasn't enabled.
     PipelineStatisticsQueryFeatureNotEnabled,
+
+    /// The performance query queue family index is not less than the number of queue families
+    /// of the physical device.
+    QueueFamilyIndexOutOfRange {
+        queue_family_index: u32,
+        queue_family_count: u32,
+    },
 }
 
 impl Error for QueryPoolCreationError {
@@ -248,17 +501,45 @@ impl Error for QueryPoolCreationError {
 
 impl Display for QueryPoolCreationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        write!(
-            f,
-            "{}",
-            match self {
-                QueryPoolCreationError::OomError(_) => "not enough memory available",
-                QueryPoolCreationError::PipelineStatisticsQueryFeatureNotEnabled => {
-                    "a pipeline statistics pool was requested but the corresponding feature \
-                    wasn't enabled"
-                }
-            }
-        )
+        match self {
+            QueryPoolCreationError::OomError(_) => write!(f, "not enough memory available"),
+            QueryPoolCreationError::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            QueryPoolCreationError::PerformanceCounterIndexOutOfRange {
+                counter_index,
+                counter_count,
+            } => write!(
+                f,
+                "the performance counter index {} is not less than the number of counters \
+                available for the queue family ({})",
+                counter_index, counter_count,
+            ),
+            QueryPoolCreationError::PerformanceQueryCreateInfoMissing => write!(
+                f,
+                "the query type is `QueryType::Performance`, but `performance_query` was not \
+                provided",
+            ),
+            QueryPoolCreationError::PipelineStatisticsQueryFeatureNotEnabled => write!(
+                f,
+                "a pipeline statistics pool was requested but the corresponding feature wasn't \
+                enabled",
+            ),
+            QueryPoolCreationError::QueueFamilyIndexOutOfRange {
+                queue_family_index,
+                queue_family_count,
+            } => write!(
+                f,
+                "the performance query queue family index ({}) is not less than the number of \
+                queue families of the physical device ({})",
+                queue_family_index, queue_family_count,
+            ),
+        }
     }
 }
 
@@ -329,17 +610,22 @@ impl<'a> QueriesRange<'a> {
 
     /// Copies the results of this range of queries to a buffer on the CPU.
     ///
-    /// [`self.pool().ty().result_len()`] will be written for each query in the range, plus 1 extra
+    /// [`self.pool().result_len()`] will be written for each query in the range, plus 1 extra
     /// element per query if [`WITH_AVAILABILITY`] is enabled. The provided buffer must be large
     /// enough to hold the data.
     ///
+    /// For [`QueryType::Performance`] pools, `T` must be [`PerformanceCounterResult`], and
+    /// [`WITH_AVAILABILITY`] and [`PARTIAL`] must not be enabled. For all other pools, `T` must
+    /// be `u32` or `u64`.
+    ///
     /// `true` is returned if every result was available and written to the buffer. `false`
     /// is returned if some results were not yet available; these will not be written to the buffer.
     ///
     /// See also [`copy_query_pool_results`].
     ///
-    /// [`self.pool().ty().result_len()`]: QueryType::result_len
+    /// [`self.pool().result_len()`]: QueryPool::result_len
     /// [`WITH_AVAILABILITY`]: QueryResultFlags::WITH_AVAILABILITY
+    /// [`PARTIAL`]: QueryResultFlags::PARTIAL
     /// [`copy_query_pool_results`]: crate::command_buffer::AutoCommandBufferBuilder::copy_query_pool_results
     #[inline]
     pub fn get_results<T>(
@@ -396,8 +682,13 @@ impl<'a> QueriesRange<'a> {
         // VUID-vkGetQueryPoolResults-flags-00815
         debug_assert!(buffer_start % std::mem::size_of::<T>() as DeviceSize == 0);
 
+        // VUID-vkGetQueryPoolResults-queryType-03229
+        if !T::is_valid_for(self.pool.query_type) {
+            return Err(GetResultsError::InvalidResultElement);
+        }
+
         let count = self.range.end - self.range.start;
-        let per_query_len = self.pool.query_type.result_len()
+        let per_query_len = self.pool.result_len()
             + flags.intersects(QueryResultFlags::WITH_AVAILABILITY) as DeviceSize;
         let required_len = per_query_len * count as DeviceSize;
 
@@ -410,14 +701,27 @@ impl<'a> QueriesRange<'a> {
         }
 
         match self.pool.query_type {
-            QueryType::Occlusion => (),
-            QueryType::PipelineStatistics(_) => (),
-            QueryType::Timestamp => {
+            QueryType::Occlusion
+            | QueryType::PipelineStatistics(_)
+            | QueryType::TransformFeedbackStream
+            | QueryType::PrimitivesGenerated
+            | QueryType::MeshPrimitivesGenerated => (),
+            QueryType::Timestamp
+            | QueryType::AccelerationStructureCompactedSize
+            | QueryType::AccelerationStructureSerializationSize => {
                 // VUID-vkGetQueryPoolResults-queryType-00818
+                // VUID-vkGetQueryPoolResults-queryType-04810
                 if flags.intersects(QueryResultFlags::PARTIAL) {
                     return Err(GetResultsError::InvalidFlags);
                 }
             }
+            QueryType::Performance => {
+                // VUID-vkGetQueryPoolResults-queryType-03230
+                if flags.intersects(QueryResultFlags::WITH_AVAILABILITY | QueryResultFlags::PARTIAL)
+                {
+                    return Err(GetResultsError::InvalidFlags);
+                }
+            }
         }
 
         Ok(per_query_len * std::mem::size_of::<T>() as DeviceSize)
@@ -448,6 +752,9 @@ pub enum GetResultsError {
 
     /// The provided flags are not allowed for this type of query.
     InvalidFlags,
+
+    /// The result element type is not allowed for this type of query.
+    InvalidResultElement,
 }
 
 impl Error for GetResultsError {
@@ -477,6 +784,10 @@ impl Display for GetResultsError {
                 f,
                 "the provided flags are not allowed for this type of query"
             ),
+            Self::InvalidResultElement => write!(
+                f,
+                "the result element type is not allowed for this type of query"
+            ),
         }
     }
 }
@@ -511,10 +822,17 @@ impl From<RequirementNotMet> for GetResultsError {
 /// A trait for elements of buffers that can be used as a destination for query results.
 ///
 /// # Safety
-/// This is implemented for `u32` and `u64`. Unless you really know what you're doing, you should
-/// not implement this trait for any other type.
+/// This is implemented for `u32`, `u64` and [`PerformanceCounterResult`]. Unless you really know
+/// what you're doing, you should not implement this trait for any other type.
 pub unsafe trait QueryResultElement: BufferContents + Sized {
     const FLAG: ash::vk::QueryResultFlags;
+
+    /// Returns whether this element type can hold the results of queries of type `query_type`.
+    #[doc(hidden)]
+    #[inline]
+    fn is_valid_for(query_type: QueryType) -> bool {
+        !matches!(query_type, QueryType::Performance)
+    }
 }
 
 unsafe impl QueryResultElement for u32 {
@@ -525,6 +843,62 @@ unsafe impl QueryResultElement for u64 {
     const FLAG: ash::vk::QueryResultFlags = ash::vk::QueryResultFlags::TYPE_64;
 }
 
+/// The result of a single performance counter in a [`QueryType::Performance`] query.
+///
+/// How the value must be interpreted depends on the [`storage`] of the counter.
+///
+/// [`storage`]: PerformanceCounter::storage
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq)]
+pub struct PerformanceCounterResult([u8; 8]);
+
+impl PerformanceCounterResult {
+    /// Returns the value of a counter with [`PerformanceCounterStorage::Int32`] storage.
+    #[inline]
+    pub fn as_i32(self) -> i32 {
+        i32::from_ne_bytes(self.0[..4].try_into().unwrap())
+    }
+
+    /// Returns the value of a counter with [`PerformanceCounterStorage::Int64`] storage.
+    #[inline]
+    pub fn as_i64(self) -> i64 {
+        i64::from_ne_bytes(self.0)
+    }
+
+    /// Returns the value of a counter with [`PerformanceCounterStorage::Uint32`] storage.
+    #[inline]
+    pub fn as_u32(self) -> u32 {
+        u32::from_ne_bytes(self.0[..4].try_into().unwrap())
+    }
+
+    /// Returns the value of a counter with [`PerformanceCounterStorage::Uint64`] storage.
+    #[inline]
+    pub fn as_u64(self) -> u64 {
+        u64::from_ne_bytes(self.0)
+    }
+
+    /// Returns the value of a counter with [`PerformanceCounterStorage::Float32`] storage.
+    #[inline]
+    pub fn as_f32(self) -> f32 {
+        f32::from_ne_bytes(self.0[..4].try_into().unwrap())
+    }
+
+    /// Returns the value of a counter with [`PerformanceCounterStorage::Float64`] storage.
+    #[inline]
+    pub fn as_f64(self) -> f64 {
+        f64::from_ne_bytes(self.0)
+    }
+}
+
+unsafe impl QueryResultElement for PerformanceCounterResult {
+    const FLAG: ash::vk::QueryResultFlags = ash::vk::QueryResultFlags::empty();
+
+    #[inline]
+    fn is_valid_for(query_type: QueryType) -> bool {
+        matches!(query_type, QueryType::Performance)
+    }
+}
+
 /// The type of query that a query pool should perform.
 #[derive(Debug, Copy, Clone)]
 pub enum QueryType {
@@ -534,14 +908,66 @@ pub enum QueryType {
     PipelineStatistics(QueryPipelineStatisticFlags),
     /// Writes timestamps at chosen points in a command buffer.
     Timestamp,
+    /// Queries the size of an acceleration structure after compaction, written with
+    /// [`write_acceleration_structures_properties`].
+    ///
+    /// The device extension [`khr_acceleration_structure`] must be enabled on the device.
+    ///
+    /// [`write_acceleration_structures_properties`]: crate::command_buffer::AutoCommandBufferBuilder::write_acceleration_structures_properties
+    /// [`khr_acceleration_structure`]: crate::device::DeviceExtensions::khr_acceleration_structure
+    AccelerationStructureCompactedSize,
+    /// Counts the number of primitives that were written to the transform feedback buffers for
+    /// vertex stream 0, and the number of primitives that would have been written if the buffers
+    /// had been large enough.
+    ///
+    /// The device extension [`ext_transform_feedback`] must be enabled on the device, and the
+    /// [`transform_feedback_queries`] property must be supported to begin queries of this type.
+    ///
+    /// [`ext_transform_feedback`]: crate::device::DeviceExtensions::ext_transform_feedback
+    /// [`transform_feedback_queries`]: crate::device::Properties::transform_feedback_queries
+    TransformFeedbackStream,
+    /// Counts the number of primitives generated by the pipeline, for vertex stream 0.
+    ///
+    /// The device extension [`ext_primitives_generated_query`] must be enabled on the device, and
+    /// the [`primitives_generated_query`] feature must be enabled to begin queries of this type.
+    ///
+    /// [`ext_primitives_generated_query`]: crate::device::DeviceExtensions::ext_primitives_generated_query
+    /// [`primitives_generated_query`]: crate::device::Features::primitives_generated_query
+    PrimitivesGenerated,
+    /// Counts the number of primitives generated by mesh shaders.
+    ///
+    /// The [`mesh_shader_queries`] feature must be enabled on the device.
+    ///
+    /// [`mesh_shader_queries`]: crate::device::Features::mesh_shader_queries
+    MeshPrimitivesGenerated,
+    /// Queries the size that an acceleration structure would need when serialized, written with
+    /// [`write_acceleration_structures_properties`].
+    ///
+    /// The device extension [`khr_acceleration_structure`] must be enabled on the device.
+    ///
+    /// [`write_acceleration_structures_properties`]: crate::command_buffer::AutoCommandBufferBuilder::write_acceleration_structures_properties
+    /// [`khr_acceleration_structure`]: crate::device::DeviceExtensions::khr_acceleration_structure
+    AccelerationStructureSerializationSize,
+    /// Gathers the values of a set of performance counters, chosen with
+    /// [`QueryPoolCreateInfo::performance_query`].
+    ///
+    /// The [`performance_counter_query_pools`] feature must be enabled on the device.
+    ///
+    /// [`performance_counter_query_pools`]: crate::device::Features::performance_counter_query_pools
+    Performance,
 }
 
 impl QueryType {
     /// Returns the number of [`QueryResultElement`]s that are needed to hold the result of a
     /// single query of this type.
     ///
-    /// - For [`Occlusion`] and [`Timestamp`] queries, this returns 1.
+    /// - For [`Occlusion`], [`Timestamp`], [`AccelerationStructureCompactedSize`],
+    ///   [`AccelerationStructureSerializationSize`], [`PrimitivesGenerated`] and
+    ///   [`MeshPrimitivesGenerated`] queries, this returns 1.
     /// - For [`PipelineStatistics`] queries, this returns the number of statistics flags enabled.
+    /// - For [`TransformFeedbackStream`] queries, this returns 2.
+    /// - For [`Performance`] queries, the length depends on the number of counters that the pool
+    ///   was created with, so this returns 0. Use [`QueryPool::result_len`] instead.
     ///
     /// If the results are retrieved with [`WITH_AVAILABILITY`] enabled, then an additional element
     /// is required per query.
@@ -549,12 +975,25 @@ impl QueryType {
     /// [`Occlusion`]: QueryType::Occlusion
     /// [`Timestamp`]: QueryType::Timestamp
     /// [`PipelineStatistics`]: QueryType::PipelineStatistics
+    /// [`AccelerationStructureCompactedSize`]: QueryType::AccelerationStructureCompactedSize
+    /// [`TransformFeedbackStream`]: QueryType::TransformFeedbackStream
+    /// [`AccelerationStructureSerializationSize`]: QueryType::AccelerationStructureSerializationSize
+    /// [`PrimitivesGenerated`]: QueryType::PrimitivesGenerated
+    /// [`MeshPrimitivesGenerated`]: QueryType::MeshPrimitivesGenerated
+    /// [`Performance`]: QueryType::Performance
     /// [`WITH_AVAILABILITY`]: QueryResultFlags::WITH_AVAILABILITY
     #[inline]
     pub const fn result_len(self) -> DeviceSize {
         match self {
-            Self::Occlusion | Self::Timestamp => 1,
+            Self::Occlusion
+            | Self::Timestamp
+            | Self::AccelerationStructureCompactedSize
+            | Self::AccelerationStructureSerializationSize
+            | Self::PrimitivesGenerated
+            | Self::MeshPrimitivesGenerated => 1,
             Self::PipelineStatistics(flags) => flags.count() as DeviceSize,
+            Self::TransformFeedbackStream => 2,
+            Self::Performance => 0,
         }
     }
 }
@@ -566,6 +1005,16 @@ impl From<QueryType> for ash::vk::QueryType {
             QueryType::Occlusion => ash::vk::QueryType::OCCLUSION,
             QueryType::PipelineStatistics(_) => ash::vk::QueryType::PIPELINE_STATISTICS,
             QueryType::Timestamp => ash::vk::QueryType::TIMESTAMP,
+            QueryType::AccelerationStructureCompactedSize => {
+                ash::vk::QueryType::ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR
+            }
+            QueryType::TransformFeedbackStream => ash::vk::QueryType::TRANSFORM_FEEDBACK_STREAM_EXT,
+            QueryType::PrimitivesGenerated => ash::vk::QueryType::PRIMITIVES_GENERATED_EXT,
+            QueryType::MeshPrimitivesGenerated => ash::vk::QueryType::MESH_PRIMITIVES_GENERATED_EXT,
+            QueryType::AccelerationStructureSerializationSize => {
+                ash::vk::QueryType::ACCELERATION_STRUCTURE_SERIALIZATION_SIZE_KHR
+            }
+            QueryType::Performance => ash::vk::QueryType::PERFORMANCE_QUERY_KHR,
         }
     }
 }
@@ -606,7 +1055,9 @@ vulkan_bitflags! {
                     .union(QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES)
                     .union(QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS)
                     .union(QueryPipelineStatisticFlags::TESSELLATION_CONTROL_SHADER_PATCHES)
-                    .union(QueryPipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS),
+                    .union(QueryPipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS)
+                    .union(QueryPipelineStatisticFlags::TASK_SHADER_INVOCATIONS)
+                    .union(QueryPipelineStatisticFlags::MESH_SHADER_INVOCATIONS),
             )
         }
     }
@@ -645,17 +1096,15 @@ vulkan_bitflags! {
     /// Count the number of times a compute shader is invoked.
     COMPUTE_SHADER_INVOCATIONS = COMPUTE_SHADER_INVOCATIONS,
 
-    /* TODO: enable
-    // TODO: document
-    TASK_SHADER_INVOCATIONS = TASK_SHADER_INVOCATIONS_NV {
-        device_extensions: [nv_mesh_shader],
-    },*/
+    /// Count the number of times a task shader is invoked.
+    TASK_SHADER_INVOCATIONS = TASK_SHADER_INVOCATIONS_EXT {
+        device_extensions: [ext_mesh_shader],
+    },
 
-    /* TODO: enable
-    // TODO: document
-    MESH_SHADER_INVOCATIONS = MESH_SHADER_INVOCATIONS_NV {
-        device_extensions: [nv_mesh_shader],
-    },*/
+    /// Count the number of times a mesh shader is invoked.
+    MESH_SHADER_INVOCATIONS = MESH_SHADER_INVOCATIONS_EXT {
+        device_extensions: [ext_mesh_shader],
+    },
 }
 
 vulkan_bitflags! {
@@ -687,6 +1136,138 @@ vulkan_bitflags! {
     },*/
 }
 
+/// Describes a performance counter that can be gathered with a [`QueryType::Performance`] query.
+///
+/// This is returned by [`PhysicalDevice::queue_family_performance_query_counters`].
+///
+/// [`PhysicalDevice::queue_family_performance_query_counters`]: crate::device::physical::PhysicalDevice::queue_family_performance_query_counters
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PerformanceCounter {
+    /// The unit that the counter's value is expressed in.
+    pub unit: PerformanceCounterUnit,
+
+    /// The scope over which the counter is gathered.
+    pub scope: PerformanceCounterScope,
+
+    /// How the counter's value is stored in a [`PerformanceCounterResult`].
+    pub storage: PerformanceCounterStorage,
+
+    /// A unique identifier for the counter, which stays the same across devices and driver
+    /// versions.
+    pub uuid: [u8; 16],
+
+    /// Additional information about the counter.
+    pub flags: PerformanceCounterDescriptionFlags,
+
+    /// The name of the counter.
+    pub name: String,
+
+    /// The category that the counter belongs to.
+    pub category: String,
+
+    /// A description of the counter.
+    pub description: String,
+}
+
+vulkan_enum! {
+    #[non_exhaustive]
+
+    /// The unit that a performance counter's value is expressed in.
+    PerformanceCounterUnit = PerformanceCounterUnitKHR(i32);
+
+    /// A dimensionless value.
+    Generic = GENERIC,
+
+    /// A percentage, ranging from 0 to 100.
+    Percentage = PERCENTAGE,
+
+    /// A number of nanoseconds.
+    Nanoseconds = NANOSECONDS,
+
+    /// A number of bytes.
+    Bytes = BYTES,
+
+    /// A number of bytes per second.
+    BytesPerSecond = BYTES_PER_SECOND,
+
+    /// A temperature in degrees Kelvin.
+    Kelvin = KELVIN,
+
+    /// A power in watts.
+    Watts = WATTS,
+
+    /// An electric potential in volts.
+    Volts = VOLTS,
+
+    /// An electric current in amperes.
+    Amps = AMPS,
+
+    /// A frequency in hertz.
+    Hertz = HERTZ,
+
+    /// A number of clock cycles.
+    Cycles = CYCLES,
+}
+
+vulkan_enum! {
+    #[non_exhaustive]
+
+    /// The scope over which a performance counter is gathered.
+    PerformanceCounterScope = PerformanceCounterScopeKHR(i32);
+
+    /// The counter is gathered over a whole command buffer. The query must be begun before, and
+    /// ended after, any other command in the command buffer.
+    CommandBuffer = COMMAND_BUFFER,
+
+    /// The counter is gathered over a whole render pass instance. The query must be begun and
+    /// ended outside of the render pass instance.
+    RenderPass = RENDER_PASS,
+
+    /// The counter is gathered over a single command. The query must only contain a single
+    /// command.
+    Command = COMMAND,
+}
+
+vulkan_enum! {
+    #[non_exhaustive]
+
+    /// How the value of a performance counter is stored in a [`PerformanceCounterResult`].
+    PerformanceCounterStorage = PerformanceCounterStorageKHR(i32);
+
+    /// A signed 32-bit integer, read with [`PerformanceCounterResult::as_i32`].
+    Int32 = INT32,
+
+    /// A signed 64-bit integer, read with [`PerformanceCounterResult::as_i64`].
+    Int64 = INT64,
+
+    /// An unsigned 32-bit integer, read with [`PerformanceCounterResult::as_u32`].
+    Uint32 = UINT32,
+
+    /// An unsigned 64-bit integer, read with [`PerformanceCounterResult::as_u64`].
+    Uint64 = UINT64,
+
+    /// A 32-bit floating point value, read with [`PerformanceCounterResult::as_f32`].
+    Float32 = FLOAT32,
+
+    /// A 64-bit floating point value, read with [`PerformanceCounterResult::as_f64`].
+    Float64 = FLOAT64,
+}
+
+vulkan_bitflags! {
+    #[non_exhaustive]
+
+    /// Additional information about a performance counter.
+    PerformanceCounterDescriptionFlags = PerformanceCounterDescriptionFlagsKHR(u32);
+
+    /// Gathering the counter has a significant impact on the performance of the device.
+    PERFORMANCE_IMPACTING = PERFORMANCE_IMPACTING,
+
+    /// Gathering the counter concurrently with other counters of the same queue family may
+    /// affect its accuracy.
+    CONCURRENTLY_IMPACTED = CONCURRENTLY_IMPACTED,
+}
+
 #[cfg(test)]
 mod tests {
     use super::QueryPoolCreateInfo;
@@ -707,4 +1288,34 @@ mod tests {
             _ => panic!(),
         };
     }
+
+    #[test]
+    fn transform_feedback_extension() {
+        let (device, _) = gfx_dev_and_queue!();
+        match QueryPool::new(
+            device,
+            QueryPoolCreateInfo {
+                query_count: 256,
+                ..QueryPoolCreateInfo::query_type(QueryType::TransformFeedbackStream)
+            },
+        ) {
+            Err(QueryPoolCreationError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        };
+    }
+
+    #[test]
+    fn primitives_generated_extension() {
+        let (device, _) = gfx_dev_and_queue!();
+        match QueryPool::new(
+            device,
+            QueryPoolCreateInfo {
+                query_count: 256,
+                ..QueryPoolCreateInfo::query_type(QueryType::PrimitivesGenerated)
+            },
+        ) {
+            Err(QueryPoolCreationError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        };
+    }
 }
@@ -470,8 +470,8 @@ impl From<&ShaderExecution> for ExecutionModel {
             ShaderExecution::Miss => Self::MissKHR,
             ShaderExecution::Intersection => Self::IntersectionKHR,
             ShaderExecution::Callable => Self::CallableKHR,
-            ShaderExecution::Task => Self::TaskNV,
-            ShaderExecution::Mesh => Self::MeshNV,
+            ShaderExecution::Task => Self::TaskEXT,
+            ShaderExecution::Mesh => Self::MeshEXT,
             ShaderExecution::SubpassShading => todo!(),
         }
     }
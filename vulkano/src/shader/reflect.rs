@@ -94,7 +94,12 @@ pub fn entry_points(spirv: &Spirv) -> impl Iterator<Item = EntryPointInfo> + '_
             spirv,
             interface,
             StorageClass::Output,
-            matches!(execution_model, ExecutionModel::TessellationControl),
+            matches!(
+                execution_model,
+                ExecutionModel::TessellationControl
+                    | ExecutionModel::MeshNV
+                    | ExecutionModel::MeshEXT
+            ),
         );
 
         Some(EntryPointInfo {
@@ -196,8 +201,8 @@ fn shader_execution(
         ExecutionModel::MissKHR => ShaderExecution::Miss,
         ExecutionModel::CallableKHR => ShaderExecution::Callable,
 
-        ExecutionModel::TaskNV => ShaderExecution::Task,
-        ExecutionModel::MeshNV => ShaderExecution::Mesh,
+        ExecutionModel::TaskNV | ExecutionModel::TaskEXT => ShaderExecution::Task,
+        ExecutionModel::MeshNV | ExecutionModel::MeshEXT => ShaderExecution::Mesh,
 
         ExecutionModel::Kernel => todo!(),
     }
@@ -950,7 +955,11 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
                 Some(element_type)
             }
 
-            Instruction::TypeAccelerationStructureKHR { .. } => None, // FIXME temporary workaround
+            Instruction::TypeAccelerationStructureKHR { .. } => {
+                reqs.descriptor_types = vec![DescriptorType::AccelerationStructure];
+
+                None
+            }
 
             _ => {
                 let name = variable_id_info
@@ -1466,3 +1475,37 @@ fn is_builtin(spirv: &Spirv, id: Id) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{entry_points, spirv_capabilities, spirv_extensions};
+    use crate::{
+        pipeline::graphics::tests::{MESH_MODULE, TASK_MODULE},
+        shader::{
+            spirv::{Capability, Spirv},
+            ShaderExecution,
+        },
+    };
+
+    #[test]
+    fn mesh_shading_entry_points() {
+        for (bytes, execution) in [
+            (&TASK_MODULE[..], ShaderExecution::Task),
+            (&MESH_MODULE[..], ShaderExecution::Mesh),
+        ] {
+            let words: Vec<u32> = bytes
+                .chunks_exact(4)
+                .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+                .collect();
+            let spirv = Spirv::new(&words).unwrap();
+
+            assert!(spirv_capabilities(&spirv).any(|&c| c == Capability::MeshShadingEXT));
+            assert!(spirv_extensions(&spirv).any(|e| e == "SPV_EXT_mesh_shader"));
+
+            let entry_points: Vec<_> = entry_points(&spirv).collect();
+            assert_eq!(entry_points.len(), 1);
+            assert_eq!(entry_points[0].name, "main");
+            assert_eq!(entry_points[0].execution, execution);
+        }
+    }
+}
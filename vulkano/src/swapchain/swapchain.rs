@@ -13,6 +13,7 @@ use super::{
 };
 use crate::{
     buffer::Buffer,
+    command_buffer::{SemaphoreSubmitInfo, SubmitInfo},
     device::{Device, DeviceOwned, Queue},
     format::Format,
     image::{
@@ -24,8 +25,8 @@ use crate::{
     sync::{
         fence::{Fence, FenceError},
         future::{AccessCheckError, AccessError, FlushError, GpuFuture, SubmitAnyBuilder},
-        semaphore::{Semaphore, SemaphoreError},
-        Sharing,
+        semaphore::{Semaphore, SemaphoreError, SemaphoreType},
+        PipelineStages, Sharing,
     },
     DeviceSize, OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
 };
@@ -1656,7 +1657,7 @@ unsafe impl GpuFuture for SwapchainAcquireFuture {
 
     unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
         if let Some(ref semaphore) = self.semaphore {
-            let sem = smallvec![semaphore.clone()];
+            let sem = smallvec![SemaphoreSubmitInfo::semaphore(semaphore.clone())];
             Ok(SubmitAnyBuilder::SemaphoresWait(sem))
         } else {
             Ok(SubmitAnyBuilder::Empty)
@@ -2024,8 +2025,44 @@ where
                 ..Default::default()
             }),
             SubmitAnyBuilder::SemaphoresWait(semaphores) => {
+                let (timeline_waits, binary_waits): (Vec<_>, Vec<_>) =
+                    semaphores.into_iter().partition(|semaphore_submit_info| {
+                        semaphore_submit_info.semaphore.semaphore_type() == SemaphoreType::Timeline
+                    });
+                let mut wait_semaphores: Vec<_> = binary_waits
+                    .into_iter()
+                    .map(|semaphore_submit_info| semaphore_submit_info.semaphore)
+                    .collect();
+
+                // Presenting can only wait on binary semaphores, so the timeline semaphores are
+                // waited on by a separate submission that signals a binary semaphore instead.
+                if !timeline_waits.is_empty() {
+                    let semaphore = Arc::new(Semaphore::from_pool(self.queue.device().clone())?);
+
+                    self.queue.with(|mut q| {
+                        q.submit_unchecked(
+                            [SubmitInfo {
+                                wait_semaphores: timeline_waits
+                                    .into_iter()
+                                    .map(|semaphore_submit_info| SemaphoreSubmitInfo {
+                                        stages: PipelineStages::ALL_COMMANDS,
+                                        ..semaphore_submit_info
+                                    })
+                                    .collect(),
+                                signal_semaphores: vec![SemaphoreSubmitInfo::semaphore(
+                                    semaphore.clone(),
+                                )],
+                                ..Default::default()
+                            }],
+                            None,
+                        )
+                    })?;
+
+                    wait_semaphores.push(semaphore);
+                }
+
                 SubmitAnyBuilder::QueuePresent(PresentInfo {
-                    wait_semaphores: semaphores.into_iter().collect(),
+                    wait_semaphores,
                     swapchain_infos: vec![self.swapchain_info.clone()],
                     ..Default::default()
                 })
@@ -177,6 +177,18 @@ where
             _ => unreachable!(),
         }
     }
+
+    /// Returns the fence that is signaled by the future, or `None` if the future was already
+    /// cleaned up, in which case the fence has been signaled.
+    pub(crate) fn fence(&self) -> Option<Arc<Fence>> {
+        match &*self.state.lock() {
+            FenceSignalFutureState::Pending(_, fence)
+            | FenceSignalFutureState::PartiallyFlushed(_, fence)
+            | FenceSignalFutureState::Flushed(_, fence) => Some(fence.clone()),
+            FenceSignalFutureState::Cleaned => None,
+            FenceSignalFutureState::Poisoned => unreachable!(),
+        }
+    }
 }
 
 impl<F> FenceSignalFuture<F>
@@ -261,11 +273,11 @@ where
                                 [SubmitInfo {
                                     wait_semaphores: semaphores
                                         .into_iter()
-                                        .map(|semaphore| {
+                                        .map(|semaphore_submit_info| {
                                             SemaphoreSubmitInfo {
                                                 // TODO: correct stages ; hard
                                                 stages: PipelineStages::ALL_COMMANDS,
-                                                ..SemaphoreSubmitInfo::semaphore(semaphore)
+                                                ..semaphore_submit_info
                                             }
                                         })
                                         .collect(),
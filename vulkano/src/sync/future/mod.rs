@@ -95,16 +95,17 @@ pub use self::{
     join::JoinFuture,
     now::{now, NowFuture},
     semaphore_signal::SemaphoreSignalFuture,
+    semaphore_wait::{timeline_semaphore_wait, TimelineSemaphoreWaitFuture},
 };
 use super::{
     fence::{Fence, FenceError},
-    semaphore::Semaphore,
+    semaphore::{Semaphore, SemaphoreError},
 };
 use crate::{
     buffer::Buffer,
     command_buffer::{
         CommandBufferExecError, CommandBufferExecFuture, PrimaryCommandBufferAbstract,
-        ResourceUseRef, SubmitInfo,
+        ResourceUseRef, SemaphoreSubmitInfo, SubmitInfo,
     },
     device::{DeviceOwned, Queue},
     image::{sys::Image, ImageLayout},
@@ -124,6 +125,7 @@ mod fence_signal;
 mod join;
 mod now;
 mod semaphore_signal;
+mod semaphore_wait;
 
 /// Represents an event that will happen on the GPU in the future.
 ///
@@ -312,6 +314,47 @@ pub unsafe trait GpuFuture: DeviceOwned {
         Ok(f)
     }
 
+    /// Signals a timeline semaphore with `value` after this future. Returns another future that
+    /// represents the signal.
+    ///
+    /// Other queues, processes or the host can wait for the semaphore to reach `value`. To wait
+    /// for it with a future, use [`timeline_semaphore_wait`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `semaphore` is not a timeline semaphore.
+    /// - Panics if `semaphore` was not created with the same device as `self`.
+    #[inline]
+    fn then_signal_timeline_semaphore(
+        self,
+        semaphore: Arc<Semaphore>,
+        value: u64,
+    ) -> SemaphoreSignalFuture<Self>
+    where
+        Self: Sized,
+    {
+        semaphore_signal::then_signal_timeline_semaphore(self, semaphore, value)
+    }
+
+    /// Signals a timeline semaphore with `value` after this future and flushes it. Returns another
+    /// future that represents the signal.
+    ///
+    /// This is a just a shortcut for `then_signal_timeline_semaphore()` followed with `flush()`.
+    #[inline]
+    fn then_signal_timeline_semaphore_and_flush(
+        self,
+        semaphore: Arc<Semaphore>,
+        value: u64,
+    ) -> Result<SemaphoreSignalFuture<Self>, FlushError>
+    where
+        Self: Sized,
+    {
+        let f = self.then_signal_timeline_semaphore(semaphore, value);
+        f.flush()?;
+
+        Ok(f)
+    }
+
     /// Signals a fence after this future. Returns another future that represents the signal.
     ///
     /// > **Note**: More often than not you want to immediately flush the future after calling this
@@ -466,7 +509,7 @@ where
 #[derive(Debug)]
 pub enum SubmitAnyBuilder {
     Empty,
-    SemaphoresWait(SmallVec<[Arc<Semaphore>; 8]>),
+    SemaphoresWait(SmallVec<[SemaphoreSubmitInfo; 8]>),
     CommandBuffer(SubmitInfo, Option<Arc<Fence>>),
     QueuePresent(PresentInfo),
     BindSparse(SmallVec<[BindSparseInfo; 1]>, Option<Arc<Fence>>),
@@ -681,6 +724,17 @@ impl From<VulkanError> for FlushError {
     }
 }
 
+impl From<SemaphoreError> for FlushError {
+    fn from(err: SemaphoreError) -> FlushError {
+        match err {
+            SemaphoreError::OomError(err) => FlushError::OomError(err),
+            SemaphoreError::Timeout => FlushError::Timeout,
+            SemaphoreError::DeviceLost => FlushError::DeviceLost,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<FenceError> for FlushError {
     fn from(err: FenceError) -> FlushError {
         match err {
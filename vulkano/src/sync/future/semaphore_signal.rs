@@ -14,7 +14,11 @@ use crate::{
     device::{Device, DeviceOwned, Queue},
     image::{sys::Image, ImageLayout},
     swapchain::Swapchain,
-    sync::{future::AccessError, semaphore::Semaphore, PipelineStages},
+    sync::{
+        future::AccessError,
+        semaphore::{Semaphore, SemaphoreType},
+        PipelineStages,
+    },
     DeviceSize,
 };
 use parking_lot::Mutex;
@@ -40,6 +44,29 @@ where
     SemaphoreSignalFuture {
         previous: future,
         semaphore: Arc::new(Semaphore::from_pool(device).unwrap()),
+        value: 0,
+        wait_submitted: Mutex::new(false),
+        finished: AtomicBool::new(false),
+    }
+}
+
+/// Builds a new timeline semaphore signal future.
+pub fn then_signal_timeline_semaphore<F>(
+    future: F,
+    semaphore: Arc<Semaphore>,
+    value: u64,
+) -> SemaphoreSignalFuture<F>
+where
+    F: GpuFuture,
+{
+    assert_eq!(future.device(), semaphore.device());
+    assert_eq!(semaphore.semaphore_type(), SemaphoreType::Timeline);
+    assert!(future.queue().is_some()); // TODO: document
+
+    SemaphoreSignalFuture {
+        previous: future,
+        semaphore,
+        value,
         wait_submitted: Mutex::new(false),
         finished: AtomicBool::new(false),
     }
@@ -54,6 +81,8 @@ where
 {
     previous: F,
     semaphore: Arc<Semaphore>,
+    // The value that a timeline semaphore is signaled with.
+    value: u64,
     // True if the signaling command has already been submitted.
     // If flush is called multiple times, we want to block so that only one flushing is executed.
     // Therefore we use a `Mutex<bool>` and not an `AtomicBool`.
@@ -61,6 +90,18 @@ where
     finished: AtomicBool,
 }
 
+impl<F> SemaphoreSignalFuture<F>
+where
+    F: GpuFuture,
+{
+    fn semaphore_submit_info(&self) -> SemaphoreSubmitInfo {
+        SemaphoreSubmitInfo {
+            value: self.value,
+            ..SemaphoreSubmitInfo::semaphore(self.semaphore.clone())
+        }
+    }
+}
+
 unsafe impl<F> GpuFuture for SemaphoreSignalFuture<F>
 where
     F: GpuFuture,
@@ -72,7 +113,7 @@ where
     unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
         // Flushing the signaling part, since it must always be submitted before the waiting part.
         self.flush()?;
-        let sem = smallvec![self.semaphore.clone()];
+        let sem = smallvec![self.semaphore_submit_info()];
 
         Ok(SubmitAnyBuilder::SemaphoresWait(sem))
     }
@@ -92,9 +133,7 @@ where
                     queue.with(|mut q| {
                         q.submit_unchecked(
                             [SubmitInfo {
-                                signal_semaphores: vec![SemaphoreSubmitInfo::semaphore(
-                                    self.semaphore.clone(),
-                                )],
+                                signal_semaphores: vec![self.semaphore_submit_info()],
                                 ..Default::default()
                             }],
                             None,
@@ -107,17 +146,15 @@ where
                             [SubmitInfo {
                                 wait_semaphores: semaphores
                                     .into_iter()
-                                    .map(|semaphore| {
+                                    .map(|semaphore_submit_info| {
                                         SemaphoreSubmitInfo {
                                             // TODO: correct stages ; hard
                                             stages: PipelineStages::ALL_COMMANDS,
-                                            ..SemaphoreSubmitInfo::semaphore(semaphore)
+                                            ..semaphore_submit_info
                                         }
                                     })
                                     .collect(),
-                                signal_semaphores: vec![SemaphoreSubmitInfo::semaphore(
-                                    self.semaphore.clone(),
-                                )],
+                                signal_semaphores: vec![self.semaphore_submit_info()],
                                 ..Default::default()
                             }],
                             None,
@@ -129,7 +166,7 @@ where
 
                     submit_info
                         .signal_semaphores
-                        .push(SemaphoreSubmitInfo::semaphore(self.semaphore.clone()));
+                        .push(self.semaphore_submit_info());
 
                     queue.with(|mut q| {
                         q.submit_with_future(submit_info, fence, &self.previous, &queue)
@@ -170,9 +207,7 @@ where
                         // FIXME: problematic because if we return an error and flush() is called again, then we'll submit the present twice
                         q.submit_unchecked(
                             [SubmitInfo {
-                                signal_semaphores: vec![SemaphoreSubmitInfo::semaphore(
-                                    self.semaphore.clone(),
-                                )],
+                                signal_semaphores: vec![self.semaphore_submit_info()],
                                 ..Default::default()
                             }],
                             None,
@@ -0,0 +1,123 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use super::{AccessCheckError, FlushError, GpuFuture, SubmitAnyBuilder};
+use crate::{
+    buffer::Buffer,
+    command_buffer::SemaphoreSubmitInfo,
+    device::{Device, DeviceOwned, Queue},
+    image::{sys::Image, ImageLayout},
+    swapchain::Swapchain,
+    sync::semaphore::{Semaphore, SemaphoreType},
+    DeviceSize,
+};
+use smallvec::smallvec;
+use std::{ops::Range, sync::Arc};
+
+/// Builds a future that represents the counter value of a timeline semaphore reaching `value`.
+///
+/// The semaphore can be signaled by another future with
+/// [`then_signal_timeline_semaphore`](GpuFuture::then_signal_timeline_semaphore), by another
+/// queue or process, or from the host with [`Semaphore::signal`].
+///
+/// # Panics
+///
+/// - Panics if `semaphore` is not a timeline semaphore.
+#[inline]
+pub fn timeline_semaphore_wait(
+    semaphore: Arc<Semaphore>,
+    value: u64,
+) -> TimelineSemaphoreWaitFuture {
+    assert_eq!(semaphore.semaphore_type(), SemaphoreType::Timeline);
+
+    TimelineSemaphoreWaitFuture { semaphore, value }
+}
+
+/// Represents the counter value of a timeline semaphore reaching a value.
+///
+/// Operations that are executed after this future wait for the semaphore on the GPU. Because the
+/// semaphore can be signaled from anywhere, this future doesn't know which resources are in use,
+/// and it never blocks when dropped.
+pub struct TimelineSemaphoreWaitFuture {
+    semaphore: Arc<Semaphore>,
+    value: u64,
+}
+
+unsafe impl GpuFuture for TimelineSemaphoreWaitFuture {
+    #[inline]
+    fn cleanup_finished(&mut self) {}
+
+    #[inline]
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        Ok(SubmitAnyBuilder::SemaphoresWait(smallvec![
+            SemaphoreSubmitInfo {
+                value: self.value,
+                ..SemaphoreSubmitInfo::semaphore(self.semaphore.clone())
+            }
+        ]))
+    }
+
+    #[inline]
+    fn flush(&self) -> Result<(), FlushError> {
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn signal_finished(&self) {}
+
+    #[inline]
+    fn queue_change_allowed(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn queue(&self) -> Option<Arc<Queue>> {
+        None
+    }
+
+    #[inline]
+    fn check_buffer_access(
+        &self,
+        _buffer: &Buffer,
+        _range: Range<DeviceSize>,
+        _exclusive: bool,
+        _queue: &Queue,
+    ) -> Result<(), AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+
+    #[inline]
+    fn check_image_access(
+        &self,
+        _image: &Image,
+        _range: Range<DeviceSize>,
+        _exclusive: bool,
+        _expected_layout: ImageLayout,
+        _queue: &Queue,
+    ) -> Result<(), AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+
+    #[inline]
+    fn check_swapchain_image_acquired(
+        &self,
+        _swapchain: &Swapchain,
+        _image_index: u32,
+        _before: bool,
+    ) -> Result<(), AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+}
+
+unsafe impl DeviceOwned for TimelineSemaphoreWaitFuture {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.semaphore.device()
+    }
+}
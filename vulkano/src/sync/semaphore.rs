@@ -12,10 +12,11 @@
 
 use crate::{
     device::{Device, DeviceOwned, Queue},
-    macros::{impl_id_counter, vulkan_bitflags, vulkan_bitflags_enum},
+    macros::{impl_id_counter, vulkan_bitflags, vulkan_bitflags_enum, vulkan_enum},
     OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
 use parking_lot::{Mutex, MutexGuard};
+use smallvec::SmallVec;
 #[cfg(unix)]
 use std::fs::File;
 use std::{
@@ -25,12 +26,17 @@ use std::{
     num::NonZeroU64,
     ptr,
     sync::{Arc, Weak},
+    time::Duration,
 };
 
 /// Used to provide synchronization between command buffers during their execution.
 ///
-/// It is similar to a fence, except that it is purely on the GPU side. The CPU can't query a
-/// semaphore's status or wait for it to be signaled.
+/// A binary semaphore is similar to a fence, except that it is purely on the GPU side. The CPU
+/// can't query a binary semaphore's status or wait for it to be signaled.
+///
+/// A timeline semaphore instead holds a 64-bit counter value that only ever increases. Queue
+/// operations can wait until the counter reaches a value, or set it to a new value. The counter
+/// can also be read, signaled and waited on from the host.
 #[derive(Debug)]
 pub struct Semaphore {
     handle: ash::vk::Semaphore,
@@ -38,6 +44,7 @@ pub struct Semaphore {
     id: NonZeroU64,
     must_put_in_pool: bool,
 
+    semaphore_type: SemaphoreType,
     export_handle_types: ExternalSemaphoreHandleTypes,
 
     state: Mutex<SemaphoreState>,
@@ -60,10 +67,37 @@ impl Semaphore {
         create_info: &SemaphoreCreateInfo,
     ) -> Result<(), SemaphoreError> {
         let &SemaphoreCreateInfo {
+            semaphore_type,
+            initial_value,
             export_handle_types,
             _ne: _,
         } = create_info;
 
+        // VUID-VkSemaphoreTypeCreateInfo-semaphoreType-parameter
+        semaphore_type.validate_device(device)?;
+
+        match semaphore_type {
+            SemaphoreType::Binary => {
+                // VUID-VkSemaphoreTypeCreateInfo-semaphoreType-03279
+                if initial_value != 0 {
+                    return Err(SemaphoreError::BinaryInitialValueNotZero);
+                }
+            }
+            SemaphoreType::Timeline => {
+                // VUID-VkSemaphoreTypeCreateInfo-timelineSemaphore-03252
+                if !device.enabled_features().timeline_semaphore {
+                    return Err(SemaphoreError::RequirementNotMet {
+                        required_for: "`create_info.semaphore_type` is \
+                            `SemaphoreType::Timeline`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["timeline_semaphore"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+        }
+
         if !export_handle_types.is_empty() {
             if !(device.api_version() >= Version::V1_1
                 || device.enabled_extensions().khr_external_semaphore)
@@ -86,9 +120,10 @@ impl Semaphore {
                 let external_semaphore_properties = unsafe {
                     device
                         .physical_device()
-                        .external_semaphore_properties_unchecked(
-                            ExternalSemaphoreInfo::handle_type(handle_type),
-                        )
+                        .external_semaphore_properties_unchecked(ExternalSemaphoreInfo {
+                            semaphore_type,
+                            ..ExternalSemaphoreInfo::handle_type(handle_type)
+                        })
                 };
 
                 if !external_semaphore_properties.exportable {
@@ -114,6 +149,8 @@ impl Semaphore {
         create_info: SemaphoreCreateInfo,
     ) -> Result<Semaphore, VulkanError> {
         let SemaphoreCreateInfo {
+            semaphore_type,
+            initial_value,
             export_handle_types,
             _ne: _,
         } = create_info;
@@ -122,8 +159,20 @@ impl Semaphore {
             flags: ash::vk::SemaphoreCreateFlags::empty(),
             ..Default::default()
         };
+        let mut semaphore_type_create_info_vk = None;
         let mut export_semaphore_create_info_vk = None;
 
+        if semaphore_type != SemaphoreType::Binary {
+            let next = semaphore_type_create_info_vk.insert(ash::vk::SemaphoreTypeCreateInfo {
+                semaphore_type: semaphore_type.into(),
+                initial_value,
+                ..Default::default()
+            });
+
+            next.p_next = create_info_vk.p_next;
+            create_info_vk.p_next = next as *const _ as *const _;
+        }
+
         if !export_handle_types.is_empty() {
             let _ = export_semaphore_create_info_vk.insert(ash::vk::ExportSemaphoreCreateInfo {
                 handle_types: export_handle_types.into(),
@@ -155,6 +204,7 @@ impl Semaphore {
             device,
             id: Self::next_id(),
             must_put_in_pool: false,
+            semaphore_type,
             export_handle_types,
             state: Mutex::new(Default::default()),
         })
@@ -175,6 +225,7 @@ impl Semaphore {
                 device,
                 id: Self::next_id(),
                 must_put_in_pool: true,
+                semaphore_type: SemaphoreType::Binary,
                 export_handle_types: ExternalSemaphoreHandleTypes::empty(),
                 state: Mutex::new(Default::default()),
             },
@@ -202,6 +253,8 @@ impl Semaphore {
         create_info: SemaphoreCreateInfo,
     ) -> Semaphore {
         let SemaphoreCreateInfo {
+            semaphore_type,
+            initial_value: _,
             export_handle_types,
             _ne: _,
         } = create_info;
@@ -211,11 +264,250 @@ impl Semaphore {
             device,
             id: Self::next_id(),
             must_put_in_pool: false,
+            semaphore_type,
             export_handle_types,
             state: Mutex::new(Default::default()),
         }
     }
 
+    /// Returns the type of the semaphore.
+    #[inline]
+    pub fn semaphore_type(&self) -> SemaphoreType {
+        self.semaphore_type
+    }
+
+    /// Returns the current counter value of a timeline semaphore.
+    #[inline]
+    pub fn counter_value(&self) -> Result<u64, SemaphoreError> {
+        self.validate_counter_value()?;
+
+        unsafe { Ok(self.counter_value_unchecked()?) }
+    }
+
+    fn validate_counter_value(&self) -> Result<(), SemaphoreError> {
+        // VUID-vkGetSemaphoreCounterValue-semaphore-03255
+        if self.semaphore_type != SemaphoreType::Timeline {
+            return Err(SemaphoreError::SemaphoreTypeNotTimeline);
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn counter_value_unchecked(&self) -> Result<u64, VulkanError> {
+        let mut output = MaybeUninit::uninit();
+        let fns = self.device.fns();
+
+        if self.device.api_version() >= Version::V1_2 {
+            (fns.v1_2.get_semaphore_counter_value)(
+                self.device.handle(),
+                self.handle,
+                output.as_mut_ptr(),
+            )
+        } else {
+            (fns.khr_timeline_semaphore.get_semaphore_counter_value_khr)(
+                self.device.handle(),
+                self.handle,
+                output.as_mut_ptr(),
+            )
+        }
+        .result()
+        .map_err(VulkanError::from)?;
+
+        Ok(output.assume_init())
+    }
+
+    /// Sets the counter value of a timeline semaphore to `value` from the host.
+    ///
+    /// `value` must be greater than the current counter value of the semaphore, and the
+    /// difference must not exceed the
+    /// [`max_timeline_semaphore_value_difference`](crate::device::Properties::max_timeline_semaphore_value_difference)
+    /// device property.
+    ///
+    /// # Safety
+    ///
+    /// - `value` must be less than the value of any signal operation for the semaphore that is
+    ///   currently pending in a queue.
+    #[inline]
+    pub unsafe fn signal(&self, value: u64) -> Result<(), SemaphoreError> {
+        self.validate_signal(value)?;
+
+        Ok(self.signal_unchecked(value)?)
+    }
+
+    fn validate_signal(&self, value: u64) -> Result<(), SemaphoreError> {
+        // VUID-VkSemaphoreSignalInfo-semaphore-03257
+        if self.semaphore_type != SemaphoreType::Timeline {
+            return Err(SemaphoreError::SemaphoreTypeNotTimeline);
+        }
+
+        let current_value = unsafe { self.counter_value_unchecked()? };
+
+        // VUID-VkSemaphoreSignalInfo-value-03258
+        if value <= current_value {
+            return Err(SemaphoreError::SignalValueNotGreater {
+                value,
+                current_value,
+            });
+        }
+
+        // VUID-VkSemaphoreSignalInfo-value-03260
+        let max_difference = self
+            .device
+            .physical_device()
+            .properties()
+            .max_timeline_semaphore_value_difference
+            .unwrap_or(u64::MAX);
+
+        if value - current_value > max_difference {
+            return Err(SemaphoreError::ValueDifferenceTooLarge {
+                value,
+                current_value,
+                max: max_difference,
+            });
+        }
+
+        // VUID-VkSemaphoreSignalInfo-value-03259
+        // Can't validate, therefore unsafe
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn signal_unchecked(&self, value: u64) -> Result<(), VulkanError> {
+        let signal_info_vk = ash::vk::SemaphoreSignalInfo {
+            semaphore: self.handle,
+            value,
+            ..Default::default()
+        };
+
+        let fns = self.device.fns();
+
+        if self.device.api_version() >= Version::V1_2 {
+            (fns.v1_2.signal_semaphore)(self.device.handle(), &signal_info_vk)
+        } else {
+            (fns.khr_timeline_semaphore.signal_semaphore_khr)(self.device.handle(), &signal_info_vk)
+        }
+        .result()
+        .map_err(VulkanError::from)?;
+
+        Ok(())
+    }
+
+    /// Waits until the counter value of a timeline semaphore is at least `value`, or at least
+    /// until the timeout duration has elapsed.
+    ///
+    /// Returns `Ok` if the counter has reached `value`. Returns `Err` if the timeout was reached
+    /// instead.
+    ///
+    /// If you pass a duration of 0, then the function will return without blocking.
+    #[inline]
+    pub fn wait(&self, value: u64, timeout: Option<Duration>) -> Result<(), SemaphoreError> {
+        Self::multi_wait([(self, value)], SemaphoreWaitFlags::empty(), timeout)
+    }
+
+    /// Waits for multiple timeline semaphores at once, until each semaphore's counter value is at
+    /// least the value paired with it.
+    ///
+    /// If `flags` contains [`SemaphoreWaitFlags::ANY`], then the function returns as soon as
+    /// one of the semaphores has reached its value, instead of waiting for all of them.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if not all semaphores belong to the same device.
+    pub fn multi_wait<'a>(
+        semaphores: impl IntoIterator<Item = (&'a Semaphore, u64)>,
+        flags: SemaphoreWaitFlags,
+        timeout: Option<Duration>,
+    ) -> Result<(), SemaphoreError> {
+        let semaphores: SmallVec<[_; 8]> = semaphores.into_iter().collect();
+        Self::validate_multi_wait(&semaphores, flags, timeout)?;
+
+        unsafe { Self::multi_wait_unchecked(semaphores, flags, timeout) }
+    }
+
+    fn validate_multi_wait(
+        semaphores: &[(&Semaphore, u64)],
+        flags: SemaphoreWaitFlags,
+        _timeout: Option<Duration>,
+    ) -> Result<(), SemaphoreError> {
+        if semaphores.is_empty() {
+            return Ok(());
+        }
+
+        let device = &semaphores[0].0.device;
+
+        // VUID-VkSemaphoreWaitInfo-flags-parameter
+        flags.validate_device(device)?;
+
+        for &(semaphore, _) in semaphores {
+            // VUID-vkWaitSemaphores-pWaitInfo-parent
+            assert_eq!(device, &semaphore.device);
+
+            // VUID-VkSemaphoreWaitInfo-pSemaphores-03256
+            if semaphore.semaphore_type != SemaphoreType::Timeline {
+                return Err(SemaphoreError::SemaphoreTypeNotTimeline);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn multi_wait_unchecked<'a>(
+        semaphores: impl IntoIterator<Item = (&'a Semaphore, u64)>,
+        flags: SemaphoreWaitFlags,
+        timeout: Option<Duration>,
+    ) -> Result<(), SemaphoreError> {
+        let semaphores: SmallVec<[_; 8]> = semaphores.into_iter().collect();
+
+        // VUID-VkSemaphoreWaitInfo-semaphoreCount-arraylength
+        // If there are no semaphores, we don't need to wait.
+        if semaphores.is_empty() {
+            return Ok(());
+        }
+
+        let device = &semaphores[0].0.device;
+        let (semaphores_vk, values_vk): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = semaphores
+            .iter()
+            .map(|&(semaphore, value)| (semaphore.handle, value))
+            .unzip();
+
+        let wait_info_vk = ash::vk::SemaphoreWaitInfo {
+            flags: flags.into(),
+            semaphore_count: semaphores_vk.len() as u32,
+            p_semaphores: semaphores_vk.as_ptr(),
+            p_values: values_vk.as_ptr(),
+            ..Default::default()
+        };
+
+        let timeout_ns = timeout.map_or(u64::MAX, |timeout| {
+            timeout
+                .as_secs()
+                .saturating_mul(1_000_000_000)
+                .saturating_add(timeout.subsec_nanos() as u64)
+        });
+
+        let fns = device.fns();
+        let result = if device.api_version() >= Version::V1_2 {
+            (fns.v1_2.wait_semaphores)(device.handle(), &wait_info_vk, timeout_ns)
+        } else {
+            (fns.khr_timeline_semaphore.wait_semaphores_khr)(
+                device.handle(),
+                &wait_info_vk,
+                timeout_ns,
+            )
+        };
+
+        match result {
+            ash::vk::Result::SUCCESS => Ok(()),
+            ash::vk::Result::TIMEOUT => Err(SemaphoreError::Timeout),
+            err => Err(VulkanError::from(err).into()),
+        }
+    }
+
     /// Exports the semaphore into a POSIX file descriptor. The caller owns the returned `File`.
     #[cfg(unix)]
     #[inline]
@@ -281,6 +573,11 @@ impl Semaphore {
         }
 
         if handle_type.has_copy_transference() {
+            // VUID-VkSemaphoreGetFdInfoKHR-handleType-03253
+            if self.semaphore_type != SemaphoreType::Binary {
+                return Err(SemaphoreError::SemaphoreTypeNotBinary);
+            }
+
             // VUID-VkSemaphoreGetFdInfoKHR-handleType-01134
             if state.is_wait_pending() {
                 return Err(SemaphoreError::QueueIsWaiting);
@@ -682,6 +979,13 @@ impl Semaphore {
             return Err(SemaphoreError::HandletypeCopyNotTemporary);
         }
 
+        // VUID-VkImportSemaphoreFdInfoKHR-flags-03323
+        if flags.intersects(SemaphoreImportFlags::TEMPORARY)
+            && self.semaphore_type != SemaphoreType::Binary
+        {
+            return Err(SemaphoreError::SemaphoreTypeNotBinary);
+        }
+
         Ok(())
     }
 
@@ -1154,6 +1458,24 @@ impl From<ExternalSemaphoreHandleType> for ImportType {
 /// Parameters to create a new `Semaphore`.
 #[derive(Clone, Debug)]
 pub struct SemaphoreCreateInfo {
+    /// The type of semaphore to create.
+    ///
+    /// If this is not [`SemaphoreType::Binary`], then the [`timeline_semaphore`] feature must be
+    /// enabled on the device.
+    ///
+    /// The default value is [`SemaphoreType::Binary`].
+    ///
+    /// [`timeline_semaphore`]: crate::device::Features::timeline_semaphore
+    pub semaphore_type: SemaphoreType,
+
+    /// If `semaphore_type` is [`SemaphoreType::Timeline`], the initial value of the semaphore's
+    /// counter.
+    ///
+    /// If `semaphore_type` is [`SemaphoreType::Binary`], this must be 0.
+    ///
+    /// The default value is 0.
+    pub initial_value: u64,
+
     /// The handle types that can be exported from the semaphore.
     ///
     /// The default value is [`ExternalSemaphoreHandleTypes::empty()`].
@@ -1166,12 +1488,53 @@ impl Default for SemaphoreCreateInfo {
     #[inline]
     fn default() -> Self {
         Self {
+            semaphore_type: SemaphoreType::Binary,
+            initial_value: 0,
             export_handle_types: ExternalSemaphoreHandleTypes::empty(),
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
+vulkan_enum! {
+    #[non_exhaustive]
+
+    /// The type of a semaphore.
+    SemaphoreType = SemaphoreType(i32);
+
+    /// A semaphore that is either signaled or unsignaled. Each signal operation must be followed
+    /// by exactly one wait operation, which unsignals the semaphore again.
+    ///
+    /// This is the `Default` value.
+    Binary = BINARY,
+
+    /// A semaphore with a 64-bit counter value that only ever increases. Signal operations set
+    /// the counter to a new value, and wait operations wait until the counter has reached at
+    /// least a given value.
+    Timeline = TIMELINE {
+        api_version: V1_2,
+        device_extensions: [khr_timeline_semaphore],
+    },
+}
+
+impl Default for SemaphoreType {
+    #[inline]
+    fn default() -> Self {
+        SemaphoreType::Binary
+    }
+}
+
+vulkan_bitflags! {
+    #[non_exhaustive]
+
+    /// Flags for a host wait operation on timeline semaphores.
+    SemaphoreWaitFlags = SemaphoreWaitFlags(u32);
+
+    /// Return as soon as at least one of the semaphores has reached its value, rather than
+    /// waiting for all of them.
+    ANY = ANY,
+}
+
 vulkan_bitflags_enum! {
     #[non_exhaustive]
 
@@ -1369,6 +1732,11 @@ pub struct ExternalSemaphoreInfo {
     /// The external handle type that will be used with the semaphore.
     pub handle_type: ExternalSemaphoreHandleType,
 
+    /// The type of semaphore that will be used with the handle.
+    ///
+    /// The default value is [`SemaphoreType::Binary`].
+    pub semaphore_type: SemaphoreType,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -1378,6 +1746,7 @@ impl ExternalSemaphoreInfo {
     pub fn handle_type(handle_type: ExternalSemaphoreHandleType) -> Self {
         Self {
             handle_type,
+            semaphore_type: SemaphoreType::Binary,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -1411,6 +1780,12 @@ pub enum SemaphoreError {
     /// Not enough memory available.
     OomError(OomError),
 
+    /// The device has been lost.
+    DeviceLost,
+
+    /// The specified timeout wasn't long enough.
+    Timeout,
+
     RequirementNotMet {
         required_for: &'static str,
         requires_one_of: RequiresOneOf,
@@ -1436,6 +1811,9 @@ pub enum SemaphoreError {
     /// but the `temporary` import flag was not set.
     HandletypeCopyNotTemporary,
 
+    /// A binary semaphore was created with an initial value other than 0.
+    BinaryInitialValueNotZero,
+
     /// The provided export handle type was not set in `export_handle_types` when creating the
     /// semaphore.
     HandleTypeNotEnabled,
@@ -1462,6 +1840,24 @@ pub enum SemaphoreError {
 
     /// A queue is currently waiting on the semaphore.
     QueueIsWaiting,
+
+    /// The operation requires a binary semaphore, but the semaphore is not binary.
+    SemaphoreTypeNotBinary,
+
+    /// The operation requires a timeline semaphore, but the semaphore is not a timeline
+    /// semaphore.
+    SemaphoreTypeNotTimeline,
+
+    /// The value to signal is not greater than the current counter value of the semaphore.
+    SignalValueNotGreater { value: u64, current_value: u64 },
+
+    /// The difference between the provided value and the current counter value of the semaphore
+    /// exceeds the `max_timeline_semaphore_value_difference` limit.
+    ValueDifferenceTooLarge {
+        value: u64,
+        current_value: u64,
+        max: u64,
+    },
 }
 
 impl Error for SemaphoreError {
@@ -1477,6 +1873,8 @@ impl Display for SemaphoreError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
             Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::DeviceLost => write!(f, "the device was lost"),
+            Self::Timeout => write!(f, "the timeout has been reached"),
             Self::RequirementNotMet {
                 required_for,
                 requires_one_of,
@@ -1491,6 +1889,10 @@ impl Display for SemaphoreError {
                 "the provided handle type does not permit more than one export, and a handle of \
                 this type was already exported previously",
             ),
+            Self::BinaryInitialValueNotZero => write!(
+                f,
+                "a binary semaphore was created with an initial value other than 0",
+            ),
             Self::ExportFromImportedNotSupported {
                 imported_handle_type,
             } => write!(
@@ -1539,6 +1941,35 @@ impl Display for SemaphoreError {
             ),
             Self::InQueue => write!(f, "the semaphore is currently in use by a queue"),
             Self::QueueIsWaiting => write!(f, "a queue is currently waiting on the semaphore"),
+            Self::SemaphoreTypeNotBinary => write!(
+                f,
+                "the operation requires a binary semaphore, but the semaphore is not binary",
+            ),
+            Self::SemaphoreTypeNotTimeline => write!(
+                f,
+                "the operation requires a timeline semaphore, but the semaphore is not a timeline \
+                semaphore",
+            ),
+            Self::SignalValueNotGreater {
+                value,
+                current_value,
+            } => write!(
+                f,
+                "the value to signal ({}) is not greater than the current counter value of the \
+                semaphore ({})",
+                value, current_value,
+            ),
+            Self::ValueDifferenceTooLarge {
+                value,
+                current_value,
+                max,
+            } => write!(
+                f,
+                "the difference between the provided value ({}) and the current counter value of \
+                the semaphore ({}) exceeds the `max_timeline_semaphore_value_difference` limit \
+                ({})",
+                value, current_value, max,
+            ),
         }
     }
 }
@@ -1549,6 +1980,7 @@ impl From<VulkanError> for SemaphoreError {
             e @ VulkanError::OutOfHostMemory | e @ VulkanError::OutOfDeviceMemory => {
                 Self::OomError(e.into())
             }
+            VulkanError::DeviceLost => Self::DeviceLost,
             _ => panic!("unexpected error: {:?}", err),
         }
     }
@@ -1571,16 +2003,27 @@ impl From<RequirementNotMet> for SemaphoreError {
 
 #[cfg(test)]
 mod tests {
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+        },
+        sync::{
+            self,
+            future::{self, GpuFuture},
+            semaphore::{
+                Semaphore, SemaphoreCreateInfo, SemaphoreError, SemaphoreType, SemaphoreWaitFlags,
+            },
+        },
+        VulkanObject,
+    };
     #[cfg(unix)]
     use crate::{
         device::{Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo},
         instance::{Instance, InstanceCreateInfo, InstanceExtensions},
-        sync::semaphore::{
-            ExternalSemaphoreHandleType, ExternalSemaphoreHandleTypes, SemaphoreCreateInfo,
-        },
+        sync::semaphore::{ExternalSemaphoreHandleType, ExternalSemaphoreHandleTypes},
         VulkanLibrary,
     };
-    use crate::{sync::semaphore::Semaphore, VulkanObject};
+    use std::{sync::Arc, time::Duration};
 
     #[test]
     fn semaphore_create() {
@@ -1588,6 +2031,104 @@ mod tests {
         let _ = Semaphore::new(device, Default::default());
     }
 
+    #[test]
+    fn binary_semaphore_no_counter() {
+        let (device, _) = gfx_dev_and_queue!();
+        let sem = Semaphore::new(device, Default::default()).unwrap();
+
+        assert_eq!(
+            sem.counter_value(),
+            Err(SemaphoreError::SemaphoreTypeNotTimeline),
+        );
+    }
+
+    #[test]
+    fn timeline_semaphore_signal_wait() {
+        let (device, _) = gfx_dev_and_queue!(timeline_semaphore);
+
+        let sem = Semaphore::new(
+            device,
+            SemaphoreCreateInfo {
+                semaphore_type: SemaphoreType::Timeline,
+                initial_value: 5,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(sem.counter_value().unwrap(), 5);
+
+        assert_eq!(
+            sem.wait(6, Some(Duration::from_secs(0))),
+            Err(SemaphoreError::Timeout),
+        );
+
+        unsafe {
+            assert!(matches!(
+                sem.signal(5),
+                Err(SemaphoreError::SignalValueNotGreater { .. }),
+            ));
+            sem.signal(7).unwrap();
+        }
+
+        assert_eq!(sem.counter_value().unwrap(), 7);
+        sem.wait(6, Some(Duration::from_secs(5))).unwrap();
+        Semaphore::multi_wait(
+            [(&sem, 7)],
+            SemaphoreWaitFlags::ANY,
+            Some(Duration::from_secs(5)),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn timeline_semaphore_futures() {
+        let (device, queue) = gfx_dev_and_queue!(timeline_semaphore);
+
+        let sem = Arc::new(
+            Semaphore::new(
+                device.clone(),
+                SemaphoreCreateInfo {
+                    semaphore_type: SemaphoreType::Timeline,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        );
+        let cb_allocator = StandardCommandBufferAllocator::new(device.clone(), Default::default());
+        let command_buffer = || {
+            AutoCommandBufferBuilder::primary(
+                &cb_allocator,
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap()
+            .build()
+            .unwrap()
+        };
+
+        // Unlike a binary semaphore, a timeline semaphore can be waited on many times.
+        for value in 1..=3 {
+            let signal = sync::now(device.clone())
+                .then_execute(queue.clone(), command_buffer())
+                .unwrap()
+                .then_signal_timeline_semaphore_and_flush(sem.clone(), value)
+                .unwrap();
+
+            for _ in 0..2 {
+                future::timeline_semaphore_wait(sem.clone(), value)
+                    .then_execute(queue.clone(), command_buffer())
+                    .unwrap()
+                    .then_signal_fence_and_flush()
+                    .unwrap()
+                    .wait(None)
+                    .unwrap();
+            }
+
+            drop(signal);
+            assert_eq!(sem.counter_value().unwrap(), value);
+        }
+    }
+
     #[test]
     fn semaphore_pool() {
         let (device, _) = gfx_dev_and_queue!();